@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use gpu_allocator::vulkan::AllocationScheme;
+
+use crate::imports::*;
+
+/// Allocations of short-lived categories from this size on get dedicated memory instead of a suballocation.
+const DEDICATED_MIN_SIZE: u64 = 256 * 1024;
+
+/// Expected lifetime of an allocation, keeping short-lived allocations out of the shared memory blocks of long-lived ones.
+///
+/// [Transient](AllocationLifetime::Transient) and [Streaming](AllocationLifetime::Streaming) allocations of at least 256 KiB
+/// get dedicated memory, so freeing them never leaves holes between [Persistent](AllocationLifetime::Persistent) suballocations.
+/// Allocation names are prefixed with the category, see [MemoryReport](crate::MemoryReport) for the usage per category.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AllocationLifetime {
+    /// Lives for many frames or the whole session, e.g. meshes, textures and the depth image of the [Head](crate::Head).
+    #[default]
+    Persistent,
+    /// Freed within a few frames, e.g. staging and readback buffers or transient attachments.
+    Transient,
+    /// Replaced while the session runs, e.g. textures of the [MipStreamer](crate::MipStreamer).
+    Streaming,
+}
+
+/// ```base_name``` with a process-wide counter, the allocation name of resources created without a name.
+pub(crate) fn generated_name(base_name: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    format!("{base_name}_{}", COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+impl AllocationLifetime {
+    pub const ALL: [Self; 3] = [Self::Persistent, Self::Transient, Self::Streaming];
+
+    pub(crate) fn allocation_name(self, base_name: &str) -> String {
+        format!("{self:?}_{base_name}")
+    }
+
+    /// ```dedicated``` if this category keeps allocations of ```size``` bytes out of shared blocks.
+    pub(crate) fn allocation_scheme(
+        self,
+        size: u64,
+        dedicated: AllocationScheme,
+    ) -> AllocationScheme {
+        match self {
+            Self::Transient | Self::Streaming if size >= DEDICATED_MIN_SIZE => dedicated,
+            _ => AllocationScheme::GpuAllocatorManaged,
+        }
+    }
+}
+
+/// Live allocations and bytes of one [AllocationLifetime].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LifetimeUsage {
+    pub allocations: usize,
+    pub bytes: u64,
+}
+
+/// Memory of the [VMABuffers](crate::VMABuffer) and [VMAImages](crate::VMAImage) of a [VkInit](crate::VkInit) per [AllocationLifetime].
+///
+/// Memory allocated outside of vku is not seen and counts as free.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LifetimeReport {
+    pub persistent: LifetimeUsage,
+    pub transient: LifetimeUsage,
+    pub streaming: LifetimeUsage,
+    /// Bytes between live suballocations of shared memory blocks, grows with fragmentation.
+    pub fragmented_bytes: u64,
+    /// Largest free range between live suballocations of a shared memory block.
+    pub largest_free_block: u64,
+}
+
+impl LifetimeReport {
+    pub fn usage(&self, lifetime: AllocationLifetime) -> LifetimeUsage {
+        match lifetime {
+            AllocationLifetime::Persistent => self.persistent,
+            AllocationLifetime::Transient => self.transient,
+            AllocationLifetime::Streaming => self.streaming,
+        }
+    }
+}
+
+struct TrackedAllocation {
+    lifetime: AllocationLifetime,
+    memory: u64,
+    offset: u64,
+    size: u64,
+    dedicated: bool,
+}
+
+/// Live allocations per device handle.
+static ALLOCATIONS: Mutex<BTreeMap<u64, Vec<TrackedAllocation>>> = Mutex::new(BTreeMap::new());
+
+fn with_allocations<R>(f: impl FnOnce(&mut BTreeMap<u64, Vec<TrackedAllocation>>) -> R) -> R {
+    let mut allocations = ALLOCATIONS.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut allocations)
+}
+
+pub(crate) fn track(device: &Device, allocation: &Allocation, lifetime: AllocationLifetime) {
+    let tracked = TrackedAllocation {
+        lifetime,
+        memory: unsafe { allocation.memory().as_raw() },
+        offset: allocation.offset(),
+        size: allocation.size(),
+        dedicated: allocation.is_dedicated(),
+    };
+    with_allocations(|allocations| {
+        allocations
+            .entry(device.handle().as_raw())
+            .or_default()
+            .push(tracked)
+    });
+}
+
+pub(crate) fn untrack(device: &Device, allocation: &Allocation) {
+    let (memory, offset) = (unsafe { allocation.memory().as_raw() }, allocation.offset());
+    with_allocations(|allocations| {
+        if let Some(tracked) = allocations.get_mut(&device.handle().as_raw()) {
+            tracked.retain(|tracked| tracked.memory != memory || tracked.offset != offset);
+        }
+    });
+}
+
+/// Drops all allocations of ```device```, e.g. once it was destroyed.
+pub(crate) fn forget_device(device: &Device) {
+    with_allocations(|allocations| allocations.remove(&device.handle().as_raw()));
+}
+
+pub(crate) fn report(device: &Device) -> LifetimeReport {
+    with_allocations(|allocations| {
+        let mut report = LifetimeReport::default();
+        let Some(tracked) = allocations.get(&device.handle().as_raw()) else {
+            return report;
+        };
+
+        let mut blocks: BTreeMap<u64, Vec<(u64, u64)>> = BTreeMap::new();
+        for allocation in tracked {
+            let usage = match allocation.lifetime {
+                AllocationLifetime::Persistent => &mut report.persistent,
+                AllocationLifetime::Transient => &mut report.transient,
+                AllocationLifetime::Streaming => &mut report.streaming,
+            };
+            usage.allocations += 1;
+            usage.bytes += allocation.size;
+            if !allocation.dedicated {
+                blocks
+                    .entry(allocation.memory)
+                    .or_default()
+                    .push((allocation.offset, allocation.size));
+            }
+        }
+
+        for ranges in blocks.values_mut() {
+            ranges.sort_unstable();
+            let mut end = 0;
+            for (offset, size) in ranges.iter() {
+                let hole = offset.saturating_sub(end);
+                report.fragmented_bytes += hole;
+                report.largest_free_block = report.largest_free_block.max(hole);
+                end = end.max(offset + size);
+            }
+        }
+        report
+    })
+}
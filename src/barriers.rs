@@ -0,0 +1,280 @@
+use crate::{imports::*, VkInit};
+
+/// Collects global, buffer and image barriers for a single ```cmd_pipeline_barrier2```.
+///
+/// Barriers are copied, so builders can be passed by reference without calling ```build()```.
+/// ```
+/// # extern crate winit;
+/// # use vku::*;
+/// # use ash::vk::*;
+/// # if VkInit::load_entry().is_err() { return Ok(()); }
+/// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+/// # let size = [800_u32, 600_u32];
+/// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+/// # let create_info = VkInitCreateInfo::default();
+/// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+/// let cmd_pool = init.create_cmd_pool(CmdType::Any)?;
+/// let cmd_buffer = init.create_command_buffers(&cmd_pool, 1)?[0];
+/// let buffer = init.create_local_buffer(1024, BufferUsageFlags::STORAGE_BUFFER)?;
+///
+/// init.begin_cmd_buffer(&cmd_buffer)?;
+/// Barriers::new()
+///     .add_global(
+///         PipelineStageFlags2::COMPUTE_SHADER,
+///         AccessFlags2::SHADER_WRITE,
+///         PipelineStageFlags2::DRAW_INDIRECT,
+///         AccessFlags2::INDIRECT_COMMAND_READ,
+///     )
+///     .add_buffer(
+///         &BufferMemoryBarrier2::builder()
+///             .buffer(buffer.buffer)
+///             .size(WHOLE_SIZE)
+///             .src_stage_mask(PipelineStageFlags2::COMPUTE_SHADER)
+///             .src_access_mask(AccessFlags2::SHADER_WRITE)
+///             .dst_stage_mask(PipelineStageFlags2::VERTEX_SHADER)
+///             .dst_access_mask(AccessFlags2::SHADER_READ),
+///     )
+///     .record(&init, &cmd_buffer);
+/// # Ok::<(), vku::Error>(())
+/// ```
+#[derive(Clone, Default)]
+pub struct Barriers {
+    memory: Vec<MemoryBarrier2>,
+    buffer: Vec<BufferMemoryBarrier2>,
+    image: Vec<ImageMemoryBarrier2>,
+    dependency_flags: Option<DependencyFlags>,
+}
+
+impl Barriers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_global(
+        mut self,
+        src_stage: PipelineStageFlags2,
+        src_access: AccessFlags2,
+        dst_stage: PipelineStageFlags2,
+        dst_access: AccessFlags2,
+    ) -> Self {
+        self.memory.push(
+            MemoryBarrier2::builder()
+                .src_stage_mask(src_stage)
+                .src_access_mask(src_access)
+                .dst_stage_mask(dst_stage)
+                .dst_access_mask(dst_access)
+                .build(),
+        );
+        self
+    }
+
+    pub fn add_buffer(mut self, barrier: &BufferMemoryBarrier2) -> Self {
+        self.buffer.push(*barrier);
+        self
+    }
+
+    pub fn add_image(mut self, barrier: &ImageMemoryBarrier2) -> Self {
+        self.image.push(*barrier);
+        self
+    }
+
+    /// Overrides the [default_dependency_flags].
+    pub fn dependency_flags(mut self, dependency_flags: DependencyFlags) -> Self {
+        self.dependency_flags = Some(dependency_flags);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.memory.is_empty() && self.buffer.is_empty() && self.image.is_empty()
+    }
+
+    /// Records all collected barriers, does nothing if there are none.
+    pub fn record(&self, vk_init: &VkInit, cmd_buffer: &CommandBuffer) {
+        if self.is_empty() {
+            return;
+        }
+        vk_init.cmd_pipeline_barrier2_full(
+            cmd_buffer,
+            &self.memory,
+            &self.image,
+            &self.buffer,
+            self.dependency_flags,
+        );
+    }
+}
+
+/// Pipeline stages that operate on framebuffer regions.
+const FRAMEBUFFER_SPACE_STAGES: PipelineStageFlags2 = PipelineStageFlags2::from_raw(
+    PipelineStageFlags2::FRAGMENT_SHADER.as_raw()
+        | PipelineStageFlags2::EARLY_FRAGMENT_TESTS.as_raw()
+        | PipelineStageFlags2::LATE_FRAGMENT_TESTS.as_raw()
+        | PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT.as_raw(),
+);
+
+/// Accesses that only touch the framebuffer region of the current fragment.
+const FRAMEBUFFER_LOCAL_ACCESS: AccessFlags2 = AccessFlags2::from_raw(
+    AccessFlags2::INPUT_ATTACHMENT_READ.as_raw()
+        | AccessFlags2::COLOR_ATTACHMENT_READ.as_raw()
+        | AccessFlags2::COLOR_ATTACHMENT_WRITE.as_raw()
+        | AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ.as_raw()
+        | AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE.as_raw(),
+);
+
+/// ```DependencyFlags::BY_REGION``` if all barriers only depend fragment stages on fragment stages
+/// and only make writes available to attachment or input attachment accesses, empty otherwise.
+///
+/// Framebuffer-local dependencies let tile-based GPUs keep attachments in tile memory.
+/// Sampled or storage reads may access other regions, e.g. ```COLOR_ATTACHMENT_OPTIMAL``` to ```SHADER_READ_ONLY_OPTIMAL```.
+pub fn default_dependency_flags(
+    memory_barriers: &[MemoryBarrier2],
+    image_memory_barriers: &[ImageMemoryBarrier2],
+    buffer_memory_barriers: &[BufferMemoryBarrier2],
+) -> DependencyFlags {
+    let framebuffer_local =
+        |stage: PipelineStageFlags2| !stage.is_empty() && FRAMEBUFFER_SPACE_STAGES.contains(stage);
+    let mut dependencies = memory_barriers
+        .iter()
+        .map(|b| (b.src_stage_mask, b.dst_stage_mask, b.dst_access_mask))
+        .chain(
+            image_memory_barriers
+                .iter()
+                .map(|b| (b.src_stage_mask, b.dst_stage_mask, b.dst_access_mask)),
+        )
+        .chain(
+            buffer_memory_barriers
+                .iter()
+                .map(|b| (b.src_stage_mask, b.dst_stage_mask, b.dst_access_mask)),
+        )
+        .peekable();
+
+    if dependencies.peek().is_some()
+        && dependencies.all(|(src, dst, dst_access)| {
+            framebuffer_local(src)
+                && framebuffer_local(dst)
+                && FRAMEBUFFER_LOCAL_ACCESS.contains(dst_access)
+        })
+    {
+        DependencyFlags::BY_REGION
+    } else {
+        DependencyFlags::empty()
+    }
+}
+
+/// How fragment shaders read color attachment values written by earlier draws of the same rendering, e.g. for programmable blending.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttachmentFeedback {
+    /// Attachment access is ordered by rasterization, no barrier is needed.
+    /// Pipelines need ```PipelineColorBlendStateCreateFlags::RASTERIZATION_ORDER_ATTACHMENT_ACCESS_EXT```.
+    RasterizationOrder,
+    /// Dynamic rendering does not allow barriers inside a rendering scope.
+    /// Rendering has to be ended, the self-dependency recorded and rendering resumed.
+    SplitRendering,
+}
+
+impl VkInit {
+    /// Prefers [RasterizationOrder](AttachmentFeedback::RasterizationOrder) if
+    /// [rasterization_order_attachment_access](crate::PhysicalDeviceInfo::rasterization_order_attachment_access) is supported.
+    pub fn attachment_feedback(&self) -> AttachmentFeedback {
+        match self
+            .physical_device_info
+            .rasterization_order_attachment_access
+        {
+            true => AttachmentFeedback::RasterizationOrder,
+            false => AttachmentFeedback::SplitRendering,
+        }
+    }
+
+    /// Makes color attachment writes to ```image``` visible to fragment shader and attachment reads of following draws.
+    ///
+    /// Records a ```BY_REGION``` barrier keeping ```layout``` with [SplitRendering](AttachmentFeedback::SplitRendering)
+    /// and must then be called outside of a rendering scope. Records nothing with [RasterizationOrder](AttachmentFeedback::RasterizationOrder).
+    /// Returns the chosen path.
+    pub fn cmd_color_attachment_self_dependency(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        image: Image,
+        layout: ImageLayout,
+        subresource_range: ImageSubresourceRange,
+    ) -> AttachmentFeedback {
+        let feedback = self.attachment_feedback();
+        trace!("Color attachment self-dependency via {feedback:?}");
+        if feedback == AttachmentFeedback::SplitRendering {
+            Barriers::new()
+                .add_image(
+                    &ImageMemoryBarrier2::builder()
+                        .image(image)
+                        .subresource_range(subresource_range)
+                        .old_layout(layout)
+                        .new_layout(layout)
+                        .src_stage_mask(PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                        .src_access_mask(AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                        .dst_stage_mask(
+                            PipelineStageFlags2::FRAGMENT_SHADER
+                                | PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+                        )
+                        .dst_access_mask(
+                            AccessFlags2::SHADER_READ
+                                | AccessFlags2::INPUT_ATTACHMENT_READ
+                                | AccessFlags2::COLOR_ATTACHMENT_READ,
+                        )
+                        .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+                        .dst_queue_family_index(QUEUE_FAMILY_IGNORED),
+                )
+                .record(self, cmd_buffer);
+        }
+        feedback
+    }
+
+    /// Layout of color attachments that are sampled while being rendered to, e.g. to multiply the destination in the fragment shader.
+    ///
+    /// ```ImageLayout::ATTACHMENT_FEEDBACK_LOOP_OPTIMAL_EXT``` if
+    /// [attachment_feedback_loop_layout](crate::PhysicalDeviceInfo::attachment_feedback_loop_layout) is supported,
+    /// ```ImageLayout::GENERAL``` otherwise. Both the attachment and the descriptor use this layout.
+    /// Create the image with [create_feedback_loop_image](VkInit::create_feedback_loop_image) and the pipeline
+    /// with [with_feedback_loop](crate::pipeline_builder::VKUPipelineBuilder::with_feedback_loop),
+    /// transition it with [feedback_loop_barrier](crate::VMAImage::feedback_loop_barrier).
+    ///
+    /// Each draw reading the attachment has to be in its own rendering scope, preceded by a
+    /// [cmd_feedback_loop_barrier](VkInit::cmd_feedback_loop_barrier). A fragment may only read the pixel it writes.
+    pub fn feedback_loop_layout(&self) -> ImageLayout {
+        match self.physical_device_info.attachment_feedback_loop_layout {
+            true => ImageLayout::ATTACHMENT_FEEDBACK_LOOP_OPTIMAL_EXT,
+            false => ImageLayout::GENERAL,
+        }
+    }
+
+    /// Makes color attachment writes to ```image``` visible to the fragment shader reads and attachment accesses of the next draw.
+    ///
+    /// Keeps ```layout```, usually the [feedback_loop_layout](VkInit::feedback_loop_layout). Must be called outside of a rendering scope.
+    pub fn cmd_feedback_loop_barrier(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        image: Image,
+        layout: ImageLayout,
+        subresource_range: ImageSubresourceRange,
+    ) {
+        trace!("Feedback loop barrier in {layout:?}");
+        Barriers::new()
+            .add_image(
+                &ImageMemoryBarrier2::builder()
+                    .image(image)
+                    .subresource_range(subresource_range)
+                    .old_layout(layout)
+                    .new_layout(layout)
+                    .src_stage_mask(PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                    .src_access_mask(AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                    .dst_stage_mask(
+                        PipelineStageFlags2::FRAGMENT_SHADER
+                            | PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+                    )
+                    .dst_access_mask(
+                        AccessFlags2::SHADER_READ
+                            | AccessFlags2::COLOR_ATTACHMENT_READ
+                            | AccessFlags2::COLOR_ATTACHMENT_WRITE,
+                    )
+                    .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+                    .dst_queue_family_index(QUEUE_FAMILY_IGNORED),
+            )
+            .record(self, cmd_buffer);
+    }
+}
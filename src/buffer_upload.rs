@@ -0,0 +1,151 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{
+    imports::*, queue_ownership::HandoffResource, CmdType, PooledStaging, QueueRequirements,
+    VMABuffer, VkInit,
+};
+
+impl VkInit {
+    /// Uploads ```data``` to ```dst``` at byte ```dst_offset``` and blocks until it was copied.
+    ///
+    /// Data larger than [max_staging_chunk](crate::VkInitCreateInfo::max_staging_chunk) is split into chunks that are copied
+    /// one after another through a single pooled staging buffer, waiting on a fence between chunks.
+    /// ```progress``` is called with the bytes done and total after every chunk.
+    ///
+    /// Setting ```cancel``` aborts before the next chunk with [UploadCancelled](Error::UploadCancelled), once the chunk in flight has been copied.
+    /// ```dst``` is partially written in that case, the staging buffer is returned to the pool either way.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let vertices = vec![0.5_f32; 1024 * 1024];
+    /// let usage = BufferUsageFlags::VERTEX_BUFFER | BufferUsageFlags::TRANSFER_DST;
+    /// let buffer = init.create_local_buffer(vertices.len() * 4, usage)?;
+    ///
+    /// let mut progress = |done, total| println!("{done} / {total} bytes");
+    /// init.upload_to_buffer(&buffer, 0, &vertices, Some(&mut progress), None)?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn upload_to_buffer<T>(
+        &mut self,
+        dst: &VMABuffer,
+        dst_offset: usize,
+        data: &[T],
+        progress: Option<&mut dyn FnMut(usize, usize)>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<(), Error> {
+        dst.check(&self.device, "upload_to_buffer");
+        if !dst.usage.contains(BufferUsageFlags::TRANSFER_DST) {
+            return Err(Error::BufferUsageMissing(BufferUsageFlags::TRANSFER_DST));
+        }
+        let bytes = unsafe {
+            std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data))
+        };
+        let available = dst.size().saturating_sub(dst_offset as u64);
+        if bytes.len() as u64 > available {
+            return Err(Error::BufferWriteOutOfBounds {
+                requested: bytes.len() as u64,
+                available,
+            });
+        }
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        let chunk_size = self.create_info.max_staging_chunk.clamp(1, bytes.len());
+        let staging = self.acquire_staging(chunk_size)?;
+
+        let result = self.upload_chunks(
+            &staging, dst, dst_offset, bytes, chunk_size, progress, cancel,
+        );
+
+        self.release_staging(staging, None)?;
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn upload_chunks(
+        &self,
+        staging: &PooledStaging,
+        dst: &VMABuffer,
+        dst_offset: usize,
+        bytes: &[u8],
+        chunk_size: usize,
+        mut progress: Option<&mut dyn FnMut(usize, usize)>,
+        cancel: Option<&AtomicBool>,
+    ) -> Result<(), Error> {
+        // Staging memory is rewritten by the host before every copy, only ```dst``` has contents to keep.
+        let mut handoff = self.queue_handoff(
+            CmdType::Transfer,
+            QueueRequirements::default(),
+            (PipelineStageFlags2::TRANSFER, AccessFlags2::TRANSFER_WRITE),
+            vec![HandoffResource::Buffer(dst.buffer)],
+        )?;
+        let total = bytes.len();
+        let mut done = 0;
+        let mut cmd_pool = CommandPool::null();
+        let mut fence = Fence::null();
+        let mut in_flight = false;
+        let result = (|| {
+            let pool_info = CommandPoolCreateInfo::builder()
+                .queue_family_index(handoff.queue_family_index())
+                .flags(CommandPoolCreateFlags::RESET_COMMAND_BUFFER);
+            cmd_pool = unsafe { self.device.create_command_pool(&pool_info, None)? };
+            fence = self.create_fence_named("VKU_Upload_To_Buffer")?;
+            let cmd_buffer =
+                self.create_command_buffers_named(&cmd_pool, 1, "VKU_Upload_To_Buffer")?[0];
+            for chunk in bytes.chunks(chunk_size) {
+                // Waits for the previous chunk before its staging memory is overwritten.
+                self.wait_on_fence_and_reset(Some(&fence), &[&cmd_buffer])?;
+                in_flight = false;
+                if cancel.is_some_and(|cancel| cancel.load(Ordering::Acquire)) {
+                    info!("Upload cancelled after {done} of {total} bytes");
+                    return Err(Error::UploadCancelled {
+                        bytes_written: done,
+                        bytes_total: total,
+                    });
+                }
+
+                staging.set_data(&self.device, 0, chunk)?;
+                self.begin_cmd_buffer(&cmd_buffer)?;
+                handoff.record_begin(self, &cmd_buffer);
+                staging.buffer.enqueue_copy_to_buffer(
+                    &self.device,
+                    &cmd_buffer,
+                    dst,
+                    Some(0),
+                    Some((dst_offset + done) as u64),
+                    Some(chunk.len() as u64),
+                )?;
+                handoff.record_end(self, &cmd_buffer);
+                unsafe { self.device.end_command_buffer(cmd_buffer)? };
+                handoff.submit(self, &[cmd_buffer], &fence)?;
+                in_flight = true;
+
+                done += chunk.len();
+                if let Some(progress) = progress.as_mut() {
+                    progress(done, total);
+                }
+            }
+            Ok(())
+        })();
+
+        // The staging buffer and command pool are freed next, a submitted chunk may still use them.
+        let result = match in_flight {
+            true => result.and(self.wait_for_fences_watched(&[fence], "upload_to_buffer")),
+            false => result,
+        };
+        unsafe {
+            self.device.destroy_fence(fence, None);
+            self.device.destroy_command_pool(cmd_pool, None);
+        }
+        handoff.destroy(&self.device);
+        result
+    }
+}
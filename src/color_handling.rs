@@ -0,0 +1,67 @@
+use crate::{imports::*, SurfaceInfo, VkInit};
+
+/// Who is responsible for encoding linear colors into the sRGB transfer function when writing to the swapchain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorHandling {
+    /// The format or color space encodes automatically, shaders output linear colors.
+    SrgbFramebuffer,
+    /// UNORM format with sRGB color space, shaders have to encode linear colors themselves.
+    LinearFramebufferNeedsEncode,
+    /// Format or color space is not covered, e.g. HDR color spaces.
+    Unknown,
+}
+
+impl ColorHandling {
+    pub fn from_surface_format(surface_format: SurfaceFormatKHR) -> Self {
+        match surface_format.color_space {
+            ColorSpaceKHR::SRGB_NONLINEAR => match surface_format.format {
+                Format::B8G8R8A8_SRGB
+                | Format::R8G8B8A8_SRGB
+                | Format::A8B8G8R8_SRGB_PACK32
+                | Format::B8G8R8_SRGB
+                | Format::R8G8B8_SRGB => Self::SrgbFramebuffer,
+                Format::B8G8R8A8_UNORM
+                | Format::R8G8B8A8_UNORM
+                | Format::A8B8G8R8_UNORM_PACK32
+                | Format::B8G8R8_UNORM
+                | Format::R8G8B8_UNORM
+                | Format::A2B10G10R10_UNORM_PACK32
+                | Format::A2R10G10B10_UNORM_PACK32
+                | Format::R5G6B5_UNORM_PACK16
+                | Format::B5G6R5_UNORM_PACK16
+                | Format::A1R5G5B5_UNORM_PACK16 => Self::LinearFramebufferNeedsEncode,
+                _ => Self::Unknown,
+            },
+            ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT => match surface_format.format {
+                Format::R16G16B16A16_SFLOAT => Self::SrgbFramebuffer,
+                _ => Self::Unknown,
+            },
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Value for a specialization or push constant telling shaders to encode to sRGB manually: 1 to encode, 0 otherwise.
+    pub fn srgb_spec_constant(&self) -> u32 {
+        match self {
+            Self::LinearFramebufferNeedsEncode => 1,
+            Self::SrgbFramebuffer | Self::Unknown => 0,
+        }
+    }
+}
+
+impl SurfaceInfo {
+    pub fn color_handling(&self) -> ColorHandling {
+        ColorHandling::from_surface_format(self.color_format)
+    }
+}
+
+impl VkInit {
+    /// Shortcut - see [ColorHandling](ColorHandling::srgb_spec_constant).
+    ///
+    /// Always 0 on headless instances.
+    pub fn srgb_spec_constant(&self) -> u32 {
+        self.head.as_ref().map_or(0, |head| {
+            head.surface_info.color_handling().srgb_spec_constant()
+        })
+    }
+}
@@ -0,0 +1,300 @@
+#[cfg(feature = "image")]
+use std::path::Path;
+
+use crate::{imports::*, CmdType, VMAImage, VkInit};
+
+/// How the 8-bit channels of an uploaded image are meant to be interpreted.
+///
+/// Selects the format of images created by [from_rgba8](VMAImage::from_rgba8) and decides whether the color channels
+/// are converted on the CPU when uploaded into a format with the other encoding. Alpha is always linear.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorSpaceIntent {
+    /// sRGB encoded colors, e.g. albedo textures and UI images exported by image editors.
+    SrgbTexture,
+    /// Linear values, e.g. roughness, metalness or lookup tables.
+    LinearData,
+    /// Linear tangent-space normals, never decoded as sRGB.
+    NormalMap,
+}
+
+impl ColorSpaceIntent {
+    /// ```R8G8B8A8_SRGB``` for [SrgbTexture](ColorSpaceIntent::SrgbTexture), ```R8G8B8A8_UNORM``` otherwise.
+    pub fn format(&self) -> Format {
+        match self {
+            ColorSpaceIntent::SrgbTexture => Format::R8G8B8A8_SRGB,
+            ColorSpaceIntent::LinearData | ColorSpaceIntent::NormalMap => Format::R8G8B8A8_UNORM,
+        }
+    }
+
+    pub fn is_srgb_encoded(&self) -> bool {
+        matches!(self, ColorSpaceIntent::SrgbTexture)
+    }
+
+    /// Converts the color channels of ```pixels``` so that sampling an image of ```format``` returns the values this intent describes.
+    ///
+    /// sRGB sources stored in ```R8G8B8A8_UNORM``` are decoded, linear sources stored in ```R8G8B8A8_SRGB``` are encoded.
+    /// Borrows ```pixels``` if the encodings already match.
+    /// Returns [UnsupportedColorSpaceFormat](Error::UnsupportedColorSpaceFormat) for other formats.
+    pub fn convert_rgba8<'a>(
+        &self,
+        pixels: &'a [u8],
+        format: Format,
+    ) -> Result<Cow<'a, [u8]>, Error> {
+        let srgb_format = match format {
+            Format::R8G8B8A8_SRGB => true,
+            Format::R8G8B8A8_UNORM => false,
+            _ => return Err(Error::UnsupportedColorSpaceFormat(format)),
+        };
+        if srgb_format == self.is_srgb_encoded() {
+            return Ok(Cow::Borrowed(pixels));
+        }
+
+        let convert: fn(f32) -> f32 = match srgb_format {
+            true => linear_to_srgb,
+            false => srgb_to_linear,
+        };
+        let mut table = [0_u8; 256];
+        for (value, converted) in table.iter_mut().enumerate() {
+            *converted = (convert(value as f32 / 255.0) * 255.0).round() as u8;
+        }
+
+        let mut converted = pixels.to_vec();
+        for pixel in converted.chunks_exact_mut(4) {
+            for channel in &mut pixel[..3] {
+                *channel = table[*channel as usize];
+            }
+        }
+        Ok(Cow::Owned(converted))
+    }
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+impl VMAImage {
+    /// Creates a sampled image from tightly packed rgba8 ```pixels``` and blocks until they were uploaded.
+    ///
+    /// The format defaults to the [intent's format](ColorSpaceIntent::format), ```format``` overrides it with
+    /// ```R8G8B8A8_SRGB``` or ```R8G8B8A8_UNORM```, converting the pixels if the encodings differ.
+    /// The image is left in ```ImageLayout::SHADER_READ_ONLY_OPTIMAL```.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let pixels = [255_u8, 128, 0, 255].repeat(16 * 16);
+    ///
+    /// let albedo = VMAImage::from_rgba8(&mut init, 16, 16, &pixels, ColorSpaceIntent::SrgbTexture, None)?;
+    /// assert_eq!(albedo.format, Format::R8G8B8A8_SRGB);
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn from_rgba8(
+        vk_init: &mut VkInit,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        intent: ColorSpaceIntent,
+        format: Option<Format>,
+    ) -> Result<VMAImage, Error> {
+        Self::texture_from_rgba8(vk_init, width, height, pixels, intent, format, false)
+    }
+
+    /// Decodes the image at ```path``` to rgba8 and creates it with [from_rgba8](VMAImage::from_rgba8).
+    #[cfg(feature = "image")]
+    pub fn from_file(
+        vk_init: &mut VkInit,
+        path: impl AsRef<Path>,
+        intent: ColorSpaceIntent,
+        format: Option<Format>,
+    ) -> Result<VMAImage, Error> {
+        let decoded = image::open(path)?.into_rgba8();
+        let (width, height) = decoded.dimensions();
+        Self::from_rgba8(vk_init, width, height, decoded.as_raw(), intent, format)
+    }
+
+    /// Same as [from_rgba8](VMAImage::from_rgba8), with a full mip chain generated from ```pixels``` if ```mipmapped``` is set.
+    fn texture_from_rgba8(
+        vk_init: &mut VkInit,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        intent: ColorSpaceIntent,
+        format: Option<Format>,
+        mipmapped: bool,
+    ) -> Result<VMAImage, Error> {
+        let expected = width as usize * height as usize * 4;
+        if pixels.len() != expected {
+            return Err(Error::ImageDataMismatch {
+                width,
+                height,
+                expected,
+                provided: pixels.len(),
+            });
+        }
+        let format = format.unwrap_or(intent.format());
+        let pixels = intent.convert_rgba8(pixels, format)?;
+
+        let extent = Extent3D {
+            width,
+            height,
+            depth: 1,
+        };
+        let mut image = match mipmapped {
+            true => {
+                if vk_init.mip_generation_for(format).is_none() {
+                    return Err(Error::MipmapGenerationNotSupported(format));
+                }
+                let mip_levels = u32::BITS - width.max(height).leading_zeros();
+                vk_init.create_mipped_image(
+                    extent,
+                    format,
+                    4,
+                    ImageAspectFlags::COLOR,
+                    mip_levels,
+                )?
+            }
+            false => vk_init.create_empty_image(extent, format, 4, ImageAspectFlags::COLOR)?,
+        };
+        if let Err(e) = image.upload_staged(vk_init, &pixels) {
+            image.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+            return Err(e);
+        }
+        Ok(image)
+    }
+
+    /// Uploads ```pixels``` to mip level 0 and generates the other levels, if any.
+    fn upload_staged(&mut self, vk_init: &VkInit, pixels: &[u8]) -> Result<(), Error> {
+        self.set_staging_data(&vk_init.device, pixels)?;
+        let to_transfer = self.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_DST_OPTIMAL,
+            None,
+            None,
+        )?;
+        let to_sampled = match self.mip_levels {
+            1 => Some(self.get_image_layout_transition_barrier2(
+                ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                None,
+                None,
+            )?),
+            _ => None,
+        };
+
+        let mut generated = Ok(());
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer], &[]);
+            self.enque_copy_from_staging_buffer_to_image(&vk_init.device, cmd_buffer);
+            match to_sampled {
+                Some(to_sampled) => vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_sampled], &[]),
+                None => generated = vk_init.enqueue_generate_mipmaps(cmd_buffer, self),
+            }
+        })?;
+        generated
+    }
+}
+
+impl VkInit {
+    /// Creates a sampled texture from tightly packed rgba8 ```pixels``` and blocks until they were uploaded.
+    ///
+    /// ```srgb``` selects ```R8G8B8A8_SRGB``` for color textures, otherwise ```R8G8B8A8_UNORM``` is used, see [from_rgba8](VMAImage::from_rgba8).
+    /// The image is left in ```ImageLayout::SHADER_READ_ONLY_OPTIMAL```.
+    /// ```
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let init_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_headless(init_info)?;
+    /// let pixels = [255_u8, 128, 0, 255].repeat(16 * 16);
+    ///
+    /// let mut texture = init.create_texture_from_rgba8(16, 16, &pixels, true)?;
+    /// assert_eq!(texture.current_layout, ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+    /// # texture.destroy(&init.device, &mut init.allocator.lock())?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn create_texture_from_rgba8(
+        &mut self,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        srgb: bool,
+    ) -> Result<VMAImage, Error> {
+        VMAImage::texture_from_rgba8(
+            self,
+            width,
+            height,
+            pixels,
+            texture_intent(srgb),
+            None,
+            false,
+        )
+    }
+
+    /// Same as [create_texture_from_rgba8](VkInit::create_texture_from_rgba8), with all mip levels generated from ```pixels```.
+    ///
+    /// Returns [MipmapGenerationNotSupported](Error::MipmapGenerationNotSupported) if the device can't generate mip levels of the format.
+    pub fn create_mipped_texture_from_rgba8(
+        &mut self,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        srgb: bool,
+    ) -> Result<VMAImage, Error> {
+        VMAImage::texture_from_rgba8(
+            self,
+            width,
+            height,
+            pixels,
+            texture_intent(srgb),
+            None,
+            true,
+        )
+    }
+
+    /// Decodes the image at ```path``` to rgba8 and creates it with [create_texture_from_rgba8](VkInit::create_texture_from_rgba8).
+    #[cfg(feature = "image")]
+    pub fn create_texture_from_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        srgb: bool,
+    ) -> Result<VMAImage, Error> {
+        let decoded = image::open(path)?.into_rgba8();
+        let (width, height) = decoded.dimensions();
+        self.create_texture_from_rgba8(width, height, decoded.as_raw(), srgb)
+    }
+
+    /// Same as [create_texture_from_file](VkInit::create_texture_from_file), with all mip levels generated.
+    #[cfg(feature = "image")]
+    pub fn create_mipped_texture_from_file(
+        &mut self,
+        path: impl AsRef<Path>,
+        srgb: bool,
+    ) -> Result<VMAImage, Error> {
+        let decoded = image::open(path)?.into_rgba8();
+        let (width, height) = decoded.dimensions();
+        self.create_mipped_texture_from_rgba8(width, height, decoded.as_raw(), srgb)
+    }
+}
+
+fn texture_intent(srgb: bool) -> ColorSpaceIntent {
+    match srgb {
+        true => ColorSpaceIntent::SrgbTexture,
+        false => ColorSpaceIntent::LinearData,
+    }
+}
@@ -1,35 +1,144 @@
+use std::sync::Mutex;
+
 use super::vma_buffer::VMABuffer;
-use crate::{imports::*, VkInit};
+use crate::{
+    descriptor_pool::DescriptorPools, imports::*, DescriptorAllocator, DescriptorPoolPolicy,
+    PoolGrowth, VMAImage, VkInit,
+};
+
+/// Resource bound to a [ComputeShader], the binding index is its position in the bindings slice.
+#[derive(Clone, Copy)]
+pub enum ComputeBinding<'a> {
+    /// ```STORAGE_BUFFER```
+    Ssbo(&'a VMABuffer),
+    /// ```UNIFORM_BUFFER```
+    Uniform(&'a VMABuffer),
+    /// ```STORAGE_BUFFER``` restricted to ```range``` bytes from ```offset```: (buffer, offset, range).
+    SsboRange(&'a VMABuffer, DeviceSize, DeviceSize),
+    /// ```UNIFORM_BUFFER``` restricted to ```range``` bytes from ```offset```: (buffer, offset, range).
+    UniformRange(&'a VMABuffer, DeviceSize, DeviceSize),
+    /// ```STORAGE_IMAGE``` in the ```GENERAL``` layout.
+    StorageImage(&'a VMAImage),
+    /// ```COMBINED_IMAGE_SAMPLER``` in the ```SHADER_READ_ONLY_OPTIMAL``` layout.
+    SampledImage(&'a VMAImage, Sampler),
+}
+
+impl ComputeBinding<'_> {
+    pub fn descriptor_type(&self) -> DescriptorType {
+        match self {
+            Self::Ssbo(_) | Self::SsboRange(..) => DescriptorType::STORAGE_BUFFER,
+            Self::Uniform(_) | Self::UniformRange(..) => DescriptorType::UNIFORM_BUFFER,
+            Self::StorageImage(_) => DescriptorType::STORAGE_IMAGE,
+            Self::SampledImage(_, _) => DescriptorType::COMBINED_IMAGE_SAMPLER,
+        }
+    }
+}
+
+/// Where the descriptor sets of a [ComputeShader] are allocated from.
+enum SetSource<'a> {
+    Own(DescriptorPoolPolicy),
+    Allocator(&'a DescriptorAllocator),
+}
 
 /// A single stage compute shader.
 pub struct ComputeShader {
     pipeline: Pipeline,
     layout: PipelineLayout,
-    desc_pool: DescriptorPool,
+    /// ```None``` if the sets come from a [DescriptorAllocator].
+    desc_pools: Option<Mutex<DescriptorPools>>,
     desc_set_layout: DescriptorSetLayout,
     desc_sets: Vec<DescriptorSet>,
+    /// Descriptor type per binding of the layout, every written set has to match.
+    binding_types: Vec<DescriptorType>,
     group_sizes: [u32; 3],
+    base_debug_name: String,
 }
 
 impl VkInit {
-    /// Only SSBOs are supported as bindings.
+    /// Binding ```i``` of set 0 is ```bindings[i]```, see [ComputeBinding] for the descriptor types and image layouts.
     ///
     /// Group sizes are read in as specialization constants: layout(local_size_x_id = 0, local_size_y_id = 1, local_size_z_id = 2) in;
-
+    ///
+    /// Descriptor pools start with one set and double for every [additional set](ComputeShader::allocate_set).
     pub fn create_compute_shader<Push>(
         &self,
-        ssbos: &[&VMABuffer],
+        bindings: &[ComputeBinding],
         code: Vec<u32>,
         group_sizes: [u32; 3],
         additional_spec_consts: &[u32],
         base_debug_name: String,
+    ) -> Result<ComputeShader, Error> {
+        let policy = DescriptorPoolPolicy {
+            initial_sets: 1,
+            growth: PoolGrowth::Double,
+        };
+        self.create_compute_shader_with_policy::<Push>(
+            bindings,
+            code,
+            group_sizes,
+            additional_spec_consts,
+            base_debug_name,
+            policy,
+        )
+    }
+
+    /// Same as [create_compute_shader](VkInit::create_compute_shader), with the sizing of the descriptor pools set by ```policy```.
+    pub fn create_compute_shader_with_policy<Push>(
+        &self,
+        bindings: &[ComputeBinding],
+        code: Vec<u32>,
+        group_sizes: [u32; 3],
+        additional_spec_consts: &[u32],
+        base_debug_name: String,
+        policy: DescriptorPoolPolicy,
+    ) -> Result<ComputeShader, Error> {
+        self.create_compute_shader_from::<Push>(
+            bindings,
+            code,
+            group_sizes,
+            additional_spec_consts,
+            base_debug_name,
+            SetSource::Own(policy),
+        )
+    }
+
+    /// Same as [create_compute_shader](VkInit::create_compute_shader), with the descriptor sets allocated from ```allocator``` instead of own pools.
+    ///
+    /// Additional sets are allocated with [allocate_set_from](ComputeShader::allocate_set_from) and freed when ```allocator``` is destroyed.
+    pub fn create_compute_shader_with_allocator<Push>(
+        &self,
+        bindings: &[ComputeBinding],
+        code: Vec<u32>,
+        group_sizes: [u32; 3],
+        additional_spec_consts: &[u32],
+        base_debug_name: String,
+        allocator: &DescriptorAllocator,
+    ) -> Result<ComputeShader, Error> {
+        self.create_compute_shader_from::<Push>(
+            bindings,
+            code,
+            group_sizes,
+            additional_spec_consts,
+            base_debug_name,
+            SetSource::Allocator(allocator),
+        )
+    }
+
+    fn create_compute_shader_from<Push>(
+        &self,
+        bindings: &[ComputeBinding],
+        code: Vec<u32>,
+        group_sizes: [u32; 3],
+        additional_spec_consts: &[u32],
+        base_debug_name: String,
+        set_source: SetSource,
     ) -> Result<ComputeShader, Error> {
         let module_info = ShaderModuleCreateInfo::builder().code(&code);
         let module = unsafe { self.device.create_shader_module(&module_info, None) }?;
-        self.set_debug_object_name(
+        self.set_debug_object_name_fmt(
             module.as_raw(),
             ObjectType::SHADER_MODULE,
-            format!("{base_debug_name}_Compute_Shader_Module"),
+            format_args!("{base_debug_name}_Compute_Shader_Module"),
         )?;
 
         let mut spec_consts_data: Vec<u8> = vec![];
@@ -67,47 +176,35 @@ impl VkInit {
             .stage_flags(ShaderStageFlags::COMPUTE)
             .build()];
 
-        let pool_size = DescriptorPoolSize::builder()
-            .ty(DescriptorType::STORAGE_BUFFER)
-            .descriptor_count(ssbos.len() as u32)
-            .build();
-
-        let pool_sizes = [pool_size];
-        let desc_pool_create_info = DescriptorPoolCreateInfo::builder()
-            .max_sets(1)
-            .pool_sizes(&pool_sizes)
-            .build();
-
-        let desc_pool = unsafe {
-            self.device
-                .create_descriptor_pool(&desc_pool_create_info, None)
-        }?;
-        self.set_debug_object_name(
-            desc_pool.as_raw(),
-            ObjectType::DESCRIPTOR_POOL,
-            format!("{base_debug_name}_Descriptor_Pool"),
-        )?;
-
-        let mut layout_bindings: Vec<DescriptorSetLayoutBinding> = Vec::new();
-        let mut descriptor_buffers: Vec<DescriptorBufferInfo> = Vec::new();
+        let binding_types: Vec<DescriptorType> = bindings
+            .iter()
+            .map(|binding| binding.descriptor_type())
+            .collect();
+        let mut pool_sizes: Vec<(DescriptorType, u32)> = vec![];
+        for ty in &binding_types {
+            match pool_sizes.iter_mut().find(|(pool_ty, _)| pool_ty == ty) {
+                Some((_, count)) => *count += 1,
+                None => pool_sizes.push((*ty, 1)),
+            }
+        }
+        let mut desc_pools = match set_source {
+            SetSource::Own(policy) => {
+                Some(DescriptorPools::new(policy, &pool_sizes, &base_debug_name))
+            }
+            SetSource::Allocator(_) => None,
+        };
 
-        for (index, vma_buffer) in ssbos.iter().enumerate() {
-            let layout_binding = DescriptorSetLayoutBinding {
+        let layout_bindings: Vec<DescriptorSetLayoutBinding> = binding_types
+            .iter()
+            .enumerate()
+            .map(|(index, ty)| DescriptorSetLayoutBinding {
                 binding: index as u32,
-                descriptor_type: DescriptorType::STORAGE_BUFFER,
+                descriptor_type: *ty,
                 descriptor_count: 1,
                 stage_flags: ShaderStageFlags::COMPUTE,
                 ..Default::default()
-            };
-            layout_bindings.push(layout_binding);
-
-            let descriptor_buffer = DescriptorBufferInfo {
-                buffer: vma_buffer.buffer,
-                offset: 0,
-                range: WHOLE_SIZE,
-            };
-            descriptor_buffers.push(descriptor_buffer);
-        }
+            })
+            .collect();
 
         let desc_set_layout_info = DescriptorSetLayoutCreateInfo::builder()
             .bindings(&layout_bindings)
@@ -117,44 +214,25 @@ impl VkInit {
             self.device
                 .create_descriptor_set_layout(&desc_set_layout_info, None)?
         };
-        self.set_debug_object_name(
+        self.set_debug_object_name_fmt(
             desc_set_layout.as_raw(),
             ObjectType::DESCRIPTOR_SET_LAYOUT,
-            format!("{base_debug_name}_SSBO_Desc_Layout"),
+            format_args!("{base_debug_name}_Desc_Layout"),
         )?;
 
-        let desc_set_layouts = [desc_set_layout];
-        let alloc_info = ash::vk::DescriptorSetAllocateInfo::builder()
-            .descriptor_pool(desc_pool)
-            .set_layouts(&desc_set_layouts)
-            .build();
-
-        let desc_sets = unsafe { self.device.allocate_descriptor_sets(&alloc_info)? };
-        for (i, set) in desc_sets.iter().enumerate() {
-            self.set_debug_object_name(
-                set.as_raw(),
-                ObjectType::DESCRIPTOR_SET,
-                format!("{base_debug_name}_SSBO_Set_{i}"),
-            )?;
-        }
-
-        let mut write_sets: Vec<ash::vk::WriteDescriptorSet> = Vec::new();
-        for (index, descriptor_buffer) in descriptor_buffers.iter().enumerate() {
-            let write_set = ash::vk::WriteDescriptorSet {
-                dst_set: desc_sets[0],
-                dst_binding: index as u32,
-                descriptor_count: 1,
-                descriptor_type: ash::vk::DescriptorType::STORAGE_BUFFER,
-                p_buffer_info: descriptor_buffer,
-                ..Default::default()
-            };
-            write_sets.push(write_set);
-        }
-
-        unsafe {
-            self.device.update_descriptor_sets(&write_sets, &[]);
-        }
+        let desc_set = match (&mut desc_pools, set_source) {
+            (Some(desc_pools), _) => desc_pools.allocate(self, desc_set_layout)?,
+            (None, SetSource::Allocator(allocator)) => allocator.allocate(self, desc_set_layout)?,
+            (None, SetSource::Own(_)) => unreachable!("own descriptor pools are always created"),
+        };
+        self.set_debug_object_name_fmt(
+            desc_set.as_raw(),
+            ObjectType::DESCRIPTOR_SET,
+            format_args!("{base_debug_name}_Set_0"),
+        )?;
+        write_set(&self.device, desc_set, bindings);
 
+        let desc_set_layouts = [desc_set_layout];
         let pipeline_layout_info = PipelineLayoutCreateInfo::builder()
             .set_layouts(&desc_set_layouts)
             .push_constant_ranges(&push_constants_ranges)
@@ -164,10 +242,10 @@ impl VkInit {
             self.device
                 .create_pipeline_layout(&pipeline_layout_info, None)?
         };
-        self.set_debug_object_name(
+        self.set_debug_object_name_fmt(
             pipeline_layout.as_raw(),
             ObjectType::PIPELINE_LAYOUT,
-            format!("{base_debug_name}_Pipeline_Layout"),
+            format_args!("{base_debug_name}_Pipeline_Layout"),
         )?;
 
         let pipeline_info = ComputePipelineCreateInfo::builder()
@@ -175,28 +253,29 @@ impl VkInit {
             .layout(pipeline_layout);
 
         let pipeline = unsafe {
-            match self.device.create_compute_pipelines(
-                PipelineCache::null(),
-                &[*pipeline_info],
-                None,
-            ) {
+            match self
+                .device
+                .create_compute_pipelines(self.pipeline_cache, &[*pipeline_info], None)
+            {
                 Ok(pipeline) => pipeline[0],
                 Err((_, e)) => return Err(e.into()),
             }
         };
-        self.set_debug_object_name(
+        self.set_debug_object_name_fmt(
             pipeline.as_raw(),
             ObjectType::PIPELINE,
-            format!("{base_debug_name}_Pipeline"),
+            format_args!("{base_debug_name}_Pipeline"),
         )?;
 
         Ok(ComputeShader {
             pipeline,
             layout: pipeline_layout,
-            desc_pool,
+            desc_pools: desc_pools.map(Mutex::new),
             desc_set_layout,
-            desc_sets,
+            desc_sets: vec![desc_set],
+            binding_types,
             group_sizes,
+            base_debug_name,
         })
     }
 }
@@ -209,12 +288,126 @@ impl ComputeShader {
             vk_init
                 .device
                 .destroy_descriptor_set_layout(self.desc_set_layout, None);
-            vk_init.device.destroy_descriptor_pool(self.desc_pool, None);
+        }
+        if let Some(Ok(mut desc_pools)) = self.desc_pools.as_ref().map(Mutex::lock) {
+            desc_pools.destroy(&vk_init.device);
+        }
+        Ok(())
+    }
+
+    /// Allocates another descriptor set with ```bindings``` bound in order and returns its index for [bind_set](ComputeShader::bind_set).
+    ///
+    /// Creates a follow-up descriptor pool according to the [DescriptorPoolPolicy] if needed.
+    /// Returns [ComputeBindingsMismatch](Error::ComputeBindingsMismatch) if the descriptor types differ from the [binding_types](ComputeShader::binding_types)
+    /// and [ComputeShaderWithoutPools](Error::ComputeShaderWithoutPools) if the shader was created with a [DescriptorAllocator].
+    pub fn allocate_set(
+        &mut self,
+        vk_init: &VkInit,
+        bindings: &[ComputeBinding],
+    ) -> Result<usize, Error> {
+        self.validate_bindings(bindings)?;
+        let desc_set = self
+            .desc_pools
+            .as_ref()
+            .ok_or(Error::ComputeShaderWithoutPools)?
+            .lock()
+            .expect("compute shader descriptor pools poisoned")
+            .allocate(vk_init, self.desc_set_layout)?;
+        self.push_set(vk_init, desc_set, bindings)
+    }
+
+    /// Same as [allocate_set](ComputeShader::allocate_set) with the set allocated from ```allocator```, which must outlive its use.
+    pub fn allocate_set_from(
+        &mut self,
+        vk_init: &VkInit,
+        allocator: &DescriptorAllocator,
+        bindings: &[ComputeBinding],
+    ) -> Result<usize, Error> {
+        self.validate_bindings(bindings)?;
+        let desc_set = allocator.allocate(vk_init, self.desc_set_layout)?;
+        self.push_set(vk_init, desc_set, bindings)
+    }
+
+    fn push_set(
+        &mut self,
+        vk_init: &VkInit,
+        desc_set: DescriptorSet,
+        bindings: &[ComputeBinding],
+    ) -> Result<usize, Error> {
+        vk_init.set_debug_object_name_fmt(
+            desc_set.as_raw(),
+            ObjectType::DESCRIPTOR_SET,
+            format_args!("{}_Set_{}", self.base_debug_name, self.desc_sets.len()),
+        )?;
+        write_set(&vk_init.device, desc_set, bindings);
+        self.desc_sets.push(desc_set);
+        Ok(self.desc_sets.len() - 1)
+    }
+
+    /// Points the set created by [create_compute_shader](VkInit::create_compute_shader) at new resources, e.g. after growing a buffer.
+    ///
+    /// The set must not be in use by pending command buffers.
+    /// Returns [ComputeBindingsMismatch](Error::ComputeBindingsMismatch) if the descriptor types differ from the [binding_types](ComputeShader::binding_types).
+    pub fn update_bindings(
+        &self,
+        device: &ash::Device,
+        bindings: &[ComputeBinding],
+    ) -> Result<(), Error> {
+        self.update_set_bindings(device, 0, bindings)
+    }
+
+    /// Same as [update_bindings](ComputeShader::update_bindings) for the set returned by [allocate_set](ComputeShader::allocate_set).
+    pub fn update_set_bindings(
+        &self,
+        device: &ash::Device,
+        set_index: usize,
+        bindings: &[ComputeBinding],
+    ) -> Result<(), Error> {
+        self.validate_bindings(bindings)?;
+        write_set(device, self.desc_sets[set_index], bindings);
+        Ok(())
+    }
+
+    /// Descriptor type per binding, in binding order.
+    pub fn binding_types(&self) -> &[DescriptorType] {
+        &self.binding_types
+    }
+
+    fn validate_bindings(&self, bindings: &[ComputeBinding]) -> Result<(), Error> {
+        let found: Vec<DescriptorType> = bindings
+            .iter()
+            .map(|binding| binding.descriptor_type())
+            .collect();
+        if found != self.binding_types {
+            return Err(Error::ComputeBindingsMismatch {
+                expected: self.binding_types.clone(),
+                found,
+            });
         }
         Ok(())
     }
 
+    /// Number of own descriptor pools created so far, always 0 with a [DescriptorAllocator].
+    pub fn descriptor_pool_count(&self) -> usize {
+        self.desc_pools
+            .as_ref()
+            .and_then(|desc_pools| desc_pools.lock().ok())
+            .map_or(0, |desc_pools| desc_pools.pool_count())
+    }
+
+    /// Binds the pipeline with the set created by [create_compute_shader](VkInit::create_compute_shader).
     pub fn bind(&self, device: &ash::Device, cmd_buffer: &CommandBuffer, constants: &[u8]) {
+        self.bind_set(device, cmd_buffer, 0, constants);
+    }
+
+    /// Same as [bind](ComputeShader::bind) with the set returned by [allocate_set](ComputeShader::allocate_set).
+    pub fn bind_set(
+        &self,
+        device: &ash::Device,
+        cmd_buffer: &CommandBuffer,
+        set_index: usize,
+        constants: &[u8],
+    ) {
         unsafe {
             device.cmd_bind_pipeline(*cmd_buffer, PipelineBindPoint::COMPUTE, self.pipeline);
             device.cmd_bind_descriptor_sets(
@@ -222,7 +415,7 @@ impl ComputeShader {
                 PipelineBindPoint::COMPUTE,
                 self.layout,
                 0,
-                &self.desc_sets,
+                &self.desc_sets[set_index..=set_index],
                 &[],
             );
             device.cmd_push_constants(
@@ -235,6 +428,9 @@ impl ComputeShader {
         }
     }
 
+    /// Dispatches enough groups to cover ```dispatch_x``` * ```dispatch_y``` * ```dispatch_z``` invocations.
+    ///
+    /// Group counts are rounded up, shaders have to skip invocations beyond the work size.
     pub fn dispatch(
         &self,
         device: &ash::Device,
@@ -242,14 +438,102 @@ impl ComputeShader {
         dispatch_x: u32,
         dispatch_y: u32,
         dispatch_z: u32,
+    ) {
+        self.dispatch_exact(
+            device,
+            cmd_buffer,
+            dispatch_x.div_ceil(self.group_sizes[0]),
+            dispatch_y.div_ceil(self.group_sizes[1]),
+            dispatch_z.div_ceil(self.group_sizes[2]),
+        );
+    }
+
+    /// Same as [dispatch](ComputeShader::dispatch) with the number of groups instead of invocations.
+    pub fn dispatch_exact(
+        &self,
+        device: &ash::Device,
+        cmd_buffer: &CommandBuffer,
+        groups_x: u32,
+        groups_y: u32,
+        groups_z: u32,
     ) {
         unsafe {
-            device.cmd_dispatch(
-                *cmd_buffer,
-                (dispatch_x / self.group_sizes[0]).max(1),
-                (dispatch_y / self.group_sizes[1]).max(1),
-                (dispatch_z / self.group_sizes[2]).max(1),
-            );
+            device.cmd_dispatch(*cmd_buffer, groups_x, groups_y, groups_z);
+        }
+    }
+
+    /// Same as [dispatch_exact](ComputeShader::dispatch_exact) with the group counts read from the ```DispatchIndirectCommand``` at byte ```offset``` of ```buffer```.
+    ///
+    /// ```buffer``` needs ```INDIRECT_BUFFER``` usage, e.g. from [create_indirect_buffer](VMABuffer::create_indirect_buffer).
+    /// Writes to it have to be made visible to ```PipelineStageFlags2::DRAW_INDIRECT``` first.
+    pub fn dispatch_indirect(
+        &self,
+        device: &ash::Device,
+        cmd_buffer: &CommandBuffer,
+        buffer: &VMABuffer,
+        offset: u64,
+    ) {
+        unsafe {
+            device.cmd_dispatch_indirect(*cmd_buffer, buffer.buffer, offset);
         }
     }
 }
+
+fn write_set(device: &Device, desc_set: DescriptorSet, bindings: &[ComputeBinding]) {
+    let buffer_infos: Vec<DescriptorBufferInfo> = bindings
+        .iter()
+        .map(|binding| match binding {
+            ComputeBinding::Ssbo(vma_buffer) | ComputeBinding::Uniform(vma_buffer) => {
+                DescriptorBufferInfo {
+                    buffer: vma_buffer.buffer,
+                    offset: 0,
+                    range: WHOLE_SIZE,
+                }
+            }
+            ComputeBinding::SsboRange(vma_buffer, offset, range)
+            | ComputeBinding::UniformRange(vma_buffer, offset, range) => DescriptorBufferInfo {
+                buffer: vma_buffer.buffer,
+                offset: *offset,
+                range: *range,
+            },
+            _ => DescriptorBufferInfo::default(),
+        })
+        .collect();
+
+    let image_infos: Vec<DescriptorImageInfo> = bindings
+        .iter()
+        .map(|binding| match binding {
+            ComputeBinding::StorageImage(vma_image) => DescriptorImageInfo {
+                sampler: Sampler::null(),
+                image_view: vma_image.image_view,
+                image_layout: ImageLayout::GENERAL,
+            },
+            ComputeBinding::SampledImage(vma_image, sampler) => DescriptorImageInfo {
+                sampler: *sampler,
+                image_view: vma_image.image_view,
+                image_layout: ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            },
+            _ => DescriptorImageInfo::default(),
+        })
+        .collect();
+
+    let write_sets: Vec<WriteDescriptorSet> = bindings
+        .iter()
+        .enumerate()
+        .map(|(index, binding)| {
+            let write_set = WriteDescriptorSet::builder()
+                .dst_set(desc_set)
+                .dst_binding(index as u32)
+                .descriptor_type(binding.descriptor_type());
+            match binding {
+                ComputeBinding::StorageImage(_) | ComputeBinding::SampledImage(_, _) => {
+                    write_set.image_info(&image_infos[index..=index])
+                }
+                _ => write_set.buffer_info(&buffer_infos[index..=index]),
+            }
+            .build()
+        })
+        .collect();
+
+    unsafe { device.update_descriptor_sets(&write_sets, &[]) };
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::sync::Mutex;
+
+use crate::{imports::*, PhysicalDeviceInfo, VkInit};
+
+/// Checkpoint markers of [crash_diagnostics](crate::VkInitCreateInfo::crash_diagnostics).
+///
+/// Only loaded if [diagnostic_checkpoints](PhysicalDeviceInfo::diagnostic_checkpoints) is enabled, otherwise all calls are no-ops.
+#[derive(Default)]
+pub(crate) struct CrashDiagnostics {
+    checkpoints_fn: Option<NvDeviceDiagnosticCheckpointsFn>,
+    labels: Mutex<CheckpointLabels>,
+}
+
+/// Interned label names, the checkpoint marker of a label is its index + 1 to keep markers non-null.
+#[derive(Default)]
+struct CheckpointLabels {
+    markers: HashMap<String, usize>,
+    names: Vec<String>,
+}
+
+impl CrashDiagnostics {
+    pub(crate) unsafe fn load(
+        instance: &Instance,
+        device: &Device,
+        physical_device_info: &PhysicalDeviceInfo,
+    ) -> Self {
+        if !physical_device_info.diagnostic_checkpoints {
+            return Self::default();
+        }
+        let checkpoints_fn = NvDeviceDiagnosticCheckpointsFn::load(|name| {
+            std::mem::transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+        });
+        Self {
+            checkpoints_fn: Some(checkpoints_fn),
+            labels: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn set_checkpoint(&self, cmd_buffer: &CommandBuffer, name: &str) {
+        let Some(checkpoints_fn) = &self.checkpoints_fn else {
+            return;
+        };
+        let Ok(mut labels) = self.labels.lock() else {
+            return;
+        };
+        let name = name.trim_end_matches('\0');
+        let marker = match labels.markers.get(name) {
+            Some(marker) => *marker,
+            None => {
+                labels.names.push(name.to_string());
+                let marker = labels.names.len();
+                labels.markers.insert(name.to_string(), marker);
+                marker
+            }
+        };
+        unsafe { (checkpoints_fn.cmd_set_checkpoint_nv)(*cmd_buffer, marker as *const c_void) };
+    }
+
+    /// Last checkpoints the device reached on ```queue``` with their pipeline stages, oldest first.
+    fn queue_checkpoints(&self, queue: Queue) -> Vec<(PipelineStageFlags, String)> {
+        let Some(checkpoints_fn) = &self.checkpoints_fn else {
+            return vec![];
+        };
+        let mut count = 0;
+        let mut data = unsafe {
+            (checkpoints_fn.get_queue_checkpoint_data_nv)(queue, &mut count, std::ptr::null_mut());
+            vec![CheckpointDataNV::default(); count as usize]
+        };
+        unsafe {
+            (checkpoints_fn.get_queue_checkpoint_data_nv)(queue, &mut count, data.as_mut_ptr())
+        };
+
+        let labels = self.labels.lock();
+        data.iter()
+            .take(count as usize)
+            .map(|checkpoint| {
+                let marker = checkpoint.p_checkpoint_marker as usize;
+                let name = labels
+                    .as_ref()
+                    .ok()
+                    .and_then(|labels| labels.names.get(marker.wrapping_sub(1)).cloned())
+                    .unwrap_or_else(|| format!("marker {marker:#x}"));
+                (checkpoint.stage, name)
+            })
+            .collect()
+    }
+}
+
+impl VkInit {
+    /// Logs everything known about a device loss, call it after any ```ERROR_DEVICE_LOST```.
+    ///
+    /// Includes the [device fault](crate::PhysicalDeviceInfo::device_fault) info and, with [crash_diagnostics](crate::VkInitCreateInfo::crash_diagnostics),
+    /// the last [debug label](VkInit::begin_debug_label) checkpoints reached on each queue.
+    /// [Watched fence waits](VkInit::wait_for_fences_watched) call this automatically.
+    pub fn log_device_lost_diagnostics(&self) {
+        self.log_device_fault();
+        let queues = [
+            ("unified", Some(self.unified_queue)),
+            ("transfer", self.transfer_queue),
+            ("compute", self.compute_queue),
+        ];
+        for (queue_name, queue) in queues {
+            let Some(queue) = queue else {
+                continue;
+            };
+            for (stage, label) in self.crash_diagnostics.queue_checkpoints(queue) {
+                error!("Last checkpoint on the {queue_name} queue: {label} at {stage:?}");
+            }
+        }
+    }
+}
@@ -1,9 +1,12 @@
-use crate::imports::*;
+use crate::{imports::*, FeatureStruct};
 
 /// Creation parameters for [VkInit](crate::init::VkInit).
 ///
 /// Windowing extensions are enabled automatically depending on the chosen platform.
-
+/// Non-exhaustive: start from a preset like [Default](VkInitCreateInfo::default) and assign the fields to change,
+/// or chain them with [builder](VkInitCreateInfo::builder).
+#[non_exhaustive]
+#[derive(Clone)]
 pub struct VkInitCreateInfo {
     pub app_name: String,
     pub engine_name: String,
@@ -20,21 +23,85 @@ pub struct VkInitCreateInfo {
 
     //PhysicalDevice
     pub allow_igpu: bool,
-    pub physical_device_1_1_features: PhysicalDeviceVulkan11Features,
-    pub physical_device_1_2_features: PhysicalDeviceVulkan12Features,
-    pub physical_device_1_3_features: PhysicalDeviceVulkan13Features,
+    pub gpu_preference: GpuSelection,
+    /// The ```p_next``` chain of the feature structs is built by vku and has to be left null.
+    pub physical_device_1_1_features: FeatureStruct<PhysicalDeviceVulkan11Features>,
+    pub physical_device_1_2_features: FeatureStruct<PhysicalDeviceVulkan12Features>,
+    pub physical_device_1_3_features: FeatureStruct<PhysicalDeviceVulkan13Features>,
 
     //Device
     pub additional_device_extensions: Vec<String>,
 
     //Surface
-    pub surface_format: Format,
+    /// Tried in order, falling back to the first format the surface supports.
+    pub surface_format_preferences: Vec<Format>,
     pub depth_format: Format,
     pub depth_format_sizeof: usize,
     pub request_img_count: u32,
-    pub present_mode: PresentModeKHR,
+    /// Tried in order, falling back to ```FIFO```.
+    pub present_mode_preferences: Vec<PresentModeKHR>,
     pub clear_color_value: ClearColorValue,
     pub clear_depth_stencil_value: ClearDepthStencilValue,
+    /// Samples per texel of the color and depth attachments, the swapchain image receives the resolved color.
+    /// Pipelines rendering to the [Head](crate::Head) need the same sample count.
+    pub msaa_samples: SampleCountFlags,
+
+    //Frames
+    /// Frames recorded ahead of the GPU, see [FrameScheme](crate::FrameScheme).
+    pub frames_in_flight: usize,
+
+    //Uploads
+    /// Largest staging buffer [upload_to_buffer](crate::VkInit::upload_to_buffer) uses, larger uploads are split into chunks.
+    pub max_staging_chunk: usize,
+    /// Bytes of staging buffers the [StagingPool](crate::StagingPool) keeps for reuse, released buffers beyond it are destroyed.
+    pub max_staging_pool_bytes: u64,
+
+    //Allocations
+    /// Device-local allocations of the [VkInit](crate::VkInit) shortcuts that fail are retried once after draining the
+    /// [deletion queue](crate::DeletionQueue), returning [GpuAllocRetryFailed](Error::GpuAllocRetryFailed) if that fails as well.
+    pub retry_failed_allocations: bool,
+
+    //Diagnostics
+    /// Fence waits of vku that take longer log a hang report and return [GpuHangSuspected](Error::GpuHangSuspected),
+    /// presents that take longer are reported by a monitoring thread. ```None``` waits forever.
+    pub gpu_watchdog: Option<std::time::Duration>,
+    /// Enables vendor crash diagnostics for GPU crash debugging tools where the device supports them:
+    /// - ```bufferDeviceAddressCaptureReplay``` together with ```bufferDeviceAddress```
+    /// - [VK_NV_device_diagnostics_config](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_NV_device_diagnostics_config.html)
+    ///   with shader debug info, resource tracking and automatic checkpoints
+    /// - [VK_NV_device_diagnostic_checkpoints](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_NV_device_diagnostic_checkpoints.html),
+    ///   inserting a checkpoint at every [begin_debug_label](crate::VkInit::begin_debug_label)
+    ///
+    /// The allocator of vku does not allocate device address memory, buffers with capture replay addresses need their own allocations.
+    /// See [log_device_lost_diagnostics](crate::VkInit::log_device_lost_diagnostics). Unsupported parts are skipped.
+    pub crash_diagnostics: bool,
+
+    //Teardown
+    /// Captures a backtrace for every [VMABuffer](crate::VMABuffer), [VMAImage](crate::VMAImage) and [VKUPipeline](crate::pipeline_builder::VKUPipeline) in debug builds,
+    /// reported by [destroy](crate::VkInit::destroy) for resources that are still alive.
+    pub capture_resource_backtraces: bool,
+    /// [destroy](crate::VkInit::destroy) returns [ResourcesStillAlive](Error::ResourcesStillAlive) instead of tearing down
+    /// while resources are still alive. Only tracked in debug builds.
+    pub strict_teardown: bool,
+
+    //Environment
+    /// [VkInit::new](crate::VkInit::new) applies [apply_env_overrides](VkInitCreateInfo::apply_env_overrides) before initialization.
+    pub auto_env: bool,
+}
+
+/// Physical device [VkInit](crate::VkInit) picks among all devices with a unified queue.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GpuSelection {
+    /// First discrete GPU, or first GPU of any type with [allow_igpu](VkInitCreateInfo::allow_igpu).
+    #[default]
+    FirstDiscrete,
+    /// Index into the enumerated physical devices, regardless of their type.
+    Index(usize),
+    /// First device whose name contains the case-insensitive substring, regardless of its type.
+    NamedSubstring(String),
+    /// Device with the most device-local memory, regardless of its type. Ties go to the first device.
+    HighestMemory,
 }
 
 impl VkInitCreateInfo {
@@ -47,7 +114,8 @@ impl VkInitCreateInfo {
     /// [DynamicRendering](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_dynamic_rendering.html),
     /// [DescriptorIndexing](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_EXT_descriptor_indexing.html),
     /// [ShaderDrawParameters](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_shader_draw_parameters.html),
-    /// and [Synchronization2](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_synchronization2.html)
+    /// [Synchronization2](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_synchronization2.html)
+    /// and [TimelineSemaphore](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_timeline_semaphore.html)
     /// are enabled by default.
 
     pub fn verbose_debug_vk_1_3() -> Self {
@@ -71,27 +139,33 @@ impl VkInitCreateInfo {
                 | DebugUtilsMessageTypeFlagsEXT::VALIDATION
                 | DebugUtilsMessageTypeFlagsEXT::PERFORMANCE,
             allow_igpu: false,
+            gpu_preference: GpuSelection::FirstDiscrete,
             physical_device_1_3_features: PhysicalDeviceVulkan13Features::builder()
                 .synchronization2(true)
                 .dynamic_rendering(true)
-                .build(),
+                .build()
+                .into(),
             physical_device_1_2_features: PhysicalDeviceVulkan12Features::builder()
                 .descriptor_binding_sampled_image_update_after_bind(true)
                 .descriptor_indexing(true)
-                .build(),
+                .timeline_semaphore(true)
+                .draw_indirect_count(true)
+                .build()
+                .into(),
             physical_device_1_1_features: PhysicalDeviceVulkan11Features::builder()
                 .shader_draw_parameters(true)
-                .build(),
+                .build()
+                .into(),
             additional_device_extensions: vec![],
-            surface_format: if cfg!(target_os = "linux") {
-                Format::B8G8R8A8_UNORM
+            surface_format_preferences: if cfg!(target_os = "linux") {
+                vec![Format::B8G8R8A8_UNORM, Format::R8G8B8A8_UNORM]
             } else {
-                Format::R8G8B8A8_UNORM
+                vec![Format::R8G8B8A8_UNORM, Format::B8G8R8A8_UNORM]
             },
             depth_format: Format::D32_SFLOAT,
             depth_format_sizeof: 4,
             request_img_count: 3,
-            present_mode: PresentModeKHR::FIFO,
+            present_mode_preferences: vec![PresentModeKHR::FIFO],
             clear_color_value: ClearColorValue {
                 float32: [0.0, 0.0, 0.0, 0.0],
             },
@@ -99,6 +173,16 @@ impl VkInitCreateInfo {
                 depth: 1.0,
                 stencil: 0,
             },
+            msaa_samples: SampleCountFlags::TYPE_1,
+            frames_in_flight: 2,
+            max_staging_chunk: 64 * 1024 * 1024,
+            max_staging_pool_bytes: 256 * 1024 * 1024,
+            retry_failed_allocations: false,
+            gpu_watchdog: None,
+            crash_diagnostics: false,
+            capture_resource_backtraces: false,
+            strict_teardown: false,
+            auto_env: false,
         }
     }
 
@@ -141,6 +225,239 @@ impl VkInitCreateInfo {
             ..Self::verbose_debug_vk_1_3()
         }
     }
+
+    /// Alias of [dist_vk_1_3](VkInitCreateInfo::dist_vk_1_3).
+    pub fn release_vk_1_3() -> Self {
+        Self::dist_vk_1_3()
+    }
+
+    /// Builder starting from [Default](VkInitCreateInfo::default), other presets convert with [From].
+    /// ```
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// let create_info = VkInitCreateInfo::builder()
+    ///     .app_name("Game")
+    ///     .present_mode(PresentModeKHR::MAILBOX)
+    ///     .build();
+    ///
+    /// let dist = VkInitCreateInfoBuilder::from(VkInitCreateInfo::dist_vk_1_3())
+    ///     .frames_in_flight(3)
+    ///     .build();
+    /// ```
+    pub fn builder() -> VkInitCreateInfoBuilder {
+        VkInitCreateInfoBuilder::from(Self::default())
+    }
+}
+
+/// Chainable setters for the fields of [VkInitCreateInfo], see [builder](VkInitCreateInfo::builder).
+#[derive(Clone)]
+pub struct VkInitCreateInfoBuilder {
+    create_info: VkInitCreateInfo,
+}
+
+impl From<VkInitCreateInfo> for VkInitCreateInfoBuilder {
+    fn from(create_info: VkInitCreateInfo) -> Self {
+        Self { create_info }
+    }
+}
+
+impl VkInitCreateInfoBuilder {
+    pub fn app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.create_info.app_name = app_name.into();
+        self
+    }
+
+    pub fn engine_name(mut self, engine_name: impl Into<String>) -> Self {
+        self.create_info.engine_name = engine_name.into();
+        self
+    }
+
+    pub fn app_version(mut self, app_version: u32) -> Self {
+        self.create_info.app_version = app_version;
+        self
+    }
+
+    pub fn vk_version(mut self, vk_version: u32) -> Self {
+        self.create_info.vk_version = vk_version;
+        self
+    }
+
+    /// Only toggles validation, the layers and features stay as they are.
+    pub fn enable_validation(mut self, enable_validation: bool) -> Self {
+        self.create_info.enable_validation = enable_validation;
+        self
+    }
+
+    pub fn enabled_validation_layers(mut self, layers: &[&str]) -> Self {
+        self.create_info.enabled_validation_layers =
+            layers.iter().map(|layer| layer.to_string()).collect();
+        self
+    }
+
+    pub fn enabled_validation_features(mut self, features: &[ValidationFeatureEnableEXT]) -> Self {
+        self.create_info.enabled_validation_features = features.to_vec();
+        self
+    }
+
+    pub fn additional_instance_extensions(mut self, extensions: &[&str]) -> Self {
+        self.create_info.additional_instance_extensions =
+            extensions.iter().map(|ext| ext.to_string()).collect();
+        self
+    }
+
+    pub fn log_level(mut self, log_level: DebugUtilsMessageSeverityFlagsEXT) -> Self {
+        self.create_info.log_level = log_level;
+        self
+    }
+
+    pub fn log_msg(mut self, log_msg: DebugUtilsMessageTypeFlagsEXT) -> Self {
+        self.create_info.log_msg = log_msg;
+        self
+    }
+
+    pub fn allow_igpu(mut self, allow_igpu: bool) -> Self {
+        self.create_info.allow_igpu = allow_igpu;
+        self
+    }
+
+    pub fn gpu_preference(mut self, gpu_preference: GpuSelection) -> Self {
+        self.create_info.gpu_preference = gpu_preference;
+        self
+    }
+
+    /// The ```p_next``` chain has to be left null.
+    pub fn physical_device_1_1_features(
+        mut self,
+        features: PhysicalDeviceVulkan11Features,
+    ) -> Self {
+        self.create_info.physical_device_1_1_features = features.into();
+        self
+    }
+
+    /// The ```p_next``` chain has to be left null.
+    pub fn physical_device_1_2_features(
+        mut self,
+        features: PhysicalDeviceVulkan12Features,
+    ) -> Self {
+        self.create_info.physical_device_1_2_features = features.into();
+        self
+    }
+
+    /// The ```p_next``` chain has to be left null.
+    pub fn physical_device_1_3_features(
+        mut self,
+        features: PhysicalDeviceVulkan13Features,
+    ) -> Self {
+        self.create_info.physical_device_1_3_features = features.into();
+        self
+    }
+
+    pub fn additional_device_extensions(mut self, extensions: &[&str]) -> Self {
+        self.create_info.additional_device_extensions =
+            extensions.iter().map(|ext| ext.to_string()).collect();
+        self
+    }
+
+    /// Single preferred surface format, see [surface_format_preferences](VkInitCreateInfoBuilder::surface_format_preferences).
+    pub fn surface_format(mut self, surface_format: Format) -> Self {
+        self.create_info.surface_format_preferences = vec![surface_format];
+        self
+    }
+
+    pub fn surface_format_preferences(mut self, preferences: &[Format]) -> Self {
+        self.create_info.surface_format_preferences = preferences.to_vec();
+        self
+    }
+
+    /// Sets ```depth_format``` together with the size of one of its texels.
+    pub fn depth_format(mut self, depth_format: Format, depth_format_sizeof: usize) -> Self {
+        self.create_info.depth_format = depth_format;
+        self.create_info.depth_format_sizeof = depth_format_sizeof;
+        self
+    }
+
+    pub fn request_img_count(mut self, request_img_count: u32) -> Self {
+        self.create_info.request_img_count = request_img_count;
+        self
+    }
+
+    /// Single preferred present mode, see [present_mode_preferences](VkInitCreateInfoBuilder::present_mode_preferences).
+    pub fn present_mode(mut self, present_mode: PresentModeKHR) -> Self {
+        self.create_info.present_mode_preferences = vec![present_mode];
+        self
+    }
+
+    pub fn present_mode_preferences(mut self, preferences: &[PresentModeKHR]) -> Self {
+        self.create_info.present_mode_preferences = preferences.to_vec();
+        self
+    }
+
+    pub fn clear_color_value(mut self, clear_color_value: ClearColorValue) -> Self {
+        self.create_info.clear_color_value = clear_color_value;
+        self
+    }
+
+    pub fn clear_depth_stencil_value(
+        mut self,
+        clear_depth_stencil_value: ClearDepthStencilValue,
+    ) -> Self {
+        self.create_info.clear_depth_stencil_value = clear_depth_stencil_value;
+        self
+    }
+
+    pub fn msaa_samples(mut self, msaa_samples: SampleCountFlags) -> Self {
+        self.create_info.msaa_samples = msaa_samples;
+        self
+    }
+
+    pub fn frames_in_flight(mut self, frames_in_flight: usize) -> Self {
+        self.create_info.frames_in_flight = frames_in_flight;
+        self
+    }
+
+    pub fn max_staging_chunk(mut self, max_staging_chunk: usize) -> Self {
+        self.create_info.max_staging_chunk = max_staging_chunk;
+        self
+    }
+
+    pub fn max_staging_pool_bytes(mut self, max_staging_pool_bytes: u64) -> Self {
+        self.create_info.max_staging_pool_bytes = max_staging_pool_bytes;
+        self
+    }
+
+    pub fn retry_failed_allocations(mut self, retry_failed_allocations: bool) -> Self {
+        self.create_info.retry_failed_allocations = retry_failed_allocations;
+        self
+    }
+
+    pub fn gpu_watchdog(mut self, gpu_watchdog: Option<std::time::Duration>) -> Self {
+        self.create_info.gpu_watchdog = gpu_watchdog;
+        self
+    }
+
+    pub fn crash_diagnostics(mut self, crash_diagnostics: bool) -> Self {
+        self.create_info.crash_diagnostics = crash_diagnostics;
+        self
+    }
+
+    pub fn capture_resource_backtraces(mut self, capture_resource_backtraces: bool) -> Self {
+        self.create_info.capture_resource_backtraces = capture_resource_backtraces;
+        self
+    }
+
+    pub fn strict_teardown(mut self, strict_teardown: bool) -> Self {
+        self.create_info.strict_teardown = strict_teardown;
+        self
+    }
+
+    pub fn auto_env(mut self, auto_env: bool) -> Self {
+        self.create_info.auto_env = auto_env;
+        self
+    }
+
+    pub fn build(self) -> VkInitCreateInfo {
+        self.create_info
+    }
 }
 
 impl Default for VkInitCreateInfo {
@@ -0,0 +1,172 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::Duration;
+
+use crate::{imports::*, GpuSelection, VkInitCreateInfo};
+
+/// Plain-data fields of [VkInitCreateInfo], enums and flags as their raw values.
+///
+/// Missing fields and the device feature structs keep the values of [Default](VkInitCreateInfo::default).
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct CreateInfoSettings {
+    app_name: String,
+    engine_name: String,
+    app_version: u32,
+    vk_version: u32,
+    enable_validation: bool,
+    enabled_validation_layers: Vec<String>,
+    enabled_validation_features: Vec<i32>,
+    additional_instance_extensions: Vec<String>,
+    log_level: u32,
+    log_msg: u32,
+    allow_igpu: bool,
+    gpu_preference: GpuSelection,
+    additional_device_extensions: Vec<String>,
+    surface_format_preferences: Vec<i32>,
+    depth_format: i32,
+    depth_format_sizeof: usize,
+    request_img_count: u32,
+    present_mode_preferences: Vec<i32>,
+    clear_color: [f32; 4],
+    clear_depth: f32,
+    clear_stencil: u32,
+    msaa_samples: u32,
+    frames_in_flight: usize,
+    max_staging_chunk: usize,
+    max_staging_pool_bytes: u64,
+    retry_failed_allocations: bool,
+    gpu_watchdog: Option<Duration>,
+    crash_diagnostics: bool,
+    capture_resource_backtraces: bool,
+    strict_teardown: bool,
+    auto_env: bool,
+}
+
+impl Default for CreateInfoSettings {
+    fn default() -> Self {
+        Self::from(&VkInitCreateInfo::default())
+    }
+}
+
+impl From<&VkInitCreateInfo> for CreateInfoSettings {
+    fn from(info: &VkInitCreateInfo) -> Self {
+        Self {
+            app_name: info.app_name.clone(),
+            engine_name: info.engine_name.clone(),
+            app_version: info.app_version,
+            vk_version: info.vk_version,
+            enable_validation: info.enable_validation,
+            enabled_validation_layers: info.enabled_validation_layers.clone(),
+            enabled_validation_features: info
+                .enabled_validation_features
+                .iter()
+                .map(|feature| feature.as_raw())
+                .collect(),
+            additional_instance_extensions: info.additional_instance_extensions.clone(),
+            log_level: info.log_level.as_raw(),
+            log_msg: info.log_msg.as_raw(),
+            allow_igpu: info.allow_igpu,
+            gpu_preference: info.gpu_preference.clone(),
+            additional_device_extensions: info.additional_device_extensions.clone(),
+            surface_format_preferences: info
+                .surface_format_preferences
+                .iter()
+                .map(|format| format.as_raw())
+                .collect(),
+            depth_format: info.depth_format.as_raw(),
+            depth_format_sizeof: info.depth_format_sizeof,
+            request_img_count: info.request_img_count,
+            present_mode_preferences: info
+                .present_mode_preferences
+                .iter()
+                .map(|mode| mode.as_raw())
+                .collect(),
+            // The union is read as floats, integer clear colors keep their bits.
+            clear_color: unsafe { info.clear_color_value.float32 },
+            clear_depth: info.clear_depth_stencil_value.depth,
+            clear_stencil: info.clear_depth_stencil_value.stencil,
+            msaa_samples: info.msaa_samples.as_raw(),
+            frames_in_flight: info.frames_in_flight,
+            max_staging_chunk: info.max_staging_chunk,
+            max_staging_pool_bytes: info.max_staging_pool_bytes,
+            retry_failed_allocations: info.retry_failed_allocations,
+            gpu_watchdog: info.gpu_watchdog,
+            crash_diagnostics: info.crash_diagnostics,
+            capture_resource_backtraces: info.capture_resource_backtraces,
+            strict_teardown: info.strict_teardown,
+            auto_env: info.auto_env,
+        }
+    }
+}
+
+impl From<CreateInfoSettings> for VkInitCreateInfo {
+    fn from(settings: CreateInfoSettings) -> Self {
+        Self {
+            app_name: settings.app_name,
+            engine_name: settings.engine_name,
+            app_version: settings.app_version,
+            vk_version: settings.vk_version,
+            enable_validation: settings.enable_validation,
+            enabled_validation_layers: settings.enabled_validation_layers,
+            enabled_validation_features: settings
+                .enabled_validation_features
+                .into_iter()
+                .map(ValidationFeatureEnableEXT::from_raw)
+                .collect(),
+            additional_instance_extensions: settings.additional_instance_extensions,
+            log_level: DebugUtilsMessageSeverityFlagsEXT::from_raw(settings.log_level),
+            log_msg: DebugUtilsMessageTypeFlagsEXT::from_raw(settings.log_msg),
+            allow_igpu: settings.allow_igpu,
+            gpu_preference: settings.gpu_preference,
+            additional_device_extensions: settings.additional_device_extensions,
+            surface_format_preferences: settings
+                .surface_format_preferences
+                .into_iter()
+                .map(Format::from_raw)
+                .collect(),
+            depth_format: Format::from_raw(settings.depth_format),
+            depth_format_sizeof: settings.depth_format_sizeof,
+            request_img_count: settings.request_img_count,
+            present_mode_preferences: settings
+                .present_mode_preferences
+                .into_iter()
+                .map(PresentModeKHR::from_raw)
+                .collect(),
+            clear_color_value: ClearColorValue {
+                float32: settings.clear_color,
+            },
+            clear_depth_stencil_value: ClearDepthStencilValue {
+                depth: settings.clear_depth,
+                stencil: settings.clear_stencil,
+            },
+            msaa_samples: SampleCountFlags::from_raw(settings.msaa_samples),
+            frames_in_flight: settings.frames_in_flight,
+            max_staging_chunk: settings.max_staging_chunk,
+            max_staging_pool_bytes: settings.max_staging_pool_bytes,
+            retry_failed_allocations: settings.retry_failed_allocations,
+            gpu_watchdog: settings.gpu_watchdog,
+            crash_diagnostics: settings.crash_diagnostics,
+            capture_resource_backtraces: settings.capture_resource_backtraces,
+            strict_teardown: settings.strict_teardown,
+            auto_env: settings.auto_env,
+            ..VkInitCreateInfo::default()
+        }
+    }
+}
+
+/// Serializes the plain-data fields, formats, present modes and flags as their raw values.
+/// The device feature structs are skipped.
+impl Serialize for VkInitCreateInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        CreateInfoSettings::from(self).serialize(serializer)
+    }
+}
+
+/// Missing fields and the device feature structs keep the values of [Default](VkInitCreateInfo::default).
+impl<'de> Deserialize<'de> for VkInitCreateInfo {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        CreateInfoSettings::deserialize(deserializer).map(Self::from)
+    }
+}
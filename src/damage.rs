@@ -0,0 +1,188 @@
+use crate::{imports::*, RenderingConfig, VkInit};
+
+impl VkInit {
+    /// Begins rendering to the swapchain image and depth image, limited to ```render_area```.
+    /// Instances without a [Head](crate::Head) render to the [offscreen head](VkInit::create_offscreen_head) instead.
+    ///
+    /// With ```AttachmentLoadOp::LOAD``` only the area is re-rendered, e.g. the dirty rectangle of a UI,
+    /// and can then be presented with [present_with_damage](VkInit::present_with_damage).
+    /// Each swapchain image keeps its own previous contents: re-render everything that changed since the image was last presented
+    /// and transition it from ```PRESENT_SRC_KHR``` instead of ```UNDEFINED```.
+    /// Pipelines with a static scissor must stay inside the area, dynamic scissors can be set with
+    /// [cmd_set_scissor_in_render_area](VkInit::cmd_set_scissor_in_render_area).
+    /// See [begin_rendering_with](VkInit::begin_rendering_with) for separate ops per attachment or without depth.
+    ///
+    /// Returns [RenderAreaOutOfBounds](Error::RenderAreaOutOfBounds) if the area exceeds the surface extent.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let cmd_pool = init.create_cmd_pool(CmdType::Any)?;
+    /// let cmd_buffer = init.create_command_buffers(&cmd_pool, 1)?[0];
+    /// let image_available = init.create_semaphore()?;
+    /// let AcquireResult::Ready { view: image_view, .. } = init.acquire_next_swapchain_image(image_available, None)? else {
+    ///     return Ok(()); // recreate the swapchain
+    /// };
+    ///
+    /// let dirty = Rect2D{offset: Offset2D{x: 16, y: 16}, extent: Extent2D{width: 64, height: 32}};
+    /// init.begin_cmd_buffer(&cmd_buffer)?;
+    /// init.begin_rendering_ex(&image_view, &cmd_buffer, dirty, AttachmentLoadOp::LOAD)?;
+    /// init.cmd_set_scissor_in_render_area(&cmd_buffer, dirty, dirty)?;
+    /// // draw the dirty rectangle
+    /// init.end_rendering(&cmd_buffer);
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn begin_rendering_ex(
+        &self,
+        swapchain_image_view: &ImageView,
+        cmd_buffer: &CommandBuffer,
+        render_area: Rect2D,
+        load_op: AttachmentLoadOp,
+    ) -> Result<(), Error> {
+        self.begin_rendering_with(
+            swapchain_image_view,
+            cmd_buffer,
+            &RenderingConfig {
+                render_area: Some(render_area),
+                color_load_op: load_op,
+                depth_load_op: load_op,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Sets the dynamic scissor, returns [ScissorOutsideRenderArea](Error::ScissorOutsideRenderArea)
+    /// without recording if ```scissor``` is not inside the ```render_area``` passed to [begin_rendering_ex](VkInit::begin_rendering_ex).
+    pub fn cmd_set_scissor_in_render_area(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        scissor: Rect2D,
+        render_area: Rect2D,
+    ) -> Result<(), Error> {
+        if !rect_contains(render_area, scissor) {
+            return Err(Error::ScissorOutsideRenderArea {
+                scissor,
+                area: render_area,
+            });
+        }
+        unsafe {
+            self.device.cmd_set_scissor(*cmd_buffer, 0, &[scissor]);
+        }
+        Ok(())
+    }
+
+    /// Same as [present](VkInit::present), but hints the presentation engine that only ```damage``` changed.
+    ///
+    /// Uses [VK_KHR_incremental_present](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_incremental_present.html)
+    /// if [incremental_present](crate::PhysicalDeviceInfo::incremental_present) is supported and presents normally otherwise.
+    /// Rectangles are clipped to the surface extent, an empty slice marks the whole image as changed.
+    pub fn present_with_damage(
+        &self,
+        rendering_complete_semaphore: &Semaphore,
+        frame: usize,
+        damage: &[Rect2D],
+    ) -> Result<(), Error> {
+        let damage = match self.physical_device_info.incremental_present {
+            true => Some(damage),
+            false => None,
+        };
+        self.present_regions(rendering_complete_semaphore, frame, damage)
+    }
+
+    pub(crate) fn present_regions(
+        &self,
+        rendering_complete_semaphore: &Semaphore,
+        frame: usize,
+        damage: Option<&[Rect2D]>,
+    ) -> Result<(), Error> {
+        let Some(head) = self.head.as_ref() else {
+            return Err(Error::HeadCallOnHeadlessInstance);
+        };
+        let swapchains = [head.swapchain];
+        let image_indices = [frame as u32];
+        let wait_sems = [*rendering_complete_semaphore];
+
+        let extent = head.surface_info.current_extent;
+        let rectangles: Vec<RectLayerKHR> = damage
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|rect| clip_to_extent(*rect, extent))
+            .map(|rect| RectLayerKHR {
+                offset: rect.offset,
+                extent: rect.extent,
+                layer: 0,
+            })
+            .collect();
+        let regions = [PresentRegionKHR::builder().rectangles(&rectangles).build()];
+        let mut present_regions = PresentRegionsKHR::builder().regions(&regions);
+
+        let mut present_info = PresentInfoKHR::builder()
+            .wait_semaphores(&wait_sems)
+            .swapchains(&swapchains)
+            .image_indices(&image_indices);
+        if damage.is_some() {
+            present_info = present_info.push_next(&mut present_regions);
+        }
+
+        self.watchdog.start("queue_present");
+        let result = unsafe {
+            head.swapchain_loader
+                .queue_present(self.unified_queue, &present_info)
+        };
+        self.watchdog.finish();
+        // Out of date swapchains still release the presented image.
+        if matches!(result, Ok(_) | Err(ash::vk::Result::ERROR_OUT_OF_DATE_KHR)) {
+            head.untrack_acquired(frame as u32);
+        }
+        Error::from_present(result)?;
+
+        Ok(())
+    }
+}
+
+pub(crate) fn rect_contains(outer: Rect2D, inner: Rect2D) -> bool {
+    let (outer_min, outer_max) = rect_bounds(outer);
+    let (inner_min, inner_max) = rect_bounds(inner);
+    inner_min.0 >= outer_min.0
+        && inner_min.1 >= outer_min.1
+        && inner_max.0 <= outer_max.0
+        && inner_max.1 <= outer_max.1
+}
+
+/// Intersection of ```rect``` with ```extent```, ```None``` if empty.
+fn clip_to_extent(rect: Rect2D, extent: Extent2D) -> Option<Rect2D> {
+    let (min, max) = rect_bounds(rect);
+    let (x0, y0) = (min.0.max(0), min.1.max(0));
+    let (x1, y1) = (
+        max.0.min(extent.width as i64),
+        max.1.min(extent.height as i64),
+    );
+    if x1 <= x0 || y1 <= y0 {
+        return None;
+    }
+    Some(Rect2D {
+        offset: Offset2D {
+            x: x0 as i32,
+            y: y0 as i32,
+        },
+        extent: Extent2D {
+            width: (x1 - x0) as u32,
+            height: (y1 - y0) as u32,
+        },
+    })
+}
+
+fn rect_bounds(rect: Rect2D) -> ((i64, i64), (i64, i64)) {
+    let min = (rect.offset.x as i64, rect.offset.y as i64);
+    let max = (
+        min.0 + rect.extent.width as i64,
+        min.1 + rect.extent.height as i64,
+    );
+    (min, max)
+}
@@ -0,0 +1,364 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::{imports::*, VkInit};
+
+const MAX_DEBUG_NAME_LEN: usize = 255;
+const RENDERDOC_LAYER: &str = "VK_LAYER_RENDERDOC_Capture";
+
+/// Nul-terminated debug name formatted on the stack.
+struct DebugName {
+    bytes: [u8; MAX_DEBUG_NAME_LEN + 1],
+    len: usize,
+    /// Formatting stopped at a nul character.
+    contains_nul: bool,
+}
+
+impl DebugName {
+    fn new(args: std::fmt::Arguments) -> Self {
+        let mut name = Self {
+            bytes: [0; MAX_DEBUG_NAME_LEN + 1],
+            len: 0,
+            contains_nul: false,
+        };
+        let _ = name.write_fmt(args);
+        name
+    }
+
+    fn as_c_str(&self) -> &CStr {
+        CStr::from_bytes_with_nul(&self.bytes[..=self.len])
+            .expect("writing debug names stops at nul bytes")
+    }
+}
+
+impl Write for DebugName {
+    /// Stops at nul characters and truncates at [MAX_DEBUG_NAME_LEN] bytes.
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for c in s.chars() {
+            if c == '\0' {
+                self.contains_nul = true;
+                return Err(std::fmt::Error);
+            }
+            let len = c.len_utf8();
+            if self.len + len > MAX_DEBUG_NAME_LEN {
+                break;
+            }
+            c.encode_utf8(&mut self.bytes[self.len..self.len + len]);
+            self.len += len;
+        }
+        Ok(())
+    }
+}
+
+/// Debug messenger output collected between [begin_debug_message_capture](VkInit::begin_debug_message_capture)
+/// and [end_debug_message_capture](VkInit::end_debug_message_capture).
+#[derive(Default)]
+pub(crate) struct DebugMessageCapture {
+    enabled: AtomicBool,
+    messages: Mutex<Vec<String>>,
+}
+
+impl DebugMessageCapture {
+    pub(crate) fn push(&self, message: &str) {
+        if !self.enabled.load(Ordering::Acquire) {
+            return;
+        }
+        if let Ok(mut messages) = self.messages.lock() {
+            messages.push(message.to_string());
+        }
+    }
+}
+
+/// Command buffer label with an owned, nul-terminated name.
+///
+/// A single trailing nul of ```name``` is accepted for labels written as ```"Name\0"```.
+/// A ```[0.0; 4]``` color leaves the label uncolored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DebugLabel {
+    name: CString,
+    color: [f32; 4],
+}
+
+impl DebugLabel {
+    pub fn new(name: &str, color: Option<[f32; 4]>) -> Result<Self, Error> {
+        let name = name.strip_suffix('\0').unwrap_or(name);
+        Ok(Self {
+            name: CString::new(name)?,
+            color: color.unwrap_or_default(),
+        })
+    }
+
+    pub fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    pub fn color(&self) -> [f32; 4] {
+        self.color
+    }
+
+    /// Label info borrowing the name, valid as long as the label.
+    pub fn label_info(&self) -> DebugUtilsLabelEXTBuilder<'_> {
+        DebugUtilsLabelEXT::builder()
+            .label_name(&self.name)
+            .color(self.color)
+    }
+}
+
+/// Label region begun by [begin_debug_label](VkInit::begin_debug_label), ended when dropped.
+#[must_use = "the label ends as soon as the scope is dropped"]
+pub struct DebugLabelScope<'a> {
+    vk_init: &'a VkInit,
+    cmd_buffer: CommandBuffer,
+    open: bool,
+}
+
+impl DebugLabelScope<'_> {
+    /// Ends the label, same as dropping the scope.
+    pub fn end(self) {}
+
+    /// Leaves the label open, e.g. to end it in a later command buffer with [end_debug_label](VkInit::end_debug_label).
+    pub fn detach(mut self) {
+        self.open = false;
+    }
+}
+
+impl Drop for DebugLabelScope<'_> {
+    fn drop(&mut self) {
+        if self.open {
+            let _ = self.vk_init.end_debug_label(&self.cmd_buffer);
+        }
+    }
+}
+
+impl VkInit {
+    /// Same as [set_debug_object_name](VkInit::set_debug_object_name), but formats the name on the stack.
+    ///
+    /// Names are truncated to 255 bytes. Returns [DebugNameContainsNul](Error::DebugNameContainsNul) if the name contains a nul character.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let fence = init.create_fence()?;
+    /// let frame = 0;
+    ///
+    /// init.set_debug_object_name_fmt(fence.as_raw(), ObjectType::FENCE, format_args!("Frame_{frame}_Fence"))?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn set_debug_object_name_fmt(
+        &self,
+        obj_handle: u64,
+        obj_type: ObjectType,
+        name: std::fmt::Arguments,
+    ) -> Result<(), Error> {
+        if obj_type == ObjectType::COMMAND_BUFFER && self.watchdog.is_enabled() {
+            self.watchdog
+                .name_command_buffer(obj_handle, name.to_string());
+        }
+        if let Some(dbg) = &self.debug_loader {
+            let name = DebugName::new(name);
+            if name.contains_nul {
+                return Err(Error::DebugNameContainsNul(
+                    name.as_c_str().to_string_lossy().into_owned(),
+                ));
+            }
+            let name_info = DebugUtilsObjectNameInfoEXT::builder()
+                .object_name(name.as_c_str())
+                .object_handle(obj_handle)
+                .object_type(obj_type)
+                .build();
+
+            unsafe { dbg.set_debug_utils_object_name(self.device.handle(), &name_info)? };
+        }
+        Ok(())
+    }
+
+    /// Inserts a single label into ```cmd_buffer```, see [DebugLabel] for the ```name``` and ```color```.
+    pub fn insert_debug_label(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        name: &str,
+        color: Option<[f32; 4]>,
+    ) -> Result<(), Error> {
+        if let Some(dbg) = &self.debug_loader {
+            let label = DebugLabel::new(name, color)?;
+            unsafe { dbg.cmd_insert_debug_utils_label(*cmd_buffer, &label.label_info()) };
+        }
+        Ok(())
+    }
+
+    /// Begins a label region in ```cmd_buffer``` that ends when the returned scope is dropped, see [DebugLabel] for the ```name``` and ```color```.
+    ///
+    /// The label is also tracked by the [GPU watchdog](crate::VkInitCreateInfo::gpu_watchdog)
+    /// and set as checkpoint for [crash_diagnostics](crate::VkInitCreateInfo::crash_diagnostics).
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    ///
+    /// init.immediate_submit(CmdType::Any, |cmd_buffer| {
+    ///     let _label = init.begin_debug_label(cmd_buffer, "Upload", Some([0.2, 0.6, 1.0, 1.0])).unwrap();
+    ///     // Commands recorded here are grouped under "Upload".
+    /// })?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn begin_debug_label(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        name: &str,
+        color: Option<[f32; 4]>,
+    ) -> Result<DebugLabelScope<'_>, Error> {
+        let label = DebugLabel::new(name, color)?;
+        if let Some(dbg) = &self.debug_loader {
+            unsafe { dbg.cmd_begin_debug_utils_label(*cmd_buffer, &label.label_info()) };
+        }
+        self.watchdog.begin_label(name);
+        self.crash_diagnostics.set_checkpoint(cmd_buffer, name);
+        Ok(DebugLabelScope {
+            vk_init: self,
+            cmd_buffer: *cmd_buffer,
+            open: true,
+        })
+    }
+
+    /// Ends the last label region of ```cmd_buffer```, only needed for [detached](DebugLabelScope::detach) scopes.
+    pub fn end_debug_label(&self, cmd_buffer: &CommandBuffer) -> Result<(), Error> {
+        if let Some(dbg) = &self.debug_loader {
+            unsafe { dbg.cmd_end_debug_utils_label(*cmd_buffer) };
+        }
+        Ok(())
+    }
+
+    /// Starts collecting debug messenger output, discarding previously captured messages.
+    ///
+    /// Messages are only emitted with enabled validation and for the configured log level.
+    pub fn begin_debug_message_capture(&self) {
+        if let Ok(mut messages) = self.debug_message_capture.messages.lock() {
+            messages.clear();
+        }
+        self.debug_message_capture
+            .enabled
+            .store(true, Ordering::Release);
+    }
+
+    /// Stops collecting debug messenger output and returns all messages since [begin_debug_message_capture](VkInit::begin_debug_message_capture).
+    pub fn end_debug_message_capture(&self) -> Vec<String> {
+        self.debug_message_capture
+            .enabled
+            .store(false, Ordering::Release);
+        self.debug_message_capture
+            .messages
+            .lock()
+            .map(|mut messages| std::mem::take(&mut *messages))
+            .unwrap_or_default()
+    }
+
+    /// True if RenderDoc's capture layer is enabled, either listed in [enabled_validation_layers](crate::VkInitCreateInfo::enabled_validation_layers)
+    /// or implicitly through ```ENABLE_VULKAN_RENDERDOC_CAPTURE=1```.
+    pub fn renderdoc_layer_active(&self) -> bool {
+        #[cfg(feature = "renderdoc")]
+        if self.renderdoc_available() {
+            return true;
+        }
+        let listed = self.create_info.enable_validation
+            && self
+                .create_info
+                .enabled_validation_layers
+                .iter()
+                .any(|layer| layer == RENDERDOC_LAYER);
+        listed || std::env::var("ENABLE_VULKAN_RENDERDOC_CAPTURE").is_ok_and(|value| value == "1")
+    }
+
+    /// Names the instance, device, queues and physical device.
+    ///
+    /// The instance stays unnamed under RenderDoc, which crashes when it has a debug name.
+    pub(crate) fn name_core_objects(&self) -> Result<(), Error> {
+        if !self.renderdoc_layer_active() {
+            self.set_debug_object_name_fmt(
+                self.instance.handle().as_raw(),
+                ObjectType::INSTANCE,
+                format_args!("VKU_Instance"),
+            )?;
+        }
+        self.set_debug_object_name_fmt(
+            self.physical_device.as_raw(),
+            ObjectType::PHYSICAL_DEVICE,
+            format_args!("VKU_Physical_Device"),
+        )?;
+        self.set_debug_object_name_fmt(
+            self.device.handle().as_raw(),
+            ObjectType::DEVICE,
+            format_args!("VKU_Device"),
+        )?;
+        self.set_debug_object_name_fmt(
+            self.unified_queue.as_raw(),
+            ObjectType::QUEUE,
+            format_args!("VKU_Unified_Queue"),
+        )?;
+        if let Some(transfer_queue) = self.transfer_queue {
+            self.set_debug_object_name_fmt(
+                transfer_queue.as_raw(),
+                ObjectType::QUEUE,
+                format_args!("VKU_Transfer_Queue"),
+            )?;
+        }
+        if let Some(compute_queue) = self.compute_queue {
+            self.set_debug_object_name_fmt(
+                compute_queue.as_raw(),
+                ObjectType::QUEUE,
+                format_args!("VKU_Compute_Queue"),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Names the surface, swapchain, swapchain images and views and the depth image.
+    ///
+    /// Has to be called again whenever the head is recreated.
+    pub(crate) fn name_head_objects(&self) -> Result<(), Error> {
+        let Some(head) = &self.head else {
+            return Ok(());
+        };
+
+        self.set_debug_object_name_fmt(
+            head.surface.as_raw(),
+            ObjectType::SURFACE_KHR,
+            format_args!("VKU_Surface"),
+        )?;
+        self.set_debug_object_name_fmt(
+            head.swapchain.as_raw(),
+            ObjectType::SWAPCHAIN_KHR,
+            format_args!("VKU_Swapchain"),
+        )?;
+        for (i, image) in head.swapchain_images.iter().enumerate() {
+            self.set_debug_object_name_fmt(
+                image.as_raw(),
+                ObjectType::IMAGE,
+                format_args!("VKU_Swapchain_Image_{i}"),
+            )?;
+        }
+        for (i, image_view) in head.swapchain_image_views.iter().enumerate() {
+            self.set_debug_object_name_fmt(
+                image_view.as_raw(),
+                ObjectType::IMAGE_VIEW,
+                format_args!("VKU_Swapchain_Image_View_{i}"),
+            )?;
+        }
+        if let Some(msaa_image) = &head.msaa_image {
+            msaa_image.set_debug_object_name(self, String::from("VKU_MSAA_Color"))?;
+        }
+        head.depth_image
+            .set_debug_object_name(self, String::from("VKU_Depth"))
+    }
+}
@@ -0,0 +1,167 @@
+use std::sync::MutexGuard;
+
+use crate::{imports::*, VMABuffer, VMAImage, VkInit};
+
+/// Resource waiting in the [DeletionQueue].
+#[allow(clippy::large_enum_variant)]
+pub enum DeferredResource {
+    Buffer(VMABuffer),
+    Image(Box<VMAImage>),
+}
+
+impl DeferredResource {
+    fn destroy(&mut self, device: &Device, allocator: &mut Allocator) -> Result<(), Error> {
+        match self {
+            DeferredResource::Buffer(buffer) => buffer.destroy(device, allocator),
+            DeferredResource::Image(image) => image.destroy(device, allocator),
+        }
+    }
+}
+
+/// Destroys resources once the fence of their last use has signaled, owned by [VkInit].
+///
+/// Drained by [collect_deletion_queue](VkInit::collect_deletion_queue) and, with
+/// [retry_failed_allocations](crate::VkInitCreateInfo::retry_failed_allocations), before retrying a failed allocation.
+#[derive(Default)]
+pub struct DeletionQueue {
+    pending: Vec<(Fence, DeferredResource)>,
+}
+
+impl DeletionQueue {
+    /// Destroys ```resource``` once ```fence``` has signaled.
+    pub fn push(&mut self, resource: DeferredResource, fence: Fence) {
+        self.pending.push((fence, resource));
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Destroys all resources whose fence has signaled and returns how many were destroyed.
+    pub fn collect(&mut self, device: &Device, allocator: &mut Allocator) -> Result<usize, Error> {
+        let mut destroyed = 0;
+        let mut index = 0;
+        while index < self.pending.len() {
+            let (fence, _) = self.pending[index];
+            if unsafe { device.get_fence_status(fence)? } {
+                let (_, mut resource) = self.pending.remove(index);
+                resource.destroy(device, allocator)?;
+                destroyed += 1;
+            } else {
+                index += 1;
+            }
+        }
+        Ok(destroyed)
+    }
+
+    /// Fence of the oldest pending resource.
+    pub fn oldest_fence(&self) -> Option<Fence> {
+        self.pending.first().map(|(fence, _)| *fence)
+    }
+
+    /// Device must be idle.
+    pub fn destroy(&mut self, device: &Device, allocator: &mut Allocator) -> Result<(), Error> {
+        for (_, mut resource) in self.pending.drain(..) {
+            resource.destroy(device, allocator)?;
+        }
+        Ok(())
+    }
+}
+
+/// Allocator state captured around a [retried allocation](crate::VkInitCreateInfo::retry_failed_allocations).
+#[derive(Clone, Debug)]
+pub struct HeapState {
+    /// Resources still waiting in the [DeletionQueue].
+    pub pending_deletions: usize,
+    /// gpu-allocator breakdown of used and reserved bytes, listing the largest allocations.
+    pub allocator_report: String,
+}
+
+impl std::fmt::Display for HeapState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} pending deletions", self.pending_deletions)?;
+        write!(f, "{}", self.allocator_report)
+    }
+}
+
+/// Allocations listed per [HeapState].
+const REPORTED_ALLOCATIONS: usize = 8;
+
+impl VkInit {
+    /// Shortcut - see [DeletionQueue](DeletionQueue::push).
+    pub fn defer_destroy_buffer(&self, buffer: VMABuffer, fence: Fence) {
+        self.lock_deletion_queue()
+            .push(DeferredResource::Buffer(buffer), fence);
+    }
+
+    /// Shortcut - see [DeletionQueue](DeletionQueue::push).
+    pub fn defer_destroy_image(&self, image: VMAImage, fence: Fence) {
+        self.lock_deletion_queue()
+            .push(DeferredResource::Image(Box::new(image)), fence);
+    }
+
+    /// Shortcut - see [DeletionQueue](DeletionQueue::collect).
+    pub fn collect_deletion_queue(&self) -> Result<usize, Error> {
+        self.lock_deletion_queue()
+            .collect(&self.device, &mut self.allocator.lock())
+    }
+
+    /// Snapshot of the allocator and the [DeletionQueue].
+    pub fn heap_state(&self) -> HeapState {
+        HeapState {
+            pending_deletions: self.lock_deletion_queue().len(),
+            allocator_report: format!("{:.*?}", REPORTED_ALLOCATIONS, *self.allocator.lock()),
+        }
+    }
+
+    /// Runs the device-local allocation ```create```, retrying it once after draining the [DeletionQueue]
+    /// if it fails with [GpuAllocError](Error::GpuAllocError) and [retry_failed_allocations](crate::VkInitCreateInfo::retry_failed_allocations) is set.
+    ///
+    /// If no pending resource has completed yet, waits on the oldest pending fence first.
+    pub(crate) fn allocate_with_retry<T>(
+        &self,
+        mut create: impl FnMut(&Device, &mut Allocator) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let first = match create(&self.device, &mut self.allocator.lock()) {
+            Err(Error::GpuAllocError(first)) if self.create_info.retry_failed_allocations => first,
+            result => return result,
+        };
+
+        let before = self.heap_state();
+        warn!("Allocation failed: {first}, draining the deletion queue before retrying\n{before}");
+        let mut destroyed = self.collect_deletion_queue()?;
+        if destroyed == 0 {
+            let oldest_fence = self.lock_deletion_queue().oldest_fence();
+            if let Some(fence) = oldest_fence {
+                self.wait_for_fences_watched(&[fence], "allocation retry")?;
+                destroyed = self.collect_deletion_queue()?;
+            }
+        }
+        let after = self.heap_state();
+
+        match create(&self.device, &mut self.allocator.lock()) {
+            Ok(resource) => {
+                info!("Allocation succeeded after destroying {destroyed} deferred resources");
+                Ok(resource)
+            }
+            Err(Error::GpuAllocError(second)) => Err(Error::GpuAllocRetryFailed {
+                first,
+                second,
+                before: Box::new(before),
+                after: Box::new(after),
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The deletion queue is always locked before the allocator.
+    fn lock_deletion_queue(&self) -> MutexGuard<'_, DeletionQueue> {
+        self.deletion_queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
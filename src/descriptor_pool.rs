@@ -0,0 +1,298 @@
+use std::sync::Mutex;
+
+use crate::{imports::*, VkInit};
+
+/// How a descriptor pool continues once its sets are used up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolGrowth {
+    /// No follow-up pool, allocations fail with ```ERROR_OUT_OF_POOL_MEMORY```.
+    Fixed,
+    /// Follow-up pools have twice the sets of the previous pool.
+    Double,
+    /// Follow-up pools have ```initial_sets``` sets.
+    Chain,
+}
+
+/// Sizing of the descriptor pools of a [ComputeShader](crate::ComputeShader), [FullscreenPass](crate::FullscreenPass) or [DescriptorAllocator].
+///
+/// Pools hold the descriptors of ```initial_sets``` sets. Once a pool is full or the driver reports ```ERROR_OUT_OF_POOL_MEMORY```
+/// or ```ERROR_FRAGMENTED_POOL```, a follow-up pool is created according to ```growth``` and destroyed together with its owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DescriptorPoolPolicy {
+    pub initial_sets: u32,
+    pub growth: PoolGrowth,
+}
+
+impl Default for DescriptorPoolPolicy {
+    fn default() -> Self {
+        Self {
+            initial_sets: 16,
+            growth: PoolGrowth::Chain,
+        }
+    }
+}
+
+impl DescriptorPoolPolicy {
+    /// Sets of the pool following a full pool of ```previous_sets```, ```None``` for [Fixed](PoolGrowth::Fixed).
+    pub fn next_pool_sets(&self, previous_sets: u32) -> Option<u32> {
+        match self.growth {
+            PoolGrowth::Fixed => None,
+            PoolGrowth::Double => Some(previous_sets.saturating_mul(2).max(1)),
+            PoolGrowth::Chain => Some(self.initial_sets.max(1)),
+        }
+    }
+}
+
+/// Descriptors of each type one set of a default [DescriptorAllocator] may use.
+pub const DEFAULT_DESCRIPTORS_PER_SET: [(DescriptorType, u32); 6] = [
+    (DescriptorType::UNIFORM_BUFFER, 4),
+    (DescriptorType::STORAGE_BUFFER, 4),
+    (DescriptorType::COMBINED_IMAGE_SAMPLER, 4),
+    (DescriptorType::SAMPLED_IMAGE, 2),
+    (DescriptorType::STORAGE_IMAGE, 2),
+    (DescriptorType::SAMPLER, 1),
+];
+
+/// Descriptor sets of any layout from pools growing on demand according to a [DescriptorPoolPolicy].
+///
+/// Persistent sets from [allocate](DescriptorAllocator::allocate) live until the allocator is destroyed.
+/// Transient sets from [allocate_transient](DescriptorAllocator::allocate_transient) are freed together by [reset_frame](DescriptorAllocator::reset_frame),
+/// which keeps the pools for reuse. Use one allocator per frame in flight, e.g. with [create_per_frame](VkInit::create_per_frame),
+/// and reset it once the frame's fence signaled.
+/// ```
+/// # extern crate winit;
+/// # use vku::*;
+/// # use ash::vk::*;
+/// # if VkInit::load_entry().is_err() { return Ok(()); }
+/// # let init = VkInit::new_headless(VkInitCreateInfo::default())?;
+/// # let bindings = [DescriptorSetLayoutBinding { descriptor_type: DescriptorType::STORAGE_BUFFER, descriptor_count: 1, stage_flags: ShaderStageFlags::COMPUTE, ..Default::default() }];
+/// # let layout_info = DescriptorSetLayoutCreateInfo::builder().bindings(&bindings);
+/// # let layout = unsafe { init.device.create_descriptor_set_layout(&layout_info, None)? };
+/// let mut allocator = DescriptorAllocator::default();
+///
+/// let material_set = allocator.allocate(&init, layout)?;
+/// let frame_set = allocator.allocate_transient(&init, layout)?;
+/// // submit the frame and wait for its fence
+/// allocator.reset_frame(&init.device)?;
+/// # allocator.destroy(&init.device);
+/// # Ok::<(), vku::Error>(())
+/// ```
+pub struct DescriptorAllocator {
+    persistent: Mutex<DescriptorPools>,
+    transient: DescriptorPools,
+}
+
+impl Default for DescriptorAllocator {
+    /// [DEFAULT_DESCRIPTORS_PER_SET] with the default [DescriptorPoolPolicy].
+    fn default() -> Self {
+        Self::new(
+            DescriptorPoolPolicy::default(),
+            &DEFAULT_DESCRIPTORS_PER_SET,
+            "VKU_Descriptor_Allocator",
+        )
+    }
+}
+
+impl DescriptorAllocator {
+    /// Pools are sized for the sets of ```policy```, each with up to ```descriptors_per_set``` descriptors of each type.
+    ///
+    /// No pools are created until the first allocation.
+    pub fn new(
+        policy: DescriptorPoolPolicy,
+        descriptors_per_set: &[(DescriptorType, u32)],
+        base_name: &str,
+    ) -> Self {
+        Self {
+            persistent: Mutex::new(DescriptorPools::new(policy, descriptors_per_set, base_name)),
+            transient: DescriptorPools::new(
+                policy,
+                descriptors_per_set,
+                &format!("{base_name}_Transient"),
+            ),
+        }
+    }
+
+    /// Allocates a set of ```layout``` that lives until the allocator is [destroyed](DescriptorAllocator::destroy).
+    ///
+    /// Creates a follow-up pool according to the [DescriptorPoolPolicy] if needed.
+    pub fn allocate(
+        &self,
+        vk_init: &VkInit,
+        layout: DescriptorSetLayout,
+    ) -> Result<DescriptorSet, Error> {
+        self.persistent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .allocate(vk_init, layout)
+    }
+
+    /// Allocates a set of ```layout``` that is freed by the next [reset_frame](DescriptorAllocator::reset_frame).
+    pub fn allocate_transient(
+        &mut self,
+        vk_init: &VkInit,
+        layout: DescriptorSetLayout,
+    ) -> Result<DescriptorSet, Error> {
+        self.transient.allocate(vk_init, layout)
+    }
+
+    /// Frees all transient sets, they must not be used by pending command buffers anymore.
+    pub fn reset_frame(&mut self, device: &Device) -> Result<(), Error> {
+        self.transient.reset(device)
+    }
+
+    /// Number of persistent and transient descriptor pools created so far.
+    pub fn pool_count(&self) -> usize {
+        let persistent = self
+            .persistent
+            .lock()
+            .map_or(0, |persistent| persistent.pool_count());
+        persistent + self.transient.pool_count()
+    }
+
+    /// Destroys all pools, freeing every set allocated from them.
+    pub fn destroy(&mut self, device: &Device) {
+        self.persistent
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .destroy(device);
+        self.transient.destroy(device);
+    }
+}
+
+/// Chain of descriptor pools growing according to a [DescriptorPoolPolicy].
+pub(crate) struct DescriptorPools {
+    policy: DescriptorPoolPolicy,
+    /// Descriptors of each type needed by one set.
+    descriptors_per_set: Vec<(DescriptorType, u32)>,
+    /// Pools with their number of sets.
+    pools: Vec<(DescriptorPool, u32)>,
+    /// Pool allocated from, earlier pools are used up until the next [reset](DescriptorPools::reset).
+    current: usize,
+    sets_in_current: u32,
+    base_name: String,
+}
+
+impl DescriptorPools {
+    pub(crate) fn new(
+        policy: DescriptorPoolPolicy,
+        descriptors_per_set: &[(DescriptorType, u32)],
+        base_name: &str,
+    ) -> Self {
+        Self {
+            policy,
+            descriptors_per_set: descriptors_per_set.to_vec(),
+            pools: vec![],
+            current: 0,
+            sets_in_current: 0,
+            base_name: base_name.to_string(),
+        }
+    }
+
+    pub(crate) fn pool_count(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Allocates a set of ```layout```, moving on to the next pool if the current one is used up.
+    ///
+    /// A layout that does not fit into an empty pool, e.g. with a descriptor type missing from ```descriptors_per_set```,
+    /// fails with ```ERROR_OUT_OF_POOL_MEMORY``` without growing the chain any further.
+    pub(crate) fn allocate(
+        &mut self,
+        vk_init: &VkInit,
+        layout: DescriptorSetLayout,
+    ) -> Result<DescriptorSet, Error> {
+        if self.pools.is_empty() {
+            self.push_pool(vk_init, self.policy.initial_sets.max(1))?;
+        } else if self.sets_in_current == self.pools[self.current].1 {
+            self.advance(vk_init)?;
+        }
+
+        match self.allocate_from_current(vk_init, layout) {
+            Err(Error::VkError(
+                ash::vk::Result::ERROR_OUT_OF_POOL_MEMORY | ash::vk::Result::ERROR_FRAGMENTED_POOL,
+            )) if self.sets_in_current > 0 => {
+                self.advance(vk_init)?;
+                self.allocate_from_current(vk_init, layout)
+            }
+            result => result,
+        }
+    }
+
+    /// Frees all sets and starts allocating from the first pool again.
+    pub(crate) fn reset(&mut self, device: &Device) -> Result<(), Error> {
+        for (pool, _) in &self.pools {
+            unsafe { device.reset_descriptor_pool(*pool, DescriptorPoolResetFlags::empty())? };
+        }
+        self.current = 0;
+        self.sets_in_current = 0;
+        Ok(())
+    }
+
+    pub(crate) fn destroy(&mut self, device: &Device) {
+        for (pool, _) in self.pools.drain(..) {
+            unsafe { device.destroy_descriptor_pool(pool, None) };
+        }
+        self.current = 0;
+        self.sets_in_current = 0;
+    }
+
+    /// Moves on to the next pool, reusing pools kept by [reset](DescriptorPools::reset) before growing.
+    fn advance(&mut self, vk_init: &VkInit) -> Result<(), Error> {
+        if self.current + 1 < self.pools.len() {
+            self.current += 1;
+            self.sets_in_current = 0;
+            return Ok(());
+        }
+        let sets_of_last_pool = self.pools.last().map_or(0, |(_, sets)| *sets);
+        let Some(sets) = self.policy.next_pool_sets(sets_of_last_pool) else {
+            return Err(Error::VkError(ash::vk::Result::ERROR_OUT_OF_POOL_MEMORY));
+        };
+        trace!(
+            "{} descriptor pool {} is used up, chaining a pool of {sets} sets",
+            self.base_name,
+            self.pools.len() - 1
+        );
+        self.push_pool(vk_init, sets)
+    }
+
+    fn push_pool(&mut self, vk_init: &VkInit, sets: u32) -> Result<(), Error> {
+        let pool_sizes: Vec<DescriptorPoolSize> = self
+            .descriptors_per_set
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(ty, count)| {
+                DescriptorPoolSize::builder()
+                    .ty(*ty)
+                    .descriptor_count(count * sets)
+                    .build()
+            })
+            .collect();
+        let create_info = DescriptorPoolCreateInfo::builder()
+            .max_sets(sets)
+            .pool_sizes(&pool_sizes);
+        let pool = unsafe { vk_init.device.create_descriptor_pool(&create_info, None)? };
+        vk_init.set_debug_object_name_fmt(
+            pool.as_raw(),
+            ObjectType::DESCRIPTOR_POOL,
+            format_args!("{}_Descriptor_Pool_{}", self.base_name, self.pools.len()),
+        )?;
+        self.pools.push((pool, sets));
+        self.current = self.pools.len() - 1;
+        self.sets_in_current = 0;
+        Ok(())
+    }
+
+    fn allocate_from_current(
+        &mut self,
+        vk_init: &VkInit,
+        layout: DescriptorSetLayout,
+    ) -> Result<DescriptorSet, Error> {
+        let set_layouts = [layout];
+        let alloc_info = DescriptorSetAllocateInfo::builder()
+            .descriptor_pool(self.pools[self.current].0)
+            .set_layouts(&set_layouts);
+        let set = unsafe { vk_init.device.allocate_descriptor_sets(&alloc_info)?[0] };
+        self.sets_in_current += 1;
+        Ok(set)
+    }
+}
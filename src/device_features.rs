@@ -0,0 +1,296 @@
+use std::ops::{Deref, DerefMut};
+use std::ptr::null_mut;
+
+use crate::{imports::*, VkInit, VkInitCreateInfo};
+
+/// Boolean features of the physical device, named by their field in the ash structs, e.g. ```"descriptor_indexing"```.
+///
+/// Stored as [supported_features](crate::PhysicalDeviceInfo::supported_features), see [supports_feature](VkInit::supports_feature).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeviceFeatures {
+    pub core: PhysicalDeviceFeatures,
+    pub vk_1_1: FeatureStruct<PhysicalDeviceVulkan11Features>,
+    pub vk_1_2: FeatureStruct<PhysicalDeviceVulkan12Features>,
+    pub vk_1_3: FeatureStruct<PhysicalDeviceVulkan13Features>,
+}
+
+/// Vulkan 1.1, 1.2 or 1.3 feature struct, derefs to the ash struct to read and set its features.
+///
+/// The ```p_next``` chain is built by vku and has to be left null, [get](FeatureStruct::get) returns a copy with a null ```p_next```.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FeatureStruct<T>(T);
+
+impl<T> Deref for FeatureStruct<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for FeatureStruct<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+macro_rules! feature_struct {
+    ($($features:ty),*) => {$(
+        impl From<$features> for FeatureStruct<$features> {
+            fn from(mut features: $features) -> Self {
+                features.p_next = null_mut();
+                Self(features)
+            }
+        }
+
+        impl FeatureStruct<$features> {
+            /// Copy of the features with a null ```p_next```, ready to be chained.
+            pub fn get(&self) -> $features {
+                let mut features = self.0;
+                features.p_next = null_mut();
+                features
+            }
+        }
+
+        // The only raw pointer is ```p_next```, which vku never follows, see ```get```.
+        unsafe impl Send for FeatureStruct<$features> {}
+        unsafe impl Sync for FeatureStruct<$features> {}
+    )*};
+}
+
+feature_struct!(
+    PhysicalDeviceVulkan11Features,
+    PhysicalDeviceVulkan12Features,
+    PhysicalDeviceVulkan13Features
+);
+
+type FeatureTable<T> = &'static [(&'static str, fn(&T) -> Bool32)];
+
+macro_rules! feature_table {
+    ($features:ty { $($field:ident),* $(,)? }) => {
+        &[$((stringify!($field), |features: &$features| features.$field)),*]
+    };
+}
+
+const CORE_FEATURES: FeatureTable<PhysicalDeviceFeatures> =
+    feature_table!(PhysicalDeviceFeatures {
+        robust_buffer_access,
+        full_draw_index_uint32,
+        image_cube_array,
+        independent_blend,
+        geometry_shader,
+        tessellation_shader,
+        sample_rate_shading,
+        dual_src_blend,
+        logic_op,
+        multi_draw_indirect,
+        draw_indirect_first_instance,
+        depth_clamp,
+        depth_bias_clamp,
+        fill_mode_non_solid,
+        depth_bounds,
+        wide_lines,
+        large_points,
+        alpha_to_one,
+        multi_viewport,
+        sampler_anisotropy,
+        texture_compression_etc2,
+        texture_compression_astc_ldr,
+        texture_compression_bc,
+        occlusion_query_precise,
+        pipeline_statistics_query,
+        vertex_pipeline_stores_and_atomics,
+        fragment_stores_and_atomics,
+        shader_tessellation_and_geometry_point_size,
+        shader_image_gather_extended,
+        shader_storage_image_extended_formats,
+        shader_storage_image_multisample,
+        shader_storage_image_read_without_format,
+        shader_storage_image_write_without_format,
+        shader_uniform_buffer_array_dynamic_indexing,
+        shader_sampled_image_array_dynamic_indexing,
+        shader_storage_buffer_array_dynamic_indexing,
+        shader_storage_image_array_dynamic_indexing,
+        shader_clip_distance,
+        shader_cull_distance,
+        shader_float64,
+        shader_int64,
+        shader_int16,
+        shader_resource_residency,
+        shader_resource_min_lod,
+        sparse_binding,
+        sparse_residency_buffer,
+        sparse_residency_image2_d,
+        sparse_residency_image3_d,
+        sparse_residency2_samples,
+        sparse_residency4_samples,
+        sparse_residency8_samples,
+        sparse_residency16_samples,
+        sparse_residency_aliased,
+        variable_multisample_rate,
+        inherited_queries
+    });
+
+const VK_1_1_FEATURES: FeatureTable<PhysicalDeviceVulkan11Features> =
+    feature_table!(PhysicalDeviceVulkan11Features {
+        storage_buffer16_bit_access,
+        uniform_and_storage_buffer16_bit_access,
+        storage_push_constant16,
+        storage_input_output16,
+        multiview,
+        multiview_geometry_shader,
+        multiview_tessellation_shader,
+        variable_pointers_storage_buffer,
+        variable_pointers,
+        protected_memory,
+        sampler_ycbcr_conversion,
+        shader_draw_parameters
+    });
+
+const VK_1_2_FEATURES: FeatureTable<PhysicalDeviceVulkan12Features> =
+    feature_table!(PhysicalDeviceVulkan12Features {
+        sampler_mirror_clamp_to_edge,
+        draw_indirect_count,
+        storage_buffer8_bit_access,
+        uniform_and_storage_buffer8_bit_access,
+        storage_push_constant8,
+        shader_buffer_int64_atomics,
+        shader_shared_int64_atomics,
+        shader_float16,
+        shader_int8,
+        descriptor_indexing,
+        shader_input_attachment_array_dynamic_indexing,
+        shader_uniform_texel_buffer_array_dynamic_indexing,
+        shader_storage_texel_buffer_array_dynamic_indexing,
+        shader_uniform_buffer_array_non_uniform_indexing,
+        shader_sampled_image_array_non_uniform_indexing,
+        shader_storage_buffer_array_non_uniform_indexing,
+        shader_storage_image_array_non_uniform_indexing,
+        shader_input_attachment_array_non_uniform_indexing,
+        shader_uniform_texel_buffer_array_non_uniform_indexing,
+        shader_storage_texel_buffer_array_non_uniform_indexing,
+        descriptor_binding_uniform_buffer_update_after_bind,
+        descriptor_binding_sampled_image_update_after_bind,
+        descriptor_binding_storage_image_update_after_bind,
+        descriptor_binding_storage_buffer_update_after_bind,
+        descriptor_binding_uniform_texel_buffer_update_after_bind,
+        descriptor_binding_storage_texel_buffer_update_after_bind,
+        descriptor_binding_update_unused_while_pending,
+        descriptor_binding_partially_bound,
+        descriptor_binding_variable_descriptor_count,
+        runtime_descriptor_array,
+        sampler_filter_minmax,
+        scalar_block_layout,
+        imageless_framebuffer,
+        uniform_buffer_standard_layout,
+        shader_subgroup_extended_types,
+        separate_depth_stencil_layouts,
+        host_query_reset,
+        timeline_semaphore,
+        buffer_device_address,
+        buffer_device_address_capture_replay,
+        buffer_device_address_multi_device,
+        vulkan_memory_model,
+        vulkan_memory_model_device_scope,
+        vulkan_memory_model_availability_visibility_chains,
+        shader_output_viewport_index,
+        shader_output_layer,
+        subgroup_broadcast_dynamic_id
+    });
+
+const VK_1_3_FEATURES: FeatureTable<PhysicalDeviceVulkan13Features> =
+    feature_table!(PhysicalDeviceVulkan13Features {
+        robust_image_access,
+        inline_uniform_block,
+        descriptor_binding_inline_uniform_block_update_after_bind,
+        pipeline_creation_cache_control,
+        private_data,
+        shader_demote_to_helper_invocation,
+        shader_terminate_invocation,
+        subgroup_size_control,
+        compute_full_subgroups,
+        synchronization2,
+        texture_compression_astc_hdr,
+        shader_zero_initialize_workgroup_memory,
+        dynamic_rendering,
+        shader_integer_dot_product,
+        maintenance4
+    });
+
+impl DeviceFeatures {
+    /// Features requested by the [VkInitCreateInfo](crate::VkInitCreateInfo), core features are enabled as supported.
+    pub(crate) fn requested(create_info: &VkInitCreateInfo) -> Self {
+        Self {
+            core: PhysicalDeviceFeatures::default(),
+            vk_1_1: create_info.physical_device_1_1_features,
+            vk_1_2: create_info.physical_device_1_2_features,
+            vk_1_3: create_info.physical_device_1_3_features,
+        }
+    }
+
+    /// Whether the feature called ```name``` is set, ```None``` for unknown names.
+    pub fn supports(&self, name: &str) -> Option<bool> {
+        lookup(CORE_FEATURES, &self.core, name)
+            .or_else(|| lookup(VK_1_1_FEATURES, &self.vk_1_1, name))
+            .or_else(|| lookup(VK_1_2_FEATURES, &self.vk_1_2, name))
+            .or_else(|| lookup(VK_1_3_FEATURES, &self.vk_1_3, name))
+    }
+
+    /// Name of the first feature set in ```requested``` but not in ```self```.
+    pub fn first_missing(&self, requested: &DeviceFeatures) -> Option<&'static str> {
+        missing(CORE_FEATURES, &requested.core, &self.core)
+            .or_else(|| missing(VK_1_1_FEATURES, &requested.vk_1_1, &self.vk_1_1))
+            .or_else(|| missing(VK_1_2_FEATURES, &requested.vk_1_2, &self.vk_1_2))
+            .or_else(|| missing(VK_1_3_FEATURES, &requested.vk_1_3, &self.vk_1_3))
+    }
+}
+
+fn lookup<T>(table: FeatureTable<T>, features: &T, name: &str) -> Option<bool> {
+    table
+        .iter()
+        .find(|(field, _)| *field == name)
+        .map(|(_, get)| get(features) == TRUE)
+}
+
+fn missing<T>(table: FeatureTable<T>, requested: &T, supported: &T) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|(_, get)| get(requested) == TRUE && get(supported) != TRUE)
+        .map(|(field, _)| *field)
+}
+
+impl VkInit {
+    pub(crate) unsafe fn query_device_features(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        core: PhysicalDeviceFeatures,
+    ) -> DeviceFeatures {
+        let mut vk_1_1 = PhysicalDeviceVulkan11Features::default();
+        let mut vk_1_2 = PhysicalDeviceVulkan12Features::default();
+        let mut vk_1_3 = PhysicalDeviceVulkan13Features::default();
+        let mut features = PhysicalDeviceFeatures2::builder()
+            .push_next(&mut vk_1_1)
+            .push_next(&mut vk_1_2)
+            .push_next(&mut vk_1_3);
+        instance.get_physical_device_features2(physical_device, &mut features);
+
+        // The chain points into this stack frame, the conversions null it.
+        DeviceFeatures {
+            core,
+            vk_1_1: vk_1_1.into(),
+            vk_1_2: vk_1_2.into(),
+            vk_1_3: vk_1_3.into(),
+        }
+    }
+
+    /// Whether the physical device supports the feature called ```name```, e.g. to branch on ```"shader_float64"```.
+    ///
+    /// Names are the fields of the ash feature structs, unknown names are not supported.
+    /// Requested 1.1/1.2/1.3 features that are missing fail device creation with [RequestedFeatureNotSupported](Error::RequestedFeatureNotSupported).
+    pub fn supports_feature(&self, name: &str) -> bool {
+        self.physical_device_info
+            .supported_features
+            .supports(name)
+            .unwrap_or(false)
+    }
+}
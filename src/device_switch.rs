@@ -0,0 +1,270 @@
+use std::mem::ManuallyDrop;
+
+use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
+
+use crate::{
+    allocation_lifetime, crash_diagnostics::CrashDiagnostics, imports::*, provenance, GpuSelection,
+    ResourceDescriptor, SharedAllocator, StagingPool, SurfaceInfo, VkInit,
+};
+
+/// Returned by [switch_device](VkInit::switch_device), lists what vku recreated on the new device.
+#[derive(Clone, Debug)]
+pub struct DeviceSwitchReport {
+    pub previous_device: String,
+    pub device: String,
+    /// [device_generation](VkInit::device_generation) after the switch.
+    pub device_generation: u64,
+    /// Objects owned by [VkInit] that were recreated, e.g. ```"swapchain"```.
+    pub recreated: Vec<&'static str>,
+    /// User resources that were still alive, using them asserts in debug builds.
+    pub invalidated: Vec<ResourceDescriptor>,
+}
+
+impl VkInit {
+    /// Incremented by every [switch_device](VkInit::switch_device), resources of older generations are invalid.
+    pub fn device_generation(&self) -> u64 {
+        self.device_generation
+    }
+
+    /// Moves to the physical device picked by ```selection```, keeping the instance and debug messenger.
+    ///
+    /// Recreates the device, allocator, queues, staging pool and, if ```window_size``` is provided, the swapchain on the existing surface.
+    /// Headless instances get a new head if handles are provided as well.
+    /// All resources created by the user on the old device are invalidated: destroy them before switching and recreate them afterwards.
+    /// Resources still alive are logged, returned in the report and assert on use in debug builds.
+    ///
+    /// Returns [RequestedGPUNotFound](Error::RequestedGPUNotFound) without changing anything if ```selection``` matches no device.
+    /// Errors while creating the new device or querying the surface leave the current device untouched.
+    /// Errors while tearing down the current device leave the instance without a device, it can only be dropped afterwards.
+    /// Errors while recreating the swapchain leave the instance on the new device without a head.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    ///
+    /// let report = init.switch_device(GpuSelection::Index(0), Some(&window), Some(size))?;
+    /// assert_eq!(init.device_generation(), 1);
+    /// assert!(report.recreated.contains(&"swapchain"));
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn switch_device<T: HasRawDisplayHandle + HasRawWindowHandle>(
+        &mut self,
+        selection: GpuSelection,
+        raw_window_handles: Option<&T>,
+        window_size: Option<[u32; 2]>,
+    ) -> Result<DeviceSwitchReport, Error> {
+        let previous_selection = std::mem::replace(&mut self.create_info.gpu_preference, selection);
+        let (physical_device, physical_device_info) =
+            match unsafe { Self::create_physical_device(&self.instance, &self.create_info) } {
+                Ok(selected) => selected,
+                Err(e) => {
+                    self.create_info.gpu_preference = previous_selection;
+                    return Err(e);
+                }
+            };
+        info!(
+            "Switching device from {} to {}",
+            self.physical_device_info.name, physical_device_info.name
+        );
+
+        if self.head.is_some() {
+            self.drain_presentation()?;
+        }
+        self.wait_device_idle()?;
+
+        // Everything the new device needs is created first, errors leave the current device untouched.
+        let created = unsafe {
+            Self::create_device(
+                &self.instance,
+                &physical_device,
+                &physical_device_info,
+                &self.create_info,
+            )
+        };
+        let (device, enabled_device_extensions) = match created {
+            Ok(created) => created,
+            Err(e) => {
+                self.create_info.gpu_preference = previous_selection;
+                return Err(e);
+            }
+        };
+        let prepared = (|| unsafe {
+            let allocator = Self::create_allocator(&self.instance, &physical_device, &device)?;
+            let (unified_queue, transfer_queue, compute_queue) =
+                Self::create_queues(&device, &physical_device_info)?;
+            // The surface belongs to the instance and is kept, only its swapchain is recreated.
+            let surface_info = match (&self.head, window_size) {
+                (Some(head), Some(window_size)) => {
+                    Self::check_msaa_samples(
+                        &physical_device_info.limits,
+                        self.create_info.msaa_samples,
+                    )?;
+                    Some(SurfaceInfo {
+                        scale_factor: head.surface_info.scale_factor,
+                        ..Self::query_surface_info(
+                            &head.surface_loader,
+                            head.surface,
+                            &physical_device,
+                            window_size,
+                            &self.create_info,
+                        )?
+                    })
+                }
+                _ => None,
+            };
+            Ok((
+                allocator,
+                (unified_queue, transfer_queue, compute_queue),
+                surface_info,
+            ))
+        })();
+        let (allocator, queues, surface_info) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                unsafe { device.destroy_device(None) };
+                self.create_info.gpu_preference = previous_selection;
+                return Err(e);
+            }
+        };
+
+        let mut recreated = vec!["device", "allocator", "queues", "staging pool"];
+        let offscreen = self.offscreen_head.as_ref().map(|offscreen| {
+            (
+                offscreen.extent,
+                offscreen.color_format,
+                offscreen.depth_image.format,
+                offscreen.images.len(),
+            )
+        });
+        let teardown = (|| unsafe {
+            self.destroy_offscreen_head()?;
+            let mut kept_surface = None;
+            if let Some(mut head) = self.head.take() {
+                head.destroy_attachments(&self.device, &mut self.allocator.lock())?;
+                for image_view in &head.swapchain_image_views {
+                    self.device.destroy_image_view(*image_view, None);
+                }
+                head.swapchain_loader
+                    .destroy_swapchain(head.swapchain, None);
+                match surface_info.is_some() {
+                    true => kept_surface = Some((head.surface_loader, head.surface)),
+                    false => head.surface_loader.destroy_surface(head.surface, None),
+                }
+            }
+            self.staging_pool
+                .destroy(&self.device, &mut self.allocator.lock())?;
+            self.deletion_queue
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner())
+                .destroy(&self.device, self.allocator.get_mut())?;
+            Ok(kept_surface)
+        })();
+        let kept_surface = match teardown {
+            Ok(kept_surface) => kept_surface,
+            Err(e) => {
+                drop(allocator);
+                unsafe { device.destroy_device(None) };
+                return Err(e);
+            }
+        };
+        self.destroy_mip_pipeline();
+        self.destroy_loaded_pipeline_cache();
+        self.destroy_frame_loop();
+        self.destroy_cached_samplers();
+
+        let invalidated = self.live_resources();
+        if !invalidated.is_empty() {
+            error!(
+                "Switching device with {} resources still alive, they are invalid from now on:",
+                invalidated.len()
+            );
+            for resource in &invalidated {
+                error!("{resource}");
+            }
+        }
+        provenance::retire_instance(&self.device);
+        allocation_lifetime::forget_device(&self.device);
+
+        unsafe {
+            ManuallyDrop::drop(&mut self.allocator);
+            self.device.destroy_device(None);
+            self.vertex_input_fn =
+                Self::load_vertex_input_fn(&self.instance, &device, &physical_device_info);
+            self.crash_diagnostics =
+                CrashDiagnostics::load(&self.instance, &device, &physical_device_info);
+        }
+        self.device_generation += 1;
+        provenance::register_instance(
+            &device,
+            self.create_info.capture_resource_backtraces,
+            self.device_generation,
+        );
+        self.allocator = ManuallyDrop::new(SharedAllocator::new(allocator));
+        self.device = device;
+        self.physical_device = physical_device;
+        (self.unified_queue, self.transfer_queue, self.compute_queue) = queues;
+        self.enabled_device_extensions = enabled_device_extensions;
+        let previous_device =
+            std::mem::replace(&mut self.physical_device_info, physical_device_info).name;
+        self.staging_pool = StagingPool::new(self.create_info.max_staging_pool_bytes);
+
+        // The new device is in place, a failing swapchain leaves the instance without a head.
+        let head = unsafe {
+            match (kept_surface, surface_info, raw_window_handles, window_size) {
+                (Some((surface_loader, surface)), Some(surface_info), _, _) => {
+                    let loader = surface_loader.clone();
+                    let head = Self::create_head_on_surface(
+                        &self.device,
+                        &mut self.allocator.lock(),
+                        &self.instance,
+                        surface_loader,
+                        surface,
+                        surface_info,
+                        &self.create_info,
+                    )
+                    .inspect_err(|_| loader.destroy_surface(surface, None))?;
+                    recreated.extend(["swapchain", "depth image"]);
+                    Some(head)
+                }
+                (_, _, Some(handles), Some(window_size)) => {
+                    let head = Self::create_head(
+                        &self.device,
+                        &mut self.allocator.lock(),
+                        &self.entry,
+                        &self.instance,
+                        handles.raw_display_handle(),
+                        handles.raw_window_handle(),
+                        window_size,
+                        &self.physical_device,
+                        &self.create_info,
+                    )?;
+                    recreated.extend(["surface", "swapchain", "depth image"]);
+                    Some(head)
+                }
+                _ => None,
+            }
+        };
+        self.head = head;
+
+        self.update_frame_scheme();
+        if let Some((extent, color_format, depth_format, image_count)) = offscreen {
+            self.create_offscreen_head(extent, color_format, depth_format, image_count)?;
+            recreated.push("offscreen head");
+        }
+        self.name_core_objects()?;
+        self.name_head_objects()?;
+
+        Ok(DeviceSwitchReport {
+            previous_device,
+            device: self.physical_device_info.name.clone(),
+            device_generation: self.device_generation,
+            recreated,
+            invalidated,
+        })
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{imports::*, VKUPipeline, VMABuffer, VkInit};
+
+impl VkInit {
+    /// Begins dynamic rendering with a custom ```rendering_info```, e.g. into offscreen images.
+    ///
+    /// See [begin_rendering](VkInit::begin_rendering) to render into the swapchain, end both with [end_rendering](VkInit::end_rendering).
+    pub fn cmd_begin_rendering(&self, cmd_buffer: &CommandBuffer, rendering_info: &RenderingInfo) {
+        unsafe {
+            self.device.cmd_begin_rendering(*cmd_buffer, rendering_info);
+        }
+    }
+
+    pub fn cmd_bind_graphics_pipeline(&self, cmd_buffer: &CommandBuffer, pipeline: &VKUPipeline) {
+        unsafe {
+            self.device.cmd_bind_pipeline(
+                *cmd_buffer,
+                PipelineBindPoint::GRAPHICS,
+                pipeline.pipeline,
+            );
+        }
+    }
+
+    pub fn cmd_bind_descriptor_sets(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        bind_point: PipelineBindPoint,
+        layout: PipelineLayout,
+        first_set: u32,
+        desc_sets: &[DescriptorSet],
+    ) {
+        unsafe {
+            self.device.cmd_bind_descriptor_sets(
+                *cmd_buffer,
+                bind_point,
+                layout,
+                first_set,
+                desc_sets,
+                &[],
+            );
+        }
+    }
+
+    /// Pushes ```push``` at offset 0, ```P``` should be ```#[repr(C)]``` and match the shader's push constant block.
+    ///
+    /// Returns [PushConstantsTooLarge](Error::PushConstantsTooLarge) without recording if ```P``` exceeds ```maxPushConstantsSize```.
+    pub fn cmd_push_constants_typed<P: Copy>(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        layout: PipelineLayout,
+        stages: ShaderStageFlags,
+        push: &P,
+    ) -> Result<(), Error> {
+        let max = self.physical_device_info.limits.max_push_constants_size;
+        if size_of::<P>() > max as usize {
+            return Err(Error::PushConstantsTooLarge {
+                size: size_of::<P>(),
+                max,
+            });
+        }
+        let bytes =
+            unsafe { std::slice::from_raw_parts(push as *const P as *const u8, size_of::<P>()) };
+        unsafe {
+            self.device
+                .cmd_push_constants(*cmd_buffer, layout, stages, 0, bytes);
+        }
+        Ok(())
+    }
+
+    pub fn cmd_set_viewport(&self, cmd_buffer: &CommandBuffer, viewports: &[Viewport]) {
+        unsafe {
+            self.device.cmd_set_viewport(*cmd_buffer, 0, viewports);
+        }
+    }
+
+    /// See [cmd_set_scissor_in_render_area](VkInit::cmd_set_scissor_in_render_area) to validate the scissor against the render area.
+    pub fn cmd_set_scissor(&self, cmd_buffer: &CommandBuffer, scissors: &[Rect2D]) {
+        unsafe {
+            self.device.cmd_set_scissor(*cmd_buffer, 0, scissors);
+        }
+    }
+
+    pub fn cmd_draw(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) {
+        unsafe {
+            self.device.cmd_draw(
+                *cmd_buffer,
+                vertex_count,
+                instance_count,
+                first_vertex,
+                first_instance,
+            );
+        }
+    }
+
+    pub fn cmd_draw_indexed(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) {
+        unsafe {
+            self.device.cmd_draw_indexed(
+                *cmd_buffer,
+                index_count,
+                instance_count,
+                first_index,
+                vertex_offset,
+                first_instance,
+            );
+        }
+    }
+    /// Draws ```draw_count``` tightly packed ```DrawIndexedIndirectCommand```s starting at byte ```offset``` of ```buffer```.
+    ///
+    /// ```buffer``` needs ```INDIRECT_BUFFER``` usage, e.g. from [create_indirect_buffer](VMABuffer::create_indirect_buffer).
+    pub fn cmd_draw_indexed_indirect(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        buffer: &VMABuffer,
+        offset: u64,
+        draw_count: u32,
+    ) {
+        unsafe {
+            self.device.cmd_draw_indexed_indirect(
+                *cmd_buffer,
+                buffer.buffer,
+                offset,
+                draw_count,
+                size_of::<DrawIndexedIndirectCommand>() as u32,
+            );
+        }
+    }
+
+    /// Same as [cmd_draw_indexed_indirect](VkInit::cmd_draw_indexed_indirect) with the draw count read as ```u32``` at byte ```count_offset``` of ```count_buffer```,
+    /// clamped to ```max_draw_count```, e.g. written by a culling compute shader.
+    ///
+    /// Requires the ```drawIndirectCount``` feature of [physical_device_1_2_features](crate::VkInitCreateInfo::physical_device_1_2_features),
+    /// which is enabled by default. Returns [FeatureNotEnabled](Error::FeatureNotEnabled) otherwise.
+    pub fn cmd_draw_indexed_indirect_count(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        buffer: &VMABuffer,
+        offset: u64,
+        count_buffer: &VMABuffer,
+        count_offset: u64,
+        max_draw_count: u32,
+    ) -> Result<(), Error> {
+        if self
+            .create_info
+            .physical_device_1_2_features
+            .draw_indirect_count
+            != TRUE
+        {
+            return Err(Error::FeatureNotEnabled("draw_indirect_count"));
+        }
+        unsafe {
+            self.device.cmd_draw_indexed_indirect_count(
+                *cmd_buffer,
+                buffer.buffer,
+                offset,
+                count_buffer.buffer,
+                count_offset,
+                max_draw_count,
+                size_of::<DrawIndexedIndirectCommand>() as u32,
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{imports::*, VMABuffer, VkInit};
+
+/// Offsets of a [DynamicUploadBuffer], one region per frame in flight with allocations bumped through the current region.
+///
+/// Regions and offsets are multiples of ```alignment```, which has to be a power of two.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UploadRing {
+    alignment: u64,
+    region_size: u64,
+    regions: usize,
+    region: usize,
+    /// Bytes used in the current region.
+    used: u64,
+}
+
+impl UploadRing {
+    /// Splits ```total_size``` bytes into ```regions``` equally sized regions, rounded down to ```alignment```.
+    pub fn new(total_size: u64, regions: usize, alignment: u64) -> Self {
+        let alignment = alignment.max(1);
+        debug_assert!(
+            alignment.is_power_of_two(),
+            "upload ring alignment {alignment} is not a power of two"
+        );
+        let regions = regions.max(1);
+        let region_size = total_size / regions as u64 / alignment * alignment;
+        Self {
+            alignment,
+            region_size,
+            regions,
+            region: 0,
+            used: 0,
+        }
+    }
+
+    pub fn alignment(&self) -> u64 {
+        self.alignment
+    }
+
+    pub fn region_size(&self) -> u64 {
+        self.region_size
+    }
+
+    /// Index of the region allocations are taken from.
+    pub fn region(&self) -> usize {
+        self.region
+    }
+
+    /// Bytes left in the current region for an allocation.
+    pub fn available(&self) -> u64 {
+        self.region_size
+            .saturating_sub(align_up(self.used, self.alignment))
+    }
+
+    /// Reserves ```size``` bytes in the current region and returns their offset into the whole ring.
+    ///
+    /// Returns [UploadBufferOverflow](Error::UploadBufferOverflow) if they do not fit, nothing is reserved in that case.
+    pub fn push(&mut self, size: u64) -> Result<u64, Error> {
+        let start = align_up(self.used, self.alignment);
+        let available = self.region_size.saturating_sub(start);
+        if size > available {
+            return Err(Error::UploadBufferOverflow {
+                requested: size,
+                available,
+            });
+        }
+        self.used = start + size;
+        Ok(self.region as u64 * self.region_size + start)
+    }
+
+    /// Moves on to the next region, wrapping around after the last one.
+    ///
+    /// ```fence_signaled``` is the state of the fence of the frame that used the next region last.
+    /// Returns [UploadRegionInFlight](Error::UploadRegionInFlight) and stays on the current region if it has not signaled yet.
+    pub fn next_frame(&mut self, fence_signaled: bool) -> Result<(), Error> {
+        let next = (self.region + 1) % self.regions;
+        if !fence_signaled {
+            return Err(Error::UploadRegionInFlight(next));
+        }
+        self.region = next;
+        self.used = 0;
+        Ok(())
+    }
+}
+
+fn align_up(offset: u64, alignment: u64) -> u64 {
+    (offset + alignment - 1) & !(alignment - 1)
+}
+
+/// Persistently mapped ring buffer for per-frame uniform and vertex data, instead of a separate buffer per frame in flight.
+///
+/// Each frame writes into its own region of the [UploadRing]. Bind ```buffer``` at the offsets returned by [allocate](DynamicUploadBuffer::allocate).
+/// ```
+/// # extern crate winit;
+/// # use vku::*;
+/// # use ash::vk::*;
+/// # if VkInit::load_entry().is_err() { return Ok(()); }
+/// # let init_info = VkInitCreateInfo::default();
+/// let mut init = VkInit::new_headless(init_info)?;
+/// let mut uniforms = init.create_dynamic_upload_buffer(64 * 1024, 2, BufferUsageFlags::UNIFORM_BUFFER)?;
+///
+/// let camera_offset = uniforms.allocate(&init.device, &[[0.0_f32; 16]])?;
+/// let light_offset = uniforms.allocate(&init.device, &[[1.0_f32; 4]; 8])?;
+/// // record and submit the frame, then move on once the fence of the frame before last has signaled
+/// uniforms.next_frame(true)?;
+/// # uniforms.destroy(&init.device, &mut init.allocator.lock())?;
+/// # Ok::<(), vku::Error>(())
+/// ```
+pub struct DynamicUploadBuffer {
+    pub buffer: VMABuffer,
+    ring: UploadRing,
+}
+
+impl DynamicUploadBuffer {
+    /// Creates a mapped buffer of ```total_size``` bytes split into ```frames``` regions.
+    ///
+    /// Offsets are aligned to ```min_uniform_buffer_offset_alignment``` and, with ```STORAGE_BUFFER``` usage, ```min_storage_buffer_offset_alignment```.
+    pub fn new(
+        device: &Device,
+        allocator: &mut Allocator,
+        limits: &PhysicalDeviceLimits,
+        total_size: usize,
+        frames: usize,
+        usage: BufferUsageFlags,
+    ) -> Result<Self, Error> {
+        let mut alignment = limits.min_uniform_buffer_offset_alignment;
+        if usage.contains(BufferUsageFlags::STORAGE_BUFFER) {
+            alignment = alignment.max(limits.min_storage_buffer_offset_alignment);
+        }
+        let buffer = VMABuffer::create_cpu_to_gpu_buffer(device, allocator, total_size, usage)?
+            .with_atom_size(limits);
+        Ok(Self {
+            buffer,
+            ring: UploadRing::new(total_size as u64, frames, alignment),
+        })
+    }
+
+    /// Copies ```data``` into the current region and returns its offset into ```buffer```.
+    ///
+    /// Returns [UploadBufferOverflow](Error::UploadBufferOverflow) if the region is full.
+    pub fn allocate<T>(&mut self, device: &Device, data: &[T]) -> Result<u64, Error> {
+        let bytes = unsafe {
+            std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data))
+        };
+        let offset = self.ring.push(bytes.len() as u64)?;
+        self.buffer.set_data(device, offset as usize, bytes)?;
+        Ok(offset)
+    }
+
+    /// Shortcut - see [UploadRing](UploadRing::next_frame).
+    pub fn next_frame(&mut self, fence_signaled: bool) -> Result<(), Error> {
+        self.ring.next_frame(fence_signaled)
+    }
+
+    pub fn ring(&self) -> &UploadRing {
+        &self.ring
+    }
+
+    pub fn destroy(&mut self, device: &Device, allocator: &mut Allocator) -> Result<(), Error> {
+        self.buffer.destroy(device, allocator)
+    }
+}
+
+impl VkInit {
+    /// Shortcut - see [DynamicUploadBuffer](DynamicUploadBuffer::new).
+    pub fn create_dynamic_upload_buffer(
+        &mut self,
+        total_size: usize,
+        frames: usize,
+        usage: BufferUsageFlags,
+    ) -> Result<DynamicUploadBuffer, Error> {
+        let buffer = DynamicUploadBuffer::new(
+            &self.device,
+            &mut self.allocator.lock(),
+            &self.physical_device_info.limits,
+            total_size,
+            frames,
+            usage,
+        )?;
+        buffer
+            .buffer
+            .set_debug_object_name(self, String::from("VKU_Dynamic_Upload"))?;
+        Ok(buffer)
+    }
+}
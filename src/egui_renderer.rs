@@ -0,0 +1,604 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use ash::util::read_spv;
+use egui::epaint::{
+    textures::{TextureFilter, TexturesDelta},
+    ClippedPrimitive, Color32, ImageData, Primitive, Rect, TextureId, Vertex,
+};
+
+use crate::{
+    descriptor_pool::DescriptorPools,
+    imports::*,
+    mipmaps::level_barrier,
+    pipeline_builder::{BlendMode, DepthInfo, StencilInfo, VKUPipeline, VertexConvert},
+    CmdType, DescriptorPoolPolicy, SamplerInfo, VMABuffer, VMAImage, VkInit,
+};
+
+const EGUI_VERT_SPV: &[u8] = include_bytes!("shaders/egui.vert.spv");
+const EGUI_FRAG_SPV: &[u8] = include_bytes!("shaders/egui.frag.spv");
+
+impl VertexConvert for Vertex {
+    fn binding_desc() -> Vec<VertexInputBindingDescription> {
+        vec![VertexInputBindingDescription::builder()
+            .binding(0)
+            .stride(size_of::<Vertex>() as u32)
+            .input_rate(VertexInputRate::VERTEX)
+            .build()]
+    }
+
+    fn attrib_desc() -> Vec<VertexInputAttributeDescription> {
+        vec![
+            VertexInputAttributeDescription::builder()
+                .binding(0)
+                .location(0)
+                .format(Format::R32G32_SFLOAT)
+                .offset(0)
+                .build(),
+            VertexInputAttributeDescription::builder()
+                .binding(0)
+                .location(1)
+                .format(Format::R32G32_SFLOAT)
+                .offset(8)
+                .build(),
+            VertexInputAttributeDescription::builder()
+                .binding(0)
+                .location(2)
+                .format(Format::R8G8B8A8_UNORM)
+                .offset(16)
+                .build(),
+        ]
+    }
+}
+
+/// Renders the output of an [egui](https://docs.rs/egui) context.
+///
+/// Owns the pipeline, the textures of [TexturesDelta] with one descriptor set each and a vertex and index buffer per frame in flight.
+/// Textures are ```R8G8B8A8_SRGB```, blending happens in linear space with premultiplied alpha.
+/// Colors are encoded manually if the surface needs it, see [srgb_spec_constant](VkInit::srgb_spec_constant).
+///
+/// Per frame: [update_textures](EguiRenderer::update_textures), [upload](EguiRenderer::upload) and
+/// [draw](EguiRenderer::draw) inside a rendering scope without depth.
+/// Paint callbacks and user textures are skipped.
+pub struct EguiRenderer {
+    pub pipeline: VKUPipeline,
+    frames: Vec<EguiFrame>,
+    textures: HashMap<TextureId, EguiTexture>,
+    /// Textures freed or replaced during a frame, destroyed once that frame is updated again.
+    pending_frees: Vec<Vec<EguiTexture>>,
+    /// [Frame scheme](VkInit::frame_scheme) generation the frames were last synced to.
+    generation: u64,
+    free_sets: Vec<DescriptorSet>,
+    pools: DescriptorPools,
+}
+
+struct EguiTexture {
+    image: VMAImage,
+    set: DescriptorSet,
+}
+
+struct EguiFrame {
+    vertex_buffer: VMABuffer,
+    index_buffer: VMABuffer,
+    draws: Vec<EguiDraw>,
+    pixels_per_point: f32,
+}
+
+struct EguiDraw {
+    clip_rect: Rect,
+    texture_id: TextureId,
+    first_index: u32,
+    index_count: u32,
+    vertex_offset: i32,
+}
+
+impl EguiRenderer {
+    /// Builds the pipeline for the color format and samples of the [Head](crate::Head) or [offscreen head](VkInit::create_offscreen_head).
+    /// One vertex and index buffer is created per [frame in flight](VkInit::frames_in_flight).
+    pub fn new(vk_init: &VkInit) -> Result<Self, Error> {
+        let frames_in_flight = vk_init.frames_in_flight().max(1);
+        let color_format = match (&vk_init.head, &vk_init.offscreen_head) {
+            (Some(head), _) => head.surface_info.color_format.format,
+            (None, Some(offscreen)) => offscreen.color_format,
+            (None, None) => return Err(Error::OffscreenHeadMissing),
+        };
+        let vertex_spirv = read_spv(&mut Cursor::new(EGUI_VERT_SPV))?;
+        let fragment_spirv = read_spv(&mut Cursor::new(EGUI_FRAG_SPV))?;
+
+        let mut pipeline = VKUPipeline::builder()
+            .with_vertex::<Vertex>(PrimitiveTopology::TRIANGLE_LIST)
+            .with_viewports_scissors(&[Viewport::default()], &[Rect2D::default()])
+            .with_rasterization(PolygonMode::FILL, CullModeFlags::NONE)
+            .with_multisample(vk_init.create_info.msaa_samples)
+            .with_depthstencil(DepthInfo::default(), StencilInfo::default())
+            .with_colorblends(&[BlendMode::PremultipliedTransparency])
+            .with_dynamic(&[DynamicState::VIEWPORT, DynamicState::SCISSOR])
+            .with_descriptors(&[(
+                false,
+                DescriptorType::COMBINED_IMAGE_SAMPLER,
+                ShaderStageFlags::FRAGMENT,
+                1,
+            )])
+            .with_rendering(&[color_format], None, None)
+            .with_push_constants::<[[f32; 4]; 4]>()
+            .push_shader_stage_spirv(
+                &vk_init.device,
+                ShaderStageFlags::VERTEX,
+                &vertex_spirv,
+                &[],
+            )?
+            .push_shader_stage_spirv(
+                &vk_init.device,
+                ShaderStageFlags::FRAGMENT,
+                &fragment_spirv,
+                &[vk_init.srgb_spec_constant()],
+            )?
+            .build(vk_init, "Egui")?;
+
+        let mut frames = vec![];
+        for frame in 0..frames_in_flight {
+            match Self::create_frame(vk_init, frame, 1 << 12, 1 << 14) {
+                Ok(egui_frame) => frames.push(egui_frame),
+                Err(e) => {
+                    for mut egui_frame in frames {
+                        Self::destroy_frame(vk_init, &mut egui_frame)?;
+                    }
+                    pipeline.destroy(&vk_init.device)?;
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(Self {
+            pipeline,
+            frames,
+            textures: HashMap::new(),
+            pending_frees: (0..frames_in_flight).map(|_| vec![]).collect(),
+            generation: vk_init.frame_scheme().generation,
+            free_sets: vec![],
+            pools: DescriptorPools::new(
+                DescriptorPoolPolicy::default(),
+                &[(DescriptorType::COMBINED_IMAGE_SAMPLER, 1)],
+                "Egui_Textures",
+            ),
+        })
+    }
+
+    /// Column-major matrix mapping egui points to NDC for a framebuffer of ```extent``` pixels.
+    pub fn screen_to_ndc(extent: Extent2D, pixels_per_point: f32) -> [[f32; 4]; 4] {
+        let width = extent.width as f32 / pixels_per_point;
+        let height = extent.height as f32 / pixels_per_point;
+        [
+            [2.0 / width, 0.0, 0.0, 0.0],
+            [0.0, 2.0 / height, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [-1.0, -1.0, 0.0, 1.0],
+        ]
+    }
+
+    /// Creates, updates and frees the textures of ```delta```, blocking until the uploads finished.
+    ///
+    /// Call before [upload](EguiRenderer::upload) once the previous use of ```frame``` finished on the GPU.
+    /// Freed and replaced textures are destroyed the next time ```frame``` is updated.
+    pub fn update_textures(
+        &mut self,
+        vk_init: &mut VkInit,
+        frame: usize,
+        delta: &TexturesDelta,
+    ) -> Result<(), Error> {
+        self.sync_frame_scheme(vk_init)?;
+        for texture in std::mem::take(&mut self.pending_frees[frame]) {
+            self.destroy_texture(vk_init, texture)?;
+        }
+
+        for (id, image_delta) in &delta.set {
+            let [width, height] = image_delta.image.size();
+            let pixels: Vec<Color32> = match &image_delta.image {
+                ImageData::Color(image) => image.pixels.clone(),
+                ImageData::Font(image) => image.srgba_pixels(None).collect(),
+            };
+
+            if let Some(pos) = image_delta.pos {
+                let Some(texture) = self.textures.get_mut(id) else {
+                    warn!("egui texture {id:?} was patched before it was set");
+                    continue;
+                };
+                Self::upload_texture(
+                    vk_init,
+                    &mut texture.image,
+                    [pos[0] as i32, pos[1] as i32],
+                    [width as u32, height as u32],
+                    &pixels,
+                )?;
+                continue;
+            }
+
+            let extent = Extent3D {
+                width: width as u32,
+                height: height as u32,
+                depth: 1,
+            };
+            let mut image =
+                vk_init.create_named_image(&format!("Egui_{id:?}"), |device, allocator| {
+                    VMAImage::create_compressed_image(
+                        device,
+                        allocator,
+                        extent,
+                        Format::R8G8B8A8_SRGB,
+                        1,
+                    )
+                })?;
+            if let Err(e) = Self::upload_texture(
+                vk_init,
+                &mut image,
+                [0, 0],
+                [extent.width, extent.height],
+                &pixels,
+            ) {
+                image.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+                return Err(e);
+            }
+
+            let filter = match image_delta.options.magnification {
+                TextureFilter::Nearest => Filter::NEAREST,
+                TextureFilter::Linear => Filter::LINEAR,
+            };
+            let sampler = vk_init.get_or_create_sampler(SamplerInfo {
+                filter,
+                ..Default::default()
+            })?;
+            let set = match self.free_sets.pop() {
+                Some(set) => set,
+                None => self.pools.allocate(vk_init, self.pipeline.set_layout)?,
+            };
+            let image_infos = [DescriptorImageInfo::builder()
+                .image_view(image.image_view)
+                .sampler(sampler)
+                .image_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                .build()];
+            let write = WriteDescriptorSet::builder()
+                .dst_set(set)
+                .dst_binding(0)
+                .descriptor_type(DescriptorType::COMBINED_IMAGE_SAMPLER)
+                .image_info(&image_infos)
+                .build();
+            unsafe { vk_init.device.update_descriptor_sets(&[write], &[]) };
+
+            if let Some(replaced) = self.textures.insert(*id, EguiTexture { image, set }) {
+                self.pending_frees[frame].push(replaced);
+            }
+        }
+
+        for id in &delta.free {
+            if let Some(texture) = self.textures.remove(id) {
+                self.pending_frees[frame].push(texture);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copies the meshes of ```primitives``` into the buffers of ```frame```, growing them if needed.
+    ///
+    /// The previous use of ```frame``` must have finished on the GPU.
+    pub fn upload(
+        &mut self,
+        vk_init: &VkInit,
+        frame: usize,
+        primitives: &[ClippedPrimitive],
+        pixels_per_point: f32,
+    ) -> Result<(), Error> {
+        self.sync_frame_scheme(vk_init)?;
+        let meshes: Vec<_> = primitives
+            .iter()
+            .filter_map(|primitive| match &primitive.primitive {
+                Primitive::Mesh(mesh) => Some((primitive.clip_rect, mesh)),
+                Primitive::Callback(_) => None,
+            })
+            .collect();
+        let vertex_count: usize = meshes.iter().map(|(_, mesh)| mesh.vertices.len()).sum();
+        let index_count: usize = meshes.iter().map(|(_, mesh)| mesh.indices.len()).sum();
+
+        let egui_frame = &mut self.frames[frame];
+        egui_frame.draws.clear();
+        egui_frame.pixels_per_point = pixels_per_point;
+        let vertex_capacity = egui_frame.vertex_buffer.size() as usize / size_of::<Vertex>();
+        let index_capacity = egui_frame.index_buffer.size() as usize / size_of::<u32>();
+        if vertex_count > vertex_capacity || index_count > index_capacity {
+            let grown = Self::create_frame(
+                vk_init,
+                frame,
+                vertex_count.max(vertex_capacity).next_power_of_two(),
+                index_count.max(index_capacity).next_power_of_two(),
+            )?;
+            Self::destroy_frame(vk_init, egui_frame)?;
+            *egui_frame = grown;
+            egui_frame.pixels_per_point = pixels_per_point;
+        }
+
+        let mut first_vertex = 0;
+        let mut first_index = 0;
+        for (clip_rect, mesh) in meshes {
+            egui_frame
+                .vertex_buffer
+                .set_data(&vk_init.device, first_vertex, &mesh.vertices)?;
+            egui_frame
+                .index_buffer
+                .set_data(&vk_init.device, first_index, &mesh.indices)?;
+            egui_frame.draws.push(EguiDraw {
+                clip_rect,
+                texture_id: mesh.texture_id,
+                first_index: first_index as u32,
+                index_count: mesh.indices.len() as u32,
+                vertex_offset: first_vertex as i32,
+            });
+            first_vertex += mesh.vertices.len();
+            first_index += mesh.indices.len();
+        }
+
+        Ok(())
+    }
+
+    /// Draws the meshes uploaded to ```frame``` into a framebuffer of ```extent``` pixels.
+    ///
+    /// Must be called inside an active rendering scope without depth attachment,
+    /// e.g. [begin_rendering_with](VkInit::begin_rendering_with) with ```depth: false```.
+    /// See [screen_to_ndc](EguiRenderer::screen_to_ndc) for ```screen_to_ndc```.
+    pub fn draw(
+        &self,
+        vk_init: &VkInit,
+        cmd_buffer: &CommandBuffer,
+        frame: usize,
+        extent: Extent2D,
+        screen_to_ndc: [[f32; 4]; 4],
+    ) -> Result<(), Error> {
+        let egui_frame = &self.frames[frame];
+        if egui_frame.draws.is_empty() {
+            return Ok(());
+        }
+
+        let viewport = Viewport {
+            x: 0.0,
+            y: 0.0,
+            width: extent.width as f32,
+            height: extent.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        };
+        vk_init.cmd_bind_graphics_pipeline(cmd_buffer, &self.pipeline);
+        vk_init.cmd_push_constants_typed(
+            cmd_buffer,
+            self.pipeline.layout,
+            ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT,
+            &screen_to_ndc,
+        )?;
+        vk_init.cmd_set_viewport(cmd_buffer, &[viewport]);
+        unsafe {
+            vk_init.device.cmd_bind_vertex_buffers(
+                *cmd_buffer,
+                0,
+                &[egui_frame.vertex_buffer.buffer],
+                &[0],
+            );
+            vk_init.device.cmd_bind_index_buffer(
+                *cmd_buffer,
+                egui_frame.index_buffer.buffer,
+                0,
+                IndexType::UINT32,
+            );
+        }
+
+        for draw in &egui_frame.draws {
+            let Some(texture) = self.textures.get(&draw.texture_id) else {
+                continue;
+            };
+            let Some(scissor) =
+                clip_to_scissor(draw.clip_rect, egui_frame.pixels_per_point, extent)
+            else {
+                continue;
+            };
+            vk_init.cmd_bind_descriptor_sets(
+                cmd_buffer,
+                PipelineBindPoint::GRAPHICS,
+                self.pipeline.layout,
+                0,
+                &[texture.set],
+            );
+            vk_init.cmd_set_scissor(cmd_buffer, &[scissor]);
+            vk_init.cmd_draw_indexed(
+                cmd_buffer,
+                draw.index_count,
+                1,
+                draw.first_index,
+                draw.vertex_offset,
+                0,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Destroys all textures, buffers and the pipeline. No frame may be in flight.
+    pub fn destroy(&mut self, vk_init: &VkInit) -> Result<(), Error> {
+        let pending: Vec<EguiTexture> = self
+            .pending_frees
+            .iter_mut()
+            .flat_map(std::mem::take)
+            .collect();
+        let textures: Vec<EguiTexture> =
+            self.textures.drain().map(|(_, texture)| texture).collect();
+        for mut texture in pending.into_iter().chain(textures) {
+            texture
+                .image
+                .destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        }
+        for mut egui_frame in self.frames.drain(..) {
+            Self::destroy_frame(vk_init, &mut egui_frame)?;
+        }
+        self.free_sets.clear();
+        self.pools.destroy(&vk_init.device);
+        self.pipeline.destroy(&vk_init.device)
+    }
+
+    /// Adds frames once the [frames in flight](VkInit::frames_in_flight) grew.
+    /// Frames beyond a shrunk count stay unused until [destroy](EguiRenderer::destroy), as they may still be in flight.
+    fn sync_frame_scheme(&mut self, vk_init: &VkInit) -> Result<(), Error> {
+        let generation = vk_init.frame_scheme().generation;
+        if self.generation == generation {
+            return Ok(());
+        }
+        for frame in self.frames.len()..vk_init.frames_in_flight() {
+            self.frames
+                .push(Self::create_frame(vk_init, frame, 1 << 12, 1 << 14)?);
+            self.pending_frees.push(vec![]);
+        }
+        self.generation = generation;
+        Ok(())
+    }
+
+    fn create_frame(
+        vk_init: &VkInit,
+        frame: usize,
+        max_vertices: usize,
+        max_indices: usize,
+    ) -> Result<EguiFrame, Error> {
+        let mut vertex_buffer = vk_init.create_cpu_to_gpu_buffer_named(
+            max_vertices * size_of::<Vertex>(),
+            BufferUsageFlags::VERTEX_BUFFER,
+            &format!("Egui_Vertices_{frame}"),
+        )?;
+        let index_buffer = match vk_init.create_cpu_to_gpu_buffer_named(
+            max_indices * size_of::<u32>(),
+            BufferUsageFlags::INDEX_BUFFER,
+            &format!("Egui_Indices_{frame}"),
+        ) {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                vertex_buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+                return Err(e);
+            }
+        };
+
+        Ok(EguiFrame {
+            vertex_buffer,
+            index_buffer,
+            draws: vec![],
+            pixels_per_point: 1.0,
+        })
+    }
+
+    fn destroy_frame(vk_init: &VkInit, egui_frame: &mut EguiFrame) -> Result<(), Error> {
+        egui_frame
+            .vertex_buffer
+            .destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        egui_frame
+            .index_buffer
+            .destroy(&vk_init.device, &mut vk_init.allocator.lock())
+    }
+
+    fn destroy_texture(&mut self, vk_init: &VkInit, mut texture: EguiTexture) -> Result<(), Error> {
+        texture
+            .image
+            .destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        self.free_sets.push(texture.set);
+        Ok(())
+    }
+
+    /// Copies ```pixels``` into the region at ```offset``` of ```image```, keeping the rest of its contents.
+    fn upload_texture(
+        vk_init: &mut VkInit,
+        image: &mut VMAImage,
+        offset: [i32; 2],
+        extent: [u32; 2],
+        pixels: &[Color32],
+    ) -> Result<(), Error> {
+        let staging = vk_init.acquire_staging(std::mem::size_of_val(pixels))?;
+        if let Err(e) = staging.set_data(&vk_init.device, 0, pixels) {
+            vk_init.release_staging(staging, None)?;
+            return Err(e);
+        }
+
+        let region = BufferImageCopy::builder()
+            .image_subresource(
+                ImageSubresourceLayers::builder()
+                    .aspect_mask(image.aspect_flags)
+                    .mip_level(0)
+                    .base_array_layer(0)
+                    .layer_count(1)
+                    .build(),
+            )
+            .image_offset(Offset3D {
+                x: offset[0],
+                y: offset[1],
+                z: 0,
+            })
+            .image_extent(Extent3D {
+                width: extent[0],
+                height: extent[1],
+                depth: 1,
+            })
+            .build();
+        let to_transfer = level_barrier(
+            image,
+            0..1,
+            (image.current_layout, ImageLayout::TRANSFER_DST_OPTIMAL),
+            (
+                PipelineStageFlags2::FRAGMENT_SHADER,
+                AccessFlags2::SHADER_SAMPLED_READ,
+            ),
+            (PipelineStageFlags2::COPY, AccessFlags2::TRANSFER_WRITE),
+        );
+        let to_sampled = level_barrier(
+            image,
+            0..1,
+            (
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+                ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            ),
+            (PipelineStageFlags2::COPY, AccessFlags2::TRANSFER_WRITE),
+            (
+                PipelineStageFlags2::FRAGMENT_SHADER,
+                AccessFlags2::SHADER_SAMPLED_READ,
+            ),
+        );
+
+        let submitted = vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer], &[]);
+            unsafe {
+                vk_init.device.cmd_copy_buffer_to_image(
+                    *cmd_buffer,
+                    staging.buffer.buffer,
+                    image.image,
+                    ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[region],
+                );
+            }
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_sampled], &[]);
+        });
+        vk_init.release_staging(staging, None)?;
+        submitted?;
+        image.current_layout = ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+        Ok(())
+    }
+}
+
+/// Scissor of ```clip_rect``` in pixels clamped to ```extent```, ```None``` if nothing is visible.
+fn clip_to_scissor(clip_rect: Rect, pixels_per_point: f32, extent: Extent2D) -> Option<Rect2D> {
+    let min_x = (clip_rect.min.x * pixels_per_point).round().max(0.0) as u32;
+    let min_y = (clip_rect.min.y * pixels_per_point).round().max(0.0) as u32;
+    let max_x = ((clip_rect.max.x * pixels_per_point).round().max(0.0) as u32).min(extent.width);
+    let max_y = ((clip_rect.max.y * pixels_per_point).round().max(0.0) as u32).min(extent.height);
+    if min_x >= max_x || min_y >= max_y {
+        return None;
+    }
+    Some(Rect2D {
+        offset: Offset2D {
+            x: min_x as i32,
+            y: min_y as i32,
+        },
+        extent: Extent2D {
+            width: max_x - min_x,
+            height: max_y - min_y,
+        },
+    })
+}
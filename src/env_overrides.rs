@@ -0,0 +1,141 @@
+use crate::{imports::*, GpuSelection, VkInitCreateInfo};
+
+const VALIDATION_LAYER: &str = "VK_LAYER_KHRONOS_validation";
+
+impl VkInitCreateInfo {
+    /// Overrides fields from ```VKU_*``` environment variables, e.g. to debug user machines without rebuilding.
+    ///
+    /// | Variable | Values | Field |
+    /// |---|---|---|
+    /// | ```VKU_VALIDATION``` | ```1```/```0```, ```true```/```false```, ```on```/```off```, ```yes```/```no``` | [enable_validation](VkInitCreateInfo::enable_validation), adds ```VK_LAYER_KHRONOS_validation``` if no layer is configured |
+    /// | ```VKU_LOG_LEVEL``` | ```verbose```, ```info```, ```warning```, ```error```, ```none``` | [log_level](VkInitCreateInfo::log_level), the given severity and above |
+    /// | ```VKU_PRESENT_MODE``` | ```immediate```, ```mailbox```, ```fifo```, ```fifo_relaxed``` | [present_mode_preferences](VkInitCreateInfo::present_mode_preferences) |
+    /// | ```VKU_DEVICE``` | device index, ```highest_memory``` or name substring | [gpu_preference](VkInitCreateInfo::gpu_preference) |
+    /// | ```VKU_ALLOW_IGPU``` | same as ```VKU_VALIDATION``` | [allow_igpu](VkInitCreateInfo::allow_igpu) |
+    /// | ```VKU_IMAGE_COUNT``` | swapchain image count > 0 | [request_img_count](VkInitCreateInfo::request_img_count) |
+    ///
+    /// Invalid values are logged as warning and ignored.
+    pub fn apply_env_overrides(&mut self) {
+        self.apply_overrides_from(|name| std::env::var(name).ok());
+    }
+
+    /// Same as [apply_env_overrides](VkInitCreateInfo::apply_env_overrides), but looks variables up with ```var``` instead of the environment.
+    /// ```
+    /// # use vku::*;
+    /// let mut create_info = VkInitCreateInfo::default();
+    /// create_info.apply_overrides_from(|name| match name {
+    ///     "VKU_DEVICE" => Some(String::from("1")),
+    ///     _ => None,
+    /// });
+    /// assert_eq!(create_info.gpu_preference, GpuSelection::Index(1));
+    /// ```
+    pub fn apply_overrides_from(&mut self, var: impl Fn(&str) -> Option<String>) {
+        if let Some(enable) = parse_var(&var, "VKU_VALIDATION", "a boolean", parse_bool) {
+            self.enable_validation = enable;
+            if enable && self.enabled_validation_layers.is_empty() {
+                self.enabled_validation_layers
+                    .push(String::from(VALIDATION_LAYER));
+            }
+        }
+
+        if let Some(log_level) = parse_var(
+            &var,
+            "VKU_LOG_LEVEL",
+            "verbose, info, warning, error or none",
+            parse_log_level,
+        ) {
+            self.log_level = log_level;
+        }
+
+        if let Some(present_mode) = parse_var(
+            &var,
+            "VKU_PRESENT_MODE",
+            "immediate, mailbox, fifo or fifo_relaxed",
+            parse_present_mode,
+        ) {
+            self.present_mode_preferences = vec![present_mode];
+        }
+
+        if let Some(gpu_preference) = parse_var(
+            &var,
+            "VKU_DEVICE",
+            "a device index, highest_memory or name",
+            parse_gpu_selection,
+        ) {
+            self.gpu_preference = gpu_preference;
+        }
+
+        if let Some(allow_igpu) = parse_var(&var, "VKU_ALLOW_IGPU", "a boolean", parse_bool) {
+            self.allow_igpu = allow_igpu;
+        }
+
+        if let Some(image_count) =
+            parse_var(&var, "VKU_IMAGE_COUNT", "a positive integer", |value| {
+                value.parse().ok().filter(|count| *count > 0)
+            })
+        {
+            self.request_img_count = image_count;
+        }
+    }
+}
+
+fn parse_var<T>(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    expected: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<T> {
+    let value = var(name)?;
+    let parsed = parse(value.trim());
+    match &parsed {
+        Some(_) => info!("Applied {name}={value}"),
+        None => warn!("Ignoring {name}={value}, expected {expected}"),
+    }
+    parsed
+}
+
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "on" | "yes" => Some(true),
+        "0" | "false" | "off" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Severity and all higher severities.
+pub(crate) fn parse_log_level(value: &str) -> Option<DebugUtilsMessageSeverityFlagsEXT> {
+    let error = DebugUtilsMessageSeverityFlagsEXT::ERROR;
+    let warning = error | DebugUtilsMessageSeverityFlagsEXT::WARNING;
+    let info = warning | DebugUtilsMessageSeverityFlagsEXT::INFO;
+    match value.to_lowercase().as_str() {
+        "verbose" | "all" => Some(info | DebugUtilsMessageSeverityFlagsEXT::VERBOSE),
+        "info" => Some(info),
+        "warning" | "warn" => Some(warning),
+        "error" => Some(error),
+        "none" | "off" => Some(DebugUtilsMessageSeverityFlagsEXT::empty()),
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_present_mode(value: &str) -> Option<PresentModeKHR> {
+    match value.to_lowercase().as_str() {
+        "immediate" => Some(PresentModeKHR::IMMEDIATE),
+        "mailbox" => Some(PresentModeKHR::MAILBOX),
+        "fifo" => Some(PresentModeKHR::FIFO),
+        "fifo_relaxed" => Some(PresentModeKHR::FIFO_RELAXED),
+        _ => None,
+    }
+}
+
+fn parse_gpu_selection(value: &str) -> Option<GpuSelection> {
+    if value.is_empty() {
+        return None;
+    }
+    if value.eq_ignore_ascii_case("highest_memory") {
+        return Some(GpuSelection::HighestMemory);
+    }
+    match value.parse() {
+        Ok(index) => Some(GpuSelection::Index(index)),
+        Err(_) => Some(GpuSelection::NamedSubstring(value.to_string())),
+    }
+}
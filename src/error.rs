@@ -1,35 +1,220 @@
 use std::{ffi::NulError, str::Utf8Error};
 use thiserror::Error;
 
-unsafe impl Send for Error {}
-unsafe impl Sync for Error {}
-
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("called function which requires a head on headless instance")]
     HeadCallOnHeadlessInstance,
+    #[error("called function which requires an offscreen head, see create_offscreen_head")]
+    OffscreenHeadMissing,
     #[error("no suitable GPU was found to create the physical device")]
     NoSuitableGPUFound,
+    #[error("no GPU matches the selection {selection:?} with allow_igpu = {allow_igpu}, available: [{available}]")]
+    RequestedGPUNotFound {
+        selection: crate::GpuSelection,
+        allow_igpu: bool,
+        available: String,
+    },
     #[error("device extension was requested but is not supported. Extension: {0}")]
     RequiredDeviceExtensionNotSupported(String),
-    #[error("requested surface format is not supported by the surface")]
+    #[error("surface does not support any format")]
     RequestedSurfaceFormatNotSupported,
     #[error("more frames in flight were requested than the surface supports")]
     InsufficientFramesInFlightSupported,
     #[error("requested present mode is not supported by the surface")]
     PresentModeNotSupported,
+    #[error("msaa sample count {0:?} is not supported for color and depth attachments, supported: {1:?}")]
+    MsaaSamplesNotSupported(ash::vk::SampleCountFlags, ash::vk::SampleCountFlags),
+    #[error("requested device feature {0} is not supported by the physical device")]
+    RequestedFeatureNotSupported(&'static str),
+    #[error("device feature {0} is required but was not enabled in the VkInitCreateInfo")]
+    FeatureNotEnabled(&'static str),
+    #[error("render area {area:?} exceeds the framebuffer extent {extent:?}")]
+    RenderAreaOutOfBounds {
+        area: ash::vk::Rect2D,
+        extent: ash::vk::Extent2D,
+    },
+    #[error("scissor {scissor:?} is not inside the render area {area:?}")]
+    ScissorOutsideRenderArea {
+        scissor: ash::vk::Rect2D,
+        area: ash::vk::Rect2D,
+    },
 
     #[error("the requested image layout transition is not supported")]
     UnsupportedImageLayoutTransition,
+    #[error("texel size of format {0:?} is unknown")]
+    UnknownTexelSize(ash::vk::Format),
+    #[error("image has no staging buffer, see VMAImage::with_staging_buffer")]
+    NoStagingBuffer,
     #[error("tried to set data on an unmapped buffer")]
     WriteAttemptToUnmappedBuffer,
+    #[error("tried to read data from an unmapped buffer")]
+    ReadAttemptFromUnmappedBuffer,
+    #[error("device does not support timestamps on all graphics and compute queues")]
+    TimestampsNotSupported,
+    #[error("timestamp query {0} has not been written yet")]
+    TimestampNotAvailable(u32),
+    #[error("all {0} profiler scopes of this frame are used")]
+    ProfilerScopesExceeded(u32),
+    #[error("profiler scope {0} was not begun")]
+    ProfilerScopeNotBegun(String),
+    #[error("upload of {requested} bytes exceeds the {available} bytes left in the frame region")]
+    UploadBufferOverflow { requested: u64, available: u64 },
+    #[error("region {0} of the upload buffer is still in flight")]
+    UploadRegionInFlight(usize),
+    #[error("contents of an unmapped buffer can only be preserved by a copy command, see VMABuffer::enqueue_resize")]
+    ResizeNeedsCopyCommand,
+    #[error("{operation} waited {waited:?} for the GPU, a hang is suspected")]
+    GpuHangSuspected {
+        operation: &'static str,
+        waited: std::time::Duration,
+    },
+    #[error("readback was attempted before its fence signaled")]
+    ReadbackNotReady,
+    #[error("tried to read zero-sized elements from a buffer")]
+    ReadZeroSizedType,
+    #[error("sparse buffer binding and residency are not supported by the device")]
+    SparseBindingNotSupported,
+    #[error("sparse page range ends at {end}, but the buffer only has {page_count} pages")]
+    SparsePagesOutOfRange { end: usize, page_count: usize },
+    #[error("{} resources created by this VkInit are still alive", .0.len())]
+    ResourcesStillAlive(Vec<crate::ResourceDescriptor>),
+    #[error("buffer was not created with the required usage {0:?}")]
+    BufferUsageMissing(ash::vk::BufferUsageFlags),
+    #[error("buffer read of {requested} bytes exceeds the {available} available bytes")]
+    BufferReadOutOfBounds { requested: u64, available: u64 },
+    #[error("buffer write of {requested} bytes exceeds the {available} available bytes")]
+    BufferWriteOutOfBounds { requested: u64, available: u64 },
+    #[error("buffer write at byte offset {offset} is not aligned to the {alignment} byte alignment of the data")]
+    BufferWriteMisaligned { offset: u64, alignment: u64 },
+    #[error("upload was cancelled after {bytes_written} of {bytes_total} bytes")]
+    UploadCancelled {
+        bytes_written: usize,
+        bytes_total: usize,
+    },
+    #[error("image has {expected} mip levels, but {provided} mip sources were provided")]
+    MipChainMismatch { expected: u32, provided: usize },
+    #[error("image atlas has no space left for a region of {0:?} texels")]
+    AtlasFull([u32; 2]),
+    #[error(
+        "atlas region needs {expected} bytes of pixel data, but {provided} bytes were provided"
+    )]
+    AtlasDataMismatch { expected: usize, provided: usize },
+    #[error("format {0:?} is not supported by image atlases")]
+    UnsupportedAtlasFormat(ash::vk::Format),
+    #[error("{width}x{height} rgba8 image needs {expected} bytes of pixel data, but {provided} bytes were provided")]
+    ImageDataMismatch {
+        width: u32,
+        height: u32,
+        expected: usize,
+        provided: usize,
+    },
+    #[error("format {0:?} is not supported for rgba8 uploads")]
+    UnsupportedColorSpaceFormat(ash::vk::Format),
+    #[error("swapchain images were not created with transfer source usage and can't be captured")]
+    CaptureNotSupported,
+    #[error("format {0:?} is not supported for frame captures")]
+    UnsupportedCaptureFormat(ash::vk::Format),
+    #[error("swapchain is out of date and has to be recreated")]
+    SwapchainOutOfDate,
+    #[error("window is minimized, the swapchain can not be recreated with a zero extent")]
+    WindowMinimized,
+    #[error("begin_frame was called before the previous frame was ended")]
+    FrameAlreadyInProgress,
+    #[error("frame {0} was not begun by begin_frame or the swapchain was recreated since")]
+    FrameNotInProgress(usize),
+    #[error("frame rate {0} is not positive or has no representable frame interval")]
+    InvalidFrameRate(f32),
+    #[error("format {0:?} supports neither blit nor compute mipmap generation")]
+    MipmapGenerationNotSupported(ash::vk::Format),
+    #[error("image was not created with the required usage {0:?}")]
+    ImageUsageMissing(ash::vk::ImageUsageFlags),
+    #[error("{requested} objects exceed the {max} objects the culler was created for")]
+    CullObjectsExceeded { requested: usize, max: usize },
+    #[error("compute shader bindings {found:?} do not match the layout {expected:?}")]
+    ComputeBindingsMismatch {
+        expected: Vec<ash::vk::DescriptorType>,
+        found: Vec<ash::vk::DescriptorType>,
+    },
+    #[error("compute shader was created with a DescriptorAllocator, see ComputeShader::allocate_set_from")]
+    ComputeShaderWithoutPools,
+    #[error("aliased resource needs {required} bytes of the allocation, but it only has {available} bytes")]
+    AliasOutOfBounds { required: u64, available: u64 },
+    #[error("alias offset {offset} is not a multiple of the required alignment {alignment}")]
+    AliasMisaligned { offset: u64, alignment: u64 },
+    #[error("aliased resource supports memory types {memory_type_bits:#b}, none of them has the allocation's properties {memory_properties:?}")]
+    AliasMemoryTypeMismatch {
+        memory_type_bits: u32,
+        memory_properties: ash::vk::MemoryPropertyFlags,
+    },
+
+    #[error(
+        "vertex binding {binding} requests divisor {divisor}, which is not supported by the device"
+    )]
+    VertexAttributeDivisorNotSupported { binding: u32, divisor: u32 },
+    #[error("vertex binding {0} has a divisor but no instance input rate")]
+    VertexAttributeDivisorOnNonInstancedBinding(u32),
+    #[error("dynamic vertex input state was requested, but VK_EXT_vertex_input_dynamic_state is not supported")]
+    VertexInputDynamicStateNotSupported,
+
+    #[error("replayable commands were created before the device was switched and have to be created again")]
+    ReplayableCommandsDeviceReplaced,
+    #[error("external handle type {0} is not supported by the device or platform")]
+    ExternalHandleTypeNotSupported(String),
+    #[error("image memory was not created exportable, see VMAImage::create_exportable")]
+    ImageMemoryNotExportable,
+
+    #[error("no pipeline cache was loaded, see VkInit::load_or_create_pipeline_cache")]
+    PipelineCacheNotLoaded,
 
     #[error("the file extension of the shader could not be handled")]
     UnknownShaderFileExtension,
+    #[error("shader could not be reflected: {0}")]
+    InvalidSpirv(&'static str),
+    #[error("push constant range of {rust} bytes is smaller than the {shader} byte block declared by the {stage:?} shader")]
+    PushConstantSizeMismatch {
+        stage: ash::vk::ShaderStageFlags,
+        rust: u32,
+        shader: u32,
+    },
+    #[error("push constants of {size} bytes exceed the device limit of {max} bytes")]
+    PushConstantsTooLarge { size: usize, max: u32 },
+    #[error("push constant data of {provided} bytes does not match the {expected} byte push constant type")]
+    PushBytesSizeMismatch { expected: usize, provided: usize },
+    #[error("reflected descriptor set {set} binding {binding} cannot be used for a pipeline layout: {reason}")]
+    ReflectedDescriptorsIncompatible {
+        set: u32,
+        binding: u32,
+        reason: &'static str,
+    },
 
     #[cfg(feature = "shader")]
     #[error("shader compilation failed, see preprocess trace above. Source error: {0}")]
     Preprocess(#[from] shaderc::Error),
+    #[cfg(feature = "shader")]
+    #[error("{file:?} ({stage:?}) failed to compile with {num_errors} errors:\n{log}")]
+    ShaderCompilation {
+        file: std::path::PathBuf,
+        stage: shaderc::ShaderKind,
+        log: String,
+        num_errors: u32,
+        preprocessed: String,
+    },
+    #[cfg(feature = "shader")]
+    #[error("{} shaders failed to compile:\n{}", .0.len(), .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))]
+    ShadersFailedToCompile(Vec<Error>),
+
+    #[cfg(feature = "image")]
+    #[error("image decoding failed: {0}")]
+    ImageDecode(#[from] image::ImageError),
+
+    #[cfg(feature = "ktx2")]
+    #[error("ktx2 parsing failed: {0}")]
+    Ktx2Parse(#[from] ktx2::ParseError),
+    #[error("ktx2 texture is not supported: {0}")]
+    UnsupportedKtx2Texture(&'static str),
+    #[error("format {0:?} can't be sampled and uploaded to on this device")]
+    TextureFormatNotSupported(ash::vk::Format),
 
     #[error("incorrect usage of the vulkan API: {0}")]
     VkError(#[from] ash::vk::Result),
@@ -37,11 +222,25 @@ pub enum Error {
     #[error("vulkan entry could not be loaded: {0}")]
     AshLoadError(#[from] ash::LoadingError),
 
+    #[error("vulkan loader could not be loaded from any of [{attempted}]. Install a Vulkan runtime or update the GPU driver. Source error: {source}")]
+    VulkanLoaderNotFound {
+        attempted: String,
+        source: ash::LoadingError,
+    },
+
+    #[error("requested vulkan version {requested} is not supported by the loader, which supports up to {supported}. Update the GPU driver or request a lower version")]
+    InstanceVersionNotSupported {
+        requested: String,
+        supported: String,
+    },
+
     #[error("utf8 error: {0}")]
     Utf8Error(#[from] Utf8Error),
 
     #[error("cstring convert error: {0}")]
     CStringConvertError(#[from] NulError),
+    #[error("debug name starting with {0:?} contains a nul character")]
+    DebugNameContainsNul(String),
 
     #[error("io error: {0}")]
     IOError(#[from] std::io::Error),
@@ -49,9 +248,17 @@ pub enum Error {
     #[error("gpu allocation error: {0}")]
     GpuAllocError(#[from] gpu_allocator::AllocationError),
 
+    #[error("gpu allocation failed again after draining the deletion queue: {second}. First attempt: {first}\nBefore draining: {before}\nAfter draining: {after}")]
+    GpuAllocRetryFailed {
+        first: gpu_allocator::AllocationError,
+        second: gpu_allocator::AllocationError,
+        before: Box<crate::HeapState>,
+        after: Box<crate::HeapState>,
+    },
+
     #[error("shaderc failed to initialize")]
     ShaderCInitError,
 
     #[error("encountered an unknown error: {0}")]
-    Catch(#[from] Box<dyn std::error::Error>),
+    Catch(#[from] Box<dyn std::error::Error + Send + Sync>),
 }
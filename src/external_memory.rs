@@ -0,0 +1,519 @@
+use ash::extensions::khr::{
+    ExternalMemoryFd, ExternalMemoryWin32, ExternalSemaphoreFd, ExternalSemaphoreWin32,
+};
+
+use crate::vma_buffer::AllocationInfo;
+use crate::vma_image::ExternalMemory;
+use crate::{imports::*, VMAImage, VkInit};
+
+/// OS handle of memory or a semaphore shared with another API, e.g. a video decoder or OpenGL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExternalHandle {
+    /// POSIX file descriptor, ownership moves to the driver on a successful import.
+    Fd(i32),
+    /// Windows handle, the caller keeps ownership and closes it.
+    Win32(HANDLE),
+}
+
+impl ExternalHandle {
+    /// Opaque memory handle type of the platform, ```OPAQUE_WIN32``` on Windows and ```OPAQUE_FD``` elsewhere.
+    pub fn platform_memory_handle_type() -> ExternalMemoryHandleTypeFlags {
+        if cfg!(windows) {
+            ExternalMemoryHandleTypeFlags::OPAQUE_WIN32
+        } else {
+            ExternalMemoryHandleTypeFlags::OPAQUE_FD
+        }
+    }
+
+    /// Opaque semaphore handle type of the platform, ```OPAQUE_WIN32``` on Windows and ```OPAQUE_FD``` elsewhere.
+    pub fn platform_semaphore_handle_type() -> ExternalSemaphoreHandleTypeFlags {
+        if cfg!(windows) {
+            ExternalSemaphoreHandleTypeFlags::OPAQUE_WIN32
+        } else {
+            ExternalSemaphoreHandleTypeFlags::OPAQUE_FD
+        }
+    }
+
+    fn is_fd_memory(handle_type: ExternalMemoryHandleTypeFlags) -> bool {
+        handle_type == ExternalMemoryHandleTypeFlags::OPAQUE_FD
+            || handle_type == ExternalMemoryHandleTypeFlags::DMA_BUF_EXT
+    }
+
+    fn is_fd_semaphore(handle_type: ExternalSemaphoreHandleTypeFlags) -> bool {
+        handle_type == ExternalSemaphoreHandleTypeFlags::OPAQUE_FD
+            || handle_type == ExternalSemaphoreHandleTypeFlags::SYNC_FD
+    }
+}
+
+/// Image created by another API, imported with [import_external](VMAImage::import_external).
+#[derive(Clone, Copy, Debug)]
+pub struct ExternalImageDesc {
+    pub extent: Extent3D,
+    pub format: Format,
+    pub usage: ImageUsageFlags,
+    pub tiling: ImageTiling,
+    pub aspect_flags: ImageAspectFlags,
+    pub handle: ExternalHandle,
+    pub handle_type: ExternalMemoryHandleTypeFlags,
+    /// Size of the exported memory, ```None``` uses the image's memory requirements.
+    pub allocation_size: Option<u64>,
+    /// The exporter allocated the memory dedicated to this image.
+    pub dedicated: bool,
+}
+
+impl VMAImage {
+    /// Imports an image whose memory was exported by another API through ```desc.handle```.
+    ///
+    /// The memory is a dedicated ```DeviceMemory``` outside of the allocator, freed by [destroy](VMAImage::destroy).
+    /// No staging buffer is created. Fd handles belong to the driver afterwards, Windows handles stay with the caller.
+    /// Returns [ExternalHandleTypeNotSupported](Error::ExternalHandleTypeNotSupported) if the device can't import the handle type for this image.
+    pub fn import_external(vk_init: &VkInit, desc: &ExternalImageDesc) -> Result<VMAImage, Error> {
+        let support = &vk_init.physical_device_info.external_handles;
+        let handle_supported = match desc.handle {
+            ExternalHandle::Fd(_) => {
+                ExternalHandle::is_fd_memory(desc.handle_type)
+                    && support.memory_fd
+                    && (desc.handle_type != ExternalMemoryHandleTypeFlags::DMA_BUF_EXT
+                        || support.memory_dma_buf)
+            }
+            ExternalHandle::Win32(_) => {
+                !ExternalHandle::is_fd_memory(desc.handle_type) && support.memory_win32
+            }
+        };
+        let properties = vk_init.external_image_properties(
+            desc.format,
+            desc.usage,
+            desc.tiling,
+            desc.handle_type,
+        )?;
+        if !handle_supported
+            || !properties
+                .external_memory_features
+                .contains(ExternalMemoryFeatureFlags::IMPORTABLE)
+        {
+            return Err(Error::ExternalHandleTypeNotSupported(format!(
+                "{:?}",
+                desc.handle_type
+            )));
+        }
+        let dedicated = desc.dedicated
+            || properties
+                .external_memory_features
+                .contains(ExternalMemoryFeatureFlags::DEDICATED_ONLY);
+
+        let mut external_info =
+            ExternalMemoryImageCreateInfo::builder().handle_types(desc.handle_type);
+        let image_info =
+            Self::external_image_info(desc.extent, desc.format, desc.usage, desc.tiling)
+                .push_next(&mut external_info)
+                .build();
+
+        unsafe {
+            let image = vk_init.device.create_image(&image_info, None)?;
+            let (info, requirements) = Self::allocate_external(
+                vk_init,
+                image,
+                desc.allocation_size,
+                desc.handle_type,
+                Some(desc.handle),
+                dedicated,
+            )?;
+            trace!(
+                "Imported {:?} image memory of {} bytes",
+                desc.handle_type,
+                info.size
+            );
+
+            let external = ExternalMemory {
+                info,
+                handle_type: desc.handle_type,
+                exportable: false,
+            };
+            Self::external_image(
+                vk_init,
+                image,
+                &image_info,
+                desc.aspect_flags,
+                requirements,
+                external,
+            )
+        }
+    }
+
+    /// Creates a device-local image in dedicated memory that can be shared with [export_memory_handle](VMAImage::export_memory_handle).
+    pub fn create_exportable(
+        vk_init: &VkInit,
+        extent: Extent3D,
+        format: Format,
+        usage: ImageUsageFlags,
+        aspect_flags: ImageAspectFlags,
+        handle_type: ExternalMemoryHandleTypeFlags,
+    ) -> Result<VMAImage, Error> {
+        let support = &vk_init.physical_device_info.external_handles;
+        let properties =
+            vk_init.external_image_properties(format, usage, ImageTiling::OPTIMAL, handle_type)?;
+        let handle_supported = match ExternalHandle::is_fd_memory(handle_type) {
+            true => support.memory_fd,
+            false => support.memory_win32,
+        };
+        if !handle_supported
+            || !properties
+                .external_memory_features
+                .contains(ExternalMemoryFeatureFlags::EXPORTABLE)
+        {
+            return Err(Error::ExternalHandleTypeNotSupported(format!(
+                "{handle_type:?}"
+            )));
+        }
+
+        let mut external_info = ExternalMemoryImageCreateInfo::builder().handle_types(handle_type);
+        let image_info = Self::external_image_info(extent, format, usage, ImageTiling::OPTIMAL)
+            .push_next(&mut external_info)
+            .build();
+
+        unsafe {
+            let image = vk_init.device.create_image(&image_info, None)?;
+            let (info, requirements) =
+                Self::allocate_external(vk_init, image, None, handle_type, None, true)?;
+
+            let external = ExternalMemory {
+                info,
+                handle_type,
+                exportable: true,
+            };
+            Self::external_image(
+                vk_init,
+                image,
+                &image_info,
+                aspect_flags,
+                requirements,
+                external,
+            )
+        }
+    }
+
+    /// Exports a new handle to the memory of an image created by [create_exportable](VMAImage::create_exportable).
+    ///
+    /// Fd handles belong to the caller, import them elsewhere or close them.
+    /// Returns [ImageMemoryNotExportable](Error::ImageMemoryNotExportable) for all other images.
+    pub fn export_memory_handle(&self, vk_init: &VkInit) -> Result<ExternalHandle, Error> {
+        let Some(external) = self.external.filter(|external| external.exportable) else {
+            return Err(Error::ImageMemoryNotExportable);
+        };
+        let memory = external.info.memory;
+        unsafe {
+            if ExternalHandle::is_fd_memory(external.handle_type) {
+                let loader = ExternalMemoryFd::new(&vk_init.instance, &vk_init.device);
+                let get_info = MemoryGetFdInfoKHR::builder()
+                    .memory(memory)
+                    .handle_type(external.handle_type);
+                Ok(ExternalHandle::Fd(loader.get_memory_fd(&get_info)?))
+            } else {
+                let loader = ExternalMemoryWin32::new(&vk_init.instance, &vk_init.device);
+                let get_info = MemoryGetWin32HandleInfoKHR::builder()
+                    .memory(memory)
+                    .handle_type(external.handle_type);
+                Ok(ExternalHandle::Win32(
+                    loader.get_memory_win32_handle(&get_info)?,
+                ))
+            }
+        }
+    }
+
+    fn external_image_info<'a>(
+        extent: Extent3D,
+        format: Format,
+        usage: ImageUsageFlags,
+        tiling: ImageTiling,
+    ) -> ImageCreateInfoBuilder<'a> {
+        ImageCreateInfo::builder()
+            .image_type(ImageType::TYPE_2D)
+            .format(format)
+            .extent(extent)
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(SampleCountFlags::TYPE_1)
+            .tiling(tiling)
+            .usage(usage)
+            .sharing_mode(SharingMode::EXCLUSIVE)
+            .initial_layout(ImageLayout::UNDEFINED)
+    }
+
+    /// Allocates and binds dedicated memory for ```image```, destroying it on failure.
+    ///
+    /// Imports ```import``` if provided, otherwise the memory is exportable as ```handle_type```.
+    unsafe fn allocate_external(
+        vk_init: &VkInit,
+        image: Image,
+        allocation_size: Option<u64>,
+        handle_type: ExternalMemoryHandleTypeFlags,
+        import: Option<ExternalHandle>,
+        dedicated: bool,
+    ) -> Result<(AllocationInfo, MemoryRequirements), Error> {
+        let device = &vk_init.device;
+        let requirements = device.get_image_memory_requirements(image);
+        let mut memory_type_bits = requirements.memory_type_bits;
+        if let (Some(ExternalHandle::Fd(fd)), ExternalMemoryHandleTypeFlags::DMA_BUF_EXT) =
+            (import, handle_type)
+        {
+            let loader = ExternalMemoryFd::new(&vk_init.instance, device);
+            match loader.get_memory_fd_properties(handle_type, fd) {
+                Ok(fd_properties) => memory_type_bits &= fd_properties.memory_type_bits,
+                Err(e) => {
+                    device.destroy_image(image, None);
+                    return Err(e.into());
+                }
+            }
+        }
+
+        let memory_props = &vk_init.physical_device_info.memory_props;
+        let memory_types = &memory_props.memory_types[..memory_props.memory_type_count as usize];
+        let supported = |index: &usize| memory_type_bits & (1 << index) != 0;
+        let memory_type_index = (0..memory_types.len())
+            .filter(supported)
+            .find(|index| {
+                memory_types[*index]
+                    .property_flags
+                    .contains(MemoryPropertyFlags::DEVICE_LOCAL)
+            })
+            .or_else(|| (0..memory_types.len()).find(supported));
+        let Some(memory_type_index) = memory_type_index else {
+            device.destroy_image(image, None);
+            return Err(Error::ExternalHandleTypeNotSupported(format!(
+                "{handle_type:?} with memory type bits {memory_type_bits:#b}"
+            )));
+        };
+
+        let allocation_size = allocation_size.unwrap_or(requirements.size);
+        let mut fd_info = ImportMemoryFdInfoKHR::builder().handle_type(handle_type);
+        let mut win32_info = ImportMemoryWin32HandleInfoKHR::builder().handle_type(handle_type);
+        let mut export_info = ExportMemoryAllocateInfo::builder().handle_types(handle_type);
+        let mut dedicated_info = MemoryDedicatedAllocateInfo::builder().image(image);
+        let mut allocate_info = MemoryAllocateInfo::builder()
+            .allocation_size(allocation_size)
+            .memory_type_index(memory_type_index as u32);
+        match import {
+            Some(ExternalHandle::Fd(fd)) => {
+                fd_info = fd_info.fd(fd);
+                allocate_info = allocate_info.push_next(&mut fd_info);
+            }
+            Some(ExternalHandle::Win32(handle)) => {
+                win32_info = win32_info.handle(handle);
+                allocate_info = allocate_info.push_next(&mut win32_info);
+            }
+            None => allocate_info = allocate_info.push_next(&mut export_info),
+        }
+        if dedicated {
+            allocate_info = allocate_info.push_next(&mut dedicated_info);
+        }
+
+        let memory = match device.allocate_memory(&allocate_info, None) {
+            Ok(memory) => memory,
+            Err(e) => {
+                device.destroy_image(image, None);
+                return Err(e.into());
+            }
+        };
+        if let Err(e) = device.bind_image_memory(image, memory, 0) {
+            device.destroy_image(image, None);
+            device.free_memory(memory, None);
+            return Err(e.into());
+        }
+
+        let info = AllocationInfo {
+            memory,
+            offset: 0,
+            size: allocation_size,
+            alignment: requirements.alignment,
+            memory_type_bits: requirements.memory_type_bits,
+            memory_properties: memory_types[memory_type_index].property_flags,
+        };
+        Ok((info, requirements))
+    }
+
+    unsafe fn external_image(
+        vk_init: &VkInit,
+        image: Image,
+        image_info: &ImageCreateInfo,
+        aspect_flags: ImageAspectFlags,
+        requirements: MemoryRequirements,
+        external: ExternalMemory,
+    ) -> Result<VMAImage, Error> {
+        let device = &vk_init.device;
+        match Self::from_external(
+            device,
+            image,
+            image_info,
+            aspect_flags,
+            requirements,
+            external,
+        ) {
+            Ok(image) => Ok(image),
+            Err(e) => {
+                device.destroy_image(image, None);
+                device.free_memory(external.info.memory, None);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl VkInit {
+    /// External memory capabilities of a 2D image with ```format```, ```usage``` and ```tiling``` for ```handle_type```.
+    pub fn external_image_properties(
+        &self,
+        format: Format,
+        usage: ImageUsageFlags,
+        tiling: ImageTiling,
+        handle_type: ExternalMemoryHandleTypeFlags,
+    ) -> Result<ExternalMemoryProperties, Error> {
+        let mut external_info =
+            PhysicalDeviceExternalImageFormatInfo::builder().handle_type(handle_type);
+        let format_info = PhysicalDeviceImageFormatInfo2::builder()
+            .format(format)
+            .ty(ImageType::TYPE_2D)
+            .tiling(tiling)
+            .usage(usage)
+            .push_next(&mut external_info);
+        let mut external_properties = ExternalImageFormatProperties::default();
+        let mut properties = ImageFormatProperties2::builder().push_next(&mut external_properties);
+        unsafe {
+            self.instance.get_physical_device_image_format_properties2(
+                self.physical_device,
+                &format_info,
+                &mut properties,
+            )?
+        };
+        Ok(external_properties.external_memory_properties)
+    }
+
+    /// Import and export capabilities of semaphores for ```handle_type```.
+    pub fn external_semaphore_features(
+        &self,
+        handle_type: ExternalSemaphoreHandleTypeFlags,
+    ) -> ExternalSemaphoreFeatureFlags {
+        let info = PhysicalDeviceExternalSemaphoreInfo::builder().handle_type(handle_type);
+        let mut properties = ExternalSemaphoreProperties::default();
+        unsafe {
+            self.instance
+                .get_physical_device_external_semaphore_properties(
+                    self.physical_device,
+                    &info,
+                    &mut properties,
+                )
+        };
+        properties.external_semaphore_features
+    }
+
+    /// Creates a binary semaphore whose payload can be shared with [export_semaphore](VkInit::export_semaphore).
+    pub fn create_exportable_semaphore(
+        &self,
+        handle_type: ExternalSemaphoreHandleTypeFlags,
+    ) -> Result<Semaphore, Error> {
+        self.check_semaphore_handle_type(handle_type, ExternalSemaphoreFeatureFlags::EXPORTABLE)?;
+        let mut export_info = ExportSemaphoreCreateInfo::builder().handle_types(handle_type);
+        let create_info = SemaphoreCreateInfo::builder().push_next(&mut export_info);
+        let semaphore = unsafe { self.device.create_semaphore(&create_info, None)? };
+        self.set_debug_object_name_fmt(
+            semaphore.as_raw(),
+            ObjectType::SEMAPHORE,
+            format_args!("VKU_Exportable_Semaphore"),
+        )?;
+        Ok(semaphore)
+    }
+
+    /// Exports a handle to the payload of a semaphore created by [create_exportable_semaphore](VkInit::create_exportable_semaphore).
+    pub fn export_semaphore(
+        &self,
+        semaphore: &Semaphore,
+        handle_type: ExternalSemaphoreHandleTypeFlags,
+    ) -> Result<ExternalHandle, Error> {
+        self.check_semaphore_handle_type(handle_type, ExternalSemaphoreFeatureFlags::EXPORTABLE)?;
+        unsafe {
+            if ExternalHandle::is_fd_semaphore(handle_type) {
+                let loader = ExternalSemaphoreFd::new(&self.instance, &self.device);
+                let get_info = SemaphoreGetFdInfoKHR::builder()
+                    .semaphore(*semaphore)
+                    .handle_type(handle_type);
+                Ok(ExternalHandle::Fd(loader.get_semaphore_fd(&get_info)?))
+            } else {
+                let loader = ExternalSemaphoreWin32::new(&self.instance, &self.device);
+                let get_info = SemaphoreGetWin32HandleInfoKHR::builder()
+                    .semaphore(*semaphore)
+                    .handle_type(handle_type);
+                Ok(ExternalHandle::Win32(
+                    loader.get_semaphore_win32_handle(&get_info)?,
+                ))
+            }
+        }
+    }
+
+    /// Replaces the payload of ```semaphore``` with the one shared through ```handle```.
+    ///
+    /// A ```temporary``` import only lasts until the next wait on the semaphore.
+    pub fn import_semaphore(
+        &self,
+        semaphore: &Semaphore,
+        handle: ExternalHandle,
+        handle_type: ExternalSemaphoreHandleTypeFlags,
+        temporary: bool,
+    ) -> Result<(), Error> {
+        self.check_semaphore_handle_type(handle_type, ExternalSemaphoreFeatureFlags::IMPORTABLE)?;
+        let flags = match temporary {
+            true => SemaphoreImportFlags::TEMPORARY,
+            false => SemaphoreImportFlags::empty(),
+        };
+        unsafe {
+            match handle {
+                ExternalHandle::Fd(fd) if ExternalHandle::is_fd_semaphore(handle_type) => {
+                    let loader = ExternalSemaphoreFd::new(&self.instance, &self.device);
+                    let import_info = ImportSemaphoreFdInfoKHR::builder()
+                        .semaphore(*semaphore)
+                        .flags(flags)
+                        .handle_type(handle_type)
+                        .fd(fd);
+                    loader.import_semaphore_fd(&import_info)?;
+                }
+                ExternalHandle::Win32(win32_handle)
+                    if !ExternalHandle::is_fd_semaphore(handle_type) =>
+                {
+                    let loader = ExternalSemaphoreWin32::new(&self.instance, &self.device);
+                    let import_info = ImportSemaphoreWin32HandleInfoKHR::builder()
+                        .semaphore(*semaphore)
+                        .flags(flags)
+                        .handle_type(handle_type)
+                        .handle(win32_handle);
+                    loader.import_semaphore_win32_handle(&import_info)?;
+                }
+                _ => {
+                    return Err(Error::ExternalHandleTypeNotSupported(format!(
+                        "{handle_type:?}"
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_semaphore_handle_type(
+        &self,
+        handle_type: ExternalSemaphoreHandleTypeFlags,
+        feature: ExternalSemaphoreFeatureFlags,
+    ) -> Result<(), Error> {
+        let support = &self.physical_device_info.external_handles;
+        let extension_supported = match ExternalHandle::is_fd_semaphore(handle_type) {
+            true => support.semaphore_fd,
+            false => support.semaphore_win32,
+        };
+        if !extension_supported
+            || !self
+                .external_semaphore_features(handle_type)
+                .contains(feature)
+        {
+            return Err(Error::ExternalHandleTypeNotSupported(format!(
+                "{handle_type:?}"
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,352 @@
+use std::io::Cursor;
+#[cfg(feature = "image")]
+use std::path::Path;
+
+use ash::util::read_spv;
+
+use crate::{
+    image_atlas::texel_size, image_layout_transitions::get_image_layout_transition_barrier2,
+    imports::*, pipeline_builder::BlendMode, CmdType, FragmentSource, FullscreenPass, SamplerInfo,
+    VMABuffer, VMAImage, VkInit,
+};
+
+const CAPTURE_FRAG_SPV: &[u8] = include_bytes!("shaders/capture.frag.spv");
+
+/// Color handling of a [capture](VkInit::capture_frame).
+///
+/// The conversion runs on the GPU into a temporary ```R8G8B8A8_UNORM``` image, values outside of ```[0, 1]``` are clamped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CaptureOptions {
+    /// Keeps the stored encoding, e.g. the sRGB encoded bytes of an ```SRGB``` swapchain.
+    AsIs,
+    /// Encodes linear sources, e.g. ```SRGB``` and float swapchains, with the sRGB transfer function.
+    /// ```UNORM``` sources are assumed to hold encoded values already and are kept.
+    ConvertToSrgb8,
+    /// Scales linear HDR values by ```exposure``` and maps them to SDR with the Reinhard operator before encoding to sRGB.
+    TonemapHdrToSdr { exposure: f32 },
+}
+
+/// Tightly packed rgba8 pixels of a [capture](VkInit::capture_frame), rows from top to bottom.
+#[derive(Clone, Debug)]
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    pub rgba8: Vec<u8>,
+}
+
+impl CapturedFrame {
+    #[cfg(feature = "image")]
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        image::save_buffer_with_format(
+            path,
+            &self.rgba8,
+            self.width,
+            self.height,
+            image::ColorType::Rgba8,
+            image::ImageFormat::Png,
+        )?;
+        Ok(())
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CapturePush {
+    exposure: f32,
+    tonemap: u32,
+    encode_srgb: u32,
+}
+
+impl CapturePush {
+    fn new(options: CaptureOptions, format: Format) -> Self {
+        let srgb_format = is_srgb_format(format);
+        let float_format = is_float_format(format);
+        match options {
+            CaptureOptions::AsIs => Self {
+                exposure: 1.0,
+                tonemap: 0,
+                encode_srgb: srgb_format as u32,
+            },
+            CaptureOptions::ConvertToSrgb8 => Self {
+                exposure: 1.0,
+                tonemap: 0,
+                encode_srgb: (srgb_format || float_format) as u32,
+            },
+            CaptureOptions::TonemapHdrToSdr { exposure } => Self {
+                exposure,
+                tonemap: 1,
+                encode_srgb: 1,
+            },
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self as *const Self as *const u8, size_of::<Self>()) }
+    }
+}
+
+fn is_srgb_format(format: Format) -> bool {
+    matches!(
+        format,
+        Format::R8G8B8A8_SRGB
+            | Format::B8G8R8A8_SRGB
+            | Format::A8B8G8R8_SRGB_PACK32
+            | Format::R8G8B8_SRGB
+            | Format::B8G8R8_SRGB
+    )
+}
+
+fn is_float_format(format: Format) -> bool {
+    matches!(
+        format,
+        Format::R16G16B16A16_SFLOAT
+            | Format::R32G32B32A32_SFLOAT
+            | Format::B10G11R11_UFLOAT_PACK32
+            | Format::E5B9G9R9_UFLOAT_PACK32
+    )
+}
+
+impl VkInit {
+    /// Reads back swapchain image ```index``` as rgba8, converted as selected by ```options```.
+    ///
+    /// Call after submitting the commands of the frame and before presenting it, the image has to be in ```ImageLayout::PRESENT_SRC_KHR```
+    /// and is returned to it. Waits for the device to be idle.
+    /// Returns [CaptureNotSupported](Error::CaptureNotSupported) if the swapchain images can't be copied from,
+    /// see [image_usage](crate::SurfaceInfo::image_usage).
+    pub fn capture_frame(
+        &mut self,
+        index: usize,
+        options: CaptureOptions,
+    ) -> Result<CapturedFrame, Error> {
+        let Some(head) = self.head.as_ref() else {
+            return Err(Error::HeadCallOnHeadlessInstance);
+        };
+        if !head
+            .surface_info
+            .image_usage
+            .contains(ImageUsageFlags::TRANSFER_SRC)
+        {
+            return Err(Error::CaptureNotSupported);
+        }
+        let swapchain_image = head.swapchain_images[index];
+        let format = head.surface_info.color_format.format;
+        let extent = Extent3D {
+            width: head.surface_info.current_extent.width,
+            height: head.surface_info.current_extent.height,
+            depth: 1,
+        };
+        let texel_size = texel_size(format).ok_or(Error::UnsupportedCaptureFormat(format))?;
+
+        self.wait_device_idle()?;
+        let mut frame =
+            self.create_empty_image(extent, format, texel_size, ImageAspectFlags::COLOR)?;
+        let result = self
+            .copy_from_swapchain(swapchain_image, &mut frame)
+            .and_then(|_| self.capture_image(&frame, options));
+        frame.destroy(&self.device, &mut self.allocator.lock())?;
+        result
+    }
+
+    /// Same as [capture_frame](VkInit::capture_frame) for any sampled ```image``` in ```ImageLayout::SHADER_READ_ONLY_OPTIMAL```.
+    ///
+    /// Blocks until the pixels were read back.
+    pub fn capture_image(
+        &mut self,
+        image: &VMAImage,
+        options: CaptureOptions,
+    ) -> Result<CapturedFrame, Error> {
+        let spirv = read_spv(&mut Cursor::new(CAPTURE_FRAG_SPV))?;
+        let mut pass = FullscreenPass::new::<CapturePush>(
+            self,
+            FragmentSource::Spirv(&spirv),
+            Format::R8G8B8A8_UNORM,
+            BlendMode::Opaque,
+            "Capture",
+        )?;
+        let sampler = self.get_or_create_sampler(SamplerInfo::nearest())?;
+        let push = CapturePush::new(options, image.format);
+
+        let result = self.render_capture(&pass, sampler, image, &push);
+
+        pass.destroy(self)?;
+        result
+    }
+
+    fn copy_from_swapchain(
+        &self,
+        swapchain_image: Image,
+        frame: &mut VMAImage,
+    ) -> Result<(), Error> {
+        let to_transfer_src = get_image_layout_transition_barrier2(
+            &swapchain_image,
+            ImageLayout::PRESENT_SRC_KHR,
+            ImageLayout::TRANSFER_SRC_OPTIMAL,
+            ImageAspectFlags::COLOR,
+            1,
+            None,
+            None,
+        )?;
+        let to_present = get_image_layout_transition_barrier2(
+            &swapchain_image,
+            ImageLayout::TRANSFER_SRC_OPTIMAL,
+            ImageLayout::PRESENT_SRC_KHR,
+            ImageAspectFlags::COLOR,
+            1,
+            None,
+            None,
+        )?;
+        let to_transfer_dst = frame.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_DST_OPTIMAL,
+            None,
+            None,
+        )?;
+        let to_sampled = frame.get_image_layout_transition_barrier2(
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            None,
+            None,
+        )?;
+
+        let subresource = ImageSubresourceLayers::builder()
+            .aspect_mask(ImageAspectFlags::COLOR)
+            .layer_count(1)
+            .build();
+        let region = ImageCopy::builder()
+            .src_subresource(subresource)
+            .dst_subresource(subresource)
+            .extent(frame.extent)
+            .build();
+
+        self.immediate_submit(CmdType::Any, |cmd_buffer| {
+            self.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer_src, to_transfer_dst], &[]);
+            unsafe {
+                self.device.cmd_copy_image(
+                    *cmd_buffer,
+                    swapchain_image,
+                    ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    frame.image,
+                    ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[region],
+                )
+            };
+            self.cmd_pipeline_barrier2(cmd_buffer, &[to_present, to_sampled], &[]);
+        })
+    }
+
+    fn render_capture(
+        &mut self,
+        pass: &FullscreenPass,
+        sampler: Sampler,
+        input: &VMAImage,
+        push: &CapturePush,
+    ) -> Result<CapturedFrame, Error> {
+        let extent = input.extent;
+        let byte_size = (extent.width * extent.height) as usize * 4;
+        let mut target = VMAImage::create_render_image(
+            &self.device,
+            &mut self.allocator.lock(),
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+        )?;
+        let mut slot = match self.create_readback_slots(byte_size, 1) {
+            Ok(mut slots) => slots.remove(0),
+            Err(e) => {
+                target.destroy(&self.device, &mut self.allocator.lock())?;
+                return Err(e);
+            }
+        };
+
+        let result = self.record_capture(pass, sampler, input, push, &mut target, &slot.buffer);
+        // The readback fence stays unsignaled, the submission waited on its own fence.
+        let result = result.and_then(|_| {
+            let rgba8 = slot.buffer.get_data::<u8>(&self.device, 0, byte_size)?;
+            Ok(CapturedFrame {
+                width: extent.width,
+                height: extent.height,
+                rgba8,
+            })
+        });
+
+        slot.destroy(&self.device, &mut self.allocator.lock())?;
+        target.destroy(&self.device, &mut self.allocator.lock())?;
+        result
+    }
+
+    fn record_capture(
+        &self,
+        pass: &FullscreenPass,
+        sampler: Sampler,
+        input: &VMAImage,
+        push: &CapturePush,
+        target: &mut VMAImage,
+        readback: &VMABuffer,
+    ) -> Result<(), Error> {
+        let extent = input.extent;
+        let to_attachment = target.get_image_layout_transition_barrier2(
+            ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            None,
+            None,
+        )?;
+        let to_transfer_src = target.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_SRC_OPTIMAL,
+            None,
+            None,
+        )?;
+        let color_attachments = [RenderingAttachmentInfo::builder()
+            .image_view(target.image_view)
+            .image_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .load_op(AttachmentLoadOp::DONT_CARE)
+            .store_op(AttachmentStoreOp::STORE)
+            .build()];
+        let render_area = Extent2D {
+            width: extent.width,
+            height: extent.height,
+        };
+        let rendering_info = RenderingInfo::builder()
+            .render_area(Rect2D {
+                offset: Offset2D { x: 0, y: 0 },
+                extent: render_area,
+            })
+            .layer_count(1)
+            .color_attachments(&color_attachments);
+        let region = BufferImageCopy::builder()
+            .image_subresource(
+                ImageSubresourceLayers::builder()
+                    .aspect_mask(ImageAspectFlags::COLOR)
+                    .layer_count(1)
+                    .build(),
+            )
+            .image_extent(extent)
+            .build();
+
+        let mut recorded = Ok(());
+        self.immediate_submit(CmdType::Any, |cmd_buffer| {
+            self.cmd_pipeline_barrier2(cmd_buffer, &[to_attachment], &[]);
+            unsafe {
+                self.device
+                    .cmd_begin_rendering(*cmd_buffer, &rendering_info)
+            };
+            recorded = pass.record(
+                self,
+                cmd_buffer,
+                input,
+                render_area,
+                sampler,
+                push.as_bytes(),
+            );
+            self.end_rendering(cmd_buffer);
+
+            self.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer_src], &[]);
+            unsafe {
+                self.device.cmd_copy_image_to_buffer(
+                    *cmd_buffer,
+                    target.image,
+                    ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    readback.buffer,
+                    &[region],
+                )
+            };
+        })?;
+        recorded
+    }
+}
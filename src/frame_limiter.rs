@@ -0,0 +1,189 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::Error;
+
+const FRAME_TIME_SAMPLES: usize = 64;
+
+/// Frame rate a [FrameLimiter] paces to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FrameTarget {
+    /// Must be positive with a representable frame interval, see [InvalidFrameRate](Error::InvalidFrameRate).
+    Fps(f32),
+    /// Paces to the interval set with [set_refresh_interval](FrameLimiter::set_refresh_interval), unlimited until then.
+    RefreshRate,
+    Unlimited,
+}
+
+/// Time source and sleep strategy of a [FrameLimiter].
+pub trait FrameClock {
+    fn now(&self) -> Instant;
+    /// Returns at or shortly after ```deadline```.
+    fn sleep_until(&mut self, deadline: Instant);
+}
+
+/// Sleeps until shortly before the deadline and spins for the remainder.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// Remaining time that is spun instead of slept, covering the OS scheduler granularity.
+    const SPIN_THRESHOLD: Duration = if cfg!(windows) {
+        Duration::from_millis(2)
+    } else {
+        Duration::from_micros(500)
+    };
+}
+
+impl FrameClock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep_until(&mut self, deadline: Instant) {
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                return;
+            }
+            let remaining = deadline - now;
+            if remaining > Self::SPIN_THRESHOLD {
+                std::thread::sleep(remaining - Self::SPIN_THRESHOLD);
+            } else {
+                std::hint::spin_loop();
+            }
+        }
+    }
+}
+
+/// Limits the frame rate by sleeping once per frame, e.g. with ```PresentModeKHR::IMMEDIATE``` or ```PresentModeKHR::MAILBOX```.
+/// ```
+/// # use vku::*;
+/// let mut limiter = FrameLimiter::new(FrameTarget::Fps(144.0))?;
+/// for _ in 0..3 {
+///     // record, submit and present
+///     limiter.wait();
+/// }
+/// let frame_time = limiter.frame_time();
+/// # Ok::<(), vku::Error>(())
+/// ```
+pub struct FrameLimiter<C: FrameClock = SystemClock> {
+    clock: C,
+    target: FrameTarget,
+    refresh_interval: Option<Duration>,
+    last_frame: Option<Instant>,
+    next_deadline: Option<Instant>,
+    samples: VecDeque<Duration>,
+    samples_sum: Duration,
+}
+
+impl FrameLimiter {
+    pub fn new(target: FrameTarget) -> Result<Self, Error> {
+        Self::with_clock(target, SystemClock)
+    }
+}
+
+impl<C: FrameClock> FrameLimiter<C> {
+    /// Returns [InvalidFrameRate](Error::InvalidFrameRate) if ```target``` is an invalid [FrameTarget::Fps].
+    pub fn with_clock(target: FrameTarget, clock: C) -> Result<Self, Error> {
+        validate(target)?;
+        Ok(Self {
+            clock,
+            target,
+            refresh_interval: None,
+            last_frame: None,
+            next_deadline: None,
+            samples: VecDeque::with_capacity(FRAME_TIME_SAMPLES),
+            samples_sum: Duration::ZERO,
+        })
+    }
+
+    pub fn target(&self) -> FrameTarget {
+        self.target
+    }
+
+    /// Restarts pacing from the next frame.
+    ///
+    /// Returns [InvalidFrameRate](Error::InvalidFrameRate) and keeps the previous target if ```target``` is an invalid [FrameTarget::Fps].
+    pub fn set_target(&mut self, target: FrameTarget) -> Result<(), Error> {
+        validate(target)?;
+        self.target = target;
+        self.next_deadline = None;
+        Ok(())
+    }
+
+    /// Display refresh interval used by [FrameTarget::RefreshRate], e.g. from the monitor's refresh rate.
+    pub fn set_refresh_interval(&mut self, interval: Option<Duration>) {
+        self.refresh_interval = interval;
+        self.next_deadline = None;
+    }
+
+    /// Interval between frames the limiter paces to, ```None``` if unlimited.
+    pub fn frame_interval(&self) -> Option<Duration> {
+        match self.target {
+            FrameTarget::Fps(fps) => fps_interval(fps),
+            FrameTarget::RefreshRate => self.refresh_interval,
+            _ => None,
+        }
+    }
+
+    /// Call once per frame. Sleeps until the next frame is due and records the frame time.
+    ///
+    /// Frames that are already late restart pacing instead of being caught up.
+    pub fn wait(&mut self) {
+        if let Some(interval) = self.frame_interval() {
+            let now = self.clock.now();
+            let deadline = self.next_deadline.unwrap_or(now);
+            if deadline > now {
+                self.clock.sleep_until(deadline);
+                self.next_deadline = Some(deadline + interval);
+            } else {
+                self.next_deadline = Some(now + interval);
+            }
+        }
+
+        let now = self.clock.now();
+        if let Some(last_frame) = self.last_frame {
+            self.push_sample(now.saturating_duration_since(last_frame));
+        }
+        self.last_frame = Some(now);
+    }
+
+    /// Rolling average of the last frame times, zero before the second [wait](FrameLimiter::wait).
+    pub fn frame_time(&self) -> Duration {
+        match self.samples.len() {
+            0 => Duration::ZERO,
+            len => self.samples_sum / len as u32,
+        }
+    }
+
+    /// Time between the last two calls to [wait](FrameLimiter::wait).
+    pub fn last_frame_time(&self) -> Duration {
+        self.samples.back().copied().unwrap_or_default()
+    }
+
+    fn push_sample(&mut self, sample: Duration) {
+        if self.samples.len() == FRAME_TIME_SAMPLES {
+            if let Some(oldest) = self.samples.pop_front() {
+                self.samples_sum -= oldest;
+            }
+        }
+        self.samples.push_back(sample);
+        self.samples_sum += sample;
+    }
+}
+
+/// ```None``` for frame rates that are not positive or too small for a [Duration].
+fn fps_interval(fps: f32) -> Option<Duration> {
+    match fps > 0.0 {
+        true => Duration::try_from_secs_f32(1.0 / fps).ok(),
+        false => None,
+    }
+}
+
+fn validate(target: FrameTarget) -> Result<(), Error> {
+    match target {
+        FrameTarget::Fps(fps) if fps_interval(fps).is_none() => Err(Error::InvalidFrameRate(fps)),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,210 @@
+use crate::{imports::*, init::semaphore_submit_info, AcquireResult, CmdType, VkInit};
+
+/// Synchronization objects of [begin_frame](VkInit::begin_frame) and [end_frame](VkInit::end_frame), created on first use.
+pub(crate) struct FrameLoop {
+    cmd_pool: CommandPool,
+    /// Per frame in flight.
+    cmd_buffers: Vec<CommandBuffer>,
+    /// Per frame in flight.
+    image_acquired: Vec<Semaphore>,
+    /// Per frame in flight.
+    in_flight: Vec<Fence>,
+    /// Per swapchain image, the presentation engine holds them until the image is acquired again.
+    render_complete: Vec<Semaphore>,
+    frame: usize,
+    /// Generation of the [FrameScheme](crate::FrameScheme) the objects were created under.
+    generation: u64,
+    /// Frame that was begun but not ended yet.
+    in_progress: Option<usize>,
+}
+
+/// Frame begun by [begin_frame](VkInit::begin_frame), record into ```cmd_buffer``` and hand it to [end_frame](VkInit::end_frame).
+#[derive(Debug)]
+#[must_use]
+pub struct FrameContext {
+    /// Index of the frame in flight, wraps around [frames_in_flight](VkInit::frames_in_flight).
+    pub frame: usize,
+    /// Index of the acquired swapchain image.
+    pub image_index: usize,
+    pub image: Image,
+    pub view: ImageView,
+    /// Already begun, [end_frame](VkInit::end_frame) ends and submits it.
+    pub cmd_buffer: CommandBuffer,
+    /// The swapchain can still be presented but should be [recreated](VkInit::on_resize).
+    pub suboptimal: bool,
+}
+
+impl VkInit {
+    /// Waits for the fence of the next frame in flight, acquires a swapchain image and begins the frame's command buffer.
+    ///
+    /// Returns [SwapchainOutOfDate](Error::SwapchainOutOfDate) if the swapchain has to be [recreated](VkInit::on_resize),
+    /// nothing was acquired in that case and the frame can be begun again afterwards.
+    /// The recorded commands must leave ```image``` in ```PRESENT_SRC_KHR```.
+    /// The fences, semaphores and command buffers are owned by [VkInit]. For manual control use
+    /// [acquire_next_swapchain_image](VkInit::acquire_next_swapchain_image), [submit_cmd_buffers2](VkInit::submit_cmd_buffers2)
+    /// and [present](VkInit::present) with your own objects instead.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    ///
+    /// let frame = init.begin_frame()?;
+    /// let to_present = ImageMemoryBarrier2::builder()
+    ///     .image(frame.image)
+    ///     .old_layout(ImageLayout::UNDEFINED)
+    ///     .new_layout(ImageLayout::PRESENT_SRC_KHR)
+    ///     .src_stage_mask(PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+    ///     .subresource_range(ImageSubresourceRange {
+    ///         aspect_mask: ImageAspectFlags::COLOR,
+    ///         level_count: 1,
+    ///         layer_count: 1,
+    ///         ..Default::default()
+    ///     })
+    ///     .build();
+    /// init.cmd_pipeline_barrier2(&frame.cmd_buffer, &[to_present], &[]);
+    /// init.end_frame(frame)?;
+    /// # init.destroy()?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn begin_frame(&mut self) -> Result<FrameContext, Error> {
+        if self.head.is_none() {
+            return Err(Error::HeadCallOnHeadlessInstance);
+        }
+        match &self.frame_loop {
+            None => self.frame_loop = Some(self.create_frame_loop()?),
+            Some(frame_loop) if frame_loop.in_progress.is_some() => {
+                return Err(Error::FrameAlreadyInProgress);
+            }
+            // E.g. after set_frames_in_flight.
+            Some(frame_loop) if frame_loop.generation != self.frame_scheme.generation => {
+                self.wait_device_idle()?;
+                self.rebuild_frame_loop()?;
+            }
+            Some(_) => {}
+        }
+        let frame_loop = self.frame_loop.as_ref().unwrap();
+
+        let frame = frame_loop.frame;
+        let fence = frame_loop.in_flight[frame];
+        let cmd_buffer = frame_loop.cmd_buffers[frame];
+        self.wait_for_fences_watched(&[fence], "begin_frame")?;
+        let acquired = self.acquire_next_swapchain_image(frame_loop.image_acquired[frame], None)?;
+        let AcquireResult::Ready {
+            index,
+            image,
+            view,
+            suboptimal,
+        } = acquired
+        else {
+            return Err(Error::SwapchainOutOfDate);
+        };
+
+        // Only reset once the frame is going to be submitted, an out of date swapchain keeps the fence signaled.
+        self.wait_on_fence_and_reset(Some(&fence), &[&cmd_buffer])?;
+        self.begin_cmd_buffer(&cmd_buffer)?;
+        self.frame_loop.as_mut().unwrap().in_progress = Some(frame);
+
+        Ok(FrameContext {
+            frame,
+            image_index: index,
+            image,
+            view,
+            cmd_buffer,
+            suboptimal,
+        })
+    }
+
+    /// Ends and submits the command buffer of ```frame``` on the unified queue and presents its image.
+    ///
+    /// The submit waits for the acquired image at ```COLOR_ATTACHMENT_OUTPUT```.
+    /// Returns [SwapchainOutOfDate](Error::SwapchainOutOfDate) if the swapchain has to be [recreated](VkInit::on_resize),
+    /// the frame was submitted anyway and the next one can be begun once it is.
+    pub fn end_frame(&mut self, frame: FrameContext) -> Result<(), Error> {
+        let Some(frame_loop) = self.frame_loop.as_mut() else {
+            return Err(Error::FrameNotInProgress(frame.frame));
+        };
+        if frame_loop.in_progress != Some(frame.frame) {
+            return Err(Error::FrameNotInProgress(frame.frame));
+        }
+        frame_loop.in_progress = None;
+        frame_loop.frame = (frame.frame + 1) % frame_loop.in_flight.len();
+
+        let frame_loop = self.frame_loop.as_ref().unwrap();
+        let render_complete = frame_loop.render_complete[frame.image_index];
+        self.end_and_submit_cmd_buffer2(
+            &frame.cmd_buffer,
+            CmdType::Any,
+            Some(&frame_loop.in_flight[frame.frame]),
+            &[semaphore_submit_info(
+                frame_loop.image_acquired[frame.frame],
+                PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+            )],
+            &[semaphore_submit_info(
+                render_complete,
+                PipelineStageFlags2::ALL_COMMANDS,
+            )],
+        )?;
+        self.present(&render_complete, frame.image_index)
+    }
+
+    /// Creates the objects of [begin_frame](VkInit::begin_frame) for the current [FrameScheme](crate::FrameScheme).
+    fn create_frame_loop(&self) -> Result<FrameLoop, Error> {
+        let frames_in_flight = self.frame_scheme.frames_in_flight.max(1);
+        let cmd_pool = self.create_cmd_pool(CmdType::Any)?;
+        Ok(FrameLoop {
+            cmd_pool,
+            cmd_buffers: self.create_command_buffers_named(
+                &cmd_pool,
+                frames_in_flight as u32,
+                "VKU_Frame_Loop",
+            )?,
+            image_acquired: self.create_semaphores_named(frames_in_flight, "VKU_Image_Acquired")?,
+            in_flight: self.create_fences_named(frames_in_flight, "VKU_Frame_Loop")?,
+            render_complete: self.create_semaphores_named(
+                self.frame_scheme.swapchain_images,
+                "VKU_Render_Complete",
+            )?,
+            frame: 0,
+            generation: self.frame_scheme.generation,
+            in_progress: None,
+        })
+    }
+
+    /// Recreates the objects of [begin_frame](VkInit::begin_frame) if they were created before, the device must be idle.
+    ///
+    /// Called after the swapchain was recreated, a frame in progress is dropped.
+    pub(crate) fn rebuild_frame_loop(&mut self) -> Result<(), Error> {
+        if self.frame_loop.is_some() {
+            self.destroy_frame_loop();
+            self.frame_loop = Some(self.create_frame_loop()?);
+        }
+        Ok(())
+    }
+
+    /// Destroys the objects of [begin_frame](VkInit::begin_frame), the device must be idle.
+    pub(crate) fn destroy_frame_loop(&mut self) {
+        let Some(frame_loop) = self.frame_loop.take() else {
+            return;
+        };
+        unsafe {
+            for semaphore in frame_loop
+                .image_acquired
+                .iter()
+                .chain(&frame_loop.render_complete)
+            {
+                self.device.destroy_semaphore(*semaphore, None);
+            }
+            for fence in &frame_loop.in_flight {
+                self.device.destroy_fence(*fence, None);
+            }
+            // Destroying the pool frees the command buffers.
+            self.device.destroy_command_pool(frame_loop.cmd_pool, None);
+        }
+    }
+}
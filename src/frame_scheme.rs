@@ -0,0 +1,200 @@
+use std::cell::Cell;
+
+use crate::{imports::*, VkInit};
+
+/// Frame counts per-frame resources are sized by, owned by [VkInit].
+///
+/// Set when the head is created and updated on [on_resize](VkInit::on_resize),
+/// [change_present_mode](VkInit::change_present_mode) and [set_frames_in_flight](VkInit::set_frames_in_flight).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameScheme {
+    /// Frames recorded ahead of the GPU, sizes fences, command buffers and per-frame buffers.
+    pub frames_in_flight: usize,
+    /// Sizes per-image resources, e.g. render finished semaphores. Zero for headless instances.
+    pub swapchain_images: usize,
+    /// Incremented whenever one of the counts changes.
+    pub generation: u64,
+}
+
+/// Count a [PerFrame] is sized by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FrameCount {
+    FramesInFlight,
+    SwapchainImages,
+}
+
+/// Per-frame resources sized by the [FrameScheme] active at creation.
+///
+/// Access warns once if the scheme changed the count since, the resources then need to be recreated.
+/// ```
+/// # extern crate winit;
+/// # use vku::*;
+/// # use ash::vk::*;
+/// # if VkInit::load_entry().is_err() { return Ok(()); }
+/// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+/// # let size = [800_u32, 600_u32];
+/// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+/// # let create_info = VkInitCreateInfo::default();
+/// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+/// let fences = init.create_per_frame(|init, _| init.create_fence())?;
+/// let render_finished = init.create_per_swapchain_image(|init, _| init.create_semaphore())?;
+///
+/// let frame = 0;
+/// init.wait_on_fence_and_reset(Some(fences.get(&init, frame)), &[])?;
+/// # Ok::<(), vku::Error>(())
+/// ```
+pub struct PerFrame<T> {
+    items: Vec<T>,
+    count: FrameCount,
+    generation: u64,
+    warned_generation: Cell<u64>,
+}
+
+impl<T> PerFrame<T> {
+    /// Resource of ```frame```, wrapping around the number of resources. Panics if empty.
+    pub fn get(&self, vk_init: &VkInit, frame: usize) -> &T {
+        self.check_scheme(vk_init);
+        &self.items[frame % self.items.len()]
+    }
+
+    /// Same as [get](PerFrame::get), but mutable.
+    pub fn get_mut(&mut self, vk_init: &VkInit, frame: usize) -> &mut T {
+        self.check_scheme(vk_init);
+        let len = self.items.len();
+        &mut self.items[frame % len]
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    /// Generation of the [FrameScheme] the resources were created under.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Whether the resource count still matches the active [FrameScheme].
+    pub fn is_current(&self, vk_init: &VkInit) -> bool {
+        self.generation == vk_init.frame_scheme.generation
+            || self.items.len() == self.count.of(&vk_init.frame_scheme)
+    }
+
+    /// Returns the resources, e.g. to destroy them.
+    pub fn into_inner(self) -> Vec<T> {
+        self.items
+    }
+
+    fn check_scheme(&self, vk_init: &VkInit) {
+        let scheme = vk_init.frame_scheme;
+        if self.is_current(vk_init) || self.warned_generation.get() == scheme.generation {
+            return;
+        }
+        self.warned_generation.set(scheme.generation);
+        warn!(
+            "{} per-frame resources were created under frame scheme generation {}, but generation {} expects {} ({scheme:?})",
+            self.items.len(),
+            self.generation,
+            scheme.generation,
+            self.count.of(&scheme)
+        );
+    }
+}
+
+impl FrameCount {
+    fn of(self, scheme: &FrameScheme) -> usize {
+        match self {
+            FrameCount::FramesInFlight => scheme.frames_in_flight,
+            FrameCount::SwapchainImages => scheme.swapchain_images,
+        }
+    }
+}
+
+impl VkInit {
+    pub fn frame_scheme(&self) -> FrameScheme {
+        self.frame_scheme
+    }
+
+    /// Shortcut - see [FrameScheme](FrameScheme::frames_in_flight).
+    pub fn frames_in_flight(&self) -> usize {
+        self.frame_scheme.frames_in_flight
+    }
+
+    /// Shortcut - see [FrameScheme](FrameScheme::swapchain_images).
+    pub fn swapchain_image_count(&self) -> usize {
+        self.frame_scheme.swapchain_images
+    }
+
+    /// Changes the frames in flight of the [FrameScheme], existing [PerFrame] resources warn on access afterwards.
+    pub fn set_frames_in_flight(&mut self, frames_in_flight: usize) {
+        self.create_info.frames_in_flight = frames_in_flight;
+        self.update_frame_scheme();
+    }
+
+    /// Creates one resource per frame in flight.
+    pub fn create_per_frame<T>(
+        &mut self,
+        create: impl FnMut(&mut VkInit, usize) -> Result<T, Error>,
+    ) -> Result<PerFrame<T>, Error> {
+        self.create_frame_resources(FrameCount::FramesInFlight, create)
+    }
+
+    /// Creates one resource per swapchain image, empty for headless instances.
+    pub fn create_per_swapchain_image<T>(
+        &mut self,
+        create: impl FnMut(&mut VkInit, usize) -> Result<T, Error>,
+    ) -> Result<PerFrame<T>, Error> {
+        self.create_frame_resources(FrameCount::SwapchainImages, create)
+    }
+
+    fn create_frame_resources<T>(
+        &mut self,
+        count: FrameCount,
+        mut create: impl FnMut(&mut VkInit, usize) -> Result<T, Error>,
+    ) -> Result<PerFrame<T>, Error> {
+        let scheme = self.frame_scheme;
+        let items = (0..count.of(&scheme))
+            .map(|i| create(self, i))
+            .collect::<Result<_, _>>()?;
+        Ok(PerFrame {
+            items,
+            count,
+            generation: scheme.generation,
+            warned_generation: Cell::new(scheme.generation),
+        })
+    }
+
+    /// Reads the counts from the create info and head, bumping the generation if they changed.
+    pub(crate) fn update_frame_scheme(&mut self) {
+        let frames_in_flight = self.create_info.frames_in_flight;
+        let swapchain_images = self
+            .head
+            .as_ref()
+            .map(|head| head.swapchain_images.len())
+            .unwrap_or(0);
+        let scheme = &mut self.frame_scheme;
+        if scheme.frames_in_flight == frames_in_flight
+            && scheme.swapchain_images == swapchain_images
+        {
+            return;
+        }
+
+        scheme.frames_in_flight = frames_in_flight;
+        scheme.swapchain_images = swapchain_images;
+        scheme.generation += 1;
+        info!("Frame scheme changed to {scheme:?}");
+        if frames_in_flight == 0 {
+            warn!("Frame scheme has zero frames in flight");
+        }
+        if swapchain_images != 0 && frames_in_flight > swapchain_images {
+            warn!("{frames_in_flight} frames in flight exceed the {swapchain_images} swapchain images, frames will block on acquire");
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::sync::Mutex;
+
+use ash::util::read_spv;
+
+use crate::{
+    descriptor_pool::DescriptorPools,
+    imports::*,
+    pipeline_builder::{BlendMode, DepthInfo, StencilInfo, VKUPipeline},
+    DescriptorPoolPolicy, VMAImage, VkInit,
+};
+
+const FULLSCREEN_VERT_SPV: &[u8] = include_bytes!("shaders/fullscreen.vert.spv");
+
+/// Fragment shader of a [FullscreenPass].
+pub enum FragmentSource<'a> {
+    Spirv(&'a [u32]),
+    #[cfg(feature = "shader")]
+    Glsl(String),
+}
+
+/// Fullscreen triangle pipeline for post-processing passes, e.g. tonemapping, FXAA or blitting.
+///
+/// The vertex shader generates an oversized triangle from ```gl_VertexIndex``` and passes ```layout(location = 0) out vec2 uv```.
+/// The fragment shader samples its input from ```layout(set = 0, binding = 0) uniform sampler2D```.
+pub struct FullscreenPass {
+    pub pipeline: VKUPipeline,
+    push_size: usize,
+    base_name: String,
+    inputs: Mutex<FullscreenInputs>,
+}
+
+/// Descriptor sets per input image, view, layout and sampler, allocated on first use.
+struct FullscreenInputs {
+    pools: DescriptorPools,
+    sets: HashMap<(Image, ImageView, ImageLayout, Sampler), DescriptorSet>,
+}
+
+impl FullscreenPass {
+    /// Builds the pipeline for dynamic rendering into a single ```color_format``` attachment.
+    ///
+    /// Push constants of type ```Push``` are visible to the vertex and fragment stage, use ```()``` for none.
+    pub fn new<Push>(
+        vk_init: &VkInit,
+        fragment: FragmentSource,
+        color_format: Format,
+        blend: BlendMode,
+        base_name: &str,
+    ) -> Result<Self, Error> {
+        Self::create::<Push>(vk_init, fragment, color_format, blend, false, base_name)
+    }
+
+    /// Same as [new](FullscreenPass::new), but the fragment shader reads the attachment it renders to,
+    /// e.g. to multiply the destination by a source color. Record with [record_feedback_loop](FullscreenPass::record_feedback_loop).
+    pub fn new_feedback_loop<Push>(
+        vk_init: &VkInit,
+        fragment: FragmentSource,
+        color_format: Format,
+        base_name: &str,
+    ) -> Result<Self, Error> {
+        Self::create::<Push>(
+            vk_init,
+            fragment,
+            color_format,
+            BlendMode::Opaque,
+            true,
+            base_name,
+        )
+    }
+
+    fn create<Push>(
+        vk_init: &VkInit,
+        fragment: FragmentSource,
+        color_format: Format,
+        blend: BlendMode,
+        feedback_loop: bool,
+        base_name: &str,
+    ) -> Result<Self, Error> {
+        let vertex_spirv = read_spv(&mut Cursor::new(FULLSCREEN_VERT_SPV))?;
+
+        let mut builder = VKUPipeline::builder()
+            .with_vertex::<()>(PrimitiveTopology::TRIANGLE_LIST)
+            .with_viewports_scissors(&[Viewport::default()], &[Rect2D::default()])
+            .with_rasterization(PolygonMode::FILL, CullModeFlags::NONE)
+            .with_multisample(SampleCountFlags::TYPE_1)
+            .with_depthstencil(DepthInfo::default(), StencilInfo::default())
+            .with_colorblends(&[blend])
+            .with_dynamic(&[DynamicState::VIEWPORT, DynamicState::SCISSOR])
+            .with_descriptors(&[(
+                false,
+                DescriptorType::COMBINED_IMAGE_SAMPLER,
+                ShaderStageFlags::FRAGMENT,
+                1,
+            )])
+            .with_rendering(&[color_format], None, None)
+            .with_feedback_loop(feedback_loop, false)
+            .push_shader_stage_spirv(
+                &vk_init.device,
+                ShaderStageFlags::VERTEX,
+                &vertex_spirv,
+                &[],
+            )?;
+
+        if size_of::<Push>() > 0 {
+            builder = builder.with_push_constants::<Push>();
+        }
+
+        builder = match fragment {
+            FragmentSource::Spirv(spirv) => builder.push_shader_stage_spirv(
+                &vk_init.device,
+                ShaderStageFlags::FRAGMENT,
+                spirv,
+                &[],
+            )?,
+            #[cfg(feature = "shader")]
+            FragmentSource::Glsl(glsl) => builder.push_shader_stage_glsl(
+                &vk_init.device,
+                ShaderStageFlags::FRAGMENT,
+                glsl,
+                &[],
+            )?,
+        };
+
+        let pipeline = builder.build(vk_init, base_name)?;
+
+        Ok(Self {
+            pipeline,
+            push_size: size_of::<Push>(),
+            base_name: base_name.to_string(),
+            inputs: Mutex::new(FullscreenInputs {
+                pools: DescriptorPools::new(
+                    DescriptorPoolPolicy::default(),
+                    &[(DescriptorType::COMBINED_IMAGE_SAMPLER, 1)],
+                    &format!("{base_name}_Input"),
+                ),
+                sets: HashMap::new(),
+            }),
+        })
+    }
+
+    /// Binds the pipeline and draws the fullscreen triangle, sampling ```input``` with ```sampler```.
+    ///
+    /// Must be called inside an active rendering scope. ```input``` needs to be in ```ImageLayout::SHADER_READ_ONLY_OPTIMAL```.
+    /// Viewport and scissor cover ```target_extent```, the extent of the attachment rendered to.
+    /// Returns [PushBytesSizeMismatch](Error::PushBytesSizeMismatch) if ```push_bytes``` does not match the ```Push``` type of the pass.
+    pub fn record(
+        &self,
+        vk_init: &VkInit,
+        cmd_buffer: &CommandBuffer,
+        input: &VMAImage,
+        target_extent: Extent2D,
+        sampler: Sampler,
+        push_bytes: &[u8],
+    ) -> Result<(), Error> {
+        self.record_sampling(
+            vk_init,
+            cmd_buffer,
+            input,
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            target_extent,
+            sampler,
+            push_bytes,
+        )
+    }
+
+    /// Draws the fullscreen triangle into ```target``` while sampling it, for passes created with
+    /// [new_feedback_loop](FullscreenPass::new_feedback_loop).
+    ///
+    /// Must be called inside a rendering scope with ```target``` as the only color attachment, both in its ```current_layout```:
+    /// the [feedback_loop_layout](VkInit::feedback_loop_layout) or ```ImageLayout::GENERAL```.
+    /// Every draw needs its own rendering scope, preceded by a [cmd_feedback_loop_barrier](VkInit::cmd_feedback_loop_barrier).
+    pub fn record_feedback_loop(
+        &self,
+        vk_init: &VkInit,
+        cmd_buffer: &CommandBuffer,
+        target: &VMAImage,
+        sampler: Sampler,
+        push_bytes: &[u8],
+    ) -> Result<(), Error> {
+        let target_extent = Extent2D {
+            width: target.extent.width,
+            height: target.extent.height,
+        };
+        self.record_sampling(
+            vk_init,
+            cmd_buffer,
+            target,
+            target.current_layout,
+            target_extent,
+            sampler,
+            push_bytes,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn record_sampling(
+        &self,
+        vk_init: &VkInit,
+        cmd_buffer: &CommandBuffer,
+        input: &VMAImage,
+        input_layout: ImageLayout,
+        target_extent: Extent2D,
+        sampler: Sampler,
+        push_bytes: &[u8],
+    ) -> Result<(), Error> {
+        if push_bytes.len() != self.push_size {
+            return Err(Error::PushBytesSizeMismatch {
+                expected: self.push_size,
+                provided: push_bytes.len(),
+            });
+        }
+        let desc_set = self.input_set(vk_init, input, input_layout, sampler)?;
+
+        let viewport = Viewport {
+            x: 0.0,
+            y: 0.0,
+            width: target_extent.width as f32,
+            height: target_extent.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        };
+        let scissor = Rect2D {
+            offset: Offset2D { x: 0, y: 0 },
+            extent: target_extent,
+        };
+
+        vk_init.cmd_bind_graphics_pipeline(cmd_buffer, &self.pipeline);
+        vk_init.cmd_bind_descriptor_sets(
+            cmd_buffer,
+            PipelineBindPoint::GRAPHICS,
+            self.pipeline.layout,
+            0,
+            &[desc_set],
+        );
+        if self.push_size > 0 {
+            unsafe {
+                vk_init.device.cmd_push_constants(
+                    *cmd_buffer,
+                    self.pipeline.layout,
+                    ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT,
+                    0,
+                    push_bytes,
+                );
+            }
+        }
+        vk_init.cmd_set_viewport(cmd_buffer, &[viewport]);
+        vk_init.cmd_set_scissor(cmd_buffer, &[scissor]);
+        vk_init.cmd_draw(cmd_buffer, 3, 1, 0, 0);
+
+        Ok(())
+    }
+
+    pub fn destroy(&mut self, vk_init: &VkInit) -> Result<(), Error> {
+        if let Ok(mut inputs) = self.inputs.lock() {
+            inputs.pools.destroy(&vk_init.device);
+            inputs.sets.clear();
+        }
+        self.pipeline.destroy(&vk_init.device)
+    }
+
+    /// Sets are keyed by image and view, so a recreated input, e.g. after a resize, gets a new set.
+    fn input_set(
+        &self,
+        vk_init: &VkInit,
+        input: &VMAImage,
+        image_layout: ImageLayout,
+        sampler: Sampler,
+    ) -> Result<DescriptorSet, Error> {
+        let key = (input.image, input.image_view, image_layout, sampler);
+        let mut inputs = self.inputs.lock().expect("fullscreen pass inputs poisoned");
+        if let Some(set) = inputs.sets.get(&key) {
+            return Ok(*set);
+        }
+
+        let set = inputs.pools.allocate(vk_init, self.pipeline.set_layout)?;
+        vk_init.set_debug_object_name_fmt(
+            set.as_raw(),
+            ObjectType::DESCRIPTOR_SET,
+            format_args!("{}_Input_Set_{}", self.base_name, inputs.sets.len()),
+        )?;
+
+        let image_infos = [DescriptorImageInfo::builder()
+            .image_view(input.image_view)
+            .sampler(sampler)
+            .image_layout(image_layout)
+            .build()];
+        let write = WriteDescriptorSet::builder()
+            .dst_set(set)
+            .dst_binding(0)
+            .descriptor_type(DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .image_info(&image_infos)
+            .build();
+        unsafe { vk_init.device.update_descriptor_sets(&[write], &[]) };
+
+        inputs.sets.insert(key, set);
+        Ok(set)
+    }
+}
@@ -0,0 +1,264 @@
+use std::cell::Cell;
+use std::io::Cursor;
+
+use ash::util::read_spv;
+
+use crate::{imports::*, Barriers, ComputeBinding, ComputeShader, VMABuffer, VkInit};
+
+const GPU_CULL_COMP_SPV: &[u8] = include_bytes!("shaders/gpu_cull.comp.spv");
+const GROUP_SIZE: u32 = 64;
+/// Size of the ```DrawIndirectCommand``` in front of the visible indices.
+const DRAW_COMMAND_SIZE: usize = size_of::<DrawIndirectCommand>();
+
+/// Local space bounding box and model matrix of an object culled by [GpuCuller].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObjectBounds {
+    /// Column-major model matrix.
+    pub transform: [[f32; 4]; 4],
+    aabb_min: [f32; 4],
+    aabb_max: [f32; 4],
+}
+
+impl ObjectBounds {
+    pub fn new(transform: [[f32; 4]; 4], aabb_min: [f32; 3], aabb_max: [f32; 3]) -> Self {
+        let [min_x, min_y, min_z] = aabb_min;
+        let [max_x, max_y, max_z] = aabb_max;
+        Self {
+            transform,
+            aabb_min: [min_x, min_y, min_z, 0.0],
+            aabb_max: [max_x, max_y, max_z, 0.0],
+        }
+    }
+}
+
+/// World space frustum planes ```(normal, distance)``` with normals pointing inside.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    /// Left, right, bottom, top, near and far plane.
+    pub planes: [[f32; 4]; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes of a column-major view projection matrix with Vulkan's ```0..1``` depth range.
+    /// ```
+    /// # use vku::*;
+    /// let identity = [
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [0.0, 1.0, 0.0, 0.0],
+    ///     [0.0, 0.0, 1.0, 0.0],
+    ///     [0.0, 0.0, 0.0, 1.0],
+    /// ];
+    /// let frustum = Frustum::from_view_projection(identity);
+    /// assert_eq!(frustum.planes[0], [1.0, 0.0, 0.0, 1.0]);
+    /// assert_eq!(frustum.planes[4], [0.0, 0.0, 1.0, 0.0]);
+    /// ```
+    pub fn from_view_projection(view_projection: [[f32; 4]; 4]) -> Self {
+        let row = |i: usize| view_projection.map(|column| column[i]);
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+        let add = |a: [f32; 4], b: [f32; 4]| [a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]];
+        let sub = |a: [f32; 4], b: [f32; 4]| [a[0] - b[0], a[1] - b[1], a[2] - b[2], a[3] - b[3]];
+
+        let planes = [add(w, x), sub(w, x), add(w, y), sub(w, y), z, sub(w, z)].map(|plane| {
+            let length = (plane[0] * plane[0] + plane[1] * plane[1] + plane[2] * plane[2]).sqrt();
+            match length > 0.0 {
+                true => plane.map(|v| v / length),
+                false => plane,
+            }
+        });
+        Self { planes }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CullPush {
+    planes: [[f32; 4]; 6],
+    object_count: u32,
+}
+
+impl CullPush {
+    fn bytes(&self) -> Vec<u8> {
+        self.planes
+            .iter()
+            .flatten()
+            .flat_map(|v| v.to_ne_bytes())
+            .chain(self.object_count.to_ne_bytes())
+            .collect()
+    }
+}
+
+/// Frustum culling compute pass writing an instanced ```DrawIndirectCommand``` for all visible objects.
+///
+/// The [indirect buffer](GpuCuller::indirect_buffer) holds the command at offset 0,
+/// followed by the ```uint``` indices of the visible objects. Vertex shaders look the object up with
+/// ```visible[gl_InstanceIndex]``` by binding the indirect buffer as storage buffer at offset 16.
+/// ```
+/// # extern crate winit;
+/// # use vku::*;
+/// # use ash::vk::*;
+/// # if VkInit::load_entry().is_err() { return Ok(()); }
+/// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+/// # let size = [800_u32, 600_u32];
+/// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+/// # let create_info = VkInitCreateInfo::default();
+/// # let view_projection = [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+/// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+/// let cmd_pool = init.create_cmd_pool(CmdType::Any)?;
+/// let cmd_buffer = init.create_command_buffers(&cmd_pool, 1)?[0];
+/// let mut culler = GpuCuller::new(&mut init, 1024, 36)?;
+///
+/// culler.update_objects(&init, &[ObjectBounds::new(view_projection, [-1.0; 3], [1.0; 3])])?;
+/// init.begin_cmd_buffer(&cmd_buffer)?;
+/// culler.record(&init, &cmd_buffer, &Frustum::from_view_projection(view_projection));
+/// // begin rendering, bind the pipeline
+/// unsafe {
+///     init.device.cmd_draw_indirect(cmd_buffer, culler.indirect_buffer().buffer, 0, 1, 0);
+/// }
+/// # Ok::<(), vku::Error>(())
+/// ```
+pub struct GpuCuller {
+    shader: ComputeShader,
+    objects: VMABuffer,
+    indirect: VMABuffer,
+    max_objects: usize,
+    vertex_count: u32,
+    object_count: Cell<u32>,
+}
+
+impl GpuCuller {
+    /// Culls up to ```max_objects``` objects, each drawn as one instance of ```vertex_count``` vertices.
+    pub fn new(vk_init: &mut VkInit, max_objects: usize, vertex_count: u32) -> Result<Self, Error> {
+        let objects = vk_init.create_cpu_to_gpu_buffer(
+            max_objects.max(1) * size_of::<ObjectBounds>(),
+            BufferUsageFlags::STORAGE_BUFFER,
+        )?;
+        objects.set_debug_object_name(vk_init, String::from("VKU_Gpu_Culler_Objects"))?;
+        let indirect = vk_init.create_local_buffer(
+            DRAW_COMMAND_SIZE + max_objects.max(1) * size_of::<u32>(),
+            BufferUsageFlags::STORAGE_BUFFER
+                | BufferUsageFlags::INDIRECT_BUFFER
+                | BufferUsageFlags::TRANSFER_DST
+                | BufferUsageFlags::TRANSFER_SRC,
+        )?;
+        indirect.set_debug_object_name(vk_init, String::from("VKU_Gpu_Culler_Indirect"))?;
+
+        let code = read_spv(&mut Cursor::new(GPU_CULL_COMP_SPV))?;
+        let shader = vk_init.create_compute_shader::<CullPush>(
+            &[
+                ComputeBinding::Ssbo(&objects),
+                ComputeBinding::Ssbo(&indirect),
+            ],
+            code,
+            [GROUP_SIZE, 1, 1],
+            &[],
+            String::from("VKU_Gpu_Culler"),
+        )?;
+
+        Ok(Self {
+            shader,
+            objects,
+            indirect,
+            max_objects,
+            vertex_count,
+            object_count: Cell::new(0),
+        })
+    }
+
+    /// Replaces the culled objects. The buffer is host-visible, do not update while a recorded cull is in flight.
+    ///
+    /// Returns [CullObjectsExceeded](Error::CullObjectsExceeded) for more than ```max_objects```.
+    pub fn update_objects(&self, vk_init: &VkInit, objects: &[ObjectBounds]) -> Result<(), Error> {
+        if objects.len() > self.max_objects {
+            return Err(Error::CullObjectsExceeded {
+                requested: objects.len(),
+                max: self.max_objects,
+            });
+        }
+        self.objects.set_data(&vk_init.device, 0, objects)?;
+        self.object_count.set(objects.len() as u32);
+        Ok(())
+    }
+
+    /// Resets the draw command, dispatches the cull and makes the results visible to indirect draws and vertex shaders.
+    ///
+    /// Must be recorded outside of rendering.
+    pub fn record(&self, vk_init: &VkInit, cmd_buffer: &CommandBuffer, frustum: &Frustum) {
+        let indirect_barrier = |src_stage, src_access, dst_stage, dst_access| {
+            BufferMemoryBarrier2::builder()
+                .buffer(self.indirect.buffer)
+                .size(WHOLE_SIZE)
+                .src_stage_mask(src_stage)
+                .src_access_mask(src_access)
+                .dst_stage_mask(dst_stage)
+                .dst_access_mask(dst_access)
+                .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+                .build()
+        };
+
+        Barriers::new()
+            .add_buffer(&indirect_barrier(
+                PipelineStageFlags2::DRAW_INDIRECT | PipelineStageFlags2::VERTEX_SHADER,
+                AccessFlags2::INDIRECT_COMMAND_READ | AccessFlags2::SHADER_READ,
+                PipelineStageFlags2::TRANSFER,
+                AccessFlags2::TRANSFER_WRITE,
+            ))
+            .record(vk_init, cmd_buffer);
+        // Instance count is incremented by the shader for every visible object.
+        let draw: Vec<u8> = [self.vertex_count, 0, 0, 0]
+            .iter()
+            .flat_map(|v| v.to_ne_bytes())
+            .collect();
+        unsafe {
+            vk_init
+                .device
+                .cmd_update_buffer(*cmd_buffer, self.indirect.buffer, 0, &draw);
+        }
+        Barriers::new()
+            .add_buffer(&indirect_barrier(
+                PipelineStageFlags2::TRANSFER,
+                AccessFlags2::TRANSFER_WRITE,
+                PipelineStageFlags2::COMPUTE_SHADER,
+                AccessFlags2::SHADER_READ | AccessFlags2::SHADER_WRITE,
+            ))
+            .record(vk_init, cmd_buffer);
+
+        let push = CullPush {
+            planes: frustum.planes,
+            object_count: self.object_count.get(),
+        };
+        self.shader.bind(&vk_init.device, cmd_buffer, &push.bytes());
+        if push.object_count > 0 {
+            self.shader
+                .dispatch(&vk_init.device, cmd_buffer, push.object_count, 1, 1);
+        }
+
+        Barriers::new()
+            .add_buffer(&indirect_barrier(
+                PipelineStageFlags2::COMPUTE_SHADER,
+                AccessFlags2::SHADER_WRITE,
+                PipelineStageFlags2::DRAW_INDIRECT | PipelineStageFlags2::VERTEX_SHADER,
+                AccessFlags2::INDIRECT_COMMAND_READ | AccessFlags2::SHADER_READ,
+            ))
+            .record(vk_init, cmd_buffer);
+    }
+
+    /// ```DrawIndirectCommand``` followed by the visible object indices.
+    pub fn indirect_buffer(&self) -> &VMABuffer {
+        &self.indirect
+    }
+
+    pub fn max_objects(&self) -> usize {
+        self.max_objects
+    }
+
+    pub fn destroy(&mut self, vk_init: &mut VkInit) -> Result<(), Error> {
+        self.shader.destroy(vk_init)?;
+        self.objects
+            .destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        self.indirect
+            .destroy(&vk_init.device, &mut vk_init.allocator.lock())
+    }
+}
@@ -0,0 +1,458 @@
+use std::borrow::Cow;
+
+use crate::{
+    imports::*, queue_ownership::HandoffResource, CmdType, QueueRequirements, VMAImage, VkInit,
+};
+
+/// Texels left empty around every region, so linear filtering does not bleed into neighbours.
+const ATLAS_PADDING: u32 = 1;
+
+/// Region of an [ImageAtlas] returned by [allocate](ImageAtlas::allocate).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasRegion {
+    /// Top left texel of the region.
+    pub offset: [u32; 2],
+    pub size: [u32; 2],
+    /// Normalized texture coordinates of the top left corner.
+    pub uv_min: [f32; 2],
+    /// Normalized texture coordinates of the bottom right corner.
+    pub uv_max: [f32; 2],
+}
+
+#[derive(Clone, Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    /// Free horizontal spans as ```(x, width)```, sorted by x and never adjacent.
+    free: Vec<(u32, u32)>,
+}
+
+/// Shelf packer placing rectangles in rows of fixed height, used by [ImageAtlas].
+///
+/// Rectangles go to the lowest shelf that fits without wasting more than half of its height,
+/// freed spans are merged and reused.
+/// ```
+/// # use vku::*;
+/// let mut packer = ShelfPacker::new([256, 256]);
+/// let a = packer.allocate([64, 32]).unwrap();
+/// let b = packer.allocate([64, 32]).unwrap();
+/// assert_eq!(a, [0, 0]);
+/// assert_eq!(b, [64, 0]);
+///
+/// packer.free(a, [64, 32]);
+/// assert_eq!(packer.allocate([32, 32]), Some([0, 0]));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ShelfPacker {
+    extent: [u32; 2],
+    shelves: Vec<Shelf>,
+}
+
+impl ShelfPacker {
+    pub fn new(extent: [u32; 2]) -> Self {
+        Self {
+            extent,
+            shelves: vec![],
+        }
+    }
+
+    pub fn extent(&self) -> [u32; 2] {
+        self.extent
+    }
+
+    /// Height covered by shelves, rectangles taller than the remaining height need a freed shelf.
+    pub fn used_height(&self) -> u32 {
+        self.shelves
+            .last()
+            .map(|shelf| shelf.y + shelf.height)
+            .unwrap_or(0)
+    }
+
+    /// Reserves a rectangle of ```size``` and returns its top left corner, ```None``` if it does not fit.
+    pub fn allocate(&mut self, size: [u32; 2]) -> Option<[u32; 2]> {
+        let [width, height] = size;
+        if width == 0 || height == 0 || width > self.extent[0] || height > self.extent[1] {
+            return None;
+        }
+
+        let fits = |shelf: &Shelf, max_height: u32| {
+            shelf.height >= height
+                && shelf.height <= max_height
+                && shelf.free.iter().any(|(_, free)| *free >= width)
+        };
+        let best_fit = |shelves: &[Shelf], max_height: u32| {
+            shelves
+                .iter()
+                .enumerate()
+                .filter(|(_, shelf)| fits(shelf, max_height))
+                .min_by_key(|(_, shelf)| shelf.height)
+                .map(|(i, _)| i)
+        };
+
+        let index = match best_fit(&self.shelves, height.saturating_mul(2)) {
+            Some(index) => index,
+            None if self.used_height() + height <= self.extent[1] => {
+                self.shelves.push(Shelf {
+                    y: self.used_height(),
+                    height,
+                    free: vec![(0, self.extent[0])],
+                });
+                self.shelves.len() - 1
+            }
+            None => best_fit(&self.shelves, u32::MAX)?,
+        };
+
+        let shelf = &mut self.shelves[index];
+        let span = shelf.free.iter().position(|(_, free)| *free >= width)?;
+        let x = shelf.free[span].0;
+        shelf.free[span].0 += width;
+        shelf.free[span].1 -= width;
+        if shelf.free[span].1 == 0 {
+            shelf.free.remove(span);
+        }
+        Some([x, shelf.y])
+    }
+
+    /// Returns a rectangle reserved by [allocate](ShelfPacker::allocate). Empty shelves at the top are removed.
+    pub fn free(&mut self, offset: [u32; 2], size: [u32; 2]) {
+        let [x, y] = offset;
+        let Some(shelf) = self.shelves.iter_mut().find(|shelf| shelf.y == y) else {
+            warn!("Freeing unknown atlas region at {offset:?}");
+            return;
+        };
+
+        let index = shelf.free.partition_point(|(free_x, _)| *free_x < x);
+        shelf.free.insert(index, (x, size[0]));
+        if index + 1 < shelf.free.len() && x + size[0] == shelf.free[index + 1].0 {
+            shelf.free[index].1 += shelf.free.remove(index + 1).1;
+        }
+        if index > 0 && shelf.free[index - 1].0 + shelf.free[index - 1].1 == x {
+            shelf.free[index - 1].1 += shelf.free.remove(index).1;
+        }
+
+        let extent = self.extent[0];
+        while self
+            .shelves
+            .last()
+            .is_some_and(|shelf| shelf.free == [(0, extent)])
+        {
+            self.shelves.pop();
+        }
+    }
+}
+
+/// Packs many small textures into a single sampled [VMAImage], e.g. glyphs, icons or sprites.
+///
+/// The image stays in ```SHADER_READ_ONLY_OPTIMAL``` between uploads.
+/// ```
+/// # extern crate winit;
+/// # use vku::*;
+/// # use ash::vk::*;
+/// # if VkInit::load_entry().is_err() { return Ok(()); }
+/// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+/// # let size = [800_u32, 600_u32];
+/// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+/// # let create_info = VkInitCreateInfo::default();
+/// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+/// let mut atlas = ImageAtlas::new(&mut init, [1024, 1024], Format::R8G8B8A8_UNORM)?;
+///
+/// let icon = vec![255_u8; 16 * 16 * 4];
+/// let region = atlas.allocate(&mut init, &icon, [16, 16])?;
+/// // sample atlas.image.image_view with region.uv_min and region.uv_max
+///
+/// atlas.free(region);
+/// atlas.destroy(&mut init)?;
+/// # Ok::<(), vku::Error>(())
+/// ```
+pub struct ImageAtlas {
+    pub image: VMAImage,
+    texel_size: usize,
+    packer: ShelfPacker,
+}
+
+impl ImageAtlas {
+    /// Creates an empty atlas of ```extent``` texels.
+    ///
+    /// Returns [UnsupportedAtlasFormat](Error::UnsupportedAtlasFormat) for compressed and depth formats.
+    pub fn new(vk_init: &mut VkInit, extent: [u32; 2], format: Format) -> Result<Self, Error> {
+        let texel_size = texel_size(format).ok_or(Error::UnsupportedAtlasFormat(format))?;
+        let image = vk_init.create_empty_image(
+            Extent3D {
+                width: extent[0],
+                height: extent[1],
+                depth: 1,
+            },
+            format,
+            texel_size,
+            ImageAspectFlags::COLOR,
+        )?;
+        image.set_debug_object_name(vk_init, String::from("VKU_Image_Atlas"))?;
+
+        Ok(Self {
+            image,
+            texel_size,
+            packer: ShelfPacker::new(extent),
+        })
+    }
+
+    pub fn packer(&self) -> &ShelfPacker {
+        &self.packer
+    }
+
+    /// Packs a region of ```size``` texels and uploads the tightly packed ```pixels``` into it.
+    ///
+    /// The padding is uploaded as zeros along with the pixels, texels of previously freed regions never show.
+    /// Blocks until the upload finished, it runs on the dedicated transfer queue if the region fits its transfer granularity.
+    /// Returns [AtlasFull](Error::AtlasFull) if no space is left.
+    pub fn allocate(
+        &mut self,
+        vk_init: &mut VkInit,
+        pixels: &[u8],
+        size: [u32; 2],
+    ) -> Result<AtlasRegion, Error> {
+        let expected = size[0] as usize * size[1] as usize * self.texel_size;
+        if pixels.len() != expected {
+            return Err(Error::AtlasDataMismatch {
+                expected,
+                provided: pixels.len(),
+            });
+        }
+
+        let padded = self.padded(size);
+        let offset = self.packer.allocate(padded).ok_or(Error::AtlasFull(size))?;
+
+        let padded_pixels = self.pad_pixels(pixels, size, padded);
+        if let Err(e) = self.upload(vk_init, &padded_pixels, offset, padded) {
+            self.packer.free(offset, padded);
+            return Err(e);
+        }
+
+        let extent = self.packer.extent();
+        Ok(AtlasRegion {
+            offset,
+            size,
+            uv_min: [
+                offset[0] as f32 / extent[0] as f32,
+                offset[1] as f32 / extent[1] as f32,
+            ],
+            uv_max: [
+                (offset[0] + size[0]) as f32 / extent[0] as f32,
+                (offset[1] + size[1]) as f32 / extent[1] as f32,
+            ],
+        })
+    }
+
+    /// Makes the region available again, its texels are overwritten by the next allocation of the space.
+    pub fn free(&mut self, region: AtlasRegion) {
+        self.packer.free(region.offset, self.padded(region.size));
+    }
+
+    pub fn destroy(&mut self, vk_init: &mut VkInit) -> Result<(), Error> {
+        self.image
+            .destroy(&vk_init.device, &mut vk_init.allocator.lock())
+    }
+
+    /// Regions spanning the whole atlas have no room for padding.
+    fn padded(&self, size: [u32; 2]) -> [u32; 2] {
+        let extent = self.packer.extent();
+        [
+            (size[0] + ATLAS_PADDING).min(extent[0]),
+            (size[1] + ATLAS_PADDING).min(extent[1]),
+        ]
+    }
+
+    /// Zero extends tightly packed rows of ```size``` texels to ```padded```, copies the transfer queue can upload without a clear.
+    fn pad_pixels<'a>(&self, pixels: &'a [u8], size: [u32; 2], padded: [u32; 2]) -> Cow<'a, [u8]> {
+        if size == padded {
+            return Cow::Borrowed(pixels);
+        }
+        let row = size[0] as usize * self.texel_size;
+        let padded_row = padded[0] as usize * self.texel_size;
+        let mut padded_pixels = vec![0; padded_row * padded[1] as usize];
+        for y in 0..size[1] as usize {
+            padded_pixels[y * padded_row..][..row].copy_from_slice(&pixels[y * row..][..row]);
+        }
+        Cow::Owned(padded_pixels)
+    }
+
+    fn upload(
+        &mut self,
+        vk_init: &mut VkInit,
+        pixels: &[u8],
+        offset: [u32; 2],
+        size: [u32; 2],
+    ) -> Result<(), Error> {
+        let staging = vk_init.acquire_staging(pixels.len())?;
+        staging.set_data(&vk_init.device, 0, pixels)?;
+
+        // Staging memory was just written by the host, only the atlas has contents to keep.
+        // The first upload discards the undefined contents, later ones keep all other regions.
+        let requirements = QueueRequirements {
+            image_granularity: Extent3D {
+                width: region_granularity(offset[0], size[0]),
+                height: region_granularity(offset[1], size[1]),
+                depth: 1,
+            },
+            ..Default::default()
+        };
+        let image = HandoffResource::Image {
+            image: self.image.image,
+            subresource_range: ImageSubresourceRange {
+                aspect_mask: self.image.aspect_flags,
+                base_mip_level: 0,
+                level_count: self.image.mip_levels,
+                base_array_layer: 0,
+                layer_count: REMAINING_ARRAY_LAYERS,
+            },
+            old_layout: self.image.current_layout,
+            layout: ImageLayout::TRANSFER_DST_OPTIMAL,
+            new_layout: ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        };
+        let mut handoff = match vk_init.queue_handoff(
+            CmdType::Transfer,
+            requirements,
+            (PipelineStageFlags2::TRANSFER, AccessFlags2::TRANSFER_WRITE),
+            vec![image],
+        ) {
+            Ok(handoff) => handoff,
+            Err(e) => {
+                vk_init.release_staging(staging, None)?;
+                return Err(e);
+            }
+        };
+        let mut cmd_pool = CommandPool::null();
+        let mut fence = Fence::null();
+
+        let result = (|| {
+            let pool_info = CommandPoolCreateInfo::builder()
+                .queue_family_index(handoff.queue_family_index())
+                .flags(CommandPoolCreateFlags::TRANSIENT);
+            cmd_pool = unsafe { vk_init.device.create_command_pool(&pool_info, None)? };
+            fence = vk_init.create_fence_named("VKU_Image_Atlas_Upload")?;
+            let cmd_buffer =
+                vk_init.create_command_buffers_named(&cmd_pool, 1, "VKU_Image_Atlas_Upload")?[0];
+            vk_init.begin_cmd_buffer(&cmd_buffer)?;
+            handoff.record_begin(vk_init, &cmd_buffer);
+
+            let copy = BufferImageCopy::builder()
+                .buffer_offset(0)
+                .buffer_row_length(0)
+                .buffer_image_height(0)
+                .image_subresource(
+                    ImageSubresourceLayers::builder()
+                        .aspect_mask(ImageAspectFlags::COLOR)
+                        .mip_level(0)
+                        .base_array_layer(0)
+                        .layer_count(1)
+                        .build(),
+                )
+                .image_offset(Offset3D {
+                    x: offset[0] as i32,
+                    y: offset[1] as i32,
+                    z: 0,
+                })
+                .image_extent(Extent3D {
+                    width: size[0],
+                    height: size[1],
+                    depth: 1,
+                });
+            unsafe {
+                vk_init.device.cmd_copy_buffer_to_image(
+                    cmd_buffer,
+                    staging.buffer.buffer,
+                    self.image.image,
+                    ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[*copy],
+                );
+            }
+
+            handoff.record_end(vk_init, &cmd_buffer);
+            unsafe {
+                vk_init.device.end_command_buffer(cmd_buffer)?;
+                // Fences are created signaled.
+                vk_init.device.reset_fences(&[fence])?;
+            }
+            handoff.submit(vk_init, &[cmd_buffer], &fence)?;
+            self.image.current_layout = ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+            vk_init.wait_for_fences_watched(&[fence], "ImageAtlas::allocate")
+        })();
+
+        handoff.destroy(&vk_init.device);
+        vk_init.destroy_fence(&fence)?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        vk_init.release_staging(staging, None)?;
+        result
+    }
+}
+
+/// Size of a texel of uncompressed color formats.
+pub(crate) fn texel_size(format: Format) -> Option<usize> {
+    match format {
+        Format::R8_UNORM | Format::R8_SNORM | Format::R8_UINT | Format::R8_SRGB => Some(1),
+        Format::R8G8_UNORM
+        | Format::R8G8_SNORM
+        | Format::R8G8_UINT
+        | Format::R8G8_SRGB
+        | Format::R16_UNORM
+        | Format::R16_SFLOAT
+        | Format::R16_UINT => Some(2),
+        Format::R8G8B8A8_UNORM
+        | Format::R8G8B8A8_SNORM
+        | Format::R8G8B8A8_UINT
+        | Format::R8G8B8A8_SRGB
+        | Format::B8G8R8A8_UNORM
+        | Format::B8G8R8A8_SRGB
+        | Format::A2B10G10R10_UNORM_PACK32
+        | Format::A2R10G10B10_UNORM_PACK32
+        | Format::R16G16_UNORM
+        | Format::R16G16_SFLOAT
+        | Format::R32_SFLOAT
+        | Format::R32_UINT => Some(4),
+        Format::R16G16B16A16_UNORM
+        | Format::R16G16B16A16_SFLOAT
+        | Format::R32G32_SFLOAT
+        | Format::R32G32_UINT => Some(8),
+        Format::R32G32B32A32_SFLOAT | Format::R32G32B32A32_UINT => Some(16),
+        _ => None,
+    }
+}
+
+/// Bytes of a texel block, the texel size for uncompressed color formats.
+pub(crate) fn texel_block_size(format: Format) -> Option<usize> {
+    let astc = Format::ASTC_4X4_UNORM_BLOCK.as_raw()..=Format::ASTC_12X12_SRGB_BLOCK.as_raw();
+    match format {
+        Format::BC1_RGB_UNORM_BLOCK
+        | Format::BC1_RGB_SRGB_BLOCK
+        | Format::BC1_RGBA_UNORM_BLOCK
+        | Format::BC1_RGBA_SRGB_BLOCK
+        | Format::BC4_UNORM_BLOCK
+        | Format::BC4_SNORM_BLOCK
+        | Format::ETC2_R8G8B8_UNORM_BLOCK
+        | Format::ETC2_R8G8B8_SRGB_BLOCK
+        | Format::ETC2_R8G8B8A1_UNORM_BLOCK
+        | Format::ETC2_R8G8B8A1_SRGB_BLOCK
+        | Format::EAC_R11_UNORM_BLOCK
+        | Format::EAC_R11_SNORM_BLOCK => Some(8),
+        Format::BC2_UNORM_BLOCK
+        | Format::BC2_SRGB_BLOCK
+        | Format::BC3_UNORM_BLOCK
+        | Format::BC3_SRGB_BLOCK
+        | Format::BC5_UNORM_BLOCK
+        | Format::BC5_SNORM_BLOCK
+        | Format::BC6H_UFLOAT_BLOCK
+        | Format::BC6H_SFLOAT_BLOCK
+        | Format::BC7_UNORM_BLOCK
+        | Format::BC7_SRGB_BLOCK
+        | Format::ETC2_R8G8B8A8_UNORM_BLOCK
+        | Format::ETC2_R8G8B8A8_SRGB_BLOCK
+        | Format::EAC_R11G11_UNORM_BLOCK
+        | Format::EAC_R11G11_SNORM_BLOCK => Some(16),
+        _ if astc.contains(&format.as_raw()) => Some(16),
+        _ => texel_size(format),
+    }
+}
+
+/// Largest power of two both ```offset``` and ```size``` of a region are a multiple of.
+fn region_granularity(offset: u32, size: u32) -> u32 {
+    1 << (offset | size).trailing_zeros().min(31)
+}
@@ -1,10 +1,11 @@
 use crate::imports::*;
 
-pub fn get_image_layout_transition_barrier2(
+pub(crate) fn get_image_layout_transition_barrier2(
     image: &Image,
     src_layout: ImageLayout,
     dst_layout: ImageLayout,
     aspect_flags: ImageAspectFlags,
+    layer_count: u32,
     src_queue: Option<u32>,
     dst_queue: Option<u32>,
 ) -> Result<ImageMemoryBarrier2, Error> {
@@ -100,6 +101,20 @@ pub fn get_image_layout_transition_barrier2(
             PipelineStageFlags2::FRAGMENT_SHADER,
         ),
 
+        (ImageLayout::PRESENT_SRC_KHR, ImageLayout::TRANSFER_SRC_OPTIMAL) => (
+            AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            AccessFlags2::TRANSFER_READ,
+            PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+            PipelineStageFlags2::TRANSFER,
+        ),
+
+        (ImageLayout::TRANSFER_SRC_OPTIMAL, ImageLayout::PRESENT_SRC_KHR) => (
+            AccessFlags2::TRANSFER_READ,
+            AccessFlags2::empty(),
+            PipelineStageFlags2::TRANSFER,
+            PipelineStageFlags2::BOTTOM_OF_PIPE,
+        ),
+
         (ImageLayout::COLOR_ATTACHMENT_OPTIMAL, ImageLayout::COLOR_ATTACHMENT_OPTIMAL) => (
             AccessFlags2::COLOR_ATTACHMENT_WRITE,
             AccessFlags2::COLOR_ATTACHMENT_WRITE,
@@ -114,28 +129,140 @@ pub fn get_image_layout_transition_barrier2(
             PipelineStageFlags2::FRAGMENT_SHADER,
         ),
 
+        (ImageLayout::COLOR_ATTACHMENT_OPTIMAL, ImageLayout::TRANSFER_SRC_OPTIMAL) => (
+            AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            AccessFlags2::TRANSFER_READ,
+            PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+            PipelineStageFlags2::TRANSFER,
+        ),
+
+        // Resolve attachments are written in the color attachment output stage as well.
+        (ImageLayout::COLOR_ATTACHMENT_OPTIMAL, ImageLayout::PRESENT_SRC_KHR) => (
+            AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            AccessFlags2::empty(),
+            PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+            PipelineStageFlags2::BOTTOM_OF_PIPE,
+        ),
+
+        // Depth is written by both fragment test stages and sampled from other regions, see default_dependency_flags.
+        (ImageLayout::DEPTH_ATTACHMENT_OPTIMAL, ImageLayout::SHADER_READ_ONLY_OPTIMAL) => (
+            AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            AccessFlags2::SHADER_READ,
+            PipelineStageFlags2::EARLY_FRAGMENT_TESTS | PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+            PipelineStageFlags2::FRAGMENT_SHADER,
+        ),
+
+        (ImageLayout::SHADER_READ_ONLY_OPTIMAL, ImageLayout::DEPTH_ATTACHMENT_OPTIMAL) => (
+            AccessFlags2::SHADER_READ,
+            AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ
+                | AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            PipelineStageFlags2::FRAGMENT_SHADER,
+            PipelineStageFlags2::EARLY_FRAGMENT_TESTS | PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+        ),
+
+        (ImageLayout::DEPTH_ATTACHMENT_OPTIMAL, ImageLayout::DEPTH_ATTACHMENT_OPTIMAL) => (
+            AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ
+                | AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+            PipelineStageFlags2::EARLY_FRAGMENT_TESTS,
+        ),
+
+        // The GENERAL fallback of feedback loops is mapped onto these by feedback_loop_barrier.
+        (ImageLayout::UNDEFINED, ImageLayout::ATTACHMENT_FEEDBACK_LOOP_OPTIMAL_EXT) => (
+            AccessFlags2::empty(),
+            AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            PipelineStageFlags2::TOP_OF_PIPE,
+            PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+        ),
+
+        (
+            ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            ImageLayout::ATTACHMENT_FEEDBACK_LOOP_OPTIMAL_EXT,
+        ) => (
+            AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            AccessFlags2::SHADER_READ
+                | AccessFlags2::COLOR_ATTACHMENT_READ
+                | AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+            PipelineStageFlags2::FRAGMENT_SHADER | PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+        ),
+
+        (ImageLayout::ATTACHMENT_FEEDBACK_LOOP_OPTIMAL_EXT, ImageLayout::TRANSFER_SRC_OPTIMAL) => (
+            AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            AccessFlags2::TRANSFER_READ,
+            PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+            PipelineStageFlags2::TRANSFER,
+        ),
+
+        (
+            ImageLayout::ATTACHMENT_FEEDBACK_LOOP_OPTIMAL_EXT,
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        ) => (
+            AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            AccessFlags2::SHADER_READ,
+            PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+            PipelineStageFlags2::FRAGMENT_SHADER,
+        ),
+
         (_, _) => {
             return Err(Error::UnsupportedImageLayoutTransition);
         }
     };
 
+    let (src_queue, dst_queue) = queue_family_indices(src_queue, dst_queue);
     let barrier = ImageMemoryBarrier2::builder()
         .image(*image)
         .src_stage_mask(src_stage)
         .dst_stage_mask(dst_stage)
         .src_access_mask(src_access)
         .dst_access_mask(dst_access)
-        .src_queue_family_index(src_queue.unwrap_or(0))
-        .src_queue_family_index(dst_queue.unwrap_or(0))
+        .src_queue_family_index(src_queue)
+        .dst_queue_family_index(dst_queue)
         .old_layout(src_layout)
         .new_layout(dst_layout)
         .subresource_range(ImageSubresourceRange {
             aspect_mask: aspect_flags,
             level_count: 1,
-            layer_count: 1,
+            layer_count,
             ..Default::default()
         })
         .build();
 
     Ok(barrier)
 }
+
+/// Same as [get_image_layout_transition_barrier2], but ```GENERAL``` on either side is the feedback loop fallback
+/// used if VK_EXT_attachment_feedback_loop_layout is missing, see VkInit::feedback_loop_layout.
+pub(crate) fn feedback_loop_barrier(
+    image: &Image,
+    src_layout: ImageLayout,
+    dst_layout: ImageLayout,
+    aspect_flags: ImageAspectFlags,
+    layer_count: u32,
+) -> Result<ImageMemoryBarrier2, Error> {
+    let as_feedback_loop = |layout| match layout {
+        ImageLayout::GENERAL => ImageLayout::ATTACHMENT_FEEDBACK_LOOP_OPTIMAL_EXT,
+        layout => layout,
+    };
+    let mut barrier = get_image_layout_transition_barrier2(
+        image,
+        as_feedback_loop(src_layout),
+        as_feedback_loop(dst_layout),
+        aspect_flags,
+        layer_count,
+        None,
+        None,
+    )?;
+    barrier.old_layout = src_layout;
+    barrier.new_layout = dst_layout;
+    Ok(barrier)
+}
+
+/// Real queue family indices only if both are provided, a barrier with a single index would encode an ownership transfer.
+pub(crate) fn queue_family_indices(src_queue: Option<u32>, dst_queue: Option<u32>) -> (u32, u32) {
+    match (src_queue, dst_queue) {
+        (Some(src_queue), Some(dst_queue)) => (src_queue, dst_queue),
+        _ => (QUEUE_FAMILY_IGNORED, QUEUE_FAMILY_IGNORED),
+    }
+}
@@ -1,11 +1,27 @@
+use std::collections::HashMap;
 use std::mem::ManuallyDrop;
+use std::sync::Mutex;
 
 use gpu_allocator::vulkan::AllocatorCreateDesc;
 use gpu_allocator::{AllocationSizes, AllocatorDebugSettings};
 use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
 
-use crate::create_info::VkInitCreateInfo;
-use crate::{imports::*, VMAImage};
+use crate::allocation_lifetime;
+use crate::crash_diagnostics::CrashDiagnostics;
+use crate::create_info::{GpuSelection, VkInitCreateInfo};
+use crate::debug::DebugMessageCapture;
+use crate::frame_loop::FrameLoop;
+use crate::image_atlas::texel_size;
+use crate::loader::version_string;
+use crate::mipmaps::MipPipeline;
+use crate::provenance;
+use crate::surface_support::{choose_present_mode, choose_surface_format};
+use crate::swapchain::{AcquireResult, AcquiredImage};
+use crate::watchdog::GpuWatchdog;
+use crate::{
+    imports::*, DeletionQueue, DeviceFeatures, FrameScheme, LoaderInfo, OffscreenHead,
+    RenderingConfig, SamplerInfo, SharedAllocator, StagingPool, SurfaceRotation, VMAImage,
+};
 
 /// Wrapper around 'static' vulkan objects (instance, device etc.), optional head (surface, swapchain etc.), and utility functions for ease of use.
 ///
@@ -14,9 +30,17 @@ use crate::{imports::*, VMAImage};
 /// - Swapchain recreation and resizing
 /// - Optionally exposed dedicated compute and transfer queues
 /// - Shortcuts for present and submit operations
+///
+/// [VkInit] is [Send] and [Sync]: resources can be created and command buffers recorded from any thread,
+/// the [allocator](SharedAllocator) and the internal caches are locked. Vulkan's external synchronization rules still apply:
+/// - A queue must not be used by two threads at once, this includes [submit_cmd_buffers](VkInit::submit_cmd_buffers),
+///   [immediate_submit](VkInit::immediate_submit) and [present](VkInit::present) on the same [CmdType].
+///   [wait_device_idle](VkInit::wait_device_idle) uses all queues.
+/// - A command pool and its command buffers must only be used by one thread at a time, create one pool per thread.
+/// - Methods taking ```&mut self``` are synchronized by the borrow checker.
 pub struct VkInit {
-    /// [GPU-Allocator](gpu-allocator::vulkan::Allocator)
-    pub allocator: ManuallyDrop<Allocator>,
+    /// [GPU-Allocator](gpu_allocator::vulkan::Allocator), locked for every use.
+    pub allocator: ManuallyDrop<SharedAllocator>,
     pub entry: Entry,
     pub instance: Instance,
     /// Only created with enabled validation
@@ -32,10 +56,56 @@ pub struct VkInit {
     /// Optionally exposed
     pub compute_queue: Option<Queue>,
     pub physical_device_info: PhysicalDeviceInfo,
+    /// Required, additional and optionally enabled device extensions
+    pub enabled_device_extensions: Vec<String>,
     pub head: Option<Head>,
+    /// Render targets of headless instances, see [create_offscreen_head](VkInit::create_offscreen_head).
+    pub offscreen_head: Option<OffscreenHead>,
     pub create_info: VkInitCreateInfo,
+    /// Reusable host-visible staging buffers
+    pub staging_pool: StagingPool,
+    /// Resources destroyed once their fence has signaled
+    pub deletion_queue: Mutex<DeletionQueue>,
+    pub(crate) frame_scheme: FrameScheme,
+    pub(crate) device_generation: u64,
+    /// Only loaded if [vertex_input_dynamic_state](PhysicalDeviceInfo::vertex_input_dynamic_state) is supported
+    pub(crate) vertex_input_fn: Option<ExtVertexInputDynamicStateFn>,
+    pub(crate) watchdog: GpuWatchdog,
+    pub(crate) crash_diagnostics: CrashDiagnostics,
+    pub(crate) loader_info: LoaderInfo,
+    /// Boxed to stay at a stable address, the debug messenger holds a pointer to it
+    pub(crate) debug_message_capture: Box<DebugMessageCapture>,
+    /// Set once [destroy](VkInit::destroy) completed, later calls are no-ops.
+    pub(crate) destroyed: bool,
+    /// Created on first use of the [compute mip generation](crate::MipGeneration::Compute).
+    pub(crate) mip_pipeline: Mutex<Option<MipPipeline>>,
+    /// Used by all pipelines, see [load_or_create_pipeline_cache](VkInit::load_or_create_pipeline_cache).
+    pub(crate) pipeline_cache: PipelineCache,
+    /// Created on first use of [begin_frame](VkInit::begin_frame).
+    pub(crate) frame_loop: Option<FrameLoop>,
+    /// Shared samplers of [get_or_create_sampler](VkInit::get_or_create_sampler).
+    pub(crate) samplers: Mutex<HashMap<SamplerInfo, Sampler>>,
+    #[cfg(feature = "renderdoc")]
+    pub(crate) renderdoc: Option<crate::renderdoc::RenderDocApi>,
+}
+
+/// Waits for or signals a binary ```semaphore``` at ```stage_mask```.
+pub(crate) fn semaphore_submit_info(
+    semaphore: Semaphore,
+    stage_mask: PipelineStageFlags2,
+) -> SemaphoreSubmitInfo {
+    SemaphoreSubmitInfo::builder()
+        .semaphore(semaphore)
+        .stage_mask(stage_mask)
+        .build()
 }
 
+// VkInit is shared between threads, any new field that is not Send and Sync fails to compile here.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<VkInit>();
+};
+
 /// Wrapper around presentation resources.
 /// - Depth image
 pub struct Head {
@@ -50,7 +120,14 @@ pub struct Head {
     pub surface_info: SurfaceInfo,
     pub depth_format: Format,
     pub depth_format_sizeof: usize,
+    /// Has the [msaa_samples](VkInitCreateInfo::msaa_samples) of the color attachment.
     pub depth_image: VMAImage,
+    pub msaa_samples: SampleCountFlags,
+    /// Multisampled color attachment resolved into the swapchain image by [begin_rendering](VkInit::begin_rendering),
+    /// ```None``` for [msaa_samples](VkInitCreateInfo::msaa_samples) of ```TYPE_1```.
+    pub msaa_image: Option<VMAImage>,
+    /// Images acquired but not presented yet, see [drain_presentation](VkInit::drain_presentation).
+    pub(crate) acquired_images: Mutex<Vec<AcquiredImage>>,
 }
 
 /// Abstraction over queue capability and command types since dedicated queues may not be available.
@@ -61,14 +138,16 @@ pub struct Head {
 /// # extern crate winit;
 /// # use vku::*;
 /// # use ash::vk::*;
+/// # if VkInit::load_entry().is_err() { return Ok(()); }
 /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
 /// # let size = [800_u32, 600_u32];
 /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
 /// # let create_info = VkInitCreateInfo::default();
-/// let init = VkInit::new(Some(&window), Some(size), create_info)?;
+/// let init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
 ///
 /// let (compute_queue, compute_queue_family_index) = init.get_queue(CmdType::Compute);
 /// # Ok::<(), vku::Error>(())
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CmdType {
     /// Graphics | Transfer | Compute
     Any,
@@ -77,30 +156,259 @@ pub enum CmdType {
     Compute,
 }
 
+/// Capabilities a dedicated queue has to provide to be returned by [get_queue_for](VkInit::get_queue_for).
+///
+/// The default requires nothing beyond the [CmdType].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct QueueRequirements {
+    /// Granularity of the image regions that will be transferred.
+    ///
+    /// A zero extent means only whole mip levels are transferred, which every queue supports.
+    pub image_granularity: Extent3D,
+    /// Queue has to support timestamp queries.
+    pub timestamps: bool,
+    /// Queue has to support sparse memory binding.
+    pub sparse_binding: bool,
+}
+
 /// Return info about the selected physical device and its capabilities.
 ///
 /// The unified queue is guarenteed to be present and can process any command.
 ///
 /// Dedicated transfer and compute queues are optional.
+#[non_exhaustive]
 pub struct PhysicalDeviceInfo {
     pub name: String,
+    /// Index into the enumerated physical devices, see [GpuSelection::Index].
+    pub device_index: usize,
+    pub device_type: PhysicalDeviceType,
+    /// PCI vendor ID, e.g. ```0x10DE``` for NVIDIA.
+    pub vendor_id: u32,
+    pub device_id: u32,
+    /// Highest Vulkan version supported by the device.
+    pub api_version: u32,
+    /// Vendor-specific encoding, see [driver_version_string](PhysicalDeviceInfo::driver_version_string).
+    pub driver_version: u32,
+    /// Supported device extensions, see [supports_extension](PhysicalDeviceInfo::supports_extension).
+    pub supported_extensions: Vec<String>,
     pub unified_queue_family_index: u32,
     pub transfer_queue_family_index: Option<u32>,
     pub compute_queue_family_index: Option<u32>,
+    /// Properties of all queue families, indexed by queue family index.
+    pub queue_family_properties: Vec<QueueFamilyProperties>,
     pub features: PhysicalDeviceFeatures,
+    /// Core and Vulkan 1.1/1.2/1.3 features, see [supports_feature](VkInit::supports_feature).
+    pub supported_features: DeviceFeatures,
     pub memory_props: PhysicalDeviceMemoryProperties,
     pub limits: PhysicalDeviceLimits,
+    /// Only present if [VK_EXT_vertex_attribute_divisor](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_EXT_vertex_attribute_divisor.html) is supported.
+    /// The extension is enabled automatically in that case.
+    pub vertex_attribute_divisor: Option<VertexAttributeDivisorSupport>,
+    /// [VK_EXT_image_view_min_lod](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_EXT_image_view_min_lod.html) is supported and enabled automatically.
+    pub image_view_min_lod: bool,
+    /// [VK_EXT_rasterization_order_attachment_access](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_EXT_rasterization_order_attachment_access.html)
+    /// color attachment access is supported and enabled automatically. See [attachment_feedback](VkInit::attachment_feedback).
+    pub rasterization_order_attachment_access: bool,
+    /// [VK_EXT_swapchain_maintenance1](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_EXT_swapchain_maintenance1.html) is supported and enabled automatically.
+    /// Requires ```VK_EXT_surface_maintenance1``` in the [additional instance extensions](VkInitCreateInfo::additional_instance_extensions).
+    pub swapchain_maintenance1: bool,
+    /// [VK_EXT_attachment_feedback_loop_layout](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_EXT_attachment_feedback_loop_layout.html)
+    /// is supported and enabled automatically. See [feedback_loop_layout](VkInit::feedback_loop_layout).
+    pub attachment_feedback_loop_layout: bool,
+    /// [VK_EXT_vertex_input_dynamic_state](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_EXT_vertex_input_dynamic_state.html)
+    /// is supported and enabled automatically. See [cmd_set_vertex_input](VkInit::cmd_set_vertex_input).
+    pub vertex_input_dynamic_state: bool,
+    /// [VK_KHR_incremental_present](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_incremental_present.html)
+    /// is supported and enabled automatically. See [present_with_damage](VkInit::present_with_damage).
+    pub incremental_present: bool,
+    /// [VK_EXT_device_fault](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_EXT_device_fault.html)
+    /// is supported and enabled automatically. Fault info is logged when a [watched fence wait](VkInit::wait_for_fences_watched) loses the device.
+    pub device_fault: bool,
+    /// [VK_NV_device_diagnostic_checkpoints](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_NV_device_diagnostic_checkpoints.html)
+    /// is supported and enabled with [crash_diagnostics](VkInitCreateInfo::crash_diagnostics).
+    pub diagnostic_checkpoints: bool,
+    /// [VK_NV_device_diagnostics_config](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_NV_device_diagnostics_config.html)
+    /// is supported and enabled with [crash_diagnostics](VkInitCreateInfo::crash_diagnostics).
+    pub diagnostics_config: bool,
+    /// ```bufferDeviceAddressCaptureReplay``` is supported and enabled with [crash_diagnostics](VkInitCreateInfo::crash_diagnostics).
+    pub buffer_device_address_capture_replay: bool,
+    /// [VK_KHR_pipeline_executable_properties](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_pipeline_executable_properties.html)
+    /// is supported and enabled automatically. See [executable_statistics](crate::pipeline_builder::VKUPipeline::executable_statistics).
+    pub pipeline_executable_properties: bool,
+    /// Platform extensions for sharing memory and semaphores with other APIs, only enabled with the ```external``` feature.
+    pub external_handles: ExternalHandleSupport,
+}
+
+impl PhysicalDeviceInfo {
+    /// Size of all device-local memory heaps in bytes.
+    pub fn device_local_memory(&self) -> u64 {
+        self.memory_props.memory_heaps[..self.memory_props.memory_heap_count as usize]
+            .iter()
+            .filter(|heap| heap.flags.contains(MemoryHeapFlags::DEVICE_LOCAL))
+            .map(|heap| heap.size)
+            .sum()
+    }
+
+    /// Checks whether the device supports the extension ```name```, enabled or not.
+    pub fn supports_extension(&self, name: &CStr) -> bool {
+        name.to_str()
+            .map(|name| self.supported_extensions.iter().any(|ext| ext == name))
+            .unwrap_or(false)
+    }
+
+    /// Driver version decoded with the scheme of the vendor, see [decode_driver_version](PhysicalDeviceInfo::decode_driver_version).
+    pub fn driver_version_string(&self) -> String {
+        Self::decode_driver_version(self.vendor_id, self.driver_version)
+    }
+
+    /// NVIDIA uses 10.8.8.6 bits, Intel on Windows 18.14 bits and everyone else the Vulkan version encoding.
+    pub fn decode_driver_version(vendor_id: u32, driver_version: u32) -> String {
+        match vendor_id {
+            0x10DE => format!(
+                "{}.{}.{}.{}",
+                driver_version >> 22,
+                (driver_version >> 14) & 0xFF,
+                (driver_version >> 6) & 0xFF,
+                driver_version & 0x3F
+            ),
+            0x8086 if cfg!(windows) => {
+                format!("{}.{}", driver_version >> 14, driver_version & 0x3FFF)
+            }
+            _ => version_string(driver_version),
+        }
+    }
+
+    fn vendor_name(&self) -> &'static str {
+        match self.vendor_id {
+            0x1002 => "AMD",
+            0x1010 => "ImgTec",
+            0x106B => "Apple",
+            0x10DE => "NVIDIA",
+            0x13B5 => "ARM",
+            0x5143 => "Qualcomm",
+            0x8086 => "Intel",
+            0x10005 => "Mesa",
+            _ => "unknown vendor",
+        }
+    }
+}
+
+impl std::fmt::Display for PhysicalDeviceInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} ({:?})", self.name, self.device_type)?;
+        writeln!(
+            f,
+            "Vendor 0x{:04X} ({}), device 0x{:04X}",
+            self.vendor_id,
+            self.vendor_name(),
+            self.device_id
+        )?;
+        writeln!(
+            f,
+            "Driver {}, Vulkan {}",
+            self.driver_version_string(),
+            version_string(self.api_version)
+        )?;
+        writeln!(
+            f,
+            "Queue families: unified {}, transfer {:?}, compute {:?}",
+            self.unified_queue_family_index,
+            self.transfer_queue_family_index,
+            self.compute_queue_family_index
+        )?;
+        writeln!(
+            f,
+            "Device local memory: {} MiB",
+            self.device_local_memory() / (1024 * 1024)
+        )?;
+        writeln!(
+            f,
+            "Limits: 2D images up to {} texels, {} bytes of push constants, {} bound descriptor sets",
+            self.limits.max_image_dimension2_d,
+            self.limits.max_push_constants_size,
+            self.limits.max_bound_descriptor_sets
+        )?;
+        writeln!(f, "Extensions ({}):", self.supported_extensions.len())?;
+        for extension in &self.supported_extensions {
+            writeln!(f, "  {extension}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Supported extensions for [external handles](crate::ExternalHandle), all ```false``` without the ```external``` feature.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExternalHandleSupport {
+    /// [VK_KHR_external_memory_fd](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_external_memory_fd.html)
+    pub memory_fd: bool,
+    /// [VK_EXT_external_memory_dma_buf](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_EXT_external_memory_dma_buf.html)
+    pub memory_dma_buf: bool,
+    /// [VK_KHR_external_memory_win32](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_external_memory_win32.html)
+    pub memory_win32: bool,
+    /// [VK_KHR_external_semaphore_fd](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_external_semaphore_fd.html)
+    pub semaphore_fd: bool,
+    /// [VK_KHR_external_semaphore_win32](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_KHR_external_semaphore_win32.html)
+    pub semaphore_win32: bool,
+}
+
+impl ExternalHandleSupport {
+    fn query(supported_extensions: &[String]) -> Self {
+        let supported = |name: &CStr| {
+            name.to_str()
+                .is_ok_and(|name| supported_extensions.iter().any(|ext| ext == name))
+        };
+        let memory_fd = supported(KhrExternalMemoryFdFn::name());
+        Self {
+            memory_fd,
+            memory_dma_buf: memory_fd && supported(ExtExternalMemoryDmaBufFn::name()),
+            memory_win32: supported(KhrExternalMemoryWin32Fn::name()),
+            semaphore_fd: supported(KhrExternalSemaphoreFdFn::name()),
+            semaphore_win32: supported(KhrExternalSemaphoreWin32Fn::name()),
+        }
+    }
+
+    fn extensions(&self) -> Vec<&'static CStr> {
+        [
+            (self.memory_fd, KhrExternalMemoryFdFn::name()),
+            (self.memory_dma_buf, ExtExternalMemoryDmaBufFn::name()),
+            (self.memory_win32, KhrExternalMemoryWin32Fn::name()),
+            (self.semaphore_fd, KhrExternalSemaphoreFdFn::name()),
+            (self.semaphore_win32, KhrExternalSemaphoreWin32Fn::name()),
+        ]
+        .into_iter()
+        .filter_map(|(supported, name)| supported.then_some(name))
+        .collect()
+    }
+}
+
+/// Instance rate divisor capabilities of the physical device.
+#[derive(Clone, Copy, Debug)]
+pub struct VertexAttributeDivisorSupport {
+    pub max_vertex_attrib_divisor: u32,
+    /// A divisor of 0 repeats the first instance's attributes for all instances.
+    pub zero_divisor: bool,
 }
 
 /// Return info about the created surface and its capabilities.
+#[non_exhaustive]
 pub struct SurfaceInfo {
     pub min_extent: Extent2D,
     pub max_extent: Extent2D,
+    /// Extent of the swapchain, the window size with width and height swapped for [quarter turns](SurfaceRotation::swaps_extent).
     pub current_extent: Extent2D,
     pub image_count: u32,
+    /// Chosen from [present_mode_preferences](VkInitCreateInfo::present_mode_preferences).
     pub present_mode: PresentModeKHR,
+    /// Present modes of the surface, see [set_present_mode](VkInit::set_present_mode).
+    pub supported_present_modes: Vec<PresentModeKHR>,
+    /// Chosen from [surface_format_preferences](VkInitCreateInfo::surface_format_preferences).
     pub color_format: SurfaceFormatKHR,
+    /// ```IDENTITY``` if supported, the current transform otherwise, see [rotation](SurfaceInfo::rotation).
     pub pre_transform: SurfaceTransformFlagsKHR,
+    /// Usage of the swapchain images, ```TRANSFER_SRC``` is added if supported for [capture_frame](VkInit::capture_frame).
+    pub image_usage: ImageUsageFlags,
+    /// Physical pixels per logical pixel of the window, kept across swapchain recreation and updated with [set_scale_factor](VkInit::set_scale_factor).
+    pub scale_factor: f64,
 }
 
 impl VkInit {
@@ -121,6 +429,7 @@ impl VkInit {
     /// use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
     /// use vku::{VkInitCreateInfo, VkInit};
     ///
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
     /// let event_loop: EventLoop<()> = EventLoopBuilder::default().build();
     /// let size = [800_u32, 600_u32];
     /// let window = WindowBuilder::new()
@@ -137,35 +446,75 @@ impl VkInit {
         window_size: Option<[u32; 2]>,
         create_info: VkInitCreateInfo,
     ) -> Result<Self, Error> {
-        unsafe {
-            let (display_h, window_h) = match raw_window_handles {
-                Some(handles) => (
-                    Some(handles.raw_display_handle()),
-                    Some(handles.raw_window_handle()),
-                ),
-                None => (None, None),
-            };
-            #[cfg(feature = "linked")]
-            let entry = ash::Entry::linked();
+        let handles = raw_window_handles
+            .map(|handles| (handles.raw_display_handle(), handles.raw_window_handle()));
+        Self::from_raw_handles(handles, window_size, 1.0, create_info)
+    }
 
-            #[cfg(not(feature = "linked"))]
-            let entry = ash::Entry::load()?;
+    /// Same as [new](VkInit::new) with a window, e.g. ```VkInit::new_windowed(&window, size, window.scale_factor(), create_info)``` for winit.
+    ///
+    /// ```window_size``` is in physical pixels, ```scale_factor``` is stored as [SurfaceInfo::scale_factor].
+    /// [new](VkInit::new) uses a scale factor of 1.0.
+    pub fn new_windowed<T: HasRawDisplayHandle + HasRawWindowHandle>(
+        window: &T,
+        window_size: [u32; 2],
+        scale_factor: f64,
+        create_info: VkInitCreateInfo,
+    ) -> Result<Self, Error> {
+        let handles = (window.raw_display_handle(), window.raw_window_handle());
+        Self::from_raw_handles(Some(handles), Some(window_size), scale_factor, create_info)
+    }
+
+    /// Same as [new](VkInit::new) without a window, functions requiring a [Head] return [HeadCallOnHeadlessInstance](Error::HeadCallOnHeadlessInstance).
+    pub fn new_headless(create_info: VkInitCreateInfo) -> Result<Self, Error> {
+        Self::from_raw_handles(None, None, 1.0, create_info)
+    }
+
+    fn from_raw_handles(
+        handles: Option<(RawDisplayHandle, RawWindowHandle)>,
+        window_size: Option<[u32; 2]>,
+        scale_factor: f64,
+        mut create_info: VkInitCreateInfo,
+    ) -> Result<Self, Error> {
+        if create_info.auto_env {
+            create_info.apply_env_overrides();
+        }
+
+        unsafe {
+            let (display_h, window_h) = handles.unzip();
+            let (entry, loader_info) = Self::load_entry()?;
+            if loader_info.instance_version < create_info.vk_version {
+                return Err(Error::InstanceVersionNotSupported {
+                    requested: version_string(create_info.vk_version),
+                    supported: version_string(loader_info.instance_version),
+                });
+            }
 
-            let (instance, debug_loader, debug_messenger) =
-                Self::create_instance_and_debug(&entry, display_h, &create_info)?;
+            let debug_message_capture = Box::<DebugMessageCapture>::default();
+            let (instance, debug_loader, debug_messenger) = Self::create_instance_and_debug(
+                &entry,
+                display_h,
+                &create_info,
+                &debug_message_capture,
+            )?;
             let (physical_device, physical_device_info) =
                 Self::create_physical_device(&instance, &create_info)?;
-            let device = Self::create_device(
+            let (device, enabled_device_extensions) = Self::create_device(
                 &instance,
                 &physical_device,
                 &physical_device_info,
                 &create_info,
             )?;
+            provenance::register_instance(&device, create_info.capture_resource_backtraces, 0);
             let mut allocator = Self::create_allocator(&instance, &physical_device, &device)?;
             let (unified_queue, transfer_queue, compute_queue) =
                 Self::create_queues(&device, &physical_device_info)?;
+            let vertex_input_fn =
+                Self::load_vertex_input_fn(&instance, &device, &physical_device_info);
+            let crash_diagnostics =
+                CrashDiagnostics::load(&instance, &device, &physical_device_info);
 
-            let head = if let (Some(display_handle), Some(window_handle), Some(window_size)) =
+            let mut head = if let (Some(display_handle), Some(window_handle), Some(window_size)) =
                 (display_h, window_h, window_size)
             {
                 Some(Self::create_head(
@@ -182,108 +531,16 @@ impl VkInit {
             } else {
                 None
             };
-
-            //TODO: Why is RenderDoc crashing when Instance debug name is set?
-            //TODO: Why are swapchain, swapchain_images, and swapchain_image_views names not set in RenderDoc?
-            if let Some(dbg) = &debug_loader {
-                Self::set_debug_object_name_static(
-                    dbg,
-                    &device,
-                    physical_device.as_raw(),
-                    ObjectType::PHYSICAL_DEVICE,
-                    "VKU_Physical_Device".to_string(),
-                )?;
-                Self::set_debug_object_name_static(
-                    dbg,
-                    &device,
-                    device.handle().as_raw(),
-                    ObjectType::DEVICE,
-                    "VKU_Device".to_string(),
-                )?;
-                Self::set_debug_object_name_static(
-                    dbg,
-                    &device,
-                    unified_queue.as_raw(),
-                    ObjectType::QUEUE,
-                    "VKU_Unified_Queue".to_string(),
-                )?;
-                if let Some(transfer_queue) = transfer_queue {
-                    Self::set_debug_object_name_static(
-                        dbg,
-                        &device,
-                        transfer_queue.as_raw(),
-                        ObjectType::QUEUE,
-                        "VKU_Transfer_Queue".to_string(),
-                    )?;
-                }
-
-                if let Some(compute_queue) = compute_queue {
-                    Self::set_debug_object_name_static(
-                        dbg,
-                        &device,
-                        compute_queue.as_raw(),
-                        ObjectType::QUEUE,
-                        "VKU_Compute_Queue".to_string(),
-                    )?;
-                }
-
-                if let Some(head) = &head {
-                    Self::set_debug_object_name_static(
-                        dbg,
-                        &device,
-                        head.swapchain.as_raw(),
-                        ObjectType::SWAPCHAIN_KHR,
-                        "VKU_SwapchainKHR".to_string(),
-                    )?;
-
-                    Self::set_debug_object_name_static(
-                        dbg,
-                        &device,
-                        head.depth_image.image.as_raw(),
-                        ObjectType::IMAGE,
-                        "VKU_DepthImage".to_string(),
-                    )?;
-                    Self::set_debug_object_name_static(
-                        dbg,
-                        &device,
-                        head.depth_image.image_view.as_raw(),
-                        ObjectType::IMAGE_VIEW,
-                        "VKU_DepthImage_View".to_string(),
-                    )?;
-                    Self::set_debug_object_name_static(
-                        dbg,
-                        &device,
-                        head.depth_image.allocation.memory().as_raw(),
-                        ObjectType::DEVICE_MEMORY,
-                        "VKU_DepthImage_Memory".to_string(),
-                    )?;
-
-                    for (i, image) in head.swapchain_images.iter().enumerate() {
-                        Self::set_debug_object_name_static(
-                            dbg,
-                            &device,
-                            image.as_raw(),
-                            ObjectType::IMAGE,
-                            format!("VKU_Swapchain_Image_{i}"),
-                        )?;
-                    }
-
-                    for (i, image_view) in head.swapchain_image_views.iter().enumerate() {
-                        Self::set_debug_object_name_static(
-                            dbg,
-                            &device,
-                            image_view.as_raw(),
-                            ObjectType::IMAGE_VIEW,
-                            format!("VKU_Swapchain_Image_View_{i}"),
-                        )?;
-                    }
-                }
+            if let Some(head) = head.as_mut() {
+                head.surface_info.scale_factor = scale_factor;
             }
 
+            let watchdog = GpuWatchdog::new(create_info.gpu_watchdog);
+            let staging_pool = StagingPool::new(create_info.max_staging_pool_bytes);
             trace!("Created VkInit");
 
-            Ok(Self {
-                allocator: ManuallyDrop::new(allocator),
+            let mut vk_init = Self {
+                allocator: ManuallyDrop::new(SharedAllocator::new(allocator)),
                 entry,
                 instance,
                 debug_loader,
@@ -294,15 +551,85 @@ impl VkInit {
                 compute_queue,
                 transfer_queue,
                 physical_device_info,
+                enabled_device_extensions,
                 head,
+                offscreen_head: None,
                 create_info,
-            })
+                staging_pool,
+                deletion_queue: Mutex::default(),
+                frame_scheme: FrameScheme::default(),
+                device_generation: 0,
+                vertex_input_fn,
+                watchdog,
+                crash_diagnostics,
+                loader_info,
+                debug_message_capture,
+                destroyed: false,
+                mip_pipeline: Mutex::new(None),
+                pipeline_cache: PipelineCache::null(),
+                frame_loop: None,
+                samplers: Mutex::default(),
+                #[cfg(feature = "renderdoc")]
+                renderdoc: crate::renderdoc::RenderDocApi::load(),
+            };
+            vk_init.update_frame_scheme();
+            vk_init.name_core_objects()?;
+            vk_init.name_head_objects()?;
+
+            Ok(vk_init)
         }
     }
 
+    /// Logs all [live_resources](VkInit::live_resources) that were not destroyed yet in debug builds.
+    ///
+    /// With [strict_teardown](VkInitCreateInfo::strict_teardown) set, returns [ResourcesStillAlive](Error::ResourcesStillAlive) instead.
+    /// Destroy the reported resources and call destroy again in that case.
+    ///
+    /// Images that were acquired but not presented are released first, see [drain_presentation](VkInit::drain_presentation).
+    ///
+    /// Destroying an already destroyed instance logs a warning and is a no-op.
+    /// Dropping an instance without destroying it logs a warning in debug builds, the device and its memory are leaked.
     pub fn destroy(&mut self) -> Result<(), Error> {
+        if self.destroyed {
+            warn!("VkInit::destroy called on an already destroyed instance");
+            return Ok(());
+        }
+        if self.head.is_some() {
+            self.drain_presentation()?;
+        }
         unsafe {
             self.device.device_wait_idle()?;
+            if let Some(head) = &mut self.head {
+                head.destroy_attachments(&self.device, &mut self.allocator.lock())?;
+            }
+            self.destroy_offscreen_head()?;
+            self.staging_pool
+                .destroy(&self.device, &mut self.allocator.lock())?;
+            self.deletion_queue
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner())
+                .destroy(&self.device, self.allocator.get_mut())?;
+            self.destroy_mip_pipeline();
+            self.destroy_loaded_pipeline_cache();
+            self.destroy_frame_loop();
+            self.destroy_cached_samplers();
+
+            let alive = self.live_resources();
+            if !alive.is_empty() {
+                error!(
+                    "VkInit destroyed with {} resources still alive:",
+                    alive.len()
+                );
+                for resource in &alive {
+                    error!("{resource}");
+                }
+                if self.create_info.strict_teardown {
+                    return Err(Error::ResourcesStillAlive(alive));
+                }
+            }
+            provenance::unregister_instance(&self.device);
+            allocation_lifetime::forget_device(&self.device);
+
             if let Some(head) = &mut self.head {
                 for image_view in &head.swapchain_image_views {
                     self.device.destroy_image_view(*image_view, None);
@@ -310,8 +637,6 @@ impl VkInit {
                 head.swapchain_loader
                     .destroy_swapchain(head.swapchain, None);
                 head.surface_loader.destroy_surface(head.surface, None);
-                head.depth_image
-                    .destroy(&self.device, &mut self.allocator)?;
             }
             if let Some(dbg_loader) = &self.debug_loader {
                 if let Some(dbg_msg) = self.debug_messenger {
@@ -325,9 +650,17 @@ impl VkInit {
             // self.instance.destroy_instance(None); seg faults for no apparant reason
         }
 
+        self.destroyed = true;
         Ok(())
     }
 
+    /// Checks whether the device extension ```name``` was enabled, e.g. automatically because it was supported.
+    pub fn device_extension_enabled(&self, name: &CStr) -> bool {
+        name.to_str()
+            .map(|name| self.enabled_device_extensions.iter().any(|ext| ext == name))
+            .unwrap_or(false)
+    }
+
     pub fn head(&self) -> &Head {
         self.head.as_ref().expect("called head() on headless vku")
     }
@@ -344,47 +677,7 @@ impl VkInit {
         obj_type: ObjectType,
         name: String,
     ) -> Result<(), Error> {
-        if let Some(dbg) = &self.debug_loader {
-            let c_name = CString::new(name)?;
-
-            let name_info = DebugUtilsObjectNameInfoEXT::builder()
-                .object_name(&c_name)
-                .object_handle(obj_handle)
-                .object_type(obj_type)
-                .build();
-
-            unsafe { dbg.set_debug_utils_object_name(self.device.handle(), &name_info)? };
-        }
-        Ok(())
-    }
-
-    pub fn insert_debug_label(&self, cmd_buffer: &CommandBuffer, name: &str) -> Result<(), Error> {
-        if let Some(dbg) = &self.debug_loader {
-            let label_info = DebugUtilsLabelEXT::builder()
-                .label_name(unsafe { CStr::from_ptr(name.as_ptr() as *const i8) })
-                .build();
-
-            unsafe { dbg.cmd_insert_debug_utils_label(*cmd_buffer, &label_info) };
-        }
-        Ok(())
-    }
-
-    pub fn begin_debug_label(&self, cmd_buffer: &CommandBuffer, name: &str) -> Result<(), Error> {
-        if let Some(dbg) = &self.debug_loader {
-            let label_info = DebugUtilsLabelEXT::builder()
-                .label_name(unsafe { CStr::from_ptr(name.as_ptr() as *const i8) })
-                .build();
-
-            unsafe { dbg.cmd_begin_debug_utils_label(*cmd_buffer, &label_info) };
-        }
-        Ok(())
-    }
-
-    pub fn end_debug_label(&self, cmd_buffer: &CommandBuffer) -> Result<(), Error> {
-        if let Some(dbg) = &self.debug_loader {
-            unsafe { dbg.cmd_end_debug_utils_label(*cmd_buffer) };
-        }
-        Ok(())
+        self.set_debug_object_name_fmt(obj_handle, obj_type, format_args!("{name}"))
     }
 
     pub fn create_cmd_pool(&self, cmd_type: CmdType) -> Result<CommandPool, Error> {
@@ -394,6 +687,11 @@ impl VkInit {
             .flags(CommandPoolCreateFlags::RESET_COMMAND_BUFFER);
 
         let command_pool = unsafe { self.device.create_command_pool(&create_info, None)? };
+        self.set_debug_object_name_fmt(
+            command_pool.as_raw(),
+            ObjectType::COMMAND_POOL,
+            format_args!("VKU_{cmd_type:?}_Cmd_Pool"),
+        )?;
         Ok(command_pool)
     }
 
@@ -401,6 +699,16 @@ impl VkInit {
         &self,
         pool: &CommandPool,
         count: u32,
+    ) -> Result<Vec<CommandBuffer>, Error> {
+        self.create_command_buffers_named(pool, count, "VKU")
+    }
+
+    /// Same as [create_command_buffers](VkInit::create_command_buffers), but names them ```{base_name}_Cmd_Buffer_{i}```.
+    pub fn create_command_buffers_named(
+        &self,
+        pool: &CommandPool,
+        count: u32,
+        base_name: &str,
     ) -> Result<Vec<CommandBuffer>, Error> {
         let create_info = CommandBufferAllocateInfo::builder()
             .command_pool(*pool)
@@ -408,23 +716,50 @@ impl VkInit {
             .command_buffer_count(count);
 
         let alloc = unsafe { self.device.allocate_command_buffers(&create_info)? };
+        for (i, cmd_buffer) in alloc.iter().enumerate() {
+            self.set_debug_object_name_fmt(
+                cmd_buffer.as_raw(),
+                ObjectType::COMMAND_BUFFER,
+                format_args!("{base_name}_Cmd_Buffer_{i}"),
+            )?;
+        }
         Ok(alloc)
     }
 
     /// Creates a signaled fence.
     pub fn create_fence(&self) -> Result<Fence, Error> {
+        self.create_fence_named("VKU")
+    }
+
+    /// Same as [create_fence](VkInit::create_fence), but names it ```{base_name}_Fence```.
+    pub fn create_fence_named(&self, base_name: &str) -> Result<Fence, Error> {
         let create_info = FenceCreateInfo::builder().flags(FenceCreateFlags::SIGNALED);
         let fence = unsafe { self.device.create_fence(&create_info, None)? };
+        self.set_debug_object_name_fmt(
+            fence.as_raw(),
+            ObjectType::FENCE,
+            format_args!("{base_name}_Fence"),
+        )?;
 
         Ok(fence)
     }
 
     /// Creates a Vec of signaled fence.
     pub fn create_fences(&self, count: usize) -> Result<Vec<Fence>, Error> {
+        self.create_fences_named(count, "VKU")
+    }
+
+    /// Same as [create_fences](VkInit::create_fences), but names them ```{base_name}_Fence_{i}```.
+    pub fn create_fences_named(&self, count: usize, base_name: &str) -> Result<Vec<Fence>, Error> {
         let mut fences = Vec::new();
-        for _ in 0..count {
+        for i in 0..count {
             let create_info = FenceCreateInfo::builder().flags(FenceCreateFlags::SIGNALED);
             let fence = unsafe { self.device.create_fence(&create_info, None)? };
+            self.set_debug_object_name_fmt(
+                fence.as_raw(),
+                ObjectType::FENCE,
+                format_args!("{base_name}_Fence_{i}"),
+            )?;
             fences.push(fence);
         }
 
@@ -440,17 +775,41 @@ impl VkInit {
     }
 
     pub fn create_semaphore(&self) -> Result<Semaphore, Error> {
+        self.create_semaphore_named("VKU")
+    }
+
+    /// Same as [create_semaphore](VkInit::create_semaphore), but names it ```{base_name}_Semaphore```.
+    pub fn create_semaphore_named(&self, base_name: &str) -> Result<Semaphore, Error> {
         let create_info = SemaphoreCreateInfo::default();
         let semaphore = unsafe { self.device.create_semaphore(&create_info, None)? };
+        self.set_debug_object_name_fmt(
+            semaphore.as_raw(),
+            ObjectType::SEMAPHORE,
+            format_args!("{base_name}_Semaphore"),
+        )?;
 
         Ok(semaphore)
     }
 
     pub fn create_semaphores(&self, count: usize) -> Result<Vec<Semaphore>, Error> {
+        self.create_semaphores_named(count, "VKU")
+    }
+
+    /// Same as [create_semaphores](VkInit::create_semaphores), but names them ```{base_name}_Semaphore_{i}```.
+    pub fn create_semaphores_named(
+        &self,
+        count: usize,
+        base_name: &str,
+    ) -> Result<Vec<Semaphore>, Error> {
         let mut semaphores = Vec::new();
-        for _ in 0..count {
+        for i in 0..count {
             let create_info = SemaphoreCreateInfo::default();
             let semaphore = unsafe { self.device.create_semaphore(&create_info, None)? };
+            self.set_debug_object_name_fmt(
+                semaphore.as_raw(),
+                ObjectType::SEMAPHORE,
+                format_args!("{base_name}_Semaphore_{i}"),
+            )?;
             semaphores.push(semaphore);
         }
 
@@ -474,29 +833,35 @@ impl VkInit {
     }
 
     /// Acquires next image and signals sempahore ```acquire_img_semaphore```.
+    ///
+    /// Waits at most ```timeout``` for an image, ```None``` waits indefinitely.
+    /// An out of date swapchain returns [AcquireResult::OutOfDate] instead of an error, recreate it with [on_resize](VkInit::on_resize).
+    /// The image counts as acquired until it is [presented](VkInit::present) or [drained](VkInit::drain_presentation).
     pub fn acquire_next_swapchain_image(
         &self,
         acquire_img_semaphore: Semaphore,
-    ) -> Result<(usize, Image, ImageView, bool), Error> {
+        timeout: Option<std::time::Duration>,
+    ) -> Result<AcquireResult, Error> {
         let Some(head) = self.head.as_ref() else {
             return Err(Error::HeadCallOnHeadlessInstance);
         };
-        let (index, sub_optimal) = unsafe {
+        let timeout_ns = timeout.map_or(u64::MAX, |timeout| {
+            u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX)
+        });
+        let result = unsafe {
             head.swapchain_loader.acquire_next_image(
                 head.swapchain,
-                1000 * 1000 * 1000, //One second
+                timeout_ns,
                 acquire_img_semaphore,
                 Fence::null(),
-            )?
+            )
         };
-        let swapchain_image = head.swapchain_images[index as usize];
-        let swapchain_image_view = head.swapchain_image_views[index as usize];
-        Ok((
-            index as usize,
-            swapchain_image,
-            swapchain_image_view,
-            sub_optimal,
-        ))
+        let acquired =
+            AcquireResult::from_vk(result, &head.swapchain_images, &head.swapchain_image_views)?;
+        if let AcquireResult::Ready { index, .. } = acquired {
+            head.track_acquired(index as u32, acquire_img_semaphore);
+        }
+        Ok(acquired)
     }
 
     pub fn begin_cmd_buffer(&self, cmd_buffer: &CommandBuffer) -> Result<(), Error> {
@@ -511,54 +876,23 @@ impl VkInit {
         Ok(())
     }
 
+    /// Clears and renders the whole surface, or the [offscreen head](VkInit::create_offscreen_head) without a [Head].
+    ///
+    /// With [msaa_samples](VkInitCreateInfo::msaa_samples) above ```TYPE_1``` the multisampled [msaa_image](Head::msaa_image) is rendered to
+    /// and resolved into ```swapchain_image_view```, both have to be in ```COLOR_ATTACHMENT_OPTIMAL```.
+    ///
+    /// See [begin_rendering_ex](VkInit::begin_rendering_ex) for partial rendering
+    /// and [begin_rendering_with](VkInit::begin_rendering_with) for other attachment ops.
     pub fn begin_rendering(
         &self,
         swapchain_image_view: &ImageView,
         cmd_buffer: &CommandBuffer,
     ) -> Result<(), Error> {
-        let Some(head) = self.head.as_ref() else {
-            return Err(Error::HeadCallOnHeadlessInstance);
-        };
-
-        let clear_color_value = ClearValue {
-            color: head.clear_color_value,
-        };
-        let clear_depth_stencil_value = ClearValue {
-            depth_stencil: head.clear_depth_stencil_value,
-        };
-
-        let render_area = Rect2D::builder()
-            .offset(Offset2D { x: 0, y: 0 })
-            .extent(head.surface_info.current_extent);
-
-        let color_attachment_info = [RenderingAttachmentInfo::builder()
-            .image_view(*swapchain_image_view)
-            .image_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
-            .load_op(AttachmentLoadOp::CLEAR)
-            .store_op(AttachmentStoreOp::STORE)
-            .clear_value(clear_color_value)
-            .build()];
-
-        let depth_attachment_info = RenderingAttachmentInfo::builder()
-            .image_view(head.depth_image.image_view)
-            .image_layout(ImageLayout::DEPTH_ATTACHMENT_OPTIMAL)
-            .load_op(AttachmentLoadOp::CLEAR)
-            .store_op(AttachmentStoreOp::STORE)
-            .clear_value(clear_depth_stencil_value)
-            .build();
-
-        let rendering_begin_info = RenderingInfo::builder()
-            .render_area(*render_area)
-            .layer_count(1)
-            .color_attachments(&color_attachment_info)
-            .depth_attachment(&depth_attachment_info);
-
-        unsafe {
-            self.device
-                .cmd_begin_rendering(*cmd_buffer, &rendering_begin_info);
-        }
-
-        Ok(())
+        self.begin_rendering_with(
+            swapchain_image_view,
+            cmd_buffer,
+            &RenderingConfig::default(),
+        )
     }
 
     pub fn end_rendering(&self, cmd_buffer: &CommandBuffer) {
@@ -567,6 +901,7 @@ impl VkInit {
         }
     }
 
+    /// Ends ```cmd_buffer``` and submits it, see [submit_cmd_buffers](VkInit::submit_cmd_buffers).
     pub fn end_and_submit_cmd_buffer(
         &self,
         cmd_buffer: &CommandBuffer,
@@ -577,29 +912,170 @@ impl VkInit {
         wait_dst_flags: &[PipelineStageFlags],
     ) -> Result<(), Error> {
         unsafe { self.device.end_command_buffer(*cmd_buffer)? };
+        self.submit_cmd_buffers(
+            &[*cmd_buffer],
+            cmd_type,
+            fence,
+            wait_sem,
+            signal_sem,
+            wait_dst_flags,
+        )
+    }
+
+    /// Submits already recorded ```cmd_buffers``` in one batch, e.g. per-frame buffers alongside [ReplayableCommands](crate::ReplayableCommands).
+    ///
+    /// ```wait_sem[i]``` is waited on at ```wait_dst_flags[i]```, ```ALL_COMMANDS``` if missing, and ```signal_sem``` are signaled once all commands completed.
+    /// Shortcut for [submit_cmd_buffers2](VkInit::submit_cmd_buffers2), which takes a stage mask per semaphore.
+    pub fn submit_cmd_buffers(
+        &self,
+        cmd_buffers: &[CommandBuffer],
+        cmd_type: CmdType,
+        fence: &Fence,
+        wait_sem: &[Semaphore],
+        signal_sem: &[Semaphore],
+        wait_dst_flags: &[PipelineStageFlags],
+    ) -> Result<(), Error> {
+        let wait_infos: Vec<SemaphoreSubmitInfo> = wait_sem
+            .iter()
+            .enumerate()
+            .map(|(index, semaphore)| {
+                // The legacy stage bits are the lower half of the synchronization2 stage bits.
+                let stage_mask = wait_dst_flags
+                    .get(index)
+                    .map_or(PipelineStageFlags2::ALL_COMMANDS, |flags| {
+                        PipelineStageFlags2::from_raw(flags.as_raw() as u64)
+                    });
+                semaphore_submit_info(*semaphore, stage_mask)
+            })
+            .collect();
+        let signal_infos: Vec<SemaphoreSubmitInfo> = signal_sem
+            .iter()
+            .map(|semaphore| semaphore_submit_info(*semaphore, PipelineStageFlags2::ALL_COMMANDS))
+            .collect();
+        self.submit_cmd_buffers2(
+            cmd_buffers,
+            cmd_type,
+            Some(fence),
+            &wait_infos,
+            &signal_infos,
+        )
+    }
+
+    /// Submits already recorded ```cmd_buffers``` in one batch through ```vkQueueSubmit2```.
+    ///
+    /// Each [SemaphoreSubmitInfo] sets its stage mask and, for timeline semaphores, the value to wait for or signal.
+    /// The ```fence``` is optional, e.g. when frames are paced by a timeline semaphore instead.
+    pub fn submit_cmd_buffers2(
+        &self,
+        cmd_buffers: &[CommandBuffer],
+        cmd_type: CmdType,
+        fence: Option<&Fence>,
+        wait_infos: &[SemaphoreSubmitInfo],
+        signal_infos: &[SemaphoreSubmitInfo],
+    ) -> Result<(), Error> {
+        let (queue, _) = self.get_queue(cmd_type);
+        self.submit_to_queue(queue, cmd_buffers, fence, wait_infos, signal_infos)
+    }
 
-        let cmd_buffers = [*cmd_buffer];
-        let mut submit_info = SubmitInfo::builder()
-            .command_buffers(&cmd_buffers)
-            .wait_dst_stage_mask(wait_dst_flags)
-            .signal_semaphores(signal_sem)
-            .wait_semaphores(wait_sem)
+    /// Same as [submit_cmd_buffers2](VkInit::submit_cmd_buffers2), but to ```queue``` picked by the caller, e.g. through [get_queue_for](VkInit::get_queue_for).
+    pub(crate) fn submit_to_queue(
+        &self,
+        queue: Queue,
+        cmd_buffers: &[CommandBuffer],
+        fence: Option<&Fence>,
+        wait_infos: &[SemaphoreSubmitInfo],
+        signal_infos: &[SemaphoreSubmitInfo],
+    ) -> Result<(), Error> {
+        let cmd_buffer_infos: Vec<CommandBufferSubmitInfo> = cmd_buffers
+            .iter()
+            .map(|cmd_buffer| {
+                CommandBufferSubmitInfo::builder()
+                    .command_buffer(*cmd_buffer)
+                    .build()
+            })
+            .collect();
+        let submit_info = SubmitInfo2::builder()
+            .command_buffer_infos(&cmd_buffer_infos)
+            .wait_semaphore_infos(wait_infos)
+            .signal_semaphore_infos(signal_infos)
             .build();
 
-        if wait_sem.is_empty() {
-            submit_info.wait_semaphore_count = 0;
-            submit_info.p_wait_semaphores = std::ptr::null();
+        let fence = fence.copied().unwrap_or_default();
+        unsafe { self.device.queue_submit2(queue, &[submit_info], fence)? };
+        self.watchdog.submitted(cmd_buffers);
+        if let Some(head) = &self.head {
+            let wait_sems: Vec<Semaphore> = wait_infos.iter().map(|info| info.semaphore).collect();
+            head.consume_acquire_semaphores(&wait_sems);
         }
 
-        if signal_sem.is_empty() {
-            submit_info.signal_semaphore_count = 0;
-            submit_info.p_signal_semaphores = std::ptr::null();
-        }
+        Ok(())
+    }
 
-        let (queue, _) = self.get_queue(cmd_type);
-        unsafe { self.device.queue_submit(queue, &[submit_info], *fence)? };
+    /// Records ```record``` into a transient command buffer, submits it to the queue of ```cmd_type``` and blocks until it has finished.
+    ///
+    /// Meant for one-off setup work, e.g. initial layout transitions or uploads. Falls back to the unified queue like [get_queue](VkInit::get_queue).
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let buffer = init.create_local_buffer(64, BufferUsageFlags::TRANSFER_DST)?;
+    ///
+    /// init.immediate_submit(CmdType::Transfer, |cmd_buffer| unsafe {
+    ///     init.device.cmd_fill_buffer(*cmd_buffer, buffer.buffer, 0, WHOLE_SIZE, 0);
+    /// })?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn immediate_submit<F: FnOnce(&CommandBuffer)>(
+        &self,
+        cmd_type: CmdType,
+        record: F,
+    ) -> Result<(), Error> {
+        self.immediate_submit_for(cmd_type, QueueRequirements::default(), record)
+    }
 
-        Ok(())
+    /// Same as [immediate_submit](VkInit::immediate_submit), but the queue is picked by [get_queue_for](VkInit::get_queue_for),
+    /// e.g. to copy oddly-sized image regions without hitting the transfer granularity of a dedicated transfer queue.
+    pub fn immediate_submit_for<F: FnOnce(&CommandBuffer)>(
+        &self,
+        cmd_type: CmdType,
+        requirements: QueueRequirements,
+        record: F,
+    ) -> Result<(), Error> {
+        let (queue, queue_family_index) = self.get_queue_for(cmd_type, requirements);
+        let pool_info = CommandPoolCreateInfo::builder()
+            .queue_family_index(queue_family_index)
+            .flags(CommandPoolCreateFlags::TRANSIENT);
+        let cmd_pool = unsafe { self.device.create_command_pool(&pool_info, None)? };
+        let fence = match unsafe { self.device.create_fence(&FenceCreateInfo::default(), None) } {
+            Ok(fence) => fence,
+            Err(e) => {
+                unsafe { self.device.destroy_command_pool(cmd_pool, None) };
+                return Err(e.into());
+            }
+        };
+
+        let result = (|| {
+            let cmd_buffer =
+                self.create_command_buffers_named(&cmd_pool, 1, "VKU_Immediate_Submit")?[0];
+            self.begin_cmd_buffer(&cmd_buffer)?;
+            record(&cmd_buffer);
+            unsafe { self.device.end_command_buffer(cmd_buffer)? };
+            self.submit_to_queue(queue, &[cmd_buffer], Some(&fence), &[], &[])?;
+            self.wait_for_fences_watched(&[fence], "immediate_submit")
+        })();
+
+        // Destroying the pool frees the command buffer.
+        unsafe {
+            self.device.destroy_fence(fence, None);
+            self.device.destroy_command_pool(cmd_pool, None);
+        }
+        result
     }
 
     pub fn wait_on_fence_and_reset(
@@ -609,7 +1085,7 @@ impl VkInit {
     ) -> Result<(), Error> {
         unsafe {
             if let Some(fence) = fence {
-                self.device.wait_for_fences(&[*fence], true, u64::MAX)?;
+                self.wait_for_fences_watched(&[*fence], "wait_on_fence_and_reset")?;
                 self.device.reset_fences(&[*fence])?;
             }
             for cmd_buffer in cmd_buffers {
@@ -628,10 +1104,39 @@ impl VkInit {
         image_memory_barriers: &[ImageMemoryBarrier2],
         buffer_memory_barriers: &[BufferMemoryBarrier2],
     ) {
+        self.cmd_pipeline_barrier2_full(
+            cmd_buffer,
+            &[],
+            image_memory_barriers,
+            buffer_memory_barriers,
+            None,
+        );
+    }
+
+    /// Same as [cmd_pipeline_barrier2](VkInit::cmd_pipeline_barrier2), but with global memory barriers and dependency flags.
+    ///
+    /// ```dependency_flags``` overrides the [default_dependency_flags](crate::default_dependency_flags).
+    /// See [Barriers](crate::Barriers) to collect barriers declaratively.
+    pub fn cmd_pipeline_barrier2_full(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        memory_barriers: &[MemoryBarrier2],
+        image_memory_barriers: &[ImageMemoryBarrier2],
+        buffer_memory_barriers: &[BufferMemoryBarrier2],
+        dependency_flags: Option<DependencyFlags>,
+    ) {
+        let dependency_flags = dependency_flags.unwrap_or_else(|| {
+            crate::default_dependency_flags(
+                memory_barriers,
+                image_memory_barriers,
+                buffer_memory_barriers,
+            )
+        });
         let dependency_info = DependencyInfo::builder()
+            .memory_barriers(memory_barriers)
             .image_memory_barriers(image_memory_barriers)
             .buffer_memory_barriers(buffer_memory_barriers)
-            .dependency_flags(DependencyFlags::empty())
+            .dependency_flags(dependency_flags)
             .build();
 
         unsafe {
@@ -640,29 +1145,15 @@ impl VkInit {
         }
     }
 
+    /// Presents swapchain image ```frame``` once ```rendering_complete_semaphore``` is signaled.
+    ///
+    /// Returns [Error::SwapchainOutOfDate] if the swapchain has to be recreated.
     pub fn present(
         &self,
         rendering_complete_semaphore: &Semaphore,
         frame: usize,
     ) -> Result<(), Error> {
-        let Some(head) = self.head.as_ref() else {
-            return Err(Error::HeadCallOnHeadlessInstance);
-        };
-        let swapchains = [head.swapchain];
-        let image_indices = [frame as u32];
-        let wait_sems = [*rendering_complete_semaphore];
-        let present_info = ash::vk::PresentInfoKHR::builder()
-            .wait_semaphores(&wait_sems)
-            .swapchains(&swapchains)
-            .image_indices(&image_indices)
-            .build();
-
-        unsafe {
-            head.swapchain_loader
-                .queue_present(self.unified_queue, &present_info)?;
-        }
-
-        Ok(())
+        self.present_regions(rendering_complete_semaphore, frame, None)
     }
 
     pub fn wait_device_idle(&self) -> Result<(), Error> {
@@ -716,28 +1207,57 @@ impl VkInit {
         }
     }
 
-    fn set_debug_object_name_static(
-        dbg: &DebugUtils,
-        device: &Device,
-        obj_handle: u64,
-        obj_type: ObjectType,
-        name: String,
-    ) -> Result<(), Error> {
-        let c_name = CString::new(name)?;
-        let name_info = DebugUtilsObjectNameInfoEXT::builder()
-            .object_name(&c_name)
-            .object_handle(obj_handle)
-            .object_type(obj_type)
-            .build();
+    /// Gets the queue and queue family index for the given [CmdType] that also satisfies ```requirements```.
+    ///
+    /// Dedicated queues are only returned if their queue family supports the requirements, otherwise this will fallback to the unified queue.
+    /// E.g. a dedicated transfer queue with a coarse ```minImageTransferGranularity``` can not copy small or oddly-sized image regions.
+    pub fn get_queue_for(
+        &self,
+        cmd_type: CmdType,
+        requirements: QueueRequirements,
+    ) -> (Queue, u32) {
+        let (queue, queue_family_index) = self.get_queue(cmd_type);
+        let unified_queue_family_index = self.physical_device_info.unified_queue_family_index;
+        if queue_family_index == unified_queue_family_index {
+            return (queue, queue_family_index);
+        }
 
-        unsafe { dbg.set_debug_utils_object_name(device.handle(), &name_info)? };
-        Ok(())
+        let props = &self.physical_device_info.queue_family_properties[queue_family_index as usize];
+        match unsupported_queue_requirement(props, &requirements) {
+            None => (queue, queue_family_index),
+            Some(reason) => {
+                trace!(
+                    "Dedicated {cmd_type:?} queue family {queue_family_index} {reason}, falling back to unified queue family {unified_queue_family_index}"
+                );
+                (self.unified_queue, unified_queue_family_index)
+            }
+        }
+    }
+
+    /// Gets a queue whose family supports sparse binding, preferring the dedicated transfer and compute queues.
+    ///
+    /// Returns ```None``` if no created queue supports it.
+    pub fn get_sparse_binding_queue(&self) -> Option<(Queue, u32)> {
+        let info = &self.physical_device_info;
+        [
+            self.transfer_queue.zip(info.transfer_queue_family_index),
+            self.compute_queue.zip(info.compute_queue_family_index),
+            Some((self.unified_queue, info.unified_queue_family_index)),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|(_, index)| {
+            info.queue_family_properties[*index as usize]
+                .queue_flags
+                .contains(QueueFlags::SPARSE_BINDING)
+        })
     }
 
     pub(crate) unsafe fn create_instance_and_debug(
         entry: &Entry,
         display_handle: Option<RawDisplayHandle>,
         create_info: &VkInitCreateInfo,
+        debug_message_capture: &DebugMessageCapture,
     ) -> Result<(Instance, Option<DebugUtils>, Option<DebugUtilsMessengerEXT>), Error> {
         let app_info = ApplicationInfo::builder()
             .application_name(CStr::from_ptr(create_info.app_name.as_ptr() as *const i8))
@@ -778,7 +1298,11 @@ impl VkInit {
             let debug_messenger_info = DebugUtilsMessengerCreateInfoEXT::builder()
                 .message_severity(create_info.log_level)
                 .message_type(create_info.log_msg)
-                .pfn_user_callback(Some(vulkan_debug_callback));
+                .pfn_user_callback(Some(vulkan_debug_callback))
+                .user_data(
+                    debug_message_capture as *const DebugMessageCapture
+                        as *mut std::os::raw::c_void,
+                );
 
             let mut val_features = ValidationFeaturesEXT::builder()
                 .enabled_validation_features(&create_info.enabled_validation_features);
@@ -843,83 +1367,480 @@ impl VkInit {
         instance: &Instance,
         create_info: &VkInitCreateInfo,
     ) -> Result<(PhysicalDevice, PhysicalDeviceInfo), Error> {
-        let all_pdevices = instance.enumerate_physical_devices()?;
-        for physical_device in all_pdevices {
-            let pdevice_queue_props =
-                instance.get_physical_device_queue_family_properties(physical_device);
-            let pdevice_prop = instance.get_physical_device_properties(physical_device);
+        let mut candidates = Self::query_physical_devices(instance, create_info)?;
+        if candidates.is_empty() {
+            return Err(Error::NoSuitableGPUFound);
+        }
 
-            if !create_info.allow_igpu
-                && pdevice_prop.device_type != PhysicalDeviceType::DISCRETE_GPU
-            {
-                continue;
+        let infos = candidates.iter().map(|(_, info)| info);
+        let selected = match &create_info.gpu_preference {
+            GpuSelection::FirstDiscrete => infos.clone().position(|info| {
+                create_info.allow_igpu || info.device_type == PhysicalDeviceType::DISCRETE_GPU
+            }),
+            GpuSelection::Index(index) => {
+                infos.clone().position(|info| info.device_index == *index)
             }
+            GpuSelection::NamedSubstring(substring) => infos
+                .clone()
+                .position(|info| info.name.to_lowercase().contains(&substring.to_lowercase())),
+            GpuSelection::HighestMemory => infos
+                .clone()
+                .enumerate()
+                .max_by_key(|(index, info)| (info.device_local_memory(), std::cmp::Reverse(*index)))
+                .map(|(index, _)| index),
+        };
 
-            let mut unified_queue_family_index: Option<u32> = None;
-            let mut transfer_queue_family_index: Option<u32> = None;
-            let mut compute_queue_family_index: Option<u32> = None;
-
-            for (index, queue_family_prop) in pdevice_queue_props.iter().enumerate() {
-                let supports_transfer =
-                    queue_family_prop.queue_flags.contains(QueueFlags::TRANSFER);
-                let supports_compute = queue_family_prop.queue_flags.contains(QueueFlags::COMPUTE);
-                let supports_graphics =
-                    queue_family_prop.queue_flags.contains(QueueFlags::GRAPHICS);
-
-                //Unified queue
-                if unified_queue_family_index.is_none()
-                    && supports_transfer
-                    && supports_compute
-                    && supports_graphics
-                {
-                    unified_queue_family_index = Some(index as u32);
-                    continue;
-                }
-
-                //Get dedicated transfer queue
-                if transfer_queue_family_index.is_none()
-                    && supports_transfer
-                    && !supports_compute
-                    && !supports_graphics
-                {
-                    transfer_queue_family_index = Some(index as u32);
-                    continue;
-                }
+        let Some(selected) = selected else {
+            let available: Vec<String> = infos
+                .map(|info| {
+                    format!(
+                        "{}: {} ({:?})",
+                        info.device_index, info.name, info.device_type
+                    )
+                })
+                .collect();
+            return Err(Error::RequestedGPUNotFound {
+                selection: create_info.gpu_preference.clone(),
+                allow_igpu: create_info.allow_igpu,
+                available: available.join(", "),
+            });
+        };
 
-                //Get dedicated compute queue
-                //Any compute queue implicitly supports transfer ops
-                if compute_queue_family_index.is_none() && supports_compute && !supports_graphics {
-                    compute_queue_family_index = Some(index as u32);
-                    continue;
-                }
+        let (physical_device, physical_device_info) = candidates.swap_remove(selected);
+        trace!("Picked suitable device: {physical_device_info}");
+        trace!("Physical device limits: {:?}", physical_device_info.limits);
+        Ok((physical_device, physical_device_info))
+    }
+
+    /// Lists all GPUs [VkInit] can use, e.g. to let users pick one for [gpu_preference](VkInitCreateInfo::gpu_preference) before initialization.
+    ///
+    /// Creates and destroys a temporary instance without validation, the ```entry``` can be loaded with [load_entry](VkInit::load_entry).
+    /// Only devices with a queue family supporting graphics, compute and transfer are listed.
+    pub fn enumerate_gpus(
+        entry: &Entry,
+        create_info: &VkInitCreateInfo,
+    ) -> Result<Vec<PhysicalDeviceInfo>, Error> {
+        let create_info = VkInitCreateInfo {
+            enable_validation: false,
+            ..create_info.clone()
+        };
+        unsafe {
+            let (instance, _, _) = Self::create_instance_and_debug(
+                entry,
+                None,
+                &create_info,
+                &DebugMessageCapture::default(),
+            )?;
+            let devices = Self::query_physical_devices(&instance, &create_info);
+            instance.destroy_instance(None);
+            Ok(devices?.into_iter().map(|(_, info)| info).collect())
+        }
+    }
+
+    /// All physical devices with a unified queue, in enumeration order.
+    unsafe fn query_physical_devices(
+        instance: &Instance,
+        create_info: &VkInitCreateInfo,
+    ) -> Result<Vec<(PhysicalDevice, PhysicalDeviceInfo)>, Error> {
+        let mut devices = vec![];
+        let all_pdevices = instance.enumerate_physical_devices()?;
+        for (device_index, physical_device) in all_pdevices.into_iter().enumerate() {
+            if let Some(info) =
+                Self::query_physical_device(instance, physical_device, device_index, create_info)?
+            {
+                devices.push((physical_device, info));
             }
+        }
+        Ok(devices)
+    }
 
-            if let Some(unified_queue_family_index) = unified_queue_family_index {
-                trace!(
-                    "Picked suitable device: {:#?}",
-                    char_array_to_string(&pdevice_prop.device_name)?
-                );
+    /// ```None``` if the device has no queue family supporting graphics, compute and transfer.
+    unsafe fn query_physical_device(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        device_index: usize,
+        create_info: &VkInitCreateInfo,
+    ) -> Result<Option<PhysicalDeviceInfo>, Error> {
+        let pdevice_queue_props =
+            instance.get_physical_device_queue_family_properties(physical_device);
+        let pdevice_prop = instance.get_physical_device_properties(physical_device);
+
+        let mut unified_queue_family_index: Option<u32> = None;
+        let mut transfer_queue_family_index: Option<u32> = None;
+        let mut compute_queue_family_index: Option<u32> = None;
+
+        for (index, queue_family_prop) in pdevice_queue_props.iter().enumerate() {
+            let supports_transfer = queue_family_prop.queue_flags.contains(QueueFlags::TRANSFER);
+            let supports_compute = queue_family_prop.queue_flags.contains(QueueFlags::COMPUTE);
+            let supports_graphics = queue_family_prop.queue_flags.contains(QueueFlags::GRAPHICS);
+
+            //Unified queue
+            if unified_queue_family_index.is_none()
+                && supports_transfer
+                && supports_compute
+                && supports_graphics
+            {
+                unified_queue_family_index = Some(index as u32);
+                continue;
+            }
+
+            //Get dedicated transfer queue
+            if transfer_queue_family_index.is_none()
+                && supports_transfer
+                && !supports_compute
+                && !supports_graphics
+            {
+                transfer_queue_family_index = Some(index as u32);
+                continue;
+            }
 
-                trace!("Physical device type: {:?}", pdevice_prop.device_type);
-                trace!("Physical device limits: {:?}", pdevice_prop.limits);
-
-                let features = instance.get_physical_device_features(physical_device);
-                let memory_props = instance.get_physical_device_memory_properties(physical_device);
-                let name = char_array_to_string(&pdevice_prop.device_name)?;
-                let physical_device_info = PhysicalDeviceInfo {
-                    name,
-                    unified_queue_family_index,
-                    transfer_queue_family_index,
-                    compute_queue_family_index,
-                    features,
-                    memory_props,
-                    limits: pdevice_prop.limits,
-                };
-
-                return Ok((physical_device, physical_device_info));
+            //Get dedicated compute queue
+            //Any compute queue implicitly supports transfer ops
+            if compute_queue_family_index.is_none() && supports_compute && !supports_graphics {
+                compute_queue_family_index = Some(index as u32);
+                continue;
             }
         }
-        Err(Error::NoSuitableGPUFound)
+
+        let Some(unified_queue_family_index) = unified_queue_family_index else {
+            return Ok(None);
+        };
+
+        let features = instance.get_physical_device_features(physical_device);
+        let supported_features = Self::query_device_features(instance, physical_device, features);
+        let memory_props = instance.get_physical_device_memory_properties(physical_device);
+        let name = char_array_to_string(&pdevice_prop.device_name)?;
+        let supported_extensions: Vec<String> = instance
+            .enumerate_device_extension_properties(physical_device)?
+            .iter()
+            .filter_map(|prop| char_array_to_string(&prop.extension_name).ok())
+            .collect();
+        let vertex_attribute_divisor = Self::query_vertex_attribute_divisor_support(
+            instance,
+            physical_device,
+            &supported_extensions,
+        );
+        let image_view_min_lod = Self::query_image_view_min_lod_support(
+            instance,
+            physical_device,
+            &supported_extensions,
+        );
+        let rasterization_order_attachment_access =
+            Self::query_rasterization_order_attachment_access_support(
+                instance,
+                physical_device,
+                &supported_extensions,
+            );
+        let swapchain_maintenance1 = Self::query_swapchain_maintenance1_support(
+            instance,
+            physical_device,
+            &supported_extensions,
+            create_info,
+        );
+        let attachment_feedback_loop_layout = Self::query_attachment_feedback_loop_layout_support(
+            instance,
+            physical_device,
+            &supported_extensions,
+        );
+        let vertex_input_dynamic_state = Self::query_vertex_input_dynamic_state_support(
+            instance,
+            physical_device,
+            &supported_extensions,
+        );
+        let incremental_present = KhrIncrementalPresentFn::name()
+            .to_str()
+            .is_ok_and(|ext_name| supported_extensions.iter().any(|ext| ext == ext_name));
+        let device_fault =
+            Self::query_device_fault_support(instance, physical_device, &supported_extensions);
+        let (diagnostic_checkpoints, diagnostics_config, buffer_device_address_capture_replay) =
+            Self::query_crash_diagnostics_support(
+                instance,
+                physical_device,
+                &supported_extensions,
+                create_info,
+            );
+        let pipeline_executable_properties = Self::query_pipeline_executable_properties_support(
+            instance,
+            physical_device,
+            &supported_extensions,
+        );
+        let external_handles = if cfg!(feature = "external") {
+            ExternalHandleSupport::query(&supported_extensions)
+        } else {
+            ExternalHandleSupport::default()
+        };
+        let physical_device_info = PhysicalDeviceInfo {
+            name,
+            device_index,
+            device_type: pdevice_prop.device_type,
+            vendor_id: pdevice_prop.vendor_id,
+            device_id: pdevice_prop.device_id,
+            api_version: pdevice_prop.api_version,
+            driver_version: pdevice_prop.driver_version,
+            supported_extensions,
+            unified_queue_family_index,
+            transfer_queue_family_index,
+            compute_queue_family_index,
+            queue_family_properties: pdevice_queue_props,
+            features,
+            supported_features,
+            memory_props,
+            limits: pdevice_prop.limits,
+            vertex_attribute_divisor,
+            image_view_min_lod,
+            rasterization_order_attachment_access,
+            swapchain_maintenance1,
+            attachment_feedback_loop_layout,
+            vertex_input_dynamic_state,
+            incremental_present,
+            device_fault,
+            diagnostic_checkpoints,
+            diagnostics_config,
+            buffer_device_address_capture_replay,
+            pipeline_executable_properties,
+            external_handles,
+        };
+
+        Ok(Some(physical_device_info))
+    }
+
+    unsafe fn query_vertex_attribute_divisor_support(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        supported_extensions: &[String],
+    ) -> Option<VertexAttributeDivisorSupport> {
+        let ext_name = ExtVertexAttributeDivisorFn::name().to_str().ok()?;
+        if !supported_extensions.iter().any(|ext| ext == ext_name) {
+            return None;
+        }
+
+        let mut divisor_features = PhysicalDeviceVertexAttributeDivisorFeaturesEXT::default();
+        let mut features = PhysicalDeviceFeatures2::builder().push_next(&mut divisor_features);
+        instance.get_physical_device_features2(physical_device, &mut features);
+
+        let mut divisor_props = PhysicalDeviceVertexAttributeDivisorPropertiesEXT::default();
+        let mut props = PhysicalDeviceProperties2::builder().push_next(&mut divisor_props);
+        instance.get_physical_device_properties2(physical_device, &mut props);
+
+        if divisor_features.vertex_attribute_instance_rate_divisor == FALSE {
+            return None;
+        }
+
+        Some(VertexAttributeDivisorSupport {
+            max_vertex_attrib_divisor: divisor_props.max_vertex_attrib_divisor,
+            zero_divisor: divisor_features.vertex_attribute_instance_rate_zero_divisor == TRUE,
+        })
+    }
+
+    unsafe fn query_image_view_min_lod_support(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        supported_extensions: &[String],
+    ) -> bool {
+        let Ok(ext_name) = ExtImageViewMinLodFn::name().to_str() else {
+            return false;
+        };
+        if !supported_extensions.iter().any(|ext| ext == ext_name) {
+            return false;
+        }
+
+        let mut min_lod_features = PhysicalDeviceImageViewMinLodFeaturesEXT::default();
+        let mut features = PhysicalDeviceFeatures2::builder().push_next(&mut min_lod_features);
+        instance.get_physical_device_features2(physical_device, &mut features);
+
+        min_lod_features.min_lod == TRUE
+    }
+
+    unsafe fn query_rasterization_order_attachment_access_support(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        supported_extensions: &[String],
+    ) -> bool {
+        let Ok(ext_name) = ExtRasterizationOrderAttachmentAccessFn::name().to_str() else {
+            return false;
+        };
+        if !supported_extensions.iter().any(|ext| ext == ext_name) {
+            return false;
+        }
+
+        let mut order_features =
+            PhysicalDeviceRasterizationOrderAttachmentAccessFeaturesEXT::default();
+        let mut features = PhysicalDeviceFeatures2::builder().push_next(&mut order_features);
+        instance.get_physical_device_features2(physical_device, &mut features);
+
+        order_features.rasterization_order_color_attachment_access == TRUE
+    }
+
+    unsafe fn query_swapchain_maintenance1_support(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        supported_extensions: &[String],
+        create_info: &VkInitCreateInfo,
+    ) -> bool {
+        let Ok(ext_name) = ExtSwapchainMaintenance1Fn::name().to_str() else {
+            return false;
+        };
+        let surface_maintenance_enabled = create_info
+            .additional_instance_extensions
+            .iter()
+            .any(|ext| ext.trim_end_matches('\0') == "VK_EXT_surface_maintenance1");
+        if !surface_maintenance_enabled || !supported_extensions.iter().any(|ext| ext == ext_name) {
+            return false;
+        }
+
+        let mut maintenance_features = PhysicalDeviceSwapchainMaintenance1FeaturesEXT::default();
+        let mut features = PhysicalDeviceFeatures2::builder().push_next(&mut maintenance_features);
+        instance.get_physical_device_features2(physical_device, &mut features);
+
+        maintenance_features.swapchain_maintenance1 == TRUE
+    }
+
+    unsafe fn query_attachment_feedback_loop_layout_support(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        supported_extensions: &[String],
+    ) -> bool {
+        let Ok(ext_name) = ExtAttachmentFeedbackLoopLayoutFn::name().to_str() else {
+            return false;
+        };
+        if !supported_extensions.iter().any(|ext| ext == ext_name) {
+            return false;
+        }
+
+        let mut feedback_features =
+            PhysicalDeviceAttachmentFeedbackLoopLayoutFeaturesEXT::default();
+        let mut features = PhysicalDeviceFeatures2::builder().push_next(&mut feedback_features);
+        instance.get_physical_device_features2(physical_device, &mut features);
+
+        feedback_features.attachment_feedback_loop_layout == TRUE
+    }
+
+    unsafe fn query_vertex_input_dynamic_state_support(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        supported_extensions: &[String],
+    ) -> bool {
+        let Ok(ext_name) = ExtVertexInputDynamicStateFn::name().to_str() else {
+            return false;
+        };
+        if !supported_extensions.iter().any(|ext| ext == ext_name) {
+            return false;
+        }
+
+        let mut vertex_input_features = PhysicalDeviceVertexInputDynamicStateFeaturesEXT::default();
+        let mut features = PhysicalDeviceFeatures2::builder().push_next(&mut vertex_input_features);
+        instance.get_physical_device_features2(physical_device, &mut features);
+
+        vertex_input_features.vertex_input_dynamic_state == TRUE
+    }
+
+    unsafe fn query_device_fault_support(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        supported_extensions: &[String],
+    ) -> bool {
+        let Ok(ext_name) = ExtDeviceFaultFn::name().to_str() else {
+            return false;
+        };
+        if !supported_extensions.iter().any(|ext| ext == ext_name) {
+            return false;
+        }
+
+        let mut fault_features = PhysicalDeviceFaultFeaturesEXT::default();
+        let mut features = PhysicalDeviceFeatures2::builder().push_next(&mut fault_features);
+        instance.get_physical_device_features2(physical_device, &mut features);
+
+        fault_features.device_fault == TRUE
+    }
+
+    /// Checkpoints, diagnostics config and capture replay support, all ```false``` without [crash_diagnostics](VkInitCreateInfo::crash_diagnostics).
+    unsafe fn query_crash_diagnostics_support(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        supported_extensions: &[String],
+        create_info: &VkInitCreateInfo,
+    ) -> (bool, bool, bool) {
+        if !create_info.crash_diagnostics {
+            return (false, false, false);
+        }
+        let supported = |name: &CStr| {
+            name.to_str()
+                .is_ok_and(|name| supported_extensions.iter().any(|ext| ext == name))
+        };
+
+        let mut config_features = PhysicalDeviceDiagnosticsConfigFeaturesNV::default();
+        let mut vk_1_2_features = PhysicalDeviceVulkan12Features::default();
+        let mut features = PhysicalDeviceFeatures2::builder().push_next(&mut vk_1_2_features);
+        if supported(NvDeviceDiagnosticsConfigFn::name()) {
+            features = features.push_next(&mut config_features);
+        }
+        instance.get_physical_device_features2(physical_device, &mut features);
+
+        (
+            supported(NvDeviceDiagnosticCheckpointsFn::name()),
+            config_features.diagnostics_config == TRUE,
+            vk_1_2_features.buffer_device_address_capture_replay == TRUE,
+        )
+    }
+
+    unsafe fn query_pipeline_executable_properties_support(
+        instance: &Instance,
+        physical_device: PhysicalDevice,
+        supported_extensions: &[String],
+    ) -> bool {
+        let Ok(ext_name) = KhrPipelineExecutablePropertiesFn::name().to_str() else {
+            return false;
+        };
+        if !supported_extensions.iter().any(|ext| ext == ext_name) {
+            return false;
+        }
+
+        let mut executable_features =
+            PhysicalDevicePipelineExecutablePropertiesFeaturesKHR::default();
+        let mut features = PhysicalDeviceFeatures2::builder().push_next(&mut executable_features);
+        instance.get_physical_device_features2(physical_device, &mut features);
+
+        executable_features.pipeline_executable_info == TRUE
+    }
+
+    /// Extensions that are enabled automatically if supported by the physical device.
+    fn optional_device_extensions(physical_device_info: &PhysicalDeviceInfo) -> Vec<&'static CStr> {
+        let mut extensions = vec![];
+        if physical_device_info.vertex_attribute_divisor.is_some() {
+            extensions.push(ExtVertexAttributeDivisorFn::name());
+        }
+        if physical_device_info.image_view_min_lod {
+            extensions.push(ExtImageViewMinLodFn::name());
+        }
+        if physical_device_info.rasterization_order_attachment_access {
+            extensions.push(ExtRasterizationOrderAttachmentAccessFn::name());
+        }
+        if physical_device_info.swapchain_maintenance1 {
+            extensions.push(ExtSwapchainMaintenance1Fn::name());
+        }
+        if physical_device_info.attachment_feedback_loop_layout {
+            extensions.push(ExtAttachmentFeedbackLoopLayoutFn::name());
+        }
+        if physical_device_info.vertex_input_dynamic_state {
+            extensions.push(ExtVertexInputDynamicStateFn::name());
+        }
+        if physical_device_info.incremental_present {
+            extensions.push(KhrIncrementalPresentFn::name());
+        }
+        if physical_device_info.device_fault {
+            extensions.push(ExtDeviceFaultFn::name());
+        }
+        if physical_device_info.diagnostic_checkpoints {
+            extensions.push(NvDeviceDiagnosticCheckpointsFn::name());
+        }
+        if physical_device_info.diagnostics_config {
+            extensions.push(NvDeviceDiagnosticsConfigFn::name());
+        }
+        if physical_device_info.pipeline_executable_properties {
+            extensions.push(KhrPipelineExecutablePropertiesFn::name());
+        }
+        extensions.extend(physical_device_info.external_handles.extensions());
+        extensions
     }
 
     pub(crate) unsafe fn create_device(
@@ -927,7 +1848,13 @@ impl VkInit {
         physical_device: &PhysicalDevice,
         physical_device_info: &PhysicalDeviceInfo,
         create_info: &VkInitCreateInfo,
-    ) -> Result<Device, Error> {
+    ) -> Result<(Device, Vec<String>), Error> {
+        if let Some(feature) = physical_device_info
+            .supported_features
+            .first_missing(&DeviceFeatures::requested(create_info))
+        {
+            return Err(Error::RequestedFeatureNotSupported(feature));
+        }
         let supported_extensions =
             instance.enumerate_device_extension_properties(*physical_device)?;
 
@@ -954,6 +1881,15 @@ impl VkInit {
             }
         }
 
+        for ext in Self::optional_device_extensions(physical_device_info) {
+            enabled_extensions_raw.push(ext.as_ptr());
+        }
+
+        let enabled_extensions = enabled_extensions_raw
+            .iter()
+            .map(|ext| Ok(CStr::from_ptr(*ext).to_str()?.to_string()))
+            .collect::<Result<Vec<String>, Error>>()?;
+
         let queue_priorities = [1.0];
 
         let mut queue_create_infos = Vec::new();
@@ -987,17 +1923,100 @@ impl VkInit {
             .enabled_features(&physical_device_info.features)
             .queue_create_infos(&queue_create_infos);
 
-        let mut pdevice_1_1_features = create_info.physical_device_1_1_features;
-        let mut pdevice_1_2_features = create_info.physical_device_1_2_features;
-        let mut pdevice_1_3_features = create_info.physical_device_1_3_features;
+        let mut pdevice_1_1_features = create_info.physical_device_1_1_features.get();
+        let mut pdevice_1_2_features = create_info.physical_device_1_2_features.get();
+        if physical_device_info.buffer_device_address_capture_replay {
+            pdevice_1_2_features.buffer_device_address = TRUE;
+            pdevice_1_2_features.buffer_device_address_capture_replay = TRUE;
+        }
+        let mut pdevice_1_3_features = create_info.physical_device_1_3_features.get();
 
         device_create_info = device_create_info.push_next(&mut pdevice_1_1_features);
         device_create_info = device_create_info.push_next(&mut pdevice_1_2_features);
         device_create_info = device_create_info.push_next(&mut pdevice_1_3_features);
 
+        let mut divisor_features = PhysicalDeviceVertexAttributeDivisorFeaturesEXT::default();
+        if let Some(support) = physical_device_info.vertex_attribute_divisor {
+            divisor_features.vertex_attribute_instance_rate_divisor = TRUE;
+            divisor_features.vertex_attribute_instance_rate_zero_divisor =
+                support.zero_divisor.into();
+            device_create_info = device_create_info.push_next(&mut divisor_features);
+        }
+
+        let mut min_lod_features = PhysicalDeviceImageViewMinLodFeaturesEXT::builder()
+            .min_lod(true)
+            .build();
+        if physical_device_info.image_view_min_lod {
+            device_create_info = device_create_info.push_next(&mut min_lod_features);
+        }
+
+        let mut order_features =
+            PhysicalDeviceRasterizationOrderAttachmentAccessFeaturesEXT::builder()
+                .rasterization_order_color_attachment_access(true)
+                .build();
+        if physical_device_info.rasterization_order_attachment_access {
+            device_create_info = device_create_info.push_next(&mut order_features);
+        }
+
+        let mut maintenance_features = PhysicalDeviceSwapchainMaintenance1FeaturesEXT::builder()
+            .swapchain_maintenance1(true)
+            .build();
+        if physical_device_info.swapchain_maintenance1 {
+            device_create_info = device_create_info.push_next(&mut maintenance_features);
+        }
+
+        let mut feedback_features =
+            PhysicalDeviceAttachmentFeedbackLoopLayoutFeaturesEXT::builder()
+                .attachment_feedback_loop_layout(true)
+                .build();
+        if physical_device_info.attachment_feedback_loop_layout {
+            device_create_info = device_create_info.push_next(&mut feedback_features);
+        }
+
+        let mut vertex_input_features = PhysicalDeviceVertexInputDynamicStateFeaturesEXT::builder()
+            .vertex_input_dynamic_state(true)
+            .build();
+        if physical_device_info.vertex_input_dynamic_state {
+            device_create_info = device_create_info.push_next(&mut vertex_input_features);
+        }
+
+        let mut fault_features = PhysicalDeviceFaultFeaturesEXT::builder()
+            .device_fault(true)
+            .build();
+        if physical_device_info.device_fault {
+            device_create_info = device_create_info.push_next(&mut fault_features);
+        }
+
+        let mut config_features = PhysicalDeviceDiagnosticsConfigFeaturesNV::builder()
+            .diagnostics_config(true)
+            .build();
+        let mut config_info = DeviceDiagnosticsConfigCreateInfoNV::builder()
+            .flags(
+                DeviceDiagnosticsConfigFlagsNV::ENABLE_SHADER_DEBUG_INFO
+                    | DeviceDiagnosticsConfigFlagsNV::ENABLE_RESOURCE_TRACKING
+                    | DeviceDiagnosticsConfigFlagsNV::ENABLE_AUTOMATIC_CHECKPOINTS,
+            )
+            .build();
+        if physical_device_info.diagnostics_config {
+            device_create_info = device_create_info
+                .push_next(&mut config_features)
+                .push_next(&mut config_info);
+        }
+
+        let mut executable_features =
+            PhysicalDevicePipelineExecutablePropertiesFeaturesKHR::builder()
+                .pipeline_executable_info(true)
+                .build();
+        if physical_device_info.pipeline_executable_properties {
+            device_create_info = device_create_info.push_next(&mut executable_features);
+        }
+
         let device = instance.create_device(*physical_device, &device_create_info, None)?;
         trace!("Created device");
-        Ok(device)
+        for ext in &enabled_extensions {
+            trace!("Enabled device extension: {ext}");
+        }
+        Ok((device, enabled_extensions))
     }
 
     pub(crate) unsafe fn create_allocator(
@@ -1054,21 +2073,32 @@ impl VkInit {
         let loader = Surface::new(entry, instance);
         let surface =
             ash_window::create_surface(entry, instance, display_handle, window_handle, None)?;
+        let surface_info =
+            Self::query_surface_info(&loader, surface, physical_device, window_size, create_info)?;
+
+        trace!("Created surface");
+        Ok((loader, surface, surface_info))
+    }
+
+    /// Queries the formats, present modes and capabilities of ```surface``` and picks the swapchain parameters for ```window_size```.
+    ///
+    /// The extent is clamped to the capabilities, returns [WindowMinimized](Error::WindowMinimized) if it is zero.
+    pub(crate) unsafe fn query_surface_info(
+        loader: &Surface,
+        surface: SurfaceKHR,
+        physical_device: &PhysicalDevice,
+        window_size: [u32; 2],
+        create_info: &VkInitCreateInfo,
+    ) -> Result<SurfaceInfo, Error> {
         let formats = loader.get_physical_device_surface_formats(*physical_device, surface)?;
 
-        let color_format = *formats
-            .iter()
-            .find(|format| format.format == create_info.surface_format)
+        let color_format = choose_surface_format(&create_info.surface_format_preferences, &formats)
             .ok_or(Error::RequestedSurfaceFormatNotSupported)?;
 
         let present_modes =
             loader.get_physical_device_surface_present_modes(*physical_device, surface)?;
-
-        let present_mode = present_modes
-            .iter()
-            .copied()
-            .find(|&mode| mode == create_info.present_mode)
-            .ok_or(Error::PresentModeNotSupported)?;
+        let present_mode =
+            choose_present_mode(&create_info.present_mode_preferences, &present_modes);
 
         let capabilities =
             loader.get_physical_device_surface_capabilities(*physical_device, surface)?;
@@ -1090,21 +2120,47 @@ impl VkInit {
             capabilities.current_transform
         };
 
+        // Rotated swapchains use the display's native orientation.
+        let rotation = SurfaceRotation::from_transform(pre_transform);
+        let rotated = rotation.rotate_extent(Extent2D {
+            width: window_size[0],
+            height: window_size[1],
+        });
+        let current_extent = Extent2D {
+            width: rotated
+                .width
+                .min(capabilities.max_image_extent.width)
+                .max(capabilities.min_image_extent.width),
+            height: rotated
+                .height
+                .min(capabilities.max_image_extent.height)
+                .max(capabilities.min_image_extent.height),
+        };
+        // Minimized windows report zero sized extents on some platforms.
+        if current_extent.width == 0 || current_extent.height == 0 {
+            return Err(Error::WindowMinimized);
+        }
+        if rotation != SurfaceRotation::None {
+            info!(
+                "Surface is pre-rotated by {} degrees, swapchain extent is {current_extent:?}",
+                rotation.degrees()
+            );
+        }
+
         let surface_info = SurfaceInfo {
             min_extent: capabilities.min_image_extent,
             max_extent: capabilities.max_image_extent,
-            current_extent: Extent2D {
-                width: window_size[0],
-                height: window_size[1],
-            },
+            current_extent,
             present_mode,
+            supported_present_modes: present_modes,
             image_count: requested_img_count,
             color_format,
             pre_transform,
+            image_usage: ImageUsageFlags::COLOR_ATTACHMENT
+                | (capabilities.supported_usage_flags & ImageUsageFlags::TRANSFER_SRC),
+            scale_factor: 1.0,
         };
-
-        trace!("Created surface");
-        Ok((loader, surface, surface_info))
+        Ok(surface_info)
     }
 
     pub(crate) unsafe fn create_swapchain(
@@ -1112,19 +2168,16 @@ impl VkInit {
         device: &Device,
         surface: &SurfaceKHR,
         surface_info: &SurfaceInfo,
-        window_size: [u32; 2],
+        old_swapchain: SwapchainKHR,
     ) -> Result<(Swapchain, SwapchainKHR), Error> {
-        let window_extent = Extent2D {
-            width: window_size[0],
-            height: window_size[1],
-        };
         let swapchain_create_info = SwapchainCreateInfoKHR::builder()
             .surface(*surface)
+            .old_swapchain(old_swapchain)
             .min_image_count(surface_info.image_count)
             .image_color_space(surface_info.color_format.color_space)
             .image_format(surface_info.color_format.format)
-            .image_extent(window_extent)
-            .image_usage(ImageUsageFlags::COLOR_ATTACHMENT)
+            .image_extent(surface_info.current_extent)
+            .image_usage(surface_info.image_usage)
             .image_sharing_mode(SharingMode::EXCLUSIVE)
             .composite_alpha(CompositeAlphaFlagsKHR::OPAQUE)
             .pre_transform(surface_info.pre_transform)
@@ -1174,23 +2227,70 @@ impl VkInit {
         Ok((images, image_views))
     }
 
-    pub(crate) unsafe fn create_depth_image(
+    /// Creates the depth image and, for more than one sample, the multisampled color attachment.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) unsafe fn create_attachments(
         device: &Device,
         allocator: &mut Allocator,
-        window_size: [u32; 2],
-        format: Format,
-        sizeof: usize,
-    ) -> Result<VMAImage, Error> {
-        let depth_extent = Extent3D {
-            width: window_size[0],
-            height: window_size[1],
+        extent: Extent2D,
+        color_format: Format,
+        depth_format: Format,
+        depth_sizeof: usize,
+        samples: SampleCountFlags,
+    ) -> Result<(VMAImage, Option<VMAImage>), Error> {
+        let extent = Extent3D {
+            width: extent.width,
+            height: extent.height,
             depth: 1,
         };
-        let depth_image =
-            VMAImage::create_depth_image(device, allocator, depth_extent, format, sizeof)?;
+        // Only the multisampled color attachment needs the texel size, any swapchain format works without MSAA.
+        let color_sizeof = match samples {
+            SampleCountFlags::TYPE_1 => 0,
+            _ => texel_size(color_format).ok_or(Error::UnknownTexelSize(color_format))?,
+        };
+        let mut depth_image = VMAImage::create_msaa_depth_image(
+            device,
+            allocator,
+            extent,
+            depth_format,
+            depth_sizeof,
+            samples,
+        )?;
+        let msaa_image = if samples == SampleCountFlags::TYPE_1 {
+            None
+        } else {
+            match VMAImage::create_msaa_render_image(
+                device,
+                allocator,
+                extent,
+                color_format,
+                color_sizeof,
+                samples,
+            ) {
+                Ok(msaa_image) => Some(msaa_image),
+                Err(err) => {
+                    depth_image.destroy(device, allocator)?;
+                    return Err(err);
+                }
+            }
+        };
 
         trace!("Created depth images");
-        Ok(depth_image)
+        Ok((depth_image, msaa_image))
+    }
+
+    /// Returns [MsaaSamplesNotSupported](Error::MsaaSamplesNotSupported) unless ```samples``` is a single sample count
+    /// supported for both color and depth attachments.
+    pub(crate) fn check_msaa_samples(
+        limits: &PhysicalDeviceLimits,
+        samples: SampleCountFlags,
+    ) -> Result<(), Error> {
+        let supported =
+            limits.framebuffer_color_sample_counts & limits.framebuffer_depth_sample_counts;
+        if samples.as_raw().count_ones() != 1 || !supported.contains(samples) {
+            return Err(Error::MsaaSamplesNotSupported(samples, supported));
+        }
+        Ok(())
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1205,6 +2305,10 @@ impl VkInit {
         physical_device: &PhysicalDevice,
         create_info: &VkInitCreateInfo,
     ) -> Result<Head, Error> {
+        let limits = instance
+            .get_physical_device_properties(*physical_device)
+            .limits;
+        Self::check_msaa_samples(&limits, create_info.msaa_samples)?;
         let (surface_loader, surface, surface_info) = Self::create_surface(
             entry,
             instance,
@@ -1214,17 +2318,69 @@ impl VkInit {
             physical_device,
             create_info,
         )?;
-        let (swapchain_loader, swapchain) =
-            Self::create_swapchain(instance, device, &surface, &surface_info, window_size)?;
-        let (swapchain_images, swapchain_image_views) =
-            Self::create_swapchain_images(device, &swapchain_loader, &swapchain, &surface_info)?;
-        let depth_image = Self::create_depth_image(
+        let loader = surface_loader.clone();
+        Self::create_head_on_surface(
             device,
             allocator,
-            window_size,
+            instance,
+            surface_loader,
+            surface,
+            surface_info,
+            create_info,
+        )
+        .inspect_err(|_| loader.destroy_surface(surface, None))
+    }
+
+    /// Creates the swapchain and attachments of a [Head] for an existing ```surface```, e.g. after switching the device.
+    ///
+    /// Objects created before an error are destroyed, ```surface``` is left to the caller.
+    pub(crate) unsafe fn create_head_on_surface(
+        device: &Device,
+        allocator: &mut Allocator,
+        instance: &Instance,
+        surface_loader: Surface,
+        surface: SurfaceKHR,
+        surface_info: SurfaceInfo,
+        create_info: &VkInitCreateInfo,
+    ) -> Result<Head, Error> {
+        let (swapchain_loader, swapchain) = Self::create_swapchain(
+            instance,
+            device,
+            &surface,
+            &surface_info,
+            SwapchainKHR::null(),
+        )?;
+        let (swapchain_images, swapchain_image_views) = match Self::create_swapchain_images(
+            device,
+            &swapchain_loader,
+            &swapchain,
+            &surface_info,
+        ) {
+            Ok(images) => images,
+            Err(e) => {
+                swapchain_loader.destroy_swapchain(swapchain, None);
+                return Err(e);
+            }
+        };
+        let attachments = Self::create_attachments(
+            device,
+            allocator,
+            surface_info.current_extent,
+            surface_info.color_format.format,
             create_info.depth_format,
             create_info.depth_format_sizeof,
-        )?;
+            create_info.msaa_samples,
+        );
+        let (depth_image, msaa_image) = match attachments {
+            Ok(attachments) => attachments,
+            Err(e) => {
+                for image_view in &swapchain_image_views {
+                    device.destroy_image_view(*image_view, None);
+                }
+                swapchain_loader.destroy_swapchain(swapchain, None);
+                return Err(e);
+            }
+        };
 
         Ok(Head {
             surface_loader,
@@ -1239,53 +2395,76 @@ impl VkInit {
             depth_format: create_info.depth_format,
             depth_format_sizeof: create_info.depth_format_sizeof,
             depth_image,
+            msaa_samples: create_info.msaa_samples,
+            msaa_image,
+            acquired_images: Mutex::default(),
         })
     }
 
+    /// Recreates the swapchain with ```mode```, falling back to ```FIFO``` if the surface does not support it.
+    ///
+    /// Shortcut - see [set_present_mode](VkInit::set_present_mode), which rejects unsupported modes instead.
     pub fn change_present_mode<T: HasRawDisplayHandle + HasRawWindowHandle>(
         &mut self,
         raw_window_handles: T,
         window_size: [u32; 2],
         mode: PresentModeKHR,
     ) -> Result<(), Error> {
-        unsafe {
-            let display_h = raw_window_handles.raw_display_handle();
-            let window_h = raw_window_handles.raw_window_handle();
+        self.create_info.present_mode_preferences = vec![mode];
+        self.recreate_swapchain_internal(
+            Some((
+                raw_window_handles.raw_display_handle(),
+                raw_window_handles.raw_window_handle(),
+            )),
+            window_size,
+        )
+    }
+}
 
-            if let Some(head) = &mut self.head {
-                self.device.device_wait_idle()?;
-                for image_view in &head.swapchain_image_views {
-                    self.device.destroy_image_view(*image_view, None);
-                }
-                head.swapchain_loader
-                    .destroy_swapchain(head.swapchain, None);
-                head.surface_loader.destroy_surface(head.surface, None);
+impl Drop for VkInit {
+    fn drop(&mut self) {
+        if cfg!(debug_assertions) && !self.destroyed {
+            warn!(
+                "VkInit dropped without destroy, leaking the device and {} live resources",
+                self.live_resources().len()
+            );
+        }
+    }
+}
 
-                self.create_info.present_mode = mode;
+fn unsupported_queue_requirement(
+    props: &QueueFamilyProperties,
+    requirements: &QueueRequirements,
+) -> Option<String> {
+    if requirements.timestamps && props.timestamp_valid_bits == 0 {
+        return Some(String::from("does not support timestamps"));
+    }
 
-                self.head = Some(Self::create_head(
-                    &self.device,
-                    &mut self.allocator,
-                    &self.entry,
-                    &self.instance,
-                    display_h,
-                    window_h,
-                    window_size,
-                    &self.physical_device,
-                    &self.create_info,
-                )?);
-            }
-        }
+    if requirements.sparse_binding && !props.queue_flags.contains(QueueFlags::SPARSE_BINDING) {
+        return Some(String::from("does not support sparse binding"));
+    }
 
-        Ok(())
+    let required = requirements.image_granularity;
+    let supported = props.min_image_transfer_granularity;
+    let whole_levels_only = required.width == 0 && required.height == 0 && required.depth == 0;
+    let fits = |required: u32, supported: u32| supported != 0 && required.is_multiple_of(supported);
+    let granularity_fits = fits(required.width, supported.width)
+        && fits(required.height, supported.height)
+        && fits(required.depth, supported.depth);
+    if !whole_levels_only && !granularity_fits {
+        return Some(format!(
+            "has image transfer granularity {supported:?} which does not fit {required:?}"
+        ));
     }
+
+    None
 }
 
 unsafe extern "system" fn vulkan_debug_callback(
     message_severity: DebugUtilsMessageSeverityFlagsEXT,
     _message_type: DebugUtilsMessageTypeFlagsEXT,
     p_callback_data: *const DebugUtilsMessengerCallbackDataEXT,
-    _user_data: *mut std::os::raw::c_void,
+    user_data: *mut std::os::raw::c_void,
 ) -> Bool32 {
     let callback_data = *p_callback_data;
 
@@ -1303,5 +2482,9 @@ unsafe extern "system" fn vulkan_debug_callback(
         _ => (),
     };
 
+    if !user_data.is_null() {
+        (*(user_data as *const DebugMessageCapture)).push(&message);
+    }
+
     FALSE
 }
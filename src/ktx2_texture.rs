@@ -0,0 +1,123 @@
+use ktx2::Reader;
+
+use crate::{imports::*, mipmaps::level_barrier, CmdType, VMAImage, VkInit};
+
+impl VkInit {
+    /// Creates a sampled texture from a KTX2 file and blocks until all of its mip levels were uploaded.
+    ///
+    /// The format of the file is used as is, e.g. ```BC7_SRGB_BLOCK```, ```BC5_UNORM_BLOCK``` or ```ASTC_4x4_UNORM_BLOCK```.
+    /// The image is left in ```ImageLayout::SHADER_READ_ONLY_OPTIMAL```.
+    ///
+    /// Returns [TextureFormatNotSupported](Error::TextureFormatNotSupported) if the device can't sample the format and
+    /// [UnsupportedKtx2Texture](Error::UnsupportedKtx2Texture) for supercompressed or Basis Universal files, arrays, cube maps and 3D textures.
+    pub fn create_texture_from_ktx2(&mut self, bytes: &[u8]) -> Result<VMAImage, Error> {
+        let reader = Reader::new(bytes)?;
+        let header = reader.header();
+        if header.supercompression_scheme.is_some() {
+            return Err(Error::UnsupportedKtx2Texture(
+                "supercompressed files have to be transcoded first",
+            ));
+        }
+        let Some(ktx2_format) = header.format else {
+            return Err(Error::UnsupportedKtx2Texture(
+                "basis universal files have to be transcoded first",
+            ));
+        };
+        if header.layer_count > 1 || header.face_count > 1 || header.pixel_depth > 1 {
+            return Err(Error::UnsupportedKtx2Texture(
+                "only single 2D images are supported",
+            ));
+        }
+
+        // KTX2 stores the VkFormat value.
+        let format = Format::from_raw(ktx2_format.0.get() as i32);
+        let required = FormatFeatureFlags::SAMPLED_IMAGE | FormatFeatureFlags::TRANSFER_DST;
+        if !self.optimal_tiling_features(format).contains(required) {
+            return Err(Error::TextureFormatNotSupported(format));
+        }
+
+        let extent = Extent3D {
+            width: header.pixel_width,
+            height: header.pixel_height.max(1),
+            depth: 1,
+        };
+        let levels: Vec<&[u8]> = reader.levels().collect();
+        let mut image = self.create_compressed_image(extent, format, levels.len() as u32)?;
+        if let Err(e) = self.upload_ktx2_levels(&mut image, &levels) {
+            image.destroy(&self.device, &mut self.allocator.lock())?;
+            return Err(e);
+        }
+        Ok(image)
+    }
+
+    fn upload_ktx2_levels(&mut self, image: &mut VMAImage, levels: &[&[u8]]) -> Result<(), Error> {
+        // Copy offsets have to be multiples of the texel block size, which is at most 16 bytes.
+        let aligned = |size: usize| (size + 15) & !15;
+        let size = levels.iter().map(|level| aligned(level.len())).sum();
+        let staging = self.acquire_staging(size)?;
+
+        let mut regions = vec![];
+        let mut offset = 0;
+        for (level, data) in levels.iter().enumerate() {
+            if let Err(e) = staging.set_data(&self.device, offset, data) {
+                self.release_staging(staging, None)?;
+                return Err(e);
+            }
+            regions.push(
+                BufferImageCopy::builder()
+                    .buffer_offset(offset as u64)
+                    .image_subresource(
+                        ImageSubresourceLayers::builder()
+                            .aspect_mask(image.aspect_flags)
+                            .mip_level(level as u32)
+                            .base_array_layer(0)
+                            .layer_count(1)
+                            .build(),
+                    )
+                    .image_extent(image.mip_extent(level as u32))
+                    .build(),
+            );
+            offset += aligned(data.len());
+        }
+
+        let all_levels = 0..image.mip_levels;
+        let to_transfer = level_barrier(
+            image,
+            all_levels.clone(),
+            (ImageLayout::UNDEFINED, ImageLayout::TRANSFER_DST_OPTIMAL),
+            (PipelineStageFlags2::NONE, AccessFlags2::NONE),
+            (PipelineStageFlags2::COPY, AccessFlags2::TRANSFER_WRITE),
+        );
+        let to_sampled = level_barrier(
+            image,
+            all_levels,
+            (
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+                ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            ),
+            (PipelineStageFlags2::COPY, AccessFlags2::TRANSFER_WRITE),
+            (
+                PipelineStageFlags2::ALL_COMMANDS,
+                AccessFlags2::SHADER_SAMPLED_READ,
+            ),
+        );
+
+        let submitted = self.immediate_submit(CmdType::Any, |cmd_buffer| {
+            self.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer], &[]);
+            unsafe {
+                self.device.cmd_copy_buffer_to_image(
+                    *cmd_buffer,
+                    staging.buffer.buffer,
+                    image.image,
+                    ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &regions,
+                );
+            }
+            self.cmd_pipeline_barrier2(cmd_buffer, &[to_sampled], &[]);
+        });
+        self.release_staging(staging, None)?;
+        submitted?;
+        image.current_layout = ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+        Ok(())
+    }
+}
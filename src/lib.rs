@@ -1,24 +1,145 @@
 #![doc = include_str!("../README.md")]
 
+mod allocation_lifetime;
+mod barriers;
+mod buffer_upload;
+mod color_handling;
+mod color_space;
 mod compute_shader;
+mod crash_diagnostics;
 mod create_info;
+mod create_info_serde;
+mod damage;
+mod debug;
+mod deletion_queue;
+mod descriptor_pool;
+mod device_features;
+mod device_switch;
+mod draw_commands;
+mod dynamic_upload_buffer;
+#[cfg(feature = "egui")]
+mod egui_renderer;
+mod env_overrides;
 mod error;
+#[cfg(feature = "external")]
+mod external_memory;
+mod frame_capture;
+mod frame_limiter;
+mod frame_loop;
+mod frame_scheme;
+mod fullscreen_pass;
+#[cfg(feature = "culling")]
+mod gpu_culler;
+mod image_atlas;
 mod image_layout_transitions;
 mod imports;
 mod init;
+#[cfg(feature = "ktx2")]
+mod ktx2_texture;
+mod loader;
+mod mip_streamer;
+mod mipmaps;
+mod offscreen_head;
 pub mod pipeline_builder;
+mod pipeline_cache;
+mod pipeline_statistics;
+pub mod prelude;
+mod provenance;
+mod queue_ownership;
+mod readback;
+#[cfg(feature = "reflection")]
+mod reflection;
+#[cfg(feature = "renderdoc")]
+mod renderdoc;
+mod renderer;
+mod rendering;
+mod replayable_commands;
+mod sampler;
+mod scale_factor;
 mod shader;
+mod shared_allocator;
+mod sparse_buffer;
+mod staging_pool;
+mod surface_rotation;
+mod surface_support;
 mod swapchain;
+mod timeline_semaphore;
+mod timestamps;
+mod vertex;
+mod vertex_input;
 mod vma_buffer;
 mod vma_image;
+mod watchdog;
 
+pub use allocation_lifetime::{AllocationLifetime, LifetimeReport, LifetimeUsage};
 pub use ash;
-pub use compute_shader::ComputeShader;
-pub use create_info::VkInitCreateInfo;
+pub use barriers::{default_dependency_flags, AttachmentFeedback, Barriers};
+pub use color_handling::ColorHandling;
+pub use color_space::ColorSpaceIntent;
+pub use compute_shader::{ComputeBinding, ComputeShader};
+pub use create_info::{GpuSelection, VkInitCreateInfo, VkInitCreateInfoBuilder};
+pub use debug::{DebugLabel, DebugLabelScope};
+pub use deletion_queue::{DeferredResource, DeletionQueue, HeapState};
+pub use descriptor_pool::{
+    DescriptorAllocator, DescriptorPoolPolicy, PoolGrowth, DEFAULT_DESCRIPTORS_PER_SET,
+};
+pub use device_features::{DeviceFeatures, FeatureStruct};
+pub use device_switch::DeviceSwitchReport;
+pub use dynamic_upload_buffer::{DynamicUploadBuffer, UploadRing};
+#[cfg(feature = "egui")]
+pub use egui_renderer::EguiRenderer;
 pub use error::Error;
+#[cfg(feature = "external")]
+pub use external_memory::{ExternalHandle, ExternalImageDesc};
+pub use frame_capture::{CaptureOptions, CapturedFrame};
+pub use frame_limiter::{FrameClock, FrameLimiter, FrameTarget, SystemClock};
+pub use frame_loop::FrameContext;
+pub use frame_scheme::{FrameScheme, PerFrame};
+pub use fullscreen_pass::{FragmentSource, FullscreenPass};
+#[cfg(feature = "culling")]
+pub use gpu_culler::{Frustum, GpuCuller, ObjectBounds};
+pub use image_atlas::{AtlasRegion, ImageAtlas, ShelfPacker};
 pub use init::*;
-
+pub use loader::LoaderInfo;
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub use memoffset;
+pub use mip_streamer::{MipStreamId, MipStreamer};
+pub use mipmaps::MipGeneration;
+pub use offscreen_head::{OffscreenHead, OffscreenImage};
+pub use pipeline_builder::{
+    BlendMode, DepthInfo, StencilInfo, VKUPipeline, VKUPipelineBuilder, VertexConvert,
+};
+pub use pipeline_statistics::{ExecutableStatValue, ExecutableStats};
+pub use provenance::ResourceDescriptor;
+pub use queue_ownership::OwnershipTransfer;
+pub use readback::ReadbackSlot;
+#[cfg(feature = "reflection")]
+pub use reflection::{
+    reflect_spirv_shader, BlockLayout, BlockMember, DescriptorBinding, ReflectedType,
+    ShaderReflection, VertexInput,
+};
+pub use renderer::{BaseRenderer, RendererCreateInfo};
+pub use rendering::{RenderTargets, RenderingAttachments, RenderingConfig};
+pub use replayable_commands::ReplayableCommands;
+pub use sampler::SamplerInfo;
 #[cfg(feature = "shader")]
-pub use shader::{compile_all_shaders, shader_ad_hoc};
-pub use vma_buffer::VMABuffer;
+pub use shader::{
+    compile_all_shaders, compile_all_shaders_incremental, compile_all_shaders_with_bindings,
+    shader_ad_hoc, ShaderCompileOptions, ShaderCompileReport,
+};
+pub use shared_allocator::SharedAllocator;
+pub use sparse_buffer::SparseBuffer;
+pub use staging_pool::{MemoryReport, PooledStaging, StagingPool, StagingPoolStats};
+pub use surface_rotation::SurfaceRotation;
+pub use surface_support::{
+    choose_present_mode, choose_surface_format, query_surface_support, DeviceSurfaceSupport,
+    SurfaceSupportReport,
+};
+pub use swapchain::AcquireResult;
+pub use timestamps::GpuFrameProfiler;
+pub use vertex::{PointVertex2D, PointVertex3D, UIVertex, Vertex2D, Vertex3D};
+#[cfg(feature = "derive")]
+pub use vku_derive::VertexConvert;
+pub use vma_buffer::{AllocationInfo, VMABuffer};
 pub use vma_image::VMAImage;
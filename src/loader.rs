@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+
+use crate::{imports::*, VkInit};
+
+/// Information about the Vulkan loader in use.
+#[derive(Clone, Debug)]
+pub struct LoaderInfo {
+    /// Loader was linked at compile time with the `linked` feature instead of loaded at runtime.
+    pub linked: bool,
+    /// Highest instance-level API version supported by the loader.
+    pub instance_version: u32,
+    /// Library the loader was loaded from. Always ```None``` when linked.
+    pub library_path: Option<PathBuf>,
+}
+
+impl std::fmt::Display for LoaderInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.library_path {
+            Some(path) => write!(f, "loaded from {path:?}")?,
+            None => write!(f, "linked")?,
+        }
+        write!(
+            f,
+            ", instance version {}",
+            version_string(self.instance_version)
+        )
+    }
+}
+
+impl VkInit {
+    /// Loads the Vulkan entry points, either linked at compile time with the `linked` feature or from the platform's loader library.
+    ///
+    /// Can be used before initialization, e.g. together with [try_instance_version](VkInit::try_instance_version) to check the available Vulkan version.
+    pub fn load_entry() -> Result<(Entry, LoaderInfo), Error> {
+        #[cfg(feature = "linked")]
+        let (entry, library_path) = (Entry::linked(), None);
+
+        #[cfg(not(feature = "linked"))]
+        let (entry, library_path) = Self::load_entry_from_library()?;
+
+        let instance_version = Self::try_instance_version(&entry)?;
+        let loader_info = LoaderInfo {
+            linked: cfg!(feature = "linked"),
+            instance_version,
+            library_path,
+        };
+        trace!("Vulkan loader {loader_info}");
+
+        Ok((entry, loader_info))
+    }
+
+    /// Highest instance-level API version supported by the loader.
+    ///
+    /// Vulkan 1.0 loaders don't support the query and report ```API_VERSION_1_0```.
+    pub fn try_instance_version(entry: &Entry) -> Result<u32, Error> {
+        let version = entry.try_enumerate_instance_version()?;
+        Ok(version.unwrap_or(API_VERSION_1_0))
+    }
+
+    pub fn loader_info(&self) -> &LoaderInfo {
+        &self.loader_info
+    }
+
+    #[cfg(not(feature = "linked"))]
+    fn load_entry_from_library() -> Result<(Entry, Option<PathBuf>), Error> {
+        #[cfg(windows)]
+        const LOADER_LIBRARY_NAMES: &[&str] = &["vulkan-1.dll"];
+
+        #[cfg(all(
+            unix,
+            not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+        ))]
+        const LOADER_LIBRARY_NAMES: &[&str] = &["libvulkan.so.1", "libvulkan.so"];
+
+        #[cfg(target_os = "android")]
+        const LOADER_LIBRARY_NAMES: &[&str] = &["libvulkan.so"];
+
+        #[cfg(any(target_os = "macos", target_os = "ios"))]
+        const LOADER_LIBRARY_NAMES: &[&str] =
+            &["libvulkan.dylib", "libvulkan.1.dylib", "libMoltenVK.dylib"];
+
+        let mut last_error = None;
+        for name in LOADER_LIBRARY_NAMES {
+            match unsafe { Entry::load_from(name) } {
+                Ok(entry) => return Ok((entry, Some(PathBuf::from(name)))),
+                Err(e) => {
+                    trace!("Failed to load vulkan loader {name:?}: {e}");
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(Error::VulkanLoaderNotFound {
+            attempted: LOADER_LIBRARY_NAMES.join(", "),
+            source: last_error.expect("at least one loader library name per platform"),
+        })
+    }
+}
+
+pub(crate) fn version_string(version: u32) -> String {
+    format!(
+        "{}.{}.{}",
+        api_version_major(version),
+        api_version_minor(version),
+        api_version_patch(version)
+    )
+}
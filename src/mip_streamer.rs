@@ -0,0 +1,430 @@
+use std::ops::Range;
+
+use crate::{image_atlas::texel_block_size, imports::*, VMAImage, VkInit};
+
+/// Handle of an image registered with the [MipStreamer].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MipStreamId(usize);
+
+struct StreamedImage {
+    image: Image,
+    format: Format,
+    extent: Extent3D,
+    aspect_flags: ImageAspectFlags,
+    array_layers: u32,
+    view_type: ImageViewType,
+    /// Bytes of a texel block of ```format```.
+    block_size: usize,
+    /// Source data per mip level, level 0 is full resolution.
+    source: Vec<Vec<u8>>,
+    /// Finest resident mip level, ```source.len()``` while nothing is resident.
+    resident_base: u32,
+    /// Finest mip level that should be resident.
+    target_base: u32,
+    priority: u32,
+    view: ImageView,
+}
+
+impl StreamedImage {
+    fn mip_levels(&self) -> u32 {
+        self.source.len() as u32
+    }
+
+    fn bytes(&self, base: u32) -> u64 {
+        self.source[base as usize..]
+            .iter()
+            .map(|level| level.len() as u64)
+            .sum()
+    }
+}
+
+/// Budget-aware mip residency for sampled images.
+///
+/// Tracks the resident mip range per registered image, uploads finer mips on promotion and evicts them on demotion.
+/// Each image gets its own view restricted to the resident mips, additionally clamped with a min LOD
+/// if [image_view_min_lod](crate::PhysicalDeviceInfo::image_view_min_lod) is supported.
+///
+/// Evicted mips stay allocated, the budget applies to the resident mip levels.
+/// Views change on promotion and demotion, descriptors referencing [image_view](MipStreamer::image_view) have to be rewritten.
+#[derive(Default)]
+pub struct MipStreamer {
+    images: Vec<Option<StreamedImage>>,
+    retired_views: Vec<ImageView>,
+}
+
+impl MipStreamer {
+    /// Registers ```image``` with one source buffer per mip level, holding all array layers. No mip level is resident initially.
+    ///
+    /// ```image``` has to stay alive until it is [unregistered](MipStreamer::unregister).
+    /// Returns [UnknownTexelSize](Error::UnknownTexelSize) if the texel block size of the format is unknown.
+    pub fn register(
+        &mut self,
+        image: &VMAImage,
+        full_mip_chain_source: Vec<Vec<u8>>,
+    ) -> Result<MipStreamId, Error> {
+        if full_mip_chain_source.len() != image.mip_levels as usize {
+            return Err(Error::MipChainMismatch {
+                expected: image.mip_levels,
+                provided: full_mip_chain_source.len(),
+            });
+        }
+        let block_size =
+            texel_block_size(image.format).ok_or(Error::UnknownTexelSize(image.format))?;
+
+        let streamed = StreamedImage {
+            image: image.image,
+            format: image.format,
+            extent: image.extent,
+            aspect_flags: image.aspect_flags,
+            array_layers: image.array_layers,
+            view_type: image.view_type,
+            block_size,
+            resident_base: image.mip_levels,
+            target_base: 0,
+            priority: 0,
+            source: full_mip_chain_source,
+            view: ImageView::null(),
+        };
+
+        let index = match self.images.iter().position(Option::is_none) {
+            Some(index) => {
+                self.images[index] = Some(streamed);
+                index
+            }
+            None => {
+                self.images.push(Some(streamed));
+                self.images.len() - 1
+            }
+        };
+
+        Ok(MipStreamId(index))
+    }
+
+    /// Stops tracking the image, its view is retired.
+    pub fn unregister(&mut self, id: MipStreamId) {
+        if let Some(streamed) = self.images.get_mut(id.0).and_then(Option::take) {
+            self.retired_views.push(streamed.view);
+        }
+    }
+
+    /// Finest mip level that should be resident, 0 for full quality. Applied by [balance](MipStreamer::balance).
+    pub fn set_target_quality(&mut self, id: MipStreamId, max_mip: u32) {
+        if let Some(streamed) = self.get_mut(id) {
+            streamed.target_base = max_mip.min(streamed.mip_levels() - 1);
+        }
+    }
+
+    /// Images with lower priority are demoted first when over budget.
+    pub fn set_priority(&mut self, id: MipStreamId, priority: u32) {
+        if let Some(streamed) = self.get_mut(id) {
+            streamed.priority = priority;
+        }
+    }
+
+    /// Currently resident mip levels, empty while nothing is resident.
+    pub fn resident_mips(&self, id: MipStreamId) -> Range<u32> {
+        match self.get(id) {
+            Some(streamed) => streamed.resident_base..streamed.mip_levels(),
+            None => 0..0,
+        }
+    }
+
+    /// View restricted to the resident mip levels. Null while nothing is resident.
+    pub fn image_view(&self, id: MipStreamId) -> ImageView {
+        self.get(id)
+            .map(|streamed| streamed.view)
+            .unwrap_or_else(ImageView::null)
+    }
+
+    /// Bytes of all resident mip levels.
+    pub fn resident_bytes(&self) -> u64 {
+        self.images
+            .iter()
+            .flatten()
+            .map(|streamed| streamed.bytes(streamed.resident_base))
+            .sum()
+    }
+
+    /// Resident base mip per image that fits ```budget_bytes```.
+    ///
+    /// Starts from each image's target quality and drops the finest mip of the lowest priority image until the budget fits.
+    /// The coarsest mip level of every image is always kept.
+    pub fn plan(&self, budget_bytes: u64) -> Vec<(MipStreamId, u32)> {
+        let mut plan: Vec<(MipStreamId, u32)> = self
+            .images
+            .iter()
+            .enumerate()
+            .filter_map(|(index, streamed)| {
+                streamed
+                    .as_ref()
+                    .map(|streamed| (MipStreamId(index), streamed.target_base))
+            })
+            .collect();
+
+        let mut total: u64 = plan
+            .iter()
+            .map(|(id, base)| self.images[id.0].as_ref().map_or(0, |s| s.bytes(*base)))
+            .sum();
+
+        while total > budget_bytes {
+            let candidate = plan
+                .iter_mut()
+                .filter_map(|(id, base)| {
+                    let streamed = self.images[id.0].as_ref()?;
+                    (*base + 1 < streamed.mip_levels()).then_some((streamed, base))
+                })
+                .min_by_key(|(streamed, base)| {
+                    (
+                        streamed.priority,
+                        std::cmp::Reverse(streamed.source[**base as usize].len()),
+                    )
+                });
+
+            let Some((streamed, base)) = candidate else {
+                break;
+            };
+            total -= streamed.source[*base as usize].len() as u64;
+            *base += 1;
+        }
+
+        plan
+    }
+
+    /// Promotes and demotes all images according to [plan](MipStreamer::plan).
+    ///
+    /// Uploads are recorded into ```cmd_buffer```, staging buffers are released with ```fence```.
+    /// Returns all images whose [image_view](MipStreamer::image_view) changed.
+    pub fn balance(
+        &mut self,
+        vk_init: &mut VkInit,
+        cmd_buffer: &CommandBuffer,
+        fence: Fence,
+        budget_bytes: u64,
+    ) -> Result<Vec<MipStreamId>, Error> {
+        let mut changed = vec![];
+        for (id, base) in self.plan(budget_bytes) {
+            let resident_base = self.resident_mips(id).start;
+            let view_changed = match base.cmp(&resident_base) {
+                std::cmp::Ordering::Less => self.promote(vk_init, cmd_buffer, fence, id, base)?,
+                std::cmp::Ordering::Greater => self.demote(vk_init, id, base)?,
+                std::cmp::Ordering::Equal => false,
+            };
+            if view_changed {
+                changed.push(id);
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Uploads all mip levels finer than the resident ones down to ```base_mip``` and widens the view.
+    ///
+    /// Returns false if the mips were already resident.
+    pub fn promote(
+        &mut self,
+        vk_init: &mut VkInit,
+        cmd_buffer: &CommandBuffer,
+        fence: Fence,
+        id: MipStreamId,
+        base_mip: u32,
+    ) -> Result<bool, Error> {
+        let Some(streamed) = self.images.get(id.0).and_then(Option::as_ref) else {
+            return Ok(false);
+        };
+        let base_mip = base_mip.min(streamed.mip_levels() - 1);
+        if base_mip >= streamed.resident_base {
+            return Ok(false);
+        }
+
+        let levels = base_mip..streamed.resident_base;
+        // Copies start at a multiple of the texel block size and of 4 bytes for transfer-only queues.
+        let optimal = vk_init
+            .physical_device_info
+            .limits
+            .optimal_buffer_copy_offset_alignment
+            .max(1) as usize;
+        let alignment = lcm(lcm(streamed.block_size, optimal), 4);
+        let offsets: Vec<usize> = levels
+            .clone()
+            .scan(0, |end: &mut usize, level| {
+                let offset = end.next_multiple_of(alignment);
+                *end = offset + streamed.source[level as usize].len();
+                Some(offset)
+            })
+            .collect();
+        let size = offsets.last().copied().unwrap_or_default()
+            + streamed.source[levels.end as usize - 1].len();
+        let staging = vk_init.acquire_staging(size)?;
+
+        let mut regions = vec![];
+        for (level, &offset) in levels.clone().zip(&offsets) {
+            let data = &streamed.source[level as usize];
+            staging.set_data(&vk_init.device, offset, data)?;
+            regions.push(
+                BufferImageCopy::builder()
+                    .buffer_offset(offset as u64)
+                    .image_subresource(
+                        ImageSubresourceLayers::builder()
+                            .aspect_mask(streamed.aspect_flags)
+                            .mip_level(level)
+                            .base_array_layer(0)
+                            .layer_count(streamed.array_layers)
+                            .build(),
+                    )
+                    .image_extent(mip_extent(streamed.extent, level))
+                    .build(),
+            );
+        }
+
+        let range = ImageSubresourceRange {
+            aspect_mask: streamed.aspect_flags,
+            base_mip_level: levels.start,
+            level_count: levels.end - levels.start,
+            base_array_layer: 0,
+            layer_count: streamed.array_layers,
+        };
+        let to_transfer = ImageMemoryBarrier2::builder()
+            .image(streamed.image)
+            .subresource_range(range)
+            .old_layout(ImageLayout::UNDEFINED)
+            .new_layout(ImageLayout::TRANSFER_DST_OPTIMAL)
+            .src_stage_mask(PipelineStageFlags2::TOP_OF_PIPE)
+            .dst_stage_mask(PipelineStageFlags2::TRANSFER)
+            .dst_access_mask(AccessFlags2::TRANSFER_WRITE)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .build();
+        let to_shader_read = ImageMemoryBarrier2::builder()
+            .image(streamed.image)
+            .subresource_range(range)
+            .old_layout(ImageLayout::TRANSFER_DST_OPTIMAL)
+            .new_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .src_stage_mask(PipelineStageFlags2::TRANSFER)
+            .src_access_mask(AccessFlags2::TRANSFER_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::FRAGMENT_SHADER)
+            .dst_access_mask(AccessFlags2::SHADER_READ)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .build();
+
+        vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer], &[]);
+        unsafe {
+            vk_init.device.cmd_copy_buffer_to_image(
+                *cmd_buffer,
+                staging.buffer.buffer,
+                streamed.image,
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+                &regions,
+            );
+        }
+        vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_shader_read], &[]);
+        vk_init.release_staging(staging, Some(fence))?;
+
+        trace!("Mip streamer promoted {id:?} to mip {base_mip}");
+        self.set_resident_base(vk_init, id, base_mip)?;
+        Ok(true)
+    }
+
+    /// Narrows the view to ```base_mip``` and coarser mips.
+    ///
+    /// Returns false if ```base_mip``` is not coarser than the resident mips.
+    pub fn demote(
+        &mut self,
+        vk_init: &VkInit,
+        id: MipStreamId,
+        base_mip: u32,
+    ) -> Result<bool, Error> {
+        let Some(streamed) = self.get(id) else {
+            return Ok(false);
+        };
+        let base_mip = base_mip.min(streamed.mip_levels() - 1);
+        if base_mip <= streamed.resident_base {
+            return Ok(false);
+        }
+
+        trace!("Mip streamer demoted {id:?} to mip {base_mip}");
+        self.set_resident_base(vk_init, id, base_mip)?;
+        Ok(true)
+    }
+
+    /// Destroys views replaced by promotions and demotions. The GPU must no longer use them.
+    pub fn destroy_retired_views(&mut self, device: &Device) {
+        for view in self.retired_views.drain(..) {
+            unsafe { device.destroy_image_view(view, None) };
+        }
+    }
+
+    /// Device must be idle.
+    pub fn destroy(&mut self, device: &Device) {
+        for streamed in self.images.drain(..).flatten() {
+            self.retired_views.push(streamed.view);
+        }
+        self.destroy_retired_views(device);
+    }
+
+    fn set_resident_base(
+        &mut self,
+        vk_init: &VkInit,
+        id: MipStreamId,
+        base_mip: u32,
+    ) -> Result<(), Error> {
+        let Some(streamed) = self.images.get_mut(id.0).and_then(Option::as_mut) else {
+            return Ok(());
+        };
+
+        let min_lod = vk_init.physical_device_info.image_view_min_lod;
+        let mut min_lod_info = ImageViewMinLodCreateInfoEXT::builder()
+            .min_lod(base_mip as f32)
+            .build();
+        let mut view_info = ImageViewCreateInfo::builder()
+            .image(streamed.image)
+            .view_type(streamed.view_type)
+            .format(streamed.format)
+            .subresource_range(ImageSubresourceRange {
+                aspect_mask: streamed.aspect_flags,
+                base_mip_level: base_mip,
+                level_count: streamed.mip_levels() - base_mip,
+                base_array_layer: 0,
+                layer_count: streamed.array_layers,
+            });
+        if min_lod {
+            view_info = view_info.push_next(&mut min_lod_info);
+        }
+        let view = unsafe { vk_init.device.create_image_view(&view_info, None)? };
+        vk_init.set_debug_object_name_fmt(
+            view.as_raw(),
+            ObjectType::IMAGE_VIEW,
+            format_args!("VKU_Mip_Streamer_{}_View_Mip_{base_mip}", id.0),
+        )?;
+
+        if streamed.view != ImageView::null() {
+            self.retired_views.push(streamed.view);
+        }
+        streamed.view = view;
+        streamed.resident_base = base_mip;
+        Ok(())
+    }
+
+    fn get(&self, id: MipStreamId) -> Option<&StreamedImage> {
+        self.images.get(id.0).and_then(Option::as_ref)
+    }
+
+    fn get_mut(&mut self, id: MipStreamId) -> Option<&mut StreamedImage> {
+        self.images.get_mut(id.0).and_then(Option::as_mut)
+    }
+}
+
+fn mip_extent(extent: Extent3D, level: u32) -> Extent3D {
+    Extent3D {
+        width: (extent.width >> level).max(1),
+        height: (extent.height >> level).max(1),
+        depth: (extent.depth >> level).max(1),
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x * b
+}
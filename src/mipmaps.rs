@@ -0,0 +1,612 @@
+use std::io::Cursor;
+
+use ash::util::read_spv;
+
+use crate::{
+    descriptor_pool::DescriptorPools, imports::*, DescriptorPoolPolicy, PoolGrowth, VMAImage,
+    VkInit,
+};
+
+const DOWNSAMPLE_COMP_SPV: &[u8] = include_bytes!("shaders/downsample.comp.spv");
+const GROUP_SIZE: u32 = 8;
+
+/// How [enqueue_generate_mipmaps_with](VkInit::enqueue_generate_mipmaps_with) downsamples each mip level from the previous one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MipGeneration {
+    /// Linear blits, needs ```BLIT_SRC```, ```BLIT_DST``` and ```SAMPLED_IMAGE_FILTER_LINEAR``` support for the format.
+    Blit,
+    /// 2x2 box filter in a compute shader writing storage views of each level, needs ```ImageUsageFlags::STORAGE```.
+    ///
+    /// ```karis_average``` weights texels by their inverse luminance, suppressing fireflies when downsampling HDR images.
+    Compute { karis_average: bool },
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct DownsamplePush {
+    karis_average: u32,
+    srgb: u32,
+}
+
+impl DownsamplePush {
+    fn as_bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self as *const Self as *const u8, size_of::<Self>()) }
+    }
+}
+
+/// Downsample pipeline shared by all images, created on first use of the compute path.
+#[derive(Clone, Copy)]
+pub(crate) struct MipPipeline {
+    pipeline: Pipeline,
+    layout: PipelineLayout,
+    set_layout: DescriptorSetLayout,
+    sampler: Sampler,
+}
+
+impl MipPipeline {
+    fn new(vk_init: &VkInit) -> Result<Self, Error> {
+        let device = &vk_init.device;
+        let sampler_info = SamplerCreateInfo::builder()
+            .mag_filter(Filter::NEAREST)
+            .min_filter(Filter::NEAREST)
+            .address_mode_u(SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_v(SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_w(SamplerAddressMode::CLAMP_TO_EDGE);
+        let sampler = unsafe { device.create_sampler(&sampler_info, None)? };
+        vk_init.set_debug_object_name_fmt(
+            sampler.as_raw(),
+            ObjectType::SAMPLER,
+            format_args!("VKU_Mip_Downsample_Sampler"),
+        )?;
+
+        let samplers = [sampler];
+        let bindings = [
+            DescriptorSetLayoutBinding::builder()
+                .binding(0)
+                .descriptor_type(DescriptorType::COMBINED_IMAGE_SAMPLER)
+                .descriptor_count(1)
+                .stage_flags(ShaderStageFlags::COMPUTE)
+                .immutable_samplers(&samplers)
+                .build(),
+            DescriptorSetLayoutBinding::builder()
+                .binding(1)
+                .descriptor_type(DescriptorType::STORAGE_IMAGE)
+                .descriptor_count(1)
+                .stage_flags(ShaderStageFlags::COMPUTE)
+                .build(),
+        ];
+        let set_layout_info = DescriptorSetLayoutCreateInfo::builder().bindings(&bindings);
+        let set_layout = unsafe { device.create_descriptor_set_layout(&set_layout_info, None)? };
+        vk_init.set_debug_object_name_fmt(
+            set_layout.as_raw(),
+            ObjectType::DESCRIPTOR_SET_LAYOUT,
+            format_args!("VKU_Mip_Downsample_Desc_Layout"),
+        )?;
+
+        let set_layouts = [set_layout];
+        let push_constant_ranges = [PushConstantRange::builder()
+            .stage_flags(ShaderStageFlags::COMPUTE)
+            .size(size_of::<DownsamplePush>() as u32)
+            .build()];
+        let layout_info = PipelineLayoutCreateInfo::builder()
+            .set_layouts(&set_layouts)
+            .push_constant_ranges(&push_constant_ranges);
+        let layout = unsafe { device.create_pipeline_layout(&layout_info, None)? };
+
+        let code = read_spv(&mut Cursor::new(DOWNSAMPLE_COMP_SPV))?;
+        let module_info = ShaderModuleCreateInfo::builder().code(&code);
+        let module = unsafe { device.create_shader_module(&module_info, None)? };
+        let entry_name = CString::new("main")?;
+        let stage = PipelineShaderStageCreateInfo::builder()
+            .stage(ShaderStageFlags::COMPUTE)
+            .module(module)
+            .name(&entry_name)
+            .build();
+        let pipeline_info = ComputePipelineCreateInfo::builder()
+            .stage(stage)
+            .layout(layout)
+            .build();
+        let pipeline = unsafe {
+            device.create_compute_pipelines(vk_init.pipeline_cache, &[pipeline_info], None)
+        };
+        unsafe { device.destroy_shader_module(module, None) };
+        let pipeline = pipeline.map_err(|(_, e)| e)?[0];
+        vk_init.set_debug_object_name_fmt(
+            pipeline.as_raw(),
+            ObjectType::PIPELINE,
+            format_args!("VKU_Mip_Downsample_Pipeline"),
+        )?;
+
+        Ok(Self {
+            pipeline,
+            layout,
+            set_layout,
+            sampler,
+        })
+    }
+
+    pub(crate) fn destroy(&self, device: &Device) {
+        unsafe {
+            device.destroy_pipeline(self.pipeline, None);
+            device.destroy_pipeline_layout(self.layout, None);
+            device.destroy_descriptor_set_layout(self.set_layout, None);
+            device.destroy_sampler(self.sampler, None);
+        }
+    }
+}
+
+/// Views of each mip level and the descriptor sets downsampling level ```i``` into ```i + 1```, owned by the [VMAImage].
+pub(crate) struct MipChain {
+    views: Vec<ImageView>,
+    pools: DescriptorPools,
+    sets: Vec<DescriptorSet>,
+}
+
+impl MipChain {
+    fn new(
+        vk_init: &VkInit,
+        image: &VMAImage,
+        set_layout: DescriptorSetLayout,
+    ) -> Result<Self, Error> {
+        let policy = DescriptorPoolPolicy {
+            initial_sets: image.mip_levels - 1,
+            growth: PoolGrowth::Fixed,
+        };
+        let mut chain = Self {
+            views: vec![],
+            pools: DescriptorPools::new(
+                policy,
+                &[
+                    (DescriptorType::COMBINED_IMAGE_SAMPLER, 1),
+                    (DescriptorType::STORAGE_IMAGE, 1),
+                ],
+                "VKU_Mip_Chain",
+            ),
+            sets: vec![],
+        };
+        if let Err(e) = chain.create_views_and_sets(vk_init, image, set_layout) {
+            chain.destroy(&vk_init.device);
+            return Err(e);
+        }
+        Ok(chain)
+    }
+
+    fn create_views_and_sets(
+        &mut self,
+        vk_init: &VkInit,
+        image: &VMAImage,
+        set_layout: DescriptorSetLayout,
+    ) -> Result<(), Error> {
+        let (view_format, _) = storage_view_format(image.format);
+        for level in 0..image.mip_levels {
+            let view_info = ImageViewCreateInfo::builder()
+                .image(image.image)
+                .view_type(ImageViewType::TYPE_2D)
+                .format(view_format)
+                .subresource_range(ImageSubresourceRange {
+                    aspect_mask: image.aspect_flags,
+                    base_mip_level: level,
+                    level_count: 1,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                });
+            let view = unsafe { vk_init.device.create_image_view(&view_info, None)? };
+            self.views.push(view);
+            vk_init.set_debug_object_name_fmt(
+                view.as_raw(),
+                ObjectType::IMAGE_VIEW,
+                format_args!("VKU_Mip_Chain_Level_{level}_View"),
+            )?;
+        }
+
+        for level in 1..image.mip_levels as usize {
+            let set = self.pools.allocate(vk_init, set_layout)?;
+            let src_info = [DescriptorImageInfo {
+                sampler: Sampler::null(),
+                image_view: self.views[level - 1],
+                image_layout: ImageLayout::GENERAL,
+            }];
+            let dst_info = [DescriptorImageInfo {
+                sampler: Sampler::null(),
+                image_view: self.views[level],
+                image_layout: ImageLayout::GENERAL,
+            }];
+            let writes = [
+                WriteDescriptorSet::builder()
+                    .dst_set(set)
+                    .dst_binding(0)
+                    .descriptor_type(DescriptorType::COMBINED_IMAGE_SAMPLER)
+                    .image_info(&src_info)
+                    .build(),
+                WriteDescriptorSet::builder()
+                    .dst_set(set)
+                    .dst_binding(1)
+                    .descriptor_type(DescriptorType::STORAGE_IMAGE)
+                    .image_info(&dst_info)
+                    .build(),
+            ];
+            unsafe { vk_init.device.update_descriptor_sets(&writes, &[]) };
+            self.sets.push(set);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn destroy(&mut self, device: &Device) {
+        self.pools.destroy(device);
+        self.sets.clear();
+        for view in self.views.drain(..) {
+            unsafe { device.destroy_image_view(view, None) };
+        }
+    }
+}
+
+impl VkInit {
+    /// Mip generation chosen by [enqueue_generate_mipmaps](VkInit::enqueue_generate_mipmaps) for ```format```.
+    ///
+    /// Blits are preferred, the compute path is used if the format can't be blitted but its storage view supports ```STORAGE_IMAGE```
+    /// and ```shaderStorageImageWriteWithoutFormat``` is supported. ```None``` if neither works, e.g. for compressed formats.
+    pub fn mip_generation_for(&self, format: Format) -> Option<MipGeneration> {
+        if self.blit_mipmaps_supported(format) {
+            Some(MipGeneration::Blit)
+        } else if self.compute_mipmaps_supported(format) {
+            Some(MipGeneration::Compute {
+                karis_average: false,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Records the generation of all mip levels of ```image``` from level 0, see [mip_generation_for](VkInit::mip_generation_for).
+    ///
+    /// Level 0 must hold the image data in the image's current layout. Afterwards all levels are in ```SHADER_READ_ONLY_OPTIMAL```.
+    /// Images from [create_mipped_image](VkInit::create_mipped_image) get storage usage if they need the compute path.
+    ///
+    /// Returns [MipmapGenerationNotSupported](Error::MipmapGenerationNotSupported) if the format supports neither path.
+    pub fn enqueue_generate_mipmaps(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        image: &mut VMAImage,
+    ) -> Result<(), Error> {
+        let Some(generation) = self.mip_generation_for(image.format) else {
+            return Err(Error::MipmapGenerationNotSupported(image.format));
+        };
+        self.enqueue_generate_mipmaps_with(cmd_buffer, image, generation)
+    }
+
+    /// Same as [enqueue_generate_mipmaps](VkInit::enqueue_generate_mipmaps), with the path chosen by ```generation```.
+    ///
+    /// The compute path keeps storage views of each level and their descriptor sets with the image until it is destroyed.
+    /// It only supports single-layer images, blits generate the mip levels of all array layers.
+    pub fn enqueue_generate_mipmaps_with(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        image: &mut VMAImage,
+        generation: MipGeneration,
+    ) -> Result<(), Error> {
+        if image.mip_levels < 2 {
+            return Ok(());
+        }
+        match generation {
+            MipGeneration::Blit => {
+                if !self.blit_mipmaps_supported(image.format) {
+                    return Err(Error::MipmapGenerationNotSupported(image.format));
+                }
+                self.record_blit_mipmaps(cmd_buffer, image);
+            }
+            MipGeneration::Compute { karis_average } => {
+                if !self.compute_mipmaps_supported(image.format) {
+                    return Err(Error::MipmapGenerationNotSupported(image.format));
+                }
+                if image.array_layers > 1 {
+                    return Err(Error::MipmapGenerationNotSupported(image.format));
+                }
+                if !image.usage.contains(ImageUsageFlags::STORAGE) {
+                    return Err(Error::ImageUsageMissing(ImageUsageFlags::STORAGE));
+                }
+                let pipeline = self.mip_pipeline()?;
+                if image.mip_chain.is_none() {
+                    image.mip_chain = Some(MipChain::new(self, image, pipeline.set_layout)?);
+                }
+                self.record_compute_mipmaps(cmd_buffer, image, &pipeline, karis_average);
+            }
+        }
+        image.current_layout = ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+        Ok(())
+    }
+
+    pub(crate) fn destroy_mip_pipeline(&mut self) {
+        let pipeline = self
+            .mip_pipeline
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        if let Some(pipeline) = pipeline {
+            pipeline.destroy(&self.device);
+        }
+    }
+
+    fn mip_pipeline(&self) -> Result<MipPipeline, Error> {
+        let mut pipeline = self
+            .mip_pipeline
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(pipeline) = *pipeline {
+            return Ok(pipeline);
+        }
+        let created = MipPipeline::new(self)?;
+        *pipeline = Some(created);
+        Ok(created)
+    }
+
+    fn blit_mipmaps_supported(&self, format: Format) -> bool {
+        let required = FormatFeatureFlags::BLIT_SRC
+            | FormatFeatureFlags::BLIT_DST
+            | FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR;
+        self.optimal_tiling_features(format).contains(required)
+    }
+
+    fn compute_mipmaps_supported(&self, format: Format) -> bool {
+        let (view_format, _) = storage_view_format(format);
+        let required = FormatFeatureFlags::STORAGE_IMAGE | FormatFeatureFlags::SAMPLED_IMAGE;
+        self.physical_device_info
+            .features
+            .shader_storage_image_write_without_format
+            == TRUE
+            && self.optimal_tiling_features(view_format).contains(required)
+    }
+
+    pub(crate) fn optimal_tiling_features(&self, format: Format) -> FormatFeatureFlags {
+        unsafe {
+            self.instance
+                .get_physical_device_format_properties(self.physical_device, format)
+                .optimal_tiling_features
+        }
+    }
+
+    fn record_blit_mipmaps(&self, cmd_buffer: &CommandBuffer, image: &VMAImage) {
+        let levels = image.mip_levels;
+        self.cmd_pipeline_barrier2(
+            cmd_buffer,
+            &[
+                level_barrier(
+                    image,
+                    0..1,
+                    (image.current_layout, ImageLayout::TRANSFER_SRC_OPTIMAL),
+                    (
+                        PipelineStageFlags2::ALL_COMMANDS,
+                        AccessFlags2::MEMORY_WRITE,
+                    ),
+                    (PipelineStageFlags2::BLIT, AccessFlags2::TRANSFER_READ),
+                ),
+                level_barrier(
+                    image,
+                    1..levels,
+                    (ImageLayout::UNDEFINED, ImageLayout::TRANSFER_DST_OPTIMAL),
+                    (PipelineStageFlags2::NONE, AccessFlags2::NONE),
+                    (PipelineStageFlags2::BLIT, AccessFlags2::TRANSFER_WRITE),
+                ),
+            ],
+            &[],
+        );
+
+        for level in 1..levels {
+            let src = image.mip_extent(level - 1);
+            let dst = image.mip_extent(level);
+            let subresource = |mip_level| ImageSubresourceLayers {
+                aspect_mask: image.aspect_flags,
+                mip_level,
+                base_array_layer: 0,
+                layer_count: image.array_layers,
+            };
+            let blit = ImageBlit {
+                src_subresource: subresource(level - 1),
+                src_offsets: [Offset3D::default(), extent_offset(src)],
+                dst_subresource: subresource(level),
+                dst_offsets: [Offset3D::default(), extent_offset(dst)],
+            };
+            unsafe {
+                self.device.cmd_blit_image(
+                    *cmd_buffer,
+                    image.image,
+                    ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    image.image,
+                    ImageLayout::TRANSFER_DST_OPTIMAL,
+                    &[blit],
+                    Filter::LINEAR,
+                );
+            }
+            self.cmd_pipeline_barrier2(
+                cmd_buffer,
+                &[level_barrier(
+                    image,
+                    level..level + 1,
+                    (
+                        ImageLayout::TRANSFER_DST_OPTIMAL,
+                        ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    ),
+                    (PipelineStageFlags2::BLIT, AccessFlags2::TRANSFER_WRITE),
+                    (PipelineStageFlags2::BLIT, AccessFlags2::TRANSFER_READ),
+                )],
+                &[],
+            );
+        }
+
+        self.cmd_pipeline_barrier2(
+            cmd_buffer,
+            &[level_barrier(
+                image,
+                0..levels,
+                (
+                    ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                ),
+                (PipelineStageFlags2::BLIT, AccessFlags2::NONE),
+                (
+                    PipelineStageFlags2::ALL_COMMANDS,
+                    AccessFlags2::SHADER_SAMPLED_READ,
+                ),
+            )],
+            &[],
+        );
+    }
+
+    fn record_compute_mipmaps(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        image: &VMAImage,
+        pipeline: &MipPipeline,
+        karis_average: bool,
+    ) {
+        let Some(chain) = &image.mip_chain else {
+            return;
+        };
+        let levels = image.mip_levels;
+        self.cmd_pipeline_barrier2(
+            cmd_buffer,
+            &[
+                level_barrier(
+                    image,
+                    0..1,
+                    (image.current_layout, ImageLayout::GENERAL),
+                    (
+                        PipelineStageFlags2::ALL_COMMANDS,
+                        AccessFlags2::MEMORY_WRITE,
+                    ),
+                    (
+                        PipelineStageFlags2::COMPUTE_SHADER,
+                        AccessFlags2::SHADER_SAMPLED_READ,
+                    ),
+                ),
+                level_barrier(
+                    image,
+                    1..levels,
+                    (ImageLayout::UNDEFINED, ImageLayout::GENERAL),
+                    (PipelineStageFlags2::NONE, AccessFlags2::NONE),
+                    (
+                        PipelineStageFlags2::COMPUTE_SHADER,
+                        AccessFlags2::SHADER_STORAGE_WRITE,
+                    ),
+                ),
+            ],
+            &[],
+        );
+
+        let push = DownsamplePush {
+            karis_average: karis_average as u32,
+            srgb: storage_view_format(image.format).1 as u32,
+        };
+        unsafe {
+            self.device.cmd_bind_pipeline(
+                *cmd_buffer,
+                PipelineBindPoint::COMPUTE,
+                pipeline.pipeline,
+            );
+            self.device.cmd_push_constants(
+                *cmd_buffer,
+                pipeline.layout,
+                ShaderStageFlags::COMPUTE,
+                0,
+                push.as_bytes(),
+            );
+        }
+
+        for level in 1..levels {
+            let dst = image.mip_extent(level);
+            unsafe {
+                self.device.cmd_bind_descriptor_sets(
+                    *cmd_buffer,
+                    PipelineBindPoint::COMPUTE,
+                    pipeline.layout,
+                    0,
+                    &[chain.sets[level as usize - 1]],
+                    &[],
+                );
+                self.device.cmd_dispatch(
+                    *cmd_buffer,
+                    dst.width.div_ceil(GROUP_SIZE),
+                    dst.height.div_ceil(GROUP_SIZE),
+                    1,
+                );
+            }
+            self.cmd_pipeline_barrier2(
+                cmd_buffer,
+                &[level_barrier(
+                    image,
+                    level..level + 1,
+                    (ImageLayout::GENERAL, ImageLayout::GENERAL),
+                    (
+                        PipelineStageFlags2::COMPUTE_SHADER,
+                        AccessFlags2::SHADER_STORAGE_WRITE,
+                    ),
+                    (
+                        PipelineStageFlags2::COMPUTE_SHADER,
+                        AccessFlags2::SHADER_SAMPLED_READ,
+                    ),
+                )],
+                &[],
+            );
+        }
+
+        self.cmd_pipeline_barrier2(
+            cmd_buffer,
+            &[level_barrier(
+                image,
+                0..levels,
+                (ImageLayout::GENERAL, ImageLayout::SHADER_READ_ONLY_OPTIMAL),
+                (PipelineStageFlags2::COMPUTE_SHADER, AccessFlags2::NONE),
+                (
+                    PipelineStageFlags2::ALL_COMMANDS,
+                    AccessFlags2::SHADER_SAMPLED_READ,
+                ),
+            )],
+            &[],
+        );
+    }
+}
+
+/// Format of the per-level views used by the compute path and whether it has to encode sRGB manually,
+/// sRGB formats don't support storage images and are viewed as their UNORM equivalent.
+pub(crate) fn storage_view_format(format: Format) -> (Format, bool) {
+    match format {
+        Format::R8G8B8A8_SRGB => (Format::R8G8B8A8_UNORM, true),
+        Format::B8G8R8A8_SRGB => (Format::B8G8R8A8_UNORM, true),
+        Format::A8B8G8R8_SRGB_PACK32 => (Format::A8B8G8R8_UNORM_PACK32, true),
+        format => (format, false),
+    }
+}
+
+pub(crate) fn level_barrier(
+    image: &VMAImage,
+    levels: std::ops::Range<u32>,
+    (old_layout, new_layout): (ImageLayout, ImageLayout),
+    (src_stage, src_access): (PipelineStageFlags2, AccessFlags2),
+    (dst_stage, dst_access): (PipelineStageFlags2, AccessFlags2),
+) -> ImageMemoryBarrier2 {
+    ImageMemoryBarrier2::builder()
+        .image(image.image)
+        .old_layout(old_layout)
+        .new_layout(new_layout)
+        .src_stage_mask(src_stage)
+        .src_access_mask(src_access)
+        .dst_stage_mask(dst_stage)
+        .dst_access_mask(dst_access)
+        .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+        .subresource_range(ImageSubresourceRange {
+            aspect_mask: image.aspect_flags,
+            base_mip_level: levels.start,
+            level_count: levels.end - levels.start,
+            base_array_layer: 0,
+            layer_count: image.array_layers,
+        })
+        .build()
+}
+
+fn extent_offset(extent: Extent3D) -> Offset3D {
+    Offset3D {
+        x: extent.width as i32,
+        y: extent.height as i32,
+        z: extent.depth as i32,
+    }
+}
@@ -0,0 +1,184 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::image_atlas::texel_size;
+use crate::{imports::*, RenderTargets, VMAImage, VkInit};
+
+/// Color images and depth image rendered to instead of a swapchain, see [create_offscreen_head](VkInit::create_offscreen_head).
+pub struct OffscreenHead {
+    pub extent: Extent2D,
+    pub color_format: Format,
+    /// Color attachments that can be sampled and copied from, cycled by [acquire_offscreen_image](VkInit::acquire_offscreen_image).
+    pub images: Vec<VMAImage>,
+    /// Has the [msaa_samples](crate::VkInitCreateInfo::msaa_samples) of the color attachment.
+    pub depth_image: VMAImage,
+    /// Multisampled color attachment resolved into the acquired image by [begin_rendering](VkInit::begin_rendering),
+    /// ```None``` for [msaa_samples](crate::VkInitCreateInfo::msaa_samples) of ```TYPE_1```.
+    pub msaa_image: Option<VMAImage>,
+    pub clear_color_value: ClearColorValue,
+    pub clear_depth_stencil_value: ClearDepthStencilValue,
+    /// Index of the image the next acquire returns.
+    next: AtomicUsize,
+}
+
+/// Image returned by [acquire_offscreen_image](VkInit::acquire_offscreen_image).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OffscreenImage {
+    pub index: usize,
+    pub image: Image,
+    pub view: ImageView,
+}
+
+impl VkInit {
+    /// Creates ```image_count``` color images and a depth image to render to without a window, e.g. for CI and server-side rendering.
+    ///
+    /// [begin_rendering](VkInit::begin_rendering) renders to them on instances without a [Head](crate::Head),
+    /// pass the view of [acquire_offscreen_image](VkInit::acquire_offscreen_image). Nothing is presented.
+    /// The clear values and [msaa_samples](crate::VkInitCreateInfo::msaa_samples) of the [VkInitCreateInfo](crate::VkInitCreateInfo) are used.
+    /// An existing offscreen head is destroyed first.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let init_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_headless(init_info)?;
+    /// let extent = Extent2D { width: 256, height: 256 };
+    /// init.create_offscreen_head(extent, Format::R8G8B8A8_UNORM, Format::D32_SFLOAT, 2)?;
+    ///
+    /// let target = init.acquire_offscreen_image()?;
+    /// // transition the images to their attachment layouts, then
+    /// // init.begin_rendering(&target.view, &cmd_buffer)?;
+    /// # init.destroy()?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn create_offscreen_head(
+        &mut self,
+        extent: Extent2D,
+        color_format: Format,
+        depth_format: Format,
+        image_count: usize,
+    ) -> Result<(), Error> {
+        self.destroy_offscreen_head()?;
+        Self::check_msaa_samples(
+            &self.physical_device_info.limits,
+            self.create_info.msaa_samples,
+        )?;
+        let color_sizeof = texel_size(color_format).ok_or(Error::UnknownTexelSize(color_format))?;
+        let depth_sizeof = match depth_format {
+            format if format == self.create_info.depth_format => {
+                self.create_info.depth_format_sizeof
+            }
+            Format::D16_UNORM => 2,
+            Format::D32_SFLOAT_S8_UINT => 8,
+            _ => 4,
+        };
+        let extent_3d = Extent3D {
+            width: extent.width,
+            height: extent.height,
+            depth: 1,
+        };
+
+        let image_count = image_count.max(1);
+        let mut images = Vec::with_capacity(image_count);
+        for _ in 0..image_count {
+            images.push(self.allocate_with_retry(|device, allocator| {
+                VMAImage::create_render_image(
+                    device,
+                    allocator,
+                    extent_3d,
+                    color_format,
+                    color_sizeof,
+                )
+            })?);
+        }
+        let samples = self.create_info.msaa_samples;
+        let (depth_image, msaa_image) = self.allocate_with_retry(|device, allocator| unsafe {
+            Self::create_attachments(
+                device,
+                allocator,
+                extent,
+                color_format,
+                depth_format,
+                depth_sizeof,
+                samples,
+            )
+        })?;
+
+        for (i, image) in images.iter().enumerate() {
+            image.set_debug_object_name(self, format!("VKU_Offscreen_Image_{i}"))?;
+        }
+        depth_image.set_debug_object_name(self, String::from("VKU_Offscreen_Depth"))?;
+        if let Some(msaa_image) = &msaa_image {
+            msaa_image.set_debug_object_name(self, String::from("VKU_Offscreen_MSAA_Color"))?;
+        }
+
+        self.offscreen_head = Some(OffscreenHead {
+            extent,
+            color_format,
+            images,
+            depth_image,
+            msaa_image,
+            clear_color_value: self.create_info.clear_color_value,
+            clear_depth_stencil_value: self.create_info.clear_depth_stencil_value,
+            next: AtomicUsize::new(0),
+        });
+        Ok(())
+    }
+
+    /// Returns the next image of the [offscreen head](VkInit::create_offscreen_head), cycling through all of them.
+    ///
+    /// Waiting until the previous rendering to the image completed is up to the caller.
+    pub fn acquire_offscreen_image(&self) -> Result<OffscreenImage, Error> {
+        let Some(offscreen) = self.offscreen_head.as_ref() else {
+            return Err(Error::OffscreenHeadMissing);
+        };
+        let index = offscreen.next.fetch_add(1, Ordering::Relaxed) % offscreen.images.len();
+        let image = &offscreen.images[index];
+        Ok(OffscreenImage {
+            index,
+            image: image.image,
+            view: image.image_view,
+        })
+    }
+
+    /// Destroys the images of the [offscreen head](VkInit::create_offscreen_head) if there is one, the device must be idle.
+    pub fn destroy_offscreen_head(&mut self) -> Result<(), Error> {
+        let Some(mut offscreen) = self.offscreen_head.take() else {
+            return Ok(());
+        };
+        for image in &mut offscreen.images {
+            image.destroy(&self.device, &mut self.allocator.lock())?;
+        }
+        if let Some(msaa_image) = &mut offscreen.msaa_image {
+            msaa_image.destroy(&self.device, &mut self.allocator.lock())?;
+        }
+        offscreen
+            .depth_image
+            .destroy(&self.device, &mut self.allocator.lock())
+    }
+
+    /// Depth attachment, extent and clear values of the swapchain, or of the offscreen head on instances without a [Head](crate::Head).
+    ///
+    /// Returns [HeadCallOnHeadlessInstance](Error::HeadCallOnHeadlessInstance) if there is neither.
+    pub fn render_targets(&self) -> Result<RenderTargets, Error> {
+        if let Some(head) = self.head.as_ref() {
+            return Ok(RenderTargets {
+                extent: head.surface_info.current_extent,
+                clear_color_value: head.clear_color_value,
+                clear_depth_stencil_value: head.clear_depth_stencil_value,
+                depth_view: head.depth_image.image_view,
+                msaa_view: head.msaa_image.as_ref().map(|image| image.image_view),
+            });
+        }
+        let Some(offscreen) = self.offscreen_head.as_ref() else {
+            return Err(Error::HeadCallOnHeadlessInstance);
+        };
+        Ok(RenderTargets {
+            extent: offscreen.extent,
+            clear_color_value: offscreen.clear_color_value,
+            clear_depth_stencil_value: offscreen.clear_depth_stencil_value,
+            depth_view: offscreen.depth_image.image_view,
+            msaa_view: offscreen.msaa_image.as_ref().map(|image| image.image_view),
+        })
+    }
+}
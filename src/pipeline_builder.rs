@@ -1,19 +1,27 @@
 use ash::util::read_spv;
 use ash::vk::*;
 use ash::Device;
+use log::{trace, warn};
 use std::ffi::CString;
 use std::mem::size_of;
 use std::path::Path;
 use std::result::Result;
 
+use crate::provenance::Provenance;
 use crate::Error;
+#[cfg(feature = "reflection")]
+use crate::ShaderReflection;
 use crate::VkInit;
 
 pub struct VKUPipeline {
     pub set_layout: DescriptorSetLayout,
+    /// ```RenderPass::null()``` for pipelines built [with_rendering](VKUPipelineBuilder::with_rendering).
     pub renderpass: RenderPass,
     pub layout: PipelineLayout,
     pub pipeline: Pipeline,
+    /// Created with ```CAPTURE_STATISTICS_KHR```, see [with_executable_statistics](VKUPipelineBuilder::with_executable_statistics).
+    pub(crate) captures_statistics: bool,
+    provenance: Provenance,
 }
 
 impl VKUPipeline {
@@ -21,13 +29,23 @@ impl VKUPipeline {
         VKUPipelineBuilder::default()
     }
 
+    /// Destroying an already destroyed pipeline is a no-op.
     pub fn destroy(&mut self, device: &Device) -> Result<(), Error> {
+        self.provenance
+            .check_instance(device, "VKUPipeline", "destroy");
+        self.provenance.release();
         unsafe {
             device.destroy_descriptor_set_layout(self.set_layout, None);
             device.destroy_pipeline_layout(self.layout, None);
             device.destroy_pipeline(self.pipeline, None);
-            device.destroy_render_pass(self.renderpass, None);
+            if self.renderpass != RenderPass::null() {
+                device.destroy_render_pass(self.renderpass, None);
+            }
         }
+        self.set_layout = DescriptorSetLayout::null();
+        self.layout = PipelineLayout::null();
+        self.pipeline = Pipeline::null();
+        self.renderpass = RenderPass::null();
 
         Ok(())
     }
@@ -44,6 +62,7 @@ pub struct VKUPipelineBuilder {
     pipeline_vertex_input: (
         Vec<VertexInputBindingDescription>,
         Vec<VertexInputAttributeDescription>,
+        Vec<VertexInputBindingDivisorDescriptionEXT>,
     ),
     pipeline_input_assembly: PrimitiveTopology,
     pipeline_tesselation: u32,
@@ -52,6 +71,7 @@ pub struct VKUPipelineBuilder {
     pipeline_multisample: SampleCountFlags,
     pipeline_depthstencil: (DepthInfo, StencilInfo),
     pipeline_colorblend: Vec<PipelineColorBlendAttachmentState>,
+    pipeline_colorblend_flags: PipelineColorBlendStateCreateFlags,
     pipeline_dynamic: Vec<DynamicState>,
     pipeline_layout: (
         Vec<DescriptorBindingFlags>,
@@ -63,15 +83,55 @@ pub struct VKUPipelineBuilder {
         Vec<SubpassDescription>,
         Vec<SubpassDependency>,
     ),
+    pipeline_rendering: Option<(Vec<Format>, Format, Format)>,
+    /// Reflected push constant block size per stage.
+    push_constant_blocks: Vec<(ShaderStageFlags, u32)>,
+    pipeline_flags: PipelineCreateFlags,
+    executable_statistics: bool,
+    pipeline_cache: Option<PipelineCache>,
 }
 
 impl VKUPipelineBuilder {
+    /// Returns [PushConstantSizeMismatch](Error::PushConstantSizeMismatch) if a shader stage declares a larger push constant block
+    /// than the [push constant range](VKUPipelineBuilder::with_push_constants) covering it, checked with the ```reflection``` feature only.
+    ///
+    /// With ```DynamicState::VERTEX_INPUT_EXT``` the static vertex input is ignored and returns
+    /// [VertexInputDynamicStateNotSupported](Error::VertexInputDynamicStateNotSupported) if the extension is not supported.
     pub fn build(self, vk_init: &VkInit, base_name: &str) -> Result<VKUPipeline, Error> {
-        let (bindings, attribs) = self.pipeline_vertex_input;
-        let pipeline_vertex_input = PipelineVertexInputStateCreateInfo::builder()
+        Self::validate_push_constants(&self.push_constant_blocks, &self.pipeline_layout.2)?;
+        let dynamic_vertex_input = self
+            .pipeline_dynamic
+            .contains(&DynamicState::VERTEX_INPUT_EXT);
+        let (bindings, attribs, divisors) = match dynamic_vertex_input {
+            true if !vk_init.physical_device_info.vertex_input_dynamic_state => {
+                return Err(Error::VertexInputDynamicStateNotSupported);
+            }
+            true => Default::default(),
+            false => self.pipeline_vertex_input,
+        };
+        Self::validate_divisors(vk_init, &bindings, &divisors)?;
+        // A divisor of 1 is the regular instance rate, only other divisors need the extension struct.
+        let divisors: Vec<VertexInputBindingDivisorDescriptionEXT> = divisors
+            .into_iter()
+            .filter(|divisor| divisor.divisor != 1)
+            .collect();
+        let mut pipeline_vertex_input_divisor =
+            PipelineVertexInputDivisorStateCreateInfoEXT::builder()
+                .vertex_binding_divisors(&divisors)
+                .build();
+        let mut pipeline_vertex_input = PipelineVertexInputStateCreateInfo::builder()
             .vertex_binding_descriptions(&bindings)
-            .vertex_attribute_descriptions(&attribs)
-            .build();
+            .vertex_attribute_descriptions(&attribs);
+        if vk_init
+            .physical_device_info
+            .vertex_attribute_divisor
+            .is_some()
+            && !divisors.is_empty()
+        {
+            pipeline_vertex_input =
+                pipeline_vertex_input.push_next(&mut pipeline_vertex_input_divisor);
+        }
+        let pipeline_vertex_input = pipeline_vertex_input.build();
 
         let topology = self.pipeline_input_assembly;
         let pipeline_input_assembly = PipelineInputAssemblyStateCreateInfo::builder()
@@ -116,6 +176,7 @@ impl VKUPipelineBuilder {
 
         let attachments = self.pipeline_colorblend;
         let pipeline_colorblend = PipelineColorBlendStateCreateInfo::builder()
+            .flags(self.pipeline_colorblend_flags)
             .attachments(&attachments)
             .build();
 
@@ -188,18 +249,47 @@ impl VKUPipelineBuilder {
             unsafe { vk_init.device.create_pipeline_layout(&create_info, None)? }
         };
 
-        let (attachments, subpasses, dependencies) = self.pipeline_renderpass;
-        let renderpass = {
-            let create_info = RenderPassCreateInfo::builder()
-                .attachments(&attachments)
-                .subpasses(&subpasses)
-                .dependencies(&dependencies)
-                .build();
+        let (color_formats, depth_format, stencil_format) =
+            self.pipeline_rendering.clone().unwrap_or_default();
+        let mut pipeline_rendering = PipelineRenderingCreateInfo::builder()
+            .color_attachment_formats(&color_formats)
+            .depth_attachment_format(depth_format)
+            .stencil_attachment_format(stencil_format)
+            .build();
 
-            unsafe { vk_init.device.create_render_pass(&create_info, None)? }
+        let (attachments, subpasses, dependencies) = self.pipeline_renderpass;
+        let renderpass = match self.pipeline_rendering {
+            Some(_) => RenderPass::null(),
+            None => {
+                let create_info = RenderPassCreateInfo::builder()
+                    .attachments(&attachments)
+                    .subpasses(&subpasses)
+                    .dependencies(&dependencies)
+                    .build();
+
+                unsafe { vk_init.device.create_render_pass(&create_info, None)? }
+            }
         };
 
-        let pipeline_create_info = GraphicsPipelineCreateInfo::builder()
+        let mut pipeline_flags = self.pipeline_flags;
+        if !vk_init.physical_device_info.attachment_feedback_loop_layout {
+            let feedback_flags = PipelineCreateFlags::COLOR_ATTACHMENT_FEEDBACK_LOOP_EXT
+                | PipelineCreateFlags::DEPTH_STENCIL_ATTACHMENT_FEEDBACK_LOOP_EXT;
+            if pipeline_flags.intersects(feedback_flags) {
+                trace!("{base_name}: attachment feedback loop layout not supported, using the GENERAL layout fallback");
+            }
+            pipeline_flags &= !feedback_flags;
+        }
+        let captures_statistics = self.executable_statistics
+            && vk_init.physical_device_info.pipeline_executable_properties;
+        if captures_statistics {
+            pipeline_flags |= PipelineCreateFlags::CAPTURE_STATISTICS_KHR;
+        } else if self.executable_statistics {
+            trace!("{base_name}: pipeline executable properties not supported, no statistics are captured");
+        }
+
+        let mut pipeline_create_info = GraphicsPipelineCreateInfo::builder()
+            .flags(pipeline_flags)
             .vertex_input_state(&pipeline_vertex_input)
             .input_assembly_state(&pipeline_input_assembly)
             .tessellation_state(&pipeline_tesselation)
@@ -212,41 +302,53 @@ impl VKUPipelineBuilder {
             .stages(&pipeline_stages)
             .layout(layout)
             .render_pass(renderpass)
-            .subpass(0)
-            .build();
+            .subpass(0);
+        if renderpass == RenderPass::null() {
+            pipeline_create_info = pipeline_create_info.push_next(&mut pipeline_rendering);
+        }
+        let pipeline_create_info = pipeline_create_info.build();
 
-        let pipeline = unsafe { Self::create_pipeline(vk_init, &[pipeline_create_info])? };
+        let pipeline_cache = self.pipeline_cache.unwrap_or(vk_init.pipeline_cache);
+        let pipeline =
+            unsafe { Self::create_pipeline(vk_init, pipeline_cache, &[pipeline_create_info])? };
 
         for (_, module, _, _) in self.pipeline_stages {
             unsafe { vk_init.device.destroy_shader_module(module, None) }
         }
 
-        vk_init.set_debug_object_name(
+        vk_init.set_debug_object_name_fmt(
             set_layouts[0].as_raw(),
             ObjectType::DESCRIPTOR_SET_LAYOUT,
-            format!("{base_name}_Desc_Set_Layout"),
+            format_args!("{base_name}_Desc_Set_Layout"),
         )?;
-        vk_init.set_debug_object_name(
+        vk_init.set_debug_object_name_fmt(
             layout.as_raw(),
             ObjectType::PIPELINE_LAYOUT,
-            format!("{base_name}_Pipeline_Layout"),
+            format_args!("{base_name}_Pipeline_Layout"),
         )?;
-        vk_init.set_debug_object_name(
+        vk_init.set_debug_object_name_fmt(
             pipeline.as_raw(),
             ObjectType::PIPELINE,
-            format!("{base_name}_Pipeline"),
-        )?;
-        vk_init.set_debug_object_name(
-            renderpass.as_raw(),
-            ObjectType::RENDER_PASS,
-            format!("{base_name}_Renderpass"),
+            format_args!("{base_name}_Pipeline"),
         )?;
+        if renderpass != RenderPass::null() {
+            vk_init.set_debug_object_name_fmt(
+                renderpass.as_raw(),
+                ObjectType::RENDER_PASS,
+                format_args!("{base_name}_Renderpass"),
+            )?;
+        }
+
+        let provenance = Provenance::new(&vk_init.device, "VKUPipeline");
+        provenance.set_name(base_name);
 
         Ok(VKUPipeline {
             set_layout: set_layouts[0],
             layout,
             pipeline,
             renderpass,
+            captures_statistics,
+            provenance,
         })
     }
 
@@ -261,6 +363,7 @@ impl VKUPipelineBuilder {
             let mut file = std::fs::File::open(path.as_ref())?;
 
             let spirv = read_spv(&mut file)?;
+            self.reflect_push_constants(stage, &spirv);
 
             let create_info = ShaderModuleCreateInfo::builder()
                 .flags(ShaderModuleCreateFlags::empty())
@@ -297,6 +400,7 @@ impl VKUPipelineBuilder {
         spirv: &[u32],
         spec_constants: &[u32],
     ) -> Result<Self, Error> {
+        self.reflect_push_constants(stage, spirv);
         let module = {
             let create_info = ShaderModuleCreateInfo::builder()
                 .flags(ShaderModuleCreateFlags::empty())
@@ -328,19 +432,30 @@ impl VKUPipelineBuilder {
 
     #[cfg(feature = "shader")]
     pub fn push_shader_stage_glsl(
+        self,
+        device: &Device,
+        stage: ShaderStageFlags,
+        glsl: String,
+        spec_constants: &[u32],
+    ) -> Result<Self, Error> {
+        let options = crate::ShaderCompileOptions::default();
+        self.push_shader_stage_glsl_with_options(device, stage, glsl, spec_constants, &options)
+    }
+
+    /// Same as [push_shader_stage_glsl](VKUPipelineBuilder::push_shader_stage_glsl), compiled with ```options```, e.g. for defines.
+    #[cfg(feature = "shader")]
+    pub fn push_shader_stage_glsl_with_options(
         mut self,
         device: &Device,
         stage: ShaderStageFlags,
         glsl: String,
         spec_constants: &[u32],
+        options: &crate::ShaderCompileOptions,
     ) -> Result<Self, Error> {
-        let ext = match stage {
-            ShaderStageFlags::VERTEX => "vert",
-            ShaderStageFlags::FRAGMENT => "frag",
-            _ => return Err(Error::UnknownShaderFileExtension),
-        };
+        let ext = crate::shader::stage_extension(stage)?;
 
-        let compiled = crate::shader::shader_ad_hoc(glsl, "", ext, false)?;
+        let compiled = crate::shader::shader_ad_hoc(glsl, "", ext, options.clone())?;
+        self.reflect_push_constants(stage, compiled.as_binary());
 
         let module = {
             let create_info = ShaderModuleCreateInfo::builder()
@@ -372,6 +487,9 @@ impl VKUPipelineBuilder {
         Ok(self)
     }
 
+    /// Creates a render pass for the pipeline, which is destroyed with it.
+    ///
+    /// Pipelines used with [begin_rendering](VkInit::begin_rendering) need [with_rendering](VKUPipelineBuilder::with_rendering) instead.
     pub fn with_render_pass(
         mut self,
         attachments: &[AttachmentDescription],
@@ -386,6 +504,23 @@ impl VKUPipelineBuilder {
         self
     }
 
+    /// Targets dynamic rendering with the given attachment formats instead of a render pass, e.g. for [begin_rendering](VkInit::begin_rendering).
+    ///
+    /// No render pass is created, [renderpass](VKUPipeline::renderpass) stays ```RenderPass::null()``` and [with_render_pass](VKUPipelineBuilder::with_render_pass) is ignored.
+    pub fn with_rendering(
+        mut self,
+        color_formats: &[Format],
+        depth_format: Option<Format>,
+        stencil_format: Option<Format>,
+    ) -> Self {
+        self.pipeline_rendering = Some((
+            color_formats.to_vec(),
+            depth_format.unwrap_or(Format::UNDEFINED),
+            stencil_format.unwrap_or(Format::UNDEFINED),
+        ));
+        self
+    }
+
     pub fn with_rasterization(
         mut self,
         polygon_mode: PolygonMode,
@@ -395,6 +530,12 @@ impl VKUPipelineBuilder {
         self
     }
 
+    /// Builds with ```cache``` instead of the cache [loaded by VkInit](VkInit::load_or_create_pipeline_cache).
+    pub fn with_pipeline_cache(mut self, cache: PipelineCache) -> Self {
+        self.pipeline_cache = Some(cache);
+        self
+    }
+
     pub fn with_tesselation(mut self, patch_control_points: u32) -> Self {
         self.pipeline_tesselation = patch_control_points;
         self
@@ -425,13 +566,45 @@ impl VKUPipelineBuilder {
         self
     }
 
+    /// E.g. ```PipelineColorBlendStateCreateFlags::RASTERIZATION_ORDER_ATTACHMENT_ACCESS_EXT```, see [AttachmentFeedback](crate::AttachmentFeedback).
+    pub fn with_colorblend_flags(mut self, flags: PipelineColorBlendStateCreateFlags) -> Self {
+        self.pipeline_colorblend_flags = flags;
+        self
+    }
+
+    /// Lets the pipeline read color and/or depth attachments it renders to, see [feedback_loop_layout](VkInit::feedback_loop_layout).
+    ///
+    /// Sets the ```ATTACHMENT_FEEDBACK_LOOP_EXT``` pipeline create flags, which are dropped on build
+    /// if [attachment_feedback_loop_layout](crate::PhysicalDeviceInfo::attachment_feedback_loop_layout) is not supported.
+    pub fn with_feedback_loop(mut self, color: bool, depth: bool) -> Self {
+        self.pipeline_flags &= !(PipelineCreateFlags::COLOR_ATTACHMENT_FEEDBACK_LOOP_EXT
+            | PipelineCreateFlags::DEPTH_STENCIL_ATTACHMENT_FEEDBACK_LOOP_EXT);
+        if color {
+            self.pipeline_flags |= PipelineCreateFlags::COLOR_ATTACHMENT_FEEDBACK_LOOP_EXT;
+        }
+        if depth {
+            self.pipeline_flags |= PipelineCreateFlags::DEPTH_STENCIL_ATTACHMENT_FEEDBACK_LOOP_EXT;
+        }
+        self
+    }
+
+    /// Captures driver statistics like register usage and instruction counts, see [executable_statistics](VKUPipeline::executable_statistics).
+    ///
+    /// Ignored if [pipeline_executable_properties](crate::PhysicalDeviceInfo::pipeline_executable_properties) is not supported.
+    pub fn with_executable_statistics(mut self, capture: bool) -> Self {
+        self.executable_statistics = capture;
+        self
+    }
+
+    /// ```DynamicState::VERTEX_INPUT_EXT``` replaces the [vertex](VKUPipelineBuilder::with_vertex) layout,
+    /// set it per draw with [cmd_set_vertex_input](VkInit::cmd_set_vertex_input).
     pub fn with_dynamic(mut self, dynamic_states: &[DynamicState]) -> Self {
         self.pipeline_dynamic = dynamic_states.to_vec();
         self
     }
 
     pub fn with_vertex<V: VertexConvert>(mut self, primitive_topology: PrimitiveTopology) -> Self {
-        self.pipeline_vertex_input = (V::binding_desc(), V::attrib_desc());
+        self.pipeline_vertex_input = (V::binding_desc(), V::attrib_desc(), V::binding_divisors());
         self.pipeline_input_assembly = primitive_topology;
         self
     }
@@ -478,13 +651,93 @@ impl VKUPipelineBuilder {
         self
     }
 
+    #[cfg(feature = "reflection")]
+    fn reflect_push_constants(&mut self, stage: ShaderStageFlags, code: &[u32]) {
+        match ShaderReflection::new(code) {
+            Ok(reflection) => self
+                .push_constant_blocks
+                .push((stage, reflection.push_constant_size())),
+            Err(e) => warn!("{stage:?} shader could not be reflected, its push constants are not validated: {e}"),
+        }
+    }
+
+    #[cfg(not(feature = "reflection"))]
+    fn reflect_push_constants(&mut self, _stage: ShaderStageFlags, _code: &[u32]) {}
+
+    fn validate_push_constants(
+        blocks: &[(ShaderStageFlags, u32)],
+        ranges: &[PushConstantRange],
+    ) -> Result<(), Error> {
+        for (stage, shader) in blocks.iter().filter(|(_, size)| *size > 0) {
+            let rust = ranges
+                .iter()
+                .filter(|range| range.stage_flags.contains(*stage))
+                .map(|range| range.offset + range.size)
+                .max()
+                .unwrap_or(0);
+            if rust < *shader {
+                return Err(Error::PushConstantSizeMismatch {
+                    stage: *stage,
+                    rust,
+                    shader: *shader,
+                });
+            }
+            if rust > *shader {
+                warn!("push constant range of {rust} bytes is larger than the {shader} byte block declared by the {stage:?} shader");
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_divisors(
+        vk_init: &VkInit,
+        bindings: &[VertexInputBindingDescription],
+        divisors: &[VertexInputBindingDivisorDescriptionEXT],
+    ) -> Result<(), Error> {
+        for divisor in divisors {
+            let instanced = bindings.iter().any(|binding| {
+                binding.binding == divisor.binding
+                    && binding.input_rate == VertexInputRate::INSTANCE
+            });
+            if !instanced {
+                return Err(Error::VertexAttributeDivisorOnNonInstancedBinding(
+                    divisor.binding,
+                ));
+            }
+
+            let Some(support) = vk_init.physical_device_info.vertex_attribute_divisor else {
+                if divisor.divisor == 1 {
+                    continue;
+                }
+                return Err(Error::VertexAttributeDivisorNotSupported {
+                    binding: divisor.binding,
+                    divisor: divisor.divisor,
+                });
+            };
+
+            let supported = match divisor.divisor {
+                0 => support.zero_divisor,
+                d => d <= support.max_vertex_attrib_divisor,
+            };
+            if !supported {
+                return Err(Error::VertexAttributeDivisorNotSupported {
+                    binding: divisor.binding,
+                    divisor: divisor.divisor,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     unsafe fn create_pipeline(
         vk_init: &VkInit,
+        pipeline_cache: PipelineCache,
         create_infos: &[GraphicsPipelineCreateInfo],
     ) -> Result<Pipeline, Error> {
         match vk_init
             .device
-            .create_graphics_pipelines(PipelineCache::null(), create_infos, None)
+            .create_graphics_pipelines(pipeline_cache, create_infos, None)
         {
             Ok(pipeline) => Ok(pipeline[0]),
             Err(e) => Err(Error::VkError(e.1)),
@@ -535,6 +788,51 @@ pub struct StencilInfo {
 pub trait VertexConvert {
     fn binding_desc() -> Vec<VertexInputBindingDescription>;
     fn attrib_desc() -> Vec<VertexInputAttributeDescription>;
+
+    /// Optional instance rate divisors for bindings with [VertexInputRate::INSTANCE].
+    ///
+    /// Divisors other than 1 require [VK_EXT_vertex_attribute_divisor](https://registry.khronos.org/vulkan/specs/1.3-extensions/man/html/VK_EXT_vertex_attribute_divisor.html),
+    /// see [vertex_attribute_divisor](crate::PhysicalDeviceInfo::vertex_attribute_divisor).
+    fn binding_divisors() -> Vec<VertexInputBindingDivisorDescriptionEXT> {
+        vec![]
+    }
+
+    /// [binding_desc](VertexConvert::binding_desc) including [binding_divisors](VertexConvert::binding_divisors),
+    /// for [cmd_set_vertex_input](VkInit::cmd_set_vertex_input).
+    fn binding_desc2() -> Vec<VertexInputBindingDescription2EXT> {
+        let divisors = Self::binding_divisors();
+        Self::binding_desc()
+            .iter()
+            .map(|binding| {
+                let divisor = divisors
+                    .iter()
+                    .find(|divisor| divisor.binding == binding.binding)
+                    .map(|divisor| divisor.divisor)
+                    .unwrap_or(1);
+                VertexInputBindingDescription2EXT::builder()
+                    .binding(binding.binding)
+                    .stride(binding.stride)
+                    .input_rate(binding.input_rate)
+                    .divisor(divisor)
+                    .build()
+            })
+            .collect()
+    }
+
+    /// [attrib_desc](VertexConvert::attrib_desc) for [cmd_set_vertex_input](VkInit::cmd_set_vertex_input).
+    fn attrib_desc2() -> Vec<VertexInputAttributeDescription2EXT> {
+        Self::attrib_desc()
+            .iter()
+            .map(|attrib| {
+                VertexInputAttributeDescription2EXT::builder()
+                    .location(attrib.location)
+                    .binding(attrib.binding)
+                    .format(attrib.format)
+                    .offset(attrib.offset)
+                    .build()
+            })
+            .collect()
+    }
 }
 
 impl VertexConvert for () {
@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use crate::{imports::*, VkInit};
+
+/// Size of ```VkPipelineCacheHeaderVersionOne```.
+const HEADER_SIZE: usize = 16 + UUID_SIZE;
+
+impl VkInit {
+    /// Creates a pipeline cache, optionally seeded with data of [get_pipeline_cache_data](VkInit::get_pipeline_cache_data).
+    ///
+    /// ```initial_data``` written by a different device or driver is ignored with a warning and an empty cache is created instead.
+    pub fn create_pipeline_cache(
+        &self,
+        initial_data: Option<&[u8]>,
+    ) -> Result<PipelineCache, Error> {
+        let initial_data = match initial_data {
+            Some(data) if !self.pipeline_cache_data_compatible(data) => {
+                warn!("Pipeline cache data does not match the current device or driver, starting with an empty cache");
+                &[]
+            }
+            Some(data) => data,
+            None => &[],
+        };
+        let create_info = PipelineCacheCreateInfo::builder().initial_data(initial_data);
+        let cache = unsafe { self.device.create_pipeline_cache(&create_info, None)? };
+        self.set_debug_object_name_fmt(
+            cache.as_raw(),
+            ObjectType::PIPELINE_CACHE,
+            format_args!("VKU_Pipeline_Cache"),
+        )?;
+        Ok(cache)
+    }
+
+    pub fn get_pipeline_cache_data(&self, cache: PipelineCache) -> Result<Vec<u8>, Error> {
+        Ok(unsafe { self.device.get_pipeline_cache_data(cache)? })
+    }
+
+    pub fn destroy_pipeline_cache(&self, cache: PipelineCache) {
+        unsafe { self.device.destroy_pipeline_cache(cache, None) };
+    }
+
+    /// Creates the pipeline cache used by all pipelines of this instance, seeded with the file at ```path``` if it exists.
+    ///
+    /// A missing file or data of a different device or driver starts with an empty cache.
+    /// Replaces and destroys a previously loaded cache, use [save_pipeline_cache](VkInit::save_pipeline_cache) to write it back.
+    /// Pipelines built [with_pipeline_cache](crate::VKUPipelineBuilder::with_pipeline_cache) use their own cache instead.
+    pub fn load_or_create_pipeline_cache(&mut self, path: &Path) -> Result<PipelineCache, Error> {
+        let data = match std::fs::read(path) {
+            Ok(data) => Some(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let cache = self.create_pipeline_cache(data.as_deref())?;
+        self.destroy_loaded_pipeline_cache();
+        self.pipeline_cache = cache;
+        Ok(cache)
+    }
+
+    /// Writes the cache of [load_or_create_pipeline_cache](VkInit::load_or_create_pipeline_cache) to ```path```.
+    ///
+    /// Returns [PipelineCacheNotLoaded](Error::PipelineCacheNotLoaded) if no cache was loaded.
+    pub fn save_pipeline_cache(&self, path: &Path) -> Result<(), Error> {
+        if self.pipeline_cache == PipelineCache::null() {
+            return Err(Error::PipelineCacheNotLoaded);
+        }
+        let data = self.get_pipeline_cache_data(self.pipeline_cache)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// Cache of [load_or_create_pipeline_cache](VkInit::load_or_create_pipeline_cache), ```PipelineCache::null()``` if none was loaded.
+    pub fn pipeline_cache(&self) -> PipelineCache {
+        self.pipeline_cache
+    }
+
+    pub(crate) fn destroy_loaded_pipeline_cache(&mut self) {
+        if self.pipeline_cache != PipelineCache::null() {
+            self.destroy_pipeline_cache(self.pipeline_cache);
+            self.pipeline_cache = PipelineCache::null();
+        }
+    }
+
+    /// Checks the header of ```data``` against the vendor, device and cache UUID of the physical device.
+    fn pipeline_cache_data_compatible(&self, data: &[u8]) -> bool {
+        if data.len() < HEADER_SIZE {
+            return false;
+        }
+        let word = |index: usize| {
+            let bytes = [
+                data[index * 4],
+                data[index * 4 + 1],
+                data[index * 4 + 2],
+                data[index * 4 + 3],
+            ];
+            u32::from_ne_bytes(bytes)
+        };
+        let properties = unsafe {
+            self.instance
+                .get_physical_device_properties(self.physical_device)
+        };
+        word(0) as usize >= HEADER_SIZE
+            && word(1) == PipelineCacheHeaderVersion::ONE.as_raw() as u32
+            && word(2) == properties.vendor_id
+            && word(3) == properties.device_id
+            && data[16..HEADER_SIZE] == properties.pipeline_cache_uuid
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::{Display, Formatter};
+
+use ash::extensions::khr::PipelineExecutableProperties;
+
+use crate::{imports::*, pipeline_builder::VKUPipeline, VkInit};
+
+/// Value of a driver statistic, e.g. a register count or whether registers were spilled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExecutableStatValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+}
+
+impl Display for ExecutableStatValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Self::Bool(value) => value.to_string(),
+            Self::Int(value) => value.to_string(),
+            Self::UInt(value) => value.to_string(),
+            Self::Float(value) => format!("{value:.3}"),
+        };
+        f.pad(&value)
+    }
+}
+
+/// Driver statistics of one executable of a pipeline, usually one per shader stage.
+#[derive(Clone, Debug)]
+pub struct ExecutableStats {
+    pub name: String,
+    pub description: String,
+    pub stages: ShaderStageFlags,
+    pub subgroup_size: u32,
+    /// Statistic names and values in driver order.
+    pub statistics: Vec<(String, ExecutableStatValue)>,
+}
+
+impl VKUPipeline {
+    /// Queries the driver's statistics per executable, e.g. register pressure, spills and instruction counts.
+    ///
+    /// Empty unless the pipeline was built [with_executable_statistics](crate::pipeline_builder::VKUPipelineBuilder::with_executable_statistics)
+    /// and [pipeline_executable_properties](crate::PhysicalDeviceInfo::pipeline_executable_properties) is supported.
+    pub fn executable_statistics(&self, vk_init: &VkInit) -> Result<Vec<ExecutableStats>, Error> {
+        if !self.captures_statistics {
+            return Ok(vec![]);
+        }
+
+        let loader = PipelineExecutableProperties::new(&vk_init.instance, &vk_init.device);
+        let pipeline_info = PipelineInfoKHR::builder().pipeline(self.pipeline);
+        let executables = unsafe { loader.get_pipeline_executable_properties(&pipeline_info)? };
+
+        executables
+            .iter()
+            .enumerate()
+            .map(|(index, properties)| {
+                let executable_info = PipelineExecutableInfoKHR::builder()
+                    .pipeline(self.pipeline)
+                    .executable_index(index as u32);
+                let statistics =
+                    unsafe { loader.get_pipeline_executable_statistics(&executable_info)? };
+                Ok(ExecutableStats {
+                    name: char_array_to_string(&properties.name)?,
+                    description: char_array_to_string(&properties.description)?,
+                    stages: properties.stages,
+                    subgroup_size: properties.subgroup_size,
+                    statistics: statistics
+                        .iter()
+                        .map(|statistic| {
+                            Ok((
+                                char_array_to_string(&statistic.name)?,
+                                stat_value(statistic),
+                            ))
+                        })
+                        .collect::<Result<_, Error>>()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Logs the [executable statistics](VKUPipeline::executable_statistics) as one table per executable.
+    pub fn log_statistics(&self, vk_init: &VkInit) -> Result<(), Error> {
+        for executable in self.executable_statistics(vk_init)? {
+            let mut table = format!(
+                "{} ({:?}, subgroup size {})",
+                executable.name, executable.stages, executable.subgroup_size
+            );
+            for (name, value) in &executable.statistics {
+                table.push_str(&format!("\n  {name:<40} {value:>12}"));
+            }
+            info!("{table}");
+        }
+        Ok(())
+    }
+}
+
+fn stat_value(statistic: &PipelineExecutableStatisticKHR) -> ExecutableStatValue {
+    unsafe {
+        match statistic.format {
+            PipelineExecutableStatisticFormatKHR::BOOL32 => {
+                ExecutableStatValue::Bool(statistic.value.b32 == TRUE)
+            }
+            PipelineExecutableStatisticFormatKHR::INT64 => {
+                ExecutableStatValue::Int(statistic.value.i64)
+            }
+            PipelineExecutableStatisticFormatKHR::FLOAT64 => {
+                ExecutableStatValue::Float(statistic.value.f64)
+            }
+            _ => ExecutableStatValue::UInt(statistic.value.u64),
+        }
+    }
+}
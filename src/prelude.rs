@@ -0,0 +1,13 @@
+//! Stable set of vku types and the ash types almost every user needs, ```use vku::prelude::*;```.
+//!
+//! Anything else is reachable through the crate root and [ash](crate::ash).
+
+pub use crate::{
+    AcquireResult, BlendMode, CmdType, DepthInfo, Error, GpuSelection, Head, PhysicalDeviceInfo,
+    StencilInfo, SurfaceInfo, VKUPipeline, VKUPipelineBuilder, VMABuffer, VMAImage, VertexConvert,
+    VkInit, VkInitCreateInfo,
+};
+
+pub use ash::vk::{
+    BufferUsageFlags, Extent2D, Extent3D, Format, ImageAspectFlags, ShaderStageFlags,
+};
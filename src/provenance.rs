@@ -0,0 +1,348 @@
+use std::ops::Range;
+#[cfg(debug_assertions)]
+use std::{
+    backtrace::Backtrace,
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+};
+
+use crate::{imports::*, VkInit};
+
+/// Still registered resource reported by [VkInit::destroy] and [VkInit::live_resources].
+#[derive(Clone, Debug)]
+pub struct ResourceDescriptor {
+    /// Resource type, e.g. ```VMABuffer```.
+    pub kind: &'static str,
+    pub name: Option<String>,
+    /// Creation backtrace if [capture_resource_backtraces](crate::VkInitCreateInfo::capture_resource_backtraces) is set.
+    pub backtrace: Option<String>,
+}
+
+impl std::fmt::Display for ResourceDescriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.kind,
+            self.name.as_deref().unwrap_or("<unnamed>")
+        )?;
+        if let Some(backtrace) = &self.backtrace {
+            write!(f, "\ncreated at:\n{backtrace}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(debug_assertions)]
+struct LiveResource {
+    instance_id: u64,
+    kind: &'static str,
+    name: Option<String>,
+    backtrace: Option<Backtrace>,
+    /// Raw memory handle and byte range of memory owned by another resource.
+    aliased: Option<(u64, Range<u64>)>,
+    /// The device was replaced by [switch_device](VkInit::switch_device) while the resource was alive.
+    retired: bool,
+}
+
+/// Resources of all live [VkInit]s, keyed by serial.
+#[cfg(debug_assertions)]
+struct Registry {
+    next_serial: u64,
+    capture_backtraces: BTreeSet<u64>,
+    /// Device generation per live instance, see [device_generation](VkInit::device_generation).
+    device_generations: BTreeMap<u64, u64>,
+    resources: BTreeMap<u64, LiveResource>,
+}
+
+#[cfg(debug_assertions)]
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    next_serial: 0,
+    capture_backtraces: BTreeSet::new(),
+    device_generations: BTreeMap::new(),
+    resources: BTreeMap::new(),
+});
+
+#[cfg(debug_assertions)]
+fn with_registry<R>(f: impl FnOnce(&mut Registry) -> R) -> R {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    f(&mut registry)
+}
+
+/// Records the creating [VkInit] and debug name of a resource, so misuse triggers a named assertion instead of a driver crash.
+///
+/// Resources stay registered until they are [released](Provenance::release), so [VkInit::destroy] can report forgotten ones.
+/// Only active in debug builds, release builds compile the checks out entirely.
+#[derive(Debug)]
+pub(crate) struct Provenance {
+    #[cfg(debug_assertions)]
+    instance_id: u64,
+    #[cfg(debug_assertions)]
+    serial: u64,
+    #[cfg(debug_assertions)]
+    device_generation: u64,
+}
+
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+impl Provenance {
+    pub(crate) fn new(device: &Device, kind: &'static str) -> Self {
+        #[cfg(debug_assertions)]
+        {
+            let instance_id = instance_id(device);
+            let (serial, device_generation) = with_registry(|registry| {
+                let device_generation = registry
+                    .device_generations
+                    .get(&instance_id)
+                    .copied()
+                    .unwrap_or(0);
+                let backtrace = registry
+                    .capture_backtraces
+                    .contains(&instance_id)
+                    .then(Backtrace::force_capture);
+                let serial = registry.next_serial;
+                registry.next_serial += 1;
+                registry.resources.insert(
+                    serial,
+                    LiveResource {
+                        instance_id,
+                        kind,
+                        name: None,
+                        backtrace,
+                        aliased: None,
+                        retired: false,
+                    },
+                );
+                (serial, device_generation)
+            });
+            Self {
+                instance_id,
+                serial,
+                device_generation,
+            }
+        }
+        #[cfg(not(debug_assertions))]
+        Self {}
+    }
+
+    pub(crate) fn set_name(&self, name: &str) {
+        #[cfg(debug_assertions)]
+        with_registry(|registry| {
+            if let Some(resource) = registry.resources.get_mut(&self.serial) {
+                resource.name = Some(name.to_string());
+            }
+        });
+    }
+
+    /// Records that the resource is bound to ```range``` of ```memory``` owned by another resource.
+    pub(crate) fn set_aliased(&self, memory: DeviceMemory, range: Range<u64>) {
+        #[cfg(debug_assertions)]
+        with_registry(|registry| {
+            if let Some(resource) = registry.resources.get_mut(&self.serial) {
+                resource.aliased = Some((memory.as_raw(), range));
+            }
+        });
+    }
+
+    /// Asserts that no live resource aliases ```range``` of ```memory``` before the owner frees it.
+    pub(crate) fn check_not_aliased(&self, memory: DeviceMemory, range: Range<u64>, kind: &str) {
+        #[cfg(debug_assertions)]
+        {
+            let aliases: Vec<String> = with_registry(|registry| {
+                registry
+                    .resources
+                    .values()
+                    .filter(|resource| resource.instance_id == self.instance_id)
+                    .filter(|resource| {
+                        resource.aliased.as_ref().is_some_and(|(raw, aliased)| {
+                            *raw == memory.as_raw()
+                                && aliased.start < range.end
+                                && range.start < aliased.end
+                        })
+                    })
+                    .map(|resource| {
+                        format!(
+                            "{} {}",
+                            resource.kind,
+                            resource.name.as_deref().unwrap_or("<unnamed>")
+                        )
+                    })
+                    .collect()
+            });
+            debug_assert!(
+                aliases.is_empty(),
+                "{kind} {} destroyed while its memory is still aliased by [{}]",
+                self.name(),
+                aliases.join(", ")
+            );
+        }
+    }
+
+    /// Unregisters the resource on destroy, releasing twice is a no-op.
+    pub(crate) fn release(&self) {
+        #[cfg(debug_assertions)]
+        with_registry(|registry| registry.resources.remove(&self.serial));
+    }
+
+    /// Asserts that the resource was created by ```device```'s [VkInit] and its current device.
+    pub(crate) fn check_instance(&self, device: &Device, kind: &str, operation: &str) {
+        #[cfg(debug_assertions)]
+        {
+            let (retired, current_generation) = with_registry(|registry| {
+                (
+                    registry
+                        .resources
+                        .get(&self.serial)
+                        .is_some_and(|resource| resource.retired),
+                    registry
+                        .device_generations
+                        .get(&instance_id(device))
+                        .copied(),
+                )
+            });
+            let stale = retired
+                || (self.instance_id == instance_id(device)
+                    && current_generation.is_some_and(|current| current != self.device_generation));
+            debug_assert!(
+                !stale,
+                "{kind} {} used in {operation} was created on device generation {}, which was replaced by switch_device",
+                self.name(),
+                self.device_generation
+            );
+            debug_assert_eq!(
+                self.instance_id,
+                instance_id(device),
+                "{kind} {} used in {operation} with a VkInit it was not created by",
+                self.name()
+            );
+        }
+    }
+
+    /// Asserts that the resource was not destroyed yet.
+    pub(crate) fn check_alive(&self, alive: bool, kind: &str, operation: &str) {
+        #[cfg(debug_assertions)]
+        debug_assert!(
+            alive,
+            "{kind} {} used in {operation} after it was destroyed",
+            self.name()
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    fn name(&self) -> String {
+        with_registry(|registry| {
+            registry
+                .resources
+                .get(&self.serial)
+                .and_then(|resource| resource.name.clone())
+        })
+        .unwrap_or_else(|| String::from("<unnamed>"))
+    }
+}
+
+/// Warns about resources dropped without being destroyed, their handles and memory are leaked.
+///
+/// The resource is unregistered, so [VkInit::destroy] does not report it again.
+impl Drop for Provenance {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        {
+            let leaked = with_registry(|registry| registry.resources.remove(&self.serial));
+            if let Some(resource) = leaked.filter(|resource| !resource.retired) {
+                warn!(
+                    "{} {} dropped without destroy, its handles and memory are leaked",
+                    resource.kind,
+                    resource.name.as_deref().unwrap_or("<unnamed>")
+                );
+                if let Some(backtrace) = &resource.backtrace {
+                    warn!("created at:\n{backtrace}");
+                }
+            }
+        }
+    }
+}
+
+fn instance_id(device: &Device) -> u64 {
+    device.handle().as_raw()
+}
+
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+pub(crate) fn register_instance(device: &Device, capture_backtraces: bool, device_generation: u64) {
+    #[cfg(debug_assertions)]
+    with_registry(|registry| {
+        let instance_id = instance_id(device);
+        // Device handles can be reused after an earlier VkInit was destroyed.
+        registry
+            .resources
+            .retain(|_, resource| resource.instance_id != instance_id);
+        match capture_backtraces {
+            true => registry.capture_backtraces.insert(instance_id),
+            false => registry.capture_backtraces.remove(&instance_id),
+        };
+        registry
+            .device_generations
+            .insert(instance_id, device_generation);
+    });
+}
+
+/// Keeps the resources of ```device``` registered as retired, so using them after [switch_device](VkInit::switch_device) asserts.
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+pub(crate) fn retire_instance(device: &Device) {
+    #[cfg(debug_assertions)]
+    with_registry(|registry| {
+        let instance_id = instance_id(device);
+        for resource in registry.resources.values_mut() {
+            if resource.instance_id == instance_id {
+                resource.retired = true;
+            }
+        }
+        registry.capture_backtraces.remove(&instance_id);
+        registry.device_generations.remove(&instance_id);
+    });
+}
+
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+pub(crate) fn unregister_instance(device: &Device) {
+    #[cfg(debug_assertions)]
+    with_registry(|registry| {
+        let instance_id = instance_id(device);
+        registry
+            .resources
+            .retain(|_, resource| resource.instance_id != instance_id);
+        registry.capture_backtraces.remove(&instance_id);
+        registry.device_generations.remove(&instance_id);
+    });
+}
+
+impl VkInit {
+    /// Id recorded by resources created from this instance to detect cross-instance use in debug builds.
+    ///
+    /// Derived from the logical device handle, unique among all live instances.
+    pub fn instance_id(&self) -> u64 {
+        instance_id(&self.device)
+    }
+
+    /// [VMABuffers](crate::VMABuffer), [VMAImages](crate::VMAImage) and [VKUPipelines](crate::pipeline_builder::VKUPipeline)
+    /// created by this instance that were not destroyed yet, including the ones owned by this instance.
+    ///
+    /// Always empty in release builds.
+    pub fn live_resources(&self) -> Vec<ResourceDescriptor> {
+        #[cfg(debug_assertions)]
+        {
+            let instance_id = self.instance_id();
+            with_registry(|registry| {
+                registry
+                    .resources
+                    .values()
+                    .filter(|resource| resource.instance_id == instance_id && !resource.retired)
+                    .map(|resource| ResourceDescriptor {
+                        kind: resource.kind,
+                        name: resource.name.clone(),
+                        backtrace: resource.backtrace.as_ref().map(|bt| bt.to_string()),
+                    })
+                    .collect()
+            })
+        }
+        #[cfg(not(debug_assertions))]
+        Vec::new()
+    }
+}
@@ -0,0 +1,404 @@
+use crate::{
+    imports::*, init::semaphore_submit_info, CmdType, QueueRequirements, VMABuffer, VMAImage,
+    VkInit,
+};
+
+/// Barrier pair moving a resource from the queue family of one [CmdType] to another.
+///
+/// Record ```release``` on the source queue and ```acquire``` on the destination queue, after the release was submitted
+/// and synchronized with a semaphore or fence. If both [CmdTypes](CmdType) map to the same queue family,
+/// ```release``` is a regular barrier covering the whole dependency and ```acquire``` is ```None```.
+#[derive(Clone, Copy, Debug)]
+pub struct OwnershipTransfer<Barrier> {
+    pub release: Barrier,
+    pub acquire: Option<Barrier>,
+}
+
+impl VkInit {
+    /// Barriers transferring ```buffer``` from ```src``` to ```dst```, see [OwnershipTransfer].
+    ///
+    /// ```src_scope``` are the stages and accesses of the last use on the source queue, ```dst_scope``` those of the first use on the destination queue.
+    pub fn buffer_ownership_transfer(
+        &self,
+        buffer: &VMABuffer,
+        src: CmdType,
+        dst: CmdType,
+        src_scope: (PipelineStageFlags2, AccessFlags2),
+        dst_scope: (PipelineStageFlags2, AccessFlags2),
+    ) -> OwnershipTransfer<BufferMemoryBarrier2> {
+        buffer.check(&self.device, "buffer_ownership_transfer");
+        let barrier =
+            |(src_stage, src_access), (dst_stage, dst_access), (src_family, dst_family)| {
+                BufferMemoryBarrier2::builder()
+                    .buffer(buffer.buffer)
+                    .size(WHOLE_SIZE)
+                    .src_stage_mask(src_stage)
+                    .src_access_mask(src_access)
+                    .dst_stage_mask(dst_stage)
+                    .dst_access_mask(dst_access)
+                    .src_queue_family_index(src_family)
+                    .dst_queue_family_index(dst_family)
+                    .build()
+            };
+        self.ownership_transfer(src, dst, src_scope, dst_scope, barrier)
+    }
+
+    /// Barriers transferring ```image``` from ```src``` to ```dst``` and transitioning it from its current layout to ```dst_layout```, see [OwnershipTransfer].
+    ///
+    /// Both barriers carry the same layout transition, which executes once. Current layout is set to ```dst_layout```.
+    pub fn image_ownership_transfer(
+        &self,
+        image: &mut VMAImage,
+        src: CmdType,
+        dst: CmdType,
+        dst_layout: ImageLayout,
+        src_scope: (PipelineStageFlags2, AccessFlags2),
+        dst_scope: (PipelineStageFlags2, AccessFlags2),
+    ) -> OwnershipTransfer<ImageMemoryBarrier2> {
+        image.check(&self.device, "image_ownership_transfer");
+        let old_layout = image.current_layout;
+        let subresource_range = ImageSubresourceRange {
+            aspect_mask: image.aspect_flags,
+            base_mip_level: 0,
+            level_count: image.mip_levels,
+            base_array_layer: 0,
+            layer_count: REMAINING_ARRAY_LAYERS,
+        };
+        let barrier =
+            |(src_stage, src_access), (dst_stage, dst_access), (src_family, dst_family)| {
+                ImageMemoryBarrier2::builder()
+                    .image(image.image)
+                    .old_layout(old_layout)
+                    .new_layout(dst_layout)
+                    .src_stage_mask(src_stage)
+                    .src_access_mask(src_access)
+                    .dst_stage_mask(dst_stage)
+                    .dst_access_mask(dst_access)
+                    .src_queue_family_index(src_family)
+                    .dst_queue_family_index(dst_family)
+                    .subresource_range(subresource_range)
+                    .build()
+            };
+        let transfer = self.ownership_transfer(src, dst, src_scope, dst_scope, barrier);
+        image.current_layout = dst_layout;
+        transfer
+    }
+
+    fn ownership_transfer<Barrier>(
+        &self,
+        src: CmdType,
+        dst: CmdType,
+        src_scope: (PipelineStageFlags2, AccessFlags2),
+        dst_scope: (PipelineStageFlags2, AccessFlags2),
+        barrier: impl Fn(
+            (PipelineStageFlags2, AccessFlags2),
+            (PipelineStageFlags2, AccessFlags2),
+            (u32, u32),
+        ) -> Barrier,
+    ) -> OwnershipTransfer<Barrier> {
+        let (_, src_family) = self.get_queue(src);
+        let (_, dst_family) = self.get_queue(dst);
+        if src_family == dst_family {
+            return OwnershipTransfer {
+                release: barrier(
+                    src_scope,
+                    dst_scope,
+                    (QUEUE_FAMILY_IGNORED, QUEUE_FAMILY_IGNORED),
+                ),
+                acquire: None,
+            };
+        }
+
+        // The destination scope of a release and the source scope of an acquire are ignored.
+        let none = (PipelineStageFlags2::NONE, AccessFlags2::NONE);
+        OwnershipTransfer {
+            release: barrier(src_scope, none, (src_family, dst_family)),
+            acquire: Some(barrier(none, dst_scope, (src_family, dst_family))),
+        }
+    }
+}
+
+/// Resource kept owned by the unified queue family around the work of a [QueueHandoff].
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum HandoffResource {
+    Buffer(Buffer),
+    /// Transitioned from ```old_layout``` to ```layout``` for the work and to ```new_layout``` after it.
+    Image {
+        image: Image,
+        subresource_range: ImageSubresourceRange,
+        old_layout: ImageLayout,
+        layout: ImageLayout,
+        new_layout: ImageLayout,
+    },
+}
+
+/// Submits work to the queue picked by [get_queue_for](VkInit::get_queue_for) while its resources stay owned by the unified queue family.
+///
+/// If a dedicated queue is picked, every [submit](QueueHandoff::submit) releases the resources on the unified queue, runs the work
+/// on the dedicated queue and acquires them back, chained with semaphores. Otherwise the work is guarded with regular barriers.
+pub(crate) struct QueueHandoff {
+    queue: Queue,
+    queue_family_index: u32,
+    scope: (PipelineStageFlags2, AccessFlags2),
+    resources: Vec<HandoffResource>,
+    unified: Option<UnifiedHandoff>,
+}
+
+struct UnifiedHandoff {
+    queue: Queue,
+    queue_family_index: u32,
+    cmd_pool: CommandPool,
+    release: CommandBuffer,
+    acquire: CommandBuffer,
+    released: Semaphore,
+    returned: Semaphore,
+}
+
+impl QueueHandoff {
+    pub(crate) fn queue_family_index(&self) -> u32 {
+        self.queue_family_index
+    }
+
+    /// Records the acquire of the resources, or a barrier from previous work on the unified queue.
+    pub(crate) fn record_begin(&self, vk_init: &VkInit, cmd_buffer: &CommandBuffer) {
+        let (images, buffers) = match &self.unified {
+            Some(unified) => self.barriers(
+                (PipelineStageFlags2::NONE, AccessFlags2::NONE),
+                self.scope,
+                (unified.queue_family_index, self.queue_family_index),
+                false,
+            ),
+            None => self.barriers(
+                (
+                    PipelineStageFlags2::ALL_COMMANDS,
+                    AccessFlags2::MEMORY_WRITE,
+                ),
+                self.scope,
+                (QUEUE_FAMILY_IGNORED, QUEUE_FAMILY_IGNORED),
+                false,
+            ),
+        };
+        vk_init.cmd_pipeline_barrier2(cmd_buffer, &images, &buffers);
+    }
+
+    /// Records the release of the resources, or a barrier to later work on the unified queue.
+    pub(crate) fn record_end(&self, vk_init: &VkInit, cmd_buffer: &CommandBuffer) {
+        let (images, buffers) = match &self.unified {
+            Some(unified) => self.barriers(
+                self.scope,
+                (PipelineStageFlags2::NONE, AccessFlags2::NONE),
+                (self.queue_family_index, unified.queue_family_index),
+                true,
+            ),
+            None => self.barriers(
+                self.scope,
+                (
+                    PipelineStageFlags2::ALL_COMMANDS,
+                    AccessFlags2::MEMORY_READ | AccessFlags2::MEMORY_WRITE,
+                ),
+                (QUEUE_FAMILY_IGNORED, QUEUE_FAMILY_IGNORED),
+                true,
+            ),
+        };
+        vk_init.cmd_pipeline_barrier2(cmd_buffer, &images, &buffers);
+    }
+
+    /// Submits ```cmd_buffers```, recorded between [record_begin](QueueHandoff::record_begin) and [record_end](QueueHandoff::record_end).
+    ///
+    /// ```fence``` signals once the resources are back on the unified queue. It has to be waited on before submitting again.
+    pub(crate) fn submit(
+        &self,
+        vk_init: &VkInit,
+        cmd_buffers: &[CommandBuffer],
+        fence: &Fence,
+    ) -> Result<(), Error> {
+        let Some(unified) = &self.unified else {
+            return vk_init.submit_to_queue(self.queue, cmd_buffers, Some(fence), &[], &[]);
+        };
+
+        unsafe {
+            vk_init
+                .device
+                .reset_command_pool(unified.cmd_pool, CommandPoolResetFlags::empty())?
+        };
+        let none = (PipelineStageFlags2::NONE, AccessFlags2::NONE);
+        let unified_scope = (
+            PipelineStageFlags2::ALL_COMMANDS,
+            AccessFlags2::MEMORY_READ | AccessFlags2::MEMORY_WRITE,
+        );
+        let (images, buffers) = self.barriers(
+            unified_scope,
+            none,
+            (unified.queue_family_index, self.queue_family_index),
+            false,
+        );
+        vk_init.begin_cmd_buffer(&unified.release)?;
+        vk_init.cmd_pipeline_barrier2(&unified.release, &images, &buffers);
+        unsafe { vk_init.device.end_command_buffer(unified.release)? };
+        let (images, buffers) = self.barriers(
+            none,
+            unified_scope,
+            (self.queue_family_index, unified.queue_family_index),
+            true,
+        );
+        vk_init.begin_cmd_buffer(&unified.acquire)?;
+        vk_init.cmd_pipeline_barrier2(&unified.acquire, &images, &buffers);
+        unsafe { vk_init.device.end_command_buffer(unified.acquire)? };
+
+        let released = [semaphore_submit_info(
+            unified.released,
+            PipelineStageFlags2::ALL_COMMANDS,
+        )];
+        let returned = [semaphore_submit_info(
+            unified.returned,
+            PipelineStageFlags2::ALL_COMMANDS,
+        )];
+        vk_init.submit_to_queue(unified.queue, &[unified.release], None, &[], &released)?;
+        let result = vk_init
+            .submit_to_queue(self.queue, cmd_buffers, None, &released, &returned)
+            .and_then(|_| {
+                vk_init.submit_to_queue(
+                    unified.queue,
+                    &[unified.acquire],
+                    Some(fence),
+                    &returned,
+                    &[],
+                )
+            });
+        if result.is_err() {
+            // The semaphores may still be signaled by the submitted part, nothing waits on the fence.
+            unsafe {
+                vk_init.device.queue_wait_idle(self.queue)?;
+                vk_init.device.queue_wait_idle(unified.queue)?;
+            }
+        }
+        result
+    }
+
+    pub(crate) fn destroy(&mut self, device: &Device) {
+        if let Some(unified) = self.unified.take() {
+            unsafe {
+                device.destroy_semaphore(unified.released, None);
+                device.destroy_semaphore(unified.returned, None);
+                device.destroy_command_pool(unified.cmd_pool, None);
+            }
+        }
+    }
+
+    fn barriers(
+        &self,
+        (src_stage, src_access): (PipelineStageFlags2, AccessFlags2),
+        (dst_stage, dst_access): (PipelineStageFlags2, AccessFlags2),
+        (src_family, dst_family): (u32, u32),
+        after: bool,
+    ) -> (Vec<ImageMemoryBarrier2>, Vec<BufferMemoryBarrier2>) {
+        let mut images = Vec::new();
+        let mut buffers = Vec::new();
+        for resource in &self.resources {
+            match *resource {
+                HandoffResource::Buffer(buffer) => buffers.push(
+                    BufferMemoryBarrier2::builder()
+                        .buffer(buffer)
+                        .size(WHOLE_SIZE)
+                        .src_stage_mask(src_stage)
+                        .src_access_mask(src_access)
+                        .dst_stage_mask(dst_stage)
+                        .dst_access_mask(dst_access)
+                        .src_queue_family_index(src_family)
+                        .dst_queue_family_index(dst_family)
+                        .build(),
+                ),
+                HandoffResource::Image {
+                    image,
+                    subresource_range,
+                    old_layout,
+                    layout,
+                    new_layout,
+                } => {
+                    let (old_layout, new_layout) = match after {
+                        true => (layout, new_layout),
+                        false => (old_layout, layout),
+                    };
+                    images.push(
+                        ImageMemoryBarrier2::builder()
+                            .image(image)
+                            .old_layout(old_layout)
+                            .new_layout(new_layout)
+                            .src_stage_mask(src_stage)
+                            .src_access_mask(src_access)
+                            .dst_stage_mask(dst_stage)
+                            .dst_access_mask(dst_access)
+                            .src_queue_family_index(src_family)
+                            .dst_queue_family_index(dst_family)
+                            .subresource_range(subresource_range)
+                            .build(),
+                    )
+                }
+            }
+        }
+        (images, buffers)
+    }
+}
+
+impl VkInit {
+    /// Picks the queue for ```cmd_type``` through [get_queue_for](VkInit::get_queue_for) and prepares handing ```resources``` to it.
+    ///
+    /// ```scope``` are the stages and accesses of the work on the picked queue.
+    pub(crate) fn queue_handoff(
+        &self,
+        cmd_type: CmdType,
+        requirements: QueueRequirements,
+        scope: (PipelineStageFlags2, AccessFlags2),
+        resources: Vec<HandoffResource>,
+    ) -> Result<QueueHandoff, Error> {
+        let (queue, queue_family_index) = self.get_queue_for(cmd_type, requirements);
+        let unified_queue_family_index = self.physical_device_info.unified_queue_family_index;
+        let mut handoff = QueueHandoff {
+            queue,
+            queue_family_index,
+            scope,
+            resources,
+            unified: None,
+        };
+        if queue_family_index == unified_queue_family_index || handoff.resources.is_empty() {
+            return Ok(handoff);
+        }
+
+        let pool_info = CommandPoolCreateInfo::builder()
+            .queue_family_index(unified_queue_family_index)
+            .flags(CommandPoolCreateFlags::TRANSIENT);
+        let cmd_pool = unsafe { self.device.create_command_pool(&pool_info, None)? };
+        handoff.unified = Some(UnifiedHandoff {
+            queue: self.unified_queue,
+            queue_family_index: unified_queue_family_index,
+            cmd_pool,
+            release: CommandBuffer::null(),
+            acquire: CommandBuffer::null(),
+            released: Semaphore::null(),
+            returned: Semaphore::null(),
+        });
+        // The handles created so far are destroyed with the handoff on error.
+        let result = (|| {
+            let unified = handoff.unified.as_mut().unwrap();
+            let cmd_buffers =
+                self.create_command_buffers_named(&unified.cmd_pool, 2, "VKU_Queue_Handoff")?;
+            (unified.release, unified.acquire) = (cmd_buffers[0], cmd_buffers[1]);
+            unified.released = unsafe {
+                self.device
+                    .create_semaphore(&SemaphoreCreateInfo::default(), None)?
+            };
+            unified.returned = unsafe {
+                self.device
+                    .create_semaphore(&SemaphoreCreateInfo::default(), None)?
+            };
+            Ok(())
+        })();
+        match result {
+            Ok(()) => Ok(handoff),
+            Err(e) => {
+                handoff.destroy(&self.device);
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use std::time::Duration;
+
+use crate::{
+    imports::*,
+    queue_ownership::{HandoffResource, QueueHandoff},
+    CmdType, QueueRequirements, VMABuffer, VMAImage, VkInit,
+};
+
+/// Host-visible readback buffer gated by a fence.
+///
+/// Pass ```fence``` to the submission that copies into ```buffer```. Reads only succeed once the fence has signaled,
+/// call [reuse](ReadbackSlot::reuse) before submitting into the slot again.
+pub struct ReadbackSlot {
+    pub buffer: VMABuffer,
+    pub fence: Fence,
+    /// Requested size in bytes, the allocation may be larger.
+    size: usize,
+    /// Transient pool of the copy recorded by [read_buffer_async](VkInit::read_buffer_async).
+    cmd_pool: CommandPool,
+    /// Ownership transfers of the copy source recorded by [read_buffer_async](VkInit::read_buffer_async).
+    handoff: Option<QueueHandoff>,
+}
+
+impl ReadbackSlot {
+    /// Creates a readback buffer of ```size``` bytes with an unsignaled fence.
+    pub fn new(device: &Device, allocator: &mut Allocator, size: usize) -> Result<Self, Error> {
+        let buffer = VMABuffer::create_readback_buffer(
+            device,
+            allocator,
+            size,
+            BufferUsageFlags::TRANSFER_DST | BufferUsageFlags::STORAGE_BUFFER,
+        )?;
+        let fence = unsafe { device.create_fence(&FenceCreateInfo::default(), None)? };
+
+        Ok(Self {
+            buffer,
+            fence,
+            size,
+            cmd_pool: CommandPool::null(),
+            handoff: None,
+        })
+    }
+
+    pub fn is_ready(&self, vk_init: &VkInit) -> bool {
+        unsafe { vk_init.device.get_fence_status(self.fence) }.unwrap_or(false)
+    }
+
+    /// Reads the requested size as ```T```.
+    ///
+    /// Returns [ReadbackNotReady](Error::ReadbackNotReady) if the fence has not signaled yet.
+    pub fn read<T: Copy>(&self, vk_init: &VkInit) -> Result<Vec<T>, Error> {
+        if !unsafe { vk_init.device.get_fence_status(self.fence)? } {
+            return Err(Error::ReadbackNotReady);
+        }
+        self.read_unchecked(vk_init)
+    }
+
+    /// Waits up to ```timeout``` for the fence before reading the requested size as ```T```.
+    ///
+    /// Returns [ReadbackNotReady](Error::ReadbackNotReady) if the fence did not signal in time.
+    pub fn read_blocking<T: Copy>(
+        &self,
+        vk_init: &VkInit,
+        timeout: Duration,
+    ) -> Result<Vec<T>, Error> {
+        let timeout = u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX);
+        match unsafe { vk_init.device.wait_for_fences(&[self.fence], true, timeout) } {
+            Ok(()) => self.read_unchecked(vk_init),
+            Err(ash::vk::Result::TIMEOUT) => Err(Error::ReadbackNotReady),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Resets the fence so the slot can be submitted into again.
+    pub fn reuse(&mut self, vk_init: &VkInit) -> Result<(), Error> {
+        self.destroy_cmd_pool(&vk_init.device);
+        unsafe { vk_init.device.reset_fences(&[self.fence])? };
+        Ok(())
+    }
+
+    pub fn destroy(&mut self, device: &Device, allocator: &mut Allocator) -> Result<(), Error> {
+        self.destroy_cmd_pool(device);
+        unsafe { device.destroy_fence(self.fence, None) };
+        self.buffer.destroy(device, allocator)
+    }
+
+    fn destroy_cmd_pool(&mut self, device: &Device) {
+        if self.cmd_pool != CommandPool::null() {
+            unsafe { device.destroy_command_pool(self.cmd_pool, None) };
+            self.cmd_pool = CommandPool::null();
+        }
+        if let Some(mut handoff) = self.handoff.take() {
+            handoff.destroy(device);
+        }
+    }
+
+    fn read_unchecked<T: Copy>(&self, vk_init: &VkInit) -> Result<Vec<T>, Error> {
+        if size_of::<T>() == 0 {
+            return Err(Error::ReadZeroSizedType);
+        }
+        let Some(ptr) = self.buffer.allocation.mapped_ptr() else {
+            return Err(Error::ReadAttemptFromUnmappedBuffer);
+        };
+
+        self.buffer
+            .invalidate_read(&vk_init.device, 0, self.size as u64)?;
+        let count = self.size / size_of::<T>();
+        let mut data = Vec::with_capacity(count);
+        unsafe {
+            (ptr.as_ptr() as *const T).copy_to_nonoverlapping(data.as_mut_ptr(), count);
+            data.set_len(count);
+        }
+        Ok(data)
+    }
+}
+
+impl VkInit {
+    /// Creates ```count``` [ReadbackSlots](ReadbackSlot) of ```size``` bytes, usually [frames_in_flight](VkInit::frames_in_flight).
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # use std::time::Duration;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let cmd_pool = init.create_cmd_pool(CmdType::Any)?;
+    /// let cmd_buffer = init.create_command_buffers(&cmd_pool, 1)?[0];
+    /// let mut slots = init.create_readback_slots(1024, 2)?;
+    ///
+    /// init.begin_cmd_buffer(&cmd_buffer)?;
+    /// // record copies into slots[0].buffer
+    /// init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &slots[0].fence, &[], &[], &[])?;
+    ///
+    /// let data: Vec<u32> = slots[0].read_blocking(&init, Duration::from_secs(1))?;
+    /// slots[0].reuse(&init)?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn create_readback_slots(
+        &mut self,
+        size: usize,
+        count: usize,
+    ) -> Result<Vec<ReadbackSlot>, Error> {
+        let mut slots = Vec::new();
+        for i in 0..count {
+            let mut slot = ReadbackSlot::new(&self.device, &mut self.allocator.lock(), size)?;
+            slot.buffer = slot
+                .buffer
+                .with_atom_size(&self.physical_device_info.limits);
+            slot.buffer
+                .set_debug_object_name(self, format!("VKU_Readback_Slot_{i}"))?;
+            self.set_debug_object_name_fmt(
+                slot.fence.as_raw(),
+                ObjectType::FENCE,
+                format_args!("VKU_Readback_Slot_{i}_Fence"),
+            )?;
+            slots.push(slot);
+        }
+        Ok(slots)
+    }
+
+    /// Copies ```count``` elements of ```T``` starting at byte ```offset``` of ```src``` back to the host and waits for the result.
+    ///
+    /// ```src``` needs ```BufferUsageFlags::TRANSFER_SRC```. The copy runs after all work previously submitted to the unified queue,
+    /// on the dedicated transfer queue if there is one.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let usage = BufferUsageFlags::STORAGE_BUFFER | BufferUsageFlags::TRANSFER_SRC;
+    /// let buffer = init.create_local_buffer(1024 * 4, usage)?;
+    ///
+    /// // dispatch compute work writing into buffer
+    /// let results: Vec<u32> = init.read_buffer(&buffer, 0, 1024)?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn read_buffer<T: Copy>(
+        &mut self,
+        src: &VMABuffer,
+        offset: u64,
+        count: usize,
+    ) -> Result<Vec<T>, Error> {
+        let mut slot = self.read_buffer_async::<T>(src, offset, count)?;
+        let data = slot.read_blocking(self, Duration::MAX);
+        slot.destroy(&self.device, &mut self.allocator.lock())?;
+        data
+    }
+
+    /// Same as [read_buffer](VkInit::read_buffer), but returns the [ReadbackSlot] the copy is submitted into.
+    ///
+    /// The slot owns the transient command buffer until it is [reused](ReadbackSlot::reuse) or destroyed.
+    pub fn read_buffer_async<T: Copy>(
+        &mut self,
+        src: &VMABuffer,
+        offset: u64,
+        count: usize,
+    ) -> Result<ReadbackSlot, Error> {
+        src.check(&self.device, "read_buffer");
+        if !src.usage.contains(BufferUsageFlags::TRANSFER_SRC) {
+            return Err(Error::BufferUsageMissing(BufferUsageFlags::TRANSFER_SRC));
+        }
+        let size = (count * size_of::<T>()) as u64;
+        let available = src.size().saturating_sub(offset);
+        if size > available {
+            return Err(Error::BufferReadOutOfBounds {
+                requested: size,
+                available,
+            });
+        }
+
+        let mut slot = ReadbackSlot::new(&self.device, &mut self.allocator.lock(), size as usize)?;
+        slot.buffer = slot
+            .buffer
+            .with_atom_size(&self.physical_device_info.limits);
+        let result = (|| {
+            slot.buffer
+                .set_debug_object_name(self, String::from("VKU_Read_Buffer"))?;
+
+            // The readback buffer is new, only ```src``` has contents to keep.
+            let handoff = slot.handoff.insert(self.queue_handoff(
+                CmdType::Transfer,
+                QueueRequirements::default(),
+                (PipelineStageFlags2::TRANSFER, AccessFlags2::TRANSFER_READ),
+                vec![HandoffResource::Buffer(src.buffer)],
+            )?);
+            let pool_info = CommandPoolCreateInfo::builder()
+                .queue_family_index(handoff.queue_family_index())
+                .flags(CommandPoolCreateFlags::TRANSIENT);
+            slot.cmd_pool = unsafe { self.device.create_command_pool(&pool_info, None)? };
+            let cmd_buffer =
+                self.create_command_buffers_named(&slot.cmd_pool, 1, "VKU_Read_Buffer")?[0];
+
+            let after_copy = BufferMemoryBarrier2::builder()
+                .buffer(slot.buffer.buffer)
+                .offset(0)
+                .size(size)
+                .src_stage_mask(PipelineStageFlags2::TRANSFER)
+                .src_access_mask(AccessFlags2::TRANSFER_WRITE)
+                .dst_stage_mask(PipelineStageFlags2::HOST)
+                .dst_access_mask(AccessFlags2::HOST_READ)
+                .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+                .build();
+
+            self.begin_cmd_buffer(&cmd_buffer)?;
+            handoff.record_begin(self, &cmd_buffer);
+            src.enqueue_copy_to_buffer(
+                &self.device,
+                &cmd_buffer,
+                &slot.buffer,
+                Some(offset),
+                Some(0),
+                Some(size),
+            )?;
+            handoff.record_end(self, &cmd_buffer);
+            self.cmd_pipeline_barrier2(&cmd_buffer, &[], &[after_copy]);
+            unsafe { self.device.end_command_buffer(cmd_buffer)? };
+            handoff.submit(self, &[cmd_buffer], &slot.fence)?;
+            Ok(())
+        })();
+        if let Err(e) = result {
+            slot.destroy(&self.device, &mut self.allocator.lock())?;
+            return Err(e);
+        }
+
+        Ok(slot)
+    }
+
+    /// Copies mip level 0 of all array layers of ```image``` back to the host and waits for the result, e.g. for screenshots or tests.
+    ///
+    /// Returns the texels tightly packed in row, then layer order, see [readback_size](VMAImage::readback_size).
+    /// ```image``` needs ```ImageUsageFlags::TRANSFER_SRC``` and is transitioned to ```TRANSFER_SRC_OPTIMAL``` for the copy,
+    /// then back to its previous layout unless that was ```UNDEFINED```. The copy is submitted to the unified queue,
+    /// after all previously submitted work.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let extent = Extent3D{width: 64, height: 64, depth: 1};
+    /// let mut target = init.create_render_image_with_lifetime(extent, Format::R8G8B8A8_UNORM, 4, AllocationLifetime::Persistent)?;
+    ///
+    /// // render into target
+    /// let rgba8 = init.download_image(&mut target)?;
+    /// assert_eq!(rgba8.len(), 64 * 64 * 4);
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn download_image(&mut self, image: &mut VMAImage) -> Result<Vec<u8>, Error> {
+        image.check(&self.device, "download_image");
+        if !image.usage.contains(ImageUsageFlags::TRANSFER_SRC) {
+            return Err(Error::ImageUsageMissing(ImageUsageFlags::TRANSFER_SRC));
+        }
+        let size = image.readback_size(0)?;
+        let mut readback = VMABuffer::create_readback_buffer(
+            &self.device,
+            &mut self.allocator.lock(),
+            size,
+            BufferUsageFlags::TRANSFER_DST,
+        )?
+        .with_atom_size(&self.physical_device_info.limits);
+
+        let previous_layout = image.current_layout;
+        let restored_layout = match previous_layout {
+            ImageLayout::UNDEFINED => ImageLayout::TRANSFER_SRC_OPTIMAL,
+            layout => layout,
+        };
+        let subresource_range = ImageSubresourceRange {
+            aspect_mask: image.aspect_flags,
+            base_mip_level: 0,
+            level_count: REMAINING_MIP_LEVELS,
+            base_array_layer: 0,
+            layer_count: REMAINING_ARRAY_LAYERS,
+        };
+        let to_transfer_src = ImageMemoryBarrier2::builder()
+            .image(image.image)
+            .old_layout(previous_layout)
+            .new_layout(ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .src_stage_mask(PipelineStageFlags2::ALL_COMMANDS)
+            .src_access_mask(AccessFlags2::MEMORY_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::TRANSFER)
+            .dst_access_mask(AccessFlags2::TRANSFER_READ)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .subresource_range(subresource_range)
+            .build();
+        let to_previous = ImageMemoryBarrier2::builder()
+            .image(image.image)
+            .old_layout(ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .new_layout(restored_layout)
+            .src_stage_mask(PipelineStageFlags2::TRANSFER)
+            .dst_stage_mask(PipelineStageFlags2::ALL_COMMANDS)
+            .dst_access_mask(AccessFlags2::MEMORY_READ | AccessFlags2::MEMORY_WRITE)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .subresource_range(subresource_range)
+            .build();
+        let after_copy = BufferMemoryBarrier2::builder()
+            .buffer(readback.buffer)
+            .offset(0)
+            .size(WHOLE_SIZE)
+            .src_stage_mask(PipelineStageFlags2::TRANSFER)
+            .src_access_mask(AccessFlags2::TRANSFER_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::HOST)
+            .dst_access_mask(AccessFlags2::HOST_READ)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .build();
+
+        let mut copied = Ok(());
+        let submitted = self.immediate_submit(CmdType::Any, |cmd_buffer| {
+            self.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer_src], &[]);
+            copied = image.enqueue_copy_image_to_buffer(&self.device, cmd_buffer, &readback, 0);
+            self.cmd_pipeline_barrier2(cmd_buffer, &[to_previous], &[after_copy]);
+        });
+        image.current_layout = restored_layout;
+
+        let data = submitted
+            .and(copied)
+            .and_then(|_| readback.get_data::<u8>(&self.device, 0, size));
+        readback.destroy(&self.device, &mut self.allocator.lock())?;
+        data
+    }
+}
@@ -0,0 +1,736 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io::Cursor;
+
+use crate::imports::*;
+
+const SPIRV_MAGIC: u32 = 0x0723_0203;
+
+const OP_NAME: u32 = 5;
+const OP_MEMBER_NAME: u32 = 6;
+const OP_ENTRY_POINT: u32 = 15;
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_TYPE_MATRIX: u32 = 24;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_CONSTANT: u32 = 43;
+const OP_SPEC_CONSTANT: u32 = 50;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+const OP_MEMBER_DECORATE: u32 = 72;
+const OP_TYPE_ACCELERATION_STRUCTURE: u32 = 5341;
+
+const DECORATION_BLOCK: u32 = 2;
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_ARRAY_STRIDE: u32 = 6;
+const DECORATION_MATRIX_STRIDE: u32 = 7;
+const DECORATION_BUILT_IN: u32 = 11;
+const DECORATION_LOCATION: u32 = 30;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+const DECORATION_OFFSET: u32 = 35;
+
+const STORAGE_CLASS_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_CLASS_INPUT: u32 = 1;
+const STORAGE_CLASS_UNIFORM: u32 = 2;
+const STORAGE_CLASS_PUSH_CONSTANT: u32 = 9;
+const STORAGE_CLASS_STORAGE_BUFFER: u32 = 12;
+
+const DIM_BUFFER: u32 = 5;
+const DIM_SUBPASS_DATA: u32 = 6;
+
+/// Execution models of ```OpEntryPoint``` and their pipeline stages.
+const EXECUTION_MODELS: [(u32, ShaderStageFlags); 16] = [
+    (0, ShaderStageFlags::VERTEX),
+    (1, ShaderStageFlags::TESSELLATION_CONTROL),
+    (2, ShaderStageFlags::TESSELLATION_EVALUATION),
+    (3, ShaderStageFlags::GEOMETRY),
+    (4, ShaderStageFlags::FRAGMENT),
+    (5, ShaderStageFlags::COMPUTE),
+    (5267, ShaderStageFlags::TASK_NV),
+    (5268, ShaderStageFlags::MESH_NV),
+    (5313, ShaderStageFlags::RAYGEN_KHR),
+    (5314, ShaderStageFlags::INTERSECTION_KHR),
+    (5315, ShaderStageFlags::ANY_HIT_KHR),
+    (5316, ShaderStageFlags::CLOSEST_HIT_KHR),
+    (5317, ShaderStageFlags::MISS_KHR),
+    (5318, ShaderStageFlags::CALLABLE_KHR),
+    (5364, ShaderStageFlags::TASK_EXT),
+    (5365, ShaderStageFlags::MESH_EXT),
+];
+
+/// Type of a reflected block member with the explicit layout of the SPIR-V module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReflectedType {
+    Bool,
+    Int {
+        width: u32,
+        signed: bool,
+    },
+    Float {
+        width: u32,
+    },
+    Vector {
+        component: Box<ReflectedType>,
+        count: u32,
+    },
+    /// Column-major matrix of ```columns``` vectors, ```stride``` bytes apart.
+    Matrix {
+        column: Box<ReflectedType>,
+        columns: u32,
+        stride: u32,
+    },
+    /// ```None``` length for runtime arrays.
+    Array {
+        element: Box<ReflectedType>,
+        length: Option<u32>,
+        stride: u32,
+    },
+    Struct(BlockLayout),
+}
+
+impl ReflectedType {
+    /// Size in bytes, excluding trailing padding of structs. Runtime arrays have no size.
+    pub fn size(&self) -> u32 {
+        match self {
+            ReflectedType::Bool => 4,
+            ReflectedType::Int { width, .. } | ReflectedType::Float { width } => width / 8,
+            ReflectedType::Vector { component, count } => component.size() * count,
+            ReflectedType::Matrix {
+                columns, stride, ..
+            } => stride * columns,
+            ReflectedType::Array { length, stride, .. } => stride * length.unwrap_or(0),
+            ReflectedType::Struct(block) => block.size,
+        }
+    }
+
+    fn scalar(&self) -> &ReflectedType {
+        match self {
+            ReflectedType::Vector { component, .. } => component.scalar(),
+            ReflectedType::Matrix { column, .. } => column.scalar(),
+            ReflectedType::Array { element, .. } => element.scalar(),
+            scalar => scalar,
+        }
+    }
+}
+
+/// Struct with its members at their explicit offsets, e.g. a push constant block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockLayout {
+    /// Type name of the block, empty if the module was stripped of debug names.
+    pub name: String,
+    /// End of the last member in bytes.
+    pub size: u32,
+    pub members: Vec<BlockMember>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockMember {
+    pub name: String,
+    pub offset: u32,
+    pub ty: ReflectedType,
+}
+
+/// Descriptor set and binding index of a shader resource.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DescriptorBinding {
+    /// Variable name, or the block name for anonymous blocks.
+    pub name: String,
+    pub set: u32,
+    pub binding: u32,
+    pub descriptor_type: DescriptorType,
+    /// Array length of the binding, ```0``` for runtime arrays.
+    pub count: u32,
+    /// Stages of the module's entry points.
+    pub stages: ShaderStageFlags,
+}
+
+/// Input attribute of a vertex shader, builtins are skipped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VertexInput {
+    pub name: String,
+    pub location: u32,
+    pub ty: ReflectedType,
+}
+
+/// Reflects a SPIR-V module from its bytes, e.g. a ```.spv``` file written by ```compile_all_shaders```.
+///
+/// Shortcut - see [ShaderReflection](ShaderReflection::new).
+pub fn reflect_spirv_shader(spv_data: &[u8]) -> Result<ShaderReflection, Error> {
+    let code = ash::util::read_spv(&mut Cursor::new(spv_data))?;
+    ShaderReflection::new(&code)
+}
+
+/// Push constant block and descriptor bindings declared by a SPIR-V module.
+/// ```
+/// # use vku::*;
+/// # use std::io::Cursor;
+/// let code = ash::util::read_spv(&mut Cursor::new(include_bytes!("../tests/scale.comp.spv")))?;
+/// let reflection = ShaderReflection::new(&code)?;
+///
+/// let push = reflection.push_constants.as_ref().unwrap();
+/// assert_eq!(push.size, 4);
+/// assert_eq!(reflection.bindings[0].binding, 0);
+/// # Ok::<(), vku::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShaderReflection {
+    /// Stages of all entry points of the module.
+    pub stages: ShaderStageFlags,
+    pub push_constants: Option<BlockLayout>,
+    /// Sorted by set and binding.
+    pub bindings: Vec<DescriptorBinding>,
+    /// Sorted by location, empty for all but vertex shaders.
+    pub vertex_inputs: Vec<VertexInput>,
+}
+
+#[derive(Default)]
+struct Module {
+    stages: ShaderStageFlags,
+    names: HashMap<u32, String>,
+    member_names: HashMap<(u32, u32), String>,
+    decorations: HashMap<(u32, u32), u32>,
+    member_decorations: HashMap<(u32, u32, u32), u32>,
+    types: HashMap<u32, (u32, Vec<u32>)>,
+    constants: HashMap<u32, u32>,
+    /// Variable id, pointer type id and storage class.
+    variables: Vec<(u32, u32, u32)>,
+}
+
+impl ShaderReflection {
+    /// Returns [InvalidSpirv](Error::InvalidSpirv) if ```code``` is not a valid SPIR-V module.
+    pub fn new(code: &[u32]) -> Result<Self, Error> {
+        let module = Module::parse(code)?;
+
+        let push_constants = module
+            .variables
+            .iter()
+            .find(|(_, _, storage)| *storage == STORAGE_CLASS_PUSH_CONSTANT)
+            .map(|(_, pointer, _)| module.pointee(*pointer))
+            .transpose()?
+            .map(|block| module.block(block))
+            .transpose()?;
+
+        let mut bindings: Vec<DescriptorBinding> = module
+            .variables
+            .iter()
+            .filter_map(|(id, pointer, storage)| {
+                let set = *module.decorations.get(&(*id, DECORATION_DESCRIPTOR_SET))?;
+                let binding = *module.decorations.get(&(*id, DECORATION_BINDING))?;
+                Some((*id, *pointer, *storage, set, binding))
+            })
+            .map(|(id, pointer, storage, set, binding)| {
+                let name = match module.names.get(&id) {
+                    Some(name) if !name.is_empty() => name.clone(),
+                    _ => module
+                        .pointee(pointer)
+                        .ok()
+                        .and_then(|ty| module.names.get(&ty).cloned())
+                        .unwrap_or_default(),
+                };
+                let (descriptor_type, count) = module.descriptor(pointer, storage)?;
+                Ok(DescriptorBinding {
+                    name,
+                    set,
+                    binding,
+                    descriptor_type,
+                    count,
+                    stages: module.stages,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        bindings.sort_by_key(|binding| (binding.set, binding.binding));
+
+        let mut vertex_inputs = vec![];
+        if module.stages.contains(ShaderStageFlags::VERTEX) {
+            for (id, pointer, storage) in &module.variables {
+                if *storage != STORAGE_CLASS_INPUT
+                    || module.decorations.contains_key(&(*id, DECORATION_BUILT_IN))
+                {
+                    continue;
+                }
+                let Some(location) = module.decorations.get(&(*id, DECORATION_LOCATION)) else {
+                    continue;
+                };
+                vertex_inputs.push(VertexInput {
+                    name: module.names.get(id).cloned().unwrap_or_default(),
+                    location: *location,
+                    ty: module.reflected_type(module.pointee(*pointer)?, None)?,
+                });
+            }
+            vertex_inputs.sort_by_key(|input| input.location);
+        }
+
+        Ok(Self {
+            stages: module.stages,
+            push_constants,
+            bindings,
+            vertex_inputs,
+        })
+    }
+
+    /// Push constant range of the block for all [stages](ShaderReflection::stages), ```None``` if there is none.
+    pub fn push_constant_range(&self) -> Option<PushConstantRange> {
+        let block = self.push_constants.as_ref()?;
+        let offset = block
+            .members
+            .iter()
+            .map(|member| member.offset)
+            .min()
+            .unwrap_or(0);
+        Some(PushConstantRange {
+            stage_flags: self.stages,
+            offset,
+            size: block.size - offset,
+        })
+    }
+
+    /// Descriptors of ```set``` used by any of ```reflections```, ready for [with_descriptors](crate::VKUPipelineBuilder::with_descriptors).
+    ///
+    /// Stages of bindings shared by several shaders are merged, none of the descriptors is update-after-bind.
+    /// Returns [ReflectedDescriptorsIncompatible](Error::ReflectedDescriptorsIncompatible) for unused binding indices
+    /// below the highest one and for shaders disagreeing on a binding's type or count.
+    pub fn pipeline_descriptors(
+        reflections: &[&ShaderReflection],
+        set: u32,
+    ) -> Result<Vec<(bool, DescriptorType, ShaderStageFlags, u32)>, Error> {
+        let mut descriptors: Vec<Option<(bool, DescriptorType, ShaderStageFlags, u32)>> = vec![];
+        let bindings = reflections
+            .iter()
+            .flat_map(|reflection| &reflection.bindings)
+            .filter(|binding| binding.set == set);
+        for binding in bindings {
+            let index = binding.binding as usize;
+            if descriptors.len() <= index {
+                descriptors.resize(index + 1, None);
+            }
+            match &mut descriptors[index] {
+                Some((_, ty, stages, count)) => {
+                    if *ty != binding.descriptor_type || *count != binding.count {
+                        return Err(Error::ReflectedDescriptorsIncompatible {
+                            set,
+                            binding: binding.binding,
+                            reason: "the type or count differs between shaders",
+                        });
+                    }
+                    *stages |= binding.stages;
+                }
+                empty => {
+                    *empty = Some((
+                        false,
+                        binding.descriptor_type,
+                        binding.stages,
+                        binding.count,
+                    ))
+                }
+            }
+        }
+
+        descriptors
+            .into_iter()
+            .enumerate()
+            .map(|(binding, descriptor)| {
+                descriptor.ok_or(Error::ReflectedDescriptorsIncompatible {
+                    set,
+                    binding: binding as u32,
+                    reason: "the binding index is unused",
+                })
+            })
+            .collect()
+    }
+
+    /// Size of the push constant block in bytes, zero if there is none.
+    pub fn push_constant_size(&self) -> u32 {
+        self.push_constants
+            .as_ref()
+            .map(|block| block.size)
+            .unwrap_or(0)
+    }
+
+    /// Rust source with a ```#[repr(C)]``` struct per push constant block, padded to the reflected offsets,
+    /// and ```{NAME}_SET``` and ```{NAME}_BINDING``` constants per descriptor binding.
+    pub fn rust_bindings(&self) -> String {
+        let mut out = String::new();
+        for binding in &self.bindings {
+            let name = match binding.name.is_empty() {
+                true => format!("SET{}_BINDING{}", binding.set, binding.binding),
+                false => screaming_snake_case(&binding.name),
+            };
+            let _ = writeln!(out, "pub const {name}_SET: u32 = {};", binding.set);
+            let _ = writeln!(out, "pub const {name}_BINDING: u32 = {};", binding.binding);
+        }
+
+        if let Some(block) = &self.push_constants {
+            let mut structs = vec![];
+            let name = match block.name.is_empty() {
+                true => String::from("PushConstants"),
+                false => block.name.clone(),
+            };
+            rust_struct(&name, block, block.size, &mut structs);
+            for definition in structs {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(&definition);
+            }
+        }
+        out
+    }
+}
+
+impl Module {
+    fn parse(code: &[u32]) -> Result<Self, Error> {
+        if code.len() < 5 || code[0] != SPIRV_MAGIC {
+            return Err(Error::InvalidSpirv("missing spir-v header"));
+        }
+
+        let mut module = Module::default();
+        let mut words = &code[5..];
+        while !words.is_empty() {
+            let count = (words[0] >> 16) as usize;
+            if count == 0 || count > words.len() {
+                return Err(Error::InvalidSpirv("truncated instruction"));
+            }
+            let opcode = words[0] & 0xFFFF;
+            let operands = &words[1..count];
+            words = &words[count..];
+
+            match (opcode, operands) {
+                (OP_ENTRY_POINT, [execution_model, ..]) => {
+                    if let Some((_, stage)) = EXECUTION_MODELS
+                        .iter()
+                        .find(|(model, _)| model == execution_model)
+                    {
+                        module.stages |= *stage;
+                    }
+                }
+                (OP_NAME, [target, name @ ..]) => {
+                    module.names.insert(*target, spirv_string(name));
+                }
+                (OP_MEMBER_NAME, [ty, member, name @ ..]) => {
+                    module
+                        .member_names
+                        .insert((*ty, *member), spirv_string(name));
+                }
+                (OP_DECORATE, [target, decoration, value @ ..]) => {
+                    let value = value.first().copied().unwrap_or(0);
+                    module.decorations.insert((*target, *decoration), value);
+                }
+                (OP_MEMBER_DECORATE, [ty, member, decoration, value, ..]) => {
+                    module
+                        .member_decorations
+                        .insert((*ty, *member, *decoration), *value);
+                }
+                (
+                    OP_TYPE_BOOL
+                    | OP_TYPE_INT
+                    | OP_TYPE_FLOAT
+                    | OP_TYPE_VECTOR
+                    | OP_TYPE_MATRIX
+                    | OP_TYPE_ARRAY
+                    | OP_TYPE_RUNTIME_ARRAY
+                    | OP_TYPE_STRUCT
+                    | OP_TYPE_POINTER
+                    | OP_TYPE_IMAGE
+                    | OP_TYPE_SAMPLER
+                    | OP_TYPE_SAMPLED_IMAGE
+                    | OP_TYPE_ACCELERATION_STRUCTURE,
+                    [id, rest @ ..],
+                ) => {
+                    module.types.insert(*id, (opcode, rest.to_vec()));
+                }
+                (OP_CONSTANT | OP_SPEC_CONSTANT, [_, id, value, ..]) => {
+                    module.constants.insert(*id, *value);
+                }
+                (OP_VARIABLE, [pointer, id, storage, ..]) => {
+                    module.variables.push((*id, *pointer, *storage));
+                }
+                _ => {}
+            }
+        }
+        Ok(module)
+    }
+
+    fn ty(&self, id: u32) -> Result<&(u32, Vec<u32>), Error> {
+        self.types
+            .get(&id)
+            .ok_or(Error::InvalidSpirv("undefined type"))
+    }
+
+    fn pointee(&self, pointer: u32) -> Result<u32, Error> {
+        match self.ty(pointer)? {
+            (OP_TYPE_POINTER, operands) if operands.len() == 2 => Ok(operands[1]),
+            _ => Err(Error::InvalidSpirv("variable type is not a pointer")),
+        }
+    }
+
+    /// Descriptor type and count of a resource variable, unwrapping arrays of resources.
+    fn descriptor(&self, pointer: u32, storage: u32) -> Result<(DescriptorType, u32), Error> {
+        let mut ty = self.pointee(pointer)?;
+        let mut count = 1;
+        loop {
+            match self.ty(ty)? {
+                (OP_TYPE_ARRAY, operands) if operands.len() == 2 => {
+                    count *= *self
+                        .constants
+                        .get(&operands[1])
+                        .ok_or(Error::InvalidSpirv("array length is not a constant"))?;
+                    ty = operands[0];
+                }
+                (OP_TYPE_RUNTIME_ARRAY, operands) if operands.len() == 1 => {
+                    count = 0;
+                    ty = operands[0];
+                }
+                _ => break,
+            }
+        }
+
+        let descriptor_type = match (storage, self.ty(ty)?) {
+            (STORAGE_CLASS_STORAGE_BUFFER, _) => DescriptorType::STORAGE_BUFFER,
+            (STORAGE_CLASS_UNIFORM, _) => {
+                match self
+                    .decorations
+                    .contains_key(&(ty, DECORATION_BUFFER_BLOCK))
+                {
+                    true => DescriptorType::STORAGE_BUFFER,
+                    false if self.decorations.contains_key(&(ty, DECORATION_BLOCK)) => {
+                        DescriptorType::UNIFORM_BUFFER
+                    }
+                    false => return Err(Error::InvalidSpirv("uniform is not a block")),
+                }
+            }
+            (STORAGE_CLASS_UNIFORM_CONSTANT, (OP_TYPE_IMAGE, operands)) => {
+                let (dim, sampled) = match operands.as_slice() {
+                    [_, dim, _, _, _, sampled, ..] => (*dim, *sampled),
+                    _ => return Err(Error::InvalidSpirv("truncated image type")),
+                };
+                match (dim, sampled) {
+                    (DIM_SUBPASS_DATA, _) => DescriptorType::INPUT_ATTACHMENT,
+                    (DIM_BUFFER, 2) => DescriptorType::STORAGE_TEXEL_BUFFER,
+                    (DIM_BUFFER, _) => DescriptorType::UNIFORM_TEXEL_BUFFER,
+                    (_, 2) => DescriptorType::STORAGE_IMAGE,
+                    _ => DescriptorType::SAMPLED_IMAGE,
+                }
+            }
+            (STORAGE_CLASS_UNIFORM_CONSTANT, (OP_TYPE_SAMPLER, _)) => DescriptorType::SAMPLER,
+            (STORAGE_CLASS_UNIFORM_CONSTANT, (OP_TYPE_SAMPLED_IMAGE, _)) => {
+                DescriptorType::COMBINED_IMAGE_SAMPLER
+            }
+            (STORAGE_CLASS_UNIFORM_CONSTANT, (OP_TYPE_ACCELERATION_STRUCTURE, _)) => {
+                DescriptorType::ACCELERATION_STRUCTURE_KHR
+            }
+            _ => return Err(Error::InvalidSpirv("unsupported descriptor type")),
+        };
+        Ok((descriptor_type, count))
+    }
+
+    fn block(&self, id: u32) -> Result<BlockLayout, Error> {
+        let (OP_TYPE_STRUCT, member_types) = self.ty(id)? else {
+            return Err(Error::InvalidSpirv("block is not a struct"));
+        };
+
+        let mut members = vec![];
+        for (index, member_type) in member_types.iter().enumerate() {
+            let index = index as u32;
+            let offset = *self
+                .member_decorations
+                .get(&(id, index, DECORATION_OFFSET))
+                .ok_or(Error::InvalidSpirv("block member without offset"))?;
+            let matrix_stride = self
+                .member_decorations
+                .get(&(id, index, DECORATION_MATRIX_STRIDE))
+                .copied();
+            members.push(BlockMember {
+                name: self
+                    .member_names
+                    .get(&(id, index))
+                    .cloned()
+                    .unwrap_or_default(),
+                offset,
+                ty: self.reflected_type(*member_type, matrix_stride)?,
+            });
+        }
+
+        Ok(BlockLayout {
+            name: self.names.get(&id).cloned().unwrap_or_default(),
+            size: members
+                .iter()
+                .map(|member| member.offset + member.ty.size())
+                .max()
+                .unwrap_or(0),
+            members,
+        })
+    }
+
+    fn reflected_type(&self, id: u32, matrix_stride: Option<u32>) -> Result<ReflectedType, Error> {
+        let (opcode, operands) = self.ty(id)?;
+        let ty = match (*opcode, operands.as_slice()) {
+            (OP_TYPE_BOOL, _) => ReflectedType::Bool,
+            (OP_TYPE_INT, [width, signed]) => ReflectedType::Int {
+                width: *width,
+                signed: *signed != 0,
+            },
+            (OP_TYPE_FLOAT, [width, ..]) => ReflectedType::Float { width: *width },
+            (OP_TYPE_VECTOR, [component, count]) => ReflectedType::Vector {
+                component: Box::new(self.reflected_type(*component, None)?),
+                count: *count,
+            },
+            (OP_TYPE_MATRIX, [column, columns]) => {
+                let column = self.reflected_type(*column, None)?;
+                ReflectedType::Matrix {
+                    stride: matrix_stride.unwrap_or(column.size()),
+                    column: Box::new(column),
+                    columns: *columns,
+                }
+            }
+            (OP_TYPE_ARRAY | OP_TYPE_RUNTIME_ARRAY, [element, length @ ..]) => {
+                let element = self.reflected_type(*element, matrix_stride)?;
+                let length = match length {
+                    [length] => Some(
+                        *self
+                            .constants
+                            .get(length)
+                            .ok_or(Error::InvalidSpirv("array length is not a constant"))?,
+                    ),
+                    _ => None,
+                };
+                ReflectedType::Array {
+                    stride: self
+                        .decorations
+                        .get(&(id, DECORATION_ARRAY_STRIDE))
+                        .copied()
+                        .unwrap_or(element.size()),
+                    element: Box::new(element),
+                    length,
+                }
+            }
+            (OP_TYPE_STRUCT, _) => ReflectedType::Struct(self.block(id)?),
+            _ => return Err(Error::InvalidSpirv("unsupported block member type")),
+        };
+        Ok(ty)
+    }
+}
+
+fn spirv_string(words: &[u32]) -> String {
+    let bytes: Vec<u8> = words
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .take_while(|byte| *byte != 0)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn screaming_snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 && !out.ends_with('_') {
+            out.push('_');
+        }
+        match c.is_ascii_alphanumeric() {
+            true => out.push(c.to_ascii_uppercase()),
+            false => out.push('_'),
+        }
+    }
+    out
+}
+
+/// Appends the definition of ```name``` and all nested structs to ```structs```, padded to ```size``` bytes.
+fn rust_struct(name: &str, block: &BlockLayout, size: u32, structs: &mut Vec<String>) {
+    let mut fields = String::new();
+    let mut cursor = 0;
+    let mut padding = 0;
+    let mut members: Vec<&BlockMember> = block.members.iter().collect();
+    members.sort_by_key(|member| member.offset);
+    for (index, member) in members.into_iter().enumerate() {
+        if member.offset > cursor {
+            let _ = writeln!(
+                fields,
+                "    pub _pad{padding}: [u8; {}],",
+                member.offset - cursor
+            );
+            padding += 1;
+        }
+        let field_name = match member.name.is_empty() {
+            true => format!("member{index}"),
+            false => member.name.clone(),
+        };
+        let (ty, ty_size) = rust_type(&member.ty, structs);
+        let _ = writeln!(fields, "    pub {field_name}: {ty},");
+        cursor = member.offset + ty_size;
+    }
+    if size > cursor {
+        let _ = writeln!(fields, "    pub _pad{padding}: [u8; {}],", size - cursor);
+    }
+
+    if structs
+        .iter()
+        .any(|definition| definition.contains(&format!("pub struct {name} {{")))
+    {
+        return;
+    }
+    structs.push(format!(
+        "#[repr(C)]\n#[derive(Clone, Copy, Debug)]\npub struct {name} {{\n{fields}}}\n\n\
+         impl Default for {name} {{\n    fn default() -> Self {{\n        unsafe {{ std::mem::zeroed() }}\n    }}\n}}\n"
+    ));
+}
+
+/// Rust type and its size, padding vectors and structs to matrix and array strides.
+fn rust_type(ty: &ReflectedType, structs: &mut Vec<String>) -> (String, u32) {
+    match ty {
+        ReflectedType::Bool => (String::from("u32"), 4),
+        ReflectedType::Int { width, signed } => {
+            let prefix = if *signed { "i" } else { "u" };
+            (format!("{prefix}{width}"), width / 8)
+        }
+        ReflectedType::Float { width: 16 } => (String::from("u16"), 2),
+        ReflectedType::Float { width } => (format!("f{width}"), width / 8),
+        ReflectedType::Vector { component, count } => {
+            let (component, component_size) = rust_type(component, structs);
+            (format!("[{component}; {count}]"), component_size * count)
+        }
+        ReflectedType::Matrix {
+            column,
+            columns,
+            stride,
+        } => {
+            let (column, _) = padded_rust_type(column, *stride, structs);
+            (format!("[{column}; {columns}]"), stride * columns)
+        }
+        ReflectedType::Array {
+            element,
+            length,
+            stride,
+        } => {
+            let (element, _) = padded_rust_type(element, *stride, structs);
+            let length = length.unwrap_or(0);
+            (format!("[{element}; {length}]"), stride * length)
+        }
+        ReflectedType::Struct(block) => {
+            rust_struct(&block.name, block, block.size, structs);
+            (block.name.clone(), block.size)
+        }
+    }
+}
+
+/// Pads vectors with extra components and structs with trailing bytes to ```stride```.
+fn padded_rust_type(ty: &ReflectedType, stride: u32, structs: &mut Vec<String>) -> (String, u32) {
+    match ty {
+        ReflectedType::Struct(block) => {
+            rust_struct(&block.name, block, stride, structs);
+            (block.name.clone(), stride)
+        }
+        _ if stride > ty.size() => {
+            let (scalar, scalar_size) = rust_type(ty.scalar(), structs);
+            (format!("[{scalar}; {}]", stride / scalar_size), stride)
+        }
+        _ => rust_type(ty, structs),
+    }
+}
@@ -0,0 +1,75 @@
+use std::os::raw::c_void;
+use std::sync::Mutex;
+
+use renderdoc::{RenderDoc, V141};
+
+use crate::{imports::*, VkInit};
+
+/// In-application API of an attached RenderDoc, owned by [VkInit].
+pub(crate) struct RenderDocApi(Mutex<RenderDoc<V141>>);
+
+// The API entry points may be called from any thread, the mutex serializes access to the entry table.
+unsafe impl Send for RenderDocApi {}
+unsafe impl Sync for RenderDocApi {}
+
+impl RenderDocApi {
+    /// Only succeeds if the application was launched or injected by RenderDoc, the library is never loaded by vku itself.
+    pub(crate) fn load() -> Option<Self> {
+        match RenderDoc::<V141>::new() {
+            Ok(api) => {
+                let (major, minor, patch) = api.get_api_version();
+                info!("RenderDoc in-application API {major}.{minor}.{patch} attached");
+                Some(Self(Mutex::new(api)))
+            }
+            Err(e) => {
+                trace!("RenderDoc is not attached: {e}");
+                None
+            }
+        }
+    }
+}
+
+impl VkInit {
+    /// True if the application runs under RenderDoc and captures can be controlled with
+    /// [trigger_capture](VkInit::trigger_capture) and [start_frame_capture](VkInit::start_frame_capture).
+    ///
+    /// RenderDoc and the validation layers can be active together, but captures then include the validation overhead and
+    /// RenderDoc's replay runs without the layers of the captured application. Validation messages about handles that are only
+    /// invalid under RenderDoc are a common support issue: disable validation, e.g. with ```VKU_VALIDATION=0```, before reporting them.
+    pub fn renderdoc_available(&self) -> bool {
+        self.renderdoc.is_some()
+    }
+
+    /// Captures the next presented frame, e.g. when F12 was pressed. Does nothing without RenderDoc.
+    pub fn trigger_capture(&self) {
+        if let Some(mut api) = self.renderdoc_api() {
+            api.trigger_capture();
+        }
+    }
+
+    /// Starts capturing all commands of this instance until [end_frame_capture](VkInit::end_frame_capture), independent of presentation.
+    /// Does nothing without RenderDoc.
+    pub fn start_frame_capture(&self) {
+        let device = self.renderdoc_device_pointer();
+        if let Some(mut api) = self.renderdoc_api() {
+            api.start_frame_capture(device, std::ptr::null());
+        }
+    }
+
+    /// Ends the capture started with [start_frame_capture](VkInit::start_frame_capture). Does nothing without RenderDoc.
+    pub fn end_frame_capture(&self) {
+        let device = self.renderdoc_device_pointer();
+        if let Some(mut api) = self.renderdoc_api() {
+            api.end_frame_capture(device, std::ptr::null());
+        }
+    }
+
+    fn renderdoc_api(&self) -> Option<std::sync::MutexGuard<'_, RenderDoc<V141>>> {
+        self.renderdoc.as_ref().and_then(|api| api.0.lock().ok())
+    }
+
+    /// ```RENDERDOC_DEVICEPOINTER_FROM_VKINSTANCE```: the dispatch table pointer the instance handle points to.
+    fn renderdoc_device_pointer(&self) -> *const c_void {
+        unsafe { *(self.instance.handle().as_raw() as *const *const c_void) }
+    }
+}
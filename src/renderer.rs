@@ -0,0 +1,253 @@
+use std::marker::PhantomData;
+
+use crate::{
+    imports::*,
+    pipeline_builder::{BlendMode, DepthInfo, StencilInfo, VKUPipeline, VertexConvert},
+    VMABuffer, VkInit,
+};
+
+/// Pipeline state and buffer capacity of a [BaseRenderer].
+pub struct RendererCreateInfo<'a> {
+    pub vertex_spirv: &'a [u32],
+    pub fragment_spirv: &'a [u32],
+    pub color_format: Format,
+    /// Enables depth test and write with ```CompareOp::LESS_OR_EQUAL```.
+    pub depth_format: Option<Format>,
+    pub blend: BlendMode,
+    pub topology: PrimitiveTopology,
+    pub cull_mode: CullModeFlags,
+    /// Bindings of descriptor set 0, see [with_descriptors](crate::VKUPipelineBuilder::with_descriptors).
+    pub descriptors: &'a [(bool, DescriptorType, ShaderStageFlags, u32)],
+    /// Vertices per frame in flight.
+    pub max_vertices: usize,
+    /// ```u32``` indices per frame in flight.
+    pub max_indices: usize,
+}
+
+impl<'a> RendererCreateInfo<'a> {
+    /// Alpha blended triangle lists without culling, depth or descriptors and room for 65536 vertices per frame.
+    pub fn new(vertex_spirv: &'a [u32], fragment_spirv: &'a [u32], color_format: Format) -> Self {
+        Self {
+            vertex_spirv,
+            fragment_spirv,
+            color_format,
+            depth_format: None,
+            blend: BlendMode::TraditionalTransparency,
+            topology: PrimitiveTopology::TRIANGLE_LIST,
+            cull_mode: CullModeFlags::NONE,
+            descriptors: &[],
+            max_vertices: 65536,
+            max_indices: 65536 * 3,
+        }
+    }
+}
+
+/// Indexed geometry that is rebuilt every frame, e.g. sprites, debug lines or UI.
+///
+/// Vertices of type ```V``` and ```u32``` indices are written to host visible buffers owned by the frame in flight,
+/// so [upload](BaseRenderer::upload) never touches data the GPU may still read.
+/// Push constants of type ```Push``` are visible to the vertex and fragment stage, use ```()``` for none.
+pub struct BaseRenderer<V: VertexConvert, Push: Copy = ()> {
+    pub pipeline: VKUPipeline,
+    frames: Vec<RendererFrame>,
+    _marker: PhantomData<(V, Push)>,
+}
+
+struct RendererFrame {
+    vertex_buffer: VMABuffer,
+    index_buffer: VMABuffer,
+    index_count: u32,
+}
+
+impl<V: VertexConvert, Push: Copy> BaseRenderer<V, Push> {
+    /// Builds the pipeline for dynamic rendering and one vertex and index buffer per [frame in flight](VkInit::frames_in_flight).
+    pub fn new(
+        vk_init: &VkInit,
+        info: &RendererCreateInfo,
+        base_name: &str,
+    ) -> Result<Self, Error> {
+        let depth = match info.depth_format {
+            Some(_) => DepthInfo {
+                test: true,
+                write: true,
+                ..Default::default()
+            },
+            None => DepthInfo::default(),
+        };
+
+        let mut builder = VKUPipeline::builder()
+            .with_vertex::<V>(info.topology)
+            .with_viewports_scissors(&[Viewport::default()], &[Rect2D::default()])
+            .with_rasterization(PolygonMode::FILL, info.cull_mode)
+            .with_multisample(SampleCountFlags::TYPE_1)
+            .with_depthstencil(depth, StencilInfo::default())
+            .with_colorblends(&[info.blend])
+            .with_dynamic(&[DynamicState::VIEWPORT, DynamicState::SCISSOR])
+            .with_descriptors(info.descriptors)
+            .with_rendering(&[info.color_format], info.depth_format, None)
+            .push_shader_stage_spirv(
+                &vk_init.device,
+                ShaderStageFlags::VERTEX,
+                info.vertex_spirv,
+                &[],
+            )?
+            .push_shader_stage_spirv(
+                &vk_init.device,
+                ShaderStageFlags::FRAGMENT,
+                info.fragment_spirv,
+                &[],
+            )?;
+        if size_of::<Push>() > 0 {
+            builder = builder.with_push_constants::<Push>();
+        }
+        let pipeline = builder.build(vk_init, base_name)?;
+
+        let mut renderer = Self {
+            pipeline,
+            frames: vec![],
+            _marker: PhantomData,
+        };
+        for frame in 0..vk_init.frames_in_flight() {
+            match Self::create_frame(vk_init, info, base_name, frame) {
+                Ok(frame) => renderer.frames.push(frame),
+                Err(e) => {
+                    renderer.destroy(vk_init)?;
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(renderer)
+    }
+
+    fn create_frame(
+        vk_init: &VkInit,
+        info: &RendererCreateInfo,
+        base_name: &str,
+        frame: usize,
+    ) -> Result<RendererFrame, Error> {
+        let mut vertex_buffer = vk_init.create_cpu_to_gpu_buffer_named(
+            info.max_vertices * size_of::<V>(),
+            BufferUsageFlags::VERTEX_BUFFER,
+            &format!("{base_name}_Vertices_{frame}"),
+        )?;
+        let index_buffer = match vk_init.create_cpu_to_gpu_buffer_named(
+            info.max_indices * size_of::<u32>(),
+            BufferUsageFlags::INDEX_BUFFER,
+            &format!("{base_name}_Indices_{frame}"),
+        ) {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                vertex_buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+                return Err(e);
+            }
+        };
+
+        Ok(RendererFrame {
+            vertex_buffer,
+            index_buffer,
+            index_count: 0,
+        })
+    }
+
+    /// Replaces the geometry drawn by the next [record](BaseRenderer::record) of ```frame```.
+    ///
+    /// The frame in flight must not be executing on the GPU, e.g. call this after its fence was waited on.
+    /// Returns [BufferWriteOutOfBounds](Error::BufferWriteOutOfBounds) if the data exceeds ```max_vertices``` or ```max_indices```.
+    pub fn upload(
+        &mut self,
+        vk_init: &VkInit,
+        frame: usize,
+        vertices: &[V],
+        indices: &[u32],
+    ) -> Result<(), Error> {
+        let frame = &mut self.frames[frame];
+        frame.index_count = 0;
+        frame.vertex_buffer.set_data(&vk_init.device, 0, vertices)?;
+        frame.index_buffer.set_data(&vk_init.device, 0, indices)?;
+        frame.index_count = indices.len() as u32;
+        Ok(())
+    }
+
+    /// Binds the pipeline, buffers and ```descriptor_sets``` of ```frame``` and draws the uploaded indices.
+    ///
+    /// Must be called inside an active rendering scope. Viewport and scissor cover ```extent```.
+    /// Does nothing besides the binds if nothing was uploaded to ```frame```.
+    pub fn record(
+        &self,
+        vk_init: &VkInit,
+        cmd_buffer: &CommandBuffer,
+        frame: usize,
+        extent: Extent2D,
+        descriptor_sets: &[DescriptorSet],
+        push: &Push,
+    ) -> Result<(), Error> {
+        let frame = &self.frames[frame];
+        let viewport = Viewport {
+            x: 0.0,
+            y: 0.0,
+            width: extent.width as f32,
+            height: extent.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        };
+        let scissor = Rect2D {
+            offset: Offset2D { x: 0, y: 0 },
+            extent,
+        };
+
+        vk_init.cmd_bind_graphics_pipeline(cmd_buffer, &self.pipeline);
+        if !descriptor_sets.is_empty() {
+            vk_init.cmd_bind_descriptor_sets(
+                cmd_buffer,
+                PipelineBindPoint::GRAPHICS,
+                self.pipeline.layout,
+                0,
+                descriptor_sets,
+            );
+        }
+        if size_of::<Push>() > 0 {
+            vk_init.cmd_push_constants_typed(
+                cmd_buffer,
+                self.pipeline.layout,
+                ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT,
+                push,
+            )?;
+        }
+        vk_init.cmd_set_viewport(cmd_buffer, &[viewport]);
+        vk_init.cmd_set_scissor(cmd_buffer, &[scissor]);
+        if frame.index_count == 0 {
+            return Ok(());
+        }
+
+        unsafe {
+            vk_init.device.cmd_bind_vertex_buffers(
+                *cmd_buffer,
+                0,
+                &[frame.vertex_buffer.buffer],
+                &[0],
+            );
+            vk_init.device.cmd_bind_index_buffer(
+                *cmd_buffer,
+                frame.index_buffer.buffer,
+                0,
+                IndexType::UINT32,
+            );
+        }
+        vk_init.cmd_draw_indexed(cmd_buffer, frame.index_count, 1, 0, 0, 0);
+
+        Ok(())
+    }
+
+    pub fn destroy(&mut self, vk_init: &VkInit) -> Result<(), Error> {
+        for mut frame in self.frames.drain(..) {
+            frame
+                .vertex_buffer
+                .destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+            frame
+                .index_buffer
+                .destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        }
+        self.pipeline.destroy(&vk_init.device)
+    }
+}
@@ -0,0 +1,224 @@
+use crate::damage::rect_contains;
+use crate::{imports::*, VMAImage, VkInit};
+
+/// Attachments, load/store ops and clear values of [begin_rendering_with](VkInit::begin_rendering_with).
+///
+/// The default matches [begin_rendering](VkInit::begin_rendering): the whole extent, color and depth cleared and stored.
+#[derive(Clone, Copy)]
+pub struct RenderingConfig {
+    /// ```None``` renders the whole extent of the targets.
+    pub render_area: Option<Rect2D>,
+    /// Attach the depth image, e.g. ```false``` for 2D UI passes.
+    pub depth: bool,
+    pub color_load_op: AttachmentLoadOp,
+    /// With MSAA this is the store op of the multisampled image, the resolved image is always written.
+    pub color_store_op: AttachmentStoreOp,
+    pub depth_load_op: AttachmentLoadOp,
+    pub depth_store_op: AttachmentStoreOp,
+    /// Overrides the clear color of the [Head](crate::Head) or offscreen head.
+    pub clear_color_value: Option<ClearColorValue>,
+    /// Overrides the depth/stencil clear value of the [Head](crate::Head) or offscreen head.
+    pub clear_depth_stencil_value: Option<ClearDepthStencilValue>,
+}
+
+impl Default for RenderingConfig {
+    fn default() -> Self {
+        Self {
+            render_area: None,
+            depth: true,
+            color_load_op: AttachmentLoadOp::CLEAR,
+            color_store_op: AttachmentStoreOp::STORE,
+            depth_load_op: AttachmentLoadOp::CLEAR,
+            depth_store_op: AttachmentStoreOp::STORE,
+            clear_color_value: None,
+            clear_depth_stencil_value: None,
+        }
+    }
+}
+
+/// Depth attachment, extent and clear values rendered to by [begin_rendering](VkInit::begin_rendering), see [render_targets](VkInit::render_targets).
+#[derive(Clone, Copy)]
+pub struct RenderTargets {
+    pub extent: Extent2D,
+    pub clear_color_value: ClearColorValue,
+    pub clear_depth_stencil_value: ClearDepthStencilValue,
+    pub depth_view: ImageView,
+    /// Rendered to instead of the passed view, which receives the resolved color.
+    pub msaa_view: Option<ImageView>,
+}
+
+/// Attachments built from a [RenderingConfig], see [rendering_info](RenderingAttachments::rendering_info).
+#[derive(Clone, Copy)]
+pub struct RenderingAttachments {
+    pub render_area: Rect2D,
+    pub color: [RenderingAttachmentInfo; 1],
+    pub depth: Option<RenderingAttachmentInfo>,
+}
+
+impl RenderingConfig {
+    /// Builds the attachments for rendering to ```color_view``` and ```targets```.
+    ///
+    /// Returns [RenderAreaOutOfBounds](Error::RenderAreaOutOfBounds) if the render area exceeds the extent of the targets.
+    pub fn attachments(
+        &self,
+        color_view: ImageView,
+        targets: &RenderTargets,
+    ) -> Result<RenderingAttachments, Error> {
+        let extent = targets.extent;
+        let framebuffer = Rect2D {
+            offset: Offset2D { x: 0, y: 0 },
+            extent,
+        };
+        let render_area = self.render_area.unwrap_or(framebuffer);
+        if !rect_contains(framebuffer, render_area) {
+            return Err(Error::RenderAreaOutOfBounds {
+                area: render_area,
+                extent,
+            });
+        }
+
+        let clear_color_value = ClearValue {
+            color: self.clear_color_value.unwrap_or(targets.clear_color_value),
+        };
+        let clear_depth_stencil_value = ClearValue {
+            depth_stencil: self
+                .clear_depth_stencil_value
+                .unwrap_or(targets.clear_depth_stencil_value),
+        };
+
+        let color = match targets.msaa_view {
+            Some(msaa_view) => RenderingAttachmentInfo::builder()
+                .image_view(msaa_view)
+                .resolve_mode(ResolveModeFlags::AVERAGE)
+                .resolve_image_view(color_view)
+                .resolve_image_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL),
+            None => RenderingAttachmentInfo::builder().image_view(color_view),
+        }
+        .image_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+        .load_op(self.color_load_op)
+        .store_op(self.color_store_op)
+        .clear_value(clear_color_value)
+        .build();
+
+        let depth = self.depth.then(|| {
+            RenderingAttachmentInfo::builder()
+                .image_view(targets.depth_view)
+                .image_layout(ImageLayout::DEPTH_ATTACHMENT_OPTIMAL)
+                .load_op(self.depth_load_op)
+                .store_op(self.depth_store_op)
+                .clear_value(clear_depth_stencil_value)
+                .build()
+        });
+
+        Ok(RenderingAttachments {
+            render_area,
+            color: [color],
+            depth,
+        })
+    }
+}
+
+impl RenderingAttachments {
+    /// Rendering info pointing into ```self```, passed to ```cmd_begin_rendering```.
+    pub fn rendering_info(&self) -> RenderingInfoBuilder<'_> {
+        let rendering_info = RenderingInfo::builder()
+            .render_area(self.render_area)
+            .layer_count(1)
+            .color_attachments(&self.color);
+        match &self.depth {
+            Some(depth) => rendering_info.depth_attachment(depth),
+            None => rendering_info,
+        }
+    }
+}
+
+impl VkInit {
+    /// Begins rendering to ```color_view``` with the attachments and ops of ```config```.
+    /// Instances without a [Head](crate::Head) render to the [offscreen head](VkInit::create_offscreen_head) instead.
+    ///
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let cmd_pool = init.create_cmd_pool(CmdType::Any)?;
+    /// let cmd_buffer = init.create_command_buffers(&cmd_pool, 1)?[0];
+    /// let image_available = init.create_semaphore()?;
+    /// let AcquireResult::Ready { view: image_view, .. } = init.acquire_next_swapchain_image(image_available, None)? else {
+    ///     return Ok(()); // recreate the swapchain
+    /// };
+    ///
+    /// init.begin_cmd_buffer(&cmd_buffer)?;
+    /// // 3D pass, keeping the depth for the second pass
+    /// init.begin_rendering(&image_view, &cmd_buffer)?;
+    /// init.end_rendering(&cmd_buffer);
+    /// // UI pass on top, without depth
+    /// let ui = RenderingConfig {
+    ///     depth: false,
+    ///     color_load_op: AttachmentLoadOp::LOAD,
+    ///     ..Default::default()
+    /// };
+    /// init.begin_rendering_with(&image_view, &cmd_buffer, &ui)?;
+    /// init.end_rendering(&cmd_buffer);
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn begin_rendering_with(
+        &self,
+        color_view: &ImageView,
+        cmd_buffer: &CommandBuffer,
+        config: &RenderingConfig,
+    ) -> Result<(), Error> {
+        let attachments = config.attachments(*color_view, &self.render_targets()?)?;
+        unsafe {
+            self.device
+                .cmd_begin_rendering(*cmd_buffer, &attachments.rendering_info());
+        }
+        Ok(())
+    }
+
+    /// Begins rendering to ```color```, e.g. a [render image](VMAImage::create_render_image) sampled in a later pass.
+    ///
+    /// The render area covers the extent of ```color``` unless ```config``` sets one.
+    /// ```depth``` is attached if [depth](RenderingConfig::depth) is set, clear values default to those of the [VkInitCreateInfo](crate::VkInitCreateInfo).
+    /// Both images have to be in their attachment layouts, transition ```color``` to ```SHADER_READ_ONLY_OPTIMAL``` after
+    /// [end_rendering](VkInit::end_rendering) to sample it.
+    ///
+    /// Returns [ImageUsageMissing](Error::ImageUsageMissing) if ```color``` is not a color attachment.
+    pub fn begin_rendering_to_image(
+        &self,
+        color: &VMAImage,
+        depth: Option<&VMAImage>,
+        cmd_buffer: &CommandBuffer,
+        config: &RenderingConfig,
+    ) -> Result<(), Error> {
+        color.check(&self.device, "begin_rendering_to_image");
+        if !color.usage.contains(ImageUsageFlags::COLOR_ATTACHMENT) {
+            return Err(Error::ImageUsageMissing(ImageUsageFlags::COLOR_ATTACHMENT));
+        }
+        let targets = RenderTargets {
+            extent: Extent2D {
+                width: color.extent.width,
+                height: color.extent.height,
+            },
+            clear_color_value: self.create_info.clear_color_value,
+            clear_depth_stencil_value: self.create_info.clear_depth_stencil_value,
+            depth_view: depth.map_or(ImageView::null(), |depth| depth.image_view),
+            msaa_view: None,
+        };
+        let config = RenderingConfig {
+            depth: config.depth && depth.is_some(),
+            ..*config
+        };
+        let attachments = config.attachments(color.image_view, &targets)?;
+        unsafe {
+            self.device
+                .cmd_begin_rendering(*cmd_buffer, &attachments.rendering_info());
+        }
+        Ok(())
+    }
+}
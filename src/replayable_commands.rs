@@ -0,0 +1,226 @@
+use crate::{imports::*, CmdType, VkInit};
+
+/// Command buffer recorded once and submitted or executed many times, e.g. for static scenery.
+///
+/// Owns its command pool, so pool resets of per-frame buffers don't affect it.
+/// Secondary buffers inherit dynamic rendering with the color and depth formats given at creation and are executed
+/// with ```cmd_execute_commands``` inside rendering begun with ```RenderingFlags::CONTENTS_SECONDARY_COMMAND_BUFFERS```.
+/// Primary buffers are submitted alongside per-frame buffers with [submit_cmd_buffers](VkInit::submit_cmd_buffers).
+///
+/// Both are recorded with ```SIMULTANEOUS_USE```, so they may be pending in several frames in flight at once.
+/// Re-recording resets the buffer: wait on the fences of all frames that use it first.
+/// ```
+/// # extern crate winit;
+/// # use vku::*;
+/// # use ash::vk::*;
+/// # if VkInit::load_entry().is_err() { return Ok(()); }
+/// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+/// # let size = [800_u32, 600_u32];
+/// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+/// # let create_info = VkInitCreateInfo::default();
+/// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+/// let mut scenery = init.create_replayable_commands_for_head(CmdType::Graphics)?;
+///
+/// // Every frame:
+/// if !scenery.is_current(&init) {
+///     init.wait_device_idle()?;
+///     scenery.update_formats_from_head(&init)?;
+///     scenery.record(&init, |init, cmd_buffer| {
+///         // bind pipelines, draw static geometry
+///         Ok(())
+///     })?;
+/// }
+/// // init.begin_rendering_ex(..) with RenderingFlags::CONTENTS_SECONDARY_COMMAND_BUFFERS, then:
+/// // unsafe { init.device.cmd_execute_commands(frame_cmd_buffer, &[*scenery.cmd()]) };
+/// # scenery.destroy(&init)?;
+/// # Ok::<(), vku::Error>(())
+/// ```
+pub struct ReplayableCommands {
+    pool: CommandPool,
+    cmd_buffer: CommandBuffer,
+    level: CommandBufferLevel,
+    color_formats: Vec<Format>,
+    depth_format: Format,
+    device_generation: u64,
+    follows_head: bool,
+    recorded: bool,
+    dirty: bool,
+}
+
+impl ReplayableCommands {
+    /// The recorded command buffer, ```cmd_execute_commands``` it if secondary or submit it if primary.
+    pub fn cmd(&self) -> &CommandBuffer {
+        &self.cmd_buffer
+    }
+
+    pub fn level(&self) -> CommandBufferLevel {
+        self.level
+    }
+
+    pub fn color_formats(&self) -> &[Format] {
+        &self.color_formats
+    }
+
+    pub fn depth_format(&self) -> Format {
+        self.depth_format
+    }
+
+    /// Marks the commands as outdated, the owner has to [record](ReplayableCommands::record) them again before use.
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Recorded and neither [invalidated](ReplayableCommands::invalidate) nor recorded on a replaced device.
+    ///
+    /// Commands following the head are also outdated once its color or depth format differs from the inherited formats,
+    /// e.g. after [change_present_mode](VkInit::change_present_mode) picked another surface format.
+    pub fn is_current(&self, vk_init: &VkInit) -> bool {
+        if !self.recorded || self.dirty || self.device_generation != vk_init.device_generation() {
+            return false;
+        }
+        match (&vk_init.head, self.follows_head) {
+            (Some(head), true) => {
+                self.color_formats == [head.surface_info.color_format.format]
+                    && self.depth_format == head.depth_format
+            }
+            _ => true,
+        }
+    }
+
+    /// Changes the inherited formats and [invalidates](ReplayableCommands::invalidate) the commands if they differ.
+    ///
+    /// The commands no longer follow the head's formats afterwards.
+    pub fn set_formats(&mut self, color_formats: &[Format], depth_format: Format) {
+        self.follows_head = false;
+        if self.color_formats != color_formats || self.depth_format != depth_format {
+            self.color_formats = color_formats.to_vec();
+            self.depth_format = depth_format;
+            self.invalidate();
+        }
+    }
+
+    /// Inherits the head's current color and depth format and follows it in [is_current](ReplayableCommands::is_current).
+    pub fn update_formats_from_head(&mut self, vk_init: &VkInit) -> Result<(), Error> {
+        let Some(head) = vk_init.head.as_ref() else {
+            return Err(Error::HeadCallOnHeadlessInstance);
+        };
+        self.set_formats(&[head.surface_info.color_format.format], head.depth_format);
+        self.follows_head = true;
+        Ok(())
+    }
+
+    /// Resets and records the command buffer with ```record```, clearing the [invalidated](ReplayableCommands::invalidate) state.
+    ///
+    /// The buffer must not be pending: wait on the fences of all frames that submitted or executed it first.
+    pub fn record(
+        &mut self,
+        vk_init: &VkInit,
+        record: impl FnOnce(&VkInit, &CommandBuffer) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        if self.device_generation != vk_init.device_generation() {
+            return Err(Error::ReplayableCommandsDeviceReplaced);
+        }
+
+        self.recorded = false;
+        let mut inheritance_rendering_info = CommandBufferInheritanceRenderingInfo::builder()
+            .color_attachment_formats(&self.color_formats)
+            .depth_attachment_format(self.depth_format)
+            .rasterization_samples(SampleCountFlags::TYPE_1);
+        let inheritance_info =
+            CommandBufferInheritanceInfo::builder().push_next(&mut inheritance_rendering_info);
+
+        let mut flags = CommandBufferUsageFlags::SIMULTANEOUS_USE;
+        let mut begin_info = CommandBufferBeginInfo::builder();
+        if self.level == CommandBufferLevel::SECONDARY {
+            flags |= CommandBufferUsageFlags::RENDER_PASS_CONTINUE;
+            begin_info = begin_info.inheritance_info(&inheritance_info);
+        }
+        let begin_info = begin_info.flags(flags);
+
+        unsafe {
+            vk_init
+                .device
+                .begin_command_buffer(self.cmd_buffer, &begin_info)?;
+        }
+        record(vk_init, &self.cmd_buffer)?;
+        unsafe { vk_init.device.end_command_buffer(self.cmd_buffer)? };
+
+        self.recorded = true;
+        self.dirty = false;
+        trace!("Recorded replayable {:?} commands", self.level);
+        Ok(())
+    }
+
+    /// Destroys the command pool and buffer, wait until they are no longer pending first.
+    pub fn destroy(&mut self, vk_init: &VkInit) -> Result<(), Error> {
+        if self.pool == CommandPool::null() {
+            return Ok(());
+        }
+        vk_init.destroy_cmd_pool(&self.pool)?;
+        self.pool = CommandPool::null();
+        self.cmd_buffer = CommandBuffer::null();
+        self.recorded = false;
+        Ok(())
+    }
+}
+
+impl VkInit {
+    /// Creates unrecorded [ReplayableCommands], secondary ones inherit dynamic rendering with the given formats.
+    ///
+    /// Pass ```Format::UNDEFINED``` as ```depth_format``` for rendering without depth attachment.
+    pub fn create_replayable_commands(
+        &self,
+        cmd_type: CmdType,
+        level: CommandBufferLevel,
+        color_formats: &[Format],
+        depth_format: Format,
+    ) -> Result<ReplayableCommands, Error> {
+        let pool = self.create_cmd_pool(cmd_type)?;
+        let allocate_info = CommandBufferAllocateInfo::builder()
+            .command_pool(pool)
+            .level(level)
+            .command_buffer_count(1);
+        let cmd_buffer = match unsafe { self.device.allocate_command_buffers(&allocate_info) } {
+            Ok(cmd_buffers) => cmd_buffers[0],
+            Err(e) => {
+                self.destroy_cmd_pool(&pool)?;
+                return Err(e.into());
+            }
+        };
+        self.set_debug_object_name_fmt(
+            cmd_buffer.as_raw(),
+            ObjectType::COMMAND_BUFFER,
+            format_args!("VKU_Replayable_{level:?}_Cmd_Buffer"),
+        )?;
+
+        Ok(ReplayableCommands {
+            pool,
+            cmd_buffer,
+            level,
+            color_formats: color_formats.to_vec(),
+            depth_format,
+            device_generation: self.device_generation(),
+            follows_head: false,
+            recorded: false,
+            dirty: false,
+        })
+    }
+
+    /// Creates secondary [ReplayableCommands] inheriting and following the head's color and depth format.
+    pub fn create_replayable_commands_for_head(
+        &self,
+        cmd_type: CmdType,
+    ) -> Result<ReplayableCommands, Error> {
+        let Some(head) = self.head.as_ref() else {
+            return Err(Error::HeadCallOnHeadlessInstance);
+        };
+        let mut commands = self.create_replayable_commands(
+            cmd_type,
+            CommandBufferLevel::SECONDARY,
+            &[head.surface_info.color_format.format],
+            head.depth_format,
+        )?;
+        commands.follows_head = true;
+        Ok(commands)
+    }
+}
@@ -0,0 +1,139 @@
+use std::hash::{Hash, Hasher};
+
+use crate::{imports::*, VkInit};
+
+/// Sampler state of [create_sampler](VkInit::create_sampler), the defaults sample linearly across mips and clamp to the edge.
+///
+/// ```anisotropy``` is clamped to [max_sampler_anisotropy](PhysicalDeviceLimits::max_sampler_anisotropy)
+/// and ignored if the device does not support anisotropic filtering.
+#[derive(Clone, Copy, Debug)]
+pub struct SamplerInfo {
+    /// Used for magnification and minification.
+    pub filter: Filter,
+    pub mipmap_mode: SamplerMipmapMode,
+    /// Used for all three coordinates.
+    pub address_mode: SamplerAddressMode,
+    pub anisotropy: Option<f32>,
+    /// Creates a comparison sampler, e.g. for shadow maps.
+    pub compare_op: Option<CompareOp>,
+    /// Only used with ```CLAMP_TO_BORDER```.
+    pub border_color: BorderColor,
+}
+
+impl Default for SamplerInfo {
+    fn default() -> Self {
+        Self {
+            filter: Filter::LINEAR,
+            mipmap_mode: SamplerMipmapMode::LINEAR,
+            address_mode: SamplerAddressMode::CLAMP_TO_EDGE,
+            anisotropy: None,
+            compare_op: None,
+            border_color: BorderColor::FLOAT_TRANSPARENT_BLACK,
+        }
+    }
+}
+
+impl SamplerInfo {
+    /// Nearest filtering without mip interpolation, e.g. for pixel art or exact texel reads.
+    pub fn nearest() -> Self {
+        Self {
+            filter: Filter::NEAREST,
+            mipmap_mode: SamplerMipmapMode::NEAREST,
+            ..Default::default()
+        }
+    }
+
+    fn key(
+        &self,
+    ) -> (
+        Filter,
+        SamplerMipmapMode,
+        SamplerAddressMode,
+        Option<u32>,
+        Option<CompareOp>,
+        BorderColor,
+    ) {
+        (
+            self.filter,
+            self.mipmap_mode,
+            self.address_mode,
+            self.anisotropy.map(f32::to_bits),
+            self.compare_op,
+            self.border_color,
+        )
+    }
+}
+
+impl PartialEq for SamplerInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for SamplerInfo {}
+
+impl Hash for SamplerInfo {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl VkInit {
+    /// Creates a sampler from ```info```, destroy it with [destroy_sampler](VkInit::destroy_sampler).
+    ///
+    /// See [get_or_create_sampler](VkInit::get_or_create_sampler) to share samplers instead.
+    pub fn create_sampler(&self, info: SamplerInfo) -> Result<Sampler, Error> {
+        let anisotropy = info
+            .anisotropy
+            .filter(|_| self.physical_device_info.features.sampler_anisotropy == TRUE)
+            .map(|anisotropy| {
+                anisotropy.clamp(1.0, self.physical_device_info.limits.max_sampler_anisotropy)
+            });
+        let create_info = SamplerCreateInfo::builder()
+            .mag_filter(info.filter)
+            .min_filter(info.filter)
+            .mipmap_mode(info.mipmap_mode)
+            .address_mode_u(info.address_mode)
+            .address_mode_v(info.address_mode)
+            .address_mode_w(info.address_mode)
+            .anisotropy_enable(anisotropy.is_some())
+            .max_anisotropy(anisotropy.unwrap_or(1.0))
+            .compare_enable(info.compare_op.is_some())
+            .compare_op(info.compare_op.unwrap_or(CompareOp::ALWAYS))
+            .min_lod(0.0)
+            .max_lod(LOD_CLAMP_NONE)
+            .border_color(info.border_color);
+        let sampler = unsafe { self.device.create_sampler(&create_info, None)? };
+        self.set_debug_object_name_fmt(
+            sampler.as_raw(),
+            ObjectType::SAMPLER,
+            format_args!("VKU_Sampler_{:?}_{:?}", info.filter, info.address_mode),
+        )?;
+        Ok(sampler)
+    }
+
+    pub fn destroy_sampler(&self, sampler: Sampler) {
+        unsafe { self.device.destroy_sampler(sampler, None) };
+    }
+
+    /// Same as [create_sampler](VkInit::create_sampler), but returns the same sampler for equal ```info```.
+    ///
+    /// Cached samplers are owned by [VkInit] and destroyed by [destroy](VkInit::destroy), don't destroy them yourself.
+    pub fn get_or_create_sampler(&self, info: SamplerInfo) -> Result<Sampler, Error> {
+        let mut samplers = self.samplers.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(sampler) = samplers.get(&info) {
+            return Ok(*sampler);
+        }
+        let sampler = self.create_sampler(info)?;
+        samplers.insert(info, sampler);
+        Ok(sampler)
+    }
+
+    /// Destroys the samplers of [get_or_create_sampler](VkInit::get_or_create_sampler).
+    pub(crate) fn destroy_cached_samplers(&mut self) {
+        let samplers = self.samplers.get_mut().unwrap_or_else(|e| e.into_inner());
+        for (_, sampler) in samplers.drain() {
+            unsafe { self.device.destroy_sampler(sampler, None) };
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{imports::*, SurfaceInfo, VkInit};
+
+impl SurfaceInfo {
+    /// Window size in logical pixels, the unrotated [current_extent](SurfaceInfo::current_extent) divided by the [scale_factor](SurfaceInfo::scale_factor).
+    pub fn logical_extent(&self) -> [f32; 2] {
+        let extent = self.rotation().rotate_extent(self.current_extent);
+        [
+            (extent.width as f64 / self.scale_factor) as f32,
+            (extent.height as f64 / self.scale_factor) as f32,
+        ]
+    }
+
+    /// Column-major projection from logical pixels with the origin at the top left to normalized device coordinates.
+    ///
+    /// Rotated surfaces additionally need the [pre_rotation_matrix](SurfaceInfo::pre_rotation_matrix) applied afterwards.
+    pub fn ui_ortho_matrix(&self) -> [[f32; 4]; 4] {
+        let [width, height] = self.logical_extent();
+        [
+            [2.0 / width, 0.0, 0.0, 0.0],
+            [0.0, 2.0 / height, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [-1.0, -1.0, 0.0, 1.0],
+        ]
+    }
+}
+
+impl VkInit {
+    /// Updates [SurfaceInfo::scale_factor], e.g. on winit's ```WindowEvent::ScaleFactorChanged```.
+    ///
+    /// The swapchain is not recreated, the accompanying resize is still handled by [on_resize](VkInit::on_resize).
+    pub fn set_scale_factor(&mut self, scale_factor: f64) -> Result<(), Error> {
+        let Some(head) = self.head.as_mut() else {
+            return Err(Error::HeadCallOnHeadlessInstance);
+        };
+        trace!("Scale factor changed to {scale_factor}");
+        head.surface_info.scale_factor = scale_factor;
+        Ok(())
+    }
+
+    /// Shortcut - see [SurfaceInfo](SurfaceInfo::ui_ortho_matrix).
+    ///
+    /// Always the identity on headless instances.
+    pub fn ui_ortho_matrix(&self) -> [[f32; 4]; 4] {
+        self.head.as_ref().map_or(
+            [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+            |head| head.surface_info.ui_ortho_matrix(),
+        )
+    }
+}
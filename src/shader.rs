@@ -1,52 +1,184 @@
 #![cfg(feature = "shader")]
 
-use crate::imports::*;
-use shaderc::CompilationArtifact;
+use crate::{imports::*, ShaderReflection};
+use shaderc::{CompilationArtifact, OptimizationLevel, ShaderKind, SpirvVersion};
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::fs::{create_dir_all, read_dir, read_to_string, remove_dir, remove_file};
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+/// Lists the files written by the last [compile_all_shaders] run, relative to the target directory,
+/// and the source hash and includes of each compiled shader.
+///
+/// Runs with an [output_suffix](ShaderCompileOptions::output_suffix) use ```.vku_manifest_{suffix}``` instead.
+const MANIFEST_FILE_NAME: &str = ".vku_manifest";
+
+/// Options for all shader compilation functions.
+///
+/// ```true``` and ```false``` convert to [debug](ShaderCompileOptions::debug) and [default](ShaderCompileOptions::default) options,
+/// which are the ```debug``` flag of earlier versions.
+#[derive(Clone, Debug)]
+pub struct ShaderCompileOptions {
+    /// Macros as with ```-D name=value```, ```None``` defines the name without a value.
+    pub defines: Vec<(String, Option<String>)>,
+    pub optimization: OptimizationLevel,
+    /// SPIR-V version of the output, by default the shaderc default and 1.5 for task, mesh and ray tracing shaders.
+    pub target_spirv: Option<SpirvVersion>,
+    /// Also writes the SPIR-V assembly as ```.txt``` next to each binary when compiling directories.
+    pub generate_debug_info: bool,
+    /// Appended to the output file names when compiling directories, e.g. ```blur.frag``` => ```blur_{suffix}.frag.spv```.
+    ///
+    /// Allows compiling the same sources with different [defines](ShaderCompileOptions::defines) into one target directory.
+    pub output_suffix: Option<String>,
+}
+
+impl Default for ShaderCompileOptions {
+    fn default() -> Self {
+        Self {
+            defines: vec![],
+            optimization: OptimizationLevel::Performance,
+            target_spirv: None,
+            generate_debug_info: false,
+            output_suffix: None,
+        }
+    }
+}
+
+impl From<bool> for ShaderCompileOptions {
+    fn from(debug: bool) -> Self {
+        match debug {
+            true => Self::debug(),
+            false => Self::default(),
+        }
+    }
+}
+
+impl ShaderCompileOptions {
+    /// Unoptimized with debug info.
+    pub fn debug() -> Self {
+        Self {
+            optimization: OptimizationLevel::Zero,
+            generate_debug_info: true,
+            ..Default::default()
+        }
+    }
 
-use std::fs::{create_dir_all, read_dir, read_to_string, remove_dir_all};
-use std::path::Path;
+    pub fn with_define(mut self, name: &str, value: Option<&str>) -> Self {
+        self.defines
+            .push((name.to_string(), value.map(str::to_string)));
+        self
+    }
+
+    pub fn with_output_suffix(mut self, suffix: &str) -> Self {
+        self.output_suffix = Some(suffix.to_string());
+        self
+    }
+
+    fn manifest_file_name(&self) -> String {
+        match &self.output_suffix {
+            Some(suffix) => format!("{MANIFEST_FILE_NAME}_{suffix}"),
+            None => MANIFEST_FILE_NAME.to_string(),
+        }
+    }
+}
+
+/// Shaders compiled and skipped by [compile_all_shaders_incremental].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShaderCompileReport {
+    pub rebuilt: usize,
+    pub cached: usize,
+}
 
 /// Compiles all GLSL shaders in ```src_dir_path``` to SPIR-V shader binaries in ```target_dir_path``` alongside optional debug text results.
 ///
+/// Subdirectories are compiled recursively and mirrored in ```target_dir_path```, e.g. ```post/blur.frag``` => ```post/blur.frag.spv```.
+/// Only files written by a previous run are removed from ```target_dir_path```, they are listed in its ```.vku_manifest```.
+///
 /// The shader kind is read from the shader's file extension:
-/// - .frag => Fragment shader
-/// - .vert => Vertex shader
+/// - .vert, .tesc, .tese, .geom, .frag => Vertex, tessellation control, tessellation evaluation, geometry and fragment shader
 /// - .comp => Compute shader
+/// - .task, .mesh => Task and mesh shader
+/// - .rgen, .rahit, .rchit, .rmiss, .rint, .rcall => Ray generation, any hit, closest hit, miss, intersection and callable shader
 /// - .glsl => Include source for other shaders
 ///
+/// Files with any other extension, e.g. a README or editor swap files, are skipped with a warning.
+///
+/// Task, mesh and ray tracing shaders are compiled for SPIR-V 1.5, all others for the shaderc default.
+///
 /// .glsl files may be used in other shaders as copy-paste include directives, but they have to provide a relative path from the calling .exe to the include file:
 ///
 /// ```#include "./assets/shaders/src/example.glsl"```
 ///
 /// Only a single entry point main() is allowed.
+///
+/// Shaders that fail to compile don't stop the remaining ones, they are returned together as [ShadersFailedToCompile](Error::ShadersFailedToCompile)
+/// with a [ShaderCompilation](Error::ShaderCompilation) error each. Their binaries of the previous run are kept.
 #[allow(unused_must_use)]
 
 pub fn compile_all_shaders(
     src_dir_path: &Path,
     target_dir_path: &Path,
-    debug: bool,
+    options: impl Into<ShaderCompileOptions>,
 ) -> Result<(), Error> {
+    compile_shaders(src_dir_path, target_dir_path, &options.into(), false, false)?;
+    Ok(())
+}
+
+/// Same as [compile_all_shaders], but additionally writes a ```bindings.rs``` to ```target_dir_path```.
+///
+/// The file contains a module per shader, e.g. ```scale_comp``` for ```scale.comp``` or ```post_blur_frag``` for ```post/blur.frag```, with a ```#[repr(C)]``` struct
+/// per push constant block that is padded to the offsets of the compiled shader and ```{NAME}_SET``` / ```{NAME}_BINDING```
+/// constants per descriptor, ready to be pulled in with ```include!```.
+///
+/// See [ShaderReflection::rust_bindings] for the generated items.
+pub fn compile_all_shaders_with_bindings(
+    src_dir_path: &Path,
+    target_dir_path: &Path,
+    options: impl Into<ShaderCompileOptions>,
+) -> Result<(), Error> {
+    compile_shaders(src_dir_path, target_dir_path, &options.into(), true, false)?;
+    Ok(())
+}
+
+/// Same as [compile_all_shaders], but only recompiles shaders whose source or ```#include```d files changed since the last run.
+///
+/// Changes are detected by content hashes stored in the ```.vku_manifest``` of ```target_dir_path```.
+/// Changing the [options](ShaderCompileOptions) or deleting a compiled binary recompiles the affected shaders as well.
+pub fn compile_all_shaders_incremental(
+    src_dir_path: &Path,
+    target_dir_path: &Path,
+    options: impl Into<ShaderCompileOptions>,
+) -> Result<ShaderCompileReport, Error> {
+    compile_shaders(src_dir_path, target_dir_path, &options.into(), false, true)
+}
+
+#[allow(unused_must_use)]
+fn compile_shaders(
+    src_dir_path: &Path,
+    target_dir_path: &Path,
+    options: &ShaderCompileOptions,
+    bindings: bool,
+    incremental: bool,
+) -> Result<ShaderCompileReport, Error> {
     trace!("Compiling all shaders inside {src_dir_path:?} to {target_dir_path:?}");
-    remove_dir_all(target_dir_path);
+    let manifest_file_name = options.manifest_file_name();
+    let previous = ShaderManifest::read(target_dir_path, &manifest_file_name);
     create_dir_all(target_dir_path)?;
 
     let compiler = shaderc::Compiler::new().ok_or(Error::ShaderCInitError)?;
 
-    let mut compiler_options = shaderc::CompileOptions::new().ok_or(Error::ShaderCInitError)?;
-    if debug {
-        compiler_options.set_optimization_level(shaderc::OptimizationLevel::Zero);
-        compiler_options.set_generate_debug_info();
-    } else {
-        compiler_options.set_optimization_level(shaderc::OptimizationLevel::Performance);
-    }
-
-    compiler_options.set_include_callback(shader_include_callback);
-
-    let shaders_dir = read_dir(src_dir_path)?;
-    for entry in shaders_dir {
-        let shader_entry = entry?;
-        let path = shader_entry.path();
-        let Some(extension) = path.extension() else {
+    let mut report = ShaderCompileReport::default();
+    let mut failures = vec![];
+    let mut manifest = ShaderManifest::default();
+    let mut bindings_src =
+        String::from("// Generated by vku::compile_all_shaders_with_bindings, do not edit.\n");
+    let mut shader_paths = vec![];
+    collect_shader_paths(src_dir_path, Path::new(""), &mut shader_paths)?;
+    for relative_path in shader_paths {
+        let Some(extension) = relative_path.extension() else {
             continue;
         };
         let Some(file_type_string) = extension.to_str() else {
@@ -55,21 +187,21 @@ pub fn compile_all_shaders(
         if file_type_string == "glsl" {
             continue;
         }
-        let shader_kind = match file_type_string {
-            "frag" => Ok(shaderc::ShaderKind::Fragment),
-            "vert" => Ok(shaderc::ShaderKind::Vertex),
-            "comp" => Ok(shaderc::ShaderKind::Compute),
-            _ => Err(Error::UnknownShaderFileExtension),
-        }?;
+        let Ok(shader_kind) = shader_kind(file_type_string) else {
+            warn!(
+                "Skipping {relative_path:?}, .{file_type_string} is not a shader stage extension"
+            );
+            continue;
+        };
 
-        let Some(shader_name) = path.file_name() else {
+        let Some(shader_name) = relative_path.file_name() else {
             continue;
         };
-        let Some(shader_ext) = path.extension() else {
+        let Some(shader_ext) = relative_path.extension() else {
             continue;
         };
 
-        let shader_src = read_to_string(&path)?;
+        let shader_src = read_to_string(src_dir_path.join(&relative_path))?;
         let Ok(shader_name) = shader_name.to_ascii_lowercase().into_string() else {
             continue;
         };
@@ -77,141 +209,522 @@ pub fn compile_all_shaders(
             continue;
         };
 
-        compile_shader(
-            shader_src,
-            &shader_name,
-            &shader_ext,
-            target_dir_path,
-            &compiler,
-            shader_kind,
-            Some(&compiler_options),
-            debug,
-        )?;
+        let relative_dir = relative_path.parent().unwrap_or(Path::new(""));
+        let target_path = target_dir_path.join(relative_dir);
+        create_dir_all(&target_path)?;
+
+        let output_name = match &options.output_suffix {
+            Some(suffix) => {
+                let extension = format!(".{shader_ext}");
+                let stem = shader_name.strip_suffix(&extension).unwrap_or(&shader_name);
+                format!("{stem}_{suffix}.{shader_ext}")
+            }
+            None => shader_name,
+        };
+        let binary_path = relative_dir.join(&output_name);
+        let mut outputs = vec![binary_path.with_extension(format!("{shader_ext}.spv"))];
+        if options.generate_debug_info {
+            outputs.push(binary_path.with_extension(format!("{shader_ext}.txt")));
+        }
+
+        let cached = previous.shaders.get(&relative_path).filter(|entry| {
+            incremental
+                && entry.hash == source_hash(&shader_src, &entry.includes, options)
+                && outputs
+                    .iter()
+                    .all(|output| target_dir_path.join(output).is_file())
+        });
+
+        let (code, includes) = match cached {
+            Some(entry) => {
+                trace!("Shader {relative_path:?} is up to date");
+                report.cached += 1;
+                let mut binary = std::fs::File::open(target_dir_path.join(&outputs[0]))?;
+                (ash::util::read_spv(&mut binary)?, entry.includes.clone())
+            }
+            None => {
+                report.rebuilt += 1;
+                let mut compiler_options = compile_options(shader_kind, options)?;
+                let included: Rc<RefCell<Vec<String>>> = Rc::default();
+                let recorder = included.clone();
+                compiler_options.set_include_callback(move |src_path, ty, dst_name, depth| {
+                    let mut recorded = recorder.borrow_mut();
+                    if !recorded.iter().any(|path| path == src_path) {
+                        recorded.push(src_path.to_string());
+                    }
+                    shader_include_callback(src_path, ty, dst_name, depth)
+                });
+
+                let compiled = compile_shader(
+                    &compiler,
+                    &shader_src,
+                    &relative_path,
+                    shader_kind,
+                    Some(&compiler_options),
+                    &target_dir_path.join(&outputs[0]),
+                    outputs.get(1).map(|text| target_dir_path.join(text)),
+                );
+                let code = match compiled {
+                    Ok(code) => code,
+                    Err(error @ Error::ShaderCompilation { .. }) => {
+                        // Keeps the binaries of the last successful run until the shader compiles again.
+                        manifest.files.extend(outputs);
+                        failures.push(error);
+                        continue;
+                    }
+                    Err(error) => return Err(error),
+                };
+                let includes = included.borrow().clone();
+                (code, includes)
+            }
+        };
+
+        manifest.shaders.insert(
+            relative_path.clone(),
+            ShaderEntry {
+                hash: source_hash(&shader_src, &includes, options),
+                includes,
+            },
+        );
+        manifest.files.extend(outputs);
+
+        if bindings {
+            let module_name: String = binary_path
+                .to_string_lossy()
+                .to_ascii_lowercase()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            let items = ShaderReflection::new(&code)?.rust_bindings();
+            writeln!(bindings_src, "\npub mod {module_name} {{");
+            for line in items.lines() {
+                match line.is_empty() {
+                    true => bindings_src.push('\n'),
+                    false => bindings_src.push_str(&format!("    {line}\n")),
+                }
+            }
+            bindings_src.push_str("}\n");
+        }
     }
 
+    if bindings {
+        // Bindings of failed shaders would be missing, the previous file is kept instead.
+        if failures.is_empty() {
+            std::fs::write(target_dir_path.join("bindings.rs"), bindings_src)?;
+        }
+        manifest.files.push(PathBuf::from("bindings.rs"));
+    }
+
+    previous.remove_stale_files(target_dir_path, &manifest);
+    manifest.write(target_dir_path, &manifest_file_name)?;
+
+    match failures.is_empty() {
+        true => Ok(report),
+        false => Err(Error::ShadersFailedToCompile(failures)),
+    }
+}
+
+/// Collects the paths of all files below ```dir_path``` relative to the source root, sorted per directory.
+fn collect_shader_paths(
+    dir_path: &Path,
+    relative_dir: &Path,
+    paths: &mut Vec<PathBuf>,
+) -> Result<(), Error> {
+    let mut entries: Vec<_> = read_dir(dir_path)?.collect::<Result<_, _>>()?;
+    entries.sort_by_key(|entry| entry.path());
+    for entry in entries {
+        let relative_path = relative_dir.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            collect_shader_paths(&entry.path(), &relative_path, paths)?;
+        } else {
+            paths.push(relative_path);
+        }
+    }
     Ok(())
 }
 
+/// FNV-1a hash of the shader source, its includes and the compile options.
+///
+/// Unreadable includes hash as empty, so the shader is recompiled and reports the missing file.
+fn source_hash(shader_src: &str, includes: &[String], options: &ShaderCompileOptions) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    feed(format!("{options:?}").as_bytes());
+    feed(shader_src.as_bytes());
+    for include in includes {
+        feed(include.as_bytes());
+        feed(read_to_string(include).unwrap_or_default().as_bytes());
+    }
+    hash
+}
+
+struct ShaderEntry {
+    hash: u64,
+    includes: Vec<String>,
+}
+
+/// Contents of the ```.vku_manifest```, one tab separated record per line:
+/// - ```file <path>``` for each generated file
+/// - ```shader <source path> <hash> <includes...>``` for each compiled shader
+#[derive(Default)]
+struct ShaderManifest {
+    files: Vec<PathBuf>,
+    shaders: HashMap<PathBuf, ShaderEntry>,
+}
+
+impl ShaderManifest {
+    fn read(target_dir_path: &Path, file_name: &str) -> Self {
+        let mut manifest = Self::default();
+        let Ok(contents) = read_to_string(target_dir_path.join(file_name)) else {
+            return manifest;
+        };
+        for line in contents.lines() {
+            let mut fields = line.split('\t');
+            match (fields.next(), fields.next()) {
+                (Some("file"), Some(path)) => manifest.files.push(PathBuf::from(path)),
+                (Some("shader"), Some(path)) => {
+                    let Some(Ok(hash)) = fields.next().map(str::parse) else {
+                        continue;
+                    };
+                    let includes = fields.map(str::to_string).collect();
+                    manifest
+                        .shaders
+                        .insert(PathBuf::from(path), ShaderEntry { hash, includes });
+                }
+                _ => continue,
+            }
+        }
+        manifest
+    }
+
+    fn write(&self, target_dir_path: &Path, file_name: &str) -> Result<(), Error> {
+        let mut contents = String::new();
+        for file in &self.files {
+            contents.push_str(&format!("file\t{}\n", file.to_string_lossy()));
+        }
+        let mut shaders: Vec<_> = self.shaders.iter().collect();
+        shaders.sort_by_key(|(path, _)| *path);
+        for (path, entry) in shaders {
+            contents.push_str(&format!(
+                "shader\t{}\t{}",
+                path.to_string_lossy(),
+                entry.hash
+            ));
+            for include in &entry.includes {
+                contents.push_str(&format!("\t{include}"));
+            }
+            contents.push('\n');
+        }
+        std::fs::write(target_dir_path.join(file_name), contents)?;
+        Ok(())
+    }
+
+    /// Removes the files of this manifest that ```current``` no longer lists and their then empty directories.
+    ///
+    /// Anything vku did not write is kept.
+    #[allow(unused_must_use)]
+    fn remove_stale_files(&self, target_dir_path: &Path, current: &ShaderManifest) {
+        let kept: HashSet<&PathBuf> = current.files.iter().collect();
+        for relative_path in self.files.iter().filter(|file| !kept.contains(file)) {
+            let escapes = relative_path
+                .components()
+                .any(|component| !matches!(component, Component::Normal(_)));
+            if escapes {
+                continue;
+            }
+            remove_file(target_dir_path.join(relative_path));
+            for dir in relative_path.ancestors().skip(1) {
+                if dir.as_os_str().is_empty() {
+                    break;
+                }
+                // Fails and stops for directories that still contain files.
+                if remove_dir(target_dir_path.join(dir)).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
 /// Compile single shader module from String without writing to a file.
+///
+/// ```shader_ext``` selects the shader kind, see [compile_all_shaders] for the supported extensions.
+/// [output_suffix](ShaderCompileOptions::output_suffix) is ignored.
 #[allow(unused_must_use)]
 
 pub fn shader_ad_hoc(
     shader_src: String,
     shader_name: &str,
     shader_ext: &str,
-    debug: bool,
+    options: impl Into<ShaderCompileOptions>,
 ) -> Result<CompilationArtifact, Error> {
     let compiler = shaderc::Compiler::new().ok_or(Error::ShaderCInitError)?;
+    let shader_kind = shader_kind(shader_ext)?;
+    let compiler_options = compile_options(shader_kind, &options.into())?;
 
+    compile_to_spirv(
+        &compiler,
+        &shader_src,
+        Path::new(shader_name),
+        shader_kind,
+        Some(&compiler_options),
+    )
+    .map(|(binary_result, _)| binary_result)
+}
+
+/// File extension, pipeline stage and shaderc kind of every supported shader stage.
+const SHADER_STAGES: [(&str, ShaderStageFlags, ShaderKind); 14] = [
+    ("vert", ShaderStageFlags::VERTEX, ShaderKind::Vertex),
+    (
+        "tesc",
+        ShaderStageFlags::TESSELLATION_CONTROL,
+        ShaderKind::TessControl,
+    ),
+    (
+        "tese",
+        ShaderStageFlags::TESSELLATION_EVALUATION,
+        ShaderKind::TessEvaluation,
+    ),
+    ("geom", ShaderStageFlags::GEOMETRY, ShaderKind::Geometry),
+    ("frag", ShaderStageFlags::FRAGMENT, ShaderKind::Fragment),
+    ("comp", ShaderStageFlags::COMPUTE, ShaderKind::Compute),
+    ("task", ShaderStageFlags::TASK_EXT, ShaderKind::Task),
+    ("mesh", ShaderStageFlags::MESH_EXT, ShaderKind::Mesh),
+    (
+        "rgen",
+        ShaderStageFlags::RAYGEN_KHR,
+        ShaderKind::RayGeneration,
+    ),
+    ("rahit", ShaderStageFlags::ANY_HIT_KHR, ShaderKind::AnyHit),
+    (
+        "rchit",
+        ShaderStageFlags::CLOSEST_HIT_KHR,
+        ShaderKind::ClosestHit,
+    ),
+    ("rmiss", ShaderStageFlags::MISS_KHR, ShaderKind::Miss),
+    (
+        "rint",
+        ShaderStageFlags::INTERSECTION_KHR,
+        ShaderKind::Intersection,
+    ),
+    (
+        "rcall",
+        ShaderStageFlags::CALLABLE_KHR,
+        ShaderKind::Callable,
+    ),
+];
+
+fn shader_kind(extension: &str) -> Result<ShaderKind, Error> {
+    SHADER_STAGES
+        .iter()
+        .find(|(ext, _, _)| *ext == extension)
+        .map(|(_, _, kind)| *kind)
+        .ok_or(Error::UnknownShaderFileExtension)
+}
+
+/// File extension selecting the shader kind of ```stage``` in [shader_ad_hoc].
+pub(crate) fn stage_extension(stage: ShaderStageFlags) -> Result<&'static str, Error> {
+    SHADER_STAGES
+        .iter()
+        .find(|(_, flags, _)| *flags == stage)
+        .map(|(ext, _, _)| *ext)
+        .ok_or(Error::UnknownShaderFileExtension)
+}
+
+fn compile_options(
+    kind: ShaderKind,
+    options: &ShaderCompileOptions,
+) -> Result<shaderc::CompileOptions<'static>, Error> {
     let mut compiler_options = shaderc::CompileOptions::new().ok_or(Error::ShaderCInitError)?;
-    if debug {
-        compiler_options.set_optimization_level(shaderc::OptimizationLevel::Zero);
+    compiler_options.set_optimization_level(options.optimization);
+    if options.generate_debug_info {
         compiler_options.set_generate_debug_info();
-    } else {
-        compiler_options.set_optimization_level(shaderc::OptimizationLevel::Performance);
+    }
+    for (name, value) in &options.defines {
+        compiler_options.add_macro_definition(name, value.as_deref());
     }
 
-    compiler_options.set_include_callback(shader_include_callback);
-
-    let shader_kind = match shader_ext {
-        "frag" => Ok(shaderc::ShaderKind::Fragment),
-        "vert" => Ok(shaderc::ShaderKind::Vertex),
-        "comp" => Ok(shaderc::ShaderKind::Compute),
-        _ => Err(Error::UnknownShaderFileExtension),
-    }?;
+    // Task, mesh and ray tracing shaders need at least SPIR-V 1.4.
+    let needs_spirv_1_4 = matches!(
+        kind,
+        ShaderKind::Task
+            | ShaderKind::Mesh
+            | ShaderKind::RayGeneration
+            | ShaderKind::AnyHit
+            | ShaderKind::ClosestHit
+            | ShaderKind::Miss
+            | ShaderKind::Intersection
+            | ShaderKind::Callable
+    );
+    if needs_spirv_1_4 {
+        compiler_options.set_target_env(
+            shaderc::TargetEnv::Vulkan,
+            shaderc::EnvVersion::Vulkan1_2 as u32,
+        );
+    }
+    if let Some(version) = options.target_spirv {
+        compiler_options.set_target_spirv(version);
+    }
 
-    compile_shader_adhoc(
-        shader_src,
-        shader_name,
-        &compiler,
-        shader_kind,
-        Some(&compiler_options),
-    )
+    compiler_options.set_include_callback(shader_include_callback);
+    Ok(compiler_options)
 }
 
-fn compile_shader_adhoc(
-    shader_src: String,
-    shader_name: &str,
+/// Preprocesses and compiles ```shader_src```, naming it ```file``` in the messages of shaderc.
+///
+/// Returns the SPIR-V and the preprocessed source.
+/// Warnings are logged, errors are returned as [ShaderCompilation](Error::ShaderCompilation).
+fn compile_to_spirv(
     compiler: &shaderc::Compiler,
-    kind: shaderc::ShaderKind,
+    shader_src: &str,
+    file: &Path,
+    kind: ShaderKind,
     add_options: Option<&shaderc::CompileOptions>,
-) -> Result<CompilationArtifact, Error> {
-    trace!("Compiling shader {shader_name:?}");
+) -> Result<(CompilationArtifact, String), Error> {
+    let input_name = file.to_string_lossy();
+    trace!("Compiling shader {input_name:?}");
+
+    let preprocess = compiler
+        .preprocess(shader_src, &input_name, "main", add_options)
+        .map_err(|e| compilation_error(e, file, kind, ""))?;
+    let preprocessed = preprocess.as_text();
+
+    let binary_result = compiler
+        .compile_into_spirv(&preprocessed, kind, &input_name, "main", add_options)
+        .map_err(|e| compilation_error(e, file, kind, &preprocessed))?;
+
+    if binary_result.get_num_warnings() > 0 {
+        let log = annotate_log(
+            &binary_result.get_warning_messages(),
+            &input_name,
+            &preprocessed,
+        );
+        warn!(
+            "{file:?} ({kind:?}) compiled with {} warnings:\n{log}",
+            binary_result.get_num_warnings()
+        );
+    }
 
-    let preprocess = compiler.preprocess(&shader_src, shader_name, "main", add_options)?;
+    Ok((binary_result, preprocessed))
+}
 
-    let binary_result = match compiler.compile_into_spirv(
-        &preprocess.as_text(),
-        kind,
-        shader_name,
-        "main",
-        add_options,
-    ) {
-        Ok(result) => result,
-        Err(e) => {
-            let preprocess_text = preprocess.as_text();
-            let preprocess_numbered: Vec<String> = preprocess_text
-                .split('\n')
-                .enumerate()
-                .map(|(index, line)| format!("{}: {line}", index + 1))
-                .collect();
-            for p in preprocess_numbered {
-                println!("{p}");
+/// Converts shaderc compilation errors to [ShaderCompilation](Error::ShaderCompilation) and logs them with the numbered preprocessed source.
+fn compilation_error(
+    error: shaderc::Error,
+    file: &Path,
+    stage: ShaderKind,
+    preprocessed: &str,
+) -> Error {
+    let (num_errors, log) = match error {
+        shaderc::Error::CompilationError(num_errors, log) => (num_errors, log),
+        error => return Error::Preprocess(error),
+    };
+    let log = annotate_log(&log, &file.to_string_lossy(), preprocessed);
+    let preprocessed: String = preprocessed
+        .lines()
+        .enumerate()
+        .map(|(index, line)| format!("{}: {line}\n", index + 1))
+        .collect();
+    error!("{file:?} ({stage:?}) failed to compile:\n{log}\nPreprocessed source:\n{preprocessed}");
+
+    Error::ShaderCompilation {
+        file: file.to_path_buf(),
+        stage,
+        log,
+        num_errors,
+        preprocessed,
+    }
+}
+
+/// Appends the line of the preprocessed source to every ```file:line: error: ...``` message of a shaderc log.
+///
+/// Includes are inlined with ```#line``` directives, so shaderc reports lines of the included files instead.
+#[allow(unused_must_use)]
+fn annotate_log(log: &str, input_name: &str, preprocessed: &str) -> String {
+    let mut preprocessed_lines = HashMap::new();
+    let mut file = input_name.to_string();
+    let mut line = 1;
+    for (index, text) in preprocessed.lines().enumerate() {
+        match line_directive(text) {
+            Some((next_line, next_file)) => {
+                line = next_line;
+                if let Some(next_file) = next_file {
+                    file = next_file.to_string();
+                }
             }
+            None => {
+                preprocessed_lines
+                    .entry((file.clone(), line))
+                    .or_insert(index + 1);
+                line += 1;
+            }
+        }
+    }
 
-            return Err(Error::Preprocess(e));
+    let mut annotated = String::new();
+    for message in log.lines() {
+        annotated.push_str(message);
+        let location = log_location(message)
+            .and_then(|(file, line)| preprocessed_lines.get(&(file.to_string(), line)));
+        if let Some(preprocessed_line) = location {
+            write!(annotated, " (preprocessed line {preprocessed_line})");
         }
-    };
+        annotated.push('\n');
+    }
+    annotated
+}
 
-    Ok(binary_result)
+/// Line and optional file name of ```#line 12 "file.glsl"```.
+fn line_directive(text: &str) -> Option<(u32, Option<&str>)> {
+    let mut tokens = text.trim().strip_prefix("#line")?.split_whitespace();
+    let line = tokens.next()?.parse().ok()?;
+    let file = tokens
+        .next()
+        .and_then(|file| file.strip_prefix('"')?.strip_suffix('"'));
+    Some((line, file))
+}
+
+/// File name and line of a ```file:line: error: ...``` or ```file:line: warning: ...``` message.
+fn log_location(message: &str) -> Option<(&str, u32)> {
+    let end = [": error: ", ": warning: "]
+        .iter()
+        .filter_map(|severity| message.find(severity))
+        .min()?;
+    let (file, line) = message[..end].rsplit_once(':')?;
+    Some((file, line.parse().ok()?))
 }
 
 fn compile_shader(
-    shader_src: String,
-    shader_name: &str,
-    shader_ext: &str,
-    target_path: &Path,
     compiler: &shaderc::Compiler,
-    kind: shaderc::ShaderKind,
+    shader_src: &str,
+    file: &Path,
+    kind: ShaderKind,
     add_options: Option<&shaderc::CompileOptions>,
-    debug: bool,
-) -> Result<(), Error> {
-    trace!("Compiling shader {shader_name:?}");
+    binary_path: &Path,
+    text_path: Option<PathBuf>,
+) -> Result<Vec<u32>, Error> {
+    let (binary_result, preprocessed) =
+        compile_to_spirv(compiler, shader_src, file, kind, add_options)?;
 
-    let preprocess = compiler.preprocess(&shader_src, shader_name, "main", add_options)?;
-
-    let binary_result = compiler.compile_into_spirv(
-        &preprocess.as_text(),
-        kind,
-        shader_name,
-        "main",
-        add_options,
-    )?;
-    let binary_extension = String::from(shader_ext) + ".spv";
-    let binary_path = Path::new(target_path)
-        .join(shader_name)
-        .with_extension(binary_extension);
-
-    if debug {
+    if let Some(text_path) = text_path {
         let text_result = compiler.compile_into_spirv_assembly(
-            &preprocess.as_text(),
+            &preprocessed,
             kind,
-            shader_name,
+            &file.to_string_lossy(),
             "main",
             add_options,
         )?;
-        let mut text_extension = String::from(shader_ext);
-        text_extension.push_str(".txt");
-        let text_path = Path::new(target_path)
-            .join(shader_name)
-            .with_extension(text_extension);
-
         std::fs::write(text_path, text_result.as_text())?;
     }
 
     std::fs::write(binary_path, binary_result.as_binary_u8())?;
 
-    Ok(())
+    Ok(binary_result.as_binary().to_vec())
 }
 
 fn shader_include_callback(
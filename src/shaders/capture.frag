@@ -0,0 +1,34 @@
+#version 450
+
+layout(location = 0) in vec2 in_uv;
+layout(location = 0) out vec4 out_color;
+
+layout(set = 0, binding = 0) uniform sampler2D frame;
+
+layout(push_constant) uniform Push {
+    float exposure;
+    uint tonemap;
+    uint encode_srgb;
+} push;
+
+vec3 linear_to_srgb(vec3 value) {
+    vec3 low = value * 12.92;
+    vec3 high = 1.055 * pow(value, vec3(1.0 / 2.4)) - 0.055;
+    return mix(high, low, lessThanEqual(value, vec3(0.0031308)));
+}
+
+// Converts the sampled frame into the rgba8 values of a capture, see CaptureOptions.
+void main() {
+    vec4 color = texture(frame, in_uv);
+    vec3 rgb = color.rgb;
+    if (push.tonemap != 0) {
+        // Reinhard
+        rgb *= push.exposure;
+        rgb = rgb / (1.0 + rgb);
+    }
+    rgb = clamp(rgb, 0.0, 1.0);
+    if (push.encode_srgb != 0) {
+        rgb = linear_to_srgb(rgb);
+    }
+    out_color = vec4(rgb, clamp(color.a, 0.0, 1.0));
+}
@@ -0,0 +1,23 @@
+#version 450
+layout(constant_id = 0) const uint ENCODE_SRGB = 0;
+
+layout(set = 0, binding = 0) uniform sampler2D tex;
+
+layout(location = 0) in vec4 i_col;
+layout(location = 1) in vec2 i_uv;
+
+layout(location = 0) out vec4 o_col;
+
+vec3 srgb_from_linear(vec3 linear) {
+    vec3 lower = linear * 12.92;
+    vec3 higher = 1.055 * pow(linear, vec3(1.0 / 2.4)) - 0.055;
+    return mix(higher, lower, lessThan(linear, vec3(0.0031308)));
+}
+
+void main() {
+    vec4 col = i_col * texture(tex, i_uv);
+    if (ENCODE_SRGB == 1) {
+        col.rgb = srgb_from_linear(col.rgb);
+    }
+    o_col = col;
+}
@@ -0,0 +1,24 @@
+#version 450
+layout(push_constant) uniform Push {
+    mat4 screen_to_ndc;
+} push;
+
+layout(location = 0) in vec2 i_pos;
+layout(location = 1) in vec2 i_uv;
+layout(location = 2) in vec4 i_col;
+
+layout(location = 0) out vec4 o_col;
+layout(location = 1) out vec2 o_uv;
+
+// egui vertex colors are premultiplied sRGB.
+vec3 linear_from_srgb(vec3 srgb) {
+    vec3 lower = srgb / 12.92;
+    vec3 higher = pow((srgb + 0.055) / 1.055, vec3(2.4));
+    return mix(higher, lower, lessThan(srgb, vec3(0.04045)));
+}
+
+void main() {
+    o_col = vec4(linear_from_srgb(i_col.rgb), i_col.a);
+    o_uv = i_uv;
+    gl_Position = push.screen_to_ndc * vec4(i_pos, 0.0, 1.0);
+}
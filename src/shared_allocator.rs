@@ -0,0 +1,24 @@
+use std::sync::{Mutex, MutexGuard};
+
+use crate::imports::*;
+
+/// [GPU-Allocator](gpu_allocator::vulkan::Allocator) of a [VkInit](crate::VkInit), locked for every use so resources can be created from any thread.
+pub struct SharedAllocator(Mutex<Allocator>);
+
+impl SharedAllocator {
+    pub(crate) fn new(allocator: Allocator) -> Self {
+        Self(Mutex::new(allocator))
+    }
+
+    /// Locks the allocator until the guard is dropped, e.g. ```buffer.destroy(&init.device, &mut init.allocator.lock())```.
+    ///
+    /// A lock poisoned by a panicking thread is recovered, the allocator is consistent between its calls.
+    pub fn lock(&self) -> MutexGuard<'_, Allocator> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Same as [lock](SharedAllocator::lock) without locking, the exclusive borrow rules out other users.
+    pub fn get_mut(&mut self) -> &mut Allocator {
+        self.0.get_mut().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,238 @@
+use std::ops::Range;
+
+use gpu_allocator::vulkan::AllocationScheme;
+
+use crate::{imports::*, provenance::Provenance, VkInit};
+
+/// Partially resident buffer, memory is bound per page with [bind_pages](SparseBuffer::bind_pages).
+///
+/// Requires ```sparse_binding``` and ```sparse_residency_buffer``` device features and a queue family with ```QueueFlags::SPARSE_BINDING```.
+/// Reads from non-resident pages return undefined values, writes are discarded.
+pub struct SparseBuffer {
+    pub buffer: Buffer,
+    /// Size of the buffer, rounded up to whole pages.
+    pub size: u64,
+    page_size: u64,
+    memory_type_bits: u32,
+    pages: Vec<Option<Allocation>>,
+    provenance: Provenance,
+}
+
+impl SparseBuffer {
+    /// Creates a buffer of at least ```size``` bytes without any resident pages.
+    ///
+    /// Returns [SparseBindingNotSupported](Error::SparseBindingNotSupported) if the device lacks the required features or queues.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # use gpu_allocator::MemoryLocation;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let usage = BufferUsageFlags::STORAGE_BUFFER | BufferUsageFlags::TRANSFER_DST;
+    /// let mut buffer = match SparseBuffer::new(&init, 4 << 30, usage) {
+    ///     Err(Error::SparseBindingNotSupported) => return Ok(()),
+    ///     buffer => buffer?,
+    /// };
+    ///
+    /// buffer.bind_pages(&mut init, 0..4, Some(MemoryLocation::GpuOnly))?;
+    /// assert!(buffer.is_resident(3));
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn new(vk_init: &VkInit, size: u64, usage: BufferUsageFlags) -> Result<Self, Error> {
+        let features = &vk_init.physical_device_info.features;
+        if features.sparse_binding == FALSE
+            || features.sparse_residency_buffer == FALSE
+            || vk_init.get_sparse_binding_queue().is_none()
+        {
+            return Err(Error::SparseBindingNotSupported);
+        }
+
+        let buffer_info = BufferCreateInfo::builder()
+            .size(size)
+            .usage(usage)
+            .flags(BufferCreateFlags::SPARSE_BINDING | BufferCreateFlags::SPARSE_RESIDENCY)
+            .sharing_mode(SharingMode::EXCLUSIVE);
+        let buffer = unsafe { vk_init.device.create_buffer(&buffer_info, None)? };
+        let requirements = unsafe { vk_init.device.get_buffer_memory_requirements(buffer) };
+
+        let page_size = requirements.alignment;
+        let page_count = requirements.size.div_ceil(page_size) as usize;
+
+        Ok(Self {
+            buffer,
+            size: page_count as u64 * page_size,
+            page_size,
+            memory_type_bits: requirements.memory_type_bits,
+            pages: (0..page_count).map(|_| None).collect(),
+            provenance: Provenance::new(&vk_init.device, "SparseBuffer"),
+        })
+    }
+
+    /// Sparse block size of the buffer in bytes.
+    pub fn page_size(&self) -> u64 {
+        self.page_size
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Byte range covered by ```pages```.
+    pub fn page_bytes(&self, pages: Range<usize>) -> Range<u64> {
+        pages.start as u64 * self.page_size..pages.end as u64 * self.page_size
+    }
+
+    pub fn is_resident(&self, page: usize) -> bool {
+        matches!(self.pages.get(page), Some(Some(_)))
+    }
+
+    pub fn resident_pages(&self) -> usize {
+        self.pages.iter().flatten().count()
+    }
+
+    /// Binds memory from ```location``` to all non-resident pages in ```pages```, or unbinds and frees resident pages for ```None```.
+    ///
+    /// Blocks until the sparse binding queue finished the bind, residency is unchanged if the bind fails.
+    /// The GPU must not access unbound pages anymore.
+    pub fn bind_pages(
+        &mut self,
+        vk_init: &mut VkInit,
+        pages: Range<usize>,
+        location: Option<MemoryLocation>,
+    ) -> Result<(), Error> {
+        self.check(&vk_init.device, "bind_pages");
+        if pages.end > self.pages.len() {
+            return Err(Error::SparsePagesOutOfRange {
+                end: pages.end,
+                page_count: self.pages.len(),
+            });
+        }
+
+        let mut binds = vec![];
+        let mut allocated = vec![];
+        let mut unbound = vec![];
+        let result = (|| {
+            for page in pages {
+                let resource_offset = page as u64 * self.page_size;
+                match (location, &self.pages[page]) {
+                    (Some(location), None) => {
+                        let allocation =
+                            vk_init.allocator.lock().allocate(&AllocationCreateDesc {
+                                name: "Sparse_Page_Memory",
+                                requirements: MemoryRequirements {
+                                    size: self.page_size,
+                                    alignment: self.page_size,
+                                    memory_type_bits: self.memory_type_bits,
+                                },
+                                location,
+                                linear: true,
+                                allocation_scheme: AllocationScheme::GpuAllocatorManaged,
+                            })?;
+                        binds.push(SparseMemoryBind {
+                            resource_offset,
+                            size: self.page_size,
+                            memory: unsafe { allocation.memory() },
+                            memory_offset: allocation.offset(),
+                            flags: SparseMemoryBindFlags::empty(),
+                        });
+                        allocated.push((page, allocation));
+                    }
+                    (None, Some(_)) => {
+                        binds.push(SparseMemoryBind {
+                            resource_offset,
+                            size: self.page_size,
+                            memory: DeviceMemory::null(),
+                            memory_offset: 0,
+                            flags: SparseMemoryBindFlags::empty(),
+                        });
+                        unbound.push(page);
+                    }
+                    _ => {}
+                }
+            }
+
+            if binds.is_empty() {
+                return Ok(());
+            }
+            let (queue, _) = vk_init
+                .get_sparse_binding_queue()
+                .ok_or(Error::SparseBindingNotSupported)?;
+            let buffer_binds = [SparseBufferMemoryBindInfo::builder()
+                .buffer(self.buffer)
+                .binds(&binds)
+                .build()];
+            let bind_info = BindSparseInfo::builder().buffer_binds(&buffer_binds);
+
+            unsafe {
+                let fence = vk_init
+                    .device
+                    .create_fence(&FenceCreateInfo::default(), None)?;
+                let submitted = vk_init
+                    .device
+                    .queue_bind_sparse(queue, &[*bind_info], fence)
+                    .map_err(Error::from);
+                let result = submitted
+                    .and_then(|_| vk_init.wait_for_fences_watched(&[fence], "SparseBuffer::bind"));
+                if result.is_err() {
+                    // The bind may still be pending, new pages are freed afterwards.
+                    let _ = vk_init.device.queue_wait_idle(queue);
+                }
+                vk_init.device.destroy_fence(fence, None);
+                result
+            }
+        })();
+
+        // Pages only change once the bind has completed.
+        if let Err(e) = result {
+            for (_, allocation) in allocated {
+                vk_init.allocator.lock().free(allocation)?;
+            }
+            return Err(e);
+        }
+        for (page, allocation) in allocated {
+            self.pages[page] = Some(allocation);
+        }
+        for allocation in unbound
+            .into_iter()
+            .filter_map(|page| self.pages[page].take())
+        {
+            vk_init.allocator.lock().free(allocation)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_debug_object_name(&self, vk_init: &VkInit, base_name: String) -> Result<(), Error> {
+        self.check(&vk_init.device, "set_debug_object_name");
+        self.provenance.set_name(&base_name);
+        vk_init.set_debug_object_name_fmt(
+            self.buffer.as_raw(),
+            ObjectType::BUFFER,
+            format_args!("{base_name}_Sparse_Buffer"),
+        )
+    }
+
+    /// Frees all resident pages. Destroying an already destroyed buffer is a no-op.
+    pub fn destroy(&mut self, device: &Device, allocator: &mut Allocator) -> Result<(), Error> {
+        self.provenance
+            .check_instance(device, "SparseBuffer", "destroy");
+        self.provenance.release();
+        unsafe { device.destroy_buffer(self.buffer, None) };
+        self.buffer = Buffer::null();
+        for allocation in self.pages.iter_mut().filter_map(Option::take) {
+            allocator.free(allocation)?;
+        }
+        Ok(())
+    }
+
+    fn check(&self, device: &Device, operation: &str) {
+        self.provenance
+            .check_alive(self.buffer != Buffer::null(), "SparseBuffer", operation);
+        self.provenance
+            .check_instance(device, "SparseBuffer", operation);
+    }
+}
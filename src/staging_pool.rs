@@ -0,0 +1,251 @@
+use crate::{
+    allocation_lifetime, imports::*, AllocationLifetime, LifetimeReport, VMABuffer, VkInit,
+};
+
+const MIN_BUCKET_SIZE: usize = 64 * 1024;
+
+/// Host-visible staging buffer borrowed from the [StagingPool].
+///
+/// Return it with [release_staging](VkInit::release_staging) once the upload was submitted.
+pub struct PooledStaging {
+    pub buffer: VMABuffer,
+    bucket_size: usize,
+}
+
+impl PooledStaging {
+    /// Size of the underlying buffer, which may be larger than the acquired size.
+    pub fn bucket_size(&self) -> usize {
+        self.bucket_size
+    }
+
+    /// Shortcut - see [VMABuffer](VMABuffer::set_data).
+    pub fn set_data<T>(&self, device: &Device, offset: usize, data: &[T]) -> Result<(), Error> {
+        self.buffer.set_data(device, offset, data)
+    }
+}
+
+/// Statistics of the [StagingPool].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StagingPoolStats {
+    /// Buffers currently allocated by the pool, whether acquired, in flight or free.
+    pub buffers_live: usize,
+    /// Bytes of host-visible memory currently held by the pool.
+    pub bytes_resident: u64,
+    /// Highest value of ```bytes_resident``` so far.
+    pub high_water_mark: u64,
+}
+
+/// Reuses host-visible staging buffers across uploads, bucketed by power-of-two sizes.
+///
+/// Released buffers are only handed out again after their upload fence has signaled.
+/// At most ```max_retained_bytes``` of released buffers are kept, see [release](StagingPool::release).
+pub struct StagingPool {
+    free: Vec<PooledStaging>,
+    in_flight: Vec<(Fence, PooledStaging)>,
+    max_retained_bytes: u64,
+    stats: StagingPoolStats,
+}
+
+impl StagingPool {
+    pub fn new(max_retained_bytes: u64) -> Self {
+        Self {
+            free: vec![],
+            in_flight: vec![],
+            max_retained_bytes,
+            stats: StagingPoolStats::default(),
+        }
+    }
+
+    pub fn acquire(
+        &mut self,
+        device: &Device,
+        allocator: &mut Allocator,
+        size: usize,
+    ) -> Result<PooledStaging, Error> {
+        self.reclaim(device)?;
+
+        let bucket_size = size.max(MIN_BUCKET_SIZE).next_power_of_two();
+        if let Some(index) = self
+            .free
+            .iter()
+            .position(|staging| staging.bucket_size == bucket_size)
+        {
+            return Ok(self.free.swap_remove(index));
+        }
+
+        let buffer = VMABuffer::create_buffer_with_lifetime(
+            device,
+            allocator,
+            bucket_size,
+            BufferUsageFlags::TRANSFER_SRC,
+            MemoryLocation::CpuToGpu,
+            AllocationLifetime::Transient,
+        )?;
+
+        self.stats.buffers_live += 1;
+        self.stats.bytes_resident += bucket_size as u64;
+        self.stats.high_water_mark = self.stats.high_water_mark.max(self.stats.bytes_resident);
+        trace!("Staging pool allocated new {bucket_size} byte buffer");
+
+        Ok(PooledStaging {
+            buffer,
+            bucket_size,
+        })
+    }
+
+    /// Returns a staging buffer to the pool.
+    ///
+    /// With ```fence```, the buffer is reused only after the fence has signaled.
+    /// Returns the buffers evicted to stay within ```max_retained_bytes```, free ones first, then the longest in flight,
+    /// each with the fence of its last upload. [release_staging](VkInit::release_staging) destroys them.
+    pub fn release(
+        &mut self,
+        staging: PooledStaging,
+        fence: Option<Fence>,
+    ) -> Vec<(PooledStaging, Option<Fence>)> {
+        match fence {
+            Some(fence) => self.in_flight.push((fence, staging)),
+            None => self.free.push(staging),
+        }
+
+        let mut evicted = Vec::new();
+        while self.retained_bytes() > self.max_retained_bytes {
+            let (staging, fence) = match self.free.is_empty() {
+                false => (self.free.remove(0), None),
+                true => {
+                    let (fence, staging) = self.in_flight.remove(0);
+                    (staging, Some(fence))
+                }
+            };
+            self.stats.buffers_live -= 1;
+            self.stats.bytes_resident -= staging.bucket_size as u64;
+            trace!("Staging pool evicted {} byte buffer", staging.bucket_size);
+            evicted.push((staging, fence));
+        }
+        evicted
+    }
+
+    /// Bytes of released buffers kept for reuse, whether in flight or free.
+    pub fn retained_bytes(&self) -> u64 {
+        let in_flight = self.in_flight.iter().map(|(_, staging)| staging);
+        self.free
+            .iter()
+            .chain(in_flight)
+            .map(|staging| staging.bucket_size as u64)
+            .sum()
+    }
+
+    /// Moves all buffers whose upload fence has signaled back to the free list.
+    pub fn reclaim(&mut self, device: &Device) -> Result<(), Error> {
+        let mut index = 0;
+        while index < self.in_flight.len() {
+            let (fence, _) = self.in_flight[index];
+            if unsafe { device.get_fence_status(fence)? } {
+                let (_, staging) = self.in_flight.swap_remove(index);
+                self.free.push(staging);
+            } else {
+                index += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Frees all buffers that are not in flight.
+    pub fn trim(&mut self, device: &Device, allocator: &mut Allocator) -> Result<(), Error> {
+        self.reclaim(device)?;
+        for mut staging in self.free.drain(..) {
+            staging.buffer.destroy(device, allocator)?;
+            self.stats.buffers_live -= 1;
+            self.stats.bytes_resident -= staging.bucket_size as u64;
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self) -> StagingPoolStats {
+        self.stats
+    }
+
+    /// Device must be idle.
+    pub fn destroy(&mut self, device: &Device, allocator: &mut Allocator) -> Result<(), Error> {
+        for (_, staging) in self.in_flight.drain(..) {
+            self.free.push(staging);
+        }
+        self.trim(device, allocator)
+    }
+}
+
+/// Snapshot of the memory held by vku itself.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MemoryReport {
+    pub staging_pool: StagingPoolStats,
+    /// Usage per [AllocationLifetime] and fragmentation of the shared memory blocks.
+    pub lifetimes: LifetimeReport,
+}
+
+impl std::fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "VKU memory report")?;
+        writeln!(
+            f,
+            "Staging pool: {} buffers, {} bytes resident, {} bytes high-water mark",
+            self.staging_pool.buffers_live,
+            self.staging_pool.bytes_resident,
+            self.staging_pool.high_water_mark
+        )?;
+        for lifetime in AllocationLifetime::ALL {
+            let usage = self.lifetimes.usage(lifetime);
+            writeln!(
+                f,
+                "{lifetime:?}: {} allocations, {} bytes",
+                usage.allocations, usage.bytes
+            )?;
+        }
+        writeln!(
+            f,
+            "Fragmentation: {} bytes between suballocations, {} bytes largest free block",
+            self.lifetimes.fragmented_bytes, self.lifetimes.largest_free_block
+        )
+    }
+}
+
+impl VkInit {
+    /// Acquires a host-visible staging buffer of at least ```size``` bytes from the [StagingPool].
+    pub fn acquire_staging(&mut self, size: usize) -> Result<PooledStaging, Error> {
+        let mut staging =
+            self.staging_pool
+                .acquire(&self.device, &mut self.allocator.lock(), size)?;
+        staging.buffer = staging
+            .buffer
+            .with_atom_size(&self.physical_device_info.limits);
+        staging
+            .buffer
+            .set_debug_object_name(self, format!("VKU_Staging_Pool_{}", staging.bucket_size))?;
+        Ok(staging)
+    }
+
+    /// Shortcut - see [StagingPool](StagingPool::release).
+    ///
+    /// Evicted buffers still in flight go to the [DeletionQueue](crate::DeletionQueue), the others are destroyed right away.
+    pub fn release_staging(
+        &mut self,
+        staging: PooledStaging,
+        fence: Option<Fence>,
+    ) -> Result<(), Error> {
+        for (mut staging, fence) in self.staging_pool.release(staging, fence) {
+            match fence {
+                Some(fence) => self.defer_destroy_buffer(staging.buffer, fence),
+                None => staging
+                    .buffer
+                    .destroy(&self.device, &mut self.allocator.lock())?,
+            }
+        }
+        Ok(())
+    }
+
+    pub fn memory_report(&self) -> MemoryReport {
+        MemoryReport {
+            staging_pool: self.staging_pool.stats(),
+            lifetimes: allocation_lifetime::report(&self.device),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{imports::*, SurfaceInfo};
+
+/// Clockwise rotation the renderer has to apply itself because the swapchain uses a rotated [pre_transform](SurfaceInfo::pre_transform),
+/// e.g. on Android or rotated embedded displays where ```IDENTITY``` is not supported.
+///
+/// The swapchain and depth image use the display's native orientation, so their extent is the window size with width and height swapped
+/// for [Cw90](SurfaceRotation::Cw90) and [Cw270](SurfaceRotation::Cw270). [SurfaceInfo::current_extent] already holds the swapped extent.
+///
+/// **Push constant convention**: pass [pre_rotation_matrix](SurfaceInfo::pre_rotation_matrix) as ```mat2``` and rotate the clip space position
+/// after all other transforms in the vertex shader:
+/// ```glsl
+/// layout(push_constant) uniform Push {
+///     mat2 pre_rotation;
+/// } push;
+///
+/// void main() {
+///     gl_Position = proj * view * model * vec4(pos, 1.0);
+///     gl_Position.xy = push.pre_rotation * gl_Position.xy;
+/// }
+/// ```
+/// Mirrored transforms are not interpreted and reported as [None](SurfaceRotation::None).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SurfaceRotation {
+    #[default]
+    None,
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+impl SurfaceRotation {
+    pub fn from_transform(transform: SurfaceTransformFlagsKHR) -> Self {
+        match transform {
+            SurfaceTransformFlagsKHR::ROTATE_90 => Self::Cw90,
+            SurfaceTransformFlagsKHR::ROTATE_180 => Self::Cw180,
+            SurfaceTransformFlagsKHR::ROTATE_270 => Self::Cw270,
+            _ => Self::None,
+        }
+    }
+
+    pub fn degrees(&self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Cw90 => 90,
+            Self::Cw180 => 180,
+            Self::Cw270 => 270,
+        }
+    }
+
+    /// True for quarter turns, which swap width and height of the swapchain extent.
+    pub fn swaps_extent(&self) -> bool {
+        matches!(self, Self::Cw90 | Self::Cw270)
+    }
+
+    /// Swapchain extent for a window of ```extent``` in this rotation.
+    pub fn rotate_extent(&self, extent: Extent2D) -> Extent2D {
+        match self.swaps_extent() {
+            true => Extent2D {
+                width: extent.height,
+                height: extent.width,
+            },
+            false => extent,
+        }
+    }
+
+    /// 2D rotation in column-major order, see [SurfaceRotation] for the push constant convention.
+    pub fn matrix(&self) -> [[f32; 2]; 2] {
+        match self {
+            Self::None => [[1.0, 0.0], [0.0, 1.0]],
+            Self::Cw90 => [[0.0, 1.0], [-1.0, 0.0]],
+            Self::Cw180 => [[-1.0, 0.0], [0.0, -1.0]],
+            Self::Cw270 => [[0.0, -1.0], [1.0, 0.0]],
+        }
+    }
+}
+
+impl SurfaceInfo {
+    /// Rotation of the selected [pre_transform](SurfaceInfo::pre_transform).
+    pub fn rotation(&self) -> SurfaceRotation {
+        SurfaceRotation::from_transform(self.pre_transform)
+    }
+
+    /// Shortcut - see [SurfaceRotation](SurfaceRotation::matrix).
+    pub fn pre_rotation_matrix(&self) -> [[f32; 2]; 2] {
+        self.rotation().matrix()
+    }
+}
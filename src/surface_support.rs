@@ -0,0 +1,210 @@
+use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
+
+use crate::debug::DebugMessageCapture;
+use crate::{imports::*, VkInit, VkInitCreateInfo};
+
+/// Surface support of all physical devices, returned by [query_surface_support].
+#[derive(Clone, Debug)]
+pub struct SurfaceSupportReport {
+    /// In enumeration order, matching [GpuSelection::Index](crate::GpuSelection::Index).
+    pub devices: Vec<DeviceSurfaceSupport>,
+}
+
+/// Surface formats, present modes and capabilities of one physical device.
+///
+/// Formats, present modes and capabilities are only queried and non-empty if ```present_supported``` is set.
+#[derive(Clone, Debug)]
+pub struct DeviceSurfaceSupport {
+    pub name: String,
+    pub device_type: PhysicalDeviceType,
+    /// Any queue family of the device can present to the surface.
+    pub present_supported: bool,
+    pub formats: Vec<SurfaceFormatKHR>,
+    pub present_modes: Vec<PresentModeKHR>,
+    pub min_image_count: u32,
+    /// ```0``` if there is no upper limit.
+    pub max_image_count: u32,
+    /// ```u32::MAX``` in both dimensions if the swapchain decides the extent.
+    pub current_extent: Extent2D,
+    pub min_extent: Extent2D,
+    pub max_extent: Extent2D,
+}
+
+impl SurfaceSupportReport {
+    /// Devices that can present to the surface, with their [GpuSelection::Index](crate::GpuSelection::Index).
+    pub fn presentable_devices(&self) -> impl Iterator<Item = (usize, &DeviceSurfaceSupport)> {
+        self.devices
+            .iter()
+            .enumerate()
+            .filter(|(_, device)| device.present_supported)
+    }
+}
+
+impl DeviceSurfaceSupport {
+    pub fn supports_format(&self, format: Format) -> bool {
+        self.formats
+            .iter()
+            .any(|surface_format| surface_format.format == format)
+    }
+
+    pub fn supports_present_mode(&self, present_mode: PresentModeKHR) -> bool {
+        self.present_modes.contains(&present_mode)
+    }
+}
+
+/// First format of ```preferences``` the surface supports, falling back to the first supported format with a warning.
+///
+/// Returns ```None``` only if ```available``` is empty.
+pub fn choose_surface_format(
+    preferences: &[Format],
+    available: &[SurfaceFormatKHR],
+) -> Option<SurfaceFormatKHR> {
+    let preferred = preferences.iter().find_map(|preference| {
+        available
+            .iter()
+            .find(|surface_format| surface_format.format == *preference)
+    });
+    if let Some(surface_format) = preferred {
+        return Some(*surface_format);
+    }
+    let fallback = *available.first()?;
+    warn!(
+        "None of the preferred surface formats {preferences:?} are supported, falling back to {:?}",
+        fallback.format
+    );
+    Some(fallback)
+}
+
+/// First present mode of ```preferences``` the surface supports, falling back to ```FIFO``` with a warning.
+pub fn choose_present_mode(
+    preferences: &[PresentModeKHR],
+    available: &[PresentModeKHR],
+) -> PresentModeKHR {
+    if let Some(present_mode) = preferences.iter().find(|mode| available.contains(mode)) {
+        return *present_mode;
+    }
+    // FIFO is the only present mode every surface has to support.
+    warn!(
+        "None of the preferred present modes {preferences:?} are supported, falling back to FIFO"
+    );
+    PresentModeKHR::FIFO
+}
+
+/// Queries the surface support of all physical devices without creating a [VkInit], e.g. for graphics settings menus.
+///
+/// Creates a temporary instance without validation and a surface for ```raw_window_handles```, both are destroyed before returning.
+/// No logical device is created.
+/// ```
+/// # extern crate winit;
+/// # use vku::*;
+/// # if VkInit::load_entry().is_err() { return Ok(()); }
+/// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+/// # let window = winit::window::WindowBuilder::new().build(&event_loop).unwrap();
+/// let report = query_surface_support(&window)?;
+/// for (index, device) in report.presentable_devices() {
+///     println!("{index}: {} {:?}", device.name, device.present_modes);
+/// }
+/// # Ok::<(), vku::Error>(())
+/// ```
+pub fn query_surface_support<T: HasRawDisplayHandle + HasRawWindowHandle>(
+    raw_window_handles: &T,
+) -> Result<SurfaceSupportReport, Error> {
+    let display_handle = raw_window_handles.raw_display_handle();
+    let window_handle = raw_window_handles.raw_window_handle();
+
+    unsafe {
+        let (entry, loader_info) = VkInit::load_entry()?;
+        let create_info = VkInitCreateInfo {
+            vk_version: loader_info.instance_version.min(API_VERSION_1_3),
+            ..VkInitCreateInfo::dist_vk_1_3()
+        };
+        let (instance, _, _) = VkInit::create_instance_and_debug(
+            &entry,
+            Some(display_handle),
+            &create_info,
+            &DebugMessageCapture::default(),
+        )?;
+
+        let surface = match ash_window::create_surface(
+            &entry,
+            &instance,
+            display_handle,
+            window_handle,
+            None,
+        ) {
+            Ok(surface) => surface,
+            Err(e) => {
+                instance.destroy_instance(None);
+                return Err(e.into());
+            }
+        };
+        let loader = Surface::new(&entry, &instance);
+
+        let devices = query_devices(&instance, &loader, surface);
+
+        loader.destroy_surface(surface, None);
+        instance.destroy_instance(None);
+        trace!("Destroyed temporary instance and surface of the surface support query");
+
+        Ok(SurfaceSupportReport { devices: devices? })
+    }
+}
+
+unsafe fn query_devices(
+    instance: &Instance,
+    loader: &Surface,
+    surface: SurfaceKHR,
+) -> Result<Vec<DeviceSurfaceSupport>, Error> {
+    let mut devices = vec![];
+    for physical_device in instance.enumerate_physical_devices()? {
+        let properties = instance.get_physical_device_properties(physical_device);
+        let queue_family_count = instance
+            .get_physical_device_queue_family_properties(physical_device)
+            .len();
+
+        let mut present_supported = false;
+        for index in 0..queue_family_count as u32 {
+            if loader.get_physical_device_surface_support(physical_device, index, surface)? {
+                present_supported = true;
+                break;
+            }
+        }
+
+        let mut device = DeviceSurfaceSupport {
+            name: char_array_to_string(&properties.device_name)?,
+            device_type: properties.device_type,
+            present_supported,
+            formats: vec![],
+            present_modes: vec![],
+            min_image_count: 0,
+            max_image_count: 0,
+            current_extent: Extent2D::default(),
+            min_extent: Extent2D::default(),
+            max_extent: Extent2D::default(),
+        };
+
+        if present_supported {
+            let capabilities =
+                loader.get_physical_device_surface_capabilities(physical_device, surface)?;
+            device.formats =
+                loader.get_physical_device_surface_formats(physical_device, surface)?;
+            device.present_modes =
+                loader.get_physical_device_surface_present_modes(physical_device, surface)?;
+            device.min_image_count = capabilities.min_image_count;
+            device.max_image_count = capabilities.max_image_count;
+            device.current_extent = capabilities.current_extent;
+            device.min_extent = capabilities.min_image_extent;
+            device.max_extent = capabilities.max_image_extent;
+        }
+
+        trace!(
+            "Surface support of {}: {} formats, {:?}",
+            device.name,
+            device.formats.len(),
+            device.present_modes
+        );
+        devices.push(device);
+    }
+
+    Ok(devices)
+}
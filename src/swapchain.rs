@@ -1,65 +1,293 @@
+use std::sync::MutexGuard;
+
+use ash::prelude::VkResult;
 use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
 
-use crate::{imports::*, VMAImage, VkInit};
+use crate::{imports::*, init::semaphore_submit_info, CmdType, Head, SurfaceInfo, VkInit};
+
+/// Outcome of [acquire_next_swapchain_image](VkInit::acquire_next_swapchain_image).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcquireResult {
+    /// The image was acquired, ```suboptimal``` swapchains can still be presented but should be recreated.
+    Ready {
+        index: usize,
+        image: Image,
+        view: ImageView,
+        suboptimal: bool,
+    },
+    /// No image was acquired and the semaphore is not signaled, the swapchain has to be [recreated](VkInit::on_resize).
+    OutOfDate,
+}
+
+impl AcquireResult {
+    /// Maps the result of ```vkAcquireNextImageKHR``` to the acquired image of ```images``` and ```views```.
+    ///
+    /// ```ERROR_OUT_OF_DATE_KHR``` becomes [OutOfDate](AcquireResult::OutOfDate), ```SUBOPTIMAL_KHR``` a suboptimal [Ready](AcquireResult::Ready) image.
+    pub fn from_vk(
+        result: VkResult<(u32, bool)>,
+        images: &[Image],
+        views: &[ImageView],
+    ) -> Result<Self, Error> {
+        match result {
+            Ok((index, suboptimal)) => Ok(Self::Ready {
+                index: index as usize,
+                image: images[index as usize],
+                view: views[index as usize],
+                suboptimal,
+            }),
+            Err(ash::vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(Self::OutOfDate),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl Error {
+    /// Maps the result of ```vkQueuePresentKHR```, ```ERROR_OUT_OF_DATE_KHR``` becomes [SwapchainOutOfDate](Error::SwapchainOutOfDate).
+    ///
+    /// Returns whether the swapchain is suboptimal.
+    pub fn from_present(result: VkResult<bool>) -> Result<bool, Error> {
+        match result {
+            Ok(suboptimal) => Ok(suboptimal),
+            Err(ash::vk::Result::ERROR_OUT_OF_DATE_KHR) => Err(Error::SwapchainOutOfDate),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Swapchain image that was acquired but not presented yet.
+pub(crate) struct AcquiredImage {
+    index: u32,
+    /// ```None``` once a submit through [submit_cmd_buffers2](VkInit::submit_cmd_buffers2) or its shortcuts waited on it.
+    semaphore: Option<Semaphore>,
+}
+
+impl Head {
+    /// Destroys the depth image and the multisampled color attachment.
+    pub(crate) fn destroy_attachments(
+        &mut self,
+        device: &Device,
+        allocator: &mut Allocator,
+    ) -> Result<(), Error> {
+        if let Some(mut msaa_image) = self.msaa_image.take() {
+            msaa_image.destroy(device, allocator)?;
+        }
+        self.depth_image.destroy(device, allocator)
+    }
+
+    fn acquired_images(&self) -> MutexGuard<'_, Vec<AcquiredImage>> {
+        self.acquired_images
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn track_acquired(&self, index: u32, semaphore: Semaphore) {
+        let mut acquired = self.acquired_images();
+        acquired.retain(|image| image.index != index);
+        acquired.push(AcquiredImage {
+            index,
+            semaphore: Some(semaphore),
+        });
+    }
+
+    pub(crate) fn untrack_acquired(&self, index: u32) {
+        self.acquired_images().retain(|image| image.index != index);
+    }
+
+    pub(crate) fn consume_acquire_semaphores(&self, wait_semaphores: &[Semaphore]) {
+        for image in self.acquired_images().iter_mut() {
+            if image
+                .semaphore
+                .is_some_and(|semaphore| wait_semaphores.contains(&semaphore))
+            {
+                image.semaphore = None;
+            }
+        }
+    }
+}
 
 impl VkInit {
     /// Utility function to recreate the swapchain, swapchain images and image views.
     ///
-    /// Function waits for device_wait_idle before destroying the swapchain.
+    /// Function waits for device_wait_idle before replacing the swapchain.
+    /// The new swapchain is created from the old one on the existing surface, the surface is only recreated if it was lost.
+    /// The extent is clamped to the surface capabilities and the depth image is recreated if it changed.
+    /// Returns [WindowMinimized](Error::WindowMinimized) for a zero extent without changing anything,
+    /// rendering can pause until the next resize.
     /// Images must be transitioned to the appropriate image layout after recreation.
-
+    /// The [surface rotation](crate::SurfaceInfo::rotation) is queried again, e.g. after the device was turned.
+    /// The synchronization objects of [begin_frame](VkInit::begin_frame) are recreated as well.
     pub fn on_resize<T: HasRawDisplayHandle + HasRawWindowHandle>(
         &mut self,
         window: &T,
         new_size: [u32; 2],
     ) -> Result<(), Error> {
-        unsafe {
-            trace!("Resizing swapchain");
+        trace!("Resizing swapchain");
+        self.recreate_swapchain_internal(
+            Some((window.raw_display_handle(), window.raw_window_handle())),
+            new_size,
+        )
+    }
 
-            let Some(head) = self.head.as_mut() else {
-                return Err(Error::HeadCallOnHeadlessInstance);
-            };
+    /// Switches the present mode, e.g. to toggle vsync, by recreating only the swapchain and its image views.
+    ///
+    /// The old swapchain is handed to the new one for a seamless transition, the surface and depth image are kept.
+    /// ```window_size``` has to match the size of the last [on_resize](VkInit::on_resize).
+    /// Returns [PresentModeNotSupported](Error::PresentModeNotSupported) without changing anything if the surface does not support ```mode```.
+    pub fn set_present_mode(
+        &mut self,
+        mode: PresentModeKHR,
+        window_size: [u32; 2],
+    ) -> Result<(), Error> {
+        let Some(head) = self.head.as_ref() else {
+            return Err(Error::HeadCallOnHeadlessInstance);
+        };
+        if !head.surface_info.supported_present_modes.contains(&mode) {
+            return Err(Error::PresentModeNotSupported);
+        }
+        trace!("Switching present mode to {mode:?}");
+        self.create_info.present_mode_preferences = vec![mode];
+        // Without window handles a lost surface is returned as an error.
+        self.recreate_swapchain_internal(None, window_size)
+    }
+
+    /// Recreates the swapchain for ```window_size``` and the current [VkInitCreateInfo](crate::VkInitCreateInfo),
+    /// used by [on_resize](VkInit::on_resize), [set_present_mode](VkInit::set_present_mode) and [change_present_mode](VkInit::change_present_mode).
+    ///
+    /// The surface is only recreated from ```window_handles``` if it was lost.
+    pub(crate) fn recreate_swapchain_internal(
+        &mut self,
+        window_handles: Option<(RawDisplayHandle, RawWindowHandle)>,
+        window_size: [u32; 2],
+    ) -> Result<(), Error> {
+        if self.head.is_none() {
+            return Err(Error::HeadCallOnHeadlessInstance);
+        }
+        self.wait_device_idle()?;
 
-            let display_h = window.raw_display_handle();
-            let window_h = window.raw_window_handle();
+        match (
+            self.recreate_swapchain_on_surface(window_size),
+            window_handles,
+        ) {
+            (
+                Err(Error::VkError(ash::vk::Result::ERROR_SURFACE_LOST_KHR)),
+                Some((display_h, window_h)),
+            ) => {
+                warn!("Surface lost while recreating the swapchain, recreating the surface");
+                self.recreate_surface(display_h, window_h, window_size)?;
+            }
+            (result, _) => result?,
+        }
 
-            self.device.device_wait_idle()?;
+        self.update_frame_scheme();
+        self.rebuild_frame_loop()?;
+        self.name_head_objects()
+    }
 
-            //destroy swapchain
+    /// Replaces the swapchain with one created from it on the same surface, the device must be idle.
+    fn recreate_swapchain_on_surface(&mut self, window_size: [u32; 2]) -> Result<(), Error> {
+        let head = self.head.as_mut().unwrap();
+        unsafe {
+            let surface_info = SurfaceInfo {
+                scale_factor: head.surface_info.scale_factor,
+                ..Self::query_surface_info(
+                    &head.surface_loader,
+                    head.surface,
+                    &self.physical_device,
+                    window_size,
+                    &self.create_info,
+                )?
+            };
+            let (swapchain_loader, swapchain) = Self::create_swapchain(
+                &self.instance,
+                &self.device,
+                &head.surface,
+                &surface_info,
+                head.swapchain,
+            )?;
+            let (swapchain_images, swapchain_image_views) = match Self::create_swapchain_images(
+                &self.device,
+                &swapchain_loader,
+                &swapchain,
+                &surface_info,
+            ) {
+                Ok(images) => images,
+                Err(e) => {
+                    swapchain_loader.destroy_swapchain(swapchain, None);
+                    return Err(e);
+                }
+            };
+
+            // Only destroyed once the new swapchain exists, the device is idle so nothing is in flight.
             for image_view in &head.swapchain_image_views {
                 self.device.destroy_image_view(*image_view, None);
             }
             head.swapchain_loader
                 .destroy_swapchain(head.swapchain, None);
 
-            //Destroy depth image
-            head.depth_image
-                .destroy(&self.device, &mut self.allocator)?;
+            head.swapchain_loader = swapchain_loader;
+            head.swapchain = swapchain;
+            head.swapchain_images = swapchain_images;
+            head.swapchain_image_views = swapchain_image_views;
+            head.acquired_images().clear();
+
+            if surface_info.current_extent != head.surface_info.current_extent
+                || surface_info.color_format != head.surface_info.color_format
+            {
+                head.destroy_attachments(&self.device, &mut self.allocator.lock())?;
+                (head.depth_image, head.msaa_image) = Self::create_attachments(
+                    &self.device,
+                    &mut self.allocator.lock(),
+                    surface_info.current_extent,
+                    surface_info.color_format.format,
+                    head.depth_format,
+                    head.depth_format_sizeof,
+                    head.msaa_samples,
+                )?;
+            }
+            head.surface_info = surface_info;
+        }
+        Ok(())
+    }
 
-            //destroy surface
+    /// Destroys the surface, swapchain and depth image and creates them again, the device must be idle.
+    fn recreate_surface(
+        &mut self,
+        display_h: RawDisplayHandle,
+        window_h: RawWindowHandle,
+        window_size: [u32; 2],
+    ) -> Result<(), Error> {
+        let head = self.head.as_mut().unwrap();
+        unsafe {
+            for image_view in &head.swapchain_image_views {
+                self.device.destroy_image_view(*image_view, None);
+            }
+            head.swapchain_loader
+                .destroy_swapchain(head.swapchain, None);
+            head.destroy_attachments(&self.device, &mut self.allocator.lock())?;
             head.surface_loader.destroy_surface(head.surface, None);
 
-            //recreate surface
             let (surface_loader, surface, surface_info) = Self::create_surface(
                 &self.entry,
                 &self.instance,
                 display_h,
                 window_h,
-                new_size,
+                window_size,
                 &self.physical_device,
                 &self.create_info,
             )?;
             head.surface_loader = surface_loader;
             head.surface = surface;
-            head.surface_info = surface_info;
+            head.surface_info = SurfaceInfo {
+                scale_factor: head.surface_info.scale_factor,
+                ..surface_info
+            };
 
-            //recreate swapchain
             let (swapchain_loader, swapchain) = Self::create_swapchain(
                 &self.instance,
                 &self.device,
                 &head.surface,
                 &head.surface_info,
-                new_size,
+                SwapchainKHR::null(),
             )?;
             let (swapchain_images, swapchain_image_views) = Self::create_swapchain_images(
                 &self.device,
@@ -67,31 +295,137 @@ impl VkInit {
                 &swapchain,
                 &head.surface_info,
             )?;
-
             head.swapchain_loader = swapchain_loader;
             head.swapchain = swapchain;
             head.swapchain_images = swapchain_images;
             head.swapchain_image_views = swapchain_image_views;
-            head.surface_info.current_extent = Extent2D {
-                width: new_size[0],
-                height: new_size[1],
-            };
+            head.acquired_images().clear();
 
-            //recreate depth image
-            let extent = Extent3D {
-                width: new_size[0],
-                height: new_size[1],
-                depth: 1,
-            };
-            head.depth_image = VMAImage::create_depth_image(
+            (head.depth_image, head.msaa_image) = Self::create_attachments(
                 &self.device,
-                &mut self.allocator,
-                extent,
+                &mut self.allocator.lock(),
+                head.surface_info.current_extent,
+                head.surface_info.color_format.format,
                 head.depth_format,
                 head.depth_format_sizeof,
+                head.msaa_samples,
             )?;
         }
-
         Ok(())
     }
+
+    /// Leaves the swapchain in a destroyable state by waiting for the device to idle and giving back all images
+    /// that were [acquired](VkInit::acquire_next_swapchain_image) but not [presented](VkInit::present).
+    ///
+    /// Pending acquire semaphores are waited on, unless a submit through [submit_cmd_buffers2](VkInit::submit_cmd_buffers2) or its shortcuts already did.
+    /// The images are released with [swapchain_maintenance1](crate::PhysicalDeviceInfo::swapchain_maintenance1) if enabled,
+    /// otherwise they are transitioned to ```PRESENT_SRC_KHR``` and presented as is.
+    ///
+    /// Called by [destroy](VkInit::destroy).
+    pub fn drain_presentation(&mut self) -> Result<(), Error> {
+        let Some(head) = self.head.as_ref() else {
+            return Err(Error::HeadCallOnHeadlessInstance);
+        };
+        self.wait_device_idle()?;
+        let acquired: Vec<AcquiredImage> = head.acquired_images().drain(..).collect();
+        if acquired.is_empty() {
+            return Ok(());
+        }
+        trace!("Draining {} acquired swapchain images", acquired.len());
+
+        let indices: Vec<u32> = acquired.iter().map(|image| image.index).collect();
+        let wait_infos: Vec<SemaphoreSubmitInfo> = acquired
+            .iter()
+            .filter_map(|image| image.semaphore)
+            .map(|semaphore| semaphore_submit_info(semaphore, PipelineStageFlags2::ALL_COMMANDS))
+            .collect();
+        let release = self.physical_device_info.swapchain_maintenance1;
+
+        let cmd_pool = self.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer =
+            self.create_command_buffers_named(&cmd_pool, 1, "VKU_Drain_Presentation")?[0];
+        let fence = self.create_fence_named("VKU_Drain_Presentation")?;
+        let present_semaphores = match release {
+            true => vec![],
+            false => self.create_semaphores_named(indices.len(), "VKU_Drain_Presentation")?,
+        };
+
+        // Fences are created signaled.
+        self.wait_on_fence_and_reset(Some(&fence), &[])?;
+        self.begin_cmd_buffer(&cmd_buffer)?;
+        if !release {
+            let to_present: Vec<ImageMemoryBarrier2> = indices
+                .iter()
+                .map(|index| {
+                    ImageMemoryBarrier2::builder()
+                        .image(head.swapchain_images[*index as usize])
+                        .old_layout(ImageLayout::UNDEFINED)
+                        .new_layout(ImageLayout::PRESENT_SRC_KHR)
+                        .src_stage_mask(PipelineStageFlags2::ALL_COMMANDS)
+                        .dst_stage_mask(PipelineStageFlags2::BOTTOM_OF_PIPE)
+                        .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+                        .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+                        .subresource_range(ImageSubresourceRange {
+                            aspect_mask: ImageAspectFlags::COLOR,
+                            base_mip_level: 0,
+                            level_count: 1,
+                            base_array_layer: 0,
+                            layer_count: 1,
+                        })
+                        .build()
+                })
+                .collect();
+            self.cmd_pipeline_barrier2(&cmd_buffer, &to_present, &[]);
+        }
+        let signal_infos: Vec<SemaphoreSubmitInfo> = present_semaphores
+            .iter()
+            .map(|semaphore| semaphore_submit_info(*semaphore, PipelineStageFlags2::ALL_COMMANDS))
+            .collect();
+        self.end_and_submit_cmd_buffer2(
+            &cmd_buffer,
+            CmdType::Any,
+            Some(&fence),
+            &wait_infos,
+            &signal_infos,
+        )?;
+        self.wait_on_fence_and_reset(Some(&fence), &[])?;
+
+        let head = self.head();
+        unsafe {
+            if release {
+                let maintenance = ExtSwapchainMaintenance1Fn::load(|name| {
+                    std::mem::transmute(
+                        self.instance
+                            .get_device_proc_addr(self.device.handle(), name.as_ptr()),
+                    )
+                });
+                let release_info = ReleaseSwapchainImagesInfoEXT::builder()
+                    .swapchain(head.swapchain)
+                    .image_indices(&indices);
+                (maintenance.release_swapchain_images_ext)(self.device.handle(), &*release_info)
+                    .result()?;
+            } else {
+                let swapchains = vec![head.swapchain; indices.len()];
+                let present_info = PresentInfoKHR::builder()
+                    .wait_semaphores(&present_semaphores)
+                    .swapchains(&swapchains)
+                    .image_indices(&indices);
+                // Out of date or suboptimal swapchains still release their images.
+                match head
+                    .swapchain_loader
+                    .queue_present(self.unified_queue, &present_info)
+                {
+                    Ok(_) | Err(ash::vk::Result::ERROR_OUT_OF_DATE_KHR) => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        self.wait_device_idle()?;
+        for semaphore in &present_semaphores {
+            self.destroy_semaphore(semaphore)?;
+        }
+        self.destroy_fence(&fence)?;
+        self.destroy_cmd_pool(&cmd_pool)
+    }
 }
@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use crate::{imports::*, CmdType, VkInit};
+
+impl VkInit {
+    /// Creates a timeline semaphore starting at ```initial_value```.
+    ///
+    /// Requires the ```timelineSemaphore``` feature of [physical_device_1_2_features](crate::VkInitCreateInfo::physical_device_1_2_features),
+    /// which is enabled by default.
+    pub fn create_timeline_semaphore(&self, initial_value: u64) -> Result<Semaphore, Error> {
+        let mut type_info = SemaphoreTypeCreateInfo::builder()
+            .semaphore_type(SemaphoreType::TIMELINE)
+            .initial_value(initial_value);
+        let create_info = SemaphoreCreateInfo::builder().push_next(&mut type_info);
+        let semaphore = unsafe { self.device.create_semaphore(&create_info, None)? };
+        self.set_debug_object_name_fmt(
+            semaphore.as_raw(),
+            ObjectType::SEMAPHORE,
+            format_args!("VKU_Timeline_Semaphore"),
+        )?;
+
+        Ok(semaphore)
+    }
+
+    /// Blocks until the timeline ```semaphore``` reached ```value```, at most ```timeout```. ```None``` waits indefinitely.
+    ///
+    /// Returns ```VkError(TIMEOUT)``` if the value was not reached in time.
+    pub fn wait_timeline_semaphore(
+        &self,
+        semaphore: &Semaphore,
+        value: u64,
+        timeout: Option<Duration>,
+    ) -> Result<(), Error> {
+        let timeout_ns = timeout.map_or(u64::MAX, |timeout| {
+            u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX)
+        });
+        let semaphores = [*semaphore];
+        let values = [value];
+        let wait_info = SemaphoreWaitInfo::builder()
+            .semaphores(&semaphores)
+            .values(&values);
+        unsafe { self.device.wait_semaphores(&wait_info, timeout_ns)? };
+
+        Ok(())
+    }
+
+    /// Sets the timeline ```semaphore``` to ```value``` from the host, which must be greater than its current value.
+    pub fn signal_timeline_semaphore(
+        &self,
+        semaphore: &Semaphore,
+        value: u64,
+    ) -> Result<(), Error> {
+        let signal_info = SemaphoreSignalInfo::builder()
+            .semaphore(*semaphore)
+            .value(value);
+        unsafe { self.device.signal_semaphore(&signal_info)? };
+
+        Ok(())
+    }
+
+    /// Current value of the timeline ```semaphore```.
+    pub fn timeline_semaphore_value(&self, semaphore: &Semaphore) -> Result<u64, Error> {
+        Ok(unsafe { self.device.get_semaphore_counter_value(*semaphore)? })
+    }
+
+    /// Ends ```cmd_buffer``` and submits it, see [submit_cmd_buffers2](VkInit::submit_cmd_buffers2).
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let cmd_pool = init.create_cmd_pool(CmdType::Any)?;
+    /// let cmd_buffer = init.create_command_buffers(&cmd_pool, 1)?[0];
+    /// let timeline = init.create_timeline_semaphore(0)?;
+    ///
+    /// init.begin_cmd_buffer(&cmd_buffer)?;
+    /// // record frame 1
+    /// let signal = SemaphoreSubmitInfo::builder()
+    ///     .semaphore(timeline)
+    ///     .value(1)
+    ///     .stage_mask(PipelineStageFlags2::ALL_COMMANDS)
+    ///     .build();
+    /// init.end_and_submit_cmd_buffer2(&cmd_buffer, CmdType::Any, None, &[], &[signal])?;
+    /// init.wait_timeline_semaphore(&timeline, 1, None)?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn end_and_submit_cmd_buffer2(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        cmd_type: CmdType,
+        fence: Option<&Fence>,
+        wait_infos: &[SemaphoreSubmitInfo],
+        signal_infos: &[SemaphoreSubmitInfo],
+    ) -> Result<(), Error> {
+        unsafe { self.device.end_command_buffer(*cmd_buffer)? };
+        self.submit_cmd_buffers2(&[*cmd_buffer], cmd_type, fence, wait_infos, signal_infos)
+    }
+}
@@ -0,0 +1,242 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+use crate::{imports::*, VkInit};
+
+impl VkInit {
+    /// Creates a pool of ```count``` timestamp queries, reset it with [reset_query_pool](VkInit::reset_query_pool) before the first use.
+    ///
+    /// Returns [TimestampsNotSupported](Error::TimestampsNotSupported) without ```timestamp_compute_and_graphics```.
+    pub fn create_timestamp_query_pool(&self, count: u32) -> Result<QueryPool, Error> {
+        if self
+            .physical_device_info
+            .limits
+            .timestamp_compute_and_graphics
+            == FALSE
+        {
+            return Err(Error::TimestampsNotSupported);
+        }
+        let create_info = QueryPoolCreateInfo::builder()
+            .query_type(QueryType::TIMESTAMP)
+            .query_count(count);
+        let pool = unsafe { self.device.create_query_pool(&create_info, None)? };
+        self.set_debug_object_name_fmt(
+            pool.as_raw(),
+            ObjectType::QUERY_POOL,
+            format_args!("VKU_Timestamp_Query_Pool_{count}"),
+        )?;
+        Ok(pool)
+    }
+
+    pub fn destroy_query_pool(&self, pool: QueryPool) {
+        unsafe { self.device.destroy_query_pool(pool, None) };
+    }
+
+    /// Resets the queries ```range``` of ```pool```, recorded outside of rendering.
+    pub fn reset_query_pool(&self, cmd_buffer: &CommandBuffer, pool: QueryPool, range: Range<u32>) {
+        unsafe {
+            self.device
+                .cmd_reset_query_pool(*cmd_buffer, pool, range.start, range.len() as u32)
+        };
+    }
+
+    /// Writes the timestamp of query ```index``` once all previous commands reached ```stage```.
+    pub fn cmd_write_timestamp(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        pool: QueryPool,
+        index: u32,
+        stage: PipelineStageFlags2,
+    ) {
+        unsafe {
+            self.device
+                .cmd_write_timestamp2(*cmd_buffer, stage, pool, index)
+        };
+    }
+
+    /// Timestamps of the queries ```range``` in milliseconds, converted with ```timestamp_period```.
+    ///
+    /// Only differences between timestamps are meaningful. Does not wait for the results,
+    /// returns [TimestampNotAvailable](Error::TimestampNotAvailable) for the first query that was not written yet.
+    pub fn get_timestamp_results(
+        &self,
+        pool: QueryPool,
+        range: Range<u32>,
+    ) -> Result<Vec<f64>, Error> {
+        let mut results = vec![[0_u64; 2]; range.len()];
+        if !results.is_empty() {
+            let query_result = unsafe {
+                self.device.get_query_pool_results(
+                    pool,
+                    range.start,
+                    range.len() as u32,
+                    &mut results,
+                    QueryResultFlags::TYPE_64 | QueryResultFlags::WITH_AVAILABILITY,
+                )
+            };
+            match query_result {
+                // Unavailable queries are reported through their availability value below.
+                Ok(()) | Err(ash::vk::Result::NOT_READY) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let valid_bits = self.physical_device_info.queue_family_properties
+            [self.physical_device_info.unified_queue_family_index as usize]
+            .timestamp_valid_bits;
+        let mask = match valid_bits {
+            64.. => u64::MAX,
+            bits => (1_u64 << bits) - 1,
+        };
+        let period = self.physical_device_info.limits.timestamp_period as f64;
+        results
+            .iter()
+            .zip(range)
+            .map(|([ticks, available], index)| match available {
+                0 => Err(Error::TimestampNotAvailable(index)),
+                _ => Ok((ticks & mask) as f64 * period / 1_000_000.0),
+            })
+            .collect()
+    }
+
+    /// Shortcut - see [GpuFrameProfiler](GpuFrameProfiler::new).
+    pub fn create_gpu_frame_profiler(&self, max_scopes: u32) -> Result<GpuFrameProfiler, Error> {
+        GpuFrameProfiler::new(self, max_scopes)
+    }
+}
+
+/// GPU time per named scope of a frame, measured with a pair of timestamps each.
+///
+/// Use one profiler per frame in flight and [collect](GpuFrameProfiler::collect) once the frame's fence has signaled.
+/// The [Display] implementation prints a report of the collected timings.
+/// ```
+/// # extern crate winit;
+/// # use vku::*;
+/// # use ash::vk::*;
+/// # if VkInit::load_entry().is_err() { return Ok(()); }
+/// # let init_info = VkInitCreateInfo::default();
+/// let init = VkInit::new_headless(init_info)?;
+/// let mut profiler = init.create_gpu_frame_profiler(16)?;
+///
+/// init.immediate_submit(CmdType::Any, |cmd_buffer| {
+///     profiler.reset(&init, cmd_buffer);
+///     profiler.begin_scope(&init, cmd_buffer, "Shadows").unwrap();
+///     // record the shadow pass
+///     profiler.end_scope(&init, cmd_buffer, "Shadows").unwrap();
+/// })?;
+/// profiler.collect(&init)?;
+/// println!("{profiler}");
+/// # profiler.destroy(&init);
+/// # Ok::<(), vku::Error>(())
+/// ```
+pub struct GpuFrameProfiler {
+    pool: QueryPool,
+    max_scopes: u32,
+    /// Scopes in the order they were begun, with whether they were ended.
+    scopes: Vec<(String, bool)>,
+    timings: Vec<(String, f64)>,
+}
+
+impl GpuFrameProfiler {
+    /// Creates the queries for up to ```max_scopes``` scopes per frame.
+    ///
+    /// Returns [TimestampsNotSupported](Error::TimestampsNotSupported) without ```timestamp_compute_and_graphics```.
+    pub fn new(vk_init: &VkInit, max_scopes: u32) -> Result<Self, Error> {
+        Ok(Self {
+            pool: vk_init.create_timestamp_query_pool(max_scopes * 2)?,
+            max_scopes,
+            scopes: vec![],
+            timings: vec![],
+        })
+    }
+
+    /// Starts a new frame, recorded before the first scope and outside of rendering.
+    pub fn reset(&mut self, vk_init: &VkInit, cmd_buffer: &CommandBuffer) {
+        vk_init.reset_query_pool(cmd_buffer, self.pool, 0..self.max_scopes * 2);
+        self.scopes.clear();
+    }
+
+    /// Returns [ProfilerScopesExceeded](Error::ProfilerScopesExceeded) if all scopes of this frame are used.
+    pub fn begin_scope(
+        &mut self,
+        vk_init: &VkInit,
+        cmd_buffer: &CommandBuffer,
+        name: &str,
+    ) -> Result<(), Error> {
+        if self.scopes.len() as u32 == self.max_scopes {
+            return Err(Error::ProfilerScopesExceeded(self.max_scopes));
+        }
+        let query = self.scopes.len() as u32 * 2;
+        vk_init.cmd_write_timestamp(
+            cmd_buffer,
+            self.pool,
+            query,
+            PipelineStageFlags2::ALL_COMMANDS,
+        );
+        self.scopes.push((name.to_string(), false));
+        Ok(())
+    }
+
+    /// Ends the last open scope called ```name```, returns [ProfilerScopeNotBegun](Error::ProfilerScopeNotBegun) if there is none.
+    pub fn end_scope(
+        &mut self,
+        vk_init: &VkInit,
+        cmd_buffer: &CommandBuffer,
+        name: &str,
+    ) -> Result<(), Error> {
+        let Some(index) = self
+            .scopes
+            .iter()
+            .rposition(|(scope, ended)| scope == name && !ended)
+        else {
+            return Err(Error::ProfilerScopeNotBegun(name.to_string()));
+        };
+        vk_init.cmd_write_timestamp(
+            cmd_buffer,
+            self.pool,
+            index as u32 * 2 + 1,
+            PipelineStageFlags2::ALL_COMMANDS,
+        );
+        self.scopes[index].1 = true;
+        Ok(())
+    }
+
+    /// Reads the timings of the ended scopes of the last frame, its commands must have completed.
+    pub fn collect(&mut self, vk_init: &VkInit) -> Result<&[(String, f64)], Error> {
+        self.timings.clear();
+        for (index, (name, ended)) in self.scopes.iter().enumerate() {
+            if !ended {
+                continue;
+            }
+            let query = index as u32 * 2;
+            let pair = vk_init.get_timestamp_results(self.pool, query..query + 2)?;
+            self.timings.push((name.clone(), pair[1] - pair[0]));
+        }
+        Ok(&self.timings)
+    }
+
+    /// Milliseconds per scope of the last [collect](GpuFrameProfiler::collect), in the order the scopes were begun.
+    pub fn timings(&self) -> &[(String, f64)] {
+        &self.timings
+    }
+
+    pub fn destroy(&self, vk_init: &VkInit) {
+        vk_init.destroy_query_pool(self.pool);
+    }
+}
+
+impl Display for GpuFrameProfiler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "GPU frame profile")?;
+        let width = self
+            .timings
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+        for (name, ms) in &self.timings {
+            writeln!(f, "{name:<width$} {ms:>8.3} ms")?;
+        }
+        Ok(())
+    }
+}
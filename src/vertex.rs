@@ -0,0 +1,141 @@
+use crate::{imports::*, pipeline_builder::VertexConvert};
+
+/// UI vertex with a packed color, e.g. for egui-style meshes. Color is read as ```R8G8B8A8_UNORM```.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct UIVertex {
+    pub pos: [f32; 2],
+    pub uv: [f32; 2],
+    pub col: [u8; 4],
+}
+
+/// Point sprite in 2D with its size in pixels, for ```PrimitiveTopology::POINT_LIST```.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PointVertex2D {
+    pub pos: [f32; 2],
+    pub size: f32,
+    pub col: [u8; 4],
+}
+
+/// Point sprite in 3D with its size in pixels, for ```PrimitiveTopology::POINT_LIST```.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PointVertex3D {
+    pub pos: [f32; 3],
+    pub size: f32,
+    pub col: [u8; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vertex2D {
+    pub pos: [f32; 2],
+    pub uv: [f32; 2],
+    pub col: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vertex3D {
+    pub pos: [f32; 3],
+    pub normal: [f32; 3],
+    pub uv: [f32; 2],
+    pub col: [f32; 4],
+}
+
+/// Binding 0 with per-vertex input rate.
+fn vertex_binding<V>() -> Vec<VertexInputBindingDescription> {
+    vec![VertexInputBindingDescription {
+        binding: 0,
+        stride: size_of::<V>() as u32,
+        input_rate: VertexInputRate::VERTEX,
+    }]
+}
+
+/// One attribute per ```(format, offset)``` of binding 0, at consecutive locations.
+fn vertex_attribs(attribs: &[(Format, u32)]) -> Vec<VertexInputAttributeDescription> {
+    attribs
+        .iter()
+        .enumerate()
+        .map(
+            |(location, (format, offset))| VertexInputAttributeDescription {
+                binding: 0,
+                location: location as u32,
+                format: *format,
+                offset: *offset,
+            },
+        )
+        .collect()
+}
+
+impl VertexConvert for UIVertex {
+    fn binding_desc() -> Vec<VertexInputBindingDescription> {
+        vertex_binding::<Self>()
+    }
+
+    fn attrib_desc() -> Vec<VertexInputAttributeDescription> {
+        vertex_attribs(&[
+            (Format::R32G32_SFLOAT, 0),
+            (Format::R32G32_SFLOAT, 8),
+            (Format::R8G8B8A8_UNORM, 16),
+        ])
+    }
+}
+
+impl VertexConvert for PointVertex2D {
+    fn binding_desc() -> Vec<VertexInputBindingDescription> {
+        vertex_binding::<Self>()
+    }
+
+    fn attrib_desc() -> Vec<VertexInputAttributeDescription> {
+        vertex_attribs(&[
+            (Format::R32G32_SFLOAT, 0),
+            (Format::R32_SFLOAT, 8),
+            (Format::R8G8B8A8_UNORM, 12),
+        ])
+    }
+}
+
+impl VertexConvert for PointVertex3D {
+    fn binding_desc() -> Vec<VertexInputBindingDescription> {
+        vertex_binding::<Self>()
+    }
+
+    fn attrib_desc() -> Vec<VertexInputAttributeDescription> {
+        vertex_attribs(&[
+            (Format::R32G32B32_SFLOAT, 0),
+            (Format::R32_SFLOAT, 12),
+            (Format::R8G8B8A8_UNORM, 16),
+        ])
+    }
+}
+
+impl VertexConvert for Vertex2D {
+    fn binding_desc() -> Vec<VertexInputBindingDescription> {
+        vertex_binding::<Self>()
+    }
+
+    fn attrib_desc() -> Vec<VertexInputAttributeDescription> {
+        vertex_attribs(&[
+            (Format::R32G32_SFLOAT, 0),
+            (Format::R32G32_SFLOAT, 8),
+            (Format::R32G32B32A32_SFLOAT, 16),
+        ])
+    }
+}
+
+impl VertexConvert for Vertex3D {
+    fn binding_desc() -> Vec<VertexInputBindingDescription> {
+        vertex_binding::<Self>()
+    }
+
+    fn attrib_desc() -> Vec<VertexInputAttributeDescription> {
+        vertex_attribs(&[
+            (Format::R32G32B32_SFLOAT, 0),
+            (Format::R32G32B32_SFLOAT, 12),
+            (Format::R32G32_SFLOAT, 24),
+            (Format::R32G32B32A32_SFLOAT, 32),
+        ])
+    }
+}
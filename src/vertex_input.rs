@@ -0,0 +1,63 @@
+use crate::{imports::*, PhysicalDeviceInfo, VkInit};
+
+impl VkInit {
+    /// Sets the vertex input of pipelines built with ```DynamicState::VERTEX_INPUT_EXT```.
+    ///
+    /// [VertexConvert](crate::pipeline_builder::VertexConvert) converts static vertex layouts with ```binding_desc2``` and ```attrib_desc2```.
+    /// Returns [VertexInputDynamicStateNotSupported](Error::VertexInputDynamicStateNotSupported)
+    /// if [vertex_input_dynamic_state](PhysicalDeviceInfo::vertex_input_dynamic_state) is not supported.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use vku::pipeline_builder::VertexConvert;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let cmd_pool = init.create_cmd_pool(CmdType::Any)?;
+    /// let cmd_buffer = init.create_command_buffers(&cmd_pool, 1)?[0];
+    ///
+    /// init.begin_cmd_buffer(&cmd_buffer)?;
+    /// // bind a pipeline built with DynamicState::VERTEX_INPUT_EXT
+    /// if init.physical_device_info.vertex_input_dynamic_state {
+    ///     init.cmd_set_vertex_input(&cmd_buffer, &<()>::binding_desc2(), &<()>::attrib_desc2())?;
+    /// }
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn cmd_set_vertex_input(
+        &self,
+        cmd_buffer: &CommandBuffer,
+        bindings: &[VertexInputBindingDescription2EXT],
+        attributes: &[VertexInputAttributeDescription2EXT],
+    ) -> Result<(), Error> {
+        let Some(vertex_input_fn) = &self.vertex_input_fn else {
+            return Err(Error::VertexInputDynamicStateNotSupported);
+        };
+        unsafe {
+            (vertex_input_fn.cmd_set_vertex_input_ext)(
+                *cmd_buffer,
+                bindings.len() as u32,
+                bindings.as_ptr(),
+                attributes.len() as u32,
+                attributes.as_ptr(),
+            );
+        }
+        Ok(())
+    }
+
+    pub(crate) unsafe fn load_vertex_input_fn(
+        instance: &Instance,
+        device: &Device,
+        physical_device_info: &PhysicalDeviceInfo,
+    ) -> Option<ExtVertexInputDynamicStateFn> {
+        if !physical_device_info.vertex_input_dynamic_state {
+            return None;
+        }
+        Some(ExtVertexInputDynamicStateFn::load(|name| {
+            std::mem::transmute(instance.get_device_proc_addr(device.handle(), name.as_ptr()))
+        }))
+    }
+}
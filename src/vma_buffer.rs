@@ -1,11 +1,74 @@
+use std::sync::Mutex;
+
 use gpu_allocator::vulkan::AllocationScheme;
 
-use crate::{imports::*, VkInit};
+use crate::{
+    allocation_lifetime::{self, generated_name},
+    image_layout_transitions::queue_family_indices,
+    imports::*,
+    provenance::Provenance,
+    AllocationLifetime, CmdType, VkInit,
+};
+
+/// Memory a [VMABuffer] or [VMAImage](crate::VMAImage) is bound to, e.g. to [alias](crate::VMAImage::create_aliased) it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllocationInfo {
+    pub memory: DeviceMemory,
+    /// Offset of the resource into ```memory```.
+    pub offset: u64,
+    pub size: u64,
+    /// Alignment required by the resource.
+    pub alignment: u64,
+    /// Memory types the resource supports.
+    pub memory_type_bits: u32,
+    /// Properties of the memory type the resource is bound to.
+    pub memory_properties: MemoryPropertyFlags,
+}
+
+impl AllocationInfo {
+    /// Range of ```size``` bytes at byte ```offset``` into the allocation to flush or invalidate,
+    /// widened to multiples of ```atom_size``` and clamped to the allocation. ```WHOLE_SIZE``` reaches the end of the allocation.
+    ///
+    /// The clamped end is valid as long as the allocation ends on a multiple of ```atom_size``` or with its memory,
+    /// which holds for host-visible [VMABuffer]s of any ```nonCoherentAtomSize```.
+    pub fn atom_aligned_range(&self, offset: u64, size: u64, atom_size: u64) -> MappedMemoryRange {
+        let atom_size = atom_size.max(1);
+        let allocation_end = self.offset + self.size;
+        let start = self.offset + offset.min(self.size);
+        let end = match size {
+            WHOLE_SIZE => allocation_end,
+            size => start.saturating_add(size).min(allocation_end),
+        };
+
+        let aligned_start = (start / atom_size * atom_size).max(self.offset);
+        let aligned_end = end.next_multiple_of(atom_size).min(allocation_end);
+        MappedMemoryRange::builder()
+            .memory(self.memory)
+            .offset(aligned_start)
+            .size(aligned_end - aligned_start)
+            .build()
+    }
+}
 
 /// Allocated buffer, allocation and allocation information.
 pub struct VMABuffer {
     pub buffer: Buffer,
     pub allocation: Allocation,
+    pub usage: BufferUsageFlags,
+    /// Requested size in bytes, the allocation may be larger.
+    size: u64,
+    location: MemoryLocation,
+    requirements: MemoryRequirements,
+    lifetime: AllocationLifetime,
+    /// Allocation name the buffer was created with.
+    memory_name: String,
+    /// Re-applied when the buffer is [resized](VMABuffer::resize).
+    debug_name: Mutex<Option<String>>,
+    memory: DeviceMemory,
+    /// Granularity of [flush](VMABuffer::flush) and [invalidate](VMABuffer::invalidate), the device's ```nonCoherentAtomSize```
+    /// for buffers created through [VkInit], otherwise the largest one the spec allows.
+    atom_size: u64,
+    provenance: Provenance,
 }
 
 impl VMABuffer {
@@ -13,39 +76,173 @@ impl VMABuffer {
         device: &Device,
         allocator: &mut Allocator,
         buffer_info: BufferCreateInfo,
-        mut allocation_create_info: AllocationCreateDesc,
+        location: MemoryLocation,
+        name: &str,
+        lifetime: AllocationLifetime,
     ) -> Result<Self, Error> {
-        let (buffer, allocation) = unsafe {
+        let (buffer, allocation, requirements) = unsafe {
             let buffer = device.create_buffer(&buffer_info, None)?;
             let req = device.get_buffer_memory_requirements(buffer);
-            allocation_create_info.requirements = req;
-            let alloc = allocator.allocate(&allocation_create_info)?;
+            let allocation_scheme =
+                lifetime.allocation_scheme(req.size, AllocationScheme::DedicatedBuffer(buffer));
+            let allocation_create_info = AllocationCreateDesc {
+                name: &lifetime.allocation_name(name),
+                requirements: atom_aligned_requirements(req, location, allocation_scheme),
+                location,
+                linear: true,
+                allocation_scheme,
+            };
+            let alloc = match allocator.allocate(&allocation_create_info) {
+                Ok(alloc) => alloc,
+                Err(e) => {
+                    device.destroy_buffer(buffer, None);
+                    return Err(e.into());
+                }
+            };
             device.bind_buffer_memory(buffer, alloc.memory(), alloc.offset())?;
-            (buffer, alloc)
+            (buffer, alloc, req)
         };
+        allocation_lifetime::track(device, &allocation, lifetime);
+
+        Ok(Self {
+            memory: unsafe { allocation.memory() },
+            buffer,
+            allocation,
+            usage: buffer_info.usage,
+            size: buffer_info.size,
+            location,
+            requirements,
+            lifetime,
+            memory_name: name.to_string(),
+            debug_name: Mutex::new(None),
+            atom_size: MAX_NON_COHERENT_ATOM_SIZE,
+            provenance: Provenance::new(device, "VMABuffer"),
+        })
+    }
+
+    pub fn lifetime(&self) -> AllocationLifetime {
+        self.lifetime
+    }
+
+    /// Requested size in bytes, the allocation may be larger.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn location(&self) -> MemoryLocation {
+        self.location
+    }
+
+    /// Host writes and device writes are visible without [flush](VMABuffer::flush) and [invalidate](VMABuffer::invalidate).
+    pub fn is_coherent(&self) -> bool {
+        self.allocation
+            .memory_properties()
+            .contains(MemoryPropertyFlags::HOST_COHERENT)
+    }
+
+    /// Makes host writes to ```size``` bytes at byte ```offset``` visible to the device, ```WHOLE_SIZE``` flushes the whole allocation.
+    ///
+    /// The range is widened to ```nonCoherentAtomSize```. No-op for coherent or unmapped memory.
+    /// [set_data](VMABuffer::set_data) and the other writes flush automatically.
+    pub fn flush(&self, device: &Device, offset: u64, size: u64) -> Result<(), Error> {
+        self.check(device, "flush");
+        if self.is_coherent() || self.allocation.mapped_ptr().is_none() {
+            return Ok(());
+        }
+        let range = self
+            .allocation_info()
+            .atom_aligned_range(offset, size, self.atom_size);
+        unsafe { device.flush_mapped_memory_ranges(&[range])? };
+        Ok(())
+    }
+
+    /// Makes device writes to ```size``` bytes at byte ```offset``` visible to the host, ```WHOLE_SIZE``` invalidates the whole allocation.
+    ///
+    /// The range is widened to ```nonCoherentAtomSize```. No-op for coherent or unmapped memory.
+    /// [get_data](VMABuffer::get_data) and [read_into](VMABuffer::read_into) invalidate automatically.
+    pub fn invalidate(&self, device: &Device, offset: u64, size: u64) -> Result<(), Error> {
+        self.check(device, "invalidate");
+        if self.is_coherent() || self.allocation.mapped_ptr().is_none() {
+            return Ok(());
+        }
+        let range = self
+            .allocation_info()
+            .atom_aligned_range(offset, size, self.atom_size);
+        unsafe { device.invalidate_mapped_memory_ranges(&[range])? };
+        Ok(())
+    }
+
+    /// Flushes ```bytes``` written at byte ```start``` if the memory is not coherent.
+    fn flush_written(&self, device: &Device, start: u64, bytes: u64) -> Result<(), Error> {
+        match bytes {
+            0 => Ok(()),
+            bytes => self.flush(device, start, bytes),
+        }
+    }
+
+    /// Invalidates ```bytes``` about to be read at byte ```start``` if the memory is not coherent.
+    pub(crate) fn invalidate_read(
+        &self,
+        device: &Device,
+        start: u64,
+        bytes: u64,
+    ) -> Result<(), Error> {
+        match bytes {
+            0 => Ok(()),
+            bytes => self.invalidate(device, start, bytes),
+        }
+    }
 
-        Ok(Self { buffer, allocation })
+    pub fn allocation_info(&self) -> AllocationInfo {
+        self.check_alive("allocation_info");
+        AllocationInfo {
+            memory: self.memory,
+            offset: self.allocation.offset(),
+            size: self.allocation.size(),
+            alignment: self.requirements.alignment,
+            memory_type_bits: self.requirements.memory_type_bits,
+            memory_properties: self.allocation.memory_properties(),
+        }
     }
 
+    /// Destroying an already destroyed buffer is a no-op.
     pub fn destroy(&mut self, device: &Device, allocator: &mut Allocator) -> Result<(), Error> {
+        self.provenance
+            .check_instance(device, "VMABuffer", "destroy");
+        if !self.allocation.is_null() {
+            let info = self.allocation_info();
+            self.provenance.check_not_aliased(
+                info.memory,
+                info.offset..info.offset + info.size,
+                "VMABuffer",
+            );
+        }
+        self.provenance.release();
         unsafe {
             device.destroy_buffer(self.buffer, None);
+            self.buffer = Buffer::null();
             let alloc = std::mem::take(&mut self.allocation);
+            if !alloc.is_null() {
+                allocation_lifetime::untrack(device, &alloc);
+            }
             allocator.free(alloc)?;
         }
         Ok(())
     }
 
     pub fn set_debug_object_name(&self, vk_init: &VkInit, base_name: String) -> Result<(), Error> {
-        vk_init.set_debug_object_name(
+        self.check(&vk_init.device, "set_debug_object_name");
+        self.provenance.set_name(&base_name);
+        *self.debug_name.lock().unwrap_or_else(|e| e.into_inner()) = Some(base_name.clone());
+        vk_init.set_debug_object_name_fmt(
             self.buffer.as_raw(),
             ObjectType::BUFFER,
-            format!("{base_name}_Buffer"),
+            format_args!("{base_name}_Buffer"),
         )?;
-        vk_init.set_debug_object_name(
+        vk_init.set_debug_object_name_fmt(
             unsafe { self.allocation.memory().as_raw() },
             ObjectType::DEVICE_MEMORY,
-            format!("{base_name}_Memory"),
+            format_args!("{base_name}_Memory"),
         )?;
         Ok(())
     }
@@ -57,15 +254,16 @@ impl VMABuffer {
     /// # extern crate winit;
     /// # use vku::*;
     /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
     /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
     /// # let size = [800_u32, 600_u32];
     /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
     /// # let create_info = VkInitCreateInfo::default();
-    /// let mut init = VkInit::new(Some(&window), Some(size), create_info)?;
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
     /// let size = 1024_usize;
     /// let usage = BufferUsageFlags::STORAGE_BUFFER;
     ///
-    /// let buffer = VMABuffer::create_local_buffer(&init.device, &mut init.allocator, size, usage)?;
+    /// let buffer = VMABuffer::create_local_buffer(&init.device, &mut init.allocator.lock(), size, usage)?;
     /// let buffer_shortcut = init.create_local_buffer(size, usage)?;
     /// # Ok::<(), vku::Error>(())
 
@@ -75,21 +273,37 @@ impl VMABuffer {
         size: usize,
         usage: BufferUsageFlags,
     ) -> Result<VMABuffer, Error> {
-        let buffer_info = BufferCreateInfo::builder()
-            .size(size as u64)
-            .sharing_mode(SharingMode::EXCLUSIVE)
-            .usage(usage)
-            .build();
-
-        let allocation_info = AllocationCreateDesc {
-            name: "Local_Buffer_Memory",
-            requirements: MemoryRequirements::default(),
-            location: MemoryLocation::GpuOnly,
-            linear: true,
-            allocation_scheme: AllocationScheme::GpuAllocatorManaged,
-        };
+        Self::create_sized(
+            device,
+            allocator,
+            size,
+            usage,
+            MemoryLocation::GpuOnly,
+            &generated_name("Local_Buffer"),
+            AllocationLifetime::Persistent,
+        )
+    }
 
-        Self::new(device, allocator, buffer_info, allocation_info)
+    /// Same as [create_local_buffer](VMABuffer::create_local_buffer), with ```name``` as allocation name.
+    ///
+    /// The name is also used by [resize_buffer](VkInit::resize_buffer), the [VkInit] shortcut sets the debug names as well.
+    pub fn create_local_buffer_named(
+        device: &Device,
+        allocator: &mut Allocator,
+        size: usize,
+        usage: BufferUsageFlags,
+        name: &str,
+    ) -> Result<VMABuffer, Error> {
+        Self::create_sized(
+            device,
+            allocator,
+            size,
+            usage,
+            MemoryLocation::GpuOnly,
+            name,
+            AllocationLifetime::Persistent,
+        )
+        .map(|buffer| buffer.with_name(name))
     }
 
     /// Creates, allocates and maps a buffer of the requested size.
@@ -100,15 +314,16 @@ impl VMABuffer {
     /// # extern crate winit;
     /// # use vku::*;
     /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
     /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
     /// # let size = [800_u32, 600_u32];
     /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
     /// # let create_info = VkInitCreateInfo::default();
-    /// let mut init = VkInit::new(Some(&window), Some(size), create_info)?;
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
     /// let size = 1024_usize;
     /// let usage = BufferUsageFlags::STORAGE_BUFFER;
     ///
-    /// let buffer = VMABuffer::create_cpu_to_gpu_buffer(&init.device, &mut init.allocator, size, usage)?;
+    /// let buffer = VMABuffer::create_cpu_to_gpu_buffer(&init.device, &mut init.allocator.lock(), size, usage)?;
     /// let buffer_shortcut = init.create_cpu_to_gpu_buffer(size, usage)?;
     /// # Ok::<(), vku::Error>(())
 
@@ -118,21 +333,35 @@ impl VMABuffer {
         size: usize,
         usage: BufferUsageFlags,
     ) -> Result<VMABuffer, Error> {
-        let buffer_info = BufferCreateInfo::builder()
-            .size(size as u64)
-            .sharing_mode(SharingMode::EXCLUSIVE)
-            .usage(usage)
-            .build();
-
-        let allocation_info = AllocationCreateDesc {
-            name: "Upload_Buffer_Memory",
-            requirements: MemoryRequirements::default(),
-            location: MemoryLocation::CpuToGpu,
-            linear: true,
-            allocation_scheme: AllocationScheme::GpuAllocatorManaged,
-        };
+        Self::create_sized(
+            device,
+            allocator,
+            size,
+            usage,
+            MemoryLocation::CpuToGpu,
+            &generated_name("Upload_Buffer"),
+            AllocationLifetime::Persistent,
+        )
+    }
 
-        Self::new(device, allocator, buffer_info, allocation_info)
+    /// Same as [create_cpu_to_gpu_buffer](VMABuffer::create_cpu_to_gpu_buffer), with ```name``` as allocation name.
+    pub fn create_cpu_to_gpu_buffer_named(
+        device: &Device,
+        allocator: &mut Allocator,
+        size: usize,
+        usage: BufferUsageFlags,
+        name: &str,
+    ) -> Result<VMABuffer, Error> {
+        Self::create_sized(
+            device,
+            allocator,
+            size,
+            usage,
+            MemoryLocation::CpuToGpu,
+            name,
+            AllocationLifetime::Persistent,
+        )
+        .map(|buffer| buffer.with_name(name))
     }
 
     pub fn create_readback_buffer(
@@ -140,6 +369,96 @@ impl VMABuffer {
         allocator: &mut Allocator,
         size: usize,
         usage: BufferUsageFlags,
+    ) -> Result<VMABuffer, Error> {
+        Self::create_sized(
+            device,
+            allocator,
+            size,
+            usage,
+            MemoryLocation::GpuToCpu,
+            &generated_name("Readback_Buffer"),
+            AllocationLifetime::Transient,
+        )
+    }
+
+    /// Same as [create_readback_buffer](VMABuffer::create_readback_buffer), with ```name``` as allocation name.
+    pub fn create_readback_buffer_named(
+        device: &Device,
+        allocator: &mut Allocator,
+        size: usize,
+        usage: BufferUsageFlags,
+        name: &str,
+    ) -> Result<VMABuffer, Error> {
+        Self::create_sized(
+            device,
+            allocator,
+            size,
+            usage,
+            MemoryLocation::GpuToCpu,
+            name,
+            AllocationLifetime::Transient,
+        )
+        .map(|buffer| buffer.with_name(name))
+    }
+
+    /// Creates a local buffer fitting ```max_draws``` ```DrawIndexedIndirectCommand```s or as many ```DispatchIndirectCommand```s.
+    ///
+    /// Besides ```INDIRECT_BUFFER``` it has ```STORAGE_BUFFER``` and ```TRANSFER_DST``` usage to be filled by compute shaders or uploads.
+    pub fn create_indirect_buffer(
+        device: &Device,
+        allocator: &mut Allocator,
+        max_draws: usize,
+    ) -> Result<VMABuffer, Error> {
+        Self::create_sized(
+            device,
+            allocator,
+            max_draws * size_of::<DrawIndexedIndirectCommand>(),
+            BufferUsageFlags::INDIRECT_BUFFER
+                | BufferUsageFlags::STORAGE_BUFFER
+                | BufferUsageFlags::TRANSFER_DST,
+            MemoryLocation::GpuOnly,
+            &generated_name("Indirect_Buffer"),
+            AllocationLifetime::Persistent,
+        )
+    }
+
+    /// Creates a buffer in ```location``` with the allocation category ```lifetime```.
+    ///
+    /// Host-visible locations are mapped. The other constructors allocate [Persistent](AllocationLifetime::Persistent) memory,
+    /// except [create_readback_buffer](VMABuffer::create_readback_buffer), which is [Transient](AllocationLifetime::Transient).
+    pub fn create_buffer_with_lifetime(
+        device: &Device,
+        allocator: &mut Allocator,
+        size: usize,
+        usage: BufferUsageFlags,
+        location: MemoryLocation,
+        lifetime: AllocationLifetime,
+    ) -> Result<VMABuffer, Error> {
+        let name = match location {
+            MemoryLocation::GpuOnly => "Local_Buffer",
+            MemoryLocation::CpuToGpu => "Upload_Buffer",
+            MemoryLocation::GpuToCpu => "Readback_Buffer",
+            MemoryLocation::Unknown => "Buffer",
+        };
+        Self::create_sized(
+            device,
+            allocator,
+            size,
+            usage,
+            location,
+            &generated_name(name),
+            lifetime,
+        )
+    }
+
+    fn create_sized(
+        device: &Device,
+        allocator: &mut Allocator,
+        size: usize,
+        usage: BufferUsageFlags,
+        location: MemoryLocation,
+        name: &str,
+        lifetime: AllocationLifetime,
     ) -> Result<VMABuffer, Error> {
         let buffer_info = BufferCreateInfo::builder()
             .size(size as u64)
@@ -147,52 +466,101 @@ impl VMABuffer {
             .usage(usage)
             .build();
 
-        let allocation_info = AllocationCreateDesc {
-            name: "Readback_Buffer_Memory",
-            requirements: MemoryRequirements::default(),
-            location: MemoryLocation::GpuToCpu,
-            linear: true,
-            allocation_scheme: AllocationScheme::GpuAllocatorManaged,
-        };
+        Self::new(device, allocator, buffer_info, location, name, lifetime)
+    }
 
-        Self::new(device, allocator, buffer_info, allocation_info)
+    /// Flushes and invalidates in whole ```nonCoherentAtomSize``` atoms of the device instead of [MAX_NON_COHERENT_ATOM_SIZE].
+    pub(crate) fn with_atom_size(mut self, limits: &PhysicalDeviceLimits) -> Self {
+        self.atom_size = limits.non_coherent_atom_size;
+        self
+    }
+
+    /// Remembers ```name``` for resource reports and [resize_buffer](VkInit::resize_buffer) without a [VkInit] to set debug names.
+    fn with_name(self, name: &str) -> Self {
+        self.provenance.set_name(name);
+        *self.debug_name.lock().unwrap_or_else(|e| e.into_inner()) = Some(name.to_string());
+        self
     }
 
     /// Sets data on a mapped buffer.
     ///
     /// Buffer needs to be created in host-visible memory and mapped.
     /// Use [create_cpu_to_gpu_buffer](VMABuffer::create_cpu_to_gpu_buffer) to allocate a compatible buffer.
+    /// ```offset``` counts elements of ```T```.
+    /// Returns [BufferWriteOutOfBounds](Error::BufferWriteOutOfBounds) if the data would exceed the buffer.
     /// ```
     /// # extern crate winit;
     /// # use vku::*;
     /// # use ash::vk::*;
     /// # use std::mem::size_of;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
     /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
     /// # let size = [800_u32, 600_u32];
     /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
     /// # let create_info = VkInitCreateInfo::default();
-    /// let mut init = VkInit::new(Some(&window), Some(size), create_info)?;
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
     /// let size = 1024 * size_of::<usize>();
     /// let usage = BufferUsageFlags::STORAGE_BUFFER;
     /// let buffer = init.create_cpu_to_gpu_buffer(size, usage)?;
     ///
     /// let data = [42_usize; 1024];
     /// let offset = 0;
-    /// buffer.set_data(offset, &data)?;
+    /// buffer.set_data(&init.device, offset, &data)?;
     /// # Ok::<(), vku::Error>(())
     /// ```
 
-    pub fn set_data<T>(&self, offset: usize, data: &[T]) -> Result<(), Error> {
+    pub fn set_data<T>(&self, device: &Device, offset: usize, data: &[T]) -> Result<(), Error> {
+        self.check_alive("set_data");
         let Some(ptr) = self.allocation.mapped_ptr() else {
             return Err(Error::WriteAttemptToUnmappedBuffer);
         };
+        let start = (offset as u64).saturating_mul(size_of::<T>() as u64);
+        let bytes = std::mem::size_of_val(data) as u64;
+        self.check_write_bounds(start, bytes)?;
 
         let mut ptr = ptr.as_ptr() as *mut T;
         unsafe {
             ptr = ptr.add(offset);
             ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
         };
-        Ok(())
+        self.flush_written(device, start, bytes)
+    }
+
+    /// Same as [set_data](VMABuffer::set_data), but ```byte_offset``` counts bytes, e.g. for members of mixed layouts.
+    ///
+    /// Returns [BufferWriteMisaligned](Error::BufferWriteMisaligned) if the destination is not aligned for ```T```
+    /// and [BufferWriteOutOfBounds](Error::BufferWriteOutOfBounds) if the data would exceed the buffer.
+    pub fn set_data_at_byte_offset<T>(
+        &self,
+        device: &Device,
+        byte_offset: usize,
+        data: &[T],
+    ) -> Result<(), Error> {
+        self.check_alive("set_data_at_byte_offset");
+        let Some(ptr) = self.allocation.mapped_ptr() else {
+            return Err(Error::WriteAttemptToUnmappedBuffer);
+        };
+        let alignment = std::mem::align_of::<T>();
+        if !(ptr.as_ptr() as usize)
+            .wrapping_add(byte_offset)
+            .is_multiple_of(alignment)
+        {
+            return Err(Error::BufferWriteMisaligned {
+                offset: byte_offset as u64,
+                alignment: alignment as u64,
+            });
+        }
+        self.check_write_bounds(byte_offset as u64, std::mem::size_of_val(data) as u64)?;
+
+        unsafe {
+            let ptr = (ptr.as_ptr() as *mut u8).add(byte_offset) as *mut T;
+            ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
+        };
+        self.flush_written(
+            device,
+            byte_offset as u64,
+            std::mem::size_of_val(data) as u64,
+        )
     }
 
     /// Sets data on a mapped buffer.
@@ -200,6 +568,12 @@ impl VMABuffer {
     /// Buffer needs to be created in host-visible memory and mapped.
     /// Use [create_cpu_to_gpu_buffer](VMABuffer::create_cpu_to_gpu_buffer) to allocate a compatible buffer.
     ///
+    /// ```data``` starts at the size of ```start_data``` rounded up to the alignment of ```T```.
+    /// GLSL aligns arrays of ```vec3```/```vec4``` to 16 bytes, use an element type with ```#[repr(C, align(16))]```
+    /// or [set_data_at_byte_offset](VMABuffer::set_data_at_byte_offset) with the offset of the shader.
+    ///
+    /// Returns [BufferWriteOutOfBounds](Error::BufferWriteOutOfBounds) if both slices together would exceed the buffer.
+    ///
     /// # Valid usage:
     /// - Validate input data type to avoid misalignment on GLSL side: usize vs uint
     ///
@@ -219,37 +593,137 @@ impl VMABuffer {
     /// # use vku::*;
     /// # use ash::vk::*;
     /// # use std::mem::size_of;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
     /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
     /// # let size = [800_u32, 600_u32];
     /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
     /// # let create_info = VkInitCreateInfo::default();
-    /// let mut init = VkInit::new(Some(&window), Some(size), create_info)?;
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
     /// let size = 2 * size_of::<u32>() + 1024 * size_of::<f32>();
     /// let usage = BufferUsageFlags::STORAGE_BUFFER;
     /// let buffer = init.create_cpu_to_gpu_buffer(size, usage)?;
     ///
     /// let start_data = [4_u32, 2_u32];
     /// let data = [42.0; 1024];
-    /// buffer.set_data_with_start_data(&start_data, &data)?;
+    /// buffer.set_data_with_start_data(&init.device, &start_data, &data)?;
     /// # Ok::<(), vku::Error>(())
     /// ```
 
     pub fn set_data_with_start_data<T, U>(
         &self,
+        device: &Device,
         start_data: &[U],
         data: &[T],
     ) -> Result<(), Error> {
-        let Some(ptr) = self.allocation.mapped_ptr() else {
-            return Err(Error::WriteAttemptToUnmappedBuffer);
-        };
+        self.check_alive("set_data_with_start_data");
+        let data_offset =
+            std::mem::size_of_val(start_data).next_multiple_of(std::mem::align_of::<T>());
+        self.check_write_bounds(0, (data_offset + std::mem::size_of_val(data)) as u64)?;
 
-        let ptr = ptr.as_ptr() as *mut U;
+        self.set_data_at_byte_offset(device, 0, start_data)?;
+        self.set_data_at_byte_offset(device, data_offset, data)
+    }
+
+    /// Reads ```count``` elements of ```T``` starting at element ```offset``` from a mapped buffer.
+    ///
+    /// Buffer needs to be created in host-visible memory and mapped, e.g. with [create_readback_buffer](VMABuffer::create_readback_buffer).
+    /// Writes by the device must be complete and made available to the host, e.g. by waiting on the fence of the copy.
+    /// Returns [BufferReadOutOfBounds](Error::BufferReadOutOfBounds) if the range exceeds the buffer.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # use std::mem::size_of;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
+    /// # let size = [800_u32, 600_u32];
+    /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
+    /// # let create_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
+    /// let cmd_pool = init.create_cmd_pool(CmdType::Any)?;
+    /// let cmd_buffer = init.create_command_buffers(&cmd_pool, 1)?[0];
+    /// let fence = init.create_fence()?;
+    ///
+    /// let size = 1024 * size_of::<u32>();
+    /// let src_buffer = init.create_cpu_to_gpu_buffer(size, BufferUsageFlags::TRANSFER_SRC)?;
+    /// let readback_buffer = init.create_readback_buffer(size, BufferUsageFlags::TRANSFER_DST)?;
+    /// let data: Vec<u32> = (0..1024).collect();
+    /// src_buffer.set_data(&init.device, 0, &data)?;
+    ///
+    /// // Fences are created signaled.
+    /// init.wait_on_fence_and_reset(Some(&fence), &[])?;
+    /// init.begin_cmd_buffer(&cmd_buffer)?;
+    /// src_buffer.enqueue_copy_to_buffer(&init.device, &cmd_buffer, &readback_buffer, None, None, None)?;
+    /// init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &fence, &[], &[], &[])?;
+    /// init.wait_on_fence_and_reset(Some(&fence), &[])?;
+    ///
+    /// assert_eq!(readback_buffer.get_data::<u32>(&init.device, 0, 1024)?, data);
+    /// let mut tail = [0_u32; 4];
+    /// readback_buffer.read_into(&init.device, 1020, &mut tail)?;
+    /// assert_eq!(tail, [1020, 1021, 1022, 1023]);
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn get_data<T: Copy>(
+        &self,
+        device: &Device,
+        offset: usize,
+        count: usize,
+    ) -> Result<Vec<T>, Error> {
+        let ptr = self.mapped_read_ptr::<T>(device, "get_data", offset, count)?;
+        let mut data = Vec::with_capacity(count);
         unsafe {
-            ptr.copy_from_nonoverlapping(start_data.as_ptr(), start_data.len());
+            ptr.copy_to_nonoverlapping(data.as_mut_ptr(), count);
+            data.set_len(count);
+        }
+        Ok(data)
+    }
 
-            let offset_ptr = ptr.add(start_data.len()) as *mut T;
-            offset_ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
+    /// Same as [get_data](VMABuffer::get_data), but reads ```out.len()``` elements into ```out``` without allocating.
+    pub fn read_into<T: Copy>(
+        &self,
+        device: &Device,
+        offset: usize,
+        out: &mut [T],
+    ) -> Result<(), Error> {
+        let ptr = self.mapped_read_ptr::<T>(device, "read_into", offset, out.len())?;
+        unsafe { ptr.copy_to_nonoverlapping(out.as_mut_ptr(), out.len()) };
+        Ok(())
+    }
+
+    /// Mapped pointer to element ```offset``` after checking that ```count``` elements fit into the buffer.
+    fn mapped_read_ptr<T>(
+        &self,
+        device: &Device,
+        operation: &str,
+        offset: usize,
+        count: usize,
+    ) -> Result<*const T, Error> {
+        self.check_alive(operation);
+        let Some(ptr) = self.allocation.mapped_ptr() else {
+            return Err(Error::ReadAttemptFromUnmappedBuffer);
         };
+        let start = (offset as u64).saturating_mul(size_of::<T>() as u64);
+        let requested = (count as u64).saturating_mul(size_of::<T>() as u64);
+        let available = self.size.saturating_sub(start);
+        if requested > available {
+            return Err(Error::BufferReadOutOfBounds {
+                requested,
+                available,
+            });
+        }
+        self.invalidate_read(device, start, requested)?;
+        Ok(unsafe { (ptr.as_ptr() as *const T).add(offset) })
+    }
+
+    /// Returns [BufferWriteOutOfBounds](Error::BufferWriteOutOfBounds) if ```bytes``` starting at byte ```start``` exceed the buffer.
+    fn check_write_bounds(&self, start: u64, bytes: u64) -> Result<(), Error> {
+        let available = self.size.saturating_sub(start);
+        if bytes > available {
+            return Err(Error::BufferWriteOutOfBounds {
+                requested: bytes,
+                available,
+            });
+        }
         Ok(())
     }
 
@@ -261,18 +735,19 @@ impl VMABuffer {
     ///  **Defaults**:
     /// - src_offset: 0.
     /// - dst_offset: 0.     
-    /// - size: full size of this buffer.
+    /// - size: requested size of this buffer after ```src_offset```.
     ///
     ///```
     /// # extern crate winit;
     /// # use vku::*;
     /// # use ash::vk::*;
     /// # use std::mem::size_of;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
     /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
     /// # let size = [800_u32, 600_u32];
     /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
     /// # let create_info = VkInitCreateInfo::default();
-    /// let mut init = VkInit::new(Some(&window), Some(size), create_info)?;
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
     /// # let cmd_buffer_pool =
     /// #    init.create_cmd_pool(CmdType::Any)?;
     /// # let cmd_buffer =
@@ -286,7 +761,7 @@ impl VMABuffer {
     ///
     /// let data = [42_u32; 1024];
     /// let offset = 0;
-    /// src_buffer.set_data(offset, &data)?;
+    /// src_buffer.set_data(&init.device, offset, &data)?;
     ///
     /// src_buffer.enqueue_copy_to_buffer(
     ///     &init.device,
@@ -308,9 +783,11 @@ impl VMABuffer {
         dst_offset: Option<u64>,
         size: Option<u64>,
     ) -> Result<(), Error> {
+        self.check(device, "enqueue_copy_to_buffer");
+        dst_buffer.check(device, "enqueue_copy_to_buffer");
         let src_offset = src_offset.unwrap_or(0);
         let dst_offset = dst_offset.unwrap_or(0);
-        let size = size.unwrap_or(self.allocation.size() - src_offset);
+        let size = size.unwrap_or(self.size.saturating_sub(src_offset));
 
         let buffer_copy_region = BufferCopy::builder()
             .src_offset(src_offset)
@@ -333,19 +810,19 @@ impl VMABuffer {
     /// Returns a barrier2 for this buffer.
     ///
     ///  **Defaults:**
-    /// - src_queue: 0.
-    /// - dst_queue: 0.     
-    /// - size: full size of this buffer.
+    /// - src_queue and dst_queue: ```QUEUE_FAMILY_IGNORED```, unless both are provided.
+    /// - size: requested size of this buffer.
     ///```
     /// # extern crate winit;
     /// # use vku::*;
     /// # use ash::vk::*;
     /// # use std::mem::size_of;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
     /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
     /// # let size = [800_u32, 600_u32];
     /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
     /// # let create_info = VkInitCreateInfo::default();
-    /// let mut init = VkInit::new(Some(&window), Some(size), create_info)?;
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
     /// let size = 1024 * size_of::<u32>();
     /// let usage = BufferUsageFlags::STORAGE_BUFFER;
     /// let buffer = init.create_cpu_to_gpu_buffer(size, usage)?;
@@ -373,9 +850,9 @@ impl VMABuffer {
         dst_queue: Option<u32>,
         size: Option<u64>,
     ) -> BufferMemoryBarrier2 {
-        let src_queue = src_queue.unwrap_or(0);
-        let dst_queue = dst_queue.unwrap_or(0);
-        let size = size.unwrap_or(self.allocation.size());
+        self.check_alive("get_barrier2");
+        let (src_queue, dst_queue) = queue_family_indices(src_queue, dst_queue);
+        let size = size.unwrap_or(self.size);
 
         BufferMemoryBarrier2::builder()
             .buffer(self.buffer)
@@ -388,46 +865,394 @@ impl VMABuffer {
             .size(size)
             .build()
     }
+
+    /// Same as [get_barrier2](VMABuffer::get_barrier2), but always transfers ownership from queue family ```src_queue``` to ```dst_queue```.
+    ///
+    /// Record it on both queues, see [OwnershipTransfer](crate::OwnershipTransfer) for the pairing.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_barrier2_with_ownership_transfer(
+        &self,
+        src_stage: PipelineStageFlags2,
+        dst_stage: PipelineStageFlags2,
+        src_access: AccessFlags2,
+        dst_access: AccessFlags2,
+        src_queue: u32,
+        dst_queue: u32,
+        size: Option<u64>,
+    ) -> BufferMemoryBarrier2 {
+        self.get_barrier2(
+            src_stage,
+            dst_stage,
+            src_access,
+            dst_access,
+            Some(src_queue),
+            Some(dst_queue),
+            size,
+        )
+    }
+
+    /// Replaces the buffer with one of ```new_size``` bytes with the same usage, memory location and lifetime.
+    ///
+    /// The old buffer is destroyed right away and must not be in use by pending command buffers.
+    /// With ```preserve_contents``` the leading bytes are copied on the host, which needs a mapped buffer.
+    /// Returns [ResizeNeedsCopyCommand](Error::ResizeNeedsCopyCommand) otherwise, see [enqueue_resize](VMABuffer::enqueue_resize).
+    /// Debug names are re-applied by [resize_buffer](VkInit::resize_buffer).
+    pub fn resize(
+        &mut self,
+        device: &Device,
+        allocator: &mut Allocator,
+        new_size: usize,
+        preserve_contents: bool,
+    ) -> Result<(), Error> {
+        self.check(device, "resize");
+        if preserve_contents && self.allocation.mapped_ptr().is_none() {
+            return Err(Error::ResizeNeedsCopyCommand);
+        }
+        let mut old = self.replace(device, allocator, new_size)?;
+        if preserve_contents {
+            if let Err(e) = old.copy_mapped_to(device, self) {
+                old.destroy(device, allocator)?;
+                return Err(e);
+            }
+        }
+        old.destroy(device, allocator)
+    }
+
+    /// Same as [resize](VMABuffer::resize), with the leading bytes copied by a command recorded into ```cmd_buffer```.
+    ///
+    /// Returns the old buffer, destroy it once ```cmd_buffer``` completed.
+    /// The buffer needs ```TRANSFER_SRC``` and ```TRANSFER_DST``` usage.
+    pub fn enqueue_resize(
+        &mut self,
+        device: &Device,
+        allocator: &mut Allocator,
+        cmd_buffer: &CommandBuffer,
+        new_size: usize,
+    ) -> Result<VMABuffer, Error> {
+        self.check(device, "enqueue_resize");
+        self.check_copy_usage()?;
+        let old = self.replace(device, allocator, new_size)?;
+        old.enqueue_copy_contents_to(device, cmd_buffer, self)?;
+        Ok(old)
+    }
+
+    /// Doubles the size until ```len_bytes``` fit, see [resize](VMABuffer::resize).
+    ///
+    /// Returns ```false``` if the buffer is large enough already.
+    pub fn grow_to_fit(
+        &mut self,
+        device: &Device,
+        allocator: &mut Allocator,
+        len_bytes: usize,
+        preserve_contents: bool,
+    ) -> Result<bool, Error> {
+        let Some(new_size) = grown_size(self.size, len_bytes) else {
+            return Ok(false);
+        };
+        self.resize(device, allocator, new_size, preserve_contents)?;
+        Ok(true)
+    }
+
+    /// Swaps in a new buffer of ```new_size``` bytes and returns the old one.
+    fn replace(
+        &mut self,
+        device: &Device,
+        allocator: &mut Allocator,
+        new_size: usize,
+    ) -> Result<VMABuffer, Error> {
+        let buffer_info = BufferCreateInfo::builder()
+            .size(new_size as u64)
+            .sharing_mode(SharingMode::EXCLUSIVE)
+            .usage(self.usage)
+            .build();
+        let new = Self::new(
+            device,
+            allocator,
+            buffer_info,
+            self.location,
+            &self.memory_name,
+            self.lifetime,
+        )?;
+        let new = Self {
+            atom_size: self.atom_size,
+            ..new
+        };
+        let old = std::mem::replace(self, new);
+        let debug_name = old.debug_name();
+        if let Some(debug_name) = &debug_name {
+            self.provenance.set_name(debug_name);
+        }
+        *self.debug_name.get_mut().unwrap_or_else(|e| e.into_inner()) = debug_name;
+        Ok(old)
+    }
+
+    /// Name set by [set_debug_object_name](VMABuffer::set_debug_object_name) or a ```_named``` constructor.
+    pub fn debug_name(&self) -> Option<String> {
+        self.debug_name
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn check_copy_usage(&self) -> Result<(), Error> {
+        let required = BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::TRANSFER_DST;
+        if !self.usage.contains(required) {
+            return Err(Error::BufferUsageMissing(required & !self.usage));
+        }
+        Ok(())
+    }
+
+    /// Copies the bytes both mapped buffers have.
+    fn copy_mapped_to(&self, device: &Device, dst: &VMABuffer) -> Result<(), Error> {
+        let (Some(src_ptr), Some(dst_ptr)) =
+            (self.allocation.mapped_ptr(), dst.allocation.mapped_ptr())
+        else {
+            return Ok(());
+        };
+        let bytes = self.size.min(dst.size) as usize;
+        self.invalidate_read(device, 0, bytes as u64)?;
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                src_ptr.as_ptr() as *const u8,
+                dst_ptr.as_ptr() as *mut u8,
+                bytes,
+            );
+        }
+        dst.flush_written(device, 0, bytes as u64)
+    }
+
+    /// Records a copy of the bytes both buffers have.
+    fn enqueue_copy_contents_to(
+        &self,
+        device: &Device,
+        cmd_buffer: &CommandBuffer,
+        dst: &VMABuffer,
+    ) -> Result<(), Error> {
+        let bytes = self.size.min(dst.size);
+        if bytes == 0 {
+            return Ok(());
+        }
+        self.enqueue_copy_to_buffer(device, cmd_buffer, dst, None, None, Some(bytes))
+    }
+
+    pub(crate) fn check(&self, device: &Device, operation: &str) {
+        self.check_alive(operation);
+        self.provenance
+            .check_instance(device, "VMABuffer", operation);
+    }
+
+    pub(crate) fn check_alive(&self, operation: &str) {
+        self.provenance
+            .check_alive(self.buffer != Buffer::null(), "VMABuffer", operation);
+    }
+}
+
+/// Size reached by doubling ```current``` until ```needed``` bytes fit, ```None``` if they fit already.
+fn grown_size(current: u64, needed: usize) -> Option<usize> {
+    let needed = needed as u64;
+    if needed <= current {
+        return None;
+    }
+    let mut size = current.max(1);
+    while size < needed {
+        size = size.saturating_mul(2);
+    }
+    Some(size as usize)
 }
 
 impl VkInit {
+    /// Same as [resize](VMABuffer::resize), copying the contents of unmapped buffers on the unified queue and re-applying the debug name.
+    ///
+    /// Preserving the contents of an unmapped buffer needs ```TRANSFER_SRC``` and ```TRANSFER_DST``` usage.
+    /// ```
+    /// # extern crate winit;
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let init_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_headless(init_info)?;
+    /// let usage = BufferUsageFlags::VERTEX_BUFFER | BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::TRANSFER_DST;
+    /// let mut vertices = init.create_local_buffer(1024, usage)?;
+    /// vertices.set_debug_object_name(&init, String::from("Vertices"))?;
+    ///
+    /// init.grow_buffer_to_fit(&mut vertices, 3000)?;
+    /// assert_eq!(vertices.size(), 4096);
+    /// # vertices.destroy(&init.device, &mut init.allocator.lock())?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn resize_buffer(
+        &self,
+        buffer: &mut VMABuffer,
+        new_size: usize,
+        preserve_contents: bool,
+    ) -> Result<(), Error> {
+        buffer.check(&self.device, "resize_buffer");
+        let copy_on_device = preserve_contents && buffer.allocation.mapped_ptr().is_none();
+        if copy_on_device {
+            buffer.check_copy_usage()?;
+        }
+        let mut old = self
+            .allocate_with_retry(|device, allocator| buffer.replace(device, allocator, new_size))?;
+
+        let mut copied = Ok(());
+        if copy_on_device {
+            self.immediate_submit(CmdType::Any, |cmd_buffer| {
+                copied = old.enqueue_copy_contents_to(&self.device, cmd_buffer, buffer);
+            })?;
+        } else if preserve_contents {
+            copied = old.copy_mapped_to(&self.device, buffer);
+        }
+        old.destroy(&self.device, &mut self.allocator.lock())?;
+        copied?;
+
+        if let Some(debug_name) = buffer.debug_name() {
+            buffer.set_debug_object_name(self, debug_name)?;
+        }
+        Ok(())
+    }
+
+    /// Same as [grow_to_fit](VMABuffer::grow_to_fit) via [resize_buffer](VkInit::resize_buffer), always preserving the contents.
+    pub fn grow_buffer_to_fit(
+        &self,
+        buffer: &mut VMABuffer,
+        len_bytes: usize,
+    ) -> Result<bool, Error> {
+        let Some(new_size) = grown_size(buffer.size, len_bytes) else {
+            return Ok(false);
+        };
+        self.resize_buffer(buffer, new_size, true)?;
+        Ok(true)
+    }
+
     /// Shortcut - see [VMABuffer](VMABuffer::create_local_buffer) for example.
     pub fn create_local_buffer(
-        &mut self,
+        &self,
         size: usize,
         usage: BufferUsageFlags,
     ) -> Result<VMABuffer, Error> {
-        VMABuffer::create_local_buffer(&self.device, &mut self.allocator, size, usage)
+        self.allocate_with_retry(|device, allocator| {
+            VMABuffer::create_local_buffer(device, allocator, size, usage)
+                .map(|buffer| buffer.with_atom_size(&self.physical_device_info.limits))
+        })
     }
 
     /// Shortcut - see [VMABuffer](VMABuffer::create_cpu_to_gpu_buffer) for example.
     pub fn create_cpu_to_gpu_buffer(
-        &mut self,
+        &self,
         size: usize,
         usage: BufferUsageFlags,
     ) -> Result<VMABuffer, Error> {
-        VMABuffer::create_cpu_to_gpu_buffer(&self.device, &mut self.allocator, size, usage)
+        VMABuffer::create_cpu_to_gpu_buffer(&self.device, &mut self.allocator.lock(), size, usage)
+            .map(|buffer| buffer.with_atom_size(&self.physical_device_info.limits))
     }
 
     pub fn create_readback_buffer(
-        &mut self,
+        &self,
         size: usize,
         usage: BufferUsageFlags,
     ) -> Result<VMABuffer, Error> {
-        VMABuffer::create_readback_buffer(&self.device, &mut self.allocator, size, usage)
+        VMABuffer::create_readback_buffer(&self.device, &mut self.allocator.lock(), size, usage)
+            .map(|buffer| buffer.with_atom_size(&self.physical_device_info.limits))
+    }
+
+    /// Shortcut - see [VMABuffer](VMABuffer::create_local_buffer_named), also sets the debug names.
+    /// ```
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let init_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_headless(init_info)?;
+    /// let mut particles = init.create_local_buffer_named(4096, BufferUsageFlags::STORAGE_BUFFER, "Particles")?;
+    /// assert_eq!(particles.debug_name().as_deref(), Some("Particles"));
+    /// # particles.destroy(&init.device, &mut init.allocator.lock())?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn create_local_buffer_named(
+        &self,
+        size: usize,
+        usage: BufferUsageFlags,
+        name: &str,
+    ) -> Result<VMABuffer, Error> {
+        let buffer = self.allocate_with_retry(|device, allocator| {
+            VMABuffer::create_local_buffer_named(device, allocator, size, usage, name)
+                .map(|buffer| buffer.with_atom_size(&self.physical_device_info.limits))
+        })?;
+        buffer.set_debug_object_name(self, name.to_string())?;
+        Ok(buffer)
+    }
+
+    /// Shortcut - see [VMABuffer](VMABuffer::create_cpu_to_gpu_buffer_named), also sets the debug names.
+    pub fn create_cpu_to_gpu_buffer_named(
+        &self,
+        size: usize,
+        usage: BufferUsageFlags,
+        name: &str,
+    ) -> Result<VMABuffer, Error> {
+        let buffer = VMABuffer::create_cpu_to_gpu_buffer_named(
+            &self.device,
+            &mut self.allocator.lock(),
+            size,
+            usage,
+            name,
+        )?
+        .with_atom_size(&self.physical_device_info.limits);
+        buffer.set_debug_object_name(self, name.to_string())?;
+        Ok(buffer)
+    }
+
+    /// Shortcut - see [VMABuffer](VMABuffer::create_readback_buffer_named), also sets the debug names.
+    pub fn create_readback_buffer_named(
+        &self,
+        size: usize,
+        usage: BufferUsageFlags,
+        name: &str,
+    ) -> Result<VMABuffer, Error> {
+        let buffer = VMABuffer::create_readback_buffer_named(
+            &self.device,
+            &mut self.allocator.lock(),
+            size,
+            usage,
+            name,
+        )?
+        .with_atom_size(&self.physical_device_info.limits);
+        buffer.set_debug_object_name(self, name.to_string())?;
+        Ok(buffer)
+    }
+
+    /// Shortcut - see [VMABuffer](VMABuffer::create_indirect_buffer).
+    pub fn create_indirect_buffer(&self, max_draws: usize) -> Result<VMABuffer, Error> {
+        self.allocate_with_retry(|device, allocator| {
+            VMABuffer::create_indirect_buffer(device, allocator, max_draws)
+                .map(|buffer| buffer.with_atom_size(&self.physical_device_info.limits))
+        })
+    }
+
+    /// Shortcut - see [VMABuffer](VMABuffer::create_buffer_with_lifetime).
+    pub fn create_buffer_with_lifetime(
+        &self,
+        size: usize,
+        usage: BufferUsageFlags,
+        location: MemoryLocation,
+        lifetime: AllocationLifetime,
+    ) -> Result<VMABuffer, Error> {
+        self.allocate_with_retry(|device, allocator| {
+            VMABuffer::create_buffer_with_lifetime(
+                device, allocator, size, usage, location, lifetime,
+            )
+            .map(|buffer| buffer.with_atom_size(&self.physical_device_info.limits))
+        })
     }
 
     /// Shortcut - see [VMABuffer](VMABuffer::create_local_buffer) for example.
     pub fn create_local_buffers(
-        &mut self,
+        &self,
         size: usize,
         usage: BufferUsageFlags,
         count: usize,
     ) -> Result<Vec<VMABuffer>, Error> {
         let mut buffers = Vec::new();
         for _ in 0..count {
-            let buffer =
-                VMABuffer::create_local_buffer(&self.device, &mut self.allocator, size, usage)?;
+            let buffer = self.create_local_buffer(size, usage)?;
             buffers.push(buffer);
         }
         Ok(buffers)
@@ -435,7 +1260,7 @@ impl VkInit {
 
     /// Shortcut - see [VMABuffer](VMABuffer::create_cpu_to_gpu_buffer) for example.
     pub fn create_cpu_to_gpu_buffers(
-        &mut self,
+        &self,
         size: usize,
         usage: BufferUsageFlags,
         count: usize,
@@ -444,12 +1269,35 @@ impl VkInit {
         for _ in 0..count {
             let buffer = VMABuffer::create_cpu_to_gpu_buffer(
                 &self.device,
-                &mut self.allocator,
+                &mut self.allocator.lock(),
                 size,
                 usage,
-            )?;
+            )?
+            .with_atom_size(&self.physical_device_info.limits);
             buffers.push(buffer);
         }
         Ok(buffers)
     }
 }
+
+/// Largest ```nonCoherentAtomSize``` allowed by the spec.
+pub(crate) const MAX_NON_COHERENT_ATOM_SIZE: u64 = 256;
+
+/// Host-visible allocations start on ```MAX_NON_COHERENT_ATOM_SIZE``` and, unless dedicated, also end on it,
+/// so flushing or invalidating their whole atoms never touches neighbouring allocations.
+/// Dedicated allocations have to match the size of the buffer and reach the end of their memory instead.
+fn atom_aligned_requirements(
+    mut requirements: MemoryRequirements,
+    location: MemoryLocation,
+    allocation_scheme: AllocationScheme,
+) -> MemoryRequirements {
+    if location != MemoryLocation::GpuOnly {
+        requirements.alignment = requirements.alignment.max(MAX_NON_COHERENT_ATOM_SIZE);
+        if allocation_scheme == AllocationScheme::GpuAllocatorManaged {
+            requirements.size = requirements
+                .size
+                .next_multiple_of(MAX_NON_COHERENT_ATOM_SIZE);
+        }
+    }
+    requirements
+}
@@ -1,18 +1,54 @@
 use gpu_allocator::vulkan::AllocationScheme;
 
-use crate::{image_layout_transitions, imports::*, vma_buffer::VMABuffer, VkInit};
+use crate::{
+    allocation_lifetime::{self, generated_name},
+    image_atlas::texel_size,
+    image_layout_transitions,
+    imports::*,
+    mipmaps::{self, MipChain},
+    provenance::Provenance,
+    vma_buffer::{AllocationInfo, VMABuffer},
+    AllocationLifetime, MipGeneration, PooledStaging, VkInit,
+};
 
 /// Allocated image, image information, image view, allocation and allocation information.
 ///
-/// Includes a host-visible staging buffer
+/// Sampled images include a host-visible staging buffer, depth and render images only get one from [with_staging_buffer](VMAImage::with_staging_buffer).
 pub struct VMAImage {
-    pub staging_buffer: VMABuffer,
+    pub staging_buffer: Option<VMABuffer>,
     pub image: Image,
     pub extent: Extent3D,
+    pub format: Format,
+    pub mip_levels: u32,
+    /// 6 for [cubemaps](VMAImage::create_cubemap), see [create_image_array](VMAImage::create_image_array).
+    pub array_layers: u32,
+    pub usage: ImageUsageFlags,
     pub aspect_flags: ImageAspectFlags,
     pub image_view: ImageView,
+    /// View type of ```image_view```, derived from the image type, array layers and cube compatibility.
+    pub view_type: ImageViewType,
     pub allocation: Allocation,
     pub current_layout: ImageLayout,
+    /// Bytes of one array layer in the staging buffer, zero if unknown.
+    layer_staging_size: usize,
+    requirements: MemoryRequirements,
+    lifetime: AllocationLifetime,
+    /// Memory of another resource this image is bound to, see [create_aliased](VMAImage::create_aliased).
+    aliased: Option<AllocationInfo>,
+    /// Dedicated memory outside the allocator for external handles.
+    pub(crate) external: Option<ExternalMemory>,
+    /// Per-level views of the [compute mip generation](crate::MipGeneration::Compute), created on first use.
+    pub(crate) mip_chain: Option<MipChain>,
+    provenance: Provenance,
+}
+
+/// Dedicated memory of an image imported from or exportable to another API.
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "external"), allow(dead_code))]
+pub(crate) struct ExternalMemory {
+    pub(crate) info: AllocationInfo,
+    pub(crate) handle_type: ExternalMemoryHandleTypeFlags,
+    pub(crate) exportable: bool,
 }
 
 impl VMAImage {
@@ -21,20 +57,115 @@ impl VMAImage {
         allocator: &mut Allocator,
         image_info: ImageCreateInfo,
         aspect_flags: ImageAspectFlags,
-        mut allocation_create_info: AllocationCreateDesc,
-        staging_buffer: VMABuffer,
+        lifetime: AllocationLifetime,
+        staging_buffer: Option<VMABuffer>,
+        layer_staging_size: usize,
     ) -> Result<Self, Error> {
-        let (image, allocation) = unsafe {
+        let (image, allocation, requirements) = unsafe {
             let image = device.create_image(&image_info, None)?;
             let req = device.get_image_memory_requirements(image);
-            allocation_create_info.requirements = req;
-            let alloc = allocator.allocate(&allocation_create_info)?;
+            let allocation_create_info = AllocationCreateDesc {
+                name: &lifetime.allocation_name(&generated_name("Local_Image")),
+                requirements: req,
+                location: MemoryLocation::GpuOnly,
+                linear: false,
+                allocation_scheme: lifetime
+                    .allocation_scheme(req.size, AllocationScheme::DedicatedImage(image)),
+            };
+            let alloc = match allocator.allocate(&allocation_create_info) {
+                Ok(alloc) => alloc,
+                Err(e) => {
+                    device.destroy_image(image, None);
+                    if let Some(mut staging_buffer) = staging_buffer {
+                        staging_buffer.destroy(device, allocator)?;
+                    }
+                    return Err(e.into());
+                }
+            };
             device.bind_image_memory(image, alloc.memory(), alloc.offset())?;
-            (image, alloc)
+            (image, alloc, req)
         };
+        allocation_lifetime::track(device, &allocation, lifetime);
+        let image_view = Self::create_image_view(device, image, &image_info, aspect_flags)?;
+
+        Ok(Self {
+            image,
+            extent: image_info.extent,
+            format: image_info.format,
+            mip_levels: image_info.mip_levels,
+            array_layers: image_info.array_layers,
+            usage: image_info.usage,
+            aspect_flags,
+            image_view,
+            view_type: Self::view_type(&image_info),
+            allocation,
+            staging_buffer,
+            layer_staging_size,
+            current_layout: ImageLayout::UNDEFINED,
+            requirements,
+            lifetime,
+            aliased: None,
+            external: None,
+            mip_chain: None,
+            provenance: Provenance::new(device, "VMAImage"),
+        })
+    }
+
+    /// Wraps an ```image``` bound to ```external``` memory, creating the image view.
+    #[cfg(feature = "external")]
+    pub(crate) fn from_external(
+        device: &Device,
+        image: Image,
+        image_info: &ImageCreateInfo,
+        aspect_flags: ImageAspectFlags,
+        requirements: MemoryRequirements,
+        external: ExternalMemory,
+    ) -> Result<Self, Error> {
+        let image_view = Self::create_image_view(device, image, image_info, aspect_flags)?;
+        Ok(Self {
+            image,
+            extent: image_info.extent,
+            format: image_info.format,
+            mip_levels: image_info.mip_levels,
+            array_layers: image_info.array_layers,
+            usage: image_info.usage,
+            aspect_flags,
+            image_view,
+            view_type: Self::view_type(image_info),
+            allocation: Allocation::default(),
+            staging_buffer: None,
+            layer_staging_size: 0,
+            current_layout: ImageLayout::UNDEFINED,
+            requirements,
+            lifetime: AllocationLifetime::default(),
+            aliased: None,
+            external: Some(external),
+            mip_chain: None,
+            provenance: Provenance::new(device, "VMAImage"),
+        })
+    }
 
+    fn view_type(image_info: &ImageCreateInfo) -> ImageViewType {
+        let cube = image_info.flags.contains(ImageCreateFlags::CUBE_COMPATIBLE);
+        match (image_info.image_type, cube, image_info.array_layers) {
+            (ImageType::TYPE_1D, _, 1) => ImageViewType::TYPE_1D,
+            (ImageType::TYPE_1D, _, _) => ImageViewType::TYPE_1D_ARRAY,
+            (ImageType::TYPE_3D, _, _) => ImageViewType::TYPE_3D,
+            (_, true, 6) => ImageViewType::CUBE,
+            (_, true, _) => ImageViewType::CUBE_ARRAY,
+            (_, false, 1) => ImageViewType::TYPE_2D,
+            (_, false, _) => ImageViewType::TYPE_2D_ARRAY,
+        }
+    }
+
+    fn create_image_view(
+        device: &Device,
+        image: Image,
+        image_info: &ImageCreateInfo,
+        aspect_flags: ImageAspectFlags,
+    ) -> Result<ImageView, Error> {
         let image_view_create_info = ImageViewCreateInfo {
-            view_type: ImageViewType::TYPE_2D,
+            view_type: Self::view_type(image_info),
             format: image_info.format,
             components: ComponentMapping {
                 r: ComponentSwizzle::R,
@@ -44,8 +175,8 @@ impl VMAImage {
             },
             subresource_range: ImageSubresourceRange {
                 aspect_mask: aspect_flags,
-                level_count: 1,
-                layer_count: 1,
+                level_count: image_info.mip_levels,
+                layer_count: image_info.array_layers,
                 base_array_layer: 0,
                 base_mip_level: 0,
             },
@@ -53,49 +184,227 @@ impl VMAImage {
             ..Default::default()
         };
 
-        let image_view = unsafe { device.create_image_view(&image_view_create_info, None) }?;
-        let extent = image_info.extent;
+        Ok(unsafe { device.create_image_view(&image_view_create_info, None) }?)
+    }
+
+    /// Creates an image bound into ```allocation``` at ```offset``` bytes instead of allocating its own memory,
+    /// e.g. transient attachments sharing memory with resources used at other times of the frame.
+    ///
+    /// The alias does not own the memory and must be destroyed before the owner frees it,
+    /// which debug builds assert for owning [VMABuffers](VMABuffer) and [VMAImages](VMAImage).
+    /// Contents are undefined whenever another alias was used in between, see [aliasing_barrier](VMAImage::aliasing_barrier).
+    /// The allocation's memory type must be supported by the image, no staging buffer is created.
+    ///
+    /// Returns [AliasOutOfBounds](Error::AliasOutOfBounds) or [AliasMisaligned](Error::AliasMisaligned) if the image does not fit at ```offset```,
+    /// [AliasMemoryTypeMismatch](Error::AliasMemoryTypeMismatch) if no memory type of ```memory_props``` supported by the image has the allocation's properties.
+    pub fn create_aliased(
+        device: &Device,
+        memory_props: &PhysicalDeviceMemoryProperties,
+        allocation: &Allocation,
+        offset: u64,
+        image_info: &ImageCreateInfo,
+        aspect_flags: ImageAspectFlags,
+    ) -> Result<VMAImage, Error> {
+        let image = unsafe { device.create_image(image_info, None)? };
+        let requirements = unsafe { device.get_image_memory_requirements(image) };
+        let alignment_error =
+            (!offset.is_multiple_of(requirements.alignment)).then_some(Error::AliasMisaligned {
+                offset,
+                alignment: requirements.alignment,
+            });
+        let bounds_error =
+            (offset + requirements.size > allocation.size()).then_some(Error::AliasOutOfBounds {
+                required: offset + requirements.size,
+                available: allocation.size(),
+            });
+        // The allocator does not expose the memory type index, types with other properties are ruled out.
+        let memory_properties = allocation.memory_properties();
+        let memory_types = &memory_props.memory_types[..memory_props.memory_type_count as usize];
+        let type_supported = memory_types.iter().enumerate().any(|(index, memory_type)| {
+            requirements.memory_type_bits & (1 << index) != 0
+                && memory_type.property_flags == memory_properties
+        });
+        let memory_type_error = (!type_supported).then_some(Error::AliasMemoryTypeMismatch {
+            memory_type_bits: requirements.memory_type_bits,
+            memory_properties,
+        });
+        if let Some(error) = alignment_error.or(bounds_error).or(memory_type_error) {
+            unsafe { device.destroy_image(image, None) };
+            return Err(error);
+        }
 
+        let aliased = AllocationInfo {
+            memory: unsafe { allocation.memory() },
+            offset: allocation.offset() + offset,
+            size: requirements.size,
+            alignment: requirements.alignment,
+            memory_type_bits: requirements.memory_type_bits,
+            memory_properties: allocation.memory_properties(),
+        };
+        let image_view = unsafe {
+            device
+                .bind_image_memory(image, aliased.memory, aliased.offset)
+                .map_err(Error::from)
+                .and_then(|_| Self::create_image_view(device, image, image_info, aspect_flags))
+        };
+        let image_view = match image_view {
+            Ok(image_view) => image_view,
+            Err(e) => {
+                unsafe { device.destroy_image(image, None) };
+                return Err(e);
+            }
+        };
+
+        let provenance = Provenance::new(device, "VMAImage");
+        provenance.set_aliased(
+            aliased.memory,
+            aliased.offset..aliased.offset + aliased.size,
+        );
         Ok(Self {
             image,
-            extent,
+            extent: image_info.extent,
+            format: image_info.format,
+            mip_levels: image_info.mip_levels,
+            array_layers: image_info.array_layers,
+            usage: image_info.usage,
             aspect_flags,
             image_view,
-            allocation,
-            staging_buffer,
+            view_type: Self::view_type(image_info),
+            allocation: Allocation::default(),
+            staging_buffer: None,
+            layer_staging_size: 0,
             current_layout: ImageLayout::UNDEFINED,
+            requirements,
+            lifetime: AllocationLifetime::default(),
+            aliased: Some(aliased),
+            external: None,
+            mip_chain: None,
+            provenance,
         })
     }
 
+    /// Memory the image is bound to, including the owner's memory for aliased images.
+    pub fn allocation_info(&self) -> AllocationInfo {
+        self.check_alive("allocation_info");
+        let external = self.external.map(|external| external.info);
+        self.aliased.or(external).unwrap_or_else(|| AllocationInfo {
+            memory: unsafe { self.allocation.memory() },
+            offset: self.allocation.offset(),
+            size: self.allocation.size(),
+            alignment: self.requirements.alignment,
+            memory_type_bits: self.requirements.memory_type_bits,
+            memory_properties: self.allocation.memory_properties(),
+        })
+    }
+
+    pub fn lifetime(&self) -> AllocationLifetime {
+        self.lifetime
+    }
+
+    pub fn is_aliased(&self) -> bool {
+        self.aliased.is_some()
+    }
+
+    /// Barrier making the whole image usable by ```dst_stage``` and ```dst_access``` after its memory was used by another alias.
+    ///
+    /// Transitions from ```UNDEFINED```, discarding the contents, and waits on all prior memory writes.
+    /// Current layout is set to ```dst_layout``` after returning this barrier.
+    pub fn aliasing_barrier(
+        &mut self,
+        dst_layout: ImageLayout,
+        dst_stage: PipelineStageFlags2,
+        dst_access: AccessFlags2,
+    ) -> ImageMemoryBarrier2 {
+        self.check_alive("aliasing_barrier");
+        self.current_layout = dst_layout;
+        ImageMemoryBarrier2::builder()
+            .image(self.image)
+            .old_layout(ImageLayout::UNDEFINED)
+            .new_layout(dst_layout)
+            .src_stage_mask(PipelineStageFlags2::ALL_COMMANDS)
+            .src_access_mask(AccessFlags2::MEMORY_WRITE)
+            .dst_stage_mask(dst_stage)
+            .dst_access_mask(dst_access)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .subresource_range(ImageSubresourceRange {
+                aspect_mask: self.aspect_flags,
+                base_mip_level: 0,
+                level_count: self.mip_levels,
+                base_array_layer: 0,
+                layer_count: REMAINING_ARRAY_LAYERS,
+            })
+            .build()
+    }
+
+    /// Destroying an already destroyed image is a no-op.
     pub fn destroy(&mut self, device: &Device, allocator: &mut Allocator) -> Result<(), Error> {
+        self.provenance
+            .check_instance(device, "VMAImage", "destroy");
+        if !self.allocation.is_null() {
+            let info = self.allocation_info();
+            self.provenance.check_not_aliased(
+                info.memory,
+                info.offset..info.offset + info.size,
+                "VMAImage",
+            );
+        }
+        self.provenance.release();
         unsafe {
-            self.staging_buffer.destroy(device, allocator)?;
+            if let Some(mut staging_buffer) = self.staging_buffer.take() {
+                staging_buffer.destroy(device, allocator)?;
+            }
+            if let Some(mut mip_chain) = self.mip_chain.take() {
+                mip_chain.destroy(device);
+            }
             device.destroy_image(self.image, None);
             device.destroy_image_view(self.image_view, None);
+            self.image = Image::null();
+            self.image_view = ImageView::null();
             let alloc = std::mem::take(&mut self.allocation);
+            if !alloc.is_null() {
+                allocation_lifetime::untrack(device, &alloc);
+            }
             allocator.free(alloc)?;
+            if let Some(external) = self.external.take() {
+                device.free_memory(external.info.memory, None);
+            }
         }
         Ok(())
     }
 
     pub fn set_debug_object_name(&self, vk_init: &VkInit, base_name: String) -> Result<(), Error> {
-        vk_init.set_debug_object_name(
+        self.check(&vk_init.device, "set_debug_object_name");
+        self.provenance.set_name(&base_name);
+        vk_init.set_debug_object_name_fmt(
             self.image.as_raw(),
             ObjectType::IMAGE,
-            format!("{base_name}_Image"),
+            format_args!("{base_name}_Image"),
         )?;
-        vk_init.set_debug_object_name(
-            unsafe { self.allocation.memory().as_raw() },
-            ObjectType::DEVICE_MEMORY,
-            format!("{base_name}_Memory"),
-        )?;
-        vk_init.set_debug_object_name(
+        vk_init.set_debug_object_name_fmt(
             self.image_view.as_raw(),
             ObjectType::IMAGE_VIEW,
-            format!("{base_name}_Image_View"),
+            format_args!("{base_name}_Image_View"),
+        )?;
+        // Aliased images neither own their memory nor have a staging buffer.
+        if self.is_aliased() {
+            return Ok(());
+        }
+        if let Some(external) = self.external {
+            return vk_init.set_debug_object_name_fmt(
+                external.info.memory.as_raw(),
+                ObjectType::DEVICE_MEMORY,
+                format_args!("{base_name}_External_Memory"),
+            );
+        }
+        vk_init.set_debug_object_name_fmt(
+            unsafe { self.allocation.memory().as_raw() },
+            ObjectType::DEVICE_MEMORY,
+            format_args!("{base_name}_Memory"),
         )?;
-        self.staging_buffer
-            .set_debug_object_name(vk_init, format!("{base_name}_Staging_Buffer"))?;
+        if let Some(staging_buffer) = &self.staging_buffer {
+            staging_buffer.set_debug_object_name(vk_init, format!("{base_name}_Staging_Buffer"))?;
+        }
         Ok(())
     }
 
@@ -104,11 +413,12 @@ impl VMAImage {
     /// # extern crate winit;
     /// # use vku::*;
     /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
     /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
     /// # let size = [800_u32, 600_u32];
     /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
     /// # let create_info = VkInitCreateInfo::default();
-    /// let mut init = VkInit::new(Some(&window), Some(size), create_info)?;
+    /// let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
     ///
     /// let extent = Extent3D{width: 100, height: 100, depth: 1};
     /// let format = Format::R8G8B8A8_UNORM;
@@ -125,53 +435,254 @@ impl VMAImage {
         format: Format,
         sizeof: usize,
         aspect_mask: ImageAspectFlags,
+    ) -> Result<VMAImage, Error> {
+        Self::create_mipped_image(device, allocator, extent, format, sizeof, aspect_mask, 1)
+    }
+
+    /// Same as [create_empty_image](VMAImage::create_empty_image), but with ```mip_levels``` mip levels.
+    ///
+    /// The view covers all mip levels, the staging buffer only fits mip level 0.
+    pub fn create_mipped_image(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        sizeof: usize,
+        aspect_mask: ImageAspectFlags,
+        mip_levels: u32,
+    ) -> Result<VMAImage, Error> {
+        Self::create_mipped_image_with_usage(
+            device,
+            allocator,
+            extent,
+            format,
+            sizeof,
+            aspect_mask,
+            mip_levels,
+            ImageUsageFlags::empty(),
+        )
+    }
+
+    /// Same as [create_mipped_image](VMAImage::create_mipped_image), adding ```additional_usage```.
+    ///
+    /// sRGB images with ```ImageUsageFlags::STORAGE``` are created with ```MUTABLE_FORMAT``` to be viewed as UNORM,
+    /// e.g. by the [compute mip generation](crate::MipGeneration::Compute).
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_mipped_image_with_usage(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        sizeof: usize,
+        aspect_mask: ImageAspectFlags,
+        mip_levels: u32,
+        additional_usage: ImageUsageFlags,
+    ) -> Result<VMAImage, Error> {
+        Self::create_sampled_image(
+            device,
+            allocator,
+            extent,
+            format,
+            sizeof,
+            aspect_mask,
+            (mip_levels, 1),
+            ImageCreateFlags::empty(),
+            additional_usage,
+            AllocationLifetime::Persistent,
+        )
+    }
+
+    /// Same as [create_mipped_image](VMAImage::create_mipped_image) with the allocation category ```lifetime```,
+    /// e.g. [Streaming](AllocationLifetime::Streaming) for textures of the [MipStreamer](crate::MipStreamer).
+    ///
+    /// The staging buffer is always [Transient](AllocationLifetime::Transient).
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_mipped_image_with_lifetime(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        sizeof: usize,
+        aspect_mask: ImageAspectFlags,
+        mip_levels: u32,
+        lifetime: AllocationLifetime,
+    ) -> Result<VMAImage, Error> {
+        Self::create_sampled_image(
+            device,
+            allocator,
+            extent,
+            format,
+            sizeof,
+            aspect_mask,
+            (mip_levels, 1),
+            ImageCreateFlags::empty(),
+            ImageUsageFlags::empty(),
+            lifetime,
+        )
+    }
+
+    /// Creates a sampled image with ```mip_levels``` mip levels without a staging buffer, e.g. for block-compressed formats.
+    ///
+    /// Upload the levels from an own buffer with one ```BufferImageCopy``` per level.
+    pub fn create_compressed_image(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        mip_levels: u32,
     ) -> Result<VMAImage, Error> {
         let image_info = ImageCreateInfo {
             image_type: ImageType::TYPE_2D,
             format,
             extent,
-            mip_levels: 1,
+            mip_levels,
             array_layers: 1,
             samples: SampleCountFlags::TYPE_1,
             tiling: ImageTiling::OPTIMAL,
-            usage: ImageUsageFlags::SAMPLED
-                | ImageUsageFlags::TRANSFER_DST
-                | ImageUsageFlags::TRANSFER_SRC,
+            usage: ImageUsageFlags::SAMPLED | ImageUsageFlags::TRANSFER_DST,
             sharing_mode: SharingMode::EXCLUSIVE,
             ..Default::default()
         };
 
-        let allocation_info = AllocationCreateDesc {
-            name: "Local_Image_Memory",
-            requirements: MemoryRequirements::default(),
-            location: MemoryLocation::GpuOnly,
-            linear: false,
-            allocation_scheme: AllocationScheme::GpuAllocatorManaged,
-        };
+        Self::new(
+            device,
+            allocator,
+            image_info,
+            ImageAspectFlags::COLOR,
+            AllocationLifetime::Persistent,
+            None,
+            0,
+        )
+    }
 
-        let staging_buffer = VMABuffer::create_cpu_to_gpu_buffer(
+    /// Creates an image with ```layers``` array layers and a ```TYPE_2D_ARRAY``` view, e.g. for shadow map arrays.
+    ///
+    /// The staging buffer fits all layers, tightly packed in layer order.
+    /// Use [set_layer_staging_data](VMAImage::set_layer_staging_data) and [enqueue_copy_to_layer](VMAImage::enqueue_copy_to_layer) to upload single layers.
+    pub fn create_image_array(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        sizeof: usize,
+        aspect_mask: ImageAspectFlags,
+        layers: u32,
+    ) -> Result<VMAImage, Error> {
+        Self::create_sampled_image(
             device,
             allocator,
-            (extent.width * extent.height * extent.depth) as usize * sizeof,
-            BufferUsageFlags::TRANSFER_SRC,
-        )?;
+            extent,
+            format,
+            sizeof,
+            aspect_mask,
+            (1, layers),
+            ImageCreateFlags::empty(),
+            ImageUsageFlags::empty(),
+            AllocationLifetime::Persistent,
+        )
+    }
+
+    /// Creates a cubemap with 6 array layers and a ```CUBE``` view, e.g. for skyboxes.
+    ///
+    /// Layers are the faces in the order +X, -X, +Y, -Y, +Z, -Z, see [create_image_array](VMAImage::create_image_array) for uploads.
+    pub fn create_cubemap(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        sizeof: usize,
+    ) -> Result<VMAImage, Error> {
+        Self::create_sampled_image(
+            device,
+            allocator,
+            extent,
+            format,
+            sizeof,
+            ImageAspectFlags::COLOR,
+            (1, 6),
+            ImageCreateFlags::CUBE_COMPATIBLE,
+            ImageUsageFlags::empty(),
+            AllocationLifetime::Persistent,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_sampled_image(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        sizeof: usize,
+        aspect_mask: ImageAspectFlags,
+        (mip_levels, array_layers): (u32, u32),
+        mut flags: ImageCreateFlags,
+        additional_usage: ImageUsageFlags,
+        lifetime: AllocationLifetime,
+    ) -> Result<VMAImage, Error> {
+        let mutable = additional_usage.contains(ImageUsageFlags::STORAGE)
+            && mipmaps::storage_view_format(format).0 != format;
+        if mutable {
+            flags |= ImageCreateFlags::MUTABLE_FORMAT;
+        }
+        let image_info = ImageCreateInfo {
+            flags,
+            image_type: ImageType::TYPE_2D,
+            format,
+            extent,
+            mip_levels,
+            array_layers,
+            samples: SampleCountFlags::TYPE_1,
+            tiling: ImageTiling::OPTIMAL,
+            usage: ImageUsageFlags::SAMPLED
+                | ImageUsageFlags::TRANSFER_DST
+                | ImageUsageFlags::TRANSFER_SRC
+                | additional_usage,
+            sharing_mode: SharingMode::EXCLUSIVE,
+            ..Default::default()
+        };
+
+        let layer_staging_size = (extent.width * extent.height * extent.depth) as usize * sizeof;
+        let staging_buffer =
+            Self::create_staging_buffer(device, allocator, layer_staging_size, array_layers)?;
 
         Self::new(
             device,
             allocator,
             image_info,
             aspect_mask,
-            allocation_info,
-            staging_buffer,
+            lifetime,
+            Some(staging_buffer),
+            layer_staging_size,
         )
     }
 
+    /// Creates a depth attachment without a staging buffer.
     pub fn create_depth_image(
         device: &Device,
         allocator: &mut Allocator,
         extent: Extent3D,
         format: Format,
         sizeof: usize,
+    ) -> Result<VMAImage, Error> {
+        Self::create_msaa_depth_image(
+            device,
+            allocator,
+            extent,
+            format,
+            sizeof,
+            SampleCountFlags::TYPE_1,
+        )
+    }
+
+    /// Creates a depth attachment with ```samples``` per texel without a staging buffer,
+    /// to be used together with a [multisampled color attachment](VMAImage::create_msaa_render_image) of the same sample count.
+    pub fn create_msaa_depth_image(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        sizeof: usize,
+        samples: SampleCountFlags,
     ) -> Result<VMAImage, Error> {
         let image_info = ImageCreateInfo {
             image_type: ImageType::TYPE_2D,
@@ -179,44 +690,113 @@ impl VMAImage {
             extent,
             mip_levels: 1,
             array_layers: 1,
-            samples: SampleCountFlags::TYPE_1,
+            samples,
             tiling: ImageTiling::OPTIMAL,
             usage: ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
             sharing_mode: SharingMode::EXCLUSIVE,
             ..Default::default()
         };
 
-        let allocation_info = AllocationCreateDesc {
-            name: "Local_Image_Memory",
-            requirements: MemoryRequirements::default(),
-            location: MemoryLocation::GpuOnly,
-            linear: false,
-            allocation_scheme: AllocationScheme::GpuAllocatorManaged,
-        };
+        let layer_staging_size = (extent.width * extent.height * extent.depth) as usize * sizeof;
 
-        let staging_buffer = VMABuffer::create_cpu_to_gpu_buffer(
+        Self::new(
             device,
             allocator,
-            (extent.width * extent.height * extent.depth) as usize * sizeof,
-            BufferUsageFlags::TRANSFER_SRC,
-        )?;
+            image_info,
+            ImageAspectFlags::DEPTH,
+            AllocationLifetime::Persistent,
+            None,
+            layer_staging_size,
+        )
+    }
+
+    /// Creates a color attachment with ```samples``` per texel without a staging buffer.
+    ///
+    /// Multisampled images can't be sampled or copied, render to them with a resolve attachment,
+    /// e.g. through [begin_rendering](VkInit::begin_rendering) with [msaa_samples](crate::VkInitCreateInfo::msaa_samples).
+    pub fn create_msaa_render_image(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        sizeof: usize,
+        samples: SampleCountFlags,
+    ) -> Result<VMAImage, Error> {
+        let image_info = ImageCreateInfo {
+            image_type: ImageType::TYPE_2D,
+            format,
+            extent,
+            mip_levels: 1,
+            array_layers: 1,
+            samples,
+            tiling: ImageTiling::OPTIMAL,
+            usage: ImageUsageFlags::COLOR_ATTACHMENT | ImageUsageFlags::TRANSIENT_ATTACHMENT,
+            sharing_mode: SharingMode::EXCLUSIVE,
+            ..Default::default()
+        };
+
+        let layer_staging_size = (extent.width * extent.height * extent.depth) as usize * sizeof;
 
         Self::new(
             device,
             allocator,
             image_info,
-            ImageAspectFlags::DEPTH,
-            allocation_info,
-            staging_buffer,
+            ImageAspectFlags::COLOR,
+            AllocationLifetime::Persistent,
+            None,
+            layer_staging_size,
         )
     }
 
+    /// Creates a color attachment that can be sampled, without a staging buffer.
     pub fn create_render_image(
         device: &Device,
         allocator: &mut Allocator,
         extent: Extent3D,
         format: Format,
         sizeof: usize,
+    ) -> Result<VMAImage, Error> {
+        Self::create_render_image_with_usage(
+            device,
+            allocator,
+            extent,
+            format,
+            sizeof,
+            ImageUsageFlags::empty(),
+            AllocationLifetime::Persistent,
+        )
+    }
+
+    /// Same as [create_render_image](VMAImage::create_render_image) with the allocation category ```lifetime```,
+    /// e.g. [Transient](AllocationLifetime::Transient) for attachments that only live for a few frames.
+    pub fn create_render_image_with_lifetime(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        sizeof: usize,
+        lifetime: AllocationLifetime,
+    ) -> Result<VMAImage, Error> {
+        Self::create_render_image_with_usage(
+            device,
+            allocator,
+            extent,
+            format,
+            sizeof,
+            ImageUsageFlags::empty(),
+            lifetime,
+        )
+    }
+
+    /// Same as [create_render_image](VMAImage::create_render_image), adding ```additional_usage```.
+    pub(crate) fn create_render_image_with_usage(
+        device: &Device,
+        allocator: &mut Allocator,
+        extent: Extent3D,
+        format: Format,
+        sizeof: usize,
+        additional_usage: ImageUsageFlags,
+        lifetime: AllocationLifetime,
     ) -> Result<VMAImage, Error> {
         let image_info = ImageCreateInfo {
             image_type: ImageType::TYPE_2D,
@@ -226,33 +806,24 @@ impl VMAImage {
             array_layers: 1,
             samples: SampleCountFlags::TYPE_1,
             tiling: ImageTiling::OPTIMAL,
-            usage: ImageUsageFlags::COLOR_ATTACHMENT | ImageUsageFlags::SAMPLED,
+            usage: ImageUsageFlags::COLOR_ATTACHMENT
+                | ImageUsageFlags::SAMPLED
+                | ImageUsageFlags::TRANSFER_SRC
+                | additional_usage,
             sharing_mode: SharingMode::EXCLUSIVE,
             ..Default::default()
         };
 
-        let allocation_info = AllocationCreateDesc {
-            name: "Local_Image_Memory",
-            requirements: MemoryRequirements::default(),
-            location: MemoryLocation::GpuOnly,
-            linear: false,
-            allocation_scheme: AllocationScheme::GpuAllocatorManaged,
-        };
-
-        let staging_buffer = VMABuffer::create_cpu_to_gpu_buffer(
-            device,
-            allocator,
-            (extent.width * extent.height * extent.depth) as usize * sizeof,
-            BufferUsageFlags::TRANSFER_SRC,
-        )?;
+        let layer_staging_size = (extent.width * extent.height * extent.depth) as usize * sizeof;
 
         Self::new(
             device,
             allocator,
             image_info,
             ImageAspectFlags::COLOR,
-            allocation_info,
-            staging_buffer,
+            lifetime,
+            None,
+            layer_staging_size,
         )
     }
 
@@ -262,11 +833,12 @@ impl VMAImage {
     /// # extern crate winit;
     /// # use vku::*;
     /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
     /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
     /// # let size = [800_u32, 600_u32];
     /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
     /// # let create_info = VkInitCreateInfo::default();
-    /// # let mut init = VkInit::new(Some(&window), Some(size), create_info)?;
+    /// # let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
     /// let extent = Extent3D{width: 100, height: 100, depth: 1};
     /// let format = Format::R8G8B8A8_UNORM;
     /// let format_bytes = 4;
@@ -274,28 +846,89 @@ impl VMAImage {
     /// let image = init.create_empty_image(extent, format, format_bytes, aspect_flags)?;
     /// let data = [42_u32; 100*100];
     ///
-    /// image.set_staging_data(&data)?;
+    /// image.set_staging_data(&init.device, &data)?;
     /// # Ok::<(), vku::Error>(())
 
-    pub fn set_staging_data<T>(&self, data: &[T]) -> Result<(), Error>
+    pub fn set_staging_data<T>(&self, device: &Device, data: &[T]) -> Result<(), Error>
     where
         T: Sized + Copy + Clone,
     {
-        self.staging_buffer.set_data(0, data)
+        self.staging_buffer()?.set_data(device, 0, data)
+    }
+
+    /// Same as [set_staging_data](VMAImage::set_staging_data), writing the data of array layer ```layer``` only.
+    pub fn set_layer_staging_data<T>(
+        &self,
+        device: &Device,
+        layer: u32,
+        data: &[T],
+    ) -> Result<(), Error>
+    where
+        T: Sized + Copy + Clone,
+    {
+        // set_data offsets in elements of T.
+        let offset = layer as usize * self.layer_staging_size / size_of::<T>();
+        self.staging_buffer()?.set_data(device, offset, data)
+    }
+
+    /// Creates the staging buffer if the image has none yet, e.g. to upload into a [render image](VMAImage::create_render_image).
+    ///
+    /// Returns [NoStagingBuffer](Error::NoStagingBuffer) for [aliased](VMAImage::create_aliased) and external images, their texel size is unknown.
+    pub fn with_staging_buffer(
+        &mut self,
+        device: &Device,
+        allocator: &mut Allocator,
+    ) -> Result<(), Error> {
+        self.check(device, "with_staging_buffer");
+        if self.staging_buffer.is_some() {
+            return Ok(());
+        }
+        if self.layer_staging_size == 0 {
+            return Err(Error::NoStagingBuffer);
+        }
+        self.staging_buffer = Some(Self::create_staging_buffer(
+            device,
+            allocator,
+            self.layer_staging_size,
+            self.array_layers,
+        )?);
+        Ok(())
+    }
+
+    fn staging_buffer(&self) -> Result<&VMABuffer, Error> {
+        self.staging_buffer.as_ref().ok_or(Error::NoStagingBuffer)
+    }
+
+    fn create_staging_buffer(
+        device: &Device,
+        allocator: &mut Allocator,
+        layer_staging_size: usize,
+        array_layers: u32,
+    ) -> Result<VMABuffer, Error> {
+        VMABuffer::create_buffer_with_lifetime(
+            device,
+            allocator,
+            layer_staging_size * array_layers as usize,
+            BufferUsageFlags::TRANSFER_SRC,
+            MemoryLocation::CpuToGpu,
+            AllocationLifetime::Transient,
+        )
     }
 
     /// Enqueues cmd_copy_buffer_to_image from staging buffer to image.
     ///
     /// No barriers are issued. Image needs to be in ```ImageLayout::TRANSFER_DST_OPTIMAL```.
+    /// Images without a staging buffer log an error and enqueue nothing.
     /// ```
     /// # extern crate winit;
     /// # use vku::*;
     /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
     /// # let event_loop: winit::event_loop::EventLoop<()> = winit::event_loop::EventLoopBuilder::default().build();
     /// # let size = [800_u32, 600_u32];
     /// # let window = winit::window::WindowBuilder::new().with_inner_size(winit::dpi::LogicalSize{width: size[0], height: size[1]}).build(&event_loop).unwrap();
     /// # let create_info = VkInitCreateInfo::default();
-    /// # let mut init = VkInit::new(Some(&window), Some(size), create_info)?;
+    /// # let mut init = VkInit::new_windowed(&window, size, window.scale_factor(), create_info)?;
     /// # let setup_cmd_buffer_pool =
     /// #     init.create_cmd_pool(CmdType::Any)?;
     /// # let setup_cmd_buffer =
@@ -321,7 +954,7 @@ impl VMAImage {
     ///     );
     ///
     /// let data = [42_u32; 100*100];
-    /// image.set_staging_data(&data)?;
+    /// image.set_staging_data(&init.device, &data)?;
     /// image.enque_copy_from_staging_buffer_to_image(&init.device, &setup_cmd_buffer);
     ///
     /// init.end_and_submit_cmd_buffer(
@@ -340,43 +973,169 @@ impl VMAImage {
         device: &Device,
         cmd_buffer: &CommandBuffer,
     ) {
+        let Some(staging_buffer) =
+            self.staging_buffer_or_log("enque_copy_from_staging_buffer_to_image")
+        else {
+            return;
+        };
+        self.enque_copy_from_buffer_to_image(device, cmd_buffer, staging_buffer);
+    }
+
+    /// Same as [enque_copy_from_staging_buffer_to_image](VMAImage::enque_copy_from_staging_buffer_to_image), copying array layer ```layer``` only.
+    pub fn enqueue_copy_to_layer(&self, device: &Device, cmd_buffer: &CommandBuffer, layer: u32) {
+        self.check(device, "enqueue_copy_to_layer");
+        let Some(staging_buffer) = self.staging_buffer_or_log("enqueue_copy_to_layer") else {
+            return;
+        };
+        staging_buffer.check(device, "enqueue_copy_to_layer");
+        let region = self.layer_copy_region(layer);
+        unsafe {
+            device.cmd_copy_buffer_to_image(
+                *cmd_buffer,
+                staging_buffer.buffer,
+                self.image,
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+                &[region],
+            );
+        }
+    }
+
+    /// Enqueues cmd_copy_buffer_to_image from a [PooledStaging] buffer to image.
+    ///
+    /// Same as [enque_copy_from_staging_buffer_to_image](VMAImage::enque_copy_from_staging_buffer_to_image), but without using the embedded staging buffer.
+    /// The pooled buffer must only be released with the fence of this submission.
+    pub fn enque_copy_from_pooled_staging_to_image(
+        &self,
+        device: &Device,
+        cmd_buffer: &CommandBuffer,
+        staging: &PooledStaging,
+    ) {
+        self.enque_copy_from_buffer_to_image(device, cmd_buffer, &staging.buffer);
+    }
+
+    /// Enqueues cmd_copy_image_to_buffer of mip level ```mip``` of all array layers into ```dst```, e.g. for [download_image](VkInit::download_image).
+    ///
+    /// Rows and layers are tightly packed without padding, ```dst``` needs at least [readback_size](VMAImage::readback_size) bytes.
+    /// No barriers are issued. Image needs to be in ```ImageLayout::TRANSFER_SRC_OPTIMAL```.
+    /// Depth/stencil images copy their depth aspect.
+    pub fn enqueue_copy_image_to_buffer(
+        &self,
+        device: &Device,
+        cmd_buffer: &CommandBuffer,
+        dst: &VMABuffer,
+        mip: u32,
+    ) -> Result<(), Error> {
+        self.check(device, "enqueue_copy_image_to_buffer");
+        dst.check(device, "enqueue_copy_image_to_buffer");
+        let size = self.readback_size(mip)? as u64;
+        if size > dst.size() {
+            return Err(Error::BufferWriteOutOfBounds {
+                requested: size,
+                available: dst.size(),
+            });
+        }
+
+        let aspect_mask = match self.aspect_flags.contains(ImageAspectFlags::DEPTH) {
+            true => ImageAspectFlags::DEPTH,
+            false => self.aspect_flags,
+        };
+        let region = BufferImageCopy::builder()
+            .buffer_offset(0)
+            .buffer_row_length(0)
+            .buffer_image_height(0)
+            .image_subresource(
+                ImageSubresourceLayers::builder()
+                    .aspect_mask(aspect_mask)
+                    .mip_level(mip)
+                    .base_array_layer(0)
+                    .layer_count(self.array_layers)
+                    .build(),
+            )
+            .image_extent(self.mip_extent(mip))
+            .build();
         unsafe {
-            let buffer_copy_regions = BufferImageCopy::builder()
-                .buffer_offset(0)
-                .buffer_row_length(0)
-                .buffer_image_height(0)
-                .image_subresource(
-                    ImageSubresourceLayers::builder()
-                        .aspect_mask(ImageAspectFlags::COLOR)
-                        .mip_level(0)
-                        .base_array_layer(0)
-                        .layer_count(1)
-                        .build(),
-                )
-                .image_extent(Extent3D {
-                    width: self.extent.width,
-                    height: self.extent.height,
-                    depth: 1,
-                })
-                .build();
+            device.cmd_copy_image_to_buffer(
+                *cmd_buffer,
+                self.image,
+                ImageLayout::TRANSFER_SRC_OPTIMAL,
+                dst.buffer,
+                &[region],
+            );
+        }
+        Ok(())
+    }
 
+    /// Bytes of mip level ```mip``` of all array layers, tightly packed.
+    ///
+    /// Returns [UnknownTexelSize](Error::UnknownTexelSize) for aliased and external images of formats vku can't size.
+    pub fn readback_size(&self, mip: u32) -> Result<usize, Error> {
+        let extent = self.mip_extent(mip);
+        let texels = (extent.width * extent.height * extent.depth) as usize;
+        let texel_size = match self.layer_staging_size {
+            0 => texel_size(self.format).ok_or(Error::UnknownTexelSize(self.format))?,
+            layer_size => {
+                layer_size / (self.extent.width * self.extent.height * self.extent.depth) as usize
+            }
+        };
+        Ok(texels * texel_size * self.array_layers as usize)
+    }
+
+    fn staging_buffer_or_log(&self, operation: &str) -> Option<&VMABuffer> {
+        if self.staging_buffer.is_none() {
+            error!("VMAImage::{operation} called on an image without a staging buffer, see VMAImage::with_staging_buffer");
+        }
+        self.staging_buffer.as_ref()
+    }
+
+    fn enque_copy_from_buffer_to_image(
+        &self,
+        device: &Device,
+        cmd_buffer: &CommandBuffer,
+        buffer: &VMABuffer,
+    ) {
+        self.check(device, "enque_copy_from_buffer_to_image");
+        buffer.check(device, "enque_copy_from_buffer_to_image");
+        let regions: Vec<BufferImageCopy> = (0..self.array_layers)
+            .map(|layer| self.layer_copy_region(layer))
+            .collect();
+        unsafe {
             device.cmd_copy_buffer_to_image(
                 *cmd_buffer,
-                self.staging_buffer.buffer,
+                buffer.buffer,
                 self.image,
                 ImageLayout::TRANSFER_DST_OPTIMAL,
-                &[buffer_copy_regions],
+                &regions,
             );
         }
     }
 
+    fn layer_copy_region(&self, layer: u32) -> BufferImageCopy {
+        BufferImageCopy::builder()
+            .buffer_offset((layer as usize * self.layer_staging_size) as u64)
+            .buffer_row_length(0)
+            .buffer_image_height(0)
+            .image_subresource(
+                ImageSubresourceLayers::builder()
+                    .aspect_mask(ImageAspectFlags::COLOR)
+                    .mip_level(0)
+                    .base_array_layer(layer)
+                    .layer_count(1)
+                    .build(),
+            )
+            .image_extent(Extent3D {
+                width: self.extent.width,
+                height: self.extent.height,
+                depth: 1,
+            })
+            .build()
+    }
+
     /// Gets appropriate ```ImageMemoryBarrier2``` from current layout to ```dst_layout``` for this image.
     ///
     /// Current layout is set to ```dst_layout``` after returning this barrier.
     ///
     /// **Defaults**:
-    /// - src_queue: 0
-    /// - dst_queue: 0
+    /// - src_queue and dst_queue: ```QUEUE_FAMILY_IGNORED```, unless both are provided.
 
     pub fn get_image_layout_transition_barrier2(
         &mut self,
@@ -384,11 +1143,13 @@ impl VMAImage {
         src_queue: Option<u32>,
         dst_queue: Option<u32>,
     ) -> Result<ImageMemoryBarrier2, Error> {
+        self.check_alive("get_image_layout_transition_barrier2");
         let barrier = image_layout_transitions::get_image_layout_transition_barrier2(
             &self.image,
             self.current_layout,
             dst_layout,
             self.aspect_flags,
+            self.array_layers,
             src_queue,
             dst_queue,
         );
@@ -396,25 +1157,292 @@ impl VMAImage {
 
         barrier
     }
+
+    /// Same as [get_image_layout_transition_barrier2](VMAImage::get_image_layout_transition_barrier2), but always transfers ownership from queue family ```src_queue``` to ```dst_queue```.
+    ///
+    /// Record the returned barrier on both queues, the layout transition executes once. See [OwnershipTransfer](crate::OwnershipTransfer) for the pairing.
+    pub fn get_image_layout_transition_barrier2_with_ownership_transfer(
+        &mut self,
+        dst_layout: ImageLayout,
+        src_queue: u32,
+        dst_queue: u32,
+    ) -> Result<ImageMemoryBarrier2, Error> {
+        self.get_image_layout_transition_barrier2(dst_layout, Some(src_queue), Some(dst_queue))
+    }
+
+    /// Same as [get_image_layout_transition_barrier2](VMAImage::get_image_layout_transition_barrier2) for transitions into and out of
+    /// the [feedback_loop_layout](VkInit::feedback_loop_layout), including its ```ImageLayout::GENERAL``` fallback.
+    pub fn feedback_loop_barrier(
+        &mut self,
+        dst_layout: ImageLayout,
+    ) -> Result<ImageMemoryBarrier2, Error> {
+        self.check_alive("feedback_loop_barrier");
+        let barrier = image_layout_transitions::feedback_loop_barrier(
+            &self.image,
+            self.current_layout,
+            dst_layout,
+            self.aspect_flags,
+            self.array_layers,
+        );
+        self.current_layout = dst_layout;
+
+        barrier
+    }
+
+    /// Extent of mip level ```level```, at least 1 in each dimension.
+    pub fn mip_extent(&self, level: u32) -> Extent3D {
+        Extent3D {
+            width: (self.extent.width >> level).max(1),
+            height: (self.extent.height >> level).max(1),
+            depth: (self.extent.depth >> level).max(1),
+        }
+    }
+
+    pub(crate) fn check(&self, device: &Device, operation: &str) {
+        self.check_alive(operation);
+        self.provenance
+            .check_instance(device, "VMAImage", operation);
+    }
+
+    fn check_alive(&self, operation: &str) {
+        self.provenance
+            .check_alive(self.image != Image::null(), "VMAImage", operation);
+    }
 }
 
 impl VkInit {
     /// Shortcut - see [VMAImage](VMAImage::create_empty_image) for example.
 
     pub fn create_empty_image(
-        &mut self,
+        &self,
         extent: Extent3D,
         format: Format,
         format_sizeof: usize,
         aspect_mask: ImageAspectFlags,
     ) -> Result<VMAImage, Error> {
-        VMAImage::create_empty_image(
-            &self.device,
-            &mut self.allocator,
-            extent,
-            format,
-            format_sizeof,
-            aspect_mask,
-        )
+        self.allocate_with_retry(|device, allocator| {
+            VMAImage::create_empty_image(
+                device,
+                allocator,
+                extent,
+                format,
+                format_sizeof,
+                aspect_mask,
+            )
+        })
+    }
+
+    /// Creates an image with any [VMAImage] constructor and names its allocation and debug objects ```name```.
+    ///
+    /// Images created without a name get a generated allocation name like ```Persistent_Local_Image_7```.
+    /// ```
+    /// # use vku::*;
+    /// # use ash::vk::*;
+    /// # if VkInit::load_entry().is_err() { return Ok(()); }
+    /// # let init_info = VkInitCreateInfo::default();
+    /// let mut init = VkInit::new_headless(init_info)?;
+    /// let extent = Extent3D { width: 512, height: 512, depth: 1 };
+    /// let mut atlas = init.create_named_image("Texture_Atlas", |device, allocator| {
+    ///     VMAImage::create_render_image(device, allocator, extent, Format::R8G8B8A8_UNORM, 4)
+    /// })?;
+    /// # atlas.destroy(&init.device, &mut init.allocator.lock())?;
+    /// # Ok::<(), vku::Error>(())
+    /// ```
+    pub fn create_named_image(
+        &self,
+        name: &str,
+        create: impl FnMut(&Device, &mut Allocator) -> Result<VMAImage, Error>,
+    ) -> Result<VMAImage, Error> {
+        let mut image = self.allocate_with_retry(create)?;
+        if !image.allocation.is_null() {
+            self.allocator
+                .lock()
+                .rename_allocation(&mut image.allocation, &image.lifetime.allocation_name(name))?;
+        }
+        image.set_debug_object_name(self, name.to_string())?;
+        Ok(image)
+    }
+
+    /// Shortcut - see [VMAImage](VMAImage::create_mipped_image).
+    ///
+    /// Adds ```ImageUsageFlags::STORAGE``` if the format needs the [compute mip generation](crate::MipGeneration::Compute).
+    pub fn create_mipped_image(
+        &self,
+        extent: Extent3D,
+        format: Format,
+        format_sizeof: usize,
+        aspect_mask: ImageAspectFlags,
+        mip_levels: u32,
+    ) -> Result<VMAImage, Error> {
+        let additional_usage = match self.mip_generation_for(format) {
+            Some(MipGeneration::Compute { .. }) => ImageUsageFlags::STORAGE,
+            _ => ImageUsageFlags::empty(),
+        };
+        self.allocate_with_retry(|device, allocator| {
+            VMAImage::create_mipped_image_with_usage(
+                device,
+                allocator,
+                extent,
+                format,
+                format_sizeof,
+                aspect_mask,
+                mip_levels,
+                additional_usage,
+            )
+        })
+    }
+
+    /// Shortcut - see [VMAImage](VMAImage::create_mipped_image_with_lifetime).
+    pub fn create_mipped_image_with_lifetime(
+        &self,
+        extent: Extent3D,
+        format: Format,
+        format_sizeof: usize,
+        aspect_mask: ImageAspectFlags,
+        mip_levels: u32,
+        lifetime: AllocationLifetime,
+    ) -> Result<VMAImage, Error> {
+        self.allocate_with_retry(|device, allocator| {
+            VMAImage::create_mipped_image_with_lifetime(
+                device,
+                allocator,
+                extent,
+                format,
+                format_sizeof,
+                aspect_mask,
+                mip_levels,
+                lifetime,
+            )
+        })
+    }
+
+    /// Shortcut - see [VMAImage](VMAImage::create_compressed_image).
+    pub fn create_compressed_image(
+        &self,
+        extent: Extent3D,
+        format: Format,
+        mip_levels: u32,
+    ) -> Result<VMAImage, Error> {
+        self.allocate_with_retry(|device, allocator| {
+            VMAImage::create_compressed_image(device, allocator, extent, format, mip_levels)
+        })
+    }
+
+    /// Shortcut - see [VMAImage](VMAImage::create_render_image_with_lifetime).
+    pub fn create_render_image_with_lifetime(
+        &self,
+        extent: Extent3D,
+        format: Format,
+        format_sizeof: usize,
+        lifetime: AllocationLifetime,
+    ) -> Result<VMAImage, Error> {
+        self.allocate_with_retry(|device, allocator| {
+            VMAImage::create_render_image_with_lifetime(
+                device,
+                allocator,
+                extent,
+                format,
+                format_sizeof,
+                lifetime,
+            )
+        })
+    }
+
+    /// Shortcut - see [VMAImage](VMAImage::create_msaa_render_image).
+    pub fn create_msaa_render_image(
+        &self,
+        extent: Extent3D,
+        format: Format,
+        format_sizeof: usize,
+        samples: SampleCountFlags,
+    ) -> Result<VMAImage, Error> {
+        self.allocate_with_retry(|device, allocator| {
+            VMAImage::create_msaa_render_image(
+                device,
+                allocator,
+                extent,
+                format,
+                format_sizeof,
+                samples,
+            )
+        })
+    }
+
+    /// Shortcut - see [VMAImage](VMAImage::create_msaa_depth_image).
+    pub fn create_msaa_depth_image(
+        &self,
+        extent: Extent3D,
+        format: Format,
+        format_sizeof: usize,
+        samples: SampleCountFlags,
+    ) -> Result<VMAImage, Error> {
+        self.allocate_with_retry(|device, allocator| {
+            VMAImage::create_msaa_depth_image(
+                device,
+                allocator,
+                extent,
+                format,
+                format_sizeof,
+                samples,
+            )
+        })
+    }
+
+    /// Shortcut - see [VMAImage](VMAImage::create_image_array).
+    pub fn create_image_array(
+        &self,
+        extent: Extent3D,
+        format: Format,
+        format_sizeof: usize,
+        aspect_mask: ImageAspectFlags,
+        layers: u32,
+    ) -> Result<VMAImage, Error> {
+        self.allocate_with_retry(|device, allocator| {
+            VMAImage::create_image_array(
+                device,
+                allocator,
+                extent,
+                format,
+                format_sizeof,
+                aspect_mask,
+                layers,
+            )
+        })
+    }
+
+    /// Shortcut - see [VMAImage](VMAImage::create_cubemap).
+    pub fn create_cubemap(
+        &self,
+        extent: Extent3D,
+        format: Format,
+        format_sizeof: usize,
+    ) -> Result<VMAImage, Error> {
+        self.allocate_with_retry(|device, allocator| {
+            VMAImage::create_cubemap(device, allocator, extent, format, format_sizeof)
+        })
+    }
+
+    /// Same as [create_render_image](VMAImage::create_render_image), usable in the [feedback_loop_layout](VkInit::feedback_loop_layout).
+    pub fn create_feedback_loop_image(
+        &self,
+        extent: Extent3D,
+        format: Format,
+        format_sizeof: usize,
+    ) -> Result<VMAImage, Error> {
+        let usage = match self.physical_device_info.attachment_feedback_loop_layout {
+            true => ImageUsageFlags::ATTACHMENT_FEEDBACK_LOOP_EXT,
+            false => ImageUsageFlags::empty(),
+        };
+        self.allocate_with_retry(|device, allocator| {
+            VMAImage::create_render_image_with_usage(
+                device,
+                allocator,
+                extent,
+                format,
+                format_sizeof,
+                usage,
+                AllocationLifetime::Persistent,
+            )
+        })
     }
 }
@@ -0,0 +1,260 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::{imports::*, VkInit};
+
+/// Debug labels kept for hang reports.
+const LABEL_HISTORY: usize = 16;
+
+/// Tracks outstanding GPU waits for [gpu_watchdog](crate::VkInitCreateInfo::gpu_watchdog).
+///
+/// Fence waits time out themselves, presents are watched by a monitoring thread that can only log.
+#[derive(Default)]
+pub(crate) struct GpuWatchdog {
+    threshold: Option<Duration>,
+    state: Arc<Mutex<WatchdogState>>,
+    stop: Arc<AtomicBool>,
+    monitor: Option<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct WatchdogState {
+    command_buffer_names: HashMap<u64, String>,
+    last_submitted: Vec<String>,
+    labels: VecDeque<String>,
+    outstanding: Option<(&'static str, Instant)>,
+    reported: bool,
+}
+
+impl GpuWatchdog {
+    pub(crate) fn new(threshold: Option<Duration>) -> Self {
+        let Some(threshold) = threshold else {
+            return Self::default();
+        };
+
+        let state = Arc::new(Mutex::new(WatchdogState::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let monitor = {
+            let state = state.clone();
+            let stop = stop.clone();
+            let interval = (threshold / 4).max(Duration::from_millis(10));
+            std::thread::Builder::new()
+                .name(String::from("vku-gpu-watchdog"))
+                .spawn(move || {
+                    while !stop.load(Ordering::Acquire) {
+                        std::thread::park_timeout(interval);
+                        let Ok(mut state) = state.lock() else {
+                            return;
+                        };
+                        let Some((operation, since)) = state.outstanding else {
+                            continue;
+                        };
+                        if state.reported || since.elapsed() < threshold {
+                            continue;
+                        }
+                        state.reported = true;
+                        error!(
+                            "GPU hang suspected: {operation} outstanding for {:?}\n{}",
+                            since.elapsed(),
+                            state.describe()
+                        );
+                    }
+                })
+                .ok()
+        };
+        trace!("Started GPU watchdog with a threshold of {threshold:?}");
+
+        Self {
+            threshold: Some(threshold),
+            state,
+            stop,
+            monitor,
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.threshold.is_some()
+    }
+
+    pub(crate) fn name_command_buffer(&self, handle: u64, name: String) {
+        if let (true, Ok(mut state)) = (self.is_enabled(), self.state.lock()) {
+            state.command_buffer_names.insert(handle, name);
+        }
+    }
+
+    pub(crate) fn begin_label(&self, name: &str) {
+        if let (true, Ok(mut state)) = (self.is_enabled(), self.state.lock()) {
+            if state.labels.len() == LABEL_HISTORY {
+                state.labels.pop_front();
+            }
+            state
+                .labels
+                .push_back(name.trim_end_matches('\0').to_string());
+        }
+    }
+
+    pub(crate) fn submitted(&self, cmd_buffers: &[CommandBuffer]) {
+        if let (true, Ok(mut state)) = (self.is_enabled(), self.state.lock()) {
+            state.last_submitted = cmd_buffers
+                .iter()
+                .map(|cmd_buffer| {
+                    state
+                        .command_buffer_names
+                        .get(&cmd_buffer.as_raw())
+                        .cloned()
+                        .unwrap_or_else(|| format!("{cmd_buffer:?}"))
+                })
+                .collect();
+        }
+    }
+
+    /// Marks ```operation``` as outstanding for the monitoring thread until [finish](GpuWatchdog::finish).
+    pub(crate) fn start(&self, operation: &'static str) {
+        if let (true, Ok(mut state)) = (self.is_enabled(), self.state.lock()) {
+            state.outstanding = Some((operation, Instant::now()));
+            state.reported = false;
+        }
+    }
+
+    pub(crate) fn finish(&self) {
+        if let (true, Ok(mut state)) = (self.is_enabled(), self.state.lock()) {
+            state.outstanding = None;
+        }
+    }
+
+    fn describe(&self) -> String {
+        self.state
+            .lock()
+            .map(|state| state.describe())
+            .unwrap_or_default()
+    }
+}
+
+impl WatchdogState {
+    fn describe(&self) -> String {
+        format!(
+            "Last submitted command buffers: {:?}\nLast debug labels begun: {:?}",
+            self.last_submitted, self.labels
+        )
+    }
+}
+
+impl Drop for GpuWatchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(monitor) = self.monitor.take() {
+            monitor.thread().unpark();
+            let _ = monitor.join();
+        }
+    }
+}
+
+impl VkInit {
+    /// Waits for all ```fences```, timing out after the [gpu_watchdog](crate::VkInitCreateInfo::gpu_watchdog) threshold if set.
+    ///
+    /// Logs a hang report and returns [GpuHangSuspected](Error::GpuHangSuspected) on timeout.
+    /// On ```ERROR_DEVICE_LOST``` the [device lost diagnostics](VkInit::log_device_lost_diagnostics) are logged.
+    pub fn wait_for_fences_watched(
+        &self,
+        fences: &[Fence],
+        operation: &'static str,
+    ) -> Result<(), Error> {
+        let timeout = self
+            .watchdog
+            .threshold
+            .map(|threshold| threshold.as_nanos().min(u64::MAX as u128) as u64)
+            .unwrap_or(u64::MAX);
+        let started = Instant::now();
+        match unsafe { self.device.wait_for_fences(fences, true, timeout) } {
+            Ok(()) => Ok(()),
+            Err(ash::vk::Result::TIMEOUT) => {
+                let waited = started.elapsed();
+                self.log_gpu_hang_report(operation, waited);
+                Err(Error::GpuHangSuspected { operation, waited })
+            }
+            Err(ash::vk::Result::ERROR_DEVICE_LOST) => {
+                self.log_device_lost_diagnostics();
+                Err(ash::vk::Result::ERROR_DEVICE_LOST.into())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn log_gpu_hang_report(&self, operation: &str, waited: Duration) {
+        error!(
+            "GPU hang suspected: {operation} waited {waited:?} on {}\n{}\nMemory: {:?}",
+            self.physical_device_info.name,
+            self.watchdog.describe(),
+            self.memory_report()
+        );
+    }
+
+    /// Logs the fault description, addresses and vendor infos of ```VK_EXT_device_fault``` after a device loss.
+    pub(crate) fn log_device_fault(&self) {
+        error!("Device lost on {}", self.physical_device_info.name);
+        if !self.physical_device_info.device_fault {
+            return;
+        }
+
+        unsafe {
+            let fault_fn = ExtDeviceFaultFn::load(|name| {
+                std::mem::transmute(
+                    self.instance
+                        .get_device_proc_addr(self.device.handle(), name.as_ptr()),
+                )
+            });
+            let mut counts = DeviceFaultCountsEXT::default();
+            let result = (fault_fn.get_device_fault_info_ext)(
+                self.device.handle(),
+                &mut counts,
+                std::ptr::null_mut(),
+            );
+            if result != ash::vk::Result::SUCCESS {
+                warn!("Device fault counts could not be queried: {result}");
+                return;
+            }
+
+            let mut address_infos =
+                vec![DeviceFaultAddressInfoEXT::default(); counts.address_info_count as usize];
+            let mut vendor_infos =
+                vec![DeviceFaultVendorInfoEXT::default(); counts.vendor_info_count as usize];
+            counts.vendor_binary_size = 0;
+            let mut info = DeviceFaultInfoEXT {
+                p_address_infos: address_infos.as_mut_ptr(),
+                p_vendor_infos: vendor_infos.as_mut_ptr(),
+                ..Default::default()
+            };
+            let result =
+                (fault_fn.get_device_fault_info_ext)(self.device.handle(), &mut counts, &mut info);
+            if result != ash::vk::Result::SUCCESS && result != ash::vk::Result::INCOMPLETE {
+                warn!("Device fault info could not be queried: {result}");
+                return;
+            }
+
+            error!(
+                "Device fault: {}",
+                char_array_to_string(&info.description).unwrap_or_default()
+            );
+            for address in address_infos
+                .iter()
+                .take(counts.address_info_count as usize)
+            {
+                error!(
+                    "Fault address {:?}: {:#x} (precision {:#x})",
+                    address.address_type, address.reported_address, address.address_precision
+                );
+            }
+            for vendor in vendor_infos.iter().take(counts.vendor_info_count as usize) {
+                error!(
+                    "Vendor fault {}: code {:#x}, data {:#x}",
+                    char_array_to_string(&vendor.description).unwrap_or_default(),
+                    vendor.vendor_fault_code,
+                    vendor.vendor_fault_data
+                );
+            }
+        }
+    }
+}
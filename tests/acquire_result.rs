@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::{Handle, Image, ImageView, Result as VkResult};
+    use vku::{AcquireResult, Error};
+
+    fn swapchain_handles() -> (Vec<Image>, Vec<ImageView>) {
+        let images = (1..=3).map(Image::from_raw).collect();
+        let views = (11..=13).map(ImageView::from_raw).collect();
+        (images, views)
+    }
+
+    #[test]
+    fn acquire_maps_out_of_date_and_suboptimal() {
+        let (images, views) = swapchain_handles();
+
+        let ready = AcquireResult::from_vk(Ok((1, false)), &images, &views).unwrap();
+        assert_eq!(
+            ready,
+            AcquireResult::Ready {
+                index: 1,
+                image: images[1],
+                view: views[1],
+                suboptimal: false,
+            }
+        );
+
+        let suboptimal = AcquireResult::from_vk(Ok((2, true)), &images, &views).unwrap();
+        assert!(matches!(
+            suboptimal,
+            AcquireResult::Ready {
+                index: 2,
+                suboptimal: true,
+                ..
+            }
+        ));
+
+        let out_of_date =
+            AcquireResult::from_vk(Err(VkResult::ERROR_OUT_OF_DATE_KHR), &images, &views);
+        assert_eq!(out_of_date.unwrap(), AcquireResult::OutOfDate);
+
+        let timeout = AcquireResult::from_vk(Err(VkResult::TIMEOUT), &images, &views);
+        assert!(matches!(timeout, Err(Error::VkError(VkResult::TIMEOUT))));
+    }
+
+    #[test]
+    fn present_maps_out_of_date_and_suboptimal() {
+        assert!(!Error::from_present(Ok(false)).unwrap());
+        assert!(Error::from_present(Ok(true)).unwrap());
+        assert!(matches!(
+            Error::from_present(Err(VkResult::ERROR_OUT_OF_DATE_KHR)),
+            Err(Error::SwapchainOutOfDate)
+        ));
+        assert!(matches!(
+            Error::from_present(Err(VkResult::ERROR_SURFACE_LOST_KHR)),
+            Err(Error::VkError(VkResult::ERROR_SURFACE_LOST_KHR))
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, VMAImage};
+
+    #[test]
+    fn aliased_image_shares_owner_memory() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let extent = Extent3D {
+            width: 256,
+            height: 256,
+            depth: 1,
+        };
+        let mut owner = vk_init.create_empty_image(
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+            ImageAspectFlags::COLOR,
+        )?;
+        let owner_info = owner.allocation_info();
+        assert!(owner_info.size >= 256 * 256 * 4);
+        assert!(owner_info.alignment > 0);
+
+        let image_info = ImageCreateInfo::builder()
+            .image_type(ImageType::TYPE_2D)
+            .format(Format::R8G8B8A8_UNORM)
+            .extent(Extent3D {
+                width: 128,
+                height: 128,
+                depth: 1,
+            })
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(SampleCountFlags::TYPE_1)
+            .tiling(ImageTiling::OPTIMAL)
+            .usage(ImageUsageFlags::SAMPLED | ImageUsageFlags::TRANSFER_DST)
+            .sharing_mode(SharingMode::EXCLUSIVE)
+            .build();
+
+        let mut alias = VMAImage::create_aliased(
+            &vk_init.device,
+            &vk_init.physical_device_info.memory_props,
+            &owner.allocation,
+            0,
+            &image_info,
+            ImageAspectFlags::COLOR,
+        )?;
+        alias.set_debug_object_name(&vk_init, String::from("Alias"))?;
+        let alias_info = alias.allocation_info();
+        assert!(alias.is_aliased());
+        assert_eq!(alias_info.memory, owner_info.memory);
+        assert_eq!(alias_info.offset, owner_info.offset);
+        assert!(alias_info.size <= owner_info.size);
+
+        let barrier = alias.aliasing_barrier(
+            ImageLayout::TRANSFER_DST_OPTIMAL,
+            PipelineStageFlags2::TRANSFER,
+            AccessFlags2::TRANSFER_WRITE,
+        );
+        assert_eq!(barrier.old_layout, ImageLayout::UNDEFINED);
+        assert_eq!(alias.current_layout, ImageLayout::TRANSFER_DST_OPTIMAL);
+
+        assert!(matches!(
+            VMAImage::create_aliased(
+                &vk_init.device,
+                &vk_init.physical_device_info.memory_props,
+                &owner.allocation,
+                owner_info.size,
+                &image_info,
+                ImageAspectFlags::COLOR,
+            ),
+            Err(Error::AliasOutOfBounds { .. }) | Err(Error::AliasMisaligned { .. })
+        ));
+        if alias_info.alignment > 1 {
+            assert!(matches!(
+                VMAImage::create_aliased(
+                    &vk_init.device,
+                    &vk_init.physical_device_info.memory_props,
+                    &owner.allocation,
+                    1,
+                    &image_info,
+                    ImageAspectFlags::COLOR,
+                ),
+                Err(Error::AliasMisaligned { offset: 1, .. })
+            ));
+        }
+
+        alias.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        owner.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use gpu_allocator::MemoryLocation;
+    use std::collections::VecDeque;
+    use std::result::Result;
+    use vku::{AllocationLifetime, Error, LifetimeReport, VMABuffer, VkInit, VkInitCreateInfo};
+
+    const ITERATIONS: usize = 2000;
+    const PERSISTENT_SIZE: usize = 16 * 1024;
+    const TRANSIENT_SIZE: usize = 256 * 1024;
+    const TRANSIENT_IN_FLIGHT: usize = 3;
+
+    /// Interleaves kept persistent buffers with short-lived buffers of ```transient_lifetime```.
+    fn soak(
+        vk_init: &mut VkInit,
+        transient_lifetime: AllocationLifetime,
+    ) -> Result<LifetimeReport, Error> {
+        let usage = BufferUsageFlags::STORAGE_BUFFER;
+        let mut persistent: Vec<VMABuffer> = vec![];
+        let mut transient: VecDeque<VMABuffer> = VecDeque::new();
+        for iteration in 0..ITERATIONS {
+            persistent.push(vk_init.create_buffer_with_lifetime(
+                PERSISTENT_SIZE,
+                usage,
+                MemoryLocation::GpuOnly,
+                AllocationLifetime::Persistent,
+            )?);
+            transient.push_back(vk_init.create_buffer_with_lifetime(
+                TRANSIENT_SIZE + (iteration % 4) * PERSISTENT_SIZE,
+                usage,
+                MemoryLocation::GpuOnly,
+                transient_lifetime,
+            )?);
+            if transient.len() > TRANSIENT_IN_FLIGHT {
+                let mut buffer = transient.pop_front().unwrap();
+                buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+            }
+        }
+
+        let in_flight = vk_init.memory_report().lifetimes;
+        assert_eq!(
+            in_flight.usage(transient_lifetime).allocations,
+            match transient_lifetime {
+                AllocationLifetime::Persistent => ITERATIONS + TRANSIENT_IN_FLIGHT,
+                _ => TRANSIENT_IN_FLIGHT,
+            }
+        );
+
+        for mut buffer in transient.drain(..) {
+            buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        }
+        let report = vk_init.memory_report().lifetimes;
+        for mut buffer in persistent {
+            buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        }
+        Ok(report)
+    }
+
+    #[test]
+    fn transient_allocations_bound_fragmentation() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+
+        let shared = soak(&mut vk_init, AllocationLifetime::Persistent)?;
+        let separated = soak(&mut vk_init, AllocationLifetime::Transient)?;
+        assert_eq!(separated.persistent.allocations, ITERATIONS);
+        assert!(separated.fragmented_bytes <= shared.fragmented_bytes);
+        assert!((separated.largest_free_block as usize) < TRANSIENT_SIZE);
+        assert!(vk_init.memory_report().lifetimes.transient.allocations == 0);
+
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, VkInit};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    const MAX_ALLOCATIONS: usize = 64;
+
+    fn retrying_vk_init() -> Result<VkInit, Error> {
+        use vku::VkInitCreateInfo;
+        use winit::dpi::LogicalSize;
+        use winit::event_loop::{EventLoop, EventLoopBuilder};
+        use winit::window::WindowBuilder;
+
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+
+        let mut create_info = VkInitCreateInfo::default();
+        create_info.retry_failed_allocations = true;
+        VkInit::new(Some(&window), Some(size), create_info)
+    }
+
+    #[test]
+    fn retry_succeeds_after_draining_deletion_queue() -> Result<(), Error> {
+        let vk_init = retrying_vk_init()?;
+        let memory_props = vk_init.physical_device_info.memory_props;
+        let largest_heap = memory_props.memory_heaps[..memory_props.memory_heap_count as usize]
+            .iter()
+            .filter(|heap| heap.flags.contains(MemoryHeapFlags::DEVICE_LOCAL))
+            .map(|heap| heap.size)
+            .max()
+            .unwrap();
+        let chunk = (largest_heap / 4).min(1 << 30) as usize;
+        let usage = BufferUsageFlags::STORAGE_BUFFER;
+
+        // Nothing to drain yet, the retry fails and reports both attempts.
+        let mut buffers = vec![];
+        let mut exhausted = false;
+        while buffers.len() < MAX_ALLOCATIONS {
+            match vk_init.create_local_buffer(chunk, usage) {
+                Ok(buffer) => buffers.push(buffer),
+                Err(Error::GpuAllocRetryFailed { before, after, .. }) => {
+                    assert_eq!(before.pending_deletions, 0);
+                    assert_eq!(after.pending_deletions, 0);
+                    exhausted = true;
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let fence = vk_init.create_fence()?;
+        for buffer in buffers {
+            vk_init.defer_destroy_buffer(buffer, fence);
+        }
+        if !exhausted {
+            // Device memory is overcommitted, the failure path can't be provoked.
+            vk_init.collect_deletion_queue()?;
+            vk_init.destroy_fence(&fence)?;
+            return Ok(());
+        }
+
+        let mut buffer = vk_init.create_local_buffer(chunk, usage)?;
+        assert!(vk_init.deletion_queue.lock().unwrap().is_empty());
+
+        buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy_fence(&fence)?;
+        Ok(())
+    }
+}
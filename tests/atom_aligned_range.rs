@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use vku::AllocationInfo;
+
+    const ATOM: u64 = 64;
+
+    fn allocation(offset: u64, size: u64) -> AllocationInfo {
+        AllocationInfo {
+            memory: DeviceMemory::null(),
+            offset,
+            size,
+            alignment: 256,
+            memory_type_bits: 1,
+            memory_properties: MemoryPropertyFlags::HOST_VISIBLE,
+        }
+    }
+
+    fn range(info: &AllocationInfo, offset: u64, size: u64) -> (u64, u64) {
+        let range = info.atom_aligned_range(offset, size, ATOM);
+        (range.offset, range.size)
+    }
+
+    #[test]
+    fn widens_offset_and_size_to_atoms() {
+        let info = allocation(256, 512);
+        assert_eq!(range(&info, 0, 4), (256, 64));
+        assert_eq!(range(&info, 10, 20), (256, 64));
+        assert_eq!(range(&info, 100, 100), (320, 192));
+        assert_eq!(range(&info, 64, 64), (320, 64));
+    }
+
+    #[test]
+    fn stays_inside_the_allocation() {
+        let info = allocation(256, 512);
+        assert_eq!(range(&info, 500, 100), (704, 64));
+        assert_eq!(range(&info, 70, WHOLE_SIZE), (320, 448));
+        assert_eq!(range(&info, 0, WHOLE_SIZE), (256, 512));
+    }
+
+    #[test]
+    fn dedicated_allocations_end_with_their_memory() {
+        let info = allocation(0, 100);
+        assert_eq!(range(&info, 90, 4), (64, 36));
+        assert_eq!(range(&info, 0, 100), (0, 100));
+        assert_eq!(range(&info, 10, WHOLE_SIZE), (0, 100));
+    }
+}
@@ -0,0 +1,65 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::Error;
+
+    #[test]
+    fn barrier_queue_families() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+
+        let mut buffer = vk_init.create_local_buffer(1024, BufferUsageFlags::STORAGE_BUFFER)?;
+        let stages = (
+            PipelineStageFlags2::COPY,
+            PipelineStageFlags2::COMPUTE_SHADER,
+        );
+        let accesses = (AccessFlags2::TRANSFER_WRITE, AccessFlags2::SHADER_READ);
+        for (src_queue, dst_queue) in [(None, None), (Some(1), None), (None, Some(1))] {
+            let barrier = buffer.get_barrier2(
+                stages.0, stages.1, accesses.0, accesses.1, src_queue, dst_queue, None,
+            );
+            assert_eq!(barrier.src_queue_family_index, QUEUE_FAMILY_IGNORED);
+            assert_eq!(barrier.dst_queue_family_index, QUEUE_FAMILY_IGNORED);
+        }
+        let barrier = buffer.get_barrier2_with_ownership_transfer(
+            stages.0, stages.1, accesses.0, accesses.1, 1, 2, None,
+        );
+        assert_eq!(barrier.src_queue_family_index, 1);
+        assert_eq!(barrier.dst_queue_family_index, 2);
+
+        let extent = Extent3D {
+            width: 16,
+            height: 16,
+            depth: 1,
+        };
+        let mut image = vk_init.create_empty_image(
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+            ImageAspectFlags::COLOR,
+        )?;
+        let barrier = image.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_DST_OPTIMAL,
+            Some(1),
+            None,
+        )?;
+        assert_eq!(barrier.src_queue_family_index, QUEUE_FAMILY_IGNORED);
+        assert_eq!(barrier.dst_queue_family_index, QUEUE_FAMILY_IGNORED);
+        let barrier = image.get_image_layout_transition_barrier2_with_ownership_transfer(
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            1,
+            2,
+        )?;
+        assert_eq!(barrier.src_queue_family_index, 1);
+        assert_eq!(barrier.dst_queue_family_index, 2);
+        assert_eq!(barrier.old_layout, ImageLayout::TRANSFER_DST_OPTIMAL);
+        assert_eq!(image.current_layout, ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+        buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        image.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
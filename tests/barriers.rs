@@ -0,0 +1,160 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{default_dependency_flags, Barriers, CmdType, Error};
+
+    #[test]
+    fn barriers_order_fill_before_copy() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let usage = BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::TRANSFER_DST;
+        let mut first = vk_init.create_local_buffer(256, usage)?;
+        let mut second = vk_init.create_local_buffer(256, usage)?;
+
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        let fence = vk_init.create_fence()?;
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[&cmd_buffer])?;
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        unsafe {
+            vk_init
+                .device
+                .cmd_fill_buffer(cmd_buffer, first.buffer, 0, WHOLE_SIZE, 0x0101_0101);
+        }
+
+        let barriers = Barriers::new();
+        assert!(barriers.is_empty());
+        barriers
+            .add_global(
+                PipelineStageFlags2::TRANSFER,
+                AccessFlags2::TRANSFER_WRITE,
+                PipelineStageFlags2::TRANSFER,
+                AccessFlags2::TRANSFER_READ,
+            )
+            .add_buffer(
+                &BufferMemoryBarrier2::builder()
+                    .buffer(second.buffer)
+                    .size(WHOLE_SIZE)
+                    .src_stage_mask(PipelineStageFlags2::TOP_OF_PIPE)
+                    .dst_stage_mask(PipelineStageFlags2::TRANSFER)
+                    .dst_access_mask(AccessFlags2::TRANSFER_WRITE)
+                    .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+                    .dst_queue_family_index(QUEUE_FAMILY_IGNORED),
+            )
+            .record(&vk_init, &cmd_buffer);
+
+        first.enqueue_copy_to_buffer(
+            &vk_init.device,
+            &cmd_buffer,
+            &second,
+            None,
+            None,
+            Some(256),
+        )?;
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &fence, &[], &[], &[])?;
+
+        let read: Vec<u8> = vk_init.read_buffer(&second, 0, 256)?;
+        assert!(read.iter().all(|v| *v == 1));
+
+        vk_init.wait_device_idle()?;
+        first.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        second.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy_fence(&fence)?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+
+        Ok(())
+    }
+
+    fn memory_barrier(src: PipelineStageFlags2, dst: PipelineStageFlags2) -> MemoryBarrier2 {
+        MemoryBarrier2::builder()
+            .src_stage_mask(src)
+            .dst_stage_mask(dst)
+            .build()
+    }
+
+    #[test]
+    fn fragment_to_fragment_dependencies_are_by_region() {
+        let attachment_feedback = memory_barrier(
+            PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+            PipelineStageFlags2::FRAGMENT_SHADER,
+        );
+        let depth_feedback = memory_barrier(
+            PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+            PipelineStageFlags2::EARLY_FRAGMENT_TESTS | PipelineStageFlags2::FRAGMENT_SHADER,
+        );
+        assert_eq!(
+            default_dependency_flags(&[attachment_feedback, depth_feedback], &[], &[]),
+            DependencyFlags::BY_REGION
+        );
+
+        let image = ImageMemoryBarrier2::builder()
+            .src_stage_mask(PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .dst_stage_mask(PipelineStageFlags2::COMPUTE_SHADER)
+            .build();
+        assert_eq!(
+            default_dependency_flags(&[attachment_feedback], &[image], &[]),
+            DependencyFlags::empty()
+        );
+
+        let buffer = BufferMemoryBarrier2::builder()
+            .src_stage_mask(PipelineStageFlags2::NONE)
+            .dst_stage_mask(PipelineStageFlags2::FRAGMENT_SHADER)
+            .build();
+        assert_eq!(
+            default_dependency_flags(&[], &[], &[buffer]),
+            DependencyFlags::empty()
+        );
+        assert_eq!(
+            default_dependency_flags(&[], &[], &[]),
+            DependencyFlags::empty()
+        );
+    }
+    #[test]
+    fn sampling_attachments_is_not_by_region() {
+        let attachment_read = |dst_access: AccessFlags2| {
+            ImageMemoryBarrier2::builder()
+                .old_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+                .src_stage_mask(PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+                .src_access_mask(AccessFlags2::COLOR_ATTACHMENT_WRITE)
+                .dst_stage_mask(PipelineStageFlags2::FRAGMENT_SHADER)
+                .dst_access_mask(dst_access)
+                .build()
+        };
+        let input_attachment = attachment_read(AccessFlags2::INPUT_ATTACHMENT_READ);
+        assert_eq!(
+            default_dependency_flags(&[], &[input_attachment], &[]),
+            DependencyFlags::BY_REGION
+        );
+
+        // COLOR_ATTACHMENT_OPTIMAL to SHADER_READ_ONLY_OPTIMAL, the shader may sample other regions.
+        let mut sampled = attachment_read(AccessFlags2::SHADER_READ);
+        sampled.new_layout = ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+        assert_eq!(
+            default_dependency_flags(&[], &[sampled], &[]),
+            DependencyFlags::empty()
+        );
+        assert_eq!(
+            default_dependency_flags(&[], &[input_attachment, sampled], &[]),
+            DependencyFlags::empty()
+        );
+
+        let depth_sampled = ImageMemoryBarrier2::builder()
+            .old_layout(ImageLayout::DEPTH_ATTACHMENT_OPTIMAL)
+            .new_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .src_stage_mask(
+                PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+                    | PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+            )
+            .src_access_mask(AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::FRAGMENT_SHADER)
+            .dst_access_mask(AccessFlags2::SHADER_SAMPLED_READ)
+            .build();
+        assert_eq!(
+            default_dependency_flags(&[], &[depth_sampled], &[]),
+            DependencyFlags::empty()
+        );
+    }
+}
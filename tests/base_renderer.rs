@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod tests {
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::io::Cursor;
+    use std::mem::size_of;
+    use std::result::Result;
+    use vku::{
+        BaseRenderer, BlendMode, CmdType, Error, RendererCreateInfo, RenderingConfig, VMAImage,
+        VertexConvert, VkInit, VkInitCreateInfo,
+    };
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct ColoredVertex {
+        pos: [f32; 2],
+        col: [f32; 4],
+    }
+
+    impl VertexConvert for ColoredVertex {
+        fn binding_desc() -> Vec<VertexInputBindingDescription> {
+            vec![VertexInputBindingDescription::builder()
+                .binding(0)
+                .stride(size_of::<Self>() as u32)
+                .input_rate(VertexInputRate::VERTEX)
+                .build()]
+        }
+
+        fn attrib_desc() -> Vec<VertexInputAttributeDescription> {
+            vec![
+                VertexInputAttributeDescription::builder()
+                    .binding(0)
+                    .location(0)
+                    .format(Format::R32G32_SFLOAT)
+                    .offset(0)
+                    .build(),
+                VertexInputAttributeDescription::builder()
+                    .binding(0)
+                    .location(1)
+                    .format(Format::R32G32B32A32_SFLOAT)
+                    .offset(8)
+                    .build(),
+            ]
+        }
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Tint([f32; 4]);
+
+    #[test]
+    fn draws_uploaded_geometry() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        vk_init.begin_debug_message_capture();
+
+        let vertex_spirv = read_spv(&mut Cursor::new(&include_bytes!("colored.vert.spv")[..]))?;
+        let fragment_spirv = read_spv(&mut Cursor::new(&include_bytes!("colored.frag.spv")[..]))?;
+        let info = RendererCreateInfo {
+            blend: BlendMode::Opaque,
+            max_vertices: 4,
+            max_indices: 6,
+            ..RendererCreateInfo::new(&vertex_spirv, &fragment_spirv, Format::R8G8B8A8_UNORM)
+        };
+        let mut renderer = BaseRenderer::<ColoredVertex, Tint>::new(&vk_init, &info, "Quads")?;
+
+        // Left half of the image.
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let quad = [
+            ColoredVertex {
+                pos: [-1.0, -1.0],
+                col: red,
+            },
+            ColoredVertex {
+                pos: [0.0, -1.0],
+                col: red,
+            },
+            ColoredVertex {
+                pos: [0.0, 1.0],
+                col: red,
+            },
+            ColoredVertex {
+                pos: [-1.0, 1.0],
+                col: red,
+            },
+        ];
+        let indices = [0, 1, 2, 2, 3, 0];
+        assert!(matches!(
+            renderer.upload(&vk_init, 0, &[quad[0]; 5], &indices),
+            Err(Error::BufferWriteOutOfBounds { .. })
+        ));
+        renderer.upload(&vk_init, 0, &quad, &indices)?;
+
+        let extent = Extent3D {
+            width: 32,
+            height: 16,
+            depth: 1,
+        };
+        let mut color = VMAImage::create_render_image(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+        )?;
+        let to_attachment = [color.get_image_layout_transition_barrier2(
+            ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            None,
+            None,
+        )?];
+        let to_sampled = [color.get_image_layout_transition_barrier2(
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            None,
+            None,
+        )?];
+        let config = RenderingConfig {
+            clear_color_value: Some(ClearColorValue {
+                float32: [0.0, 0.0, 0.0, 1.0],
+            }),
+            ..Default::default()
+        };
+
+        let mut recorded = Ok(());
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &to_attachment, &[]);
+            recorded = vk_init
+                .begin_rendering_to_image(&color, None, cmd_buffer, &config)
+                .and_then(|_| {
+                    let recorded = renderer.record(
+                        &vk_init,
+                        cmd_buffer,
+                        0,
+                        Extent2D {
+                            width: 32,
+                            height: 16,
+                        },
+                        &[],
+                        &Tint([1.0; 4]),
+                    );
+                    vk_init.end_rendering(cmd_buffer);
+                    recorded
+                });
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &to_sampled, &[]);
+        })?;
+        recorded?;
+
+        let pixels = vk_init.download_image(&mut color)?;
+        for (index, texel) in pixels.chunks(4).enumerate() {
+            let expected = match index % 32 < 16 {
+                true => [255, 0, 0, 255],
+                false => [0, 0, 0, 255],
+            };
+            assert_eq!(texel, expected, "texel {index}");
+        }
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        renderer.destroy(&vk_init)?;
+        color.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
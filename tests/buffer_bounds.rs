@@ -0,0 +1,114 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::Error;
+
+    #[test]
+    fn mapped_access_bounds() -> Result<(), Error> {
+        let vk_init = default_vk_init()?;
+        let mut buffer = vk_init.create_cpu_to_gpu_buffer(256, BufferUsageFlags::STORAGE_BUFFER)?;
+        let size = buffer.size();
+        let elements = size as usize / 4;
+
+        buffer.set_data(&vk_init.device, 0, &[1_u32; 4])?;
+        buffer.set_data(&vk_init.device, elements - 4, &[2_u32; 4])?;
+        assert!(matches!(
+            buffer.set_data(&vk_init.device, elements - 3, &[3_u32; 4]),
+            Err(Error::BufferWriteOutOfBounds {
+                requested: 16,
+                available: 12
+            })
+        ));
+        assert!(matches!(
+            buffer.set_data(&vk_init.device, elements + 1, &[4_u32]),
+            Err(Error::BufferWriteOutOfBounds {
+                requested: 4,
+                available: 0
+            })
+        ));
+
+        buffer.set_data_with_start_data(
+            &vk_init.device,
+            &[5_u32, 6_u32],
+            &vec![7.0_f32; elements - 2],
+        )?;
+        assert!(matches!(
+            buffer.set_data_with_start_data(&vk_init.device, &[5_u32, 6_u32], &vec![7.0_f32; elements - 1]),
+            Err(Error::BufferWriteOutOfBounds { available, .. }) if available == size
+        ));
+
+        assert_eq!(buffer.get_data::<u32>(&vk_init.device, 0, 2)?, [5, 6]);
+        let mut tail = [0.0_f32; 2];
+        buffer.read_into(&vk_init.device, elements - 2, &mut tail)?;
+        assert_eq!(tail, [7.0, 7.0]);
+        assert!(matches!(
+            buffer.read_into(&vk_init.device, elements - 1, &mut tail),
+            Err(Error::BufferReadOutOfBounds {
+                requested: 8,
+                available: 4
+            })
+        ));
+        assert!(matches!(
+            buffer.get_data::<u32>(&vk_init.device, 0, elements + 1),
+            Err(Error::BufferReadOutOfBounds { .. })
+        ));
+
+        buffer.set_data_at_byte_offset(&vk_init.device, 4, &[8_u32])?;
+        assert_eq!(buffer.get_data::<u32>(&vk_init.device, 1, 1)?, [8]);
+        assert!(matches!(
+            buffer.set_data_at_byte_offset(&vk_init.device, 2, &[9_u32]),
+            Err(Error::BufferWriteMisaligned {
+                offset: 2,
+                alignment: 4
+            })
+        ));
+
+        // Trailing data starts at the next multiple of its alignment, like a GLSL vec4 array.
+        #[repr(C, align(16))]
+        struct Vec4([f32; 4]);
+        buffer.set_data_with_start_data(&vk_init.device, &[1_u32, 2, 3], &[Vec4([10.0; 4])])?;
+        assert_eq!(buffer.get_data::<f32>(&vk_init.device, 4, 4)?, [10.0; 4]);
+
+        let mut local = vk_init.create_local_buffer(256, BufferUsageFlags::STORAGE_BUFFER)?;
+        assert!(matches!(
+            local.get_data::<u32>(&vk_init.device, 0, 1),
+            Err(Error::ReadAttemptFromUnmappedBuffer)
+        ));
+
+        // Defaults cover the requested size, not the atom padding of the allocation.
+        let mut padded = vk_init.create_cpu_to_gpu_buffer(100, BufferUsageFlags::TRANSFER_SRC)?;
+        let barrier = padded.get_barrier2(
+            PipelineStageFlags2::HOST,
+            PipelineStageFlags2::TRANSFER,
+            AccessFlags2::HOST_WRITE,
+            AccessFlags2::TRANSFER_READ,
+            None,
+            None,
+            None,
+        );
+        assert_eq!(barrier.size, 100);
+        assert!(matches!(
+            padded.set_data(&vk_init.device, 24, &[1_u32; 2]),
+            Err(Error::BufferWriteOutOfBounds {
+                requested: 8,
+                available: 4
+            })
+        ));
+        assert!(matches!(
+            padded.get_data::<u32>(&vk_init.device, 24, 2),
+            Err(Error::BufferReadOutOfBounds {
+                requested: 8,
+                available: 4
+            })
+        ));
+
+        padded.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        local.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        Ok(())
+    }
+}
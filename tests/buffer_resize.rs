@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, VkInit, VkInitCreateInfo};
+
+    const COUNT: usize = 100;
+
+    #[test]
+    fn contents_survive_grow() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        let values: Vec<u32> = (0..COUNT as u32).collect();
+        let bytes = COUNT * size_of::<u32>();
+
+        // Mapped buffers are copied on the host and keep their usage.
+        let mut mapped =
+            vk_init.create_cpu_to_gpu_buffer(bytes, BufferUsageFlags::VERTEX_BUFFER)?;
+        mapped.set_data(&vk_init.device, 0, &values)?;
+        assert!(mapped.grow_to_fit(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            bytes + 1,
+            true
+        )?);
+        assert_eq!(mapped.size(), 2 * bytes as u64);
+        assert_eq!(mapped.usage, BufferUsageFlags::VERTEX_BUFFER);
+        assert_eq!(mapped.get_data::<u32>(&vk_init.device, 0, COUNT)?, values);
+        assert!(!mapped.grow_to_fit(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            bytes,
+            true
+        )?);
+
+        // Unmapped buffers need a copy command.
+        let usage = BufferUsageFlags::STORAGE_BUFFER
+            | BufferUsageFlags::TRANSFER_SRC
+            | BufferUsageFlags::TRANSFER_DST;
+        let mut local = vk_init.create_local_buffer(bytes, usage)?;
+        local.set_debug_object_name(&vk_init, String::from("Resized"))?;
+        vk_init.upload_to_buffer(&local, 0, &values, None, None)?;
+        assert!(matches!(
+            local.resize(
+                &vk_init.device,
+                &mut vk_init.allocator.lock(),
+                2 * bytes,
+                true
+            ),
+            Err(Error::ResizeNeedsCopyCommand)
+        ));
+        assert!(vk_init.grow_buffer_to_fit(&mut local, 3 * bytes)?);
+        assert_eq!(local.size(), 4 * bytes as u64);
+        assert_eq!(local.location(), gpu_allocator::MemoryLocation::GpuOnly);
+        let read: Vec<u32> = vk_init.read_buffer(&local, 0, COUNT)?;
+        assert_eq!(read, values);
+
+        // Shrinking keeps the leading contents.
+        vk_init.resize_buffer(&mut local, bytes / 2, true)?;
+        let read: Vec<u32> = vk_init.read_buffer(&local, 0, COUNT / 2)?;
+        assert_eq!(read, values[..COUNT / 2]);
+
+        mapped.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        local.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
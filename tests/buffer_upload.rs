@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use std::sync::atomic::AtomicBool;
+    use vku::{Error, VkInit};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    fn small_chunk_vk_init() -> Result<VkInit, Error> {
+        use vku::VkInitCreateInfo;
+        use winit::dpi::LogicalSize;
+        use winit::event_loop::{EventLoop, EventLoopBuilder};
+        use winit::window::WindowBuilder;
+
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+
+        let mut create_info = VkInitCreateInfo::default();
+        create_info.max_staging_chunk = 1000;
+        VkInit::new(Some(&window), Some(size), create_info)
+    }
+
+    #[test]
+    fn uploads_in_chunks() -> Result<(), Error> {
+        let mut vk_init = small_chunk_vk_init()?;
+        let data: Vec<u32> = (0..10_000).collect();
+        let usage = BufferUsageFlags::TRANSFER_DST | BufferUsageFlags::TRANSFER_SRC;
+        let mut buffer = vk_init.create_local_buffer(data.len() * 4, usage)?;
+
+        let mut reports = vec![];
+        let mut progress = |done, total| reports.push((done, total));
+        vk_init.upload_to_buffer(&buffer, 0, &data, Some(&mut progress), None)?;
+        assert_eq!(reports.len(), 40);
+        assert_eq!(reports[0], (1000, 40_000));
+        assert_eq!(reports.last(), Some(&(40_000, 40_000)));
+        assert_eq!(vk_init.read_buffer::<u32>(&buffer, 0, data.len())?, data);
+
+        let cancel = AtomicBool::new(true);
+        assert!(matches!(
+            vk_init.upload_to_buffer(&buffer, 0, &data, None, Some(&cancel)),
+            Err(Error::UploadCancelled {
+                bytes_written: 0,
+                bytes_total: 40_000
+            })
+        ));
+        let end = buffer.allocation.size() as usize;
+        assert!(matches!(
+            vk_init.upload_to_buffer(&buffer, end - 4, &data, None, None),
+            Err(Error::BufferWriteOutOfBounds {
+                requested: 40_000,
+                available: 4
+            })
+        ));
+
+        buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use vku::ColorHandling;
+
+    fn classify(format: Format, color_space: ColorSpaceKHR) -> ColorHandling {
+        ColorHandling::from_surface_format(SurfaceFormatKHR {
+            format,
+            color_space,
+        })
+    }
+
+    #[test]
+    fn srgb_formats_encode_in_hardware() {
+        for format in [
+            Format::B8G8R8A8_SRGB,
+            Format::R8G8B8A8_SRGB,
+            Format::A8B8G8R8_SRGB_PACK32,
+        ] {
+            let handling = classify(format, ColorSpaceKHR::SRGB_NONLINEAR);
+            assert_eq!(handling, ColorHandling::SrgbFramebuffer, "{format:?}");
+            assert_eq!(handling.srgb_spec_constant(), 0);
+        }
+    }
+
+    #[test]
+    fn unorm_formats_need_shader_encode() {
+        for format in [
+            Format::B8G8R8A8_UNORM,
+            Format::R8G8B8A8_UNORM,
+            Format::A8B8G8R8_UNORM_PACK32,
+            Format::A2B10G10R10_UNORM_PACK32,
+            Format::A2R10G10B10_UNORM_PACK32,
+        ] {
+            let handling = classify(format, ColorSpaceKHR::SRGB_NONLINEAR);
+            assert_eq!(
+                handling,
+                ColorHandling::LinearFramebufferNeedsEncode,
+                "{format:?}"
+            );
+            assert_eq!(handling.srgb_spec_constant(), 1);
+        }
+    }
+
+    #[test]
+    fn extended_linear_float_is_srgb_framebuffer() {
+        assert_eq!(
+            classify(
+                Format::R16G16B16A16_SFLOAT,
+                ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT
+            ),
+            ColorHandling::SrgbFramebuffer
+        );
+    }
+
+    #[test]
+    fn uncovered_pairs_are_unknown() {
+        for (format, color_space) in [
+            (
+                Format::A2B10G10R10_UNORM_PACK32,
+                ColorSpaceKHR::HDR10_ST2084_EXT,
+            ),
+            (Format::R16G16B16A16_SFLOAT, ColorSpaceKHR::SRGB_NONLINEAR),
+            (
+                Format::B8G8R8A8_UNORM,
+                ColorSpaceKHR::DISPLAY_P3_NONLINEAR_EXT,
+            ),
+        ] {
+            let handling = classify(format, color_space);
+            assert_eq!(
+                handling,
+                ColorHandling::Unknown,
+                "{format:?} {color_space:?}"
+            );
+            assert_eq!(handling.srgb_spec_constant(), 0);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::pipeline_builder::BlendMode;
+    use vku::{CmdType, ColorSpaceIntent, Error, FragmentSource, FullscreenPass, VMAImage, VkInit};
+
+    const GRADIENT_WIDTH: u32 = 16;
+    const TOLERANCE: f32 = 2.0 / 255.0;
+
+    fn srgb_to_linear(value: f32) -> f32 {
+        if value <= 0.04045 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    }
+
+    fn gradient() -> Vec<u8> {
+        (0..GRADIENT_WIDTH)
+            .flat_map(|x| {
+                let value = (x * 17) as u8;
+                [value, value, value, 255]
+            })
+            .collect()
+    }
+
+    /// Sampled linear values of the gradient each intent describes.
+    fn reference(intent: ColorSpaceIntent) -> Vec<f32> {
+        (0..GRADIENT_WIDTH)
+            .map(|x| {
+                let value = (x * 17) as f32 / 255.0;
+                match intent {
+                    ColorSpaceIntent::SrgbTexture => srgb_to_linear(value),
+                    _ => value,
+                }
+            })
+            .collect()
+    }
+
+    /// Renders ```1.0 - texture(input, uv)``` into a float image and reads it back.
+    fn sample_inverted(
+        vk_init: &mut VkInit,
+        pass: &FullscreenPass,
+        sampler: Sampler,
+        input: &VMAImage,
+    ) -> Result<Vec<[f32; 4]>, Error> {
+        let extent = input.extent;
+        let mut output = VMAImage::create_render_image(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            extent,
+            Format::R32G32B32A32_SFLOAT,
+            16,
+        )?;
+        let mut slot = vk_init
+            .create_readback_slots((extent.width * extent.height) as usize * 16, 1)?
+            .remove(0);
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+
+        let barrier = output.get_image_layout_transition_barrier2(
+            ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            None,
+            None,
+        )?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[barrier], &[]);
+        let color_attachments = [RenderingAttachmentInfo::builder()
+            .image_view(output.image_view)
+            .image_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .load_op(AttachmentLoadOp::DONT_CARE)
+            .store_op(AttachmentStoreOp::STORE)
+            .build()];
+        let render_area = Extent2D {
+            width: extent.width,
+            height: extent.height,
+        };
+        let rendering_info = RenderingInfo::builder()
+            .render_area(Rect2D {
+                offset: Offset2D { x: 0, y: 0 },
+                extent: render_area,
+            })
+            .layer_count(1)
+            .color_attachments(&color_attachments);
+        vk_init.cmd_begin_rendering(&cmd_buffer, &rendering_info);
+        pass.record(vk_init, &cmd_buffer, input, render_area, sampler, &[])?;
+        vk_init.end_rendering(&cmd_buffer);
+
+        let barrier = output.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_SRC_OPTIMAL,
+            None,
+            None,
+        )?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[barrier], &[]);
+        let region = BufferImageCopy::builder()
+            .image_subresource(
+                ImageSubresourceLayers::builder()
+                    .aspect_mask(ImageAspectFlags::COLOR)
+                    .layer_count(1)
+                    .build(),
+            )
+            .image_extent(extent)
+            .build();
+        unsafe {
+            vk_init.device.cmd_copy_image_to_buffer(
+                cmd_buffer,
+                output.image,
+                ImageLayout::TRANSFER_SRC_OPTIMAL,
+                slot.buffer.buffer,
+                &[region],
+            )
+        };
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &slot.fence, &[], &[], &[])?;
+        let pixels = slot.read_blocking(vk_init, Duration::from_secs(1))?;
+
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        output.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        Ok(pixels)
+    }
+
+    #[test]
+    fn sampled_gradient_matches_intent() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let invert_spirv = read_spv(&mut Cursor::new(&include_bytes!("invert.frag.spv")[..]))?;
+        let pass = FullscreenPass::new::<()>(
+            &vk_init,
+            FragmentSource::Spirv(&invert_spirv),
+            Format::R32G32B32A32_SFLOAT,
+            BlendMode::Opaque,
+            "Color_Space_Pass",
+        )?;
+        let sampler_info = SamplerCreateInfo::builder()
+            .mag_filter(Filter::NEAREST)
+            .min_filter(Filter::NEAREST);
+        let sampler = unsafe { vk_init.device.create_sampler(&sampler_info, None)? };
+
+        let pixels = gradient();
+        for intent in [ColorSpaceIntent::SrgbTexture, ColorSpaceIntent::LinearData] {
+            // Once in the intent's format, once converted into the other encoding.
+            for format in [Format::R8G8B8A8_SRGB, Format::R8G8B8A8_UNORM] {
+                let mut input = VMAImage::from_rgba8(
+                    &mut vk_init,
+                    GRADIENT_WIDTH,
+                    1,
+                    &pixels,
+                    intent,
+                    Some(format),
+                )?;
+                let sampled = sample_inverted(&mut vk_init, &pass, sampler, &input)?;
+                for (texel, expected) in sampled.iter().zip(reference(intent)) {
+                    for channel in &texel[..3] {
+                        let value = 1.0 - channel;
+                        assert!(
+                            (value - expected).abs() <= TOLERANCE,
+                            "{intent:?} in {format:?}: sampled {value}, expected {expected}"
+                        );
+                    }
+                    assert!(texel[3].abs() <= TOLERANCE);
+                }
+                input.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+            }
+        }
+
+        unsafe { vk_init.device.destroy_sampler(sampler, None) };
+        Ok(())
+    }
+
+    #[test]
+    fn conversion_borrows_matching_encodings() -> Result<(), Error> {
+        let pixels = gradient();
+        let srgb = ColorSpaceIntent::SrgbTexture.convert_rgba8(&pixels, Format::R8G8B8A8_SRGB)?;
+        assert!(matches!(srgb, std::borrow::Cow::Borrowed(_)));
+        let normals = ColorSpaceIntent::NormalMap.convert_rgba8(&pixels, Format::R8G8B8A8_UNORM)?;
+        assert!(matches!(normals, std::borrow::Cow::Borrowed(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn conversion_keeps_alpha_and_round_trips() -> Result<(), Error> {
+        let pixels = gradient();
+        let encoded = ColorSpaceIntent::LinearData.convert_rgba8(&pixels, Format::R8G8B8A8_SRGB)?;
+        assert!(encoded.chunks_exact(4).all(|pixel| pixel[3] == 255));
+        // Encoding brightens linear values, the endpoints stay fixed.
+        assert_eq!(&encoded[..4], &[0, 0, 0, 255]);
+        assert_eq!(&encoded[encoded.len() - 4..], &[255, 255, 255, 255]);
+        assert!(encoded[4 * 8] > pixels[4 * 8]);
+
+        let decoded =
+            ColorSpaceIntent::SrgbTexture.convert_rgba8(&encoded, Format::R8G8B8A8_UNORM)?;
+        for (decoded, original) in decoded.iter().zip(pixels.iter()) {
+            assert!(decoded.abs_diff(*original) <= 1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn conversion_rejects_other_formats() {
+        let pixels = gradient();
+        assert!(matches!(
+            ColorSpaceIntent::LinearData.convert_rgba8(&pixels, Format::B8G8R8A8_SRGB),
+            Err(Error::UnsupportedColorSpaceFormat(Format::B8G8R8A8_SRGB))
+        ));
+    }
+}
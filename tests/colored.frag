@@ -0,0 +1,12 @@
+#version 450
+layout(push_constant) uniform Push {
+    vec4 tint;
+} push;
+
+layout(location = 0) in vec4 i_col;
+
+layout(location = 0) out vec4 o_col;
+
+void main() {
+    o_col = i_col * push.tint;
+}
@@ -0,0 +1,10 @@
+#version 450
+layout(location = 0) in vec2 i_pos;
+layout(location = 1) in vec4 i_col;
+
+layout(location = 0) out vec4 o_col;
+
+void main() {
+    o_col = i_col;
+    gl_Position = vec4(i_pos, 0.0, 1.0);
+}
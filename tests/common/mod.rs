@@ -0,0 +1,22 @@
+use vku::{Error, VkInit, VkInitCreateInfo};
+use winit::dpi::LogicalSize;
+use winit::event_loop::{EventLoop, EventLoopBuilder};
+use winit::platform::x11::EventLoopBuilderExtX11;
+use winit::window::WindowBuilder;
+
+/// Windowed [VkInit] with the default create info, initializes the logger once per test binary.
+pub fn default_vk_init() -> Result<VkInit, Error> {
+    env_logger::init();
+    let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+    let size = [800_u32, 600_u32];
+    let window = WindowBuilder::new()
+        .with_inner_size(LogicalSize {
+            width: size[0],
+            height: size[1],
+        })
+        .build(&event_loop)
+        .unwrap();
+
+    let create_info = VkInitCreateInfo::default();
+    VkInit::new(Some(&window), Some(size), create_info)
+}
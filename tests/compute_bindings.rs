@@ -0,0 +1,139 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::io::Cursor;
+
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, ComputeBinding, Error, VMAImage};
+
+    const SIZE: u32 = 8;
+
+    fn color_barrier(
+        image: Image,
+        (old_layout, new_layout): (ImageLayout, ImageLayout),
+        (src_stage, src_access): (PipelineStageFlags2, AccessFlags2),
+        (dst_stage, dst_access): (PipelineStageFlags2, AccessFlags2),
+    ) -> ImageMemoryBarrier2 {
+        ImageMemoryBarrier2::builder()
+            .image(image)
+            .old_layout(old_layout)
+            .new_layout(new_layout)
+            .src_stage_mask(src_stage)
+            .src_access_mask(src_access)
+            .dst_stage_mask(dst_stage)
+            .dst_access_mask(dst_access)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .subresource_range(ImageSubresourceRange {
+                aspect_mask: ImageAspectFlags::COLOR,
+                level_count: 1,
+                layer_count: 1,
+                ..Default::default()
+            })
+            .build()
+    }
+
+    #[test]
+    fn storage_image_written_from_uniform_buffer() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let extent = Extent3D {
+            width: SIZE,
+            height: SIZE,
+            depth: 1,
+        };
+        let mut image = VMAImage::create_mipped_image_with_usage(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+            ImageAspectFlags::COLOR,
+            1,
+            ImageUsageFlags::STORAGE,
+        )?;
+        let mut params = vk_init.create_cpu_to_gpu_buffer(16, BufferUsageFlags::UNIFORM_BUFFER)?;
+        params.set_data(&vk_init.device, 0, &[1.0_f32, 0.2, 0.6, 1.0])?;
+
+        let code = read_spv(&mut Cursor::new(include_bytes!("fill_image.comp.spv")))?;
+        let mut compute_shader = vk_init.create_compute_shader::<u32>(
+            &[
+                ComputeBinding::StorageImage(&image),
+                ComputeBinding::Uniform(&params),
+            ],
+            code,
+            [SIZE, SIZE, 1],
+            &[],
+            String::from("Fill_Image"),
+        )?;
+        assert_eq!(
+            compute_shader.binding_types(),
+            [
+                DescriptorType::STORAGE_IMAGE,
+                DescriptorType::UNIFORM_BUFFER
+            ]
+        );
+        assert!(matches!(
+            compute_shader.allocate_set(&vk_init, &[ComputeBinding::Ssbo(&params)]),
+            Err(Error::ComputeBindingsMismatch { .. })
+        ));
+
+        let texel_bytes = (SIZE * SIZE * 4) as usize;
+        let mut slot = vk_init.create_readback_slots(texel_bytes, 1)?.remove(0);
+        let to_general = color_barrier(
+            image.image,
+            (ImageLayout::UNDEFINED, ImageLayout::GENERAL),
+            (PipelineStageFlags2::NONE, AccessFlags2::NONE),
+            (
+                PipelineStageFlags2::COMPUTE_SHADER,
+                AccessFlags2::SHADER_STORAGE_WRITE,
+            ),
+        );
+        let to_transfer_src = color_barrier(
+            image.image,
+            (ImageLayout::GENERAL, ImageLayout::TRANSFER_SRC_OPTIMAL),
+            (
+                PipelineStageFlags2::COMPUTE_SHADER,
+                AccessFlags2::SHADER_STORAGE_WRITE,
+            ),
+            (PipelineStageFlags2::COPY, AccessFlags2::TRANSFER_READ),
+        );
+        let region = BufferImageCopy::builder()
+            .image_subresource(ImageSubresourceLayers {
+                aspect_mask: ImageAspectFlags::COLOR,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: 1,
+            })
+            .image_extent(extent)
+            .build();
+
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_general], &[]);
+            compute_shader.bind(&vk_init.device, cmd_buffer, &0_u32.to_ne_bytes());
+            compute_shader.dispatch(&vk_init.device, cmd_buffer, SIZE, SIZE, 1);
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer_src], &[]);
+            unsafe {
+                vk_init.device.cmd_copy_image_to_buffer(
+                    *cmd_buffer,
+                    image.image,
+                    ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    slot.buffer.buffer,
+                    &[region],
+                );
+            }
+        })?;
+
+        let data: Vec<u8> = slot.buffer.get_data(&vk_init.device, 0, texel_bytes)?;
+        assert!(data.chunks(4).all(|texel| texel == [255, 51, 153, 255]));
+
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        compute_shader.destroy(&vk_init)?;
+        params.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        image.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,74 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::io::Cursor;
+    use std::mem::size_of;
+    use std::time::Duration;
+
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, ComputeBinding, Error};
+
+    #[test]
+    fn compute_shader_results_read_back() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let count = 1024;
+        let factor = 3_u32;
+
+        let mut values = vk_init.create_local_buffer(
+            count * size_of::<u32>(),
+            BufferUsageFlags::STORAGE_BUFFER | BufferUsageFlags::TRANSFER_SRC,
+        )?;
+        let code = read_spv(&mut Cursor::new(include_bytes!("scale.comp.spv")))?;
+        let compute_shader = vk_init.create_compute_shader::<u32>(
+            &[ComputeBinding::Ssbo(&values)],
+            code,
+            [64, 1, 1],
+            &[],
+            String::from("Scale"),
+        )?;
+
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        let fence = vk_init.create_fence()?;
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[&cmd_buffer])?;
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        compute_shader.bind(&vk_init.device, &cmd_buffer, &factor.to_ne_bytes());
+        compute_shader.dispatch(&vk_init.device, &cmd_buffer, count as u32, 1, 1);
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &fence, &[], &[], &[])?;
+
+        let expected: Vec<u32> = (0..count as u32).map(|i| i * factor).collect();
+        let read: Vec<u32> = vk_init.read_buffer(&values, 0, count)?;
+        assert_eq!(read, expected);
+
+        let offset = 16 * size_of::<u32>();
+        let mut slot = vk_init.read_buffer_async::<u32>(&values, offset as u64, 16)?;
+        let read: Vec<u32> = slot.read_blocking(&vk_init, Duration::from_secs(1))?;
+        assert_eq!(read, expected[16..32]);
+
+        assert!(matches!(
+            vk_init.read_buffer::<u32>(&values, offset as u64, count),
+            Err(Error::BufferReadOutOfBounds { .. })
+        ));
+
+        let mut storage_only = vk_init
+            .create_local_buffer(count * size_of::<u32>(), BufferUsageFlags::STORAGE_BUFFER)?;
+        assert!(matches!(
+            vk_init.read_buffer::<u32>(&storage_only, 0, count),
+            Err(Error::BufferUsageMissing(BufferUsageFlags::TRANSFER_SRC))
+        ));
+
+        vk_init.wait_device_idle()?;
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        compute_shader.destroy(&vk_init)?;
+        storage_only.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        values.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy_fence(&fence)?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use std::result::Result;
+    use vku::{Error, VkInit, VkInitCreateInfo};
+
+    #[test]
+    fn release_alias_matches_dist() {
+        let release = VkInitCreateInfo::release_vk_1_3();
+        let dist = VkInitCreateInfo::dist_vk_1_3();
+        assert!(!release.enable_validation);
+        assert_eq!(release.vk_version, dist.vk_version);
+        assert_eq!(release.enable_validation, dist.enable_validation);
+    }
+
+    #[test]
+    fn headless_rejects_head_calls() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        assert!(vk_init.head.is_none());
+
+        let semaphore = vk_init.create_semaphore()?;
+        let acquired = vk_init.acquire_next_swapchain_image(semaphore, None);
+        assert!(matches!(acquired, Err(Error::HeadCallOnHeadlessInstance)));
+
+        unsafe { vk_init.device.destroy_semaphore(semaphore, None) };
+        vk_init.destroy()
+    }
+}
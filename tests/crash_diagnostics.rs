@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use std::result::Result;
+    use vku::{CmdType, Error, VkInit};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    fn crash_diagnostics_vk_init() -> Result<VkInit, Error> {
+        use vku::VkInitCreateInfo;
+        use winit::dpi::LogicalSize;
+        use winit::event_loop::{EventLoop, EventLoopBuilder};
+        use winit::window::WindowBuilder;
+
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+
+        let mut create_info = VkInitCreateInfo::default();
+        create_info.crash_diagnostics = true;
+        VkInit::new_windowed(&window, size, window.scale_factor(), create_info)
+    }
+
+    #[test]
+    fn crash_diagnostics_follow_extension_support() -> Result<(), Error> {
+        let mut vk_init = crash_diagnostics_vk_init()?;
+        let enabled = |name: &str| {
+            vk_init
+                .enabled_device_extensions
+                .iter()
+                .any(|ext| ext == name)
+        };
+        assert_eq!(
+            enabled("VK_NV_device_diagnostic_checkpoints"),
+            vk_init.physical_device_info.diagnostic_checkpoints
+        );
+        assert_eq!(
+            enabled("VK_NV_device_diagnostics_config"),
+            vk_init.physical_device_info.diagnostics_config
+        );
+
+        let mut labeled = Ok(());
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            labeled = vk_init
+                .begin_debug_label(cmd_buffer, "Crash_Diagnostics_Test", None)
+                .map(|label| label.end());
+        })?;
+        labeled?;
+        // Without a device loss this only logs the checkpoints reached by the label above.
+        vk_init.log_device_lost_diagnostics();
+
+        vk_init.destroy()
+    }
+}
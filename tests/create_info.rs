@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::time::Duration;
+    use vku::{GpuSelection, VkInitCreateInfo, VkInitCreateInfoBuilder};
+
+    fn assert_same(a: &VkInitCreateInfo, b: &VkInitCreateInfo) {
+        assert_eq!(a.app_name, b.app_name);
+        assert_eq!(a.engine_name, b.engine_name);
+        assert_eq!(a.app_version, b.app_version);
+        assert_eq!(a.vk_version, b.vk_version);
+        assert_eq!(a.enable_validation, b.enable_validation);
+        assert_eq!(a.enabled_validation_layers, b.enabled_validation_layers);
+        assert_eq!(a.enabled_validation_features, b.enabled_validation_features);
+        assert_eq!(
+            a.additional_instance_extensions,
+            b.additional_instance_extensions
+        );
+        assert_eq!(a.log_level, b.log_level);
+        assert_eq!(a.log_msg, b.log_msg);
+        assert_eq!(a.allow_igpu, b.allow_igpu);
+        assert_eq!(a.gpu_preference, b.gpu_preference);
+        assert_eq!(
+            format!("{:?}", a.physical_device_1_1_features),
+            format!("{:?}", b.physical_device_1_1_features)
+        );
+        assert_eq!(
+            format!("{:?}", a.physical_device_1_2_features),
+            format!("{:?}", b.physical_device_1_2_features)
+        );
+        assert_eq!(
+            format!("{:?}", a.physical_device_1_3_features),
+            format!("{:?}", b.physical_device_1_3_features)
+        );
+        assert_eq!(
+            a.additional_device_extensions,
+            b.additional_device_extensions
+        );
+        assert_eq!(a.surface_format_preferences, b.surface_format_preferences);
+        assert_eq!(a.depth_format, b.depth_format);
+        assert_eq!(a.depth_format_sizeof, b.depth_format_sizeof);
+        assert_eq!(a.request_img_count, b.request_img_count);
+        assert_eq!(a.present_mode_preferences, b.present_mode_preferences);
+        assert_eq!(unsafe { a.clear_color_value.uint32 }, unsafe {
+            b.clear_color_value.uint32
+        });
+        assert_eq!(
+            a.clear_depth_stencil_value.depth,
+            b.clear_depth_stencil_value.depth
+        );
+        assert_eq!(
+            a.clear_depth_stencil_value.stencil,
+            b.clear_depth_stencil_value.stencil
+        );
+        assert_eq!(a.msaa_samples, b.msaa_samples);
+        assert_eq!(a.frames_in_flight, b.frames_in_flight);
+        assert_eq!(a.max_staging_chunk, b.max_staging_chunk);
+        assert_eq!(a.max_staging_pool_bytes, b.max_staging_pool_bytes);
+        assert_eq!(a.retry_failed_allocations, b.retry_failed_allocations);
+        assert_eq!(a.gpu_watchdog, b.gpu_watchdog);
+        assert_eq!(a.crash_diagnostics, b.crash_diagnostics);
+        assert_eq!(a.capture_resource_backtraces, b.capture_resource_backtraces);
+        assert_eq!(a.strict_teardown, b.strict_teardown);
+        assert_eq!(a.auto_env, b.auto_env);
+    }
+
+    #[test]
+    fn builder_without_setters_keeps_presets() {
+        assert_same(
+            &VkInitCreateInfo::builder().build(),
+            &VkInitCreateInfo::default(),
+        );
+        for preset in [
+            VkInitCreateInfo::verbose_debug_vk_1_3(),
+            VkInitCreateInfo::test_release_vk_1_3(),
+            VkInitCreateInfo::dist_vk_1_3(),
+        ] {
+            assert_same(
+                &VkInitCreateInfoBuilder::from(preset.clone()).build(),
+                &preset,
+            );
+        }
+    }
+
+    #[test]
+    fn builder_matches_field_assignment() {
+        let mut assigned = VkInitCreateInfo::dist_vk_1_3();
+        assigned.app_name = String::from("Builder");
+        assigned.present_mode_preferences = vec![PresentModeKHR::MAILBOX];
+        assigned.surface_format_preferences = vec![Format::R8G8B8A8_SRGB];
+        assigned.depth_format = Format::D24_UNORM_S8_UINT;
+        assigned.depth_format_sizeof = 4;
+        assigned.enable_validation = true;
+        assigned.additional_device_extensions = vec![String::from("VK_KHR_present_id")];
+        assigned.gpu_preference = GpuSelection::HighestMemory;
+        assigned.gpu_watchdog = Some(Duration::from_secs(2));
+        assigned.msaa_samples = SampleCountFlags::TYPE_4;
+        assigned.frames_in_flight = 3;
+
+        let built = VkInitCreateInfoBuilder::from(VkInitCreateInfo::dist_vk_1_3())
+            .app_name("Builder")
+            .present_mode(PresentModeKHR::MAILBOX)
+            .surface_format(Format::R8G8B8A8_SRGB)
+            .depth_format(Format::D24_UNORM_S8_UINT, 4)
+            .enable_validation(true)
+            .additional_device_extensions(&["VK_KHR_present_id"])
+            .gpu_preference(GpuSelection::HighestMemory)
+            .gpu_watchdog(Some(Duration::from_secs(2)))
+            .msaa_samples(SampleCountFlags::TYPE_4)
+            .frames_in_flight(3)
+            .build();
+        assert_same(&built, &assigned);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let original = VkInitCreateInfo::builder()
+            .app_name("Settings")
+            .present_mode_preferences(&[PresentModeKHR::IMMEDIATE, PresentModeKHR::MAILBOX])
+            .surface_format(Format::A2B10G10R10_UNORM_PACK32)
+            .enabled_validation_features(&[ValidationFeatureEnableEXT::GPU_ASSISTED])
+            .gpu_preference(GpuSelection::NamedSubstring(String::from("radeon")))
+            .gpu_watchdog(Some(Duration::from_millis(1500)))
+            .msaa_samples(SampleCountFlags::TYPE_8)
+            .clear_color_value(ClearColorValue {
+                float32: [0.1, 0.2, 0.3, 1.0],
+            })
+            .build();
+        let json = serde_json::to_string(&original).unwrap();
+        let loaded: VkInitCreateInfo = serde_json::from_str(&json).unwrap();
+        assert_same(&loaded, &original);
+
+        for preset in [
+            VkInitCreateInfo::verbose_debug_vk_1_3(),
+            VkInitCreateInfo::dist_vk_1_3(),
+        ] {
+            let json = serde_json::to_string(&preset).unwrap();
+            assert_same(&serde_json::from_str(&json).unwrap(), &preset);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_missing_fields_use_default() {
+        let loaded: VkInitCreateInfo =
+            serde_json::from_str(r#"{ "present_mode_preferences": [1], "frames_in_flight": 3 }"#)
+                .unwrap();
+        assert_same(
+            &loaded,
+            &VkInitCreateInfo::builder()
+                .present_mode(PresentModeKHR::MAILBOX)
+                .frames_in_flight(3)
+                .build(),
+        );
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error};
+
+    const SIZE: u32 = 8;
+
+    #[test]
+    fn cubemap_faces_upload_per_layer() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let extent = Extent3D {
+            width: SIZE,
+            height: SIZE,
+            depth: 1,
+        };
+        let mut cubemap = vk_init.create_cubemap(extent, Format::R8G8B8A8_UNORM, 4)?;
+        assert_eq!(cubemap.array_layers, 6);
+
+        let face_bytes = (SIZE * SIZE * 4) as usize;
+        for face in 0..6_u8 {
+            cubemap.set_layer_staging_data(
+                &vk_init.device,
+                face as u32,
+                &vec![face * 40; face_bytes],
+            )?;
+        }
+        let mut slot = vk_init.create_readback_slots(face_bytes * 6, 1)?.remove(0);
+
+        let to_transfer_dst = cubemap.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_DST_OPTIMAL,
+            None,
+            None,
+        )?;
+        assert_eq!(to_transfer_dst.subresource_range.layer_count, 6);
+        let to_transfer_src = ImageMemoryBarrier2::builder()
+            .image(cubemap.image)
+            .old_layout(ImageLayout::TRANSFER_DST_OPTIMAL)
+            .new_layout(ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .src_stage_mask(PipelineStageFlags2::COPY)
+            .src_access_mask(AccessFlags2::TRANSFER_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::COPY)
+            .dst_access_mask(AccessFlags2::TRANSFER_READ)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .subresource_range(ImageSubresourceRange {
+                aspect_mask: ImageAspectFlags::COLOR,
+                level_count: 1,
+                layer_count: 6,
+                ..Default::default()
+            })
+            .build();
+        let region = BufferImageCopy::builder()
+            .image_subresource(ImageSubresourceLayers {
+                aspect_mask: ImageAspectFlags::COLOR,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: 6,
+            })
+            .image_extent(extent)
+            .build();
+
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer_dst], &[]);
+            for face in 0..6 {
+                cubemap.enqueue_copy_to_layer(&vk_init.device, cmd_buffer, face);
+            }
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer_src], &[]);
+            unsafe {
+                vk_init.device.cmd_copy_image_to_buffer(
+                    *cmd_buffer,
+                    cubemap.image,
+                    ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    slot.buffer.buffer,
+                    &[region],
+                );
+            }
+        })?;
+
+        let data: Vec<u8> = slot.buffer.get_data(&vk_init.device, 0, face_bytes * 6)?;
+        for (face, texels) in data.chunks(face_bytes).enumerate() {
+            assert!(texels.iter().all(|texel| *texel == face as u8 * 40));
+        }
+
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        cubemap.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use vku::{DebugLabel, Error};
+
+    fn label_name(label: &DebugLabel) -> &str {
+        let info = label.label_info();
+        unsafe { CStr::from_ptr(info.p_label_name) }
+            .to_str()
+            .unwrap()
+    }
+
+    #[test]
+    fn label_names_are_passed_intact() -> Result<(), Error> {
+        let label = DebugLabel::new("Shadow pass: cascade 2/4", None)?;
+        assert_eq!(label_name(&label), "Shadow pass: cascade 2/4");
+        assert_eq!(label.label_info().color, [0.0; 4]);
+
+        // Slicing must not read past the end of the borrowed str.
+        let names = "Opaque_Transparent";
+        let label = DebugLabel::new(&names[..6], None)?;
+        assert_eq!(label_name(&label), "Opaque");
+        Ok(())
+    }
+
+    #[test]
+    fn label_colors_are_passed() -> Result<(), Error> {
+        let color = [0.25, 0.5, 0.75, 1.0];
+        let label = DebugLabel::new("Colored", Some(color))?;
+        assert_eq!(label.label_info().color, color);
+        assert_eq!(label.color(), color);
+        Ok(())
+    }
+
+    #[test]
+    fn trailing_nul_is_accepted() -> Result<(), Error> {
+        let label = DebugLabel::new("Legacy_Label\0", None)?;
+        assert_eq!(label.name().to_bytes(), b"Legacy_Label");
+        assert!(matches!(
+            DebugLabel::new("Interior\0Nul", None),
+            Err(Error::CStringConvertError(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::Error;
+
+    #[test]
+    fn validation_messages_contain_debug_names() -> Result<(), Error> {
+        let vk_init = default_vk_init()?;
+        let validation_available = vk_init
+            .entry
+            .enumerate_instance_layer_properties()?
+            .iter()
+            .any(|layer| {
+                let name = unsafe { std::ffi::CStr::from_ptr(layer.layer_name.as_ptr()) };
+                name.to_bytes() == b"VK_LAYER_KHRONOS_validation"
+            });
+        if !validation_available {
+            return Ok(());
+        }
+
+        let fence = vk_init.create_fence()?;
+        let result = vk_init.set_debug_object_name(
+            fence.as_raw(),
+            ObjectType::FENCE,
+            "VKU_Nul\0Fence".to_string(),
+        );
+        assert!(
+            matches!(&result, Err(Error::DebugNameContainsNul(prefix)) if prefix == "VKU_Nul"),
+            "nul in debug name was not rejected: {result:?}"
+        );
+
+        let name = "VKU_Test_Signaled_Fence";
+        vk_init.set_debug_object_name_fmt(
+            fence.as_raw(),
+            ObjectType::FENCE,
+            format_args!("{name}"),
+        )?;
+
+        // Submitting with an already signaled fence is invalid and reported by the validation layer.
+        vk_init.begin_debug_message_capture();
+        let _ = unsafe {
+            vk_init
+                .device
+                .queue_submit(vk_init.unified_queue, &[], fence)
+        };
+        let messages = vk_init.end_debug_message_capture();
+        vk_init.wait_device_idle()?;
+
+        assert!(
+            messages.iter().any(|message| message.contains(name)),
+            "no message names {name}: {messages:#?}"
+        );
+
+        Ok(())
+    }
+}
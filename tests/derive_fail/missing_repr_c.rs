@@ -0,0 +1,8 @@
+use vku::VertexConvert;
+
+#[derive(Clone, Copy, VertexConvert)]
+struct Vertex {
+    pos: [f32; 3],
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use vku::VertexConvert;
+
+#[repr(C)]
+#[derive(Clone, Copy, VertexConvert)]
+struct Vertex {
+    pos: [f32; 3],
+    weight: f64,
+}
+
+fn main() {}
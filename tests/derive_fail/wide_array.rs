@@ -0,0 +1,9 @@
+use vku::VertexConvert;
+
+#[repr(C)]
+#[derive(Clone, Copy, VertexConvert)]
+struct Vertex {
+    transform: [f32; 16],
+}
+
+fn main() {}
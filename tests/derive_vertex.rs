@@ -0,0 +1,118 @@
+#[cfg(test)]
+#[cfg(feature = "derive")]
+mod tests {
+    use ash::vk::*;
+    use std::mem::size_of;
+    use vku::VertexConvert;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, VertexConvert)]
+    struct DerivedVertex3D {
+        pos: [f32; 3],
+        normal: [f32; 3],
+        uv: [f32; 2],
+        col: [u8; 4],
+        #[vertex(format = "R16G16_UINT")]
+        joints: [u16; 2],
+        material: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Vertex3D {
+        pos: [f32; 3],
+        normal: [f32; 3],
+        uv: [f32; 2],
+        col: [u8; 4],
+        joints: [u16; 2],
+        material: u32,
+    }
+
+    impl VertexConvert for Vertex3D {
+        fn binding_desc() -> Vec<VertexInputBindingDescription> {
+            vec![VertexInputBindingDescription::builder()
+                .binding(0)
+                .stride(size_of::<Self>() as u32)
+                .input_rate(VertexInputRate::VERTEX)
+                .build()]
+        }
+
+        fn attrib_desc() -> Vec<VertexInputAttributeDescription> {
+            let attrib = |location, format, offset| {
+                VertexInputAttributeDescription::builder()
+                    .binding(0)
+                    .location(location)
+                    .format(format)
+                    .offset(offset)
+                    .build()
+            };
+            vec![
+                attrib(0, Format::R32G32B32_SFLOAT, 0),
+                attrib(1, Format::R32G32B32_SFLOAT, 12),
+                attrib(2, Format::R32G32_SFLOAT, 24),
+                attrib(3, Format::R8G8B8A8_UNORM, 32),
+                attrib(4, Format::R16G16_UINT, 36),
+                attrib(5, Format::R32_UINT, 40),
+            ]
+        }
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy, VertexConvert)]
+    #[vertex(binding = 1, input_rate = "instance")]
+    struct Instance {
+        #[vertex(location = 4)]
+        offset: [f32; 2],
+        scale: f32,
+        layer: i32,
+    }
+
+    fn bindings<V: VertexConvert>() -> Vec<(u32, u32, VertexInputRate)> {
+        V::binding_desc()
+            .iter()
+            .map(|binding| (binding.binding, binding.stride, binding.input_rate))
+            .collect()
+    }
+
+    fn attribs<V: VertexConvert>() -> Vec<(u32, u32, Format, u32)> {
+        V::attrib_desc()
+            .iter()
+            .map(|attrib| {
+                (
+                    attrib.location,
+                    attrib.binding,
+                    attrib.format,
+                    attrib.offset,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn derive_matches_handwritten_impl() {
+        assert_eq!(bindings::<DerivedVertex3D>(), bindings::<Vertex3D>());
+        assert_eq!(attribs::<DerivedVertex3D>(), attribs::<Vertex3D>());
+    }
+
+    #[test]
+    fn derive_applies_binding_attributes() {
+        assert_eq!(
+            bindings::<Instance>(),
+            vec![(1, 16, VertexInputRate::INSTANCE)]
+        );
+        assert_eq!(
+            attribs::<Instance>(),
+            vec![
+                (4, 1, Format::R32G32_SFLOAT, 0),
+                (5, 1, Format::R32_SFLOAT, 8),
+                (6, 1, Format::R32_SINT, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn derive_rejects_unsupported_structs() {
+        let cases = trybuild::TestCases::new();
+        cases.compile_fail("tests/derive_fail/*.rs");
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::mem::size_of;
+
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{
+        CmdType, ComputeBinding, DescriptorAllocator, DescriptorPoolPolicy, Error, PoolGrowth,
+        VMABuffer, VkInit, VkInitCreateInfo,
+    };
+
+    const COUNT: usize = 256;
+
+    #[test]
+    fn allocator_grows_and_resets_transient_pools() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        let buffers: Vec<VMABuffer> = (0..2)
+            .map(|_| {
+                vk_init.create_local_buffer(
+                    COUNT * size_of::<u32>(),
+                    BufferUsageFlags::STORAGE_BUFFER | BufferUsageFlags::TRANSFER_SRC,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        let policy = DescriptorPoolPolicy {
+            initial_sets: 2,
+            growth: PoolGrowth::Chain,
+        };
+        let mut allocator = DescriptorAllocator::new(
+            policy,
+            &[(DescriptorType::STORAGE_BUFFER, 1)],
+            "Test_Allocator",
+        );
+        assert_eq!(allocator.pool_count(), 0);
+
+        // Sets 0..5 from pools of 2 sets: three persistent pools, none owned by the shader.
+        let code = read_spv(&mut Cursor::new(include_bytes!("scale.comp.spv")))?;
+        let mut shader = vk_init.create_compute_shader_with_allocator::<u32>(
+            &[ComputeBinding::Ssbo(&buffers[0])],
+            code,
+            [64, 1, 1],
+            &[],
+            String::from("Allocator_Scale"),
+            &allocator,
+        )?;
+        let mut last_set = 0;
+        for _ in 0..4 {
+            last_set = shader.allocate_set_from(
+                &vk_init,
+                &allocator,
+                &[ComputeBinding::Ssbo(&buffers[1])],
+            )?;
+        }
+        assert_eq!(allocator.pool_count(), 3);
+        assert_eq!(shader.descriptor_pool_count(), 0);
+        assert!(matches!(
+            shader.allocate_set(&vk_init, &[ComputeBinding::Ssbo(&buffers[1])]),
+            Err(Error::ComputeShaderWithoutPools)
+        ));
+
+        // Transient sets reuse their pools after a reset.
+        let bindings = [DescriptorSetLayoutBinding {
+            binding: 0,
+            descriptor_type: DescriptorType::STORAGE_BUFFER,
+            descriptor_count: 1,
+            stage_flags: ShaderStageFlags::COMPUTE,
+            ..Default::default()
+        }];
+        let layout_info = DescriptorSetLayoutCreateInfo::builder().bindings(&bindings);
+        let layout = unsafe {
+            vk_init
+                .device
+                .create_descriptor_set_layout(&layout_info, None)?
+        };
+        for _ in 0..3 {
+            allocator.allocate_transient(&vk_init, layout)?;
+        }
+        assert_eq!(allocator.pool_count(), 5);
+        allocator.reset_frame(&vk_init.device)?;
+        for _ in 0..3 {
+            allocator.allocate_transient(&vk_init, layout)?;
+        }
+        assert_eq!(allocator.pool_count(), 5);
+
+        // Layouts with descriptor types the pools do not hold fail without piling up pools.
+        let uniform_bindings = [DescriptorSetLayoutBinding {
+            binding: 0,
+            descriptor_type: DescriptorType::UNIFORM_BUFFER,
+            descriptor_count: 1,
+            stage_flags: ShaderStageFlags::COMPUTE,
+            ..Default::default()
+        }];
+        let uniform_layout_info =
+            DescriptorSetLayoutCreateInfo::builder().bindings(&uniform_bindings);
+        let uniform_layout = unsafe {
+            vk_init
+                .device
+                .create_descriptor_set_layout(&uniform_layout_info, None)?
+        };
+        for _ in 0..3 {
+            assert!(matches!(
+                allocator.allocate(&vk_init, uniform_layout),
+                Err(Error::VkError(ash::vk::Result::ERROR_OUT_OF_POOL_MEMORY))
+            ));
+        }
+        assert!(allocator.pool_count() <= 6);
+
+        // A set from a chained allocator pool binds like one from own pools.
+        let factor = 3_u32;
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            shader.bind_set(&vk_init.device, cmd_buffer, last_set, &factor.to_ne_bytes());
+            shader.dispatch(&vk_init.device, cmd_buffer, COUNT as u32, 1, 1);
+        })?;
+        let expected: Vec<u32> = (0..COUNT as u32).map(|i| i * factor).collect();
+        let read: Vec<u32> = vk_init.read_buffer(&buffers[1], 0, COUNT)?;
+        assert_eq!(read, expected);
+
+        shader.destroy(&vk_init)?;
+        allocator.destroy(&vk_init.device);
+        assert_eq!(allocator.pool_count(), 0);
+        unsafe {
+            vk_init.device.destroy_descriptor_set_layout(layout, None);
+            vk_init
+                .device
+                .destroy_descriptor_set_layout(uniform_layout, None);
+        }
+        for mut buffer in buffers {
+            buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        }
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,109 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::io::Cursor;
+    use std::mem::size_of;
+
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{
+        CmdType, ComputeBinding, DescriptorPoolPolicy, Error, PoolGrowth, VMABuffer, VkInit,
+    };
+
+    const COUNT: usize = 256;
+
+    #[test]
+    fn exhausted_pools_chain() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let buffers: Vec<VMABuffer> = (0..2)
+            .map(|_| {
+                vk_init.create_local_buffer(
+                    COUNT * size_of::<u32>(),
+                    BufferUsageFlags::STORAGE_BUFFER | BufferUsageFlags::TRANSFER_SRC,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+        let code = read_spv(&mut Cursor::new(include_bytes!("scale.comp.spv")))?;
+        let create = |vk_init: &VkInit, growth| {
+            let policy = DescriptorPoolPolicy {
+                initial_sets: 2,
+                growth,
+            };
+            vk_init.create_compute_shader_with_policy::<u32>(
+                &[ComputeBinding::Ssbo(&buffers[0])],
+                code.clone(),
+                [64, 1, 1],
+                &[],
+                format!("Pool_{growth:?}"),
+                policy,
+            )
+        };
+
+        // Fixed pools fail once the initial sets are used up.
+        let mut fixed = create(&vk_init, PoolGrowth::Fixed)?;
+        fixed.allocate_set(&vk_init, &[ComputeBinding::Ssbo(&buffers[1])])?;
+        assert!(matches!(
+            fixed.allocate_set(&vk_init, &[ComputeBinding::Ssbo(&buffers[1])]),
+            Err(Error::VkError(ash::vk::Result::ERROR_OUT_OF_POOL_MEMORY))
+        ));
+        assert_eq!(fixed.descriptor_pool_count(), 1);
+
+        // Sets 0..6: two pools of 2 and 4 sets when doubling, three pools of 2 sets when chaining.
+        let mut doubling = create(&vk_init, PoolGrowth::Double)?;
+        let mut chained = create(&vk_init, PoolGrowth::Chain)?;
+        let mut last_set = 0;
+        for _ in 0..5 {
+            doubling.allocate_set(&vk_init, &[ComputeBinding::Ssbo(&buffers[1])])?;
+            last_set = chained.allocate_set(&vk_init, &[ComputeBinding::Ssbo(&buffers[1])])?;
+        }
+        assert_eq!(doubling.descriptor_pool_count(), 2);
+        assert_eq!(chained.descriptor_pool_count(), 3);
+
+        // A set from a chained pool binds like the first one.
+        let factor = 5_u32;
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        let fence = vk_init.create_fence()?;
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[&cmd_buffer])?;
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        chained.bind_set(
+            &vk_init.device,
+            &cmd_buffer,
+            last_set,
+            &factor.to_ne_bytes(),
+        );
+        chained.dispatch(&vk_init.device, &cmd_buffer, COUNT as u32, 1, 1);
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &fence, &[], &[], &[])?;
+        vk_init.wait_for_fences_watched(&[fence], "chained dispatch")?;
+
+        let expected: Vec<u32> = (0..COUNT as u32).map(|i| i * factor).collect();
+        let read: Vec<u32> = vk_init.read_buffer(&buffers[1], 0, COUNT)?;
+        assert_eq!(read, expected);
+
+        vk_init.wait_device_idle()?;
+        for shader in [fixed, doubling, chained] {
+            shader.destroy(&vk_init)?;
+        }
+        for mut buffer in buffers {
+            buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        }
+        vk_init.destroy_fence(&fence)?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        Ok(())
+    }
+
+    #[test]
+    fn growth_sizes_follow_up_pools() {
+        let policy = |growth| DescriptorPoolPolicy {
+            initial_sets: 4,
+            growth,
+        };
+        assert_eq!(policy(PoolGrowth::Fixed).next_pool_sets(4), None);
+        assert_eq!(policy(PoolGrowth::Chain).next_pool_sets(4), Some(4));
+        assert_eq!(policy(PoolGrowth::Double).next_pool_sets(4), Some(8));
+        assert_eq!(policy(PoolGrowth::Double).next_pool_sets(8), Some(16));
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use vku::DeviceFeatures;
+
+    fn supported() -> DeviceFeatures {
+        let mut features = DeviceFeatures::default();
+        features.core.sampler_anisotropy = TRUE;
+        features.vk_1_2.descriptor_indexing = TRUE;
+        features.vk_1_2.timeline_semaphore = TRUE;
+        features.vk_1_3.dynamic_rendering = TRUE;
+        features.vk_1_3.synchronization2 = TRUE;
+        features
+    }
+
+    #[test]
+    fn supports_by_field_name() {
+        let features = supported();
+        assert_eq!(features.supports("sampler_anisotropy"), Some(true));
+        assert_eq!(features.supports("descriptor_indexing"), Some(true));
+        assert_eq!(features.supports("dynamic_rendering"), Some(true));
+        assert_eq!(features.supports("shader_float64"), Some(false));
+        assert_eq!(features.supports("multiview"), Some(false));
+        assert_eq!(features.supports("dynamicRendering"), None);
+    }
+
+    #[test]
+    fn names_first_missing_feature() {
+        let mut requested = DeviceFeatures::default();
+        requested.vk_1_2.timeline_semaphore = TRUE;
+        requested.vk_1_3.synchronization2 = TRUE;
+        assert_eq!(supported().first_missing(&requested), None);
+
+        requested
+            .vk_1_2
+            .descriptor_binding_sampled_image_update_after_bind = TRUE;
+        requested.vk_1_3.maintenance4 = TRUE;
+        assert_eq!(
+            supported().first_missing(&requested),
+            Some("descriptor_binding_sampled_image_update_after_bind")
+        );
+    }
+}
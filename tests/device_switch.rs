@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, GpuSelection, VkInit};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    #[test]
+    fn switch_device_recreates_head() -> Result<(), Error> {
+        use vku::VkInitCreateInfo;
+        use winit::dpi::LogicalSize;
+        use winit::event_loop::{EventLoop, EventLoopBuilder};
+        use winit::window::WindowBuilder;
+
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+        let mut vk_init = VkInit::new(Some(&window), Some(size), VkInitCreateInfo::default())?;
+
+        let forgotten = vk_init.create_local_buffer(256, BufferUsageFlags::STORAGE_BUFFER)?;
+        forgotten.set_debug_object_name(&vk_init, String::from("Forgotten"))?;
+
+        assert!(matches!(
+            vk_init.switch_device(
+                GpuSelection::NamedSubstring(String::from("no such gpu")),
+                Some(&window),
+                Some(size)
+            ),
+            Err(Error::NoSuitableGPUFound)
+        ));
+        assert_eq!(vk_init.device_generation(), 0);
+
+        let report = vk_init.switch_device(GpuSelection::Index(0), Some(&window), Some(size))?;
+        assert_eq!(report.device_generation, 1);
+        assert_eq!(vk_init.device_generation(), 1);
+        assert!(report.recreated.contains(&"swapchain"));
+        assert!(report.recreated.contains(&"depth image"));
+        if cfg!(debug_assertions) {
+            assert_eq!(report.invalidated.len(), 1);
+            assert_eq!(report.invalidated[0].name.as_deref(), Some("Forgotten"));
+        }
+        assert!(
+            vk_init.live_resources().len() <= 1,
+            "only the depth image is alive"
+        );
+        // The buffer's memory went away with the old device.
+        std::mem::forget(forgotten);
+
+        let mut buffer = vk_init.create_local_buffer(256, BufferUsageFlags::STORAGE_BUFFER)?;
+        buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
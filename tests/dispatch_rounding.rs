@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::mem::size_of;
+
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, ComputeBinding, Error, VkInit};
+
+    const GROUP_SIZE: u32 = 256;
+
+    #[test]
+    fn dispatch_covers_partial_groups() -> Result<(), Error> {
+        use vku::VkInitCreateInfo;
+
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        let count = 1000_u32;
+        let mut values = vk_init.create_local_buffer(
+            count as usize * size_of::<u32>(),
+            BufferUsageFlags::STORAGE_BUFFER
+                | BufferUsageFlags::TRANSFER_DST
+                | BufferUsageFlags::TRANSFER_SRC,
+        )?;
+        let code = read_spv(&mut Cursor::new(include_bytes!("invocation_id.comp.spv")))?;
+        let compute_shader = vk_init.create_compute_shader::<u32>(
+            &[ComputeBinding::Ssbo(&values)],
+            code,
+            [GROUP_SIZE, 1, 1],
+            &[],
+            String::from("Invocation_Id"),
+        )?;
+
+        // 1000 invocations need 4 groups of 256, the last one only partially used.
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            compute_shader.bind(&vk_init.device, cmd_buffer, &count.to_ne_bytes());
+            compute_shader.dispatch(&vk_init.device, cmd_buffer, count, 1, 1);
+        })?;
+        let read: Vec<u32> = vk_init.read_buffer(&values, 0, count as usize)?;
+        assert_eq!(read, (0..count).collect::<Vec<u32>>());
+
+        // Exact group counts are taken as is, a single group leaves the tail untouched.
+        let fill_to_compute = BufferMemoryBarrier2::builder()
+            .buffer(values.buffer)
+            .size(WHOLE_SIZE)
+            .src_stage_mask(PipelineStageFlags2::CLEAR)
+            .src_access_mask(AccessFlags2::TRANSFER_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::COMPUTE_SHADER)
+            .dst_access_mask(AccessFlags2::SHADER_STORAGE_WRITE)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .build();
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            unsafe {
+                vk_init
+                    .device
+                    .cmd_fill_buffer(*cmd_buffer, values.buffer, 0, WHOLE_SIZE, u32::MAX);
+            }
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[], &[fill_to_compute]);
+            compute_shader.bind(&vk_init.device, cmd_buffer, &count.to_ne_bytes());
+            compute_shader.dispatch_exact(&vk_init.device, cmd_buffer, 1, 1, 1);
+        })?;
+        let read: Vec<u32> = vk_init.read_buffer(&values, 0, count as usize)?;
+        assert_eq!(read[GROUP_SIZE as usize - 1], GROUP_SIZE - 1);
+        assert_eq!(read[count as usize - 1], u32::MAX);
+
+        compute_shader.destroy(&vk_init)?;
+        values.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
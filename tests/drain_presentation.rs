@@ -0,0 +1,29 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::result::Result;
+    use vku::Error;
+
+    #[test]
+    fn drains_acquired_image() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let acquire_semaphore = vk_init.create_semaphore()?;
+        vk_init.acquire_next_swapchain_image(acquire_semaphore, None)?;
+
+        vk_init.begin_debug_message_capture();
+        vk_init.drain_presentation()?;
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+
+        // The semaphore was waited on and the image given back, so both can be reused.
+        vk_init.acquire_next_swapchain_image(acquire_semaphore, None)?;
+        vk_init.drain_presentation()?;
+        vk_init.destroy_semaphore(&acquire_semaphore)?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,127 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::pipeline_builder::BlendMode;
+    use vku::{CaptureOptions, CmdType, Error, VKUPipeline, VMAImage};
+
+    const SIZE: u32 = 4;
+
+    #[test]
+    fn draw_without_unsafe() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let format = Format::R8G8B8A8_UNORM;
+        let stages = ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT;
+        let mut pipeline = VKUPipeline::builder()
+            .with_vertex::<()>(PrimitiveTopology::TRIANGLE_LIST)
+            .with_viewports_scissors(&[Viewport::default()], &[Rect2D::default()])
+            .with_rasterization(PolygonMode::FILL, CullModeFlags::NONE)
+            .with_multisample(SampleCountFlags::TYPE_1)
+            .with_colorblends(&[BlendMode::Opaque])
+            .with_dynamic(&[DynamicState::VIEWPORT, DynamicState::SCISSOR])
+            .with_push_constants::<[f32; 4]>()
+            .push_shader_stage(
+                &vk_init.device,
+                ShaderStageFlags::VERTEX,
+                "./src/shaders/fullscreen.vert.spv",
+                &[],
+            )?
+            .push_shader_stage(
+                &vk_init.device,
+                ShaderStageFlags::FRAGMENT,
+                "./tests/push_color.frag.spv",
+                &[],
+            )?
+            .with_rendering(&[format], None, None)
+            .build(&vk_init, "Push_Color")?;
+
+        let extent = Extent3D {
+            width: SIZE,
+            height: SIZE,
+            depth: 1,
+        };
+        let mut target = VMAImage::create_render_image(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            extent,
+            format,
+            4,
+        )?;
+        let to_attachment = target.get_image_layout_transition_barrier2(
+            ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            None,
+            None,
+        )?;
+        let to_sampled = target.get_image_layout_transition_barrier2(
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            None,
+            None,
+        )?;
+        let render_area = Rect2D {
+            offset: Offset2D { x: 0, y: 0 },
+            extent: Extent2D {
+                width: SIZE,
+                height: SIZE,
+            },
+        };
+        let viewport = Viewport {
+            width: SIZE as f32,
+            height: SIZE as f32,
+            max_depth: 1.0,
+            ..Default::default()
+        };
+        let color_attachments = [RenderingAttachmentInfo::builder()
+            .image_view(target.image_view)
+            .image_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .load_op(AttachmentLoadOp::DONT_CARE)
+            .store_op(AttachmentStoreOp::STORE)
+            .build()];
+        let rendering_info = RenderingInfo::builder()
+            .render_area(render_area)
+            .layer_count(1)
+            .color_attachments(&color_attachments);
+
+        let mut oversized = Ok(());
+        let mut pushed = Ok(());
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_attachment], &[]);
+            vk_init.cmd_begin_rendering(cmd_buffer, &rendering_info);
+            vk_init.cmd_bind_graphics_pipeline(cmd_buffer, &pipeline);
+            vk_init.cmd_set_viewport(cmd_buffer, &[viewport]);
+            vk_init.cmd_set_scissor(cmd_buffer, &[render_area]);
+            oversized = vk_init.cmd_push_constants_typed(
+                cmd_buffer,
+                pipeline.layout,
+                stages,
+                &[0_u8; 1 << 16],
+            );
+            pushed = vk_init.cmd_push_constants_typed(
+                cmd_buffer,
+                pipeline.layout,
+                stages,
+                &[1.0_f32, 0.2, 0.6, 1.0],
+            );
+            vk_init.cmd_draw(cmd_buffer, 3, 1, 0, 0);
+            vk_init.end_rendering(cmd_buffer);
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_sampled], &[]);
+        })?;
+        assert!(matches!(
+            oversized,
+            Err(Error::PushConstantsTooLarge { size: 65536, .. })
+        ));
+        pushed?;
+
+        let captured = vk_init.capture_image(&target, CaptureOptions::AsIs)?;
+        assert!(captured
+            .rgba8
+            .chunks(4)
+            .all(|texel| texel == [255, 51, 153, 255]));
+
+        target.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        pipeline.destroy(&vk_init.device)?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,99 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::mem::size_of;
+
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::prelude::*;
+
+    #[repr(C)]
+    struct Vertex2D {
+        pub pos: [f32; 4],
+        pub uv: [f32; 2],
+        pub vol: [u8; 4],
+    }
+
+    impl VertexConvert for Vertex2D {
+        fn binding_desc() -> Vec<VertexInputBindingDescription> {
+            vec![VertexInputBindingDescription {
+                stride: size_of::<Self>() as u32,
+                input_rate: VertexInputRate::VERTEX,
+                binding: 0,
+            }]
+        }
+
+        fn attrib_desc() -> Vec<VertexInputAttributeDescription> {
+            vec![
+                VertexInputAttributeDescription {
+                    binding: 0,
+                    location: 0,
+                    offset: 0,
+                    format: Format::R32G32B32A32_SFLOAT,
+                },
+                VertexInputAttributeDescription {
+                    binding: 0,
+                    location: 1,
+                    offset: 16,
+                    format: Format::R32G32_SFLOAT,
+                },
+                VertexInputAttributeDescription {
+                    binding: 0,
+                    location: 2,
+                    offset: 24,
+                    format: Format::R8G8B8A8_UNORM,
+                },
+            ]
+        }
+    }
+
+    #[repr(C)]
+    struct Push {
+        pub mat_0: [f32; 16],
+        pub vec_0: [f32; 4],
+        pub vec_1: [f32; 4],
+        pub vec_2: [f32; 4],
+        pub vec_3: [f32; 4],
+    }
+
+    #[test]
+    fn dynamic_rendering_pipeline() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+
+        let mut pipeline = VKUPipeline::builder()
+            .with_vertex::<Vertex2D>(PrimitiveTopology::TRIANGLE_LIST)
+            .with_viewports_scissors(&[Viewport::default()], &[Rect2D::default()]) // using dynamic viewport/scissor later
+            .with_rasterization(PolygonMode::FILL, CullModeFlags::BACK)
+            .with_multisample(SampleCountFlags::TYPE_1)
+            .with_depthstencil(DepthInfo::enabled_positive_depth(), StencilInfo::default())
+            .with_colorblends(&[BlendMode::TraditionalTransparency])
+            .with_dynamic(&[DynamicState::VIEWPORT, DynamicState::SCISSOR])
+            .with_push_constants::<Push>()
+            .with_descriptors(&[(
+                false,
+                DescriptorType::COMBINED_IMAGE_SAMPLER,
+                ShaderStageFlags::FRAGMENT,
+                1,
+            )])
+            .push_shader_stage(
+                &vk_init.device,
+                ShaderStageFlags::VERTEX,
+                "./tests/default.vert.spv",
+                &[],
+            )?
+            .push_shader_stage(
+                &vk_init.device,
+                ShaderStageFlags::FRAGMENT,
+                "./tests/default.frag.spv",
+                &[],
+            )?
+            .with_rendering(&[Format::R8G8B8A8_UNORM], Some(Format::D32_SFLOAT), None)
+            .build(&vk_init, "Dynamic_Rendering_Pipeline")?;
+        assert_eq!(pipeline.renderpass, RenderPass::null());
+
+        pipeline.destroy(&vk_init.device)?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, UploadRing, VkInit, VkInitCreateInfo};
+
+    #[test]
+    fn offsets_respect_alignment() -> Result<(), Error> {
+        let mut ring = UploadRing::new(1024, 2, 256);
+        assert_eq!(ring.region_size(), 512);
+        assert_eq!(ring.push(4)?, 0);
+        assert_eq!(ring.push(100)?, 256);
+        assert_eq!(ring.available(), 0);
+        assert_eq!(ring.push(0)?, 512);
+
+        // Regions are rounded down to the alignment.
+        let ring = UploadRing::new(1000, 3, 64);
+        assert_eq!(ring.region_size(), 320);
+        assert_eq!(UploadRing::new(100, 1, 0).alignment(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn overflow_is_an_error() -> Result<(), Error> {
+        let mut ring = UploadRing::new(512, 2, 64);
+        ring.push(100)?;
+        assert_eq!(ring.available(), 128);
+        assert!(matches!(
+            ring.push(129),
+            Err(Error::UploadBufferOverflow {
+                requested: 129,
+                available: 128
+            })
+        ));
+        // The failed push reserved nothing.
+        assert_eq!(ring.push(128)?, 128);
+        Ok(())
+    }
+
+    #[test]
+    fn regions_wrap_after_fence() -> Result<(), Error> {
+        let mut ring = UploadRing::new(300, 3, 4);
+        ring.push(100)?;
+        assert!(matches!(
+            ring.next_frame(false),
+            Err(Error::UploadRegionInFlight(1))
+        ));
+        assert_eq!(ring.region(), 0);
+        ring.next_frame(true)?;
+        assert_eq!(ring.push(8)?, 100);
+        ring.next_frame(true)?;
+        ring.next_frame(true)?;
+        assert_eq!(ring.region(), 0);
+        assert_eq!(ring.push(8)?, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn allocations_land_at_their_offsets() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        let mut uploads =
+            vk_init.create_dynamic_upload_buffer(64 * 1024, 2, BufferUsageFlags::UNIFORM_BUFFER)?;
+        let alignment = uploads.ring().alignment();
+        assert_eq!(
+            alignment,
+            vk_init
+                .physical_device_info
+                .limits
+                .min_uniform_buffer_offset_alignment
+                .max(1)
+        );
+
+        let first = uploads.allocate(&vk_init.device, &[1_u32, 2, 3])?;
+        let second = uploads.allocate(&vk_init.device, &[4_u32])?;
+        assert_eq!(first, 0);
+        assert_eq!(second, 12_u64.next_multiple_of(alignment));
+        assert_eq!(
+            uploads.buffer.get_data::<u32>(&vk_init.device, 0, 3)?,
+            [1, 2, 3]
+        );
+        assert_eq!(
+            uploads
+                .buffer
+                .get_data::<u32>(&vk_init.device, second as usize / 4, 1)?,
+            [4]
+        );
+
+        uploads.next_frame(true)?;
+        assert_eq!(
+            uploads.allocate(&vk_init.device, &[5_u32])?,
+            uploads.ring().region_size()
+        );
+
+        uploads.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
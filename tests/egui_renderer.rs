@@ -0,0 +1,93 @@
+#[cfg(test)]
+#[cfg(feature = "egui")]
+mod tests {
+    use ash::vk::*;
+    use egui::{pos2, Color32, Context, LayerId, RawInput, Rect};
+    use std::result::Result;
+    use vku::{CmdType, EguiRenderer, Error, RenderingConfig, VkInit, VkInitCreateInfo};
+
+    #[test]
+    fn draws_egui_shapes() -> Result<(), Error> {
+        env_logger::init();
+        let extent = Extent2D {
+            width: 32,
+            height: 16,
+        };
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        vk_init.begin_debug_message_capture();
+        vk_init.create_offscreen_head(extent, Format::R8G8B8A8_UNORM, Format::D32_SFLOAT, 1)?;
+        let mut renderer = EguiRenderer::new(&vk_init)?;
+
+        let ctx = Context::default();
+        let input = RawInput {
+            screen_rect: Some(Rect::from_min_max(pos2(0.0, 0.0), pos2(32.0, 16.0))),
+            ..Default::default()
+        };
+        let output = ctx.run(input, |ctx| {
+            ctx.layer_painter(LayerId::background()).rect_filled(
+                Rect::from_min_max(pos2(0.0, 0.0), pos2(16.0, 16.0)),
+                0.0,
+                Color32::RED,
+            );
+        });
+        let primitives = ctx.tessellate(output.shapes, output.pixels_per_point);
+        renderer.update_textures(&mut vk_init, 0, &output.textures_delta)?;
+        renderer.upload(&vk_init, 0, &primitives, output.pixels_per_point)?;
+
+        let target = vk_init.acquire_offscreen_image()?;
+        let mut offscreen = vk_init.offscreen_head.take().unwrap();
+        let to_attachment = [
+            offscreen.images[target.index].get_image_layout_transition_barrier2(
+                ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                None,
+                None,
+            )?,
+        ];
+        vk_init.offscreen_head = Some(offscreen);
+        let config = RenderingConfig {
+            depth: false,
+            clear_color_value: Some(ClearColorValue {
+                float32: [0.0, 0.0, 0.0, 1.0],
+            }),
+            ..Default::default()
+        };
+
+        let mut recorded = Ok(());
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &to_attachment, &[]);
+            recorded = vk_init
+                .begin_rendering_with(&target.view, cmd_buffer, &config)
+                .and_then(|_| {
+                    let screen_to_ndc =
+                        EguiRenderer::screen_to_ndc(extent, output.pixels_per_point);
+                    let recorded = renderer.draw(&vk_init, cmd_buffer, 0, extent, screen_to_ndc);
+                    vk_init.end_rendering(cmd_buffer);
+                    recorded
+                });
+        })?;
+        recorded?;
+
+        let mut offscreen = vk_init.offscreen_head.take().unwrap();
+        let pixels = vk_init.download_image(&mut offscreen.images[target.index])?;
+        vk_init.offscreen_head = Some(offscreen);
+        for (index, texel) in pixels.chunks(4).enumerate() {
+            let expected = match index % 32 < 16 {
+                true => [255, 0, 0, 255],
+                false => [0, 0, 0, 255],
+            };
+            assert_eq!(texel, expected, "texel {index}");
+        }
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+
+        // Frames added to the frame scheme get their own buffers.
+        vk_init.set_frames_in_flight(2);
+        renderer.upload(&vk_init, 1, &primitives, output.pixels_per_point)?;
+        renderer.destroy(&vk_init)?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ash::vk::*;
+    use vku::{GpuSelection, VkInitCreateInfo};
+
+    fn with_vars(vars: &[(&str, &str)]) -> VkInitCreateInfo {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let mut create_info = VkInitCreateInfo::dist_vk_1_3();
+        create_info.apply_overrides_from(|name| vars.get(name).cloned());
+        create_info
+    }
+
+    #[test]
+    fn no_variables_change_nothing() {
+        let create_info = with_vars(&[]);
+        let dist = VkInitCreateInfo::dist_vk_1_3();
+        assert_eq!(create_info.enable_validation, dist.enable_validation);
+        assert_eq!(create_info.log_level, dist.log_level);
+        assert_eq!(
+            create_info.present_mode_preferences,
+            dist.present_mode_preferences
+        );
+        assert_eq!(create_info.gpu_preference, dist.gpu_preference);
+        assert_eq!(create_info.allow_igpu, dist.allow_igpu);
+        assert_eq!(create_info.request_img_count, dist.request_img_count);
+    }
+
+    #[test]
+    fn validation() {
+        for value in ["1", "true", "ON", "yes"] {
+            let create_info = with_vars(&[("VKU_VALIDATION", value)]);
+            assert!(create_info.enable_validation);
+            assert_eq!(
+                create_info.enabled_validation_layers,
+                vec![String::from("VK_LAYER_KHRONOS_validation")]
+            );
+        }
+        for value in ["0", "false", "off", "No"] {
+            let mut create_info = VkInitCreateInfo::debug_vk_1_3();
+            create_info
+                .apply_overrides_from(|name| (name == "VKU_VALIDATION").then(|| value.to_string()));
+            assert!(!create_info.enable_validation);
+        }
+        assert!(!with_vars(&[("VKU_VALIDATION", "maybe")]).enable_validation);
+    }
+
+    #[test]
+    fn log_level() {
+        let expected = [
+            (
+                "verbose",
+                DebugUtilsMessageSeverityFlagsEXT::from_raw(0x1111),
+            ),
+            ("info", DebugUtilsMessageSeverityFlagsEXT::from_raw(0x1110)),
+            (
+                "warning",
+                DebugUtilsMessageSeverityFlagsEXT::from_raw(0x1100),
+            ),
+            ("warn", DebugUtilsMessageSeverityFlagsEXT::from_raw(0x1100)),
+            ("error", DebugUtilsMessageSeverityFlagsEXT::ERROR),
+            ("none", DebugUtilsMessageSeverityFlagsEXT::empty()),
+        ];
+        for (value, log_level) in expected {
+            assert_eq!(with_vars(&[("VKU_LOG_LEVEL", value)]).log_level, log_level);
+        }
+        assert_eq!(
+            with_vars(&[("VKU_LOG_LEVEL", "loud")]).log_level,
+            DebugUtilsMessageSeverityFlagsEXT::empty()
+        );
+    }
+
+    #[test]
+    fn present_mode() {
+        let expected = [
+            ("immediate", PresentModeKHR::IMMEDIATE),
+            ("Mailbox", PresentModeKHR::MAILBOX),
+            ("fifo", PresentModeKHR::FIFO),
+            ("fifo_relaxed", PresentModeKHR::FIFO_RELAXED),
+        ];
+        for (value, present_mode) in expected {
+            assert_eq!(
+                with_vars(&[("VKU_PRESENT_MODE", value)]).present_mode_preferences,
+                vec![present_mode]
+            );
+        }
+        assert_eq!(
+            with_vars(&[("VKU_PRESENT_MODE", "vsync")]).present_mode_preferences,
+            vec![PresentModeKHR::FIFO]
+        );
+    }
+
+    #[test]
+    fn device() {
+        assert_eq!(
+            with_vars(&[("VKU_DEVICE", "1")]).gpu_preference,
+            GpuSelection::Index(1)
+        );
+        assert_eq!(
+            with_vars(&[("VKU_DEVICE", "Radeon")]).gpu_preference,
+            GpuSelection::NamedSubstring(String::from("Radeon"))
+        );
+        assert_eq!(
+            with_vars(&[("VKU_DEVICE", "highest_memory")]).gpu_preference,
+            GpuSelection::HighestMemory
+        );
+        assert_eq!(
+            with_vars(&[("VKU_DEVICE", " ")]).gpu_preference,
+            GpuSelection::FirstDiscrete
+        );
+    }
+
+    #[test]
+    fn allow_igpu() {
+        assert!(with_vars(&[("VKU_ALLOW_IGPU", "1")]).allow_igpu);
+        assert!(!with_vars(&[("VKU_ALLOW_IGPU", "0")]).allow_igpu);
+        assert!(!with_vars(&[("VKU_ALLOW_IGPU", "igpu")]).allow_igpu);
+    }
+
+    #[test]
+    fn image_count() {
+        assert_eq!(with_vars(&[("VKU_IMAGE_COUNT", "2")]).request_img_count, 2);
+        for invalid in ["0", "-1", "two"] {
+            assert_eq!(
+                with_vars(&[("VKU_IMAGE_COUNT", invalid)]).request_img_count,
+                3
+            );
+        }
+    }
+}
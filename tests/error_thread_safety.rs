@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use vku::Error;
+
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    #[test]
+    fn error_is_send_and_sync() {
+        assert_send::<Error>();
+        assert_sync::<Error>();
+    }
+
+    #[test]
+    fn catch_moves_across_threads() {
+        let worker = thread::spawn(|| -> Result<(), Error> {
+            let parsed: Result<u32, _> = "not a number".parse::<u32>();
+            parsed.map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)?;
+            Ok(())
+        });
+        let err = worker.join().unwrap().unwrap_err();
+        assert!(matches!(err, Error::Catch(_)));
+        assert_eq!(
+            err.to_string(),
+            "encountered an unknown error: invalid digit found in string"
+        );
+    }
+}
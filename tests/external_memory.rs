@@ -0,0 +1,140 @@
+#[cfg(feature = "external")]
+mod common;
+
+#[cfg(all(test, feature = "external"))]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::time::Duration;
+
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, ExternalHandle, ExternalImageDesc, VMAImage};
+
+    #[test]
+    fn export_and_reimport_image_memory() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let support = vk_init.physical_device_info.external_handles;
+        if !(support.memory_fd || support.memory_win32) {
+            return Ok(());
+        }
+
+        let extent = Extent3D {
+            width: 4,
+            height: 4,
+            depth: 1,
+        };
+        let format = Format::R8G8B8A8_UNORM;
+        let usage = ImageUsageFlags::TRANSFER_SRC | ImageUsageFlags::TRANSFER_DST;
+        let handle_type = ExternalHandle::platform_memory_handle_type();
+
+        let mut exported = VMAImage::create_exportable(
+            &vk_init,
+            extent,
+            format,
+            usage,
+            ImageAspectFlags::COLOR,
+            handle_type,
+        )?;
+        let handle = exported.export_memory_handle(&vk_init)?;
+        let desc = ExternalImageDesc {
+            extent,
+            format,
+            usage,
+            tiling: ImageTiling::OPTIMAL,
+            aspect_flags: ImageAspectFlags::COLOR,
+            handle,
+            handle_type,
+            allocation_size: Some(exported.allocation_info().size),
+            dedicated: true,
+        };
+        let mut imported = VMAImage::import_external(&vk_init, &desc)?;
+        assert_eq!(
+            imported.allocation_info().size,
+            exported.allocation_info().size
+        );
+        assert!(matches!(
+            imported.export_memory_handle(&vk_init),
+            Err(Error::ImageMemoryNotExportable)
+        ));
+
+        // Clear through the exported image, read back through the imported one.
+        let mut slot = vk_init.create_readback_slots(64, 1)?.remove(0);
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        let barrier = exported.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_DST_OPTIMAL,
+            None,
+            None,
+        )?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[barrier], &[]);
+        let range = ImageSubresourceRange {
+            aspect_mask: ImageAspectFlags::COLOR,
+            level_count: 1,
+            layer_count: 1,
+            ..Default::default()
+        };
+        unsafe {
+            vk_init.device.cmd_clear_color_image(
+                cmd_buffer,
+                exported.image,
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+                &ClearColorValue {
+                    float32: [0.0, 0.0, 1.0, 1.0],
+                },
+                &[range],
+            )
+        };
+
+        imported.current_layout = ImageLayout::TRANSFER_DST_OPTIMAL;
+        let barrier = imported.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_SRC_OPTIMAL,
+            None,
+            None,
+        )?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[barrier], &[]);
+        let region = BufferImageCopy::builder()
+            .image_subresource(
+                ImageSubresourceLayers::builder()
+                    .aspect_mask(ImageAspectFlags::COLOR)
+                    .layer_count(1)
+                    .build(),
+            )
+            .image_extent(extent)
+            .build();
+        unsafe {
+            vk_init.device.cmd_copy_image_to_buffer(
+                cmd_buffer,
+                imported.image,
+                ImageLayout::TRANSFER_SRC_OPTIMAL,
+                slot.buffer.buffer,
+                &[region],
+            )
+        };
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &slot.fence, &[], &[], &[])?;
+        let pixels: Vec<[u8; 4]> = slot.read_blocking(&vk_init, Duration::from_secs(1))?;
+        assert!(pixels.iter().all(|pixel| *pixel == [0, 0, 255, 255]));
+
+        // Semaphore payloads can be moved between semaphores the same way.
+        let semaphore_type = ExternalHandle::platform_semaphore_handle_type();
+        let semaphore_features = vk_init.external_semaphore_features(semaphore_type);
+        if semaphore_features.contains(
+            ExternalSemaphoreFeatureFlags::EXPORTABLE | ExternalSemaphoreFeatureFlags::IMPORTABLE,
+        ) {
+            let exportable = vk_init.create_exportable_semaphore(semaphore_type)?;
+            let semaphore_handle = vk_init.export_semaphore(&exportable, semaphore_type)?;
+            let target = vk_init.create_semaphore()?;
+            vk_init.import_semaphore(&target, semaphore_handle, semaphore_type, false)?;
+            vk_init.destroy_semaphore(&target)?;
+            vk_init.destroy_semaphore(&exportable)?;
+        }
+
+        vk_init.wait_device_idle()?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        imported.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        exported.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, FragmentSource, FullscreenPass, VkInit};
+
+    /// Clears to ```clear```, then multiplies the destination by ```source``` twice, each draw in its own rendering scope.
+    fn multiply_destination(
+        vk_init: &mut VkInit,
+        pass: &FullscreenPass,
+        layout: ImageLayout,
+        clear: [f32; 4],
+        source: [f32; 4],
+    ) -> Result<Vec<[u8; 4]>, Error> {
+        let extent = Extent3D {
+            width: 4,
+            height: 4,
+            depth: 1,
+        };
+        let pixel_count = (extent.width * extent.height) as usize;
+        let mut target = vk_init.create_feedback_loop_image(extent, Format::R8G8B8A8_UNORM, 4)?;
+        let slot = vk_init.create_readback_slots(pixel_count * 4, 1)?.remove(0);
+
+        let sampler_info = SamplerCreateInfo::builder()
+            .mag_filter(Filter::NEAREST)
+            .min_filter(Filter::NEAREST);
+        let sampler = unsafe { vk_init.device.create_sampler(&sampler_info, None)? };
+
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+
+        let barrier = target.feedback_loop_barrier(layout)?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[barrier], &[]);
+
+        let render_area = Rect2D {
+            offset: Offset2D { x: 0, y: 0 },
+            extent: Extent2D {
+                width: extent.width,
+                height: extent.height,
+            },
+        };
+        let range = ImageSubresourceRange {
+            aspect_mask: ImageAspectFlags::COLOR,
+            level_count: 1,
+            layer_count: 1,
+            ..Default::default()
+        };
+        let push: Vec<u8> = source.iter().flat_map(|v| v.to_ne_bytes()).collect();
+
+        for draw in 0..3 {
+            let load_op = match draw {
+                0 => AttachmentLoadOp::CLEAR,
+                _ => AttachmentLoadOp::LOAD,
+            };
+            let color_attachments = [RenderingAttachmentInfo::builder()
+                .image_view(target.image_view)
+                .image_layout(layout)
+                .load_op(load_op)
+                .store_op(AttachmentStoreOp::STORE)
+                .clear_value(ClearValue {
+                    color: ClearColorValue { float32: clear },
+                })
+                .build()];
+            let rendering_info = RenderingInfo::builder()
+                .render_area(render_area)
+                .layer_count(1)
+                .color_attachments(&color_attachments);
+
+            if draw > 0 {
+                vk_init.cmd_feedback_loop_barrier(&cmd_buffer, target.image, layout, range);
+            }
+            vk_init.cmd_begin_rendering(&cmd_buffer, &rendering_info);
+            if draw > 0 {
+                pass.record_feedback_loop(vk_init, &cmd_buffer, &target, sampler, &push)?;
+            }
+            vk_init.end_rendering(&cmd_buffer);
+        }
+
+        let barrier = target.feedback_loop_barrier(ImageLayout::TRANSFER_SRC_OPTIMAL)?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[barrier], &[]);
+        let region = BufferImageCopy::builder()
+            .image_subresource(
+                ImageSubresourceLayers::builder()
+                    .aspect_mask(ImageAspectFlags::COLOR)
+                    .layer_count(1)
+                    .build(),
+            )
+            .image_extent(extent)
+            .build();
+        unsafe {
+            vk_init.device.cmd_copy_image_to_buffer(
+                cmd_buffer,
+                target.image,
+                ImageLayout::TRANSFER_SRC_OPTIMAL,
+                slot.buffer.buffer,
+                &[region],
+            )
+        };
+
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &slot.fence, &[], &[], &[])?;
+        let pixels: Vec<[u8; 4]> = slot.read_blocking(vk_init, Duration::from_secs(1))?;
+
+        vk_init.wait_device_idle()?;
+        unsafe { vk_init.device.destroy_sampler(sampler, None) };
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        target.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+
+        Ok(pixels)
+    }
+
+    #[test]
+    fn multiply_destination_by_source() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let multiply_spirv = read_spv(&mut Cursor::new(&include_bytes!("multiply.frag.spv")[..]))?;
+        let pass = FullscreenPass::new_feedback_loop::<[f32; 4]>(
+            &vk_init,
+            FragmentSource::Spirv(&multiply_spirv),
+            Format::R8G8B8A8_UNORM,
+            "Multiply_Pass",
+        )?;
+
+        // Exercises the GENERAL fallback even if the extension is supported.
+        let mut layouts = vec![ImageLayout::GENERAL];
+        if vk_init.physical_device_info.attachment_feedback_loop_layout {
+            assert_eq!(
+                vk_init.feedback_loop_layout(),
+                ImageLayout::ATTACHMENT_FEEDBACK_LOOP_OPTIMAL_EXT
+            );
+            layouts.push(ImageLayout::ATTACHMENT_FEEDBACK_LOOP_OPTIMAL_EXT);
+        } else {
+            assert_eq!(vk_init.feedback_loop_layout(), ImageLayout::GENERAL);
+        }
+
+        // 1.0 * 0.5 * 0.5 = 0.25, 0.8 * 1.0 * 1.0 = 0.8, 0.2 * 0.0 * 0.0 = 0.0
+        let expected = [64_u8, 204, 0, 255];
+        for layout in layouts {
+            let pixels = multiply_destination(
+                &mut vk_init,
+                &pass,
+                layout,
+                [1.0, 0.8, 0.2, 1.0],
+                [0.5, 1.0, 0.0, 1.0],
+            )?;
+            for pixel in pixels {
+                assert!(
+                    pixel
+                        .iter()
+                        .zip(expected)
+                        .all(|(channel, expected)| channel.abs_diff(expected) <= 1),
+                    "{layout:?}: {pixel:?} != {expected:?}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::result::Result;
+    use vku::{CaptureOptions, ColorSpaceIntent, Error, VMAImage};
+
+    const GRADIENT_WIDTH: u32 = 64;
+    const GRADIENT_HEIGHT: u32 = 2;
+
+    fn gradient() -> Vec<u8> {
+        (0..GRADIENT_HEIGHT)
+            .flat_map(|_| 0..GRADIENT_WIDTH)
+            .flat_map(|x| {
+                let value = (x * 255 / (GRADIENT_WIDTH - 1)) as u8;
+                [value, 255 - value, value / 2, 255]
+            })
+            .collect()
+    }
+
+    fn assert_close(captured: &[u8], expected: &[u8], case: &str) {
+        assert_eq!(captured.len(), expected.len());
+        for (index, (captured, expected)) in captured.iter().zip(expected).enumerate() {
+            assert!(
+                captured.abs_diff(*expected) <= 1,
+                "{case}: byte {index} captured as {captured}, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn srgb_gradient_captures_stored_bytes() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let pixels = gradient();
+
+        // Sampling decodes the sRGB image, the capture has to encode it again.
+        let mut srgb = VMAImage::from_rgba8(
+            &mut vk_init,
+            GRADIENT_WIDTH,
+            GRADIENT_HEIGHT,
+            &pixels,
+            ColorSpaceIntent::SrgbTexture,
+            None,
+        )?;
+        for options in [CaptureOptions::AsIs, CaptureOptions::ConvertToSrgb8] {
+            let captured = vk_init.capture_image(&srgb, options)?;
+            assert_eq!(
+                [captured.width, captured.height],
+                [GRADIENT_WIDTH, GRADIENT_HEIGHT]
+            );
+            assert_close(&captured.rgba8, &pixels, &format!("SRGB {options:?}"));
+        }
+
+        // UNORM values are taken as already encoded and kept.
+        let mut unorm = VMAImage::from_rgba8(
+            &mut vk_init,
+            GRADIENT_WIDTH,
+            GRADIENT_HEIGHT,
+            &pixels,
+            ColorSpaceIntent::LinearData,
+            None,
+        )?;
+        let captured = vk_init.capture_image(&unorm, CaptureOptions::ConvertToSrgb8)?;
+        assert_close(&captured.rgba8, &pixels, "UNORM ConvertToSrgb8");
+
+        // Tonemapping compresses values towards black and keeps the endpoints ordered.
+        let captured =
+            vk_init.capture_image(&srgb, CaptureOptions::TonemapHdrToSdr { exposure: 1.0 })?;
+        let red = |pixels: &[u8], x: usize| pixels[x * 4];
+        assert_eq!(red(&captured.rgba8, 0), 0);
+        assert!(red(&captured.rgba8, GRADIENT_WIDTH as usize - 1) < 255);
+        assert!(red(&captured.rgba8, 1) <= red(&captured.rgba8, 2));
+
+        srgb.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        unorm.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        Ok(())
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn saved_png_round_trips() -> Result<(), Error> {
+        let frame = vku::CapturedFrame {
+            width: GRADIENT_WIDTH,
+            height: GRADIENT_HEIGHT,
+            rgba8: gradient(),
+        };
+        let path = std::env::temp_dir().join("vku_frame_capture.png");
+        frame.save_png(&path)?;
+        let decoded = image::open(&path)?.into_rgba8();
+        std::fs::remove_file(&path)?;
+        assert_eq!(decoded.dimensions(), (frame.width, frame.height));
+        assert_eq!(decoded.as_raw(), &frame.rgba8);
+        Ok(())
+    }
+}
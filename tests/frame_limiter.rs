@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::time::{Duration, Instant};
+
+    use vku::{Error, FrameClock, FrameLimiter, FrameTarget};
+
+    /// Clock that only advances when the test simulates work or the limiter sleeps.
+    #[derive(Clone)]
+    struct MockClock {
+        now: Rc<Cell<Instant>>,
+        sleeps: Rc<Cell<u32>>,
+    }
+
+    impl MockClock {
+        fn new() -> Self {
+            Self {
+                now: Rc::new(Cell::new(Instant::now())),
+                sleeps: Rc::new(Cell::new(0)),
+            }
+        }
+
+        fn work(&self, duration: Duration) {
+            self.now.set(self.now.get() + duration);
+        }
+    }
+
+    impl FrameClock for MockClock {
+        fn now(&self) -> Instant {
+            self.now.get()
+        }
+
+        fn sleep_until(&mut self, deadline: Instant) {
+            self.sleeps.set(self.sleeps.get() + 1);
+            self.now.set(deadline);
+        }
+    }
+
+    fn run_frames(
+        limiter: &mut FrameLimiter<MockClock>,
+        clock: &MockClock,
+        work: Duration,
+        frames: u32,
+    ) {
+        for _ in 0..frames {
+            clock.work(work);
+            limiter.wait();
+        }
+    }
+
+    #[test]
+    fn fps_target_paces_fast_frames() {
+        let clock = MockClock::new();
+        let mut limiter = FrameLimiter::with_clock(FrameTarget::Fps(100.0), clock.clone()).unwrap();
+        let start = clock.now();
+
+        run_frames(&mut limiter, &clock, Duration::from_millis(4), 11);
+
+        let interval = limiter.frame_interval().unwrap();
+        assert_eq!(limiter.last_frame_time(), interval);
+        assert_eq!(limiter.frame_time(), interval);
+        let elapsed = clock.now() - start;
+        assert!(elapsed >= interval * 10 && elapsed < interval * 11);
+        assert_eq!(clock.sleeps.get(), 10);
+    }
+
+    #[test]
+    fn late_frames_are_not_caught_up() {
+        let clock = MockClock::new();
+        let mut limiter = FrameLimiter::with_clock(FrameTarget::Fps(100.0), clock.clone()).unwrap();
+
+        run_frames(&mut limiter, &clock, Duration::from_millis(4), 2);
+        run_frames(&mut limiter, &clock, Duration::from_millis(25), 1);
+        assert_eq!(limiter.last_frame_time(), Duration::from_millis(25));
+
+        // The next fast frame is paced from the late frame instead of being released immediately.
+        let sleeps = clock.sleeps.get();
+        run_frames(&mut limiter, &clock, Duration::from_millis(4), 1);
+        assert_eq!(clock.sleeps.get(), sleeps + 1);
+        assert_eq!(limiter.last_frame_time(), limiter.frame_interval().unwrap());
+    }
+
+    #[test]
+    fn unlimited_and_unknown_refresh_rate_never_sleep() {
+        let clock = MockClock::new();
+        let mut limiter = FrameLimiter::with_clock(FrameTarget::Unlimited, clock.clone()).unwrap();
+        run_frames(&mut limiter, &clock, Duration::from_millis(1), 5);
+        assert_eq!(limiter.frame_time(), Duration::from_millis(1));
+
+        limiter.set_target(FrameTarget::RefreshRate).unwrap();
+        assert_eq!(limiter.frame_interval(), None);
+        run_frames(&mut limiter, &clock, Duration::from_millis(1), 5);
+        assert_eq!(clock.sleeps.get(), 0);
+
+        let refresh_interval = Duration::from_micros(16_667);
+        limiter.set_refresh_interval(Some(refresh_interval));
+        run_frames(&mut limiter, &clock, Duration::from_millis(1), 3);
+        assert_eq!(limiter.last_frame_time(), refresh_interval);
+        assert_eq!(clock.sleeps.get(), 2);
+    }
+
+    #[test]
+    fn frame_time_is_a_rolling_average() {
+        let clock = MockClock::new();
+        let mut limiter = FrameLimiter::with_clock(FrameTarget::Unlimited, clock.clone()).unwrap();
+        assert_eq!(limiter.frame_time(), Duration::ZERO);
+
+        run_frames(&mut limiter, &clock, Duration::from_millis(10), 65);
+        assert_eq!(limiter.frame_time(), Duration::from_millis(10));
+
+        // Older samples leave the window.
+        run_frames(&mut limiter, &clock, Duration::from_millis(2), 64);
+        assert_eq!(limiter.frame_time(), Duration::from_millis(2));
+    }
+    #[test]
+    fn invalid_fps_targets_are_rejected() {
+        let clock = MockClock::new();
+        for fps in [0.0, -60.0, f32::NAN, 1e-30] {
+            assert!(matches!(
+                FrameLimiter::with_clock(FrameTarget::Fps(fps), clock.clone()),
+                Err(Error::InvalidFrameRate(_))
+            ));
+        }
+
+        let mut limiter = FrameLimiter::with_clock(FrameTarget::Fps(60.0), clock.clone()).unwrap();
+        assert!(matches!(
+            limiter.set_target(FrameTarget::Fps(0.0)),
+            Err(Error::InvalidFrameRate(fps)) if fps == 0.0
+        ));
+        assert_eq!(limiter.target(), FrameTarget::Fps(60.0));
+    }
+}
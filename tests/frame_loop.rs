@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, FrameContext, VkInit, VkInitCreateInfo};
+    use winit::dpi::LogicalSize;
+    use winit::event_loop::{EventLoop, EventLoopBuilder};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+    use winit::window::WindowBuilder;
+
+    fn to_present(vk_init: &VkInit, frame: &FrameContext) {
+        let barrier = ImageMemoryBarrier2::builder()
+            .image(frame.image)
+            .old_layout(ImageLayout::UNDEFINED)
+            .new_layout(ImageLayout::PRESENT_SRC_KHR)
+            .src_stage_mask(PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .dst_stage_mask(PipelineStageFlags2::BOTTOM_OF_PIPE)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .subresource_range(ImageSubresourceRange {
+                aspect_mask: ImageAspectFlags::COLOR,
+                level_count: 1,
+                layer_count: 1,
+                ..Default::default()
+            })
+            .build();
+        vk_init.cmd_pipeline_barrier2(&frame.cmd_buffer, &[barrier], &[]);
+    }
+
+    /// Runs ```count``` frames and returns their frame in flight indices.
+    fn run_frames(vk_init: &mut VkInit, count: usize) -> Result<Vec<usize>, Error> {
+        let mut frames = vec![];
+        for _ in 0..count {
+            let frame = vk_init.begin_frame()?;
+            to_present(vk_init, &frame);
+            frames.push(frame.frame);
+            vk_init.end_frame(frame)?;
+        }
+        Ok(frames)
+    }
+
+    #[test]
+    fn frames_cycle_through_frames_in_flight() -> Result<(), Error> {
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+        let mut vk_init = VkInit::new_windowed(
+            &window,
+            size,
+            window.scale_factor(),
+            VkInitCreateInfo::default(),
+        )?;
+        assert_eq!(vk_init.frames_in_flight(), 2);
+
+        vk_init.begin_debug_message_capture();
+        assert_eq!(run_frames(&mut vk_init, 5)?, [0, 1, 0, 1, 0]);
+
+        let frame = vk_init.begin_frame()?;
+        assert!(matches!(
+            vk_init.begin_frame(),
+            Err(Error::FrameAlreadyInProgress)
+        ));
+        to_present(&vk_init, &frame);
+        vk_init.end_frame(frame)?;
+
+        // Recreating the swapchain starts over with fresh synchronization objects.
+        vk_init.on_resize(&window, [640, 480])?;
+        assert_eq!(run_frames(&mut vk_init, 3)?, [0, 1, 0]);
+
+        vk_init.set_frames_in_flight(3);
+        assert_eq!(run_frames(&mut vk_init, 4)?, [0, 1, 2, 0]);
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+
+        vk_init.destroy()
+    }
+}
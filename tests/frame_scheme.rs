@@ -0,0 +1,43 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::result::Result;
+    use vku::Error;
+
+    #[test]
+    fn frame_scheme_sizes_per_frame_resources() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let scheme = vk_init.frame_scheme();
+        assert_eq!(scheme.frames_in_flight, 2);
+        assert_eq!(
+            scheme.swapchain_images,
+            vk_init.head().swapchain_images.len()
+        );
+
+        let fences = vk_init.create_per_frame(|vk_init, _| vk_init.create_fence())?;
+        let semaphores =
+            vk_init.create_per_swapchain_image(|vk_init, _| vk_init.create_semaphore())?;
+        assert_eq!(fences.len(), 2);
+        assert_eq!(semaphores.len(), scheme.swapchain_images);
+        assert_eq!(fences.get(&vk_init, 2), fences.get(&vk_init, 0));
+
+        vk_init.set_frames_in_flight(2);
+        assert_eq!(vk_init.frame_scheme().generation, scheme.generation);
+
+        vk_init.set_frames_in_flight(1);
+        assert_eq!(vk_init.frame_scheme().generation, scheme.generation + 1);
+        assert!(!fences.is_current(&vk_init));
+        assert!(semaphores.is_current(&vk_init));
+
+        for fence in fences.into_inner() {
+            vk_init.destroy_fence(&fence)?;
+        }
+        for semaphore in semaphores.into_inner() {
+            vk_init.destroy_semaphore(&semaphore)?;
+        }
+        vk_init.destroy()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::pipeline_builder::BlendMode;
+    use vku::{CmdType, Error, FragmentSource, FullscreenPass, VMAImage};
+
+    #[test]
+    fn invert_offscreen() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let extent = Extent3D {
+            width: 4,
+            height: 4,
+            depth: 1,
+        };
+        let format = Format::R8G8B8A8_UNORM;
+        let pixel_count = (extent.width * extent.height) as usize;
+
+        let invert_spirv = read_spv(&mut Cursor::new(&include_bytes!("invert.frag.spv")[..]))?;
+        let pass = FullscreenPass::new::<()>(
+            &vk_init,
+            FragmentSource::Spirv(&invert_spirv),
+            format,
+            BlendMode::Opaque,
+            "Invert_Pass",
+        )?;
+
+        let mut input = vk_init.create_empty_image(extent, format, 4, ImageAspectFlags::COLOR)?;
+        input.set_staging_data(&vk_init.device, &vec![[10_u8, 20, 30, 255]; pixel_count])?;
+        let mut output = VMAImage::create_render_image(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            extent,
+            format,
+            4,
+        )?;
+        let slot = vk_init.create_readback_slots(pixel_count * 4, 1)?.remove(0);
+
+        let sampler_info = SamplerCreateInfo::builder()
+            .mag_filter(Filter::NEAREST)
+            .min_filter(Filter::NEAREST);
+        let sampler = unsafe { vk_init.device.create_sampler(&sampler_info, None)? };
+
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+
+        let barrier = input.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_DST_OPTIMAL,
+            None,
+            None,
+        )?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[barrier], &[]);
+        input.enque_copy_from_staging_buffer_to_image(&vk_init.device, &cmd_buffer);
+        let barriers = [
+            input.get_image_layout_transition_barrier2(
+                ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                None,
+                None,
+            )?,
+            output.get_image_layout_transition_barrier2(
+                ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                None,
+                None,
+            )?,
+        ];
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &barriers, &[]);
+
+        let color_attachments = [RenderingAttachmentInfo::builder()
+            .image_view(output.image_view)
+            .image_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .load_op(AttachmentLoadOp::DONT_CARE)
+            .store_op(AttachmentStoreOp::STORE)
+            .build()];
+        let render_area = Extent2D {
+            width: extent.width,
+            height: extent.height,
+        };
+        let rendering_info = RenderingInfo::builder()
+            .render_area(Rect2D {
+                offset: Offset2D { x: 0, y: 0 },
+                extent: render_area,
+            })
+            .layer_count(1)
+            .color_attachments(&color_attachments);
+        vk_init.cmd_begin_rendering(&cmd_buffer, &rendering_info);
+        let unexpected_push =
+            pass.record(&vk_init, &cmd_buffer, &input, render_area, sampler, &[0; 4]);
+        assert!(matches!(
+            unexpected_push,
+            Err(Error::PushBytesSizeMismatch {
+                expected: 0,
+                provided: 4
+            })
+        ));
+        pass.record(&vk_init, &cmd_buffer, &input, render_area, sampler, &[])?;
+        vk_init.end_rendering(&cmd_buffer);
+
+        let barrier = output.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_SRC_OPTIMAL,
+            None,
+            None,
+        )?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[barrier], &[]);
+        let region = BufferImageCopy::builder()
+            .image_subresource(
+                ImageSubresourceLayers::builder()
+                    .aspect_mask(ImageAspectFlags::COLOR)
+                    .layer_count(1)
+                    .build(),
+            )
+            .image_extent(extent)
+            .build();
+        unsafe {
+            vk_init.device.cmd_copy_image_to_buffer(
+                cmd_buffer,
+                output.image,
+                ImageLayout::TRANSFER_SRC_OPTIMAL,
+                slot.buffer.buffer,
+                &[region],
+            )
+        };
+
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &slot.fence, &[], &[], &[])?;
+        let pixels: Vec<[u8; 4]> = slot.read_blocking(&vk_init, Duration::from_secs(1))?;
+        assert_eq!(pixels, vec![[245_u8, 235, 225, 0]; pixel_count]);
+
+        Ok(())
+    }
+}
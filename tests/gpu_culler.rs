@@ -0,0 +1,82 @@
+#[cfg(feature = "culling")]
+mod common;
+
+#[cfg(all(test, feature = "culling"))]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::result::Result;
+    use vku::{CmdType, Error, Frustum, GpuCuller, ObjectBounds};
+
+    fn translation(x: f32, y: f32, z: f32) -> [[f32; 4]; 4] {
+        [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [x, y, z, 1.0],
+        ]
+    }
+
+    #[test]
+    fn frustum_planes_are_normalized() {
+        // Orthographic projection of x, y in -4..4 and z in 0..2.
+        let ortho = [
+            [0.25, 0.0, 0.0, 0.0],
+            [0.0, 0.25, 0.0, 0.0],
+            [0.0, 0.0, 0.5, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        let frustum = Frustum::from_view_projection(ortho);
+        assert_eq!(frustum.planes[0], [1.0, 0.0, 0.0, 4.0]);
+        assert_eq!(frustum.planes[3], [0.0, -1.0, 0.0, 4.0]);
+        assert_eq!(frustum.planes[4], [0.0, 0.0, 1.0, 0.0]);
+        assert_eq!(frustum.planes[5], [0.0, 0.0, -1.0, 2.0]);
+    }
+
+    #[test]
+    fn culler_writes_visible_objects() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let mut culler = GpuCuller::new(&mut vk_init, 100, 36)?;
+
+        let half = [0.1; 3];
+        let objects = [
+            ObjectBounds::new(translation(0.0, 0.0, 0.5), half.map(|v| -v), half),
+            ObjectBounds::new(translation(5.0, 0.0, 0.5), half.map(|v| -v), half),
+            ObjectBounds::new(translation(0.0, 0.0, -3.0), half.map(|v| -v), half),
+            ObjectBounds::new(translation(1.05, -0.5, 0.5), half.map(|v| -v), half),
+            ObjectBounds::new(translation(0.0, 1.5, 0.5), half.map(|v| -v), half),
+        ];
+        culler.update_objects(&vk_init, &objects)?;
+        assert!(matches!(
+            culler.update_objects(&vk_init, &[objects[0]; 101]),
+            Err(Error::CullObjectsExceeded {
+                requested: 101,
+                max: 100
+            })
+        ));
+
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        let fence = vk_init.create_fence()?;
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[&cmd_buffer])?;
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        culler.record(
+            &vk_init,
+            &cmd_buffer,
+            &Frustum::from_view_projection(translation(0.0, 0.0, 0.0)),
+        );
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &fence, &[], &[], &[])?;
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[])?;
+
+        let indirect: Vec<u32> = vk_init.read_buffer(culler.indirect_buffer(), 0, 4 + 100)?;
+        assert_eq!(indirect[..4], [36, 2, 0, 0]);
+        let mut visible = indirect[4..6].to_vec();
+        visible.sort();
+        assert_eq!(visible, [0, 3]);
+
+        vk_init.destroy_fence(&fence)?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        culler.destroy(&mut vk_init)?;
+        vk_init.destroy()?;
+        Ok(())
+    }
+}
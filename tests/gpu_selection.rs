@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tests {
+    use std::result::Result;
+    use vku::{Error, GpuSelection, VkInit, VkInitCreateInfo};
+
+    #[test]
+    fn gpu_preference_is_honored() -> Result<(), Error> {
+        env_logger::init();
+        let (entry, _) = VkInit::load_entry()?;
+        let mut create_info = VkInitCreateInfo::default();
+        create_info.allow_igpu = true;
+        let gpus = VkInit::enumerate_gpus(&entry, &create_info)?;
+        assert!(!gpus.is_empty());
+
+        let largest = gpus
+            .iter()
+            .map(|gpu| gpu.device_local_memory())
+            .max()
+            .unwrap();
+        create_info.gpu_preference = GpuSelection::HighestMemory;
+        let mut vk_init = VkInit::new_headless(create_info.clone())?;
+        assert_eq!(vk_init.physical_device_info.device_local_memory(), largest);
+        vk_init.destroy()?;
+
+        let last = gpus.last().unwrap();
+        create_info.gpu_preference = GpuSelection::Index(last.device_index);
+        let mut vk_init = VkInit::new_headless(create_info.clone())?;
+        assert_eq!(vk_init.physical_device_info.name, last.name);
+        vk_init.destroy()?;
+
+        create_info.gpu_preference = GpuSelection::NamedSubstring(String::from("No Such GPU"));
+        assert!(matches!(
+            VkInit::new_headless(create_info),
+            Err(Error::RequestedGPUNotFound { .. })
+        ));
+        Ok(())
+    }
+}
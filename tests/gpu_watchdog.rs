@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, VkInit};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    const THRESHOLD: Duration = Duration::from_millis(50);
+
+    fn watchdog_vk_init() -> Result<VkInit, Error> {
+        use vku::VkInitCreateInfo;
+        use winit::dpi::LogicalSize;
+        use winit::event_loop::{EventLoop, EventLoopBuilder};
+        use winit::window::WindowBuilder;
+
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+
+        let mut create_info = VkInitCreateInfo::default();
+        create_info.gpu_watchdog = Some(THRESHOLD);
+        VkInit::new(Some(&window), Some(size), create_info)
+    }
+
+    #[test]
+    fn never_signaled_fence_reports_hang() -> Result<(), Error> {
+        let vk_init = watchdog_vk_init()?;
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        vk_init.set_debug_object_name(
+            cmd_buffer.as_raw(),
+            ObjectType::COMMAND_BUFFER,
+            String::from("Watched_Cmd_Buffer"),
+        )?;
+
+        // Completing work stays below the threshold.
+        let fence = vk_init.create_fence()?;
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[&cmd_buffer])?;
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        vk_init
+            .begin_debug_label(&cmd_buffer, "Watched_Label", None)?
+            .end();
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &fence, &[], &[], &[])?;
+        vk_init.wait_for_fences_watched(&[fence], "submitted work")?;
+
+        // A fence that is never submitted stands in for a hung GPU.
+        let never_signaled = unsafe {
+            vk_init
+                .device
+                .create_fence(&FenceCreateInfo::default(), None)?
+        };
+        let Err(Error::GpuHangSuspected { operation, .. }) =
+            vk_init.wait_for_fences_watched(&[never_signaled], "never signaled")
+        else {
+            panic!("waiting on a never signaled fence did not time out");
+        };
+        assert_eq!(operation, "never signaled");
+
+        unsafe { vk_init.device.destroy_fence(never_signaled, None) };
+        vk_init.destroy_fence(&fence)?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use std::time::Duration;
+    use vku::{CmdType, Error, ImageAtlas, ShelfPacker};
+
+    fn overlaps(a: ([u32; 2], [u32; 2]), b: ([u32; 2], [u32; 2])) -> bool {
+        let ((a_pos, a_size), (b_pos, b_size)) = (a, b);
+        a_pos[0] < b_pos[0] + b_size[0]
+            && b_pos[0] < a_pos[0] + a_size[0]
+            && a_pos[1] < b_pos[1] + b_size[1]
+            && b_pos[1] < a_pos[1] + a_size[1]
+    }
+
+    #[test]
+    fn packer_places_without_overlap() {
+        let mut packer = ShelfPacker::new([128, 128]);
+        let sizes = [[30, 20], [50, 10], [10, 40], [64, 20], [20, 20], [100, 8]];
+        let rects: Vec<_> = sizes
+            .iter()
+            .map(|size| (packer.allocate(*size).unwrap(), *size))
+            .collect();
+
+        for (i, a) in rects.iter().enumerate() {
+            assert!(a.0[0] + a.1[0] <= 128 && a.0[1] + a.1[1] <= 128);
+            for b in &rects[i + 1..] {
+                assert!(!overlaps(*a, *b), "{a:?} overlaps {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn packer_rejects_when_full() {
+        let mut packer = ShelfPacker::new([64, 64]);
+        assert_eq!(packer.allocate([65, 1]), None);
+        assert_eq!(packer.allocate([0, 4]), None);
+        for _ in 0..4 {
+            assert!(packer.allocate([64, 16]).is_some());
+        }
+        assert_eq!(packer.used_height(), 64);
+        assert_eq!(packer.allocate([1, 1]), None);
+    }
+
+    #[test]
+    fn packer_reuses_freed_space() {
+        let mut packer = ShelfPacker::new([64, 64]);
+        let a = packer.allocate([32, 32]).unwrap();
+        let b = packer.allocate([32, 32]).unwrap();
+        let c = packer.allocate([64, 32]).unwrap();
+        assert_eq!(packer.allocate([16, 16]), None);
+
+        packer.free(a, [32, 32]);
+        packer.free(b, [32, 32]);
+        assert_eq!(packer.allocate([64, 20]), Some([0, 0]));
+
+        packer.free([0, 0], [64, 20]);
+        packer.free(c, [64, 32]);
+        assert_eq!(packer.used_height(), 0);
+        assert_eq!(packer.allocate([64, 64]), Some([0, 0]));
+    }
+
+    #[test]
+    fn atlas_uploads_regions() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let mut atlas = ImageAtlas::new(&mut vk_init, [64, 64], Format::R8G8B8A8_UNORM)?;
+
+        let sizes = [[8_u32, 8_u32], [20, 4], [5, 13]];
+        let mut regions = vec![];
+        for (i, size) in sizes.iter().enumerate() {
+            let texel = [i as u8 + 1, 0x40, 0x80, 0xFF];
+            let pixels = texel.repeat((size[0] * size[1]) as usize);
+            regions.push(atlas.allocate(&mut vk_init, &pixels, *size)?);
+        }
+        assert!(matches!(
+            atlas.allocate(&mut vk_init, &[0; 3], [1, 1]),
+            Err(Error::AtlasDataMismatch {
+                expected: 4,
+                provided: 3
+            })
+        ));
+
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        let mut slot = vk_init.create_readback_slots(64 * 64 * 4, 1)?.remove(0);
+
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        let to_transfer = ImageMemoryBarrier2::builder()
+            .image(atlas.image.image)
+            .old_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .new_layout(ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .src_stage_mask(PipelineStageFlags2::ALL_COMMANDS)
+            .src_access_mask(AccessFlags2::MEMORY_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::TRANSFER)
+            .dst_access_mask(AccessFlags2::TRANSFER_READ)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .subresource_range(ImageSubresourceRange {
+                aspect_mask: ImageAspectFlags::COLOR,
+                base_mip_level: 0,
+                level_count: 1,
+                base_array_layer: 0,
+                layer_count: 1,
+            })
+            .build();
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[to_transfer], &[]);
+        let mut buffer_offset = 0;
+        for region in &regions {
+            let copy = BufferImageCopy::builder()
+                .buffer_offset(buffer_offset)
+                .image_subresource(ImageSubresourceLayers {
+                    aspect_mask: ImageAspectFlags::COLOR,
+                    mip_level: 0,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                })
+                .image_offset(Offset3D {
+                    x: region.offset[0] as i32,
+                    y: region.offset[1] as i32,
+                    z: 0,
+                })
+                .image_extent(Extent3D {
+                    width: region.size[0],
+                    height: region.size[1],
+                    depth: 1,
+                });
+            unsafe {
+                vk_init.device.cmd_copy_image_to_buffer(
+                    cmd_buffer,
+                    atlas.image.image,
+                    ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    slot.buffer.buffer,
+                    &[*copy],
+                );
+            }
+            buffer_offset += (region.size[0] * region.size[1] * 4) as u64;
+        }
+        // The texel right of the first region is padding.
+        let padding = BufferImageCopy::builder()
+            .buffer_offset(buffer_offset)
+            .image_subresource(ImageSubresourceLayers {
+                aspect_mask: ImageAspectFlags::COLOR,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: 1,
+            })
+            .image_offset(Offset3D {
+                x: (regions[0].offset[0] + regions[0].size[0]) as i32,
+                y: regions[0].offset[1] as i32,
+                z: 0,
+            })
+            .image_extent(Extent3D {
+                width: 1,
+                height: 1,
+                depth: 1,
+            });
+        unsafe {
+            vk_init.device.cmd_copy_image_to_buffer(
+                cmd_buffer,
+                atlas.image.image,
+                ImageLayout::TRANSFER_SRC_OPTIMAL,
+                slot.buffer.buffer,
+                &[*padding],
+            );
+        }
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &slot.fence, &[], &[], &[])?;
+
+        let texels: Vec<[u8; 4]> = slot.read_blocking(&vk_init, Duration::from_secs(5))?;
+        let mut texels = texels.into_iter();
+        for (i, region) in regions.iter().enumerate() {
+            let count = (region.size[0] * region.size[1]) as usize;
+            for texel in texels.by_ref().take(count) {
+                assert_eq!(texel, [i as u8 + 1, 0x40, 0x80, 0xFF]);
+            }
+            let extent = [64.0, 64.0];
+            assert_eq!(region.uv_min[0], region.offset[0] as f32 / extent[0]);
+            assert_eq!(
+                region.uv_max[1],
+                (region.offset[1] + region.size[1]) as f32 / extent[1]
+            );
+        }
+        assert_eq!(texels.next(), Some([0; 4]));
+
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        atlas.destroy(&mut vk_init)?;
+        vk_init.destroy()?;
+        Ok(())
+    }
+}
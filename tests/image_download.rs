@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, VkInit, VkInitCreateInfo};
+
+    /// Uploads ```data``` into a new ```width``` x ```height``` image of ```format``` and downloads it again.
+    fn round_trip(
+        vk_init: &mut VkInit,
+        format: Format,
+        texel_size: usize,
+        [width, height]: [u32; 2],
+        data: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let extent = Extent3D {
+            width,
+            height,
+            depth: 1,
+        };
+        let mut image =
+            vk_init.create_empty_image(extent, format, texel_size, ImageAspectFlags::COLOR)?;
+        image.set_staging_data(&vk_init.device, data)?;
+        let to_transfer_dst = image.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_DST_OPTIMAL,
+            None,
+            None,
+        )?;
+        let to_sampled = image.get_image_layout_transition_barrier2(
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            None,
+            None,
+        )?;
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer_dst], &[]);
+            image.enque_copy_from_staging_buffer_to_image(&vk_init.device, cmd_buffer);
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_sampled], &[]);
+        })?;
+
+        let downloaded = vk_init.download_image(&mut image)?;
+        assert_eq!(image.current_layout, ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+        image.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        Ok(downloaded)
+    }
+
+    #[test]
+    fn uploaded_bytes_are_downloaded_intact() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+
+        // Widths that are no multiple of 4 must not be padded per row.
+        let rgba: Vec<u8> = (0..7 * 5 * 4).map(|i| i as u8).collect();
+        let downloaded = round_trip(&mut vk_init, Format::R8G8B8A8_UNORM, 4, [7, 5], &rgba)?;
+        assert_eq!(downloaded, rgba);
+
+        let r8: Vec<u8> = (0..5 * 3).map(|i| 255 - i as u8).collect();
+        let downloaded = round_trip(&mut vk_init, Format::R8_UNORM, 1, [5, 3], &r8)?;
+        assert_eq!(downloaded, r8);
+
+        vk_init.destroy()
+    }
+}
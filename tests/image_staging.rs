@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{AllocationLifetime, Error, VMAImage, VkInit, VkInitCreateInfo};
+
+    const SIZE: u32 = 256;
+
+    #[test]
+    fn staging_buffers_are_created_on_demand() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        let extent = Extent3D {
+            width: SIZE,
+            height: SIZE,
+            depth: 1,
+        };
+        let before = staging_bytes(&vk_init);
+
+        let mut depth = VMAImage::create_depth_image(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            extent,
+            Format::D32_SFLOAT,
+            4,
+        )?;
+        let mut target = vk_init.create_render_image_with_lifetime(
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+            AllocationLifetime::Persistent,
+        )?;
+        assert!(depth.staging_buffer.is_none() && target.staging_buffer.is_none());
+        assert_eq!(staging_bytes(&vk_init), before);
+        assert!(matches!(
+            target.set_staging_data(&vk_init.device, &[0_u8; 4]),
+            Err(Error::NoStagingBuffer)
+        ));
+
+        target.with_staging_buffer(&vk_init.device, &mut vk_init.allocator.lock())?;
+        let with_staging = staging_bytes(&vk_init);
+        assert!(with_staging >= before + (SIZE * SIZE * 4) as u64);
+        target.set_staging_data(
+            &vk_init.device,
+            &vec![[1_u8, 2, 3, 4]; (SIZE * SIZE) as usize],
+        )?;
+        // Adding it again keeps the existing buffer.
+        target.with_staging_buffer(&vk_init.device, &mut vk_init.allocator.lock())?;
+        assert_eq!(staging_bytes(&vk_init), with_staging);
+
+        let mut sampled = vk_init.create_empty_image(
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+            ImageAspectFlags::COLOR,
+        )?;
+        assert!(sampled.staging_buffer.is_some());
+
+        sampled.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        target.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        depth.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        assert_eq!(staging_bytes(&vk_init), before);
+        vk_init.destroy()
+    }
+
+    /// Staging buffers are the only transient allocations of images.
+    fn staging_bytes(vk_init: &VkInit) -> u64 {
+        vk_init.memory_report().lifetimes.transient.bytes
+    }
+}
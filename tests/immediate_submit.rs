@@ -0,0 +1,65 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::mem::size_of;
+
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error};
+
+    const COUNT: usize = 64;
+
+    #[test]
+    fn fills_on_every_queue_type() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let size = COUNT * size_of::<u32>();
+        let mut buffer = vk_init.create_local_buffer(
+            size,
+            BufferUsageFlags::TRANSFER_DST | BufferUsageFlags::TRANSFER_SRC,
+        )?;
+        let mut slot = vk_init.create_readback_slots(size, 1)?.remove(0);
+        let fill_to_copy = BufferMemoryBarrier2::builder()
+            .buffer(buffer.buffer)
+            .size(WHOLE_SIZE)
+            .src_stage_mask(PipelineStageFlags2::TRANSFER)
+            .src_access_mask(AccessFlags2::TRANSFER_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::TRANSFER)
+            .dst_access_mask(AccessFlags2::TRANSFER_READ)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .build();
+        let region = BufferCopy::builder().size(size as u64).build();
+
+        // Dedicated queues fall back to the unified queue where missing.
+        // Fill and copy run on the same queue, so no ownership transfer is needed.
+        for (cmd_type, value) in [
+            (CmdType::Any, 1_u32),
+            (CmdType::Compute, 2),
+            (CmdType::Transfer, 3),
+        ] {
+            vk_init.immediate_submit(cmd_type, |cmd_buffer| unsafe {
+                vk_init
+                    .device
+                    .cmd_fill_buffer(*cmd_buffer, buffer.buffer, 0, WHOLE_SIZE, value);
+                vk_init.cmd_pipeline_barrier2(cmd_buffer, &[], &[fill_to_copy]);
+                vk_init.device.cmd_copy_buffer(
+                    *cmd_buffer,
+                    buffer.buffer,
+                    slot.buffer.buffer,
+                    &[region],
+                );
+            })?;
+            let read: Vec<u32> = slot.buffer.get_data(&vk_init.device, 0, COUNT)?;
+            assert!(
+                read.iter().all(|read| *read == value),
+                "{cmd_type:?} fill was not visible after immediate_submit returned"
+            );
+        }
+
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::mem::size_of;
+
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, ComputeBinding, Error, VkInit, VkInitCreateInfo};
+
+    #[test]
+    fn dispatches_group_counts_written_by_compute() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        vk_init.begin_debug_message_capture();
+        let count = 1000_u32;
+        let mut values = vk_init.create_local_buffer(
+            count as usize * size_of::<u32>(),
+            BufferUsageFlags::STORAGE_BUFFER | BufferUsageFlags::TRANSFER_SRC,
+        )?;
+        let mut indirect = vk_init.create_indirect_buffer(1)?;
+        assert!(indirect.usage.contains(BufferUsageFlags::INDIRECT_BUFFER));
+        assert_eq!(
+            indirect.size(),
+            size_of::<DrawIndexedIndirectCommand>() as u64
+        );
+
+        let args_code = read_spv(&mut Cursor::new(include_bytes!("indirect_args.comp.spv")))?;
+        let args_shader = vk_init.create_compute_shader::<u32>(
+            &[ComputeBinding::Ssbo(&indirect)],
+            args_code,
+            [1, 1, 1],
+            &[],
+            String::from("Indirect_Args"),
+        )?;
+        let code = read_spv(&mut Cursor::new(include_bytes!("invocation_id.comp.spv")))?;
+        let compute_shader = vk_init.create_compute_shader::<u32>(
+            &[ComputeBinding::Ssbo(&values)],
+            code,
+            [256, 1, 1],
+            &[],
+            String::from("Invocation_Id"),
+        )?;
+
+        let args_to_indirect = BufferMemoryBarrier2::builder()
+            .buffer(indirect.buffer)
+            .size(WHOLE_SIZE)
+            .src_stage_mask(PipelineStageFlags2::COMPUTE_SHADER)
+            .src_access_mask(AccessFlags2::SHADER_STORAGE_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::DRAW_INDIRECT)
+            .dst_access_mask(AccessFlags2::INDIRECT_COMMAND_READ)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .build();
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            args_shader.bind(&vk_init.device, cmd_buffer, &count.to_ne_bytes());
+            args_shader.dispatch_exact(&vk_init.device, cmd_buffer, 1, 1, 1);
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[], &[args_to_indirect]);
+            compute_shader.bind(&vk_init.device, cmd_buffer, &count.to_ne_bytes());
+            compute_shader.dispatch_indirect(&vk_init.device, cmd_buffer, &indirect, 0);
+        })?;
+        let read: Vec<u32> = vk_init.read_buffer(&values, 0, count as usize)?;
+        assert_eq!(read, (0..count).collect::<Vec<u32>>());
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        args_shader.destroy(&vk_init)?;
+        compute_shader.destroy(&vk_init)?;
+        indirect.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        values.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+#[cfg(feature = "ktx2")]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, VkInit, VkInitCreateInfo};
+
+    const MAGIC: [u8; 12] = [
+        0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+    ];
+
+    /// Minimal KTX2 file with an empty data format descriptor.
+    fn ktx2(format: Format, size: u32, levels: &[Vec<u8>], supercompression: u32) -> Vec<u8> {
+        let dfd_offset = 80 + 24 * levels.len();
+        let mut level_offset = dfd_offset + 4;
+        let mut header: Vec<u32> = vec![
+            format.as_raw() as u32,
+            1,
+            size,
+            size,
+            0,
+            0,
+            1,
+            levels.len() as u32,
+            supercompression,
+            dfd_offset as u32,
+            4,
+            0,
+            0,
+        ];
+        header.extend([0; 4]);
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(header.iter().flat_map(|value| value.to_le_bytes()));
+        for level in levels {
+            for value in [level_offset, level.len(), level.len()] {
+                bytes.extend((value as u64).to_le_bytes());
+            }
+            level_offset += level.len();
+        }
+        bytes.extend(4_u32.to_le_bytes());
+        for level in levels {
+            bytes.extend(level);
+        }
+        bytes
+    }
+
+    #[test]
+    fn uploads_all_mip_levels() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        vk_init.begin_debug_message_capture();
+        let levels: Vec<Vec<u8>> = [8_usize, 4, 2, 1]
+            .iter()
+            .map(|size| vec![255; size * size * 4])
+            .collect();
+
+        assert!(matches!(
+            vk_init.create_texture_from_ktx2(&[0; 100]),
+            Err(Error::Ktx2Parse(_))
+        ));
+        assert!(matches!(
+            vk_init.create_texture_from_ktx2(&ktx2(Format::R8G8B8A8_UNORM, 8, &levels, 2)),
+            Err(Error::UnsupportedKtx2Texture(_))
+        ));
+        assert!(matches!(
+            vk_init.create_texture_from_ktx2(&ktx2(Format::UNDEFINED, 8, &levels, 0)),
+            Err(Error::UnsupportedKtx2Texture(_))
+        ));
+
+        let mut texture =
+            vk_init.create_texture_from_ktx2(&ktx2(Format::R8G8B8A8_UNORM, 8, &levels, 0))?;
+        assert_eq!(texture.format, Format::R8G8B8A8_UNORM);
+        assert_eq!(texture.mip_levels, 4);
+        assert_eq!(
+            texture.current_layout,
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL
+        );
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        texture.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use std::sync::Mutex;
+
+    use ash::vk::*;
+    use log::{Level, Log, Metadata, Record};
+    use std::result::Result;
+    use vku::{Error, VkInit};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    /// Collects warnings instead of printing them.
+    struct WarningCollector;
+
+    impl Log for WarningCollector {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() <= Level::Warn
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                WARNINGS.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn collecting_vk_init() -> Result<VkInit, Error> {
+        use vku::VkInitCreateInfo;
+        use winit::dpi::LogicalSize;
+        use winit::event_loop::{EventLoop, EventLoopBuilder};
+        use winit::window::WindowBuilder;
+
+        log::set_logger(&WarningCollector).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+
+        let create_info = VkInitCreateInfo::default();
+        VkInit::new(Some(&window), Some(size), create_info)
+    }
+
+    fn take_leak_warnings() -> Vec<String> {
+        WARNINGS
+            .lock()
+            .unwrap()
+            .drain(..)
+            .filter(|warning| warning.contains("without destroy"))
+            .collect()
+    }
+
+    #[test]
+    fn forgotten_resources_warn_on_drop() -> Result<(), Error> {
+        let mut vk_init = collecting_vk_init()?;
+        let usage = BufferUsageFlags::STORAGE_BUFFER;
+
+        let mut destroyed = vk_init.create_cpu_to_gpu_buffer(64, usage)?;
+        destroyed.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        destroyed.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        drop(destroyed);
+        assert!(take_leak_warnings().is_empty());
+
+        let forgotten = vk_init.create_cpu_to_gpu_buffer(64, usage)?;
+        forgotten.set_debug_object_name(&vk_init, String::from("Forgotten"))?;
+        drop(forgotten);
+        let warnings = take_leak_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("VMABuffer Forgotten dropped without destroy"));
+
+        // Dropped resources are not reported again on teardown.
+        assert!(!vk_init
+            .live_resources()
+            .iter()
+            .any(|resource| resource.name.as_deref() == Some("Forgotten")));
+        vk_init.destroy()?;
+        vk_init.destroy()?;
+        drop(vk_init);
+        assert!(take_leak_warnings().is_empty());
+
+        Ok(())
+    }
+}
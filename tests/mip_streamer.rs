@@ -0,0 +1,72 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, MipStreamer};
+
+    #[test]
+    fn mip_streamer_balances_to_budget() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let extent = Extent3D {
+            width: 64,
+            height: 64,
+            depth: 1,
+        };
+        let mip_levels = 7;
+        let mut image = vk_init.create_mipped_image(
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+            ImageAspectFlags::COLOR,
+            mip_levels,
+        )?;
+        let source: Vec<Vec<u8>> = (0..mip_levels)
+            .map(|level| {
+                let mip = image.mip_extent(level);
+                vec![level as u8; (mip.width * mip.height * 4) as usize]
+            })
+            .collect();
+
+        let mut streamer = MipStreamer::default();
+        assert!(matches!(
+            streamer.register(&image, vec![]),
+            Err(Error::MipChainMismatch { .. })
+        ));
+        let id = streamer.register(&image, source)?;
+        assert!(streamer.resident_mips(id).is_empty());
+
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        let fence = vk_init.create_fence()?;
+
+        // 32x32 and coarser fit, 64x64 does not.
+        let budget = 32 * 32 * 4 * 2;
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[&cmd_buffer])?;
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        let changed = streamer.balance(&mut vk_init, &cmd_buffer, fence, budget)?;
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &fence, &[], &[], &[])?;
+        assert_eq!(changed, vec![id]);
+        assert_eq!(streamer.resident_mips(id), 1..mip_levels);
+        assert!(streamer.resident_bytes() <= budget);
+        assert_ne!(streamer.image_view(id), ImageView::null());
+
+        streamer.set_target_quality(id, 3);
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[&cmd_buffer])?;
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        let changed = streamer.balance(&mut vk_init, &cmd_buffer, fence, budget)?;
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &fence, &[], &[], &[])?;
+        assert_eq!(changed, vec![id]);
+        assert_eq!(streamer.resident_mips(id), 3..mip_levels);
+
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[])?;
+        streamer.destroy(&vk_init.device);
+        image.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy_fence(&fence)?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+
+        Ok(())
+    }
+}
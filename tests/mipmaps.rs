@@ -0,0 +1,152 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, MipGeneration, VMAImage, VkInit};
+
+    const SIZE: u32 = 64;
+    const LEVELS: u32 = 7;
+
+    fn gradient() -> Vec<u8> {
+        (0..SIZE)
+            .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+            .flat_map(|(x, y)| {
+                let u = (x * 255 / (SIZE - 1)) as u8;
+                let v = (y * 255 / (SIZE - 1)) as u8;
+                [u, v, u / 2 + v / 2, 255]
+            })
+            .collect()
+    }
+
+    fn level_bytes(level: u32) -> usize {
+        let size = (SIZE >> level).max(1) as usize;
+        size * size * 4
+    }
+
+    /// Generates the mip chain of the gradient and reads back levels 1 and up.
+    fn generate(vk_init: &mut VkInit, generation: MipGeneration) -> Result<Vec<Vec<u8>>, Error> {
+        let extent = Extent3D {
+            width: SIZE,
+            height: SIZE,
+            depth: 1,
+        };
+        let mut image = VMAImage::create_mipped_image_with_usage(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+            ImageAspectFlags::COLOR,
+            LEVELS,
+            ImageUsageFlags::STORAGE,
+        )?;
+        image.set_staging_data(&vk_init.device, &gradient())?;
+        let readback_size: usize = (1..LEVELS).map(level_bytes).sum();
+        let mut slot = vk_init.create_readback_slots(readback_size, 1)?.remove(0);
+
+        let all_levels = ImageSubresourceRange {
+            aspect_mask: ImageAspectFlags::COLOR,
+            level_count: LEVELS,
+            layer_count: 1,
+            ..Default::default()
+        };
+        let to_transfer_dst = ImageMemoryBarrier2::builder()
+            .image(image.image)
+            .old_layout(ImageLayout::UNDEFINED)
+            .new_layout(ImageLayout::TRANSFER_DST_OPTIMAL)
+            .dst_stage_mask(PipelineStageFlags2::COPY)
+            .dst_access_mask(AccessFlags2::TRANSFER_WRITE)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .subresource_range(all_levels)
+            .build();
+        let to_transfer_src = ImageMemoryBarrier2::builder()
+            .image(image.image)
+            .old_layout(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .new_layout(ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .src_stage_mask(PipelineStageFlags2::ALL_COMMANDS)
+            .dst_stage_mask(PipelineStageFlags2::COPY)
+            .dst_access_mask(AccessFlags2::TRANSFER_READ)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .subresource_range(all_levels)
+            .build();
+        let mut offset = 0;
+        let regions: Vec<BufferImageCopy> = (1..LEVELS)
+            .map(|level| {
+                let region = BufferImageCopy::builder()
+                    .buffer_offset(offset as u64)
+                    .image_subresource(ImageSubresourceLayers {
+                        aspect_mask: ImageAspectFlags::COLOR,
+                        mip_level: level,
+                        base_array_layer: 0,
+                        layer_count: 1,
+                    })
+                    .image_extent(image.mip_extent(level))
+                    .build();
+                offset += level_bytes(level);
+                region
+            })
+            .collect();
+
+        let mut generated = Ok(());
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer_dst], &[]);
+            image.enque_copy_from_staging_buffer_to_image(&vk_init.device, cmd_buffer);
+            image.current_layout = ImageLayout::TRANSFER_DST_OPTIMAL;
+            generated = vk_init.enqueue_generate_mipmaps_with(cmd_buffer, &mut image, generation);
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer_src], &[]);
+            unsafe {
+                vk_init.device.cmd_copy_image_to_buffer(
+                    *cmd_buffer,
+                    image.image,
+                    ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    slot.buffer.buffer,
+                    &regions,
+                );
+            }
+        })?;
+        generated?;
+        assert_eq!(image.current_layout, ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+        let data: Vec<u8> = slot.buffer.get_data(&vk_init.device, 0, readback_size)?;
+        let mut levels = vec![];
+        let mut offset = 0;
+        for level in 1..LEVELS {
+            levels.push(data[offset..offset + level_bytes(level)].to_vec());
+            offset += level_bytes(level);
+        }
+
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        image.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        Ok(levels)
+    }
+
+    #[test]
+    fn compute_fallback_matches_blit() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        assert!(vk_init.mip_generation_for(Format::R8G8B8A8_UNORM).is_some());
+
+        let blit = generate(&mut vk_init, MipGeneration::Blit)?;
+        let compute = generate(
+            &mut vk_init,
+            MipGeneration::Compute {
+                karis_average: false,
+            },
+        )?;
+        for (level, (blit, compute)) in blit.iter().zip(&compute).enumerate() {
+            for (index, (blit, compute)) in blit.iter().zip(compute).enumerate() {
+                assert!(
+                    blit.abs_diff(*compute) <= 2,
+                    "level {}: byte {index} is {compute} computed, {blit} blitted",
+                    level + 1
+                );
+            }
+        }
+
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, VkInit, VkInitCreateInfo};
+
+    #[test]
+    fn msaa_resolves_into_offscreen_image() -> Result<(), Error> {
+        env_logger::init();
+        let extent = Extent2D {
+            width: 16,
+            height: 16,
+        };
+
+        let mut create_info = VkInitCreateInfo::default();
+        create_info.msaa_samples = SampleCountFlags::TYPE_1 | SampleCountFlags::TYPE_4;
+        let mut vk_init = VkInit::new_headless(create_info)?;
+        assert!(matches!(
+            vk_init.create_offscreen_head(extent, Format::R8G8B8A8_UNORM, Format::D32_SFLOAT, 1),
+            Err(Error::MsaaSamplesNotSupported(..))
+        ));
+        vk_init.destroy()?;
+
+        // Four samples are required for color and depth attachments by the spec.
+        let mut create_info = VkInitCreateInfo::default();
+        create_info.msaa_samples = SampleCountFlags::TYPE_4;
+        create_info.clear_color_value = ClearColorValue {
+            float32: [1.0, 0.0, 0.0, 1.0],
+        };
+        let mut vk_init = VkInit::new_headless(create_info)?;
+        vk_init.begin_debug_message_capture();
+        vk_init.create_offscreen_head(extent, Format::R8G8B8A8_UNORM, Format::D32_SFLOAT, 1)?;
+        let target = vk_init.acquire_offscreen_image()?;
+
+        let mut offscreen = vk_init.offscreen_head.take().unwrap();
+        let msaa_image = offscreen.msaa_image.as_mut().unwrap();
+        assert!(msaa_image
+            .usage
+            .contains(ImageUsageFlags::TRANSIENT_ATTACHMENT));
+        let barriers = [
+            msaa_image.get_image_layout_transition_barrier2(
+                ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                None,
+                None,
+            )?,
+            offscreen.images[target.index].get_image_layout_transition_barrier2(
+                ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                None,
+                None,
+            )?,
+            offscreen.depth_image.get_image_layout_transition_barrier2(
+                ImageLayout::DEPTH_ATTACHMENT_OPTIMAL,
+                None,
+                None,
+            )?,
+        ];
+        vk_init.offscreen_head = Some(offscreen);
+
+        let mut rendered = Ok(());
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &barriers, &[]);
+            rendered = vk_init.begin_rendering(&target.view, cmd_buffer);
+            if rendered.is_ok() {
+                vk_init.end_rendering(cmd_buffer);
+            }
+        })?;
+        rendered?;
+
+        let mut offscreen = vk_init.offscreen_head.take().unwrap();
+        let pixels = vk_init.download_image(&mut offscreen.images[target.index])?;
+        vk_init.offscreen_head = Some(offscreen);
+        assert!(pixels.chunks(4).all(|texel| texel == [255, 0, 0, 255]));
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        vk_init.destroy()
+    }
+}
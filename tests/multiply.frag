@@ -0,0 +1,15 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D dst;
+
+layout(push_constant) uniform Push {
+    vec4 source;
+} push;
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    out_color = texture(dst, uv) * push.source;
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, VMABuffer, VkInit, VkInitCreateInfo};
+
+    const THREADS: usize = 4;
+    const BUFFERS_PER_THREAD: usize = 32;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn buffers_are_created_from_four_threads() -> Result<(), Error> {
+        assert_send_sync::<VkInit>();
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        vk_init.begin_debug_message_capture();
+
+        let results: Vec<Result<Vec<VMABuffer>, Error>> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..THREADS)
+                .map(|thread| {
+                    let vk_init = &vk_init;
+                    scope.spawn(move || {
+                        // Command pools are externally synchronized, every thread records into its own.
+                        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+                        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+                        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+                        let mut buffers = vec![];
+                        for index in 0..BUFFERS_PER_THREAD {
+                            let size = 1024 * (1 + index % 4);
+                            let local = vk_init.create_local_buffer(
+                                size,
+                                BufferUsageFlags::STORAGE_BUFFER | BufferUsageFlags::TRANSFER_DST,
+                            )?;
+                            let upload = vk_init
+                                .create_cpu_to_gpu_buffer(size, BufferUsageFlags::TRANSFER_SRC)?;
+                            upload.set_data(&vk_init.device, 0, &vec![thread as u8; size])?;
+                            upload.enqueue_copy_to_buffer(
+                                &vk_init.device,
+                                &cmd_buffer,
+                                &local,
+                                None,
+                                None,
+                                Some(size as u64),
+                            )?;
+                            buffers.push(local);
+                            buffers.push(upload);
+                        }
+                        unsafe { vk_init.device.end_command_buffer(cmd_buffer)? };
+                        vk_init.destroy_cmd_pool(&cmd_pool)?;
+                        Ok(buffers)
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().unwrap())
+                .collect()
+        });
+
+        let mut created = 0;
+        for buffers in results {
+            for mut buffer in buffers? {
+                assert!(buffer.buffer != Buffer::null());
+                buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+                created += 1;
+            }
+        }
+        assert_eq!(created, THREADS * BUFFERS_PER_THREAD * 2);
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,119 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{AcquireResult, CmdType, Error};
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> Rect2D {
+        Rect2D {
+            offset: Offset2D { x, y },
+            extent: Extent2D { width, height },
+        }
+    }
+
+    #[test]
+    fn renders_and_presents_dirty_rectangle() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let extent = vk_init.head.as_ref().unwrap().surface_info.current_extent;
+        let acquire_semaphore = vk_init.create_semaphore()?;
+        let render_semaphore = vk_init.create_semaphore()?;
+        let fence = vk_init.create_fence()?;
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+
+        vk_init.begin_debug_message_capture();
+        let AcquireResult::Ready {
+            index,
+            image,
+            view: image_view,
+            ..
+        } = vk_init.acquire_next_swapchain_image(acquire_semaphore, None)?
+        else {
+            panic!("freshly created swapchain is out of date");
+        };
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[&cmd_buffer])?;
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+
+        let color_range = ImageSubresourceRange {
+            aspect_mask: ImageAspectFlags::COLOR,
+            level_count: 1,
+            layer_count: 1,
+            ..Default::default()
+        };
+        let to_attachment = ImageMemoryBarrier2::builder()
+            .image(image)
+            .subresource_range(color_range)
+            .old_layout(ImageLayout::UNDEFINED)
+            .new_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .src_stage_mask(PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .dst_stage_mask(PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .dst_access_mask(
+                AccessFlags2::COLOR_ATTACHMENT_READ | AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            )
+            .build();
+        let depth_barrier = vk_init
+            .head
+            .as_mut()
+            .unwrap()
+            .depth_image
+            .get_image_layout_transition_barrier2(
+                ImageLayout::DEPTH_ATTACHMENT_OPTIMAL,
+                None,
+                None,
+            )?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[to_attachment, depth_barrier], &[]);
+
+        let outside = rect(extent.width as i32 - 8, 0, 16, 16);
+        assert!(matches!(
+            vk_init.begin_rendering_ex(&image_view, &cmd_buffer, outside, AttachmentLoadOp::LOAD),
+            Err(Error::RenderAreaOutOfBounds { .. })
+        ));
+
+        let dirty = rect(16, 16, 64, 32);
+        vk_init.begin_rendering_ex(&image_view, &cmd_buffer, dirty, AttachmentLoadOp::LOAD)?;
+        assert!(matches!(
+            vk_init.cmd_set_scissor_in_render_area(&cmd_buffer, rect(0, 0, 32, 32), dirty),
+            Err(Error::ScissorOutsideRenderArea { .. })
+        ));
+        vk_init.cmd_set_scissor_in_render_area(&cmd_buffer, rect(32, 24, 16, 16), dirty)?;
+        vk_init.end_rendering(&cmd_buffer);
+
+        let to_present = ImageMemoryBarrier2::builder()
+            .image(image)
+            .subresource_range(color_range)
+            .old_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .new_layout(ImageLayout::PRESENT_SRC_KHR)
+            .src_stage_mask(PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(AccessFlags2::COLOR_ATTACHMENT_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::BOTTOM_OF_PIPE)
+            .build();
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[to_present], &[]);
+        vk_init.end_and_submit_cmd_buffer(
+            &cmd_buffer,
+            CmdType::Any,
+            &fence,
+            &[acquire_semaphore],
+            &[render_semaphore],
+            &[PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT],
+        )?;
+
+        // The second rectangle is clipped to the surface extent.
+        let damage = [dirty, rect(extent.width as i32 - 8, 0, 16, 16)];
+        vk_init.present_with_damage(&render_semaphore, index, &damage)?;
+        vk_init.wait_device_idle()?;
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        vk_init.destroy_fence(&fence)?;
+        vk_init.destroy_semaphore(&acquire_semaphore)?;
+        vk_init.destroy_semaphore(&render_semaphore)?;
+        vk_init.destroy()
+    }
+}
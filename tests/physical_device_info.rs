@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod tests {
+    use vku::PhysicalDeviceInfo;
+
+    #[test]
+    fn decodes_driver_versions_per_vendor() {
+        // NVIDIA 535.104.5.0
+        let nvidia = (535 << 22) | (104 << 14) | (5 << 6);
+        assert_eq!(
+            PhysicalDeviceInfo::decode_driver_version(0x10DE, nvidia),
+            "535.104.5.0"
+        );
+        // Mesa drivers use the Vulkan version encoding.
+        let mesa = ash::vk::make_api_version(0, 23, 1, 4);
+        assert_eq!(
+            PhysicalDeviceInfo::decode_driver_version(0x1002, mesa),
+            "23.1.4"
+        );
+        let intel = (101 << 14) | 4887;
+        let expected = if cfg!(windows) {
+            "101.4887"
+        } else {
+            "0.405.791"
+        };
+        assert_eq!(
+            PhysicalDeviceInfo::decode_driver_version(0x8086, intel),
+            expected
+        );
+    }
+}
@@ -1,12 +1,13 @@
+mod common;
+
 #[cfg(test)]
 mod tests {
+    use crate::common::default_vk_init;
     use std::mem::size_of;
 
     use ash::vk::*;
     use std::result::Result;
-    use vku::pipeline_builder::{BlendMode, DepthInfo, StencilInfo, VKUPipeline, VertexConvert};
-    use vku::{Error, VkInit};
-    use winit::platform::x11::EventLoopBuilderExtX11;
+    use vku::prelude::*;
 
     #[repr(C)]
     struct Vertex2D {
@@ -57,27 +58,6 @@ mod tests {
         pub vec_3: [f32; 4],
     }
 
-    fn default_vk_init() -> Result<VkInit, Error> {
-        use vku::VkInitCreateInfo;
-        use winit::dpi::LogicalSize;
-        use winit::event_loop::{EventLoop, EventLoopBuilder};
-        use winit::window::WindowBuilder;
-
-        env_logger::init();
-        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
-        let size = [800_u32, 600_u32];
-        let window = WindowBuilder::new()
-            .with_inner_size(LogicalSize {
-                width: size[0],
-                height: size[1],
-            })
-            .build(&event_loop)
-            .unwrap();
-
-        let create_info = VkInitCreateInfo::default();
-        VkInit::new(Some(&window), Some(size), create_info)
-    }
-
     #[test]
     fn default_pipeline() -> Result<(), Error> {
         let vk_init = default_vk_init()?;
@@ -146,4 +126,26 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn vertex_convert_to_dynamic_vertex_input() {
+        let bindings = Vertex2D::binding_desc2();
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0].stride, size_of::<Vertex2D>() as u32);
+        assert_eq!(bindings[0].input_rate, VertexInputRate::VERTEX);
+        assert_eq!(bindings[0].divisor, 1);
+
+        let attributes: Vec<(u32, u32, Format)> = Vertex2D::attrib_desc2()
+            .iter()
+            .map(|attrib| (attrib.location, attrib.offset, attrib.format))
+            .collect();
+        assert_eq!(
+            attributes,
+            [
+                (0, 0, Format::R32G32B32A32_SFLOAT),
+                (1, 16, Format::R32G32_SFLOAT),
+                (2, 24, Format::R8G8B8A8_UNORM)
+            ]
+        );
+    }
 }
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::pipeline_builder::BlendMode;
+    use vku::{Error, VKUPipeline, VKUPipelineBuilder, VkInit, VkInitCreateInfo};
+
+    fn builder(vk_init: &VkInit) -> Result<VKUPipelineBuilder, Error> {
+        VKUPipeline::builder()
+            .with_vertex::<()>(PrimitiveTopology::TRIANGLE_LIST)
+            .with_viewports_scissors(&[Viewport::default()], &[Rect2D::default()])
+            .with_rasterization(PolygonMode::FILL, CullModeFlags::NONE)
+            .with_multisample(SampleCountFlags::TYPE_1)
+            .with_colorblends(&[BlendMode::Opaque])
+            .with_dynamic(&[DynamicState::VIEWPORT, DynamicState::SCISSOR])
+            .with_push_constants::<[f32; 4]>()
+            .push_shader_stage(
+                &vk_init.device,
+                ShaderStageFlags::VERTEX,
+                "./src/shaders/fullscreen.vert.spv",
+                &[],
+            )?
+            .push_shader_stage(
+                &vk_init.device,
+                ShaderStageFlags::FRAGMENT,
+                "./tests/push_color.frag.spv",
+                &[],
+            )
+            .map(|builder| builder.with_rendering(&[Format::R8G8B8A8_UNORM], None, None))
+    }
+
+    #[test]
+    fn pipeline_cache_round_trips_through_disk() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        let path = std::env::temp_dir().join("vku_pipeline_cache/cache.bin");
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(
+            vk_init.save_pipeline_cache(&path),
+            Err(Error::PipelineCacheNotLoaded)
+        ));
+        vk_init.load_or_create_pipeline_cache(&path)?;
+        let mut pipeline = builder(&vk_init)?.build(&vk_init, "Cached")?;
+        pipeline.destroy(&vk_init.device)?;
+        vk_init.save_pipeline_cache(&path)?;
+        let saved = std::fs::read(&path)?;
+        assert!(saved.len() >= 16 + UUID_SIZE);
+
+        // Reloading replaces the first cache, the second build consumes the saved data.
+        let reloaded = vk_init.load_or_create_pipeline_cache(&path)?;
+        assert_eq!(vk_init.pipeline_cache(), reloaded);
+        let mut pipeline = builder(&vk_init)?.build(&vk_init, "Cached_Reloaded")?;
+        pipeline.destroy(&vk_init.device)?;
+
+        let explicit = vk_init.create_pipeline_cache(Some(&saved))?;
+        let mut pipeline = builder(&vk_init)?
+            .with_pipeline_cache(explicit)
+            .build(&vk_init, "Cached_Explicit")?;
+        pipeline.destroy(&vk_init.device)?;
+        assert!(!vk_init.get_pipeline_cache_data(explicit)?.is_empty());
+        vk_init.destroy_pipeline_cache(explicit);
+
+        // Data of another device or driver is dropped instead of passed to the driver.
+        let mut foreign = saved.clone();
+        foreign[16] ^= 0xFF;
+        let fresh = vk_init.create_pipeline_cache(Some(&foreign))?;
+        vk_init.destroy_pipeline_cache(fresh);
+
+        let _ = std::fs::remove_file(&path);
+        vk_init.destroy()
+    }
+}
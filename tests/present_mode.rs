@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, VkInit, VkInitCreateInfo};
+    use winit::dpi::LogicalSize;
+    use winit::event_loop::{EventLoop, EventLoopBuilder};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+    use winit::window::WindowBuilder;
+
+    #[test]
+    fn set_present_mode_keeps_surface_and_depth_image() -> Result<(), Error> {
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+        let mut vk_init = VkInit::new_windowed(
+            &window,
+            size,
+            window.scale_factor(),
+            VkInitCreateInfo::default(),
+        )?;
+        vk_init.begin_debug_message_capture();
+        let (surface, swapchain, depth_image) = {
+            let head = vk_init.head();
+            (head.surface, head.swapchain, head.depth_image.image)
+        };
+
+        // Shared present modes are only available for shared presentable images.
+        assert!(matches!(
+            vk_init.set_present_mode(PresentModeKHR::SHARED_DEMAND_REFRESH, size),
+            Err(Error::PresentModeNotSupported)
+        ));
+        assert_eq!(vk_init.head().swapchain, swapchain);
+
+        let supported = vk_init.head().surface_info.supported_present_modes.clone();
+        let mode = [PresentModeKHR::MAILBOX, PresentModeKHR::IMMEDIATE]
+            .into_iter()
+            .find(|mode| supported.contains(mode))
+            .unwrap_or(PresentModeKHR::FIFO);
+        vk_init.set_present_mode(mode, size)?;
+        let head = vk_init.head();
+        assert_eq!(head.surface_info.present_mode, mode);
+        assert_eq!(head.surface, surface);
+        assert_eq!(head.depth_image.image, depth_image);
+        assert_ne!(head.swapchain, swapchain);
+        assert_eq!(
+            head.swapchain_images.len(),
+            head.swapchain_image_views.len()
+        );
+
+        let frame = vk_init.begin_frame()?;
+        let to_present = ImageMemoryBarrier2::builder()
+            .image(frame.image)
+            .old_layout(ImageLayout::UNDEFINED)
+            .new_layout(ImageLayout::PRESENT_SRC_KHR)
+            .src_stage_mask(PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT)
+            .dst_stage_mask(PipelineStageFlags2::BOTTOM_OF_PIPE)
+            .subresource_range(ImageSubresourceRange {
+                aspect_mask: ImageAspectFlags::COLOR,
+                level_count: 1,
+                layer_count: 1,
+                ..Default::default()
+            })
+            .build();
+        vk_init.cmd_pipeline_barrier2(&frame.cmd_buffer, &[to_present], &[]);
+        vk_init.end_frame(frame)?;
+
+        // Resizing and the window handle shortcut reuse the surface as well.
+        vk_init.on_resize(&window, [640, 480])?;
+        let swapchain = vk_init.head().swapchain;
+        assert!(matches!(
+            vk_init.on_resize(&window, [0, 480]),
+            Err(Error::WindowMinimized)
+        ));
+        assert_eq!(vk_init.head().swapchain, swapchain);
+        vk_init.change_present_mode(&window, [640, 480], PresentModeKHR::FIFO)?;
+        let head = vk_init.head();
+        assert_eq!(head.surface, surface);
+        assert_eq!(head.surface_info.present_mode, PresentModeKHR::FIFO);
+        let extent = head.surface_info.current_extent;
+        assert_eq!(
+            [
+                head.depth_image.extent.width,
+                head.depth_image.extent.height
+            ],
+            [extent.width, extent.height]
+        );
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        vk_init.destroy()
+    }
+}
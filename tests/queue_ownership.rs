@@ -0,0 +1,85 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CaptureOptions, CmdType, Error};
+
+    const WIDTH: u32 = 64;
+    const HEIGHT: u32 = 2;
+
+    #[test]
+    fn upload_on_transfer_queue_sampled_on_unified_queue() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let pixels: Vec<u8> = (0..HEIGHT)
+            .flat_map(|_| 0..WIDTH)
+            .flat_map(|x| {
+                let value = (x * 4) as u8;
+                [value, 255 - value, 0, 255]
+            })
+            .collect();
+        let extent = Extent3D {
+            width: WIDTH,
+            height: HEIGHT,
+            depth: 1,
+        };
+        let mut image = vk_init.create_empty_image(
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+            ImageAspectFlags::COLOR,
+        )?;
+        image.set_staging_data(&vk_init.device, &pixels)?;
+        let to_transfer_dst = image.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_DST_OPTIMAL,
+            None,
+            None,
+        )?;
+        let transfer = vk_init.image_ownership_transfer(
+            &mut image,
+            CmdType::Transfer,
+            CmdType::Any,
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            (PipelineStageFlags2::COPY, AccessFlags2::TRANSFER_WRITE),
+            (
+                PipelineStageFlags2::FRAGMENT_SHADER,
+                AccessFlags2::SHADER_SAMPLED_READ,
+            ),
+        );
+        let (_, transfer_family) = vk_init.get_queue(CmdType::Transfer);
+        let (_, unified_family) = vk_init.get_queue(CmdType::Any);
+        match transfer.acquire {
+            Some(acquire) => {
+                assert_ne!(transfer_family, unified_family);
+                assert_eq!(transfer.release.src_queue_family_index, transfer_family);
+                assert_eq!(acquire.dst_queue_family_index, unified_family);
+            }
+            None => assert_eq!(transfer_family, unified_family),
+        }
+
+        vk_init.begin_debug_message_capture();
+        // The fence wait of immediate_submit orders the release before the acquire.
+        vk_init.immediate_submit(CmdType::Transfer, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[to_transfer_dst], &[]);
+            image.enque_copy_from_staging_buffer_to_image(&vk_init.device, cmd_buffer);
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &[transfer.release], &[]);
+        })?;
+        if let Some(acquire) = transfer.acquire {
+            vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+                vk_init.cmd_pipeline_barrier2(cmd_buffer, &[acquire], &[]);
+            })?;
+        }
+        let captured = vk_init.capture_image(&image, CaptureOptions::AsIs)?;
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        assert_eq!(captured.rgba8, pixels);
+
+        image.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
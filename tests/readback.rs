@@ -0,0 +1,60 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::mem::size_of;
+    use std::time::Duration;
+
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error};
+
+    #[test]
+    fn readback_slot_round_trip() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let size = 1024 * size_of::<u32>();
+        let data: Vec<u32> = (0..1024).collect();
+
+        let src = vk_init.create_cpu_to_gpu_buffer(size, BufferUsageFlags::TRANSFER_SRC)?;
+        src.set_data(&vk_init.device, 0, &data)?;
+        let mut slots = vk_init.create_readback_slots(size, 2)?;
+        assert!(matches!(
+            slots[0].read::<u32>(&vk_init),
+            Err(Error::ReadbackNotReady)
+        ));
+
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        src.enqueue_copy_to_buffer(
+            &vk_init.device,
+            &cmd_buffer,
+            &slots[0].buffer,
+            None,
+            None,
+            None,
+        )?;
+        vk_init.end_and_submit_cmd_buffer(
+            &cmd_buffer,
+            CmdType::Any,
+            &slots[0].fence,
+            &[],
+            &[],
+            &[],
+        )?;
+
+        let read: Vec<u32> = slots[0].read_blocking(&vk_init, Duration::from_secs(1))?;
+        assert_eq!(read, data);
+        assert!(slots[0].is_ready(&vk_init));
+        assert!(matches!(
+            slots[0].read::<()>(&vk_init),
+            Err(Error::ReadZeroSizedType)
+        ));
+
+        slots[0].reuse(&vk_init)?;
+        assert!(!slots[0].is_ready(&vk_init));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+#version 450
+layout(location = 0) in vec2 i_uv;
+layout(location = 0) out vec4 o_col;
+
+layout(set = 0, binding = 0) uniform Params {
+    vec4 tint;
+} params;
+layout(set = 0, binding = 1) readonly buffer Weights {
+    float weights[];
+};
+layout(set = 0, binding = 2) uniform texture2D tex;
+layout(set = 0, binding = 3) uniform sampler tex_sampler;
+
+void main() {
+    o_col = params.tint * weights[0] * texture(sampler2D(tex, tex_sampler), i_uv);
+}
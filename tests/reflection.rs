@@ -0,0 +1,133 @@
+#[cfg(all(test, feature = "reflection"))]
+mod tests {
+    use ash::util::read_spv;
+    use ash::vk::{DescriptorType, ShaderStageFlags};
+    use std::io::Cursor;
+    use vku::{reflect_spirv_shader, Error, ReflectedType, ShaderReflection};
+
+    const PUSH_LAYOUT_COMP_SPV: &[u8] = include_bytes!("push_layout.comp.spv");
+    const REFLECT_LAYOUT_FRAG_SPV: &[u8] = include_bytes!("reflect_layout.frag.spv");
+    const DEFAULT_VERT_SPV: &[u8] = include_bytes!("default.vert.spv");
+    const DEFAULT_FRAG_SPV: &[u8] = include_bytes!("default.frag.spv");
+
+    fn check_push_layout(reflection: &ShaderReflection) {
+        let block = reflection.push_constants.as_ref().unwrap();
+        let offsets: Vec<(&str, u32)> = block
+            .members
+            .iter()
+            .map(|member| (member.name.as_str(), member.offset))
+            .collect();
+        assert_eq!(
+            offsets,
+            [
+                ("position", 0),
+                ("radius", 12),
+                ("color", 16),
+                ("rotation", 32),
+                ("weights", 80)
+            ]
+        );
+        assert!(matches!(
+            block.members[3].ty,
+            ReflectedType::Matrix {
+                columns: 3,
+                stride: 16,
+                ..
+            }
+        ));
+        assert_eq!(block.size, 88);
+        assert_eq!(reflection.push_constant_size(), 88);
+
+        let bindings = reflection.rust_bindings();
+        assert!(bindings.contains("pub const OUT_DATA_SET: u32 = 0;"));
+        assert!(bindings.contains("pub const OUT_DATA_BINDING: u32 = 1;"));
+        let fields = [
+            "pub position: [f32; 3],",
+            "pub radius: f32,",
+            "pub color: [f32; 3],",
+            "pub _pad0: [u8; 4],",
+            "pub rotation: [[f32; 4]; 3],",
+            "pub weights: [f32; 2],",
+        ];
+        let struct_start = bindings.find("pub struct PushLayout {").unwrap();
+        let mut cursor = struct_start;
+        for field in fields {
+            let position = bindings[cursor..].find(field).unwrap();
+            cursor += position + field.len();
+        }
+    }
+
+    #[test]
+    fn reflects_push_constant_layout() -> Result<(), Error> {
+        let code = read_spv(&mut Cursor::new(PUSH_LAYOUT_COMP_SPV))?;
+        let reflection = ShaderReflection::new(&code)?;
+        check_push_layout(&reflection);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_spirv() {
+        assert!(matches!(
+            ShaderReflection::new(&[0, 1, 2]),
+            Err(Error::InvalidSpirv(_))
+        ));
+    }
+
+    #[test]
+    fn reflects_descriptor_types_and_vertex_inputs() -> Result<(), Error> {
+        let frag = reflect_spirv_shader(REFLECT_LAYOUT_FRAG_SPV)?;
+        assert_eq!(frag.stages, ShaderStageFlags::FRAGMENT);
+        let types: Vec<(u32, DescriptorType, u32)> = frag
+            .bindings
+            .iter()
+            .map(|binding| (binding.binding, binding.descriptor_type, binding.count))
+            .collect();
+        assert_eq!(
+            types,
+            [
+                (0, DescriptorType::UNIFORM_BUFFER, 1),
+                (1, DescriptorType::STORAGE_BUFFER, 1),
+                (2, DescriptorType::SAMPLED_IMAGE, 1),
+                (3, DescriptorType::SAMPLER, 1),
+            ]
+        );
+        assert!(frag.vertex_inputs.is_empty());
+
+        let vert = reflect_spirv_shader(DEFAULT_VERT_SPV)?;
+        let locations: Vec<(&str, u32)> = vert
+            .vertex_inputs
+            .iter()
+            .map(|input| (input.name.as_str(), input.location))
+            .collect();
+        assert_eq!(locations, [("i_pos", 0), ("i_uv", 1), ("i_col", 2)]);
+        let range = vert.push_constant_range().unwrap();
+        assert_eq!(range.stage_flags, ShaderStageFlags::VERTEX);
+        assert_eq!(range.size, vert.push_constant_size());
+
+        let default_frag = reflect_spirv_shader(DEFAULT_FRAG_SPV)?;
+        let descriptors = ShaderReflection::pipeline_descriptors(&[&vert, &default_frag], 0)?;
+        assert_eq!(
+            descriptors,
+            [(
+                false,
+                DescriptorType::COMBINED_IMAGE_SAMPLER,
+                ShaderStageFlags::FRAGMENT,
+                1
+            )]
+        );
+        assert!(matches!(
+            ShaderReflection::pipeline_descriptors(&[&frag, &default_frag], 0),
+            Err(Error::ReflectedDescriptorsIncompatible { binding: 0, .. })
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "shader")]
+    #[test]
+    fn reflects_compiled_glsl() -> Result<(), Error> {
+        let src = std::fs::read_to_string("tests/push_layout.comp")?;
+        let compiled = vku::shader_ad_hoc(src, "push_layout.comp", "comp", true)?;
+        check_push_layout(&ShaderReflection::new(compiled.as_binary())?);
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, RenderingConfig, VMAImage, VkInit, VkInitCreateInfo};
+
+    #[test]
+    fn renders_to_image_and_samples_it() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        vk_init.begin_debug_message_capture();
+        let extent = Extent3D {
+            width: 32,
+            height: 16,
+            depth: 1,
+        };
+        let mut color = VMAImage::create_render_image(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            extent,
+            Format::R8G8B8A8_UNORM,
+            4,
+        )?;
+        let mut depth = VMAImage::create_depth_image(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            extent,
+            Format::D32_SFLOAT,
+            4,
+        )?;
+        assert!(matches!(
+            vk_init.begin_rendering_to_image(
+                &depth,
+                None,
+                &CommandBuffer::null(),
+                &RenderingConfig::default()
+            ),
+            Err(Error::ImageUsageMissing(ImageUsageFlags::COLOR_ATTACHMENT))
+        ));
+
+        let to_attachments = [
+            color.get_image_layout_transition_barrier2(
+                ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                None,
+                None,
+            )?,
+            depth.get_image_layout_transition_barrier2(
+                ImageLayout::DEPTH_ATTACHMENT_OPTIMAL,
+                None,
+                None,
+            )?,
+        ];
+        let to_sampled = [color.get_image_layout_transition_barrier2(
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            None,
+            None,
+        )?];
+        let config = RenderingConfig {
+            clear_color_value: Some(ClearColorValue {
+                float32: [0.0, 1.0, 0.0, 1.0],
+            }),
+            ..Default::default()
+        };
+
+        let mut rendered = Ok(());
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &to_attachments, &[]);
+            rendered = vk_init.begin_rendering_to_image(&color, Some(&depth), cmd_buffer, &config);
+            if rendered.is_ok() {
+                vk_init.end_rendering(cmd_buffer);
+            }
+            vk_init.cmd_pipeline_barrier2(cmd_buffer, &to_sampled, &[]);
+        })?;
+        rendered?;
+        assert_eq!(color.current_layout, ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+        let pixels = vk_init.download_image(&mut color)?;
+        assert_eq!(pixels.len(), 32 * 16 * 4);
+        assert!(pixels.chunks(4).all(|texel| texel == [0, 255, 0, 255]));
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        color.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        depth.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,26 @@
+#[cfg(feature = "renderdoc")]
+mod common;
+
+#[cfg(all(test, feature = "renderdoc"))]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::result::Result;
+    use vku::Error;
+
+    #[test]
+    fn capture_calls_without_renderdoc() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+
+        // Outside of RenderDoc the API is never loaded and the capture calls are no-ops.
+        if std::env::var("ENABLE_VULKAN_RENDERDOC_CAPTURE").is_err() {
+            assert!(!vk_init.renderdoc_available());
+            assert!(!vk_init.renderdoc_layer_active());
+        }
+        vk_init.trigger_capture();
+        vk_init.start_frame_capture();
+        vk_init.end_frame_capture();
+
+        vk_init.destroy()?;
+        Ok(())
+    }
+}
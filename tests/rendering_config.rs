@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use vku::{Error, RenderTargets, RenderingConfig};
+
+    fn targets() -> RenderTargets {
+        RenderTargets {
+            extent: Extent2D {
+                width: 800,
+                height: 600,
+            },
+            clear_color_value: ClearColorValue {
+                float32: [0.0, 0.0, 0.0, 1.0],
+            },
+            clear_depth_stencil_value: ClearDepthStencilValue {
+                depth: 1.0,
+                stencil: 0,
+            },
+            depth_view: ImageView::from_raw(2),
+            msaa_view: None,
+        }
+    }
+
+    #[test]
+    fn load_depth_pass() {
+        let area = Rect2D {
+            offset: Offset2D { x: 0, y: 100 },
+            extent: Extent2D {
+                width: 800,
+                height: 500,
+            },
+        };
+        let config = RenderingConfig {
+            render_area: Some(area),
+            color_load_op: AttachmentLoadOp::LOAD,
+            depth_load_op: AttachmentLoadOp::LOAD,
+            depth_store_op: AttachmentStoreOp::DONT_CARE,
+            clear_color_value: Some(ClearColorValue {
+                float32: [1.0, 0.0, 0.0, 1.0],
+            }),
+            ..Default::default()
+        };
+        let attachments = config
+            .attachments(ImageView::from_raw(1), &targets())
+            .unwrap();
+        let info = attachments.rendering_info();
+
+        assert_eq!(info.render_area, area);
+        assert_eq!(info.layer_count, 1);
+        assert_eq!(info.color_attachment_count, 1);
+        let (color, depth) = unsafe { (&*info.p_color_attachments, &*info.p_depth_attachment) };
+        assert_eq!(color.image_view, ImageView::from_raw(1));
+        assert_eq!(color.image_layout, ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+        assert_eq!(color.load_op, AttachmentLoadOp::LOAD);
+        assert_eq!(color.store_op, AttachmentStoreOp::STORE);
+        assert_eq!(color.resolve_mode, ResolveModeFlags::NONE);
+        assert_eq!(
+            unsafe { color.clear_value.color.float32 },
+            [1.0, 0.0, 0.0, 1.0]
+        );
+        assert_eq!(depth.image_view, ImageView::from_raw(2));
+        assert_eq!(depth.image_layout, ImageLayout::DEPTH_ATTACHMENT_OPTIMAL);
+        assert_eq!(depth.load_op, AttachmentLoadOp::LOAD);
+        assert_eq!(depth.store_op, AttachmentStoreOp::DONT_CARE);
+        assert_eq!(unsafe { depth.clear_value.depth_stencil.depth }, 1.0);
+    }
+
+    #[test]
+    fn default_config_clears_whole_extent() {
+        let attachments = RenderingConfig::default()
+            .attachments(ImageView::from_raw(1), &targets())
+            .unwrap();
+        let info = attachments.rendering_info();
+        assert_eq!(info.render_area.extent, targets().extent);
+        let (color, depth) = unsafe { (&*info.p_color_attachments, &*info.p_depth_attachment) };
+        assert_eq!(color.load_op, AttachmentLoadOp::CLEAR);
+        assert_eq!(depth.load_op, AttachmentLoadOp::CLEAR);
+        assert_eq!(depth.store_op, AttachmentStoreOp::STORE);
+    }
+
+    #[test]
+    fn without_depth_and_with_msaa() {
+        let config = RenderingConfig {
+            depth: false,
+            ..Default::default()
+        };
+        let targets = RenderTargets {
+            msaa_view: Some(ImageView::from_raw(3)),
+            ..targets()
+        };
+        let attachments = config
+            .attachments(ImageView::from_raw(1), &targets)
+            .unwrap();
+        let info = attachments.rendering_info();
+        assert!(info.p_depth_attachment.is_null());
+        let color = unsafe { &*info.p_color_attachments };
+        assert_eq!(color.image_view, ImageView::from_raw(3));
+        assert_eq!(color.resolve_image_view, ImageView::from_raw(1));
+        assert_eq!(color.resolve_mode, ResolveModeFlags::AVERAGE);
+    }
+
+    #[test]
+    fn render_area_outside_extent() {
+        let config = RenderingConfig {
+            render_area: Some(Rect2D {
+                offset: Offset2D { x: 700, y: 0 },
+                extent: Extent2D {
+                    width: 200,
+                    height: 600,
+                },
+            }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.attachments(ImageView::null(), &targets()),
+            Err(Error::RenderAreaOutOfBounds { .. })
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::time::Duration;
+
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, VMAImage};
+
+    #[test]
+    fn record_once_replay_many() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+
+        // Primary commands submitted directly, several times.
+        let mut slot = vk_init.create_readback_slots(16, 1)?.remove(0);
+        let mut fill = vk_init.create_replayable_commands(
+            CmdType::Any,
+            CommandBufferLevel::PRIMARY,
+            &[],
+            Format::UNDEFINED,
+        )?;
+        assert!(!fill.is_current(&vk_init));
+        let buffer = slot.buffer.buffer;
+        fill.record(&vk_init, |vk_init, cmd_buffer| {
+            unsafe {
+                vk_init
+                    .device
+                    .cmd_fill_buffer(*cmd_buffer, buffer, 0, 16, 0xABCD)
+            };
+            Ok(())
+        })?;
+        assert!(fill.is_current(&vk_init));
+
+        for _ in 0..3 {
+            vk_init.submit_cmd_buffers(&[*fill.cmd()], CmdType::Any, &slot.fence, &[], &[], &[])?;
+            let values: Vec<u32> = slot.read_blocking(&vk_init, Duration::from_secs(1))?;
+            assert_eq!(values, [0xABCD; 4]);
+            slot.reuse(&vk_init)?;
+        }
+
+        fill.invalidate();
+        assert!(!fill.is_current(&vk_init));
+
+        // Secondary commands executed inside rendering of a per-frame buffer.
+        let extent = Extent3D {
+            width: 4,
+            height: 4,
+            depth: 1,
+        };
+        let format = Format::R8G8B8A8_UNORM;
+        let mut target = VMAImage::create_render_image(
+            &vk_init.device,
+            &mut vk_init.allocator.lock(),
+            extent,
+            format,
+            4,
+        )?;
+        let mut pixel_slot = vk_init.create_readback_slots(64, 1)?.remove(0);
+
+        let mut scenery = vk_init.create_replayable_commands(
+            CmdType::Graphics,
+            CommandBufferLevel::SECONDARY,
+            &[format],
+            Format::UNDEFINED,
+        )?;
+        let clear_rect = ClearRect {
+            rect: Rect2D {
+                offset: Offset2D { x: 0, y: 0 },
+                extent: Extent2D {
+                    width: extent.width,
+                    height: extent.height,
+                },
+            },
+            base_array_layer: 0,
+            layer_count: 1,
+        };
+        scenery.record(&vk_init, |vk_init, cmd_buffer| {
+            let clear = ClearAttachment {
+                aspect_mask: ImageAspectFlags::COLOR,
+                color_attachment: 0,
+                clear_value: ClearValue {
+                    color: ClearColorValue {
+                        float32: [0.0, 1.0, 0.0, 1.0],
+                    },
+                },
+            };
+            unsafe {
+                vk_init
+                    .device
+                    .cmd_clear_attachments(*cmd_buffer, &[clear], &[clear_rect])
+            };
+            Ok(())
+        })?;
+
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Graphics)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        let barrier = target.get_image_layout_transition_barrier2(
+            ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            None,
+            None,
+        )?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[barrier], &[]);
+
+        let color_attachments = [RenderingAttachmentInfo::builder()
+            .image_view(target.image_view)
+            .image_layout(ImageLayout::COLOR_ATTACHMENT_OPTIMAL)
+            .load_op(AttachmentLoadOp::CLEAR)
+            .store_op(AttachmentStoreOp::STORE)
+            .clear_value(ClearValue {
+                color: ClearColorValue {
+                    float32: [1.0, 0.0, 0.0, 1.0],
+                },
+            })
+            .build()];
+        let rendering_info = RenderingInfo::builder()
+            .flags(RenderingFlags::CONTENTS_SECONDARY_COMMAND_BUFFERS)
+            .render_area(clear_rect.rect)
+            .layer_count(1)
+            .color_attachments(&color_attachments);
+        unsafe {
+            vk_init
+                .device
+                .cmd_begin_rendering(cmd_buffer, &rendering_info);
+            vk_init
+                .device
+                .cmd_execute_commands(cmd_buffer, &[*scenery.cmd()]);
+        }
+        vk_init.end_rendering(&cmd_buffer);
+
+        let barrier = target.get_image_layout_transition_barrier2(
+            ImageLayout::TRANSFER_SRC_OPTIMAL,
+            None,
+            None,
+        )?;
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[barrier], &[]);
+        let region = BufferImageCopy::builder()
+            .image_subresource(
+                ImageSubresourceLayers::builder()
+                    .aspect_mask(ImageAspectFlags::COLOR)
+                    .layer_count(1)
+                    .build(),
+            )
+            .image_extent(extent)
+            .build();
+        unsafe {
+            vk_init.device.cmd_copy_image_to_buffer(
+                cmd_buffer,
+                target.image,
+                ImageLayout::TRANSFER_SRC_OPTIMAL,
+                pixel_slot.buffer.buffer,
+                &[region],
+            )
+        };
+        vk_init.end_and_submit_cmd_buffer(
+            &cmd_buffer,
+            CmdType::Graphics,
+            &pixel_slot.fence,
+            &[],
+            &[],
+            &[],
+        )?;
+        let pixels: Vec<[u8; 4]> = pixel_slot.read_blocking(&vk_init, Duration::from_secs(1))?;
+        assert!(pixels.iter().all(|pixel| *pixel == [0, 255, 0, 255]));
+
+        scenery.set_formats(&[Format::B8G8R8A8_UNORM], Format::UNDEFINED);
+        assert!(!scenery.is_current(&vk_init));
+
+        vk_init.wait_device_idle()?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        fill.destroy(&vk_init)?;
+        scenery.destroy(&vk_init)?;
+        target.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        pixel_slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, SamplerInfo, VkInit, VkInitCreateInfo};
+
+    #[test]
+    fn sampler_info_defaults() {
+        let info = SamplerInfo::default();
+        assert_eq!(info.filter, Filter::LINEAR);
+        assert_eq!(info.address_mode, SamplerAddressMode::CLAMP_TO_EDGE);
+        assert_eq!(info.anisotropy, None);
+        assert_eq!(info.compare_op, None);
+        assert_ne!(SamplerInfo::nearest(), info);
+    }
+
+    #[test]
+    fn cached_samplers_are_shared() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        vk_init.begin_debug_message_capture();
+
+        let linear = vk_init.get_or_create_sampler(SamplerInfo::default())?;
+        assert_eq!(
+            vk_init.get_or_create_sampler(SamplerInfo::default())?,
+            linear
+        );
+        let nearest = vk_init.get_or_create_sampler(SamplerInfo::nearest())?;
+        assert_ne!(nearest, linear);
+
+        // Anisotropy beyond the device limit is clamped instead of failing validation.
+        let anisotropic = vk_init.create_sampler(SamplerInfo {
+            anisotropy: Some(1024.0),
+            address_mode: SamplerAddressMode::CLAMP_TO_BORDER,
+            border_color: BorderColor::FLOAT_OPAQUE_WHITE,
+            ..Default::default()
+        })?;
+        let shadow = vk_init.create_sampler(SamplerInfo {
+            compare_op: Some(CompareOp::LESS_OR_EQUAL),
+            ..Default::default()
+        })?;
+        vk_init.destroy_sampler(anisotropic);
+        vk_init.destroy_sampler(shadow);
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        vk_init.destroy()
+    }
+}
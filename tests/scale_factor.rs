@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use std::result::Result;
+    use vku::{Error, VkInit};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    fn transform(matrix: [[f32; 4]; 4], point: [f32; 2]) -> [f32; 2] {
+        [
+            matrix[0][0] * point[0] + matrix[1][0] * point[1] + matrix[3][0],
+            matrix[0][1] * point[0] + matrix[1][1] * point[1] + matrix[3][1],
+        ]
+    }
+
+    #[test]
+    fn ui_ortho_matrix_follows_scale_factor() -> Result<(), Error> {
+        use vku::VkInitCreateInfo;
+        use winit::dpi::PhysicalSize;
+        use winit::event_loop::{EventLoop, EventLoopBuilder};
+        use winit::window::WindowBuilder;
+
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(PhysicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+
+        let create_info = VkInitCreateInfo::default();
+        let mut vk_init = VkInit::new_windowed(&window, size, 2.0, create_info)?;
+        let surface_info = &vk_init.head.as_ref().unwrap().surface_info;
+        assert_eq!(surface_info.scale_factor, 2.0);
+        let [width, height] = surface_info.logical_extent();
+        assert_eq!(
+            transform(vk_init.ui_ortho_matrix(), [0.0, 0.0]),
+            [-1.0, -1.0]
+        );
+        assert_eq!(
+            transform(vk_init.ui_ortho_matrix(), [width, height]),
+            [1.0, 1.0]
+        );
+
+        vk_init.set_scale_factor(1.0)?;
+        vk_init.on_resize(&window, size)?;
+        let surface_info = &vk_init.head.as_ref().unwrap().surface_info;
+        assert_eq!(surface_info.scale_factor, 1.0);
+        assert_eq!(surface_info.logical_extent(), [width * 2.0, height * 2.0]);
+
+        vk_init.destroy()
+    }
+}
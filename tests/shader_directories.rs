@@ -0,0 +1,68 @@
+#[cfg(test)]
+#[cfg(feature = "shader")]
+mod tests {
+    use std::fs::{create_dir_all, remove_dir_all, remove_file, write};
+    use vku::Error;
+
+    const COMP: &str = "#version 450\nlayout(local_size_x = 1) in;\nvoid main() {}\n";
+    const VERT: &str = "#version 450\nvoid main() { gl_Position = vec4(0.0); }\n";
+    const FRAG: &str =
+        "#version 450\nlayout(location = 0) out vec4 color;\nvoid main() { color = vec4(1.0); }\n";
+
+    #[test]
+    fn compiles_nested_directories() -> Result<(), Error> {
+        let root = std::env::temp_dir().join("vku_shader_directories");
+        let _ = remove_dir_all(&root);
+        let src = root.join("src");
+        let target = root.join("target");
+        create_dir_all(src.join("post/fx"))?;
+        create_dir_all(&target)?;
+        write(src.join("top.comp"), COMP)?;
+        write(src.join("post/blur.frag"), FRAG)?;
+        write(src.join("post/fx/deep.vert"), VERT)?;
+        write(target.join("keep.txt"), "not generated by vku")?;
+
+        vku::compile_all_shaders_with_bindings(&src, &target, false)?;
+        for path in [
+            "top.comp.spv",
+            "post/blur.frag.spv",
+            "post/fx/deep.vert.spv",
+        ] {
+            assert!(target.join(path).is_file(), "missing {path}");
+        }
+        let bindings = std::fs::read_to_string(target.join("bindings.rs"))?;
+        assert!(bindings.contains("pub mod post_blur_frag {"));
+        assert!(bindings.contains("pub mod post_fx_deep_vert {"));
+        assert!(bindings.contains("pub mod top_comp {"));
+
+        remove_file(src.join("post/fx/deep.vert"))?;
+        vku::compile_all_shaders(&src, &target, false)?;
+        assert!(!target.join("post/fx").exists());
+        assert!(!target.join("bindings.rs").exists());
+        assert!(target.join("post/blur.frag.spv").is_file());
+        assert!(target.join("keep.txt").is_file());
+
+        remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn skips_unknown_files() -> Result<(), Error> {
+        let root = std::env::temp_dir().join("vku_shader_unknown_files");
+        let _ = remove_dir_all(&root);
+        let src = root.join("src");
+        let target = root.join("target");
+        create_dir_all(&src)?;
+        create_dir_all(&target)?;
+        write(src.join("top.comp"), COMP)?;
+        write(src.join("README.md"), "# Shaders")?;
+        write(src.join(".top.comp.swp"), "editor swap file")?;
+
+        vku::compile_all_shaders(&src, &target, false)?;
+        assert!(target.join("top.comp.spv").is_file());
+        assert!(!target.join("README.md.spv").exists());
+
+        remove_dir_all(&root)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+#[cfg(feature = "shader")]
+mod tests {
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use std::path::PathBuf;
+    use vku::{shader_ad_hoc, Error};
+
+    const GOOD: &str = "#version 450\nlayout(local_size_x = 1) in;\nvoid main() {}\n";
+    const BROKEN: &str =
+        "#version 450\nlayout(location = 0) out vec4 color;\nvoid main() {\n    color = missing;\n}\n";
+
+    #[test]
+    fn reports_every_failed_shader() -> Result<(), Error> {
+        let Err(Error::ShaderCompilation {
+            file,
+            num_errors,
+            log,
+            preprocessed,
+            ..
+        }) = shader_ad_hoc(BROKEN.to_string(), "broken", "frag", false)
+        else {
+            panic!("broken shader compiled");
+        };
+        assert_eq!(file.to_str(), Some("broken"));
+        assert!(num_errors >= 1);
+        assert!(log.contains("broken:4: error:"), "{log}");
+        let (_, annotation) = log.split_once("(preprocessed line ").expect(&log);
+        let (line, _) = annotation.split_once(')').unwrap();
+        let numbered = format!("{line}: ");
+        let source_line = preprocessed
+            .lines()
+            .find(|numbered_line| numbered_line.starts_with(&numbered))
+            .unwrap();
+        assert!(source_line.contains("missing"), "{preprocessed}");
+
+        let root = std::env::temp_dir().join("vku_shader_errors");
+        let _ = remove_dir_all(&root);
+        let src = root.join("src");
+        let target = root.join("target");
+        create_dir_all(&src)?;
+        write(src.join("a_broken.frag"), BROKEN)?;
+        write(src.join("b_good.comp"), GOOD)?;
+        write(src.join("c_broken.frag"), BROKEN)?;
+
+        let Err(Error::ShadersFailedToCompile(failures)) =
+            vku::compile_all_shaders(&src, &target, false)
+        else {
+            panic!("broken shaders compiled");
+        };
+        let files: Vec<_> = failures
+            .iter()
+            .map(|failure| match failure {
+                Error::ShaderCompilation { file, .. } => file.clone(),
+                other => panic!("unexpected error {other}"),
+            })
+            .collect();
+        assert_eq!(files, ["a_broken.frag", "c_broken.frag"].map(PathBuf::from));
+        assert!(target.join("b_good.comp.spv").is_file());
+
+        remove_dir_all(&root)?;
+        Ok(())
+    }
+}
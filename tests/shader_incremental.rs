@@ -0,0 +1,60 @@
+#[cfg(test)]
+#[cfg(feature = "shader")]
+mod tests {
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use vku::{compile_all_shaders_incremental, Error, ShaderCompileReport};
+
+    const COMP: &str = "#version 450\nlayout(local_size_x = 1) in;\nvoid main() {}\n";
+
+    #[test]
+    fn recompiles_changed_shaders_only() -> Result<(), Error> {
+        let root = std::env::temp_dir().join("vku_shader_incremental");
+        let _ = remove_dir_all(&root);
+        let src = root.join("src");
+        let target = root.join("target");
+        create_dir_all(&src)?;
+
+        let common = src.join("common.glsl");
+        write(&common, "const uint GROUP = 1;\n")?;
+        let included = format!(
+            "#version 450\n#extension GL_GOOGLE_include_directive : require\n#include \"{}\"\nlayout(local_size_x = GROUP) in;\nvoid main() {{}}\n",
+            common.to_string_lossy()
+        );
+        write(src.join("plain.comp"), COMP)?;
+        write(src.join("included.comp"), &included)?;
+
+        let report = |rebuilt, cached| ShaderCompileReport { rebuilt, cached };
+        assert_eq!(
+            compile_all_shaders_incremental(&src, &target, false)?,
+            report(2, 0)
+        );
+        assert_eq!(
+            compile_all_shaders_incremental(&src, &target, false)?,
+            report(0, 2)
+        );
+
+        write(
+            src.join("plain.comp"),
+            COMP.replace("void main", "\nvoid main"),
+        )?;
+        assert_eq!(
+            compile_all_shaders_incremental(&src, &target, false)?,
+            report(1, 1)
+        );
+
+        write(&common, "const uint GROUP = 2;\n")?;
+        assert_eq!(
+            compile_all_shaders_incremental(&src, &target, false)?,
+            report(1, 1)
+        );
+
+        assert_eq!(
+            compile_all_shaders_incremental(&src, &target, true)?,
+            report(2, 0)
+        );
+        assert!(target.join("included.comp.txt").is_file());
+
+        remove_dir_all(&root)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+#[cfg(feature = "shader")]
+mod tests {
+    use std::fs::{create_dir_all, remove_dir_all, write};
+    use vku::{shader_ad_hoc, Error, ShaderCompileOptions};
+
+    const FRAG: &str = r#"
+#version 450
+layout(location = 0) out vec4 color;
+void main() {
+#if SRGB_TEXTURES
+    color = vec4(pow(vec3(0.5), vec3(2.2)), 1.0);
+#else
+    color = vec4(0.5);
+#endif
+}
+"#;
+
+    #[test]
+    fn defines_change_compiled_spirv() -> Result<(), Error> {
+        let srgb = ShaderCompileOptions::default().with_define("SRGB_TEXTURES", Some("1"));
+        let linear = ShaderCompileOptions::default().with_define("SRGB_TEXTURES", Some("0"));
+        let srgb_code = shader_ad_hoc(FRAG.to_string(), "frag", "frag", srgb.clone())?;
+        let linear_code = shader_ad_hoc(FRAG.to_string(), "frag", "frag", linear.clone())?;
+        assert_ne!(srgb_code.as_binary(), linear_code.as_binary());
+
+        let undefined = shader_ad_hoc(FRAG.to_string(), "frag", "frag", false)?;
+        assert_eq!(undefined.as_binary(), linear_code.as_binary());
+
+        let root = std::env::temp_dir().join("vku_shader_options");
+        let _ = remove_dir_all(&root);
+        let src = root.join("src");
+        let target = root.join("target");
+        create_dir_all(&src)?;
+        write(src.join("tonemap.frag"), FRAG)?;
+
+        vku::compile_all_shaders(&src, &target, srgb.with_output_suffix("srgb"))?;
+        vku::compile_all_shaders(&src, &target, linear.with_output_suffix("linear"))?;
+        let srgb_file = std::fs::read(target.join("tonemap_srgb.frag.spv"))?;
+        let linear_file = std::fs::read(target.join("tonemap_linear.frag.spv"))?;
+        assert_eq!(srgb_file, srgb_code.as_binary_u8());
+        assert_eq!(linear_file, linear_code.as_binary_u8());
+
+        remove_dir_all(&root)?;
+        Ok(())
+    }
+}
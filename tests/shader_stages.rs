@@ -0,0 +1,83 @@
+#[cfg(test)]
+#[cfg(feature = "shader")]
+mod tests {
+    use vku::{shader_ad_hoc, Error};
+
+    const TESC: &str = r#"
+#version 450
+layout(vertices = 3) out;
+void main() {
+    gl_out[gl_InvocationID].gl_Position = gl_in[gl_InvocationID].gl_Position;
+    gl_TessLevelOuter[0] = 1.0;
+    gl_TessLevelOuter[1] = 1.0;
+    gl_TessLevelOuter[2] = 1.0;
+    gl_TessLevelInner[0] = 1.0;
+}
+"#;
+
+    const TESE: &str = r#"
+#version 450
+layout(triangles, equal_spacing, ccw) in;
+void main() {
+    gl_Position = gl_TessCoord.x * gl_in[0].gl_Position
+        + gl_TessCoord.y * gl_in[1].gl_Position
+        + gl_TessCoord.z * gl_in[2].gl_Position;
+}
+"#;
+
+    const GEOM: &str = r#"
+#version 450
+layout(triangles) in;
+layout(triangle_strip, max_vertices = 3) out;
+void main() {
+    for (int i = 0; i < 3; i++) {
+        gl_Position = gl_in[i].gl_Position;
+        EmitVertex();
+    }
+    EndPrimitive();
+}
+"#;
+
+    const RGEN: &str = r#"
+#version 460
+#extension GL_EXT_ray_tracing : require
+layout(location = 0) rayPayloadEXT vec4 payload;
+layout(set = 0, binding = 0) uniform accelerationStructureEXT tlas;
+void main() {
+    traceRayEXT(tlas, gl_RayFlagsOpaqueEXT, 0xFF, 0, 0, 0, vec3(0.0), 0.0, vec3(0.0, 0.0, 1.0), 100.0, 0);
+}
+"#;
+
+    const RMISS: &str = r#"
+#version 460
+#extension GL_EXT_ray_tracing : require
+layout(location = 0) rayPayloadInEXT vec4 payload;
+void main() {
+    payload = vec4(0.0);
+}
+"#;
+
+    #[test]
+    fn compiles_all_stage_extensions() -> Result<(), Error> {
+        let sources = [
+            ("tesc", TESC),
+            ("tese", TESE),
+            ("geom", GEOM),
+            ("rgen", RGEN),
+            ("rmiss", RMISS),
+        ];
+        for (ext, src) in sources {
+            let compiled = shader_ad_hoc(src.to_string(), ext, ext, true)?;
+            assert!(!compiled.as_binary().is_empty());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unknown_extension() {
+        assert!(matches!(
+            shader_ad_hoc(GEOM.to_string(), "geom", "gs", false),
+            Err(Error::UnknownShaderFileExtension)
+        ));
+    }
+}
@@ -0,0 +1,111 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use std::time::Duration;
+
+    use ash::vk::*;
+    use gpu_allocator::MemoryLocation;
+    use std::result::Result;
+    use vku::{CmdType, Error, SparseBuffer, VkInit};
+
+    /// Fills ```page``` with ```value``` and reads it back.
+    fn write_and_read_page(
+        vk_init: &mut VkInit,
+        sparse: &SparseBuffer,
+        page: usize,
+        value: u8,
+    ) -> Result<Vec<u8>, Error> {
+        let page_size = sparse.page_size();
+        let mut slot = vk_init
+            .create_readback_slots(page_size as usize, 1)?
+            .remove(0);
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffer = vk_init.create_command_buffers(&cmd_pool, 1)?[0];
+        let offset = sparse.page_bytes(page..page + 1).start;
+
+        vk_init.begin_cmd_buffer(&cmd_buffer)?;
+        unsafe {
+            vk_init.device.cmd_fill_buffer(
+                cmd_buffer,
+                sparse.buffer,
+                offset,
+                page_size,
+                u32::from_ne_bytes([value; 4]),
+            );
+        }
+        let barrier = BufferMemoryBarrier2::builder()
+            .buffer(sparse.buffer)
+            .offset(offset)
+            .size(page_size)
+            .src_stage_mask(PipelineStageFlags2::TRANSFER)
+            .src_access_mask(AccessFlags2::TRANSFER_WRITE)
+            .dst_stage_mask(PipelineStageFlags2::TRANSFER)
+            .dst_access_mask(AccessFlags2::TRANSFER_READ)
+            .src_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(QUEUE_FAMILY_IGNORED)
+            .build();
+        vk_init.cmd_pipeline_barrier2(&cmd_buffer, &[], &[barrier]);
+        let region = BufferCopy {
+            src_offset: offset,
+            dst_offset: 0,
+            size: page_size,
+        };
+        unsafe {
+            vk_init.device.cmd_copy_buffer(
+                cmd_buffer,
+                sparse.buffer,
+                slot.buffer.buffer,
+                &[region],
+            );
+        }
+        vk_init.end_and_submit_cmd_buffer(&cmd_buffer, CmdType::Any, &slot.fence, &[], &[], &[])?;
+
+        let data = slot.read_blocking(vk_init, Duration::from_secs(1));
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        data
+    }
+
+    #[test]
+    fn sparse_pages_bind_unbind_rebind() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let usage = BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::TRANSFER_DST;
+        let mut sparse = match SparseBuffer::new(&vk_init, 1, usage) {
+            Err(Error::SparseBindingNotSupported) => return Ok(()),
+            sparse => sparse?,
+        };
+        let page_size = sparse.page_size();
+        sparse.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+
+        let mut sparse = SparseBuffer::new(&vk_init, page_size * 4, usage)?;
+        assert_eq!(sparse.page_count(), 4);
+        assert_eq!(sparse.resident_pages(), 0);
+
+        sparse.bind_pages(&mut vk_init, 0..2, Some(MemoryLocation::GpuOnly))?;
+        assert!(sparse.is_resident(0) && sparse.is_resident(1));
+        assert!(!sparse.is_resident(2));
+        assert!(write_and_read_page(&mut vk_init, &sparse, 1, 7)?
+            .iter()
+            .all(|v| *v == 7));
+
+        sparse.bind_pages(&mut vk_init, 1..2, None)?;
+        assert_eq!(sparse.resident_pages(), 1);
+        assert!(!sparse.is_resident(1));
+
+        sparse.bind_pages(&mut vk_init, 1..4, Some(MemoryLocation::GpuOnly))?;
+        assert_eq!(sparse.resident_pages(), 4);
+        assert!(write_and_read_page(&mut vk_init, &sparse, 3, 42)?
+            .iter()
+            .all(|v| *v == 42));
+
+        assert!(matches!(
+            sparse.bind_pages(&mut vk_init, 3..5, None),
+            Err(Error::SparsePagesOutOfRange { .. })
+        ));
+
+        sparse.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, VkInit};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    const BUCKET: u64 = 64 * 1024;
+
+    fn capped_vk_init() -> Result<VkInit, Error> {
+        use vku::VkInitCreateInfo;
+        use winit::dpi::LogicalSize;
+        use winit::event_loop::{EventLoop, EventLoopBuilder};
+        use winit::window::WindowBuilder;
+
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+
+        let mut create_info = VkInitCreateInfo::default();
+        create_info.max_staging_pool_bytes = 2 * BUCKET;
+        VkInit::new(Some(&window), Some(size), create_info)
+    }
+
+    #[test]
+    fn reuses_buckets_and_evicts_beyond_cap() -> Result<(), Error> {
+        let mut vk_init = capped_vk_init()?;
+
+        let a = vk_init.acquire_staging(1024)?;
+        let a_buffer = a.buffer.buffer;
+        vk_init.release_staging(a, None)?;
+        let a = vk_init.acquire_staging(2048)?;
+        assert_eq!(a.buffer.buffer, a_buffer, "same bucket was not reused");
+        assert_eq!(vk_init.staging_pool.stats().buffers_live, 1);
+
+        // Three released buckets exceed the cap of two, the oldest free one is destroyed.
+        let b = vk_init.acquire_staging(1024)?;
+        let c = vk_init.acquire_staging(1024)?;
+        vk_init.release_staging(a, None)?;
+        vk_init.release_staging(b, None)?;
+        vk_init.release_staging(c, None)?;
+        assert_eq!(vk_init.staging_pool.retained_bytes(), 2 * BUCKET);
+        assert_eq!(vk_init.staging_pool.stats().buffers_live, 2);
+        let reused = vk_init.acquire_staging(1024)?;
+        assert_ne!(reused.buffer.buffer, a_buffer, "evicted buffer was reused");
+
+        // In-flight buffers are evicted last and through the deletion queue.
+        let fence = unsafe {
+            vk_init
+                .device
+                .create_fence(&FenceCreateInfo::default(), None)?
+        };
+        vk_init.release_staging(reused, Some(fence))?;
+        let large = vk_init.acquire_staging(4 * BUCKET as usize)?;
+        vk_init.release_staging(large, Some(fence))?;
+        let stats = vk_init.staging_pool.stats();
+        assert_eq!(stats.buffers_live, 0);
+        assert_eq!(stats.bytes_resident, 0);
+        assert_eq!(stats.high_water_mark, 6 * BUCKET);
+        assert_eq!(vk_init.heap_state().pending_deletions, 2);
+        assert_eq!(vk_init.collect_deletion_queue()?, 0);
+
+        vk_init.submit_cmd_buffers2(&[], CmdType::Any, Some(&fence), &[], &[])?;
+        vk_init.wait_for_fences_watched(&[fence], "staging pool test")?;
+        assert_eq!(vk_init.collect_deletion_queue()?, 2);
+
+        vk_init.destroy_fence(&fence)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, VkInit, VkInitCreateInfo};
+
+    const COUNT: usize = 64;
+
+    #[test]
+    fn batched_submit2_feeds_legacy_submit() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        vk_init.begin_debug_message_capture();
+        let usage = BufferUsageFlags::TRANSFER_SRC | BufferUsageFlags::TRANSFER_DST;
+        let size = COUNT * std::mem::size_of::<u32>();
+        let mut buffers = vk_init.create_local_buffers(size, usage, 3)?;
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffers = vk_init.create_command_buffers(&cmd_pool, 3)?;
+        let filled = vk_init.create_semaphore()?;
+        let fence = vk_init.create_fence()?;
+        vk_init.wait_on_fence_and_reset(Some(&fence), &[])?;
+
+        // Two command buffers in one submit, each filling its own buffer.
+        for (cmd_buffer, (buffer, value)) in cmd_buffers.iter().zip(buffers.iter().zip([7, 9])) {
+            vk_init.begin_cmd_buffer(cmd_buffer)?;
+            unsafe {
+                vk_init
+                    .device
+                    .cmd_fill_buffer(*cmd_buffer, buffer.buffer, 0, WHOLE_SIZE, value);
+                vk_init.device.end_command_buffer(*cmd_buffer)?;
+            }
+        }
+        let signal = SemaphoreSubmitInfo::builder()
+            .semaphore(filled)
+            .stage_mask(PipelineStageFlags2::CLEAR)
+            .build();
+        vk_init.submit_cmd_buffers2(&cmd_buffers[..2], CmdType::Any, None, &[], &[signal])?;
+
+        // The legacy submit waits on the semaphore at the copy stage.
+        vk_init.begin_cmd_buffer(&cmd_buffers[2])?;
+        buffers[1].enqueue_copy_to_buffer(
+            &vk_init.device,
+            &cmd_buffers[2],
+            &buffers[2],
+            None,
+            Some(4),
+            Some(4),
+        )?;
+        vk_init.end_and_submit_cmd_buffer(
+            &cmd_buffers[2],
+            CmdType::Any,
+            &fence,
+            &[filled],
+            &[],
+            &[PipelineStageFlags::TRANSFER],
+        )?;
+        vk_init.wait_for_fences_watched(&[fence], "submit2 test")?;
+        vk_init.wait_device_idle()?;
+
+        let first: Vec<u32> = vk_init.read_buffer(&buffers[0], 0, COUNT)?;
+        assert!(first.iter().all(|value| *value == 7));
+        let copied: Vec<u32> = vk_init.read_buffer(&buffers[2], 0, 2)?;
+        assert_eq!(copied[1], 9);
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        vk_init.destroy_semaphore(&filled)?;
+        vk_init.destroy_fence(&fence)?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        for buffer in &mut buffers {
+            buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        }
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use vku::SurfaceRotation;
+
+    fn rotate(matrix: [[f32; 2]; 2], point: [f32; 2]) -> [f32; 2] {
+        [
+            matrix[0][0] * point[0] + matrix[1][0] * point[1],
+            matrix[0][1] * point[0] + matrix[1][1] * point[1],
+        ]
+    }
+
+    #[test]
+    fn rotations_from_transforms() {
+        let cases = [
+            (SurfaceTransformFlagsKHR::IDENTITY, SurfaceRotation::None),
+            (SurfaceTransformFlagsKHR::ROTATE_90, SurfaceRotation::Cw90),
+            (SurfaceTransformFlagsKHR::ROTATE_180, SurfaceRotation::Cw180),
+            (SurfaceTransformFlagsKHR::ROTATE_270, SurfaceRotation::Cw270),
+            (
+                SurfaceTransformFlagsKHR::HORIZONTAL_MIRROR,
+                SurfaceRotation::None,
+            ),
+            (SurfaceTransformFlagsKHR::INHERIT, SurfaceRotation::None),
+        ];
+        for (transform, rotation) in cases {
+            assert_eq!(SurfaceRotation::from_transform(transform), rotation);
+        }
+    }
+
+    #[test]
+    fn quarter_turns_swap_extent() {
+        let extent = Extent2D {
+            width: 1920,
+            height: 1080,
+        };
+        let swapped = Extent2D {
+            width: 1080,
+            height: 1920,
+        };
+        assert_eq!(SurfaceRotation::None.rotate_extent(extent), extent);
+        assert_eq!(SurfaceRotation::Cw90.rotate_extent(extent), swapped);
+        assert_eq!(SurfaceRotation::Cw180.rotate_extent(extent), extent);
+        assert_eq!(SurfaceRotation::Cw270.rotate_extent(extent), swapped);
+    }
+
+    #[test]
+    fn matrices_rotate_clip_space() {
+        // Clip space y points down, a positive rotation turns the top right corner clockwise on screen.
+        let corner = [1.0, -1.0];
+        assert_eq!(rotate(SurfaceRotation::None.matrix(), corner), [1.0, -1.0]);
+        assert_eq!(rotate(SurfaceRotation::Cw90.matrix(), corner), [1.0, 1.0]);
+        assert_eq!(rotate(SurfaceRotation::Cw180.matrix(), corner), [-1.0, 1.0]);
+        assert_eq!(
+            rotate(SurfaceRotation::Cw270.matrix(), corner),
+            [-1.0, -1.0]
+        );
+
+        for rotation in [
+            SurfaceRotation::Cw90,
+            SurfaceRotation::Cw180,
+            SurfaceRotation::Cw270,
+        ] {
+            // Four turns of any rotation end where they started.
+            let mut point = corner;
+            for _ in 0..4 {
+                point = rotate(rotation.matrix(), point);
+            }
+            assert_eq!(point, corner);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{
+        choose_present_mode, choose_surface_format, query_surface_support, Error, GpuSelection,
+        VkInit, VkInitCreateInfo,
+    };
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    #[test]
+    fn report_matches_created_surface() -> Result<(), Error> {
+        use winit::dpi::LogicalSize;
+        use winit::event_loop::{EventLoop, EventLoopBuilder};
+        use winit::window::WindowBuilder;
+
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+
+        let report = query_surface_support(&window)?;
+        let (index, device) = report.presentable_devices().next().unwrap();
+        assert!(device.supports_present_mode(PresentModeKHR::FIFO));
+        assert!(device.min_image_count >= 1);
+        assert!(device.max_image_count == 0 || device.max_image_count >= device.min_image_count);
+
+        // Queried twice to check the temporary instance and surface are released.
+        let second_report = query_surface_support(&window)?;
+        assert_eq!(second_report.devices.len(), report.devices.len());
+
+        let surface_format = device.formats[0].format;
+        let mut create_info = VkInitCreateInfo::default();
+        create_info.gpu_preference = GpuSelection::Index(index);
+        create_info.allow_igpu = true;
+        create_info.surface_format_preferences = vec![surface_format];
+        create_info.present_mode_preferences = vec![PresentModeKHR::FIFO];
+        let mut vk_init = VkInit::new(Some(&window), Some(size), create_info)?;
+        assert_eq!(vk_init.physical_device_info.name, device.name);
+        assert_eq!(
+            vk_init.head().surface_info.color_format.format,
+            surface_format
+        );
+        vk_init.destroy()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn preferences_fall_back_in_order() {
+        let srgb = |format| SurfaceFormatKHR {
+            format,
+            color_space: ColorSpaceKHR::SRGB_NONLINEAR,
+        };
+        let available = [srgb(Format::B8G8R8A8_SRGB), srgb(Format::B8G8R8A8_UNORM)];
+        let chosen = |preferences: &[Format]| {
+            choose_surface_format(preferences, &available)
+                .map(|surface_format| surface_format.format)
+        };
+        assert_eq!(
+            chosen(&[Format::R8G8B8A8_UNORM, Format::B8G8R8A8_UNORM]),
+            Some(Format::B8G8R8A8_UNORM)
+        );
+        assert_eq!(
+            chosen(&[Format::R8G8B8A8_UNORM]),
+            Some(Format::B8G8R8A8_SRGB)
+        );
+        assert_eq!(chosen(&[]), Some(Format::B8G8R8A8_SRGB));
+        assert_eq!(choose_surface_format(&[Format::R8G8B8A8_UNORM], &[]), None);
+
+        let modes = [PresentModeKHR::FIFO, PresentModeKHR::IMMEDIATE];
+        assert_eq!(
+            choose_present_mode(
+                &[PresentModeKHR::MAILBOX, PresentModeKHR::IMMEDIATE],
+                &modes
+            ),
+            PresentModeKHR::IMMEDIATE
+        );
+        assert_eq!(
+            choose_present_mode(&[PresentModeKHR::MAILBOX], &modes),
+            PresentModeKHR::FIFO
+        );
+    }
+}
@@ -0,0 +1,49 @@
+// Resource tracking is only compiled into debug builds.
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, VkInit, VkInitCreateInfo};
+    use winit::platform::x11::EventLoopBuilderExtX11;
+
+    fn strict_vk_init() -> Result<VkInit, Error> {
+        use winit::dpi::LogicalSize;
+        use winit::event_loop::{EventLoop, EventLoopBuilder};
+        use winit::window::WindowBuilder;
+
+        env_logger::init();
+        let event_loop: EventLoop<()> = EventLoopBuilder::default().with_any_thread(true).build();
+        let size = [800_u32, 600_u32];
+        let window = WindowBuilder::new()
+            .with_inner_size(LogicalSize {
+                width: size[0],
+                height: size[1],
+            })
+            .build(&event_loop)
+            .unwrap();
+
+        let mut create_info = VkInitCreateInfo::default();
+        create_info.capture_resource_backtraces = true;
+        create_info.strict_teardown = true;
+        VkInit::new(Some(&window), Some(size), create_info)
+    }
+
+    #[test]
+    fn strict_teardown_reports_live_resources() -> Result<(), Error> {
+        let mut vk_init = strict_vk_init()?;
+        let mut buffer = vk_init.create_local_buffer(1024, BufferUsageFlags::STORAGE_BUFFER)?;
+        buffer.set_debug_object_name(&vk_init, String::from("Forgotten"))?;
+
+        let Err(Error::ResourcesStillAlive(alive)) = vk_init.destroy() else {
+            panic!("destroy proceeded with a live buffer");
+        };
+        assert_eq!(alive.len(), 1);
+        assert_eq!(alive[0].kind, "VMABuffer");
+        assert_eq!(alive[0].name.as_deref(), Some("Forgotten"));
+        assert!(alive[0].backtrace.is_some());
+
+        buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        assert!(vk_init.live_resources().is_empty());
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{Error, VkInit, VkInitCreateInfo};
+
+    #[test]
+    fn creates_sampled_textures() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        vk_init.begin_debug_message_capture();
+        let pixels: Vec<u8> = (0..16 * 8 * 4).map(|value| value as u8).collect();
+
+        assert!(matches!(
+            vk_init.create_texture_from_rgba8(16, 8, &pixels[4..], false),
+            Err(Error::ImageDataMismatch { .. })
+        ));
+
+        let mut texture = vk_init.create_texture_from_rgba8(16, 8, &pixels, false)?;
+        assert_eq!(texture.format, Format::R8G8B8A8_UNORM);
+        assert_eq!(texture.mip_levels, 1);
+        assert_eq!(
+            texture.current_layout,
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL
+        );
+        assert_eq!(vk_init.download_image(&mut texture)?, pixels);
+
+        let mut mipped = vk_init.create_mipped_texture_from_rgba8(16, 8, &pixels, true)?;
+        assert_eq!(mipped.format, Format::R8G8B8A8_SRGB);
+        assert_eq!(mipped.mip_levels, 5);
+        assert_eq!(mipped.current_layout, ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        texture.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        mipped.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,85 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::mem::size_of;
+    use std::result::Result;
+    use std::time::Duration;
+    use vku::{CmdType, Error};
+
+    const COUNT: usize = 256;
+
+    #[test]
+    fn chained_submits_wait_on_timeline_values() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let size = COUNT * size_of::<u32>();
+        let mut buffer = vk_init.create_local_buffer(
+            size,
+            BufferUsageFlags::TRANSFER_DST | BufferUsageFlags::TRANSFER_SRC,
+        )?;
+        let mut slot = vk_init.create_readback_slots(size, 1)?.remove(0);
+        let cmd_pool = vk_init.create_cmd_pool(CmdType::Any)?;
+        let cmd_buffers = vk_init.create_command_buffers(&cmd_pool, 2)?;
+        let timeline = vk_init.create_timeline_semaphore(0)?;
+        let submit_info = |value| {
+            SemaphoreSubmitInfo::builder()
+                .semaphore(timeline)
+                .value(value)
+                .stage_mask(PipelineStageFlags2::ALL_TRANSFER)
+                .build()
+        };
+
+        // 1 (host) -> fill -> 2 -> copy -> 3, submitted before the host signals the first value.
+        vk_init.begin_cmd_buffer(&cmd_buffers[0])?;
+        unsafe {
+            vk_init
+                .device
+                .cmd_fill_buffer(cmd_buffers[0], buffer.buffer, 0, WHOLE_SIZE, 7);
+        }
+        vk_init.end_and_submit_cmd_buffer2(
+            &cmd_buffers[0],
+            CmdType::Any,
+            None,
+            &[submit_info(1)],
+            &[submit_info(2)],
+        )?;
+
+        vk_init.begin_cmd_buffer(&cmd_buffers[1])?;
+        let region = BufferCopy::builder().size(size as u64).build();
+        unsafe {
+            vk_init.device.cmd_copy_buffer(
+                cmd_buffers[1],
+                buffer.buffer,
+                slot.buffer.buffer,
+                &[region],
+            );
+        }
+        vk_init.end_and_submit_cmd_buffer2(
+            &cmd_buffers[1],
+            CmdType::Any,
+            None,
+            &[submit_info(2)],
+            &[submit_info(3)],
+        )?;
+
+        assert_eq!(vk_init.timeline_semaphore_value(&timeline)?, 0);
+        assert!(matches!(
+            vk_init.wait_timeline_semaphore(&timeline, 3, Some(Duration::from_millis(10))),
+            Err(Error::VkError(ash::vk::Result::TIMEOUT))
+        ));
+        vk_init.signal_timeline_semaphore(&timeline, 1)?;
+        vk_init.wait_timeline_semaphore(&timeline, 3, None)?;
+        assert_eq!(vk_init.timeline_semaphore_value(&timeline)?, 3);
+
+        let read: Vec<u32> = slot.buffer.get_data(&vk_init.device, 0, COUNT)?;
+        assert!(read.iter().all(|read| *read == 7));
+
+        vk_init.destroy_semaphore(&timeline)?;
+        vk_init.destroy_cmd_pool(&cmd_pool)?;
+        slot.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, VkInit, VkInitCreateInfo};
+
+    #[test]
+    fn profiler_times_named_scopes() -> Result<(), Error> {
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        let mut profiler = match vk_init.create_gpu_frame_profiler(4) {
+            Err(Error::TimestampsNotSupported) => return vk_init.destroy(),
+            profiler => profiler?,
+        };
+        vk_init.begin_debug_message_capture();
+        let mut buffer =
+            vk_init.create_local_buffer(1024 * 1024, BufferUsageFlags::TRANSFER_DST)?;
+
+        let mut recorded = Ok(());
+        vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+            profiler.reset(&vk_init, cmd_buffer);
+            recorded = profiler.begin_scope(&vk_init, cmd_buffer, "Fill");
+            unsafe {
+                vk_init
+                    .device
+                    .cmd_fill_buffer(*cmd_buffer, buffer.buffer, 0, WHOLE_SIZE, 42);
+            }
+            if recorded.is_ok() {
+                recorded = profiler.end_scope(&vk_init, cmd_buffer, "Fill");
+            }
+            assert!(matches!(
+                profiler.end_scope(&vk_init, cmd_buffer, "Unknown"),
+                Err(Error::ProfilerScopeNotBegun(name)) if name == "Unknown"
+            ));
+        })?;
+        recorded?;
+
+        let timings = profiler.collect(&vk_init)?;
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[0].0, "Fill");
+        assert!(timings[0].1 >= 0.0);
+        assert!(profiler.to_string().contains("Fill"));
+
+        let messages = vk_init.end_debug_message_capture();
+        assert!(
+            messages.iter().all(|message| !message.contains("VUID")),
+            "{messages:?}"
+        );
+        profiler.destroy(&vk_init);
+        buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
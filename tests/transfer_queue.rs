@@ -0,0 +1,69 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::default_vk_init;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, Error, QueueRequirements};
+
+    const COUNT: usize = 256;
+
+    #[test]
+    fn uploads_and_reads_keep_buffer_contents_across_queues() -> Result<(), Error> {
+        let mut vk_init = default_vk_init()?;
+        let info = &vk_init.physical_device_info;
+        let unified = info.unified_queue_family_index;
+
+        // A single texel can not be copied on queues with a coarse granularity.
+        let texel = QueueRequirements {
+            image_granularity: Extent3D {
+                width: 1,
+                height: 1,
+                depth: 1,
+            },
+            ..Default::default()
+        };
+        let (_, family) = vk_init.get_queue_for(CmdType::Transfer, texel);
+        let granularity =
+            info.queue_family_properties[family as usize].min_image_transfer_granularity;
+        assert!(
+            family == unified
+                || (granularity.width == 1 && granularity.height == 1 && granularity.depth == 1),
+            "queue family {family} with granularity {granularity:?} was picked for single texel copies"
+        );
+
+        let usage = BufferUsageFlags::TRANSFER_DST | BufferUsageFlags::TRANSFER_SRC;
+        let mut scratch = vk_init.create_local_buffer(COUNT * 4, usage)?;
+        vk_init.immediate_submit_for(CmdType::Transfer, texel, |cmd_buffer| unsafe {
+            vk_init
+                .device
+                .cmd_fill_buffer(*cmd_buffer, scratch.buffer, 0, WHOLE_SIZE, 0);
+        })?;
+
+        let mut buffer = vk_init.create_local_buffer(COUNT * 4, usage)?;
+
+        // Both uploads and the reads are handed to the transfer queue and back, the first half has to survive the second upload.
+        let first: Vec<u32> = (0..COUNT as u32 / 2).collect();
+        let second: Vec<u32> = (COUNT as u32 / 2..COUNT as u32).collect();
+        vk_init.upload_to_buffer(&buffer, 0, &first, None, None)?;
+        vk_init.upload_to_buffer(&buffer, COUNT / 2 * 4, &second, None, None)?;
+        let read: Vec<u32> = vk_init.read_buffer(&buffer, 0, COUNT)?;
+        assert_eq!(read, (0..COUNT as u32).collect::<Vec<u32>>());
+
+        let tail: Vec<u32> = vk_init.read_buffer(&buffer, (COUNT as u64 - 4) * 4, 4)?;
+        assert_eq!(tail, second[second.len() - 4..]);
+        // Bounds are checked against the requested size, not the possibly larger allocation.
+        assert!(matches!(
+            vk_init.read_buffer::<u32>(&buffer, (COUNT as u64 - 4) * 4, 5),
+            Err(Error::BufferReadOutOfBounds {
+                requested: 20,
+                available: 16
+            })
+        ));
+
+        scratch.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        buffer.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::mem::size_of;
+
+    use ash::util::read_spv;
+    use ash::vk::*;
+    use std::result::Result;
+    use vku::{CmdType, ComputeBinding, Error, VkInit};
+
+    /// Offset of the second binding, a multiple of every allowed ```minStorageBufferOffsetAlignment```.
+    const OFFSET: usize = 256;
+
+    #[test]
+    fn swap_buffers_between_dispatches() -> Result<(), Error> {
+        use vku::VkInitCreateInfo;
+
+        env_logger::init();
+        let mut vk_init = VkInit::new_headless(VkInitCreateInfo::default())?;
+        let count = 300_u32;
+        let usage = BufferUsageFlags::STORAGE_BUFFER | BufferUsageFlags::TRANSFER_SRC;
+        let mut first = vk_init.create_local_buffer(count as usize * size_of::<u32>(), usage)?;
+        let mut second =
+            vk_init.create_local_buffer(OFFSET + count as usize * size_of::<u32>(), usage)?;
+        let code = read_spv(&mut Cursor::new(include_bytes!("invocation_id.comp.spv")))?;
+        let compute_shader = vk_init.create_compute_shader::<u32>(
+            &[ComputeBinding::Ssbo(&first)],
+            code,
+            [256, 1, 1],
+            &[],
+            String::from("Update_Bindings"),
+        )?;
+        let dispatch = |vk_init: &VkInit| {
+            vk_init.immediate_submit(CmdType::Any, |cmd_buffer| {
+                compute_shader.bind(&vk_init.device, cmd_buffer, &count.to_ne_bytes());
+                compute_shader.dispatch(&vk_init.device, cmd_buffer, count, 1, 1);
+            })
+        };
+        let expected: Vec<u32> = (0..count).collect();
+
+        dispatch(&vk_init)?;
+        assert_eq!(
+            vk_init.read_buffer::<u32>(&first, 0, count as usize)?,
+            expected
+        );
+
+        assert!(matches!(
+            compute_shader.update_bindings(&vk_init.device, &[]),
+            Err(Error::ComputeBindingsMismatch { .. })
+        ));
+        compute_shader.update_bindings(
+            &vk_init.device,
+            &[ComputeBinding::SsboRange(
+                &second,
+                OFFSET as u64,
+                count as u64 * size_of::<u32>() as u64,
+            )],
+        )?;
+        dispatch(&vk_init)?;
+        let read: Vec<u32> = vk_init.read_buffer(&second, OFFSET as u64, count as usize)?;
+        assert_eq!(read, expected);
+
+        compute_shader.destroy(&vk_init)?;
+        first.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        second.destroy(&vk_init.device, &mut vk_init.allocator.lock())?;
+        vk_init.destroy()
+    }
+}
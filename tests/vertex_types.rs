@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use ash::vk::*;
+    use std::mem::size_of;
+    use vku::{PointVertex2D, PointVertex3D, UIVertex, Vertex2D, Vertex3D, VertexConvert};
+
+    fn format_size(format: Format) -> u32 {
+        match format {
+            Format::R8G8B8A8_UNORM | Format::R32_SFLOAT => 4,
+            Format::R32G32_SFLOAT => 8,
+            Format::R32G32B32_SFLOAT => 12,
+            Format::R32G32B32A32_SFLOAT => 16,
+            _ => panic!("unexpected vertex format {format:?}"),
+        }
+    }
+
+    fn assert_layout<V: VertexConvert>() {
+        let name = std::any::type_name::<V>();
+        let bindings = V::binding_desc();
+        assert_eq!(bindings.len(), 1, "{name}");
+        let stride = bindings[0].stride;
+        assert_eq!(stride as usize, size_of::<V>(), "{name}");
+
+        let mut ranges: Vec<(u32, u32)> = V::attrib_desc()
+            .iter()
+            .enumerate()
+            .map(|(location, attrib)| {
+                assert_eq!(attrib.location, location as u32, "{name}");
+                assert_eq!(attrib.binding, bindings[0].binding, "{name}");
+                (attrib.offset, attrib.offset + format_size(attrib.format))
+            })
+            .collect();
+        ranges.sort();
+        assert!(
+            ranges.iter().all(|(_, end)| *end <= stride),
+            "{name} attributes {ranges:?} exceed the stride {stride}"
+        );
+        assert!(
+            ranges.windows(2).all(|pair| pair[0].1 <= pair[1].0),
+            "{name} attributes {ranges:?} overlap"
+        );
+    }
+
+    #[test]
+    fn vertex_attributes_fit_their_structs() {
+        assert_layout::<UIVertex>();
+        assert_layout::<PointVertex2D>();
+        assert_layout::<PointVertex3D>();
+        assert_layout::<Vertex2D>();
+        assert_layout::<Vertex3D>();
+    }
+}
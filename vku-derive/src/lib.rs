@@ -0,0 +1,210 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Error, Expr, ExprLit,
+    Fields, Ident, Lit, LitInt, LitStr, Type,
+};
+
+/// Implements ```vku::VertexConvert``` for a ```#[repr(C)]``` struct with named fields.
+///
+/// Every field is one attribute, locations count up from 0 in declaration order and offsets are computed with ```memoffset```.
+/// Supported field types are ```f32```, ```u32```, ```i32```, ```[f32; N]```, ```[u32; N]```, ```[i32; N]``` with N up to 4 and ```[u8; 4]``` as ```R8G8B8A8_UNORM```.
+///
+/// - ```#[vertex(binding = 1, input_rate = "instance")]``` on the struct sets the binding, defaults to binding 0 and ```"vertex"```.
+/// - ```#[vertex(format = "R16G16_SFLOAT")]``` on a field overrides the format, required for all other field types.
+/// - ```#[vertex(location = 4)]``` on a field sets its location, following fields continue from there.
+/// ```ignore
+/// #[repr(C)]
+/// #[derive(Clone, Copy, VertexConvert)]
+/// struct Vertex2D {
+///     pos: [f32; 2],
+///     uv: [f32; 2],
+///     #[vertex(format = "R8G8B8A8_SRGB")]
+///     col: [u8; 4],
+/// }
+/// ```
+#[proc_macro_derive(VertexConvert, attributes(vertex))]
+pub fn derive_vertex_convert(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    vertex_convert(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn vertex_convert(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "VertexConvert can't be derived for generic structs",
+        ));
+    }
+    if !is_repr_c(&input.attrs)? {
+        return Err(Error::new(
+            name.span(),
+            "VertexConvert requires #[repr(C)] for stable field offsets",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            name.span(),
+            "VertexConvert can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(
+            data.fields.span(),
+            "VertexConvert requires named fields",
+        ));
+    };
+
+    let (binding, input_rate) = binding_attributes(&input.attrs)?;
+    let mut location = 0_u32;
+    let mut attribs = vec![];
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named field");
+        let (format, location_override) = field_attributes(&field.attrs)?;
+        let format = match format {
+            Some(format) => format,
+            None => default_format(&field.ty)?,
+        };
+        if let Some(location_override) = location_override {
+            location = location_override;
+        }
+        attribs.push(quote! {
+            ::vku::ash::vk::VertexInputAttributeDescription {
+                location: #location,
+                binding: #binding,
+                format: ::vku::ash::vk::Format::#format,
+                offset: ::vku::memoffset::offset_of!(#name, #ident) as u32,
+            }
+        });
+        location += 1;
+    }
+
+    Ok(quote! {
+        impl ::vku::VertexConvert for #name {
+            fn binding_desc() -> ::std::vec::Vec<::vku::ash::vk::VertexInputBindingDescription> {
+                ::std::vec![::vku::ash::vk::VertexInputBindingDescription {
+                    binding: #binding,
+                    stride: ::std::mem::size_of::<#name>() as u32,
+                    input_rate: ::vku::ash::vk::VertexInputRate::#input_rate,
+                }]
+            }
+
+            fn attrib_desc() -> ::std::vec::Vec<::vku::ash::vk::VertexInputAttributeDescription> {
+                ::std::vec![#(#attribs),*]
+            }
+        }
+    })
+}
+
+fn is_repr_c(attrs: &[Attribute]) -> Result<bool, Error> {
+    let mut repr_c = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                repr_c = true;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(repr_c)
+}
+
+fn binding_attributes(attrs: &[Attribute]) -> Result<(u32, Ident), Error> {
+    let mut binding = 0;
+    let mut input_rate = Ident::new("VERTEX", Span::call_site());
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("vertex")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("binding") {
+                binding = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                Ok(())
+            } else if meta.path.is_ident("input_rate") {
+                let rate: LitStr = meta.value()?.parse()?;
+                input_rate = match rate.value().as_str() {
+                    "vertex" => Ident::new("VERTEX", rate.span()),
+                    "instance" => Ident::new("INSTANCE", rate.span()),
+                    _ => {
+                        return Err(Error::new(
+                            rate.span(),
+                            "input_rate must be \"vertex\" or \"instance\"",
+                        ))
+                    }
+                };
+                Ok(())
+            } else {
+                Err(meta.error("expected binding or input_rate"))
+            }
+        })?;
+    }
+    Ok((binding, input_rate))
+}
+
+fn field_attributes(attrs: &[Attribute]) -> Result<(Option<Ident>, Option<u32>), Error> {
+    let mut format = None;
+    let mut location = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("vertex")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                let name: LitStr = meta.value()?.parse()?;
+                format = Some(name.parse::<Ident>()?);
+                Ok(())
+            } else if meta.path.is_ident("location") {
+                location = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected format or location"))
+            }
+        })?;
+    }
+    Ok((format, location))
+}
+
+fn default_format(ty: &Type) -> Result<Ident, Error> {
+    let unsupported = || {
+        Error::new(
+            ty.span(),
+            "unsupported vertex field type, set the format with #[vertex(format = \"...\")]",
+        )
+    };
+    let (scalar, count) = match ty {
+        Type::Path(path) => (path.path.get_ident().ok_or_else(unsupported)?, 1),
+        Type::Array(array) => {
+            let Type::Path(path) = array.elem.as_ref() else {
+                return Err(unsupported());
+            };
+            let Expr::Lit(ExprLit {
+                lit: Lit::Int(len), ..
+            }) = &array.len
+            else {
+                return Err(unsupported());
+            };
+            (
+                path.path.get_ident().ok_or_else(unsupported)?,
+                len.base10_parse::<usize>()?,
+            )
+        }
+        _ => return Err(unsupported()),
+    };
+
+    let components = ["R", "G", "B", "A"];
+    let format = match (scalar.to_string().as_str(), count) {
+        ("u8", 4) => "R8G8B8A8_UNORM".to_string(),
+        ("f32" | "u32" | "i32", 1..=4) => {
+            let suffix = match scalar.to_string().as_str() {
+                "f32" => "SFLOAT",
+                "u32" => "UINT",
+                _ => "SINT",
+            };
+            let channels: String = components[..count]
+                .iter()
+                .map(|component| format!("{component}32"))
+                .collect();
+            format!("{channels}_{suffix}")
+        }
+        _ => return Err(unsupported()),
+    };
+    Ok(Ident::new(&format, ty.span()))
+}